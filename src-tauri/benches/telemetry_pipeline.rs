@@ -0,0 +1,159 @@
+// Benchmarks for the telemetry measurement path. Time spent here is time the sampler
+// is not sleeping, so regressions skew the sampling interval and the CPU load it reports.
+//
+//   cargo bench --bench telemetry_pipeline
+//
+// Set APPLES2ORANGES_BENCH_SYNTHETIC=1 (CI) to skip the hardware reads and run only the
+// deterministic serialization and compression benchmarks on synthetic samples. The
+// hardware benchmarks are skipped automatically off macOS.
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::Value;
+
+use app_lib::hardware::{read_core_temperatures, CoreTemperatureData, CpuUtilizationMonitor, ThermalTrend};
+use app_lib::persistence::compression::{compress_telemetry_data, decompress_telemetry_data};
+use app_lib::TelemetryUpdate;
+
+const SYNTHETIC_ENV: &str = "APPLES2ORANGES_BENCH_SYNTHETIC";
+
+// Session lengths in samples (1 Hz: one minute, ten minutes, one hour)
+const SESSION_LENGTHS: [usize; 3] = [60, 600, 3600];
+
+fn synthetic_mode() -> bool {
+    !cfg!(target_os = "macos") || std::env::var(SYNTHETIC_ENV).is_ok_and(|v| v == "1")
+}
+
+// Deterministic M-series-shaped sample (12 P-cores, 4 E-cores, 4 GPU clusters)
+fn synthetic_sample(i: usize) -> TelemetryUpdate {
+    let wave = |period: usize, amplitude: f64| ((i % period) as f64 / period as f64 * std::f64::consts::TAU).sin() * amplitude;
+    let p_cores: Vec<f64> = (0..12).map(|core| 58.0 + core as f64 * 0.7 + wave(30, 6.0)).collect();
+    let e_cores: Vec<f64> = (0..4).map(|core| 47.0 + core as f64 * 0.5 + wave(45, 3.0)).collect();
+    let gpu_temps: Vec<f64> = (0..4).map(|cluster| 52.0 + cluster as f64 + wave(20, 4.0)).collect();
+    let cpu_temp_avg = p_cores.iter().chain(&e_cores).sum::<f64>() / 16.0;
+    let cpu_temp_max = p_cores.iter().chain(&e_cores).cloned().fold(f64::MIN, f64::max);
+
+    TelemetryUpdate {
+        timestamp_ms: 1_700_000_000_000 + i as u64 * 1000,
+        cpu_power_watts: Some(18.5 + wave(30, 4.0)),
+        gpu_power_watts: Some(6.2 + wave(20, 2.0)),
+        ane_power_watts: Some(0.0),
+        cpu_temp_celsius: Some(cpu_temp_avg),
+        gpu_temp_celsius: Some(53.5),
+        cpu_freq_mhz: Some(3_780.0),
+        gpu_freq_mhz: Some(1_398.0),
+        ram_usage_gb: Some(21.4),
+        current_tps: Some(42.0 + wave(15, 3.0)),
+        instantaneous_tps: Some(41.0 + wave(7, 5.0)),
+        rolling_tps: Some(42.0),
+        model: Some("qwen2.5-7b-instruct-q4_k_m.gguf".to_string()),
+        cpu_temp_avg: Some(cpu_temp_avg),
+        cpu_temp_max: Some(cpu_temp_max),
+        cpu_p_core_temps: Some(p_cores.clone()),
+        cpu_e_core_temps: Some(e_cores.clone()),
+        gpu_temp_avg: Some(53.5),
+        gpu_temp_max: Some(57.0),
+        gpu_cluster_temps: Some(gpu_temps.clone()),
+        cpu_p_core_utilization: Some((0..12).map(|core| 70.0 + wave(10 + core, 25.0)).collect()),
+        cpu_e_core_utilization: Some((0..4).map(|core| 20.0 + wave(10 + core, 15.0)).collect()),
+        cpu_overall_utilization: Some(61.0 + wave(10, 20.0)),
+        inference_cpu_utilization: Some(55.0),
+        inference_cpu_share: Some(90.0),
+        gpu_utilization: Some(35.0 + wave(20, 10.0)),
+        fan_rpm: Some(vec![2400.0 + wave(30, 300.0)]),
+        core_temperatures: Some(CoreTemperatureData {
+            p_cores,
+            e_cores,
+            cpu_temp_avg,
+            cpu_temp_max,
+            cpu_temp_min: 45.0,
+            gpu_temps,
+            gpu_temp_avg: Some(53.5),
+            gpu_temp_max: Some(57.0),
+            battery_temp_avg: Some(31.0),
+            thermal_trend: ThermalTrend::Stable,
+            dies: None,
+        }),
+        total_energy_wh: Some(i as f64 * 0.0068),
+        cpu_energy_wh: Some(i as f64 * 0.0051),
+        gpu_energy_wh: Some(i as f64 * 0.0017),
+        ane_energy_wh: Some(0.0),
+        ..Default::default()
+    }
+}
+
+fn synthetic_session(len: usize) -> Vec<Value> {
+    (0..len).map(|i| serde_json::to_value(synthetic_sample(i)).unwrap()).collect()
+}
+
+fn bench_serialization(c: &mut Criterion) {
+    let sample = synthetic_sample(7);
+    let mut group = c.benchmark_group("telemetry_serialization");
+    group.throughput(Throughput::Elements(1));
+    // What the window emit does for every sample
+    group.bench_function("to_string", |b| b.iter(|| serde_json::to_string(black_box(&sample)).unwrap()));
+    // What session persistence does for every recorded sample
+    group.bench_function("to_value", |b| b.iter(|| serde_json::to_value(black_box(&sample)).unwrap()));
+    group.finish();
+}
+
+fn bench_compression(c: &mut Criterion) {
+    let mut group = c.benchmark_group("telemetry_compression");
+    for len in SESSION_LENGTHS {
+        let session = synthetic_session(len);
+        let compressed = compress_telemetry_data(&session).unwrap();
+        assert_eq!(decompress_telemetry_data(&compressed).unwrap().len(), len);
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("compress", len), &session, |b, session| {
+            b.iter(|| compress_telemetry_data(black_box(session)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("round_trip", len), &session, |b, session| {
+            b.iter(|| decompress_telemetry_data(&compress_telemetry_data(black_box(session)).unwrap()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_hardware_reads(c: &mut Criterion) {
+    if synthetic_mode() {
+        println!("Skipping hardware read benchmarks (synthetic mode)");
+        return;
+    }
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("hardware_reads");
+    // Each iteration talks to IOHID / sysinfo, so keep the sample count low
+    group.sample_size(10);
+
+    group.bench_function("read_core_temperatures", |b| {
+        b.to_async(&runtime).iter(|| async { read_core_temperatures().await })
+    });
+
+    // Includes the monitor's fixed refresh delay; a regression shows as time on top of it
+    let mut monitor = CpuUtilizationMonitor::new();
+    group.measurement_time(Duration::from_secs(10));
+    group.bench_function("get_cpu_utilization", |b| {
+        b.iter(|| runtime.block_on(monitor.get_cpu_utilization()))
+    });
+    group.finish();
+}
+
+fn config() -> Criterion {
+    if synthetic_mode() {
+        // Short, stable runs for CI; enough to catch regressions of a few percent
+        Criterion::default()
+            .warm_up_time(Duration::from_millis(500))
+            .measurement_time(Duration::from_secs(2))
+    } else {
+        Criterion::default()
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = config();
+    targets = bench_serialization, bench_compression, bench_hardware_reads
+}
+criterion_main!(benches);
@@ -1,3 +1,26 @@
+// Locked version of a package from Cargo.lock ("name = ..." is followed by "version = ...")
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name_line = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == name_line {
+            let version = lines.next()?.trim().strip_prefix("version = \"")?.strip_suffix('"')?;
+            return Some(version.to_string());
+        }
+    }
+    None
+}
+
 fn main() {
+    // Each llama-cpp-sys-2 release vendors one llama.cpp commit, so the locked binding
+    // versions identify the llama.cpp build that sessions were recorded with
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+    for (package, env_var) in [("llama-cpp-2", "LLAMA_CPP_2_VERSION"), ("llama-cpp-sys-2", "LLAMA_CPP_SYS_2_VERSION")] {
+        let version = locked_version(&lock, package).unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={}={}", env_var, version);
+    }
+    println!("cargo:rustc-env=APP_BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+
     tauri_build::build()
 }
@@ -0,0 +1,42 @@
+// Contains the commands that start and stop the OpenAI-compatible API server
+
+use serde::Serialize;
+use tauri::Window;
+
+use crate::server;
+use crate::error::A2oError;
+
+#[derive(Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub base_url: Option<String>,   // e.g. "http://127.0.0.1:8080/v1"
+    pub token: Option<String>,      // Bearer token for this app launch, while running
+}
+
+fn status() -> ApiServerStatus {
+    let address = server::api_server_address();
+    ApiServerStatus {
+        running: address.is_some(),
+        base_url: address.map(|addr| format!("http://{}/v1", addr)),
+        token: address.map(|_| server::api_server_token().to_string()),
+    }
+}
+
+/// Serve `/v1/chat/completions` on 127.0.0.1:`port`; the runs it starts show up in this
+/// window like any other turn. Clients authenticate with the returned token.
+#[tauri::command]
+pub async fn start_api_server(window: Window, port: u16) -> Result<ApiServerStatus, A2oError> {
+    server::start_api_server(window, port).await?;
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn stop_api_server() -> ApiServerStatus {
+    server::stop_api_server();
+    status()
+}
+
+#[tauri::command]
+pub fn get_api_server_status() -> ApiServerStatus {
+    status()
+}
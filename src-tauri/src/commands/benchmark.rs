@@ -0,0 +1,228 @@
+// Contains benchmark suite commands
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Emitter, State, Window};
+
+use crate::commands::generation::execute_generation_turn;
+use crate::events::{window_sink_with, EventSink};
+use crate::persistence::analysis::compare_benchmark_models;
+use crate::persistence::benchmark::{aggregate_benchmark_runs, model_orders, BenchmarkReport, BenchmarkRun, BenchmarkSuiteRequest, ModelOrder};
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::cooldown::{measure_cooldown_baseline, wait_for_cpu_cooldown, wait_for_thermal_pressure, MAX_THERMAL_PRESSURE_WAIT};
+use crate::telemetry::processor::GLOBAL_STOP_SIGNAL;
+use crate::telemetry::temperature_unit::TemperatureUnit;
+use crate::telemetry::types::{GenerationConfig, Message, DEFAULT_COOLDOWN_MARGIN_C};
+use crate::error::A2oError;
+
+// Emitted after each prompt/repetition of a suite
+#[derive(Clone, Serialize)]
+struct BenchmarkProgressEvent {
+    suite_id: String,
+    completed: usize,
+    total: usize,
+}
+
+// Thermal waits before a suite or comparison run: back to the baseline CPU temperature
+// (when one was measured), then out of serious thermal pressure. stop_generation cancels
+// the waits, which is reported as false.
+pub(crate) async fn wait_before_run(
+    events: &dyn EventSink,
+    baseline_c: Option<f64>,
+    margin_c: f64,
+    defer_on_thermal_pressure: bool,
+    unit: TemperatureUnit,
+) -> bool {
+    if baseline_c.is_none() && !defer_on_thermal_pressure {
+        return true;
+    }
+    // No run is active between runs, so the waits get their own stop signal
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = Some(stop_signal.clone());
+    }
+    if let Some(baseline_c) = baseline_c {
+        wait_for_cpu_cooldown(events, baseline_c, margin_c, unit).await;
+    }
+    if defer_on_thermal_pressure && !stop_signal.load(Ordering::Relaxed) {
+        wait_for_thermal_pressure(events, MAX_THERMAL_PRESSURE_WAIT).await;
+    }
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = None;
+    }
+    !stop_signal.load(Ordering::Relaxed)
+}
+
+/// Run every prompt `repetitions` times against the configured target ("Both" runs A then
+/// B back-to-back, never in parallel), each as a fresh single-turn chat, then save the
+/// runs and their per-model statistics as one report. With an alternating or randomized
+/// model order, "Both" runs each model as its own turn in that repetition's order. Runs can wait for the CPU to cool
+/// back to its pre-suite temperature and be deferred under serious thermal pressure, so
+/// unattended suites stay comparable. Stopping the generation ends the suite early; the
+/// runs completed so far are still saved.
+#[tauri::command]
+pub async fn run_benchmark_suite(
+    window: Window,
+    db: State<'_, SessionDatabase>,
+    request: BenchmarkSuiteRequest,
+    config: GenerationConfig,
+) -> Result<BenchmarkReport, A2oError> {
+    execute_benchmark_suite(window, db.inner(), request, config).await
+}
+
+// Body of run_benchmark_suite, shared with the night batch scheduler
+pub(crate) async fn execute_benchmark_suite(
+    window: Window,
+    db: &SessionDatabase,
+    request: BenchmarkSuiteRequest,
+    config: GenerationConfig,
+) -> Result<BenchmarkReport, A2oError> {
+    let prompts: Vec<String> = request.prompts.iter()
+        .map(|prompt| prompt.trim().to_string())
+        .filter(|prompt| !prompt.is_empty())
+        .collect();
+    if prompts.is_empty() {
+        return Err(A2oError::InvalidInput("A benchmark suite needs at least one prompt".to_string()));
+    }
+    if request.repetitions == 0 {
+        return Err(A2oError::InvalidInput("Repetitions must be at least 1".to_string()));
+    }
+    let cooldown = match request.cooldown_secs {
+        Some(secs) if !secs.is_finite() || secs < 0.0 => {
+            return Err(A2oError::InvalidInput(format!("Invalid cooldown of {} s", secs)));
+        }
+        secs => Duration::from_secs_f64(secs.unwrap_or(0.0)),
+    };
+
+    let model_order = if config.target == "Both" {
+        request.model_order
+    } else {
+        if request.model_order != ModelOrder::Fixed {
+            println!("ℹ️ Model order only applies to \"Both\" suites - running {} only", config.target);
+        }
+        ModelOrder::Fixed
+    };
+    let orders = model_orders(model_order, request.repetitions, || {
+        use std::hash::{BuildHasher, Hasher};
+        std::collections::hash_map::RandomState::new().build_hasher().finish() % 2 == 0
+    });
+
+    let margin_c = request.cooldown_margin_c.unwrap_or(DEFAULT_COOLDOWN_MARGIN_C).clamp(-20.0, 20.0);
+    let unit = db.temperature_unit().unwrap_or_default();
+    let events = window_sink_with(window.clone(), config.event_sink.as_deref());
+
+    let suite_id = uuid::Uuid::new_v4().to_string();
+    let total = prompts.len() * request.repetitions as usize;
+    println!("🏋️ Benchmark suite {}: {} prompt(s) x {} repetition(s), target {}, {:?} model order",
+             suite_id, prompts.len(), request.repetitions, config.target, model_order);
+
+    let baseline_c = if request.wait_for_cpu_baseline {
+        measure_cooldown_baseline(events.as_ref(), margin_c, unit).await
+    } else {
+        None
+    };
+
+    let mut runs: Vec<BenchmarkRun> = Vec::new();
+    let mut thermal_wait = Duration::ZERO;
+    let mut completed = 0;
+    let mut turns_run = 0;
+    let mut stopped = false;
+    'suite: for (repetition, order) in (0..request.repetitions).zip(&orders) {
+        // One "Both" turn in fixed order, otherwise one single-model turn per model
+        let targets: Vec<&str> = match model_order {
+            ModelOrder::Fixed => vec![config.target.as_str()],
+            _ => order.to_vec(),
+        };
+        for (prompt_index, prompt) in prompts.iter().enumerate() {
+            let mut position = 0;
+            for target in &targets {
+                if turns_run > 0 && !cooldown.is_zero() {
+                    tokio::time::sleep(cooldown).await;
+                }
+                let wait_start = Instant::now();
+                let baseline_c = baseline_c.filter(|_| turns_run > 0);
+                let proceed = wait_before_run(events.as_ref(), baseline_c, margin_c, request.defer_on_thermal_pressure, unit).await;
+                thermal_wait += wait_start.elapsed();
+                if !proceed {
+                    stopped = true;
+                    break 'suite;
+                }
+
+                let mut turn_config = config.clone();
+                turn_config.target = target.to_string();
+                turn_config.chat_history = vec![Message { role: "user".to_string(), content: prompt.clone(), model: None }];
+                turn_config.parallel = Some(false);
+                let turn = match execute_generation_turn(window.clone(), turn_config).await {
+                    Ok(turn) => turn,
+                    Err(A2oError::Cancelled) => {
+                        stopped = true;
+                        break 'suite;
+                    }
+                    Err(e) => return Err(e),
+                };
+                turns_run += 1;
+                for result in &turn.results {
+                    runs.push(BenchmarkRun::from_result(prompt_index, repetition, position, result));
+                    position += 1;
+                }
+                if turn.results.iter().any(|result| result.finish_reason == "user_stop") {
+                    stopped = true;
+                    break 'suite;
+                }
+            }
+            completed += 1;
+            let _ = window.emit("benchmark_progress", BenchmarkProgressEvent {
+                suite_id: suite_id.clone(),
+                completed,
+                total,
+            });
+        }
+    }
+    if stopped {
+        println!("🛑 Benchmark suite {} stopped after {}/{} run(s)", suite_id, completed, total);
+    }
+
+    let created_at = chrono::Utc::now().timestamp();
+    let report = BenchmarkReport {
+        name: request.name.filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| format!("Benchmark {}", chrono::Utc::now().format("%Y-%m-%d %H:%M"))),
+        uuid: suite_id,
+        created_at,
+        target: config.target.clone(),
+        prompts,
+        repetitions: request.repetitions,
+        cooldown_secs: request.cooldown_secs,
+        wait_for_cpu_baseline: request.wait_for_cpu_baseline,
+        defer_on_thermal_pressure: request.defer_on_thermal_pressure,
+        model_order,
+        thermal_wait_secs: thermal_wait.as_secs_f64(),
+        completed: !stopped,
+        models: aggregate_benchmark_runs(&runs),
+        differences: compare_benchmark_models(&runs),
+        runs,
+    };
+    for difference in &report.differences {
+        let verdict = match difference.p_value {
+            Some(p_value) => format!("p = {:.3}{}", p_value, if difference.significant { "" } else { " - not significant" }),
+            None => "too few runs to test".to_string(),
+        };
+        println!("📐 Benchmark suite {}: {} B - A = {:.4} (95% CI {:.4} to {:.4}, {})",
+                 report.uuid, difference.metric, difference.difference, difference.ci95_low, difference.ci95_high, verdict);
+    }
+    db.save_benchmark_report(&report)?;
+    println!("🏋️ Benchmark suite {} saved with {} run result(s)", report.uuid, report.runs.len());
+    Ok(report)
+}
+
+/// Saved benchmark reports, newest first
+#[tauri::command]
+pub fn list_benchmark_reports(db: State<'_, SessionDatabase>) -> Result<Vec<BenchmarkReport>, A2oError> {
+    let mut reports = db.benchmark_reports()?;
+    // Differences are recomputed from the runs, so older reports follow the current method
+    for report in reports.iter_mut() {
+        report.differences = compare_benchmark_models(&report.runs);
+    }
+    Ok(reports)
+}
@@ -0,0 +1,57 @@
+// Contains measurement-overhead calibration commands
+
+use std::time::Duration;
+use tauri::Window;
+
+use crate::events::TauriWindowSink;
+use crate::telemetry::calibration::{run_overhead_calibration, OverheadCalibration};
+use crate::telemetry::processor::{ActiveRunGuard, OVERHEAD_CALIBRATION};
+use crate::telemetry::types::{
+    TelemetryProfile, DEFAULT_TELEMETRY_SAMPLING_HZ, MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ
+};
+use crate::error::A2oError;
+
+const DEFAULT_CALIBRATION_PHASE_S: u64 = 10;
+const MIN_CALIBRATION_PHASE_S: u64 = 3;
+const MAX_CALIBRATION_PHASE_S: u64 = 60;
+
+/// Measure the telemetry system's own overhead with inference idle and keep the result
+/// for later power summaries. Holds the run guard so no generation can start meanwhile.
+#[tauri::command]
+pub async fn calibrate_measurement_overhead(
+    window: Window,
+    sampling_hz: Option<f32>,
+    phase_duration_s: Option<u64>,
+    profile: Option<TelemetryProfile>,
+) -> Result<OverheadCalibration, A2oError> {
+    let _run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected calibration request: run {} in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+
+    let sampling_hz = sampling_hz
+        .unwrap_or(DEFAULT_TELEMETRY_SAMPLING_HZ)
+        .clamp(MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ);
+    let phase_duration_s = phase_duration_s
+        .unwrap_or(DEFAULT_CALIBRATION_PHASE_S)
+        .clamp(MIN_CALIBRATION_PHASE_S, MAX_CALIBRATION_PHASE_S);
+
+    let calibration = run_overhead_calibration(&TauriWindowSink::new(window), profile.unwrap_or_default(), sampling_hz, Duration::from_secs(phase_duration_s)).await;
+
+    let mut stored = OVERHEAD_CALIBRATION.write()
+        .map_err(|e| format!("Failed to store calibration: {}", e))?;
+    *stored = Some(calibration.clone());
+    Ok(calibration)
+}
+
+#[tauri::command]
+pub fn get_measurement_overhead() -> Option<OverheadCalibration> {
+    OVERHEAD_CALIBRATION.read().ok().and_then(|calibration| calibration.clone())
+}
+
+#[tauri::command]
+pub fn clear_measurement_overhead() {
+    if let Ok(mut calibration) = OVERHEAD_CALIBRATION.write() {
+        *calibration = None;
+    }
+}
@@ -0,0 +1,341 @@
+// Contains render_comparison_summary Tauri command (shareable Markdown/text/JSON comparison summaries)
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::hardware::cpu_monitor::detect_apple_silicon_configuration;
+use crate::inference::context_size::system_memory_bytes;
+use crate::persistence::analysis::{compare_benchmark_models, MetricDifference, MIN_RUNS_FOR_SIGNIFICANCE, SIGNIFICANCE_LEVEL};
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::temperature_unit::TemperatureUnit;
+use crate::error::A2oError;
+
+/// Identifies the JSON comparison card layout so other tools can parse shared cards
+pub const COMPARISON_CARD_SCHEMA: &str = "apples2oranges.comparison-card/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    Markdown,
+    Text,
+    Json,
+}
+
+/// Key metrics for one model, as collected by the frontend for the current run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelComparisonEntry {
+    pub label: String,                      // "A" / "B"
+    pub model: String,                      // Model file name
+    pub ttft_ms: Option<f64>,
+    pub decode_tps: Option<f64>,
+    pub prefill_tps: Option<f64>,
+    pub output_tokens: Option<usize>,
+    pub total_energy_wh: Option<f64>,
+    pub energy_per_token_wh: Option<f64>,
+    pub inter_token_cv: Option<f64>,
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub peak_cpu_temp_c: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_cpu_temp_f: Option<f64>,   // Convenience copy when the preference is Fahrenheit
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareSummary {
+    pub chip: String,
+    pub total_cores: usize,
+    pub p_cores: usize,
+    pub e_cores: usize,
+    pub memory_gb: Option<f64>,
+    pub os: String,
+}
+
+/// Self-describing comparison card (the JSON format, also the source for Markdown/text)
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonCard {
+    pub schema: String,
+    pub app_version: String,
+    pub created_at_ms: u64,
+    pub hardware: HardwareSummary,
+    pub telemetry_sampling_hz: Option<f32>,
+    pub temperature_unit: TemperatureUnit,
+    pub models: Vec<ModelComparisonEntry>,
+    pub differences: Vec<MetricDifference>,   // B vs A over a benchmark suite's repeated runs; empty for single runs
+}
+
+/// Render the current run's comparison card. With `benchmark_uuid`, the card also reports
+/// that suite's B-vs-A differences and whether each is statistically significant.
+#[tauri::command]
+pub fn render_comparison_summary(
+    db: State<'_, SessionDatabase>,
+    mut models: Vec<ModelComparisonEntry>,
+    telemetry_sampling_hz: Option<f32>,
+    format: SummaryFormat,
+    benchmark_uuid: Option<String>,
+) -> Result<String, A2oError> {
+    if models.is_empty() {
+        return Err(A2oError::InvalidInput("No model results to summarize".to_string()));
+    }
+    let differences = match benchmark_uuid {
+        Some(uuid) => db.benchmark_reports()?
+            .into_iter()
+            .find(|report| report.uuid == uuid)
+            .map(|report| compare_benchmark_models(&report.runs))
+            .ok_or_else(|| A2oError::NotFound(format!("Benchmark report {} not found", uuid)))?,
+        None => Vec::new(),
+    };
+    let temperature_unit = db.temperature_unit()?;
+    if temperature_unit == TemperatureUnit::Fahrenheit {
+        for entry in &mut models {
+            entry.peak_cpu_temp_f = entry.peak_cpu_temp_c.map(|c| temperature_unit.from_celsius(c));
+        }
+    }
+    let card = ComparisonCard {
+        schema: COMPARISON_CARD_SCHEMA.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        hardware: hardware_summary(),
+        telemetry_sampling_hz,
+        temperature_unit,
+        models,
+        differences,
+    };
+    Ok(render(&card, format)?)
+}
+
+pub(crate) fn hardware_summary() -> HardwareSummary {
+    let total_cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(0);
+    let silicon = detect_apple_silicon_configuration(total_cores);
+    HardwareSummary {
+        chip: silicon.chip_name,
+        total_cores,
+        p_cores: silicon.p_cores,
+        e_cores: silicon.e_cores,
+        memory_gb: system_memory_bytes().map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0)),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+    }
+}
+
+pub fn render(card: &ComparisonCard, format: SummaryFormat) -> Result<String, String> {
+    match format {
+        SummaryFormat::Json => serde_json::to_string_pretty(card)
+            .map_err(|e| format!("Failed to serialize comparison card: {}", e)),
+        SummaryFormat::Markdown => Ok(render_markdown(card)),
+        SummaryFormat::Text => Ok(render_text(card)),
+    }
+}
+
+// (header, value) for each metric row, in display order
+fn metric_rows(entry: &ModelComparisonEntry, unit: TemperatureUnit) -> Vec<(&'static str, String)> {
+    vec![
+        ("TTFT", fmt_opt(entry.ttft_ms, |v| format!("{:.0} ms", v))),
+        ("Decode", fmt_opt(entry.decode_tps, |v| format!("{:.2} tok/s", v))),
+        ("Prefill", fmt_opt(entry.prefill_tps, |v| format!("{:.1} tok/s", v))),
+        ("Tokens", fmt_opt(entry.output_tokens, |v| v.to_string())),
+        ("Energy", fmt_opt(entry.total_energy_wh, |v| format!("{:.4} Wh", v))),
+        ("Energy/token", fmt_opt(entry.energy_per_token_wh, |v| format!("{:.3} mWh", v * 1000.0))),
+        ("Jitter (CV)", fmt_opt(entry.inter_token_cv, |v| format!("{:.2}", v))),
+        ("Finish", entry.finish_reason.clone().unwrap_or_else(|| "-".to_string())),
+        ("Peak CPU", fmt_opt(entry.peak_cpu_temp_c, |v| unit.format(v))),
+    ]
+}
+
+// (metric, B - A with its interval, verdict) for each compared difference
+fn difference_rows(card: &ComparisonCard) -> Vec<(&'static str, String, String)> {
+    card.differences.iter()
+        .map(|difference| {
+            let (name, fmt): (&'static str, fn(f64) -> String) = match difference.metric.as_str() {
+                "ttft_ms" => ("TTFT", |v| format!("{:+.0} ms", v)),
+                "avg_tps" => ("Decode", |v| format!("{:+.2} tok/s", v)),
+                "total_energy_wh" => ("Energy", |v| format!("{:+.4} Wh", v)),
+                "energy_per_token_wh" => ("Energy/token", |v| format!("{:+.3} mWh", v * 1000.0)),
+                _ => ("Other", |v| format!("{:+.4}", v)),
+            };
+            let value = format!("{} (95% CI {} to {}, {} vs {} runs)",
+                                fmt(difference.difference), fmt(difference.ci95_low), fmt(difference.ci95_high),
+                                difference.runs_b, difference.runs_a);
+            let verdict = match difference.p_value {
+                Some(p_value) => format!("{} (p = {:.3})",
+                                         if difference.significant { "significant" } else { "not significant" }, p_value),
+                None => format!("not tested (fewer than {} runs per model)", MIN_RUNS_FOR_SIGNIFICANCE),
+            };
+            (name, value, verdict)
+        })
+        .collect()
+}
+
+// Shown instead of the differences when there is nothing to test them with
+const SINGLE_RUN_NOTE: &str = "Single run per model: differences are not tested for significance";
+
+fn fmt_opt<T>(value: Option<T>, f: impl Fn(T) -> String) -> String {
+    value.map(f).unwrap_or_else(|| "-".to_string())
+}
+
+fn hardware_line(card: &ComparisonCard) -> String {
+    let hw = &card.hardware;
+    let memory = hw.memory_gb.map(|gb| format!(", {:.0} GB", gb)).unwrap_or_default();
+    format!("{} ({}P + {}E cores{}), {}", hw.chip, hw.p_cores, hw.e_cores, memory, hw.os)
+}
+
+fn render_markdown(card: &ComparisonCard) -> String {
+    let mut out = String::from("## apples2oranges comparison\n\n");
+    out.push_str(&format!("**Hardware:** {}\n", hardware_line(card)));
+    if let Some(hz) = card.telemetry_sampling_hz {
+        out.push_str(&format!("**Telemetry:** {} Hz\n", hz));
+    }
+    out.push('\n');
+
+    let headers: Vec<&str> = metric_rows(&card.models[0], card.temperature_unit).iter().map(|(header, _)| *header).collect();
+    out.push_str(&format!("| Model | {} |\n", headers.join(" | ")));
+    out.push_str(&format!("|---|{}\n", "---|".repeat(headers.len())));
+    for entry in &card.models {
+        let values: Vec<String> = metric_rows(entry, card.temperature_unit).into_iter().map(|(_, value)| value).collect();
+        out.push_str(&format!("| {} ({}) | {} |\n", entry.label, escape_markdown_cell(&entry.model), values.join(" | ")));
+    }
+    if !card.differences.is_empty() {
+        out.push_str(&format!("\n**B vs A** (bootstrap 95% CI, permutation test at p < {})\n\n", SIGNIFICANCE_LEVEL));
+        out.push_str("| Metric | B - A | Verdict |\n|---|---|---|\n");
+        for (name, value, verdict) in difference_rows(card) {
+            out.push_str(&format!("| {} | {} | {} |\n", name, value, verdict));
+        }
+    } else if card.models.len() > 1 {
+        out.push_str(&format!("\n_{}._\n", SINGLE_RUN_NOTE));
+    }
+    out.push_str(&format!("\n_apples2oranges {}_\n", card.app_version));
+    out
+}
+
+fn escape_markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn render_text(card: &ComparisonCard) -> String {
+    let mut out = String::from("apples2oranges comparison\n");
+    out.push_str(&format!("Hardware: {}\n", hardware_line(card)));
+    if let Some(hz) = card.telemetry_sampling_hz {
+        out.push_str(&format!("Telemetry: {} Hz\n", hz));
+    }
+    for entry in &card.models {
+        out.push_str(&format!("\nModel {}: {}\n", entry.label, entry.model));
+        for (header, value) in metric_rows(entry, card.temperature_unit) {
+            out.push_str(&format!("  {:<13}{}\n", format!("{}:", header), value));
+        }
+    }
+    if !card.differences.is_empty() {
+        out.push_str(&format!("\nB vs A (bootstrap 95% CI, permutation test at p < {}):\n", SIGNIFICANCE_LEVEL));
+        for (name, value, verdict) in difference_rows(card) {
+            out.push_str(&format!("  {:<13}{} - {}\n", format!("{}:", name), value, verdict));
+        }
+    } else if card.models.len() > 1 {
+        out.push_str(&format!("\n{}.\n", SINGLE_RUN_NOTE));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::analysis::compare_metric;
+
+    fn sample_card() -> ComparisonCard {
+        ComparisonCard {
+            schema: COMPARISON_CARD_SCHEMA.to_string(),
+            app_version: "0.1.0".to_string(),
+            created_at_ms: 0,
+            hardware: HardwareSummary {
+                chip: "M3 Max".to_string(),
+                total_cores: 16,
+                p_cores: 12,
+                e_cores: 4,
+                memory_gb: Some(64.0),
+                os: "macos aarch64".to_string(),
+            },
+            telemetry_sampling_hz: Some(1.0),
+            temperature_unit: TemperatureUnit::Celsius,
+            models: vec![
+                ModelComparisonEntry {
+                    label: "A".to_string(),
+                    model: "qwen|7b.gguf".to_string(),
+                    ttft_ms: Some(212.0),
+                    decode_tps: Some(41.256),
+                    energy_per_token_wh: Some(0.0012),
+                    finish_reason: Some("eog".to_string()),
+                    peak_cpu_temp_c: Some(78.0),
+                    ..Default::default()
+                },
+                ModelComparisonEntry {
+                    label: "B".to_string(),
+                    model: "llama-8b.gguf".to_string(),
+                    ..Default::default()
+                },
+            ],
+            differences: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_table_has_one_row_per_model() {
+        let markdown = render(&sample_card(), SummaryFormat::Markdown).unwrap();
+        assert!(markdown.contains("**Hardware:** M3 Max (12P + 4E cores, 64 GB), macos aarch64"));
+        assert!(markdown.contains("| A (qwen\\|7b.gguf) | 212 ms | 41.26 tok/s | - | - | - | 1.200 mWh | - | eog |"));
+        assert!(markdown.contains("| B (llama-8b.gguf) | - | - | - | - | - | - | - | - |"));
+        // Header and separator have the same column count as the rows
+        let columns = |line: &str| line.matches('|').count();
+        let lines: Vec<&str> = markdown.lines().filter(|line| line.starts_with('|')).collect();
+        assert_eq!(columns(lines[0]), columns(lines[1]));
+    }
+
+    #[test]
+    fn test_json_card_is_self_describing() {
+        let json = render(&sample_card(), SummaryFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema"], COMPARISON_CARD_SCHEMA);
+        assert_eq!(value["models"][0]["decode_tps"], 41.256);
+        assert_eq!(value["hardware"]["p_cores"], 12);
+    }
+
+    #[test]
+    fn test_text_lists_metrics_per_model() {
+        let text = render(&sample_card(), SummaryFormat::Text).unwrap();
+        assert!(text.contains("Model A: qwen|7b.gguf"));
+        assert!(text.contains("  Decode:      41.26 tok/s"));
+        assert!(text.contains(SINGLE_RUN_NOTE));
+    }
+
+    #[test]
+    fn test_differences_are_marked_significant_or_not() {
+        let mut card = sample_card();
+        card.differences = vec![
+            compare_metric("avg_tps", &[40.1, 40.3, 39.8, 39.8], &[44.9, 45.2, 45.1, 44.8]).unwrap(),
+            compare_metric("ttft_ms", &[210.0, 260.0, 190.0, 240.0], &[230.0, 205.0, 250.0, 247.0]).unwrap(),
+            compare_metric("total_energy_wh", &[0.010, 0.011], &[0.012, 0.013]).unwrap(),
+        ];
+        let markdown = render(&card, SummaryFormat::Markdown).unwrap();
+        assert!(markdown.contains("| Decode | +5.00 tok/s (95% CI"));
+        assert!(markdown.contains("4 vs 4 runs) | significant (p = "));
+        assert!(markdown.contains("| TTFT | +8 ms (95% CI"));
+        assert!(markdown.contains("4 vs 4 runs) | not significant (p = "));
+        assert!(markdown.contains("2 vs 2 runs) | not tested (fewer than 4 runs per model) |"));
+        assert!(!markdown.contains(SINGLE_RUN_NOTE));
+        let value: serde_json::Value = serde_json::from_str(&render(&card, SummaryFormat::Json).unwrap()).unwrap();
+        assert_eq!(value["differences"][1]["significant"], false);
+    }
+
+    #[test]
+    fn test_temperatures_follow_unit_preference() {
+        let mut card = sample_card();
+        assert!(render(&card, SummaryFormat::Text).unwrap().contains("  Peak CPU:    78.0°C"));
+        card.temperature_unit = TemperatureUnit::Fahrenheit;
+        card.models[0].peak_cpu_temp_f = Some(172.4);
+        let markdown = render(&card, SummaryFormat::Markdown).unwrap();
+        assert!(markdown.contains("| eog | 172.4°F |"));
+        // The JSON keeps the recorded Celsius value alongside the converted one
+        let value: serde_json::Value = serde_json::from_str(&render(&card, SummaryFormat::Json).unwrap()).unwrap();
+        assert_eq!(value["temperature_unit"], "fahrenheit");
+        assert_eq!(value["models"][0]["peak_cpu_temp_c"], 78.0);
+        assert_eq!(value["models"][0]["peak_cpu_temp_f"], 172.4);
+    }
+}
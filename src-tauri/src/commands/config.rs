@@ -0,0 +1,49 @@
+// Contains configuration-related Tauri commands (canonical backend defaults)
+
+use crate::ModelConfig;
+use crate::telemetry::types::{
+    DefaultConfigs, DEFAULT_TELEMETRY_SAMPLING_HZ, MIN_TELEMETRY_SAMPLING_HZ,
+    MAX_TELEMETRY_SAMPLING_HZ, DEFAULT_COOLDOWN_MARGIN_C, MINIMAL_PROFILE_MAX_SAMPLING_HZ,
+    TelemetryProfile, IntegrationMethod, DEFAULT_ROLLING_TPS_WINDOW_SECS, DEFAULT_STALL_THRESHOLD_MS
+};
+use crate::inference::generation::{DEFAULT_N_CTX, DEFAULT_MAX_TOKENS};
+use crate::inference::sampler_presets::{sampler_presets, SamplerPreset};
+
+#[tauri::command]
+pub fn get_default_configs() -> DefaultConfigs {
+    let model = ModelConfig::default();
+    DefaultConfigs {
+        telemetry_sampling_hz: model.telemetry_sampling_hz.unwrap_or(DEFAULT_TELEMETRY_SAMPLING_HZ),
+        model,
+        fallback_n_ctx: DEFAULT_N_CTX,
+        max_generation_tokens: DEFAULT_MAX_TOKENS,
+        telemetry_sampling_hz_min: MIN_TELEMETRY_SAMPLING_HZ,
+        telemetry_sampling_hz_max: MAX_TELEMETRY_SAMPLING_HZ,
+        run_without_telemetry: false,
+        telemetry_profile: TelemetryProfile::default(),
+        minimal_profile_max_sampling_hz: MINIMAL_PROFILE_MAX_SAMPLING_HZ,
+        energy_integration_method: IntegrationMethod::default(),
+        rolling_tps_window_secs: DEFAULT_ROLLING_TPS_WINDOW_SECS,
+        stall_threshold_ms: DEFAULT_STALL_THRESHOLD_MS,
+        wait_for_cpu_baseline_between_models: false,
+        wait_for_cpu_baseline_margin_c: DEFAULT_COOLDOWN_MARGIN_C,
+    }
+}
+
+/// Named sampler presets a ModelConfig can reference via `sampler_preset`
+#[tauri::command]
+pub fn get_sampler_presets() -> Vec<SamplerPreset> {
+    sampler_presets()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::types::ContextSize;
+
+    #[test]
+    fn test_default_model_context_matches_fallback() {
+        let defaults = get_default_configs();
+        assert_eq!(defaults.model.n_ctx, Some(ContextSize::Fixed(defaults.fallback_n_ctx)));
+    }
+}
@@ -0,0 +1,267 @@
+// Contains backend-held conversations (create_conversation, append_user_message, run_turn,
+// export_conversation, delete_conversation). The backend owns the canonical history, so a
+// caller sends only the new user message each turn instead of re-sending the whole chat.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::Window;
+
+use crate::commands::generation::execute_generation_turn;
+use crate::persistence::migration::SESSION_SCHEMA_VERSION;
+use crate::telemetry::types::{GenerationConfig, GenerationResult, GenerationTurnResult, Message};
+use crate::error::A2oError;
+
+// Conversations held by the backend, keyed by conversation id
+static CONVERSATIONS: RwLock<Option<HashMap<String, Conversation>>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    pub token_count: Option<usize>,        // Assistant turns only, from the GenerationResult
+    pub generation_time_ms: Option<u64>,
+    pub finish_reason: Option<String>,
+    pub truncated: Option<bool>,
+}
+
+impl ConversationMessage {
+    fn user(content: &str) -> Self {
+        Self {
+            message: Message { role: "user".to_string(), content: content.to_string(), model: None },
+            token_count: None,
+            generation_time_ms: None,
+            finish_reason: None,
+            truncated: None,
+        }
+    }
+
+    fn assistant(result: &GenerationResult) -> Self {
+        Self {
+            message: Message {
+                role: "assistant".to_string(),
+                content: result.text.clone(),
+                model: Some(result.model.clone()),
+            },
+            token_count: Some(result.output_tokens),
+            generation_time_ms: Some(result.generation_time_ms),
+            finish_reason: Some(result.finish_reason.clone()),
+            truncated: Some(result.truncated),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversation {
+    pub id: String,
+    pub created_at_ms: u64,
+    pub system_prompt: Option<String>,
+    pub messages: Vec<ConversationMessage>,
+}
+
+impl Conversation {
+    pub fn new(id: &str, system_prompt: Option<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            created_at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            system_prompt: system_prompt.filter(|prompt| !prompt.trim().is_empty()),
+            messages: Vec::new(),
+        }
+    }
+
+    /// History in the shape execute_generation_turn expects
+    pub fn chat_history(&self) -> Vec<Message> {
+        self.messages.iter().map(|entry| entry.message.clone()).collect()
+    }
+
+    /// A user message may only follow an answered turn, so each turn has exactly one prompt
+    pub fn append_user_message(&mut self, content: &str) -> Result<(), String> {
+        if content.trim().is_empty() {
+            return Err("User message is empty".to_string());
+        }
+        if self.has_pending_user_message() {
+            return Err(format!("Conversation {} already has a user message waiting for a turn", self.id));
+        }
+        self.messages.push(ConversationMessage::user(content));
+        Ok(())
+    }
+
+    pub fn has_pending_user_message(&self) -> bool {
+        self.messages.last().is_some_and(|entry| entry.message.role == "user")
+    }
+
+    /// Append each model's reply in run order
+    pub fn record_results(&mut self, results: &[GenerationResult]) {
+        self.messages.extend(results.iter().map(ConversationMessage::assistant));
+    }
+
+    /// Session-format data (as saved by the frontend) so a conversation can be saved or loaded
+    pub fn to_session_data(&self) -> Value {
+        let chat_history: Vec<Value> = self.messages.iter().enumerate()
+            .map(|(index, entry)| json!({
+                "id": format!("msg_{}_{}", self.id, index),
+                "role": entry.message.role,
+                "content": entry.message.content,
+                "model": entry.message.model,
+                "token_count": entry.token_count,
+                "generation_time_ms": entry.generation_time_ms,
+                "finish_reason": entry.finish_reason,
+                "truncated": entry.truncated,
+            }))
+            .collect();
+        json!({
+            "schema_version": SESSION_SCHEMA_VERSION,
+            "session_metadata": {
+                "saved_at": self.created_at_ms,
+                "app_version": env!("CARGO_PKG_VERSION"),
+                "conversation_id": self.id,
+            },
+            "chat_history": chat_history,
+            "configuration": { "system_prompt": self.system_prompt },
+            "model_fingerprints": {},
+            "run_manifest": Value::Null,
+        })
+    }
+}
+
+fn with_conversation<T>(id: &str, f: impl FnOnce(&mut Conversation) -> Result<T, A2oError>) -> Result<T, A2oError> {
+    let mut store = CONVERSATIONS.write().map_err(|_| "Conversation store poisoned".to_string())?;
+    let conversation = store.get_or_insert_with(HashMap::new)
+        .get_mut(id)
+        .ok_or_else(|| A2oError::NotFound(format!("Unknown conversation {}", id)))?;
+    f(conversation)
+}
+
+#[tauri::command]
+pub fn create_conversation(system_prompt: Option<String>) -> Result<Conversation, A2oError> {
+    let conversation = Conversation::new(&uuid::Uuid::new_v4().to_string(), system_prompt);
+    let mut store = CONVERSATIONS.write().map_err(|_| "Conversation store poisoned".to_string())?;
+    store.get_or_insert_with(HashMap::new).insert(conversation.id.clone(), conversation.clone());
+    println!("💬 Created conversation {}", conversation.id);
+    Ok(conversation)
+}
+
+#[tauri::command]
+pub fn append_user_message(conversation_id: String, content: String) -> Result<Conversation, A2oError> {
+    with_conversation(&conversation_id, |conversation| {
+        conversation.append_user_message(&content)?;
+        Ok(conversation.clone())
+    })
+}
+
+/// Run one generation turn on the conversation's history. `config.chat_history` is
+/// ignored; the conversation's system prompt, when set, replaces `config.system_prompt`.
+#[tauri::command]
+pub async fn run_turn(
+    window: Window,
+    conversation_id: String,
+    mut config: GenerationConfig,
+) -> Result<GenerationTurnResult, A2oError> {
+    let (chat_history, system_prompt) = with_conversation(&conversation_id, |conversation| {
+        if !conversation.has_pending_user_message() {
+            return Err(A2oError::InvalidInput(format!("Conversation {} has no user message to answer", conversation.id)));
+        }
+        Ok((conversation.chat_history(), conversation.system_prompt.clone()))
+    })?;
+    config.chat_history = chat_history;
+    if system_prompt.is_some() {
+        config.system_prompt = system_prompt;
+    }
+
+    let turn = execute_generation_turn(window, config).await?;
+    // The conversation may have been deleted while the turn ran; the result is still returned
+    if let Err(e) = with_conversation(&conversation_id, |conversation| {
+        conversation.record_results(&turn.results);
+        Ok(())
+    }) {
+        println!("⚠️ Turn {} not recorded: {}", turn.run_id, e);
+    }
+    Ok(turn)
+}
+
+#[tauri::command]
+pub fn export_conversation(conversation_id: String) -> Result<Value, A2oError> {
+    with_conversation(&conversation_id, |conversation| Ok(conversation.to_session_data()))
+}
+
+#[tauri::command]
+pub fn delete_conversation(conversation_id: String) -> Result<(), A2oError> {
+    let mut store = CONVERSATIONS.write().map_err(|_| "Conversation store poisoned".to_string())?;
+    store.get_or_insert_with(HashMap::new)
+        .remove(&conversation_id)
+        .map(|_| println!("💬 Deleted conversation {}", conversation_id))
+        .ok_or_else(|| A2oError::NotFound(format!("Unknown conversation {}", conversation_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::types::{MemoryUsageEvent, SamplingParams};
+
+    fn result(model: &str, text: &str) -> GenerationResult {
+        GenerationResult {
+            model: model.to_string(),
+            text: text.to_string(),
+            finish_reason: "eog".to_string(),
+            truncated: false,
+            stop_sequence: None,
+            input_tokens: 12,
+            output_tokens: 3,
+            model_load_ms: 900.0,
+            model_reused: false,
+            effective_context: None,
+            ttft_ms: Some(80),
+            prefill_ms: 40.0,
+            generation_time_ms: 250,
+            avg_tps: Some(12.0),
+            energy: None,
+            combined_energy: None,
+            memory: MemoryUsageEvent {
+                model: model.to_string(),
+                baseline_gb: 8.0,
+                load_peak_gb: 12.0,
+                load_delta_gb: 4.0,
+                generation_peak_gb: 12.5,
+                generation_delta_gb: 4.5,
+                timestamp_ms: 0,
+            },
+            sampling: SamplingParams::default(),
+            context_used_tokens: 15,
+            n_ctx: 4096,
+            context_shifts: 0,
+            resumed_tokens: 0,
+            resume_diverged_at: None,
+            speculative: None,
+        }
+    }
+
+    #[test]
+    fn test_conversation_turns_and_session_export() {
+        let mut conversation = Conversation::new("conv-1", Some("Be brief.".to_string()));
+        assert!(conversation.append_user_message("   ").is_err());
+        conversation.append_user_message("Hello").unwrap();
+        assert!(conversation.append_user_message("Hello again").is_err());
+        assert!(conversation.has_pending_user_message());
+
+        conversation.record_results(&[result("A", "Hi from A"), result("B", "Hi from B")]);
+        assert!(!conversation.has_pending_user_message());
+        conversation.append_user_message("Thanks").unwrap();
+
+        let history = conversation.chat_history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[1].model.as_deref(), Some("A"));
+        assert_eq!(history[2].content, "Hi from B");
+
+        let session = conversation.to_session_data();
+        assert_eq!(session["schema_version"], SESSION_SCHEMA_VERSION);
+        assert_eq!(session["configuration"]["system_prompt"], "Be brief.");
+        assert_eq!(session["chat_history"][1]["token_count"], 3);
+        assert_eq!(session["chat_history"][3]["role"], "user");
+        assert!(session["chat_history"][3]["token_count"].is_null());
+    }
+}
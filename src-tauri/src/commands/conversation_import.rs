@@ -0,0 +1,276 @@
+// Contains import_conversation Tauri command (chat exports from other tools -> Message history)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Message;
+use crate::error::A2oError;
+
+/// Export formats accepted by import_conversation
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationFormat {
+    /// ChatGPT data export (conversations.json: a list of conversations with a message tree)
+    Chatgpt,
+    /// LM Studio conversation file (per-message versions) or a plain {"messages": [...]} file
+    Lmstudio,
+    /// Ollama /api/chat messages JSON, or a Modelfile with MESSAGE lines (`ollama show --modelfile`)
+    Ollama,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedConversation {
+    pub title: Option<String>,
+    pub messages: Vec<Message>,
+}
+
+#[tauri::command]
+pub fn import_conversation(path: String, format: ConversationFormat) -> Result<Vec<ImportedConversation>, A2oError> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| A2oError::Io(format!("Failed to read {}: {}", path, e)))?;
+    let conversations = parse_conversations(&contents, format).map_err(A2oError::InvalidInput)?;
+    println!("📥 Imported {} conversation(s) from {} ({:?})", conversations.len(), path, format);
+    Ok(conversations)
+}
+
+/// Parse an export into conversations, dropping empty and non-chat (tool) messages
+pub fn parse_conversations(contents: &str, format: ConversationFormat) -> Result<Vec<ImportedConversation>, String> {
+    let conversations = match format {
+        ConversationFormat::Chatgpt => parse_chatgpt(&parse_json(contents)?)?,
+        ConversationFormat::Lmstudio => vec![parse_lmstudio(&parse_json(contents)?)?],
+        ConversationFormat::Ollama => match serde_json::from_str::<Value>(contents) {
+            Ok(json) => vec![parse_ollama_json(&json)?],
+            Err(_) => vec![parse_ollama_modelfile(contents)?],
+        },
+    };
+
+    let conversations: Vec<ImportedConversation> = conversations.into_iter()
+        .filter(|conversation| !conversation.messages.is_empty())
+        .collect();
+    if conversations.is_empty() {
+        return Err("No user or assistant messages found in the export".to_string());
+    }
+    Ok(conversations)
+}
+
+fn parse_json(contents: &str) -> Result<Value, String> {
+    serde_json::from_str(contents).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Build a Message for chat roles; assistant turns are not attributed to model A or B
+fn chat_message(role: &str, content: String) -> Option<Message> {
+    let role = match role {
+        "user" | "human" => "user",
+        "assistant" | "model" => "assistant",
+        "system" => "system",
+        _ => return None,
+    };
+    let content = content.trim();
+    if content.is_empty() {
+        return None;
+    }
+    Some(Message { role: role.to_string(), content: content.to_string(), model: None })
+}
+
+// ChatGPT stores each conversation as a tree keyed by node id; the visible thread is the path
+// from current_node back to the root (other branches are earlier edits/regenerations)
+fn parse_chatgpt(json: &Value) -> Result<Vec<ImportedConversation>, String> {
+    let conversations = match json {
+        Value::Array(conversations) => conversations.iter().collect::<Vec<_>>(),
+        Value::Object(_) => vec![json],
+        _ => return Err("Expected a ChatGPT conversations array".to_string()),
+    };
+
+    conversations.into_iter().map(|conversation| {
+        let mapping = conversation.get("mapping").and_then(Value::as_object)
+            .ok_or_else(|| "ChatGPT conversation has no message mapping".to_string())?;
+
+        let mut thread = Vec::new();
+        let mut node_id = conversation.get("current_node").and_then(Value::as_str);
+        while let Some(id) = node_id {
+            let Some(node) = mapping.get(id) else { break };
+            if thread.len() > mapping.len() {
+                return Err("ChatGPT message tree contains a cycle".to_string());
+            }
+            thread.push(node);
+            node_id = node.get("parent").and_then(Value::as_str);
+        }
+        thread.reverse();
+
+        let messages = thread.into_iter()
+            .filter_map(|node| node.get("message"))
+            .filter_map(|message| {
+                let role = message.pointer("/author/role").and_then(Value::as_str)?;
+                let parts = message.pointer("/content/parts").and_then(Value::as_array)?;
+                let text = parts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join("\n");
+                chat_message(role, text)
+            })
+            .collect();
+
+        Ok(ImportedConversation {
+            title: conversation.get("title").and_then(Value::as_str).map(str::to_string),
+            messages,
+        })
+    }).collect()
+}
+
+// LM Studio keeps every regenerated version of a message and marks the selected one
+fn parse_lmstudio(json: &Value) -> Result<ImportedConversation, String> {
+    let messages = json.get("messages").and_then(Value::as_array)
+        .ok_or_else(|| "LM Studio conversation has no messages".to_string())?;
+
+    let messages = messages.iter().filter_map(|message| {
+        let Some(versions) = message.get("versions").and_then(Value::as_array) else {
+            // Plain {"role", "content"} messages (older exports)
+            return simple_message(message);
+        };
+        let selected = message.get("currentlySelected").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let version = versions.get(selected).or_else(|| versions.last())?;
+        let role = version.get("role").and_then(Value::as_str)?;
+
+        // User turns hold content blocks directly; assistant turns hold them inside steps
+        let blocks: Vec<&Value> = match version.get("steps").and_then(Value::as_array) {
+            Some(steps) => steps.iter()
+                .filter_map(|step| step.get("content").and_then(Value::as_array))
+                .flatten()
+                .collect(),
+            None => version.get("content").and_then(Value::as_array)
+                .map(|content| content.iter().collect())
+                .unwrap_or_default(),
+        };
+        let text = blocks.iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n");
+        chat_message(role, text)
+    }).collect();
+
+    Ok(ImportedConversation {
+        title: json.get("name").and_then(Value::as_str).map(str::to_string),
+        messages,
+    })
+}
+
+fn simple_message(message: &Value) -> Option<Message> {
+    let role = message.get("role").and_then(Value::as_str)?;
+    let content = message.get("content").and_then(Value::as_str)?;
+    chat_message(role, content.to_string())
+}
+
+// Ollama chat history as sent to /api/chat: {"model": ..., "messages": [{"role", "content"}]}
+fn parse_ollama_json(json: &Value) -> Result<ImportedConversation, String> {
+    let messages = json.get("messages").or(Some(json)).and_then(Value::as_array)
+        .ok_or_else(|| "Ollama export has no messages array".to_string())?;
+    Ok(ImportedConversation {
+        title: json.get("model").and_then(Value::as_str).map(str::to_string),
+        messages: messages.iter().filter_map(simple_message).collect(),
+    })
+}
+
+// Modelfile saved with /save: `MESSAGE <role> <text>`, where text may be a """-quoted block
+fn parse_ollama_modelfile(contents: &str) -> Result<ImportedConversation, String> {
+    let mut messages = Vec::new();
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let Some(rest) = line.trim_start().strip_prefix("MESSAGE ") else { continue };
+        let (role, text) = rest.trim_start().split_once(char::is_whitespace).unwrap_or((rest.trim(), ""));
+        let text = text.trim_start();
+
+        let content = match text.strip_prefix("\"\"\"") {
+            Some(first) => match first.split_once("\"\"\"") {
+                Some((single_line, _)) => single_line.to_string(),
+                None => {
+                    let mut block = vec![first.to_string()];
+                    for next in lines.by_ref() {
+                        match next.split_once("\"\"\"") {
+                            Some((last, _)) => {
+                                block.push(last.to_string());
+                                break;
+                            }
+                            None => block.push(next.to_string()),
+                        }
+                    }
+                    block.join("\n")
+                }
+            },
+            None => text.to_string(),
+        };
+        if let Some(message) = chat_message(&role.to_lowercase(), content) {
+            messages.push(message);
+        }
+    }
+    if messages.is_empty() {
+        return Err("Expected Ollama messages JSON or a Modelfile with MESSAGE lines".to_string());
+    }
+    Ok(ImportedConversation { title: None, messages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roles_and_content(conversation: &ImportedConversation) -> Vec<(&str, &str)> {
+        conversation.messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect()
+    }
+
+    #[test]
+    fn test_chatgpt_follows_current_branch() {
+        let export = r#"[{
+            "title": "Rust question",
+            "current_node": "c",
+            "mapping": {
+                "root": {"message": null, "parent": null},
+                "sys": {"message": {"author": {"role": "system"}, "content": {"content_type": "text", "parts": [""]}}, "parent": "root"},
+                "a": {"message": {"author": {"role": "user"}, "content": {"content_type": "text", "parts": ["What is a borrow?"]}}, "parent": "sys"},
+                "old": {"message": {"author": {"role": "assistant"}, "content": {"content_type": "text", "parts": ["Discarded"]}}, "parent": "a"},
+                "b": {"message": {"author": {"role": "tool"}, "content": {"content_type": "text", "parts": ["search results"]}}, "parent": "a"},
+                "c": {"message": {"author": {"role": "assistant"}, "content": {"content_type": "text", "parts": ["A reference.", {"asset": "image"}]}}, "parent": "b"}
+            }
+        }]"#;
+        let conversations = parse_conversations(export, ConversationFormat::Chatgpt).unwrap();
+        assert_eq!(conversations.len(), 1);
+        assert_eq!(conversations[0].title.as_deref(), Some("Rust question"));
+        assert_eq!(roles_and_content(&conversations[0]), vec![("user", "What is a borrow?"), ("assistant", "A reference.")]);
+    }
+
+    #[test]
+    fn test_lmstudio_uses_selected_versions() {
+        let export = r#"{
+            "name": "Chat",
+            "messages": [
+                {"versions": [{"type": "singleStep", "role": "user", "content": [{"type": "text", "text": "Hi"}]}], "currentlySelected": 0},
+                {"versions": [
+                    {"type": "multiStep", "role": "assistant", "steps": [{"type": "contentBlock", "content": [{"type": "text", "text": "First"}]}]},
+                    {"type": "multiStep", "role": "assistant", "steps": [{"type": "contentBlock", "content": [{"type": "text", "text": "Second"}]}]}
+                ], "currentlySelected": 1}
+            ]
+        }"#;
+        let conversations = parse_conversations(export, ConversationFormat::Lmstudio).unwrap();
+        assert_eq!(roles_and_content(&conversations[0]), vec![("user", "Hi"), ("assistant", "Second")]);
+
+        let plain = r#"{"messages": [{"role": "user", "content": "Hello"}, {"role": "assistant", "content": "Hey"}]}"#;
+        let conversations = parse_conversations(plain, ConversationFormat::Lmstudio).unwrap();
+        assert_eq!(roles_and_content(&conversations[0]), vec![("user", "Hello"), ("assistant", "Hey")]);
+    }
+
+    #[test]
+    fn test_ollama_json_and_modelfile() {
+        let json = r#"{"model": "llama3", "messages": [{"role": "system", "content": "Be brief"}, {"role": "user", "content": "Hi"}]}"#;
+        let conversations = parse_conversations(json, ConversationFormat::Ollama).unwrap();
+        assert_eq!(conversations[0].title.as_deref(), Some("llama3"));
+        assert_eq!(roles_and_content(&conversations[0]), vec![("system", "Be brief"), ("user", "Hi")]);
+
+        let modelfile = "FROM llama3\nPARAMETER temperature 0.7\nMESSAGE user Why is the sky blue?\nMESSAGE assistant \"\"\"Rayleigh\nscattering.\"\"\"\nMESSAGE user \"\"\"Thanks\"\"\"\n";
+        let conversations = parse_conversations(modelfile, ConversationFormat::Ollama).unwrap();
+        assert_eq!(roles_and_content(&conversations[0]),
+                   vec![("user", "Why is the sky blue?"), ("assistant", "Rayleigh\nscattering."), ("user", "Thanks")]);
+    }
+
+    #[test]
+    fn test_export_without_messages_is_an_error() {
+        assert!(parse_conversations(r#"{"messages": []}"#, ConversationFormat::Lmstudio).is_err());
+        assert!(parse_conversations("FROM llama3\n", ConversationFormat::Ollama).is_err());
+        assert!(parse_conversations("not json", ConversationFormat::Chatgpt).is_err());
+    }
+}
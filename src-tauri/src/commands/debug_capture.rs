@@ -0,0 +1,17 @@
+// Contains load_debug_capture Tauri command (replay of developer debug captures)
+
+use std::path::PathBuf;
+
+use crate::telemetry::debug_capture::{replay_debug_capture, DebugCaptureReplay};
+use crate::error::A2oError;
+
+/// Replay a debug capture written with `debug_capture` enabled through the aggregation pipeline
+#[tauri::command]
+pub async fn load_debug_capture(path: String) -> Result<DebugCaptureReplay, A2oError> {
+    let path = PathBuf::from(path);
+    let replay = tauri::async_runtime::spawn_blocking(move || replay_debug_capture(&path))
+        .await
+        .map_err(|e| format!("Debug capture replay failed: {}", e))??;
+    println!("🔁 Replayed debug capture: {} samples, {} skipped lines", replay.samples.len(), replay.skipped_lines.len());
+    Ok(replay)
+}
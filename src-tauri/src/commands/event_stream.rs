@@ -0,0 +1,16 @@
+// Contains configure_event_stream Tauri command
+
+use crate::events::filter::{set_stream_options, EventStreamOptions};
+use crate::error::A2oError;
+
+/// Opt a stream in or out of event classes (tokens, telemetry, debug, phase_markers) and
+/// rate-limit them. `session_id` names the stream: the window label ("main") for the
+/// desktop UI, or an `event_sink` spec such as "ws:127.0.0.1:9100". Run lifecycle events
+/// are always delivered; default options remove any earlier configuration.
+#[tauri::command]
+pub fn configure_event_stream(session_id: String, options: EventStreamOptions) -> Result<EventStreamOptions, A2oError> {
+    options.validate().map_err(A2oError::InvalidInput)?;
+    println!("📡 Event stream '{}' configured: {:?}", session_id, options);
+    set_stream_options(session_id.trim(), options.clone());
+    Ok(options)
+}
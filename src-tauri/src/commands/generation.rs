@@ -1,17 +1,43 @@
 // Contains run_generation_turn Tauri command
 
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use tauri::{Emitter, Window};
+use tauri::{Manager, Window};
 use tokio::sync::broadcast;
 
 // Import types and functions from parent module
 use crate::{
     GenerationConfig, GLOBAL_STOP_SIGNAL,
-    run_model_inference, start_enhanced_monitoring,
-    read_core_temperatures
+    run_model_inference, start_enhanced_monitoring
 };
-use crate::telemetry::types::TelemetryCommand;
+use crate::telemetry::types::{
+    TelemetryCommand, GenerationStartedEvent, PromptBudgetEvent, DebugCaptureStartedEvent, SessionEnergySummaryEvent, GenerationResult, GenerationTurnResult, DEFAULT_TELEMETRY_SAMPLING_HZ,
+    TelemetryProfile, IntegrationMethod, ModelConfig, SamplerSeed,
+    MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ, DEFAULT_COOLDOWN_MARGIN_C,
+    DEFAULT_ROLLING_TPS_WINDOW_SECS, MIN_ROLLING_TPS_WINDOW_SECS, MAX_ROLLING_TPS_WINDOW_SECS,
+    DEFAULT_STALL_THRESHOLD_MS
+};
+use crate::telemetry::processor::{
+    ActiveRunGuard, MODEL_ENERGY, OVERHEAD_CALIBRATION, TELEMETRY_COMMANDS, begin_model_stop_signals, begin_partial_outputs,
+    clear_model_stop_signals, finish_partial_outputs
+};
+use crate::telemetry::calibration::OverheadCorrection;
+use crate::telemetry::cooldown::{measure_cooldown_baseline, wait_for_cpu_cooldown};
+use crate::telemetry::heat_soak::{soak_to_band, DEFAULT_HEAT_SOAK_BAND_C, HEAT_SOAK_TARGET_RANGE_C};
+use crate::telemetry::temperature_unit::TemperatureUnit;
+use crate::telemetry::phase::{begin_model_phase, end_model_phase, InferencePhaseMarkers};
+use crate::telemetry::debug_capture::{DebugCaptureHeader, DebugCaptureWriter, SharedDebugCapture};
+use crate::inference::output_stream::OutputStreamWriter;
+use crate::inference::model_cache::ModelCache;
+use crate::inference::generation::{energy_summary_event, DecodeMetricsConfig, ModelRun, preflight_prompt_budget, resolve_max_tokens, resolve_model_path};
+use crate::inference::prompt_budget::truncation_mismatch_warning;
+use crate::events::{window_sink_with, EventSink};
+use crate::persistence::database::SessionDatabase;
+use crate::persistence::disk_space::ensure_free_space;
+use crate::persistence::run_manifest::{ResolvedRunConfig, RunManifest, build_run_manifest, collect_environment, record_effective_context, write_run_manifest};
 use crate::utils::debug::DEBUG_LOGS;
+use crate::commands::jobs::{attach_run_to_job, start_job, JobHandle};
+use crate::error::A2oError;
 
 #[allow(unused_macros)]
 macro_rules! dprintln {
@@ -20,22 +46,147 @@ macro_rules! dprintln {
     }
 }
 
-#[derive(Clone, serde::Serialize)]
-struct CooldownUpdateEvent {
-    state: String,              // "started" | "progress" | "complete" | "timeout" | "canceled"
-    baseline_c: Option<f64>,    // Baseline CPU max temp (°C)
-    margin_c: f64,              // Allowed margin above baseline (°C)
-    threshold_c: Option<f64>,   // Baseline + margin target (°C)
-    current_c: Option<f64>,     // Current CPU max temp (°C)
-    elapsed_s: Option<u64>,     // Seconds since start of cooldown
-    timestamp_ms: u64,          // Event timestamp
+// Energy label for parallel "Both" runs, where A and B share the measured power
+const PARALLEL_PHASE_LABEL: &str = "A+B";
+
+/// Open the per-model JSONL output stream when streaming to disk is enabled
+/// Failures are logged and the run continues without streaming.
+fn open_output_stream(dir: Option<&Path>, run_id: &str, model_label: &str, model: &ModelConfig) -> Option<OutputStreamWriter> {
+    let dir = dir?;
+    let seed = match model.seed {
+        Some(SamplerSeed::Fixed(seed)) => Some(seed),
+        _ => None,
+    };
+    match OutputStreamWriter::create(dir, run_id, model_label, &model.model_path, seed) {
+        Ok(writer) => {
+            println!("💾 Streaming Model {} output to {}", model_label, writer.path().display());
+            Some(writer)
+        }
+        Err(e) => {
+            println!("⚠️ {} - continuing without output streaming for Model {}", e, model_label);
+            None
+        }
+    }
+}
+
+/// Write the run manifest under `<app data>/runs` and emit it as `run_manifest`
+fn publish_run_manifest(window: &Window, events: &dyn EventSink, manifest: &RunManifest) {
+    match window.app_handle().path().app_data_dir() {
+        Ok(app_data_dir) => match write_run_manifest(&app_data_dir.join("runs"), manifest) {
+            Ok(path) => println!("📝 Run manifest written to {}", path.display()),
+            Err(e) => println!("⚠️ {}", e),
+        },
+        Err(e) => println!("⚠️ Failed to resolve app data directory for the run manifest: {}", e),
+    }
+    let _ = events.emit("run_manifest", manifest);
+}
+
+fn heat_soak_band(config: &GenerationConfig) -> f64 {
+    config.heat_soak_band_c.unwrap_or(DEFAULT_HEAT_SOAK_BAND_C).clamp(0.5, 10.0)
+}
+
+// With isolate_models set, sequential "Both" runs measure each model alone: unload the other
+// side's model (and its draft model) from the model cache so its weights do not count
+// against this model's memory, power and thermal readings. Files both sides use stay resident.
+fn unload_other_model(model_cache: &ModelCache, other: Option<&ModelConfig>, running: &ModelConfig, label: &str) {
+    let Some(other) = other else { return };
+    let running_paths = model_file_paths(running);
+    for path in model_file_paths(other).into_iter().filter(|path| !running_paths.contains(path)) {
+        for unloaded in model_cache.unload(Some(&path)) {
+            println!("🧹 Unloaded {} before Model {} runs", unloaded.model_path, label);
+        }
+    }
+}
+
+// Resolved files a model config loads: the model and its draft model, if any
+fn model_file_paths(model: &ModelConfig) -> Vec<PathBuf> {
+    [Some(model.model_path.as_str()), model.draft_model_path.as_deref()].into_iter()
+        .flatten()
+        .filter_map(resolve_model_path)
+        .collect()
+}
+
+// With heat_soak_target_c set, bring the CPU into the target band before `label` runs
+async fn heat_soak_before(events: &dyn EventSink, config: &GenerationConfig, label: &str, unit: TemperatureUnit) {
+    if let Some(target_c) = config.heat_soak_target_c {
+        soak_to_band(events, label, target_c, heat_soak_band(config), unit).await;
+    }
 }
 
+/// Start a generation turn as a background job and return its id right away; the
+/// GenerationTurnResult comes from `await_job` (or `get_job_status`) and `cancel_job`
+/// stops it. Streaming events are emitted exactly as before.
 #[tauri::command]
-pub async fn run_generation_turn(
+pub fn run_generation_turn(window: Window, config: GenerationConfig) -> Result<JobHandle, A2oError> {
+    start_job("generation_turn", execute_generation_turn(window, config))
+}
+
+/// Run one generation turn to completion (the body of a `run_generation_turn` job; also
+/// used directly by run_turn and benchmark suites)
+pub async fn execute_generation_turn(
     window: Window,
-    config: GenerationConfig,
-) -> Result<(), String> {
+    mut config: GenerationConfig,
+) -> Result<GenerationTurnResult, A2oError> {
+    // Presets and per-turn overrides replace the stored sampling parameters for this run
+    // only; the manifest and each GenerationResult record the effective values
+    config.resolve_sampling()?;
+    if let Some(target_c) = config.heat_soak_target_c {
+        let (min_c, max_c) = HEAT_SOAK_TARGET_RANGE_C;
+        if !(min_c..=max_c).contains(&target_c) {
+            return Err(A2oError::InvalidInput(format!("Heat soak target must be between {} and {} °C, got {}", min_c, max_c, target_c)));
+        }
+    }
+    // Single-flight guard: reject overlapping runs before touching any global state.
+    // The guard is held for the whole command and released when it goes out of scope.
+    let run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected generation request: run {} already in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+    attach_run_to_job(run_guard.run_id())?;
+    run_guard.set_model_paths(
+        [&config.model_a, &config.model_b].into_iter()
+            .flatten()
+            .flat_map(model_file_paths)
+            .collect(),
+    );
+
+    // Resolve the output streaming directory (None when streaming to disk is disabled)
+    let stream_output_dir: Option<PathBuf> = if config.stream_output_to_disk.unwrap_or(false) {
+        match &config.stream_output_dir {
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => match window.app_handle().path().app_data_dir() {
+                Ok(app_data_dir) => Some(app_data_dir.join("streams")),
+                Err(e) => {
+                    println!("⚠️ Failed to resolve app data directory for output streaming: {}", e);
+                    None
+                }
+            },
+        }
+    } else {
+        None
+    };
+    // Refuse up front rather than losing the stream partway through the run
+    if let Some(dir) = &stream_output_dir {
+        ensure_free_space(&window, "stream_output", dir, 0)?;
+    }
+
+    println!("🏁 Generation run {} started", run_guard.run_id());
+    begin_partial_outputs(run_guard.run_id());
+    // Events go to the window, plus the configured extra sink (JSONL file, WebSocket) if any
+    let events = window_sink_with(window.clone(), config.event_sink.as_deref());
+    let _ = events.emit("generation_started", GenerationStartedEvent {
+        run_id: run_guard.run_id().to_string(),
+        target: config.target.clone(),
+        seeds: [("A", &config.model_a), ("B", &config.model_b)].into_iter()
+            .filter(|(label, _)| config.target == *label || config.target == "Both")
+            .filter_map(|(label, model)| Some((label.to_string(), model.as_ref()?.seed?.resolve())))
+            .collect(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    });
+
     // Determine if telemetry should be disabled for this run
     let disable_telemetry = config.run_without_telemetry.unwrap_or(false);
 
@@ -43,9 +194,13 @@ pub async fn run_generation_turn(
     let (telemetry_tx, _) = broadcast::channel(1000);
     let telemetry_broadcaster = Arc::new(telemetry_tx);
     
-    // Create command broadcaster for power calculator reset
-    let (command_tx, _) = broadcast::channel(100);
+    // Create command broadcaster for per-model energy phase markers
+    let (command_tx, _) = broadcast::channel::<TelemetryCommand>(100);
     let command_broadcaster = Arc::new(command_tx);
+    // set_telemetry_state pauses and resumes this run's monitors through it
+    if let Ok(mut commands) = TELEMETRY_COMMANDS.write() {
+        *commands = Some(command_broadcaster.clone());
+    }
     
     let stop_signal = Arc::new(AtomicBool::new(false));
     
@@ -56,6 +211,8 @@ pub async fn run_generation_turn(
             println!("🛑 Global stop signal initialized for generation session");
         }
     }
+    // Per-model signals let stop_generation(target) end one model of a "Both" run
+    begin_model_stop_signals(&["A", "B"]);
     
     if disable_telemetry {
         dprintln!("🚫 Telemetry disabled for this generation run");
@@ -64,8 +221,166 @@ pub async fn run_generation_turn(
     }
     
     // Extract sampling frequency from global telemetry configuration
-    let desired_sampling_hz = config.telemetry_sampling_hz.unwrap_or(1.0).max(0.1).min(50.0);
+    let telemetry_profile: TelemetryProfile = config.telemetry_profile.unwrap_or_default();
+    let integration_method: IntegrationMethod = config.energy_integration_method.unwrap_or_default();
+    let desired_sampling_hz = telemetry_profile.clamp_sampling_hz(config.telemetry_sampling_hz
+        .unwrap_or(DEFAULT_TELEMETRY_SAMPLING_HZ)
+        .max(MIN_TELEMETRY_SAMPLING_HZ)
+        .min(MAX_TELEMETRY_SAMPLING_HZ));
+    // Phase markers are applied once per monitor loop; the 1 Hz prewarm monitor may be the one applying them
+    let phase_ack_timeout = crate::telemetry::phase::phase_ack_timeout(
+        std::time::Duration::from_secs_f32(1.0 / desired_sampling_hz.min(1.0)));
+    let decode_metrics = DecodeMetricsConfig {
+        rolling_tps_window: std::time::Duration::from_secs_f64(config.rolling_tps_window_secs
+            .unwrap_or(DEFAULT_ROLLING_TPS_WINDOW_SECS)
+            .clamp(MIN_ROLLING_TPS_WINDOW_SECS, MAX_ROLLING_TPS_WINDOW_SECS)),
+        stall_threshold: std::time::Duration::from_secs_f64(config.stall_threshold_ms
+            .unwrap_or(DEFAULT_STALL_THRESHOLD_MS)
+            .max(0.0) / 1000.0),
+    };
+    if telemetry_profile == TelemetryProfile::Minimal {
+        println!("🪶 Minimal-overhead telemetry profile: package power and aggregate temps at {:.2}Hz", desired_sampling_hz);
+    }
+
+    // Stored idle baseline, subtracted from every power reading when enabled
+    let idle_power = if disable_telemetry || !config.subtract_idle_baseline.unwrap_or(false) {
+        None
+    } else {
+        match window.state::<SessionDatabase>().idle_baseline() {
+            Ok(Some(baseline)) => Some(baseline.power),
+            Ok(None) => {
+                println!("⚠️ Idle baseline subtraction requested but no baseline has been captured - reporting gross energy");
+                None
+            }
+            Err(e) => {
+                println!("⚠️ Failed to read the idle baseline: {} - reporting gross energy", e);
+                None
+            }
+        }
+    };
+
+    // Calibrated telemetry overhead at this sampling rate (reported, and optionally subtracted)
+    let measurement_overhead = if disable_telemetry {
+        None
+    } else {
+        OVERHEAD_CALIBRATION.read().ok()
+            .and_then(|calibration| calibration.as_ref()
+                .filter(|c| c.profile == telemetry_profile)
+                .and_then(|c| c.power_overhead_at(desired_sampling_hz)))
+            .map(|watts| OverheadCorrection {
+                watts,
+                // The idle baseline was sampled with telemetry running, so it already covers the overhead
+                subtract: config.subtract_measurement_overhead.unwrap_or(false) && idle_power.is_none(),
+            })
+    };
+
+    // Record run provenance (resolved settings, environment, model fingerprints) before starting
+    let resolved_config = ResolvedRunConfig {
+        target: config.target.clone(),
+        parallel: config.target == "Both" && config.parallel.unwrap_or(false),
+        telemetry_enabled: !disable_telemetry,
+        telemetry_sampling_hz: desired_sampling_hz,
+        telemetry_profile,
+        energy_integration_method: integration_method,
+        rolling_tps_window_secs: decode_metrics.rolling_tps_window.as_secs_f64(),
+        stall_threshold_ms: decode_metrics.stall_threshold.as_secs_f64() * 1000.0,
+        measurement_overhead_watts: measurement_overhead.map(|correction| correction.watts),
+        subtract_measurement_overhead: measurement_overhead.is_some_and(|correction| correction.subtract),
+        idle_baseline_watts: idle_power.map(|idle| idle.total_w()),
+        wait_for_cpu_baseline_between_models: config.wait_for_cpu_baseline_between_models.unwrap_or(false),
+        wait_for_cpu_baseline_margin_c: config.wait_for_cpu_baseline_margin_c.unwrap_or(DEFAULT_COOLDOWN_MARGIN_C).clamp(-20.0, 20.0),
+        heat_soak_target_c: config.heat_soak_target_c,
+        heat_soak_band_c: config.heat_soak_target_c.map(|_| heat_soak_band(&config)),
+        system_prompt: config.system_prompt.clone(),
+    };
+    let manifest = {
+        let run_id = run_guard.run_id().to_string();
+        let config = config.clone();
+        // Fingerprinting reads the model files, so keep it off the async runtime
+        tauri::async_runtime::spawn_blocking(move || {
+            build_run_manifest(&run_id, &config, resolved_config, collect_environment())
+        }).await
+    };
+    let run_manifest = match manifest {
+        Ok(manifest) => {
+            publish_run_manifest(&window, events.as_ref(), &manifest);
+            Some(manifest)
+        }
+        Err(e) => {
+            println!("⚠️ Failed to build run manifest: {}", e);
+            None
+        }
+    };
+
+    // Tokenizers differ, so check the identical conversation leaves every model room for a full reply
+    if config.target == "Both" {
+        if let (Some(model_a), Some(model_b)) = (config.model_a.clone(), config.model_b.clone()) {
+            let chat_history = config.chat_history.clone();
+            let system_prompt = config.system_prompt.clone();
+            // The warning is phrased against the larger of the two budgets
+            let max_generation_tokens = resolve_max_tokens(model_a.max_tokens).max(resolve_max_tokens(model_b.max_tokens));
+            let budgets = tauri::async_runtime::spawn_blocking(move || {
+                [("A", &model_a), ("B", &model_b)].into_iter()
+                    .map(|(label, model)| preflight_prompt_budget(model, &chat_history, system_prompt.as_deref(), label))
+                    .collect::<Result<Vec<_>, String>>()
+            }).await;
+            match budgets {
+                Ok(Ok(budgets)) => {
+                    let warning = truncation_mismatch_warning(&budgets, max_generation_tokens);
+                    for budget in &budgets {
+                        println!("📏 PROMPT BUDGET: Model {} prompt {} tokens, n_ctx {}, {} left for the reply",
+                                 budget.model, budget.prompt_tokens, budget.n_ctx, budget.output_budget);
+                    }
+                    if let Some(warning) = &warning {
+                        println!("⚠️ PROMPT BUDGET: {}", warning);
+                    }
+                    let _ = events.emit("prompt_budget", PromptBudgetEvent {
+                        budgets,
+                        max_generation_tokens,
+                        warning,
+                        timestamp_ms: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis() as u64,
+                    });
+                }
+                Ok(Err(e)) => println!("⚠️ Prompt budget preflight skipped: {}", e),
+                Err(e) => println!("⚠️ Prompt budget preflight failed: {}", e),
+            }
+        }
+    }
     
+    // Developer debug capture of raw sensor readings (only meaningful with telemetry running)
+    let debug_capture: Option<SharedDebugCapture> = if config.debug_capture.unwrap_or(false) && !disable_telemetry {
+        let capture_dir = match &config.debug_capture_dir {
+            Some(dir) => Ok(PathBuf::from(dir)),
+            None => window.app_handle().path().app_data_dir()
+                .map(|dir| dir.join("debug_captures"))
+                .map_err(|e| format!("Failed to resolve app data directory for debug capture: {}", e)),
+        };
+        let header = DebugCaptureHeader::new(run_guard.run_id(), desired_sampling_hz, telemetry_profile, integration_method);
+        match capture_dir.and_then(|dir| DebugCaptureWriter::create(&dir, &header)) {
+            Ok(capture) => {
+                println!("🐛 Debug capture enabled: {}", capture.path().display());
+                let _ = events.emit("debug_capture_started", DebugCaptureStartedEvent {
+                    run_id: run_guard.run_id().to_string(),
+                    path: capture.path().to_string_lossy().into_owned(),
+                    timestamp_ms: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64,
+                });
+                Some(Arc::new(capture))
+            }
+            Err(e) => {
+                println!("⚠️ {} - continuing without debug capture", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Pre-warm monitoring at 1.0 Hz, then optionally switch to desired rate
     let mut monitoring_handle = None;
     let mut prewarm_monitoring_handle = None;
@@ -86,9 +401,10 @@ pub async fn run_generation_turn(
         prewarm_stop_signal_opt = Some(prewarm_stop_signal.clone());
         let telemetry_for_prewarm = telemetry_broadcaster.clone();
         let command_for_prewarm = Some(command_broadcaster.clone());
+        let capture_for_prewarm = debug_capture.clone();
         prewarm_monitoring_handle = Some(tokio::spawn(async move {
             println!("🔋 Pre-warming telemetry at 1.0Hz...");
-            if let Err(e) = start_enhanced_monitoring(telemetry_for_prewarm, prewarm_stop_signal.clone(), command_for_prewarm, Some(1.0)).await {
+            if let Err(e) = start_enhanced_monitoring(telemetry_for_prewarm, prewarm_stop_signal.clone(), command_for_prewarm, Some(1.0), telemetry_profile, integration_method, capture_for_prewarm, idle_power).await {
                 println!("❌ Pre-warm monitoring error: {}", e);
             }
         }));
@@ -101,9 +417,10 @@ pub async fn run_generation_turn(
             let telemetry_for_monitoring = telemetry_broadcaster.clone();
             let command_for_monitoring = Some(command_broadcaster.clone());
             let stop_for_monitoring = stop_signal.clone();
+            let capture_for_monitoring = debug_capture.clone();
             monitoring_handle = Some(tokio::spawn(async move {
                 println!("🔋 Starting telemetry monitor at {:.1}Hz...", desired_sampling_hz);
-                if let Err(e) = start_enhanced_monitoring(telemetry_for_monitoring, stop_for_monitoring, command_for_monitoring, Some(desired_sampling_hz)).await {
+                if let Err(e) = start_enhanced_monitoring(telemetry_for_monitoring, stop_for_monitoring, command_for_monitoring, Some(desired_sampling_hz), telemetry_profile, integration_method, capture_for_monitoring, idle_power).await {
                     println!("❌ Telemetry monitoring error: {}", e);
                 }
             }));
@@ -116,7 +433,7 @@ pub async fn run_generation_turn(
     let event_handle = if !disable_telemetry {
         dprintln!("🔧 BACKEND: Setting up telemetry event emitter task...");
         let _telemetry_for_events = telemetry_broadcaster.clone();
-        let window_for_events = window.clone();
+        let events_for_telemetry = events.clone();
         let mut telemetry_rx = telemetry_broadcaster.subscribe();
         dprintln!("🔧 BACKEND: About to spawn event emitter task...");
         dprintln!("🔧 BACKEND: Current broadcaster receiver count: {}", telemetry_broadcaster.receiver_count());
@@ -130,10 +447,6 @@ pub async fn run_generation_turn(
             Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_)) => dprintln!("🔧 BACKEND: telemetry_rx is lagged but valid"),
         }
 
-        // Test window validity
-        dprintln!("🔧 BACKEND: Testing window_for_events validity...");
-        dprintln!("🔧 BACKEND: Window label: {:?}", window_for_events.label());
-
         dprintln!("🔧 BACKEND: All variables validated, spawning task...");
         Some(tauri::async_runtime::spawn(async move {
             dprintln!("🎯 BACKEND: *** TELEMETRY EVENT EMITTER TASK STARTED ***");
@@ -165,7 +478,7 @@ pub async fn run_generation_turn(
                                 dprintln!("🎯 BACKEND: Telemetry data: timestamp={}, cpu_power={:?}, model={:?}",
                                          telemetry.timestamp_ms, telemetry.cpu_power_watts, telemetry.model);
 
-                                match window_for_events.emit("telemetry_update", &telemetry) {
+                                match events_for_telemetry.emit("telemetry_update", &telemetry) {
                                     Ok(()) => {
                                         dprintln!("🎯 BACKEND: ✅ Telemetry event successfully emitted to frontend!");
                                     }
@@ -197,10 +510,15 @@ if event_handle.is_some() {
     }
 
 let inference_handle = {
-        let window = window.clone();
+        let events = events.clone();
         let telemetry_broadcaster = telemetry_broadcaster.clone();
         let config = config.clone();
         let disable_telemetry_inner = disable_telemetry;
+        let run_id = run_guard.run_id().to_string();
+        let stream_output_dir = stream_output_dir.clone();
+        // Models stay resident in the app's cache between turns
+        let model_cache = window.state::<ModelCache>().inner().clone();
+        let temperature_unit = window.state::<SessionDatabase>().temperature_unit().unwrap_or_default();
         // The inference process is CPU-bound and blocks the async runtime, starving other tasks.
         // We use `spawn_blocking` to move the entire inference process to a separate thread pool
         // where it won't interfere with the main async runtime responsible for telemetry.
@@ -214,230 +532,227 @@ let inference_handle = {
 
                 // Prepare optional telemetry broadcaster for inference
                 let telemetry_opt = if disable_telemetry_inner { None } else { Some(telemetry_broadcaster.clone()) };
+                // Prefill/decode markers for the per-phase energy breakdown
+                let phase_markers = |model: &str| {
+                    (!disable_telemetry_inner).then(|| InferencePhaseMarkers::new(command_broadcaster.clone(), model, phase_ack_timeout))
+                };
+                let mut results = Vec::new();
 
 match config.target.as_str() {
                     "A" => {
                         if let Some(model_a) = &config.model_a {
+                            heat_soak_before(events.as_ref(), &config, "A", temperature_unit).await;
                             dprintln!("🤖 Running inference for Model A{}", if disable_telemetry_inner { " (telemetry disabled)" } else { " with telemetry..." });
-                            // Reset power calculator only when telemetry is enabled
+                            // Attribute energy to Model A from here on (only when telemetry is enabled)
                             if !disable_telemetry_inner {
-                                if let Err(e) = command_broadcaster.send(TelemetryCommand::ResetPowerCalculator) {
-                                    println!("⚠️ Failed to send power calculator reset command for Model A: {}", e);
-                                } else {
-                                    println!("🔄 Sent power calculator reset command for Model A");
-                                }
+                                begin_model_phase(&command_broadcaster, "A", phase_ack_timeout).await;
+                            }
+                            let output_stream = open_output_stream(stream_output_dir.as_deref(), &run_id, "A", model_a);
+                            results.push(run_model_inference(&events, ModelRun {
+                                model_config: model_a,
+                                chat_history: &config.chat_history,
+                                model_label: "A",
+                                system_prompt: config.system_prompt.as_deref(),
+                                telemetry_broadcaster: telemetry_opt.clone(),
+                                phase_markers: phase_markers("A"),
+                                output_stream,
+                                measurement_overhead,
+                                decode_metrics,
+                            }, &model_cache).await?);
+                            if !disable_telemetry_inner {
+                                end_model_phase(&command_broadcaster, "A", phase_ack_timeout).await;
                             }
-                            run_model_inference(&window, model_a, &config.chat_history, "A", telemetry_opt.clone(), config.system_prompt.as_deref()).await?;
                         } else {
-                            return Err("Model A configuration missing".to_string());
+                            return Err(A2oError::InvalidInput("Model A configuration missing".to_string()));
                         }
                     }
                     "B" => {
                         if let Some(model_b) = &config.model_b {
+                            heat_soak_before(events.as_ref(), &config, "B", temperature_unit).await;
                             dprintln!("🤖 Running inference for Model B{}", if disable_telemetry_inner { " (telemetry disabled)" } else { " with telemetry..." });
-                            // Reset power calculator only when telemetry is enabled
+                            // Attribute energy to Model B from here on (only when telemetry is enabled)
                             if !disable_telemetry_inner {
-                                if let Err(e) = command_broadcaster.send(TelemetryCommand::ResetPowerCalculator) {
-                                    println!("⚠️ Failed to send power calculator reset command for Model B: {}", e);
-                                } else {
-                                    println!("🔄 Sent power calculator reset command for Model B");
-                                }
+                                begin_model_phase(&command_broadcaster, "B", phase_ack_timeout).await;
+                            }
+                            let output_stream = open_output_stream(stream_output_dir.as_deref(), &run_id, "B", model_b);
+                            results.push(run_model_inference(&events, ModelRun {
+                                model_config: model_b,
+                                chat_history: &config.chat_history,
+                                model_label: "B",
+                                system_prompt: config.system_prompt.as_deref(),
+                                telemetry_broadcaster: telemetry_opt.clone(),
+                                phase_markers: phase_markers("B"),
+                                output_stream,
+                                measurement_overhead,
+                                decode_metrics,
+                            }, &model_cache).await?);
+                            if !disable_telemetry_inner {
+                                end_model_phase(&command_broadcaster, "B", phase_ack_timeout).await;
                             }
-                            run_model_inference(&window, model_b, &config.chat_history, "B", telemetry_opt.clone(), config.system_prompt.as_deref()).await?;
                         } else {
-                            return Err("Model B configuration missing".to_string());
+                            return Err(A2oError::InvalidInput("Model B configuration missing".to_string()));
+                        }
+                    }
+                    "Both" if config.parallel.unwrap_or(false) => {
+                        // Concurrent execution: both models load and generate at once on their own
+                        // threads, so token events interleave and telemetry shows the combined load
+                        let (Some(model_a), Some(model_b)) = (config.model_a.clone(), config.model_b.clone()) else {
+                            return Err(A2oError::InvalidInput("Parallel mode needs both Model A and Model B configured".to_string()));
+                        };
+                        if config.wait_for_cpu_baseline_between_models.unwrap_or(false) {
+                            println!("ℹ️ Cooldown between models does not apply to parallel runs - skipping");
+                        }
+                        // Both start together, so a single soak covers them
+                        heat_soak_before(events.as_ref(), &config, PARALLEL_PHASE_LABEL, temperature_unit).await;
+                        println!("🏎️ Running Model A and Model B in parallel");
+                        // Power is shared hardware, so energy is attributed to both models together
+                        if !disable_telemetry_inner {
+                            begin_model_phase(&command_broadcaster, PARALLEL_PHASE_LABEL, phase_ack_timeout).await;
+                        }
+                        let parallel_start = std::time::Instant::now();
+                        let sides = [("A", model_a), ("B", model_b)].map(|(label, model)| {
+                            let events = events.clone();
+                            let chat_history = config.chat_history.clone();
+                            let system_prompt = config.system_prompt.clone();
+                            let telemetry_opt = telemetry_opt.clone();
+                            let model_cache = model_cache.clone();
+                            let output_stream = open_output_stream(stream_output_dir.as_deref(), &run_id, label, &model);
+                            tauri::async_runtime::spawn_blocking(move || {
+                                tauri::async_runtime::handle().block_on(run_model_inference(&events, ModelRun {
+                                    model_config: &model,
+                                    chat_history: &chat_history,
+                                    model_label: label,
+                                    system_prompt: system_prompt.as_deref(),
+                                    telemetry_broadcaster: telemetry_opt,
+                                    // No per-model phase: energy is only known for both models together
+                                    phase_markers: None,
+                                    output_stream,
+                                    measurement_overhead,
+                                    decode_metrics,
+                                }, &model_cache))
+                            })
+                        });
+                        let [side_a, side_b] = sides;
+                        let (result_a, result_b) = tokio::join!(side_a, side_b);
+                        if !disable_telemetry_inner {
+                            end_model_phase(&command_broadcaster, PARALLEL_PHASE_LABEL, phase_ack_timeout).await;
+                        }
+                        // Both sides run to completion; report the first failure
+                        for (label, result) in [("A", result_a), ("B", result_b)] {
+                            results.push(result.map_err(|e| format!("Model {} task failed: {}", label, e))??);
+                        }
+                        // Report the shared energy explicitly, over both models' tokens; per-model
+                        // energy stays unavailable
+                        let combined_energy = MODEL_ENERGY.read()
+                            .ok()
+                            .and_then(|totals| totals.as_ref().and_then(|totals| totals.get(PARALLEL_PHASE_LABEL).cloned()))
+                            .filter(|_| !disable_telemetry_inner)
+                            .map(|energy| energy_summary_event(
+                                energy,
+                                PARALLEL_PHASE_LABEL,
+                                results.iter().map(|result| result.output_tokens).sum(),
+                                results.iter().map(|result| result.input_tokens).sum(),
+                                measurement_overhead,
+                                parallel_start.elapsed(),
+                            ));
+                        match &combined_energy {
+                            Some(summary) => {
+                                let _ = events.emit("power_consumption_summary", summary.clone());
+                            }
+                            None if !disable_telemetry_inner => println!("⚠️ ENERGY SUMMARY: No energy recorded for Models {}", PARALLEL_PHASE_LABEL),
+                            None => {}
+                        }
+                        for result in &mut results {
+                            result.combined_energy = combined_energy.clone();
                         }
                     }
                     "Both" => {
-                        // Sequential execution: A -> unload -> optional cooldown -> B -> unload
+                        // Sequential execution: A -> optional cooldown -> B. With isolate_models each model
+                        // runs with the other unloaded; B stays cached until the next turn's A runs.
                         let wait_for_cooldown = config.wait_for_cpu_baseline_between_models.unwrap_or(false);
-                        let margin_c_raw = config.wait_for_cpu_baseline_margin_c.unwrap_or(2.0);
+                        let margin_c_raw = config.wait_for_cpu_baseline_margin_c.unwrap_or(DEFAULT_COOLDOWN_MARGIN_C);
                         // Clamp to a reasonable range but allow negative values to require cooling below baseline
                         let margin_c: f64 = margin_c_raw.max(-20.0).min(20.0);
                         let mut baseline_cpu_max: Option<f64> = None;
+                        let isolate_models = config.isolate_models.unwrap_or(false);
 
                         if let Some(model_a) = &config.model_a {
+                            if isolate_models {
+                                unload_other_model(&model_cache, config.model_b.as_ref(), model_a, "A");
+                            }
                             // Measure baseline CPU temp just before Model A loads/starts
                             if wait_for_cooldown {
-                                println!("🌡️ Measuring baseline CPU temperature before Model A starts...");
-                                match read_core_temperatures().await {
-                                    Ok(core_temp) => {
-                                        baseline_cpu_max = Some(core_temp.cpu_temp_max);
-                                        println!("🌡️ Baseline CPU max recorded: {:.1}°C", core_temp.cpu_temp_max);
-                                        // Emit cooldown started event with baseline and threshold
-                                        let baseline = core_temp.cpu_temp_max;
-                                        let threshold = baseline + margin_c;
-                                        let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                            state: "started".to_string(),
-                                            baseline_c: Some(baseline),
-                                            margin_c,
-                                            threshold_c: Some(threshold),
-                                            current_c: None,
-                                            elapsed_s: Some(0),
-                                            timestamp_ms: std::time::SystemTime::now()
-                                                .duration_since(std::time::UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64,
-                                        });
-                                    }
-                                    Err(e) => {
-                                        println!("⚠️ Failed to read baseline CPU temperature: {}. Proceeding without cooldown.", e);
-                                    }
-                                }
+                                baseline_cpu_max = measure_cooldown_baseline(events.as_ref(), margin_c, temperature_unit).await;
                             }
+                            heat_soak_before(events.as_ref(), &config, "A", temperature_unit).await;
 
                             dprintln!("🤖 Running inference for Model A (Both mode){}", if disable_telemetry_inner { " (telemetry disabled)" } else { " with telemetry..." });
-                            // Reset power calculator for Model A when telemetry is enabled
+                            // Attribute energy to Model A from here on (only when telemetry is enabled)
                             if !disable_telemetry_inner {
-                                if let Err(e) = command_broadcaster.send(TelemetryCommand::ResetPowerCalculator) {
-                                    println!("⚠️ Failed to send power calculator reset command for Model A (Both mode): {}", e);
-                                } else {
-                                    println!("🔄 Sent power calculator reset command for Model A (Both mode)");
-                                }
+                                begin_model_phase(&command_broadcaster, "A", phase_ack_timeout).await;
                             }
-                            run_model_inference(&window, model_a, &config.chat_history, "A", telemetry_opt.clone(), config.system_prompt.as_deref()).await?;
-                            // Model A is automatically unloaded when it goes out of scope
+                            let output_stream = open_output_stream(stream_output_dir.as_deref(), &run_id, "A", model_a);
+                            results.push(run_model_inference(&events, ModelRun {
+                                model_config: model_a,
+                                chat_history: &config.chat_history,
+                                model_label: "A",
+                                system_prompt: config.system_prompt.as_deref(),
+                                telemetry_broadcaster: telemetry_opt.clone(),
+                                phase_markers: phase_markers("A"),
+                                output_stream,
+                                measurement_overhead,
+                                decode_metrics,
+                            }, &model_cache).await?);
+                            if !disable_telemetry_inner {
+                                end_model_phase(&command_broadcaster, "A", phase_ack_timeout).await;
+                            }
+                        }
+                        if let Some(model_b) = config.model_b.as_ref().filter(|_| isolate_models) {
+                            unload_other_model(&model_cache, config.model_a.as_ref(), model_b, "B");
                         }
 
                         // Optional cooldown before starting Model B
                         if wait_for_cooldown {
                             if let Some(baseline) = baseline_cpu_max {
-                                const POLL_INTERVAL_MS: u64 = 1000;
-                                const MAX_WAIT_SECS: u64 = 300; // Safety cap
-
-                                println!("🧊 Waiting for CPU to cool to baseline + {:.1}°C (≤ {:.1}°C)...", margin_c, baseline + margin_c);
-                                let start_wait = std::time::Instant::now();
-
-                                loop {
-                                    // Check for cancellation
-                                    if let Ok(stop_signal_guard) = GLOBAL_STOP_SIGNAL.read() {
-                                        if let Some(stop) = stop_signal_guard.as_ref() {
-                                            if stop.load(Ordering::Relaxed) {
-                                                println!("🛑 Cooldown wait canceled by stop signal");
-                                                // Emit canceled event
-                                                let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                                    state: "canceled".to_string(),
-                                                    baseline_c: Some(baseline),
-                                                    margin_c,
-                                                    threshold_c: Some(baseline + margin_c),
-                                                    current_c: None,
-                                                    elapsed_s: Some(start_wait.elapsed().as_secs()),
-                                                    timestamp_ms: std::time::SystemTime::now()
-                                                        .duration_since(std::time::UNIX_EPOCH)
-                                                        .unwrap()
-                                                        .as_millis() as u64,
-                                                });
-                                                break;
-                                            }
-                                        }
-                                    }
-
-                                    match read_core_temperatures().await {
-                                        Ok(core_temp) => {
-                                            let current_max = core_temp.cpu_temp_max;
-                                            let threshold = baseline + margin_c;
-                                            let elapsed = start_wait.elapsed().as_secs();
-                                            println!("🌡️ Current CPU max: {:.1}°C (target ≤ {:.1}°C)", current_max, threshold);
-
-                                            // Emit progress event
-                                            let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                                state: "progress".to_string(),
-                                                baseline_c: Some(baseline),
-                                                margin_c,
-                                                threshold_c: Some(threshold),
-                                                current_c: Some(current_max),
-                                                elapsed_s: Some(elapsed),
-                                                timestamp_ms: std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH)
-                                                    .unwrap()
-                                                    .as_millis() as u64,
-                                            });
-
-                                            if current_max <= threshold {
-                                                println!("✅ CPU cooled to within target threshold. Proceeding to Model B.");
-                                                // Emit completion event
-                                                let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                                    state: "complete".to_string(),
-                                                    baseline_c: Some(baseline),
-                                                    margin_c,
-                                                    threshold_c: Some(threshold),
-                                                    current_c: Some(current_max),
-                                                    elapsed_s: Some(elapsed),
-                                                    timestamp_ms: std::time::SystemTime::now()
-                                                        .duration_since(std::time::UNIX_EPOCH)
-                                                        .unwrap()
-                                                        .as_millis() as u64,
-                                                });
-                                                break;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            println!("⚠️ Failed to read CPU temperature during cooldown wait: {}. Proceeding without further wait.", e);
-                                            // Emit canceled event due to read error
-                                            let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                                state: "canceled".to_string(),
-                                                baseline_c: Some(baseline),
-                                                margin_c,
-                                                threshold_c: Some(baseline + margin_c),
-                                                current_c: None,
-                                                elapsed_s: Some(start_wait.elapsed().as_secs()),
-                                                timestamp_ms: std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH)
-                                                    .unwrap()
-                                                    .as_millis() as u64,
-                                            });
-                                            break;
-                                        }
-                                    }
-
-                                    if start_wait.elapsed().as_secs() >= MAX_WAIT_SECS {
-                                        println!("⏱️ Cooldown wait timed out after {} seconds. Proceeding to Model B.", MAX_WAIT_SECS);
-                                        // Emit timeout event
-                                        let _ = window.emit("cooldown_update", CooldownUpdateEvent {
-                                            state: "timeout".to_string(),
-                                            baseline_c: Some(baseline),
-                                            margin_c,
-                                            threshold_c: Some(baseline + margin_c),
-                                            current_c: None,
-                                            elapsed_s: Some(MAX_WAIT_SECS),
-                                            timestamp_ms: std::time::SystemTime::now()
-                                                .duration_since(std::time::UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64,
-                                        });
-                                        break;
-                                    }
-
-                                    tokio::time::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)).await;
-                                }
+                                wait_for_cpu_cooldown(events.as_ref(), baseline, margin_c, temperature_unit).await;
                             } else {
                                 println!("ℹ️ No baseline CPU temperature recorded. Skipping cooldown wait.");
                             }
                         }
 
                         if let Some(model_b) = &config.model_b {
+                            heat_soak_before(events.as_ref(), &config, "B", temperature_unit).await;
                             dprintln!("🤖 Running inference for Model B (Both mode){}", if disable_telemetry_inner { " (telemetry disabled)" } else { " with telemetry..." });
-                            // Reset power calculator for Model B - only when telemetry is enabled
+                            // Attribute energy to Model B from here on (only when telemetry is enabled)
                             if !disable_telemetry_inner {
-                                if let Err(e) = command_broadcaster.send(TelemetryCommand::ResetPowerCalculator) {
-                                    println!("⚠️ Failed to send power calculator reset command for Model B (Both mode): {}", e);
-                                } else {
-                                    println!("🔄 Sent power calculator reset command for Model B (Both mode) - energy will reset to 0");
-                                }
+                                begin_model_phase(&command_broadcaster, "B", phase_ack_timeout).await;
+                            }
+                            let output_stream = open_output_stream(stream_output_dir.as_deref(), &run_id, "B", model_b);
+                            results.push(run_model_inference(&events, ModelRun {
+                                model_config: model_b,
+                                chat_history: &config.chat_history,
+                                model_label: "B",
+                                system_prompt: config.system_prompt.as_deref(),
+                                telemetry_broadcaster: telemetry_opt.clone(),
+                                phase_markers: phase_markers("B"),
+                                output_stream,
+                                measurement_overhead,
+                                decode_metrics,
+                            }, &model_cache).await?);
+                            if !disable_telemetry_inner {
+                                end_model_phase(&command_broadcaster, "B", phase_ack_timeout).await;
                             }
-                            run_model_inference(&window, model_b, &config.chat_history, "B", telemetry_opt.clone(), config.system_prompt.as_deref()).await?;
-                            // Model B is automatically unloaded when it goes out of scope
                         }
                     }
                     _ => {
-                        return Err(format!("Invalid target: {}", config.target));
+                        return Err(A2oError::InvalidInput(format!("Invalid target: {}", config.target)));
                     }
                 }
 
                 println!("🎯 BACKEND: Inference completed - now safe to stop telemetry");
 
                 // Explicitly define the Ok type for the Result
-                Ok::<(), String>(())
+                Ok::<Vec<GenerationResult>, A2oError>(results)
             })
         })
     };
@@ -445,9 +760,9 @@ match config.target.as_str() {
     // The result from spawn_blocking's JoinHandle is a Result from the thread,
     // which contains another Result from the block_on call.
     let result = match inference_handle.await {
-        Ok(Ok(res)) => Ok(res), // Successfully completed, `res` is `Ok(())` from the inner block
+        Ok(Ok(res)) => Ok(res), // Successfully completed, `res` holds each model's GenerationResult
         Ok(Err(e)) => Err(e), // `block_on` returned an error from `run_model_inference`
-        Err(e) => Err(e.to_string()), // The blocking task panicked
+        Err(e) => Err(A2oError::Other(e.to_string())), // The blocking task panicked
     };
     
     // Stop monitoring and cleanup
@@ -465,6 +780,29 @@ match config.target.as_str() {
     dprintln!("🛑 BACKEND: Aborting event emitter handle if running...");
     if let Some(handle) = event_handle { handle.abort(); }
     dprintln!("🛑 BACKEND: All telemetry tasks have been stopped (or were not started)");
+
+    // Rewrite the manifest with the context parameters llama.cpp actually used
+    if let (Some(mut manifest), Ok(results)) = (run_manifest, &result) {
+        record_effective_context(&mut manifest, results);
+        for warning in &manifest.context_warnings {
+            println!("⚠️ RUN MANIFEST: {}", warning);
+        }
+        publish_run_manifest(&window, events.as_ref(), &manifest);
+    }
+
+    // Both models' energy totals for the session summary
+    if !disable_telemetry {
+        let models = MODEL_ENERGY.read().ok().and_then(|totals| totals.clone()).unwrap_or_default();
+        println!("📊 SESSION ENERGY: {} model(s) recorded", models.len());
+        let _ = events.emit("session_energy_summary", SessionEnergySummaryEvent {
+            run_id: run_guard.run_id().to_string(),
+            models,
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
+    }
     
     // Clear global stop signal
     {
@@ -473,6 +811,15 @@ match config.target.as_str() {
             println!("🛑 Global stop signal cleared");
         }
     }
-    
-    result
+    clear_model_stop_signals();
+    if let Ok(mut commands) = TELEMETRY_COMMANDS.write() {
+        *commands = None;
+    }
+
+    finish_partial_outputs(run_guard.run_id());
+    println!("🏁 Generation run {} finished", run_guard.run_id());
+    let run_id = run_guard.run_id().to_string();
+    drop(run_guard);
+
+    result.map(|results| GenerationTurnResult { run_id, results })
 }
\ No newline at end of file
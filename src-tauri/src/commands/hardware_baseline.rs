@@ -0,0 +1,38 @@
+// Contains hardware capability baseline commands
+
+use tauri::State;
+
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_cache::ModelCache;
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::hardware_baseline::{run_hardware_baseline, HardwareBaseline};
+use crate::telemetry::processor::ActiveRunGuard;
+use crate::error::A2oError;
+
+/// Benchmark this machine with a small reference model and keep the result; sessions
+/// saved afterwards record it. Holds the run guard so no generation can start meanwhile.
+#[tauri::command]
+pub async fn measure_hardware_baseline(
+    db: State<'_, SessionDatabase>,
+    model_cache: State<'_, ModelCache>,
+    model_path: String,
+) -> Result<HardwareBaseline, A2oError> {
+    let run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected hardware baseline request: run {} in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+    run_guard.set_model_paths(resolve_model_path(&model_path).into_iter().collect());
+
+    let model_cache = model_cache.inner().clone();
+    let baseline = tauri::async_runtime::spawn_blocking(move || run_hardware_baseline(&model_path, &model_cache))
+        .await
+        .map_err(|e| format!("Hardware baseline task failed: {}", e))??;
+    db.set_hardware_baseline(&baseline)?;
+    Ok(baseline)
+}
+
+/// The stored baseline; None until one has been measured (e.g. on first launch)
+#[tauri::command]
+pub fn get_hardware_baseline(db: State<'_, SessionDatabase>) -> Result<Option<HardwareBaseline>, A2oError> {
+    db.hardware_baseline().map_err(A2oError::from)
+}
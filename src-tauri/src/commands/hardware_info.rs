@@ -0,0 +1,29 @@
+// Contains get_hardware_info and get_backend_info Tauri commands (chip summary, platform
+// capability probe and llama.cpp build info)
+
+use serde::Serialize;
+
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::hardware::backend_info::{backend_info, BackendInfo};
+use crate::hardware::capabilities::{platform_capabilities, PlatformCapabilities};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HardwareInfo {
+    pub hardware: HardwareSummary,
+    pub capabilities: PlatformCapabilities,
+}
+
+/// Chip summary plus the startup capability probe (Rosetta, Metal, NEON/Accelerate)
+#[tauri::command]
+pub fn get_hardware_info() -> HardwareInfo {
+    HardwareInfo {
+        hardware: hardware_summary(),
+        capabilities: platform_capabilities().clone(),
+    }
+}
+
+/// llama.cpp binding versions, registered ggml backends and compile-time features
+#[tauri::command]
+pub fn get_backend_info() -> BackendInfo {
+    backend_info().clone()
+}
@@ -0,0 +1,45 @@
+// Contains idle power baseline commands
+
+use std::time::Duration;
+use tauri::{State, Window};
+
+use crate::events::TauriWindowSink;
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::idle_baseline::{capture_idle_baseline, IdleBaseline};
+use crate::telemetry::processor::ActiveRunGuard;
+use crate::error::A2oError;
+
+const DEFAULT_IDLE_BASELINE_S: f64 = 30.0;
+const MIN_IDLE_BASELINE_S: f64 = 5.0;
+const MAX_IDLE_BASELINE_S: f64 = 300.0;
+
+/// Sample idle power and temperatures for `duration_s` and keep the result; runs with
+/// subtract_idle_baseline then report energy above it. Holds the run guard so no
+/// generation can start meanwhile.
+#[tauri::command]
+pub async fn capture_baseline(
+    window: Window,
+    db: State<'_, SessionDatabase>,
+    duration_s: Option<f64>,
+) -> Result<IdleBaseline, A2oError> {
+    let duration_s = duration_s.unwrap_or(DEFAULT_IDLE_BASELINE_S);
+    if !duration_s.is_finite() {
+        return Err(A2oError::InvalidInput(format!("Invalid baseline duration of {} s", duration_s)));
+    }
+    let _run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected idle baseline request: run {} in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+
+    let duration = Duration::from_secs_f64(duration_s.clamp(MIN_IDLE_BASELINE_S, MAX_IDLE_BASELINE_S));
+    let baseline = capture_idle_baseline(&TauriWindowSink::new(window), duration).await
+        .map_err(A2oError::SensorUnavailable)?;
+    db.set_idle_baseline(&baseline)?;
+    Ok(baseline)
+}
+
+/// The stored idle baseline; None until one has been captured
+#[tauri::command]
+pub fn get_idle_baseline(db: State<'_, SessionDatabase>) -> Result<Option<IdleBaseline>, A2oError> {
+    db.idle_baseline().map_err(A2oError::from)
+}
@@ -0,0 +1,292 @@
+// Contains the job registry behind run_generation_turn and the job commands
+// (get_job_status, await_job, cancel_job)
+//
+// A job runs in the background from the moment it is created, so the invoking call returns
+// its id at once; the outcome is polled or awaited by id. Finished jobs are kept (up to
+// MAX_FINISHED_JOBS) so a late await still gets the result. A job learns its run id from
+// attach_run_to_job, and cancel_job only stops generation while that run is the active one.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::commands::utils::stop_generation;
+use crate::telemetry::processor::ACTIVE_RUN_ID;
+use crate::telemetry::types::GenerationTurnResult;
+use crate::error::A2oError;
+
+const MAX_FINISHED_JOBS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,   // cancel_job was called; `result` holds whatever the run produced
+}
+
+#[derive(Clone, Serialize)]
+pub struct JobStatus {
+    pub job_id: String,
+    pub kind: String,   // "generation_turn"
+    pub state: JobState,
+    pub run_id: Option<String>,   // Run the job drives once it has started; for get_partial_output
+    pub created_at_ms: u64,
+    pub finished_at_ms: Option<u64>,
+    pub result: Option<GenerationTurnResult>,
+    pub error: Option<A2oError>,
+}
+
+/// Returned by commands that start a job
+#[derive(Clone, Serialize)]
+pub struct JobHandle {
+    pub job_id: String,
+}
+
+struct Job {
+    status: watch::Receiver<JobStatus>,
+    run: Arc<JobRun>,
+}
+
+// Shared between a job's entry and its running future
+#[derive(Default)]
+struct JobRun {
+    cancel_requested: AtomicBool,
+    run_id: RwLock<Option<String>>,   // Set by attach_run_to_job once the run holds the guard
+}
+
+tokio::task_local! {
+    static CURRENT_JOB: Arc<JobRun>;
+}
+
+static JOBS: RwLock<Option<HashMap<String, Job>>> = RwLock::new(None);
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+// Drop the oldest finished jobs beyond MAX_FINISHED_JOBS
+fn prune_finished(jobs: &mut HashMap<String, Job>) {
+    let mut finished: Vec<(u64, String)> = jobs.iter()
+        .filter_map(|(id, job)| {
+            let status = job.status.borrow();
+            status.finished_at_ms.map(|finished_at| (finished_at, id.clone()))
+        })
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+        jobs.remove(id);
+    }
+}
+
+/// Start `run` in the background as a job of `kind` and return its id. A job cancelled
+/// before `run` got going never starts it.
+pub fn start_job<F>(kind: &str, run: F) -> Result<JobHandle, A2oError>
+where
+    F: Future<Output = Result<GenerationTurnResult, A2oError>> + Send + 'static,
+{
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let (sender, receiver) = watch::channel(JobStatus {
+        job_id: job_id.clone(),
+        kind: kind.to_string(),
+        state: JobState::Running,
+        run_id: None,
+        created_at_ms: now_ms(),
+        finished_at_ms: None,
+        result: None,
+        error: None,
+    });
+    let job_run = Arc::new(JobRun::default());
+    {
+        let mut jobs = JOBS.write().map_err(|_| "Job registry poisoned".to_string())?;
+        let jobs = jobs.get_or_insert_with(HashMap::new);
+        prune_finished(jobs);
+        jobs.insert(job_id.clone(), Job { status: receiver, run: job_run.clone() });
+    }
+    println!("🧾 Job {} ({}) started", job_id, kind);
+
+    let id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let outcome = if job_run.cancel_requested.load(Ordering::SeqCst) {
+            Err(A2oError::Cancelled)
+        } else {
+            CURRENT_JOB.scope(job_run.clone(), run).await
+        };
+        let cancelled = job_run.cancel_requested.load(Ordering::SeqCst);
+        sender.send_modify(|status| {
+            status.finished_at_ms = Some(now_ms());
+            match outcome {
+                Ok(result) => {
+                    status.state = if cancelled { JobState::Cancelled } else { JobState::Completed };
+                    status.result = Some(result);
+                }
+                Err(error) => {
+                    status.state = if cancelled { JobState::Cancelled } else { JobState::Failed };
+                    status.error = Some(error);
+                }
+            }
+        });
+        println!("🧾 Job {} finished: {:?}", id, sender.borrow().state);
+    });
+    Ok(JobHandle { job_id })
+}
+
+/// Record that the calling job is driving run `run_id`, so cancel_job can stop it. Fails with
+/// Cancelled when the job was cancelled before its run started; does nothing outside a job.
+pub fn attach_run_to_job(run_id: &str) -> Result<(), A2oError> {
+    CURRENT_JOB.try_with(|job| {
+        // Publish the run id before checking the flag; cancel_job does the reverse, so one
+        // of the two always sees the other
+        *job.run_id.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(run_id.to_string());
+        if job.cancel_requested.load(Ordering::SeqCst) { Err(A2oError::Cancelled) } else { Ok(()) }
+    }).unwrap_or(Ok(()))
+}
+
+// The job's status with the run id its run attached, which the watch channel does not carry
+fn current_status(status: &watch::Receiver<JobStatus>, job_run: &JobRun) -> JobStatus {
+    let mut status = status.borrow().clone();
+    status.run_id = job_run.run_id.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    status
+}
+
+fn job_receiver(job_id: &str) -> Result<(watch::Receiver<JobStatus>, Arc<JobRun>), A2oError> {
+    let jobs = JOBS.read().map_err(|_| "Job registry poisoned".to_string())?;
+    jobs.as_ref()
+        .and_then(|jobs| jobs.get(job_id))
+        .map(|job| (job.status.clone(), job.run.clone()))
+        .ok_or_else(|| A2oError::NotFound(format!("Unknown job {}", job_id)))
+}
+
+#[tauri::command]
+pub fn get_job_status(job_id: String) -> Result<JobStatus, A2oError> {
+    let (status, job_run) = job_receiver(&job_id)?;
+    Ok(current_status(&status, &job_run))
+}
+
+/// Wait for the job to finish and return its result, or the error it failed with. A
+/// cancelled job still returns what it generated before stopping.
+#[tauri::command]
+pub async fn await_job(job_id: String) -> Result<GenerationTurnResult, A2oError> {
+    let (mut status, _) = job_receiver(&job_id)?;
+    let status = status.wait_for(|status| status.state != JobState::Running)
+        .await
+        .map_err(|_| format!("Job {} ended without reporting a result", job_id))?
+        .clone();
+    match (status.result, status.error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(error),
+        (None, None) => Err(A2oError::Cancelled),
+    }
+}
+
+/// Stop a running job; it finishes as `cancelled` once the run has wound down. Only the
+/// job's own run is stopped: a job whose run has not started yet never starts it, and a run
+/// started by anything else keeps going. Cancelling a finished job does nothing.
+#[tauri::command]
+pub fn cancel_job(job_id: String) -> Result<JobStatus, A2oError> {
+    let (status, job_run) = job_receiver(&job_id)?;
+    let status = current_status(&status, &job_run);
+    if status.state == JobState::Running {
+        println!("🧾 Cancelling job {}", job_id);
+        job_run.cancel_requested.store(true, Ordering::SeqCst);
+        let run_id = status.run_id.clone();
+        // Hold the active run id while signalling, so a run starting meanwhile is not hit
+        let active = ACTIVE_RUN_ID.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match run_id {
+            Some(run_id) if active.as_deref() == Some(run_id.as_str()) => stop_generation(None)?,
+            _ => println!("🧾 Job {} has no active run to stop", job_id),
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::utils::get_partial_output;
+    use crate::telemetry::processor::{append_partial_output, begin_partial_outputs};
+
+    #[test]
+    fn test_jobs_report_results_and_errors() {
+        tauri::async_runtime::block_on(async {
+            let done = start_job("test", async {
+                Ok(GenerationTurnResult { run_id: "run-1".to_string(), results: Vec::new() })
+            }).unwrap();
+            assert_eq!(await_job(done.job_id.clone()).await.unwrap().run_id, "run-1");
+            let status = get_job_status(done.job_id).unwrap();
+            assert_eq!(status.state, JobState::Completed);
+            assert!(status.finished_at_ms.is_some());
+
+            let failed = start_job("test", async { Err(A2oError::ModelNotFound("missing.gguf".to_string())) }).unwrap();
+            let error = await_job(failed.job_id.clone()).await.err().unwrap();
+            assert_eq!(error.code(), "model_not_found");
+            assert_eq!(get_job_status(failed.job_id).unwrap().state, JobState::Failed);
+
+            assert_eq!(get_job_status("nope".to_string()).err().unwrap().code(), "not_found");
+        });
+    }
+
+    #[test]
+    fn test_cancelling_before_the_run_attaches_stops_it_from_starting() {
+        tauri::async_runtime::block_on(async {
+            let release = Arc::new(tokio::sync::Notify::new());
+            let started = release.clone();
+            let job = start_job("test", async move {
+                started.notified().await;
+                attach_run_to_job("run-late")?;
+                Ok(GenerationTurnResult { run_id: "run-late".to_string(), results: Vec::new() })
+            }).unwrap();
+            tokio::task::yield_now().await;
+
+            // The job's run is not the active one, so nothing else is signalled to stop
+            assert_eq!(cancel_job(job.job_id.clone()).unwrap().state, JobState::Running);
+            release.notify_one();
+            assert_eq!(await_job(job.job_id.clone()).await.err().unwrap().code(), "cancelled");
+            assert_eq!(get_job_status(job.job_id).unwrap().state, JobState::Cancelled);
+        });
+        // Outside a job there is nothing to attach to
+        assert!(attach_run_to_job("run-free").is_ok());
+    }
+
+    #[test]
+    fn test_running_job_reports_its_run_id_for_partial_output() {
+        tauri::async_runtime::block_on(async {
+            let (attached_tx, attached_rx) = tokio::sync::oneshot::channel();
+            let release = Arc::new(tokio::sync::Notify::new());
+            let finish = release.clone();
+            let job = start_job("test", async move {
+                attach_run_to_job("run-partial")?;
+                begin_partial_outputs("run-partial");
+                append_partial_output("A", "Hello, wor");
+                let _ = attached_tx.send(());
+                finish.notified().await;
+                Ok(GenerationTurnResult { run_id: "run-partial".to_string(), results: Vec::new() })
+            }).unwrap();
+            attached_rx.await.unwrap();
+
+            // A client that missed generation_started finds the run through the job
+            let status = get_job_status(job.job_id.clone()).unwrap();
+            assert_eq!(status.state, JobState::Running);
+            let run_id = status.run_id.unwrap();
+            assert_eq!(run_id, "run-partial");
+            let partial = get_partial_output(run_id, "A".to_string()).unwrap();
+            assert_eq!((partial.text.as_str(), partial.finished), ("Hello, wor", false));
+            assert_eq!(get_partial_output("run-older".to_string(), "A".to_string()).err().unwrap().code(), "not_found");
+
+            release.notify_one();
+            await_job(job.job_id.clone()).await.unwrap();
+            assert_eq!(get_job_status(job.job_id).unwrap().run_id.as_deref(), Some("run-partial"));
+        });
+    }
+}
@@ -0,0 +1,154 @@
+// Contains prepare_benchmark_record / export_benchmark_record Tauri commands
+// (opt-in, anonymized benchmark records for community leaderboards)
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::inference::gguf::GgufMetadata;
+use crate::inference::model_hash::sha256_file;
+use crate::error::A2oError;
+
+/// Identifies the benchmark record layout for community results repositories
+pub const BENCHMARK_RECORD_SCHEMA: &str = "apples2oranges.benchmark-record/v1";
+
+/// Run metrics for one model, as shown in the performance summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchmarkMetrics {
+    pub decode_tps: Option<f64>,
+    pub prefill_tps: Option<f64>,
+    pub ttft_ms: Option<f64>,
+    pub energy_per_token_wh: Option<f64>,
+    pub output_tokens: Option<usize>,
+    pub n_ctx: Option<u32>,
+}
+
+/// Identifies the model by content, never by path or file name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkModel {
+    pub sha256: String,
+    pub file_size_bytes: u64,
+    pub architecture: Option<String>,      // e.g. "llama"
+    pub size_label: Option<String>,        // e.g. "8B"
+    pub quantization: Option<String>,      // e.g. "Q4_K_M"
+}
+
+/// Anonymized record: no paths, file names, prompts, outputs, host or user names,
+/// memory rounded to whole GB and only the date of the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub schema: String,
+    pub app_version: String,
+    pub date: String,                      // YYYY-MM-DD (UTC)
+    pub hardware: HardwareSummary,
+    pub model: BenchmarkModel,
+    pub metrics: BenchmarkMetrics,
+}
+
+/// Build a record for the user to review; nothing is written or sent anywhere
+#[tauri::command]
+pub async fn prepare_benchmark_record(model_path: String, metrics: BenchmarkMetrics) -> Result<BenchmarkRecord, A2oError> {
+    println!("🏁 Preparing anonymized benchmark record (hashing model file)...");
+    let model = tauri::async_runtime::spawn_blocking(move || describe_model(Path::new(&model_path)))
+        .await
+        .map_err(|e| format!("Model hashing task failed: {}", e))??;
+
+    let mut hardware = hardware_summary();
+    hardware.memory_gb = hardware.memory_gb.map(f64::round);
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    Ok(build_record(model, hardware, metrics, date))
+}
+
+/// Write a record the user has reviewed to the chosen file
+#[tauri::command]
+pub fn export_benchmark_record(record: BenchmarkRecord, path: String) -> Result<(), A2oError> {
+    let json = record_json(&record)?;
+    std::fs::write(&path, json).map_err(|e| A2oError::Io(format!("Failed to write {}: {}", path, e)))?;
+    println!("🏁 Benchmark record exported to {}", path);
+    Ok(())
+}
+
+pub fn describe_model(path: &Path) -> Result<BenchmarkModel, String> {
+    let metadata = GgufMetadata::read_from_file(path)?;
+    let file_size_bytes = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to stat {}: {}", path.display(), e))?
+        .len();
+    Ok(BenchmarkModel {
+        sha256: sha256_file(path)?,
+        file_size_bytes,
+        architecture: metadata.architecture().map(str::to_string),
+        size_label: metadata.get_str("general.size_label").map(str::to_string),
+        quantization: metadata.quantization(),
+    })
+}
+
+pub fn build_record(model: BenchmarkModel, hardware: HardwareSummary, metrics: BenchmarkMetrics, date: String) -> BenchmarkRecord {
+    BenchmarkRecord {
+        schema: BENCHMARK_RECORD_SCHEMA.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        date,
+        hardware,
+        model,
+        metrics,
+    }
+}
+
+/// Serialize a record for export; refuses records that were not produced by prepare_benchmark_record
+pub fn record_json(record: &BenchmarkRecord) -> Result<String, String> {
+    if record.schema != BENCHMARK_RECORD_SCHEMA {
+        return Err(format!("Unsupported benchmark record schema: {}", record.schema));
+    }
+    if record.model.sha256.len() != 64 || !record.model.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Benchmark record has an invalid model hash".to_string());
+    }
+    serde_json::to_string_pretty(record).map_err(|e| format!("Failed to serialize benchmark record: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_record() -> BenchmarkRecord {
+        build_record(
+            BenchmarkModel {
+                sha256: "ab".repeat(32),
+                file_size_bytes: 4_920_000_000,
+                architecture: Some("llama".to_string()),
+                size_label: Some("8B".to_string()),
+                quantization: Some("Q4_K_M".to_string()),
+            },
+            HardwareSummary {
+                chip: "M2 Pro".to_string(),
+                total_cores: 12,
+                p_cores: 8,
+                e_cores: 4,
+                memory_gb: Some(32.0),
+                os: "macos aarch64".to_string(),
+            },
+            BenchmarkMetrics { decode_tps: Some(38.5), ..Default::default() },
+            "2025-01-31".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_record_round_trips_for_review() {
+        let json = record_json(&sample_record()).unwrap();
+        let reviewed: BenchmarkRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(reviewed.schema, BENCHMARK_RECORD_SCHEMA);
+        assert_eq!(reviewed.model.quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(reviewed.metrics.decode_tps, Some(38.5));
+        assert!(!json.contains("path"));
+    }
+
+    #[test]
+    fn test_export_rejects_edited_identity_fields() {
+        let mut record = sample_record();
+        record.model.sha256 = "/Users/me/models/llama.gguf".to_string();
+        assert!(record_json(&record).is_err());
+
+        let mut record = sample_record();
+        record.schema = "something-else".to_string();
+        assert!(record_json(&record).is_err());
+    }
+}
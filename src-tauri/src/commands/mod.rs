@@ -1,2 +1,26 @@
+pub mod api_server;
+pub mod benchmark;
+pub mod calibration;
+pub mod comparison_summary;
+pub mod config;
+pub mod conversation;
+pub mod conversation_import;
+pub mod debug_capture;
+pub mod event_stream;
 pub mod generation;
+pub mod hardware_baseline;
+pub mod hardware_info;
+pub mod idle_baseline;
+pub mod jobs;
+pub mod leaderboard;
+pub mod night_batch;
+pub mod model_cache;
+pub mod model_library;
+pub mod model_info;
+pub mod placement_comparison;
+pub mod sensor_profile;
+pub mod start_comparison;
+pub mod synthetic_load;
+pub mod throughput;
 pub mod utils;
+pub mod validation;
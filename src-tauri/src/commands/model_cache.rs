@@ -0,0 +1,31 @@
+// Contains commands for the resident model cache
+
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_cache::{CachedModelInfo, ModelCache};
+use crate::error::A2oError;
+
+/// Free the memory of a resident model (all load variants of the file), or of every model
+/// when no path is given. A run still using it keeps it alive until the run finishes.
+#[tauri::command]
+pub fn unload_model(
+    model_cache: State<'_, ModelCache>,
+    model_path: Option<String>,
+) -> Result<Vec<CachedModelInfo>, A2oError> {
+    // Cache keys hold resolved paths, so resolve the same way a run does
+    let path = model_path.map(|path| resolve_model_path(&path).unwrap_or_else(|| PathBuf::from(path)));
+    let unloaded = model_cache.unload(path.as_deref());
+    for info in &unloaded {
+        println!("♻️ MODEL CACHE: Unloaded {} on request", info.model_path);
+    }
+    Ok(unloaded)
+}
+
+/// Models currently resident, most recently used first
+#[tauri::command]
+pub fn get_loaded_models(model_cache: State<'_, ModelCache>) -> Result<Vec<CachedModelInfo>, A2oError> {
+    Ok(model_cache.loaded())
+}
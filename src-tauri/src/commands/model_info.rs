@@ -0,0 +1,14 @@
+// Contains the GGUF model inspection command
+
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_info::{inspect_model_file, ModelInfo};
+use crate::error::A2oError;
+
+/// Architecture, parameter count, quantization, context length, chat template and sizes
+/// of a GGUF file, read from its header without loading the model
+#[tauri::command]
+pub fn inspect_model(path: String) -> Result<ModelInfo, A2oError> {
+    let model_path = resolve_model_path(&path)
+        .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {}", path)))?;
+    inspect_model_file(&model_path).map_err(A2oError::InvalidInput)
+}
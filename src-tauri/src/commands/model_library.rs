@@ -0,0 +1,127 @@
+// Contains commands for the model library and its directory watcher
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_cache::ModelCache;
+use crate::persistence::database::SessionDatabase;
+use crate::persistence::model_library::{move_to_trash, scan_model_directories, ModelFile, ModelLibraryChange, ModelLibrarySettings};
+use crate::telemetry::processor::active_run_model_paths;
+use crate::error::A2oError;
+
+// Directory listings are cheap; a few seconds keeps the picker current without notice
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Managed as Tauri state. Each (re)start bumps the generation, which ends the previous
+/// watch loop at its next tick.
+#[derive(Clone, Default)]
+pub struct ModelLibraryWatcher {
+    generation: Arc<AtomicU64>,
+}
+
+impl ModelLibraryWatcher {
+    /// Poll the configured directories while watching is enabled; stops any running loop
+    pub fn restart(&self, app: &AppHandle, settings: &ModelLibrarySettings) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if !settings.watch || settings.directories.is_empty() {
+            return;
+        }
+        println!("📚 MODEL LIBRARY: Watching {} director(ies)", settings.directories.len());
+        let current = self.generation.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+                if current.load(Ordering::SeqCst) != generation {
+                    break;
+                }
+                if let Err(e) = refresh_library(&app) {
+                    println!("⚠️ MODEL LIBRARY: Rescan failed: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// Rescan the configured directories into the library table, emitting
+/// `model_library_changed` when anything was added, removed or rewritten
+pub fn refresh_library(app: &AppHandle) -> Result<ModelLibraryChange, A2oError> {
+    let db = app.state::<SessionDatabase>();
+    let settings = db.model_library_settings()?;
+    let files = scan_model_directories(&settings.directories);
+    let change = db.replace_model_library(&files)?;
+    if !change.is_empty() {
+        println!("📚 MODEL LIBRARY: {} added, {} removed, {} changed",
+                 change.added.len(), change.removed.len(), change.changed.len());
+        let _ = app.emit("model_library_changed", &change);
+    }
+    Ok(change)
+}
+
+/// GGUF files found under the model directories, sorted by path
+#[tauri::command]
+pub fn get_model_library(db: State<'_, SessionDatabase>) -> Result<Vec<ModelFile>, A2oError> {
+    db.model_library().map_err(A2oError::from)
+}
+
+#[tauri::command]
+pub async fn rescan_model_library(app: AppHandle) -> Result<ModelLibraryChange, A2oError> {
+    refresh_library(&app)
+}
+
+#[tauri::command]
+pub fn get_model_library_settings(db: State<'_, SessionDatabase>) -> Result<ModelLibrarySettings, A2oError> {
+    db.model_library_settings().map_err(A2oError::from)
+}
+
+/// Store the directories and watch flag, rescan right away and (re)start the watcher
+#[tauri::command]
+pub async fn set_model_library_settings(
+    app: AppHandle,
+    watcher: State<'_, ModelLibraryWatcher>,
+    settings: ModelLibrarySettings,
+) -> Result<ModelLibrarySettings, A2oError> {
+    let settings = settings.normalized();
+    app.state::<SessionDatabase>().set_model_library_settings(&settings)?;
+    refresh_library(&app)?;
+    watcher.restart(&app, &settings);
+    Ok(settings)
+}
+
+// Symlinks and relative spellings of the same file compare equal
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Move a GGUF to the Trash and drop it from the library. Refused while the model is
+/// resident in the model cache (unload it first) or used by the run in progress.
+#[tauri::command]
+pub async fn delete_model_file(
+    app: AppHandle,
+    model_cache: State<'_, ModelCache>,
+    path: String,
+) -> Result<ModelLibraryChange, A2oError> {
+    let resolved = resolve_model_path(&path)
+        .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {}", path)))?;
+    if !resolved.is_file() || !resolved.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gguf")) {
+        return Err(A2oError::InvalidInput(format!("{} is not a GGUF file", resolved.display())));
+    }
+    if active_run_model_paths().iter().any(|active| same_file(active, &resolved)) {
+        return Err(A2oError::ModelInUse(format!("{} is used by the run in progress", resolved.display())));
+    }
+    if model_cache.loaded().iter().any(|info| same_file(Path::new(&info.model_path), &resolved)) {
+        return Err(A2oError::ModelInUse(format!("{} is loaded in the model cache; unload it first", resolved.display())));
+    }
+
+    move_to_trash(&resolved).map_err(A2oError::Io)?;
+    println!("🗑️ MODEL LIBRARY: Moved {} to the Trash", resolved.display());
+    refresh_library(&app)
+}
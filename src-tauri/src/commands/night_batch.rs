@@ -0,0 +1,283 @@
+// Contains the night batch commands (schedule_night_batch, get_night_batch,
+// cancel_night_batch)
+//
+// A night batch is a benchmark suite started unattended at a set local time, when the
+// machine is otherwise idle. It holds an idle-sleep assertion while it runs, saves its
+// report like run_benchmark_suite and posts a summary notification for the morning.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, NaiveTime, TimeZone};
+use serde::Serialize;
+use tauri::{Manager, Window};
+
+use crate::commands::benchmark::execute_benchmark_suite;
+use crate::commands::utils::stop_generation;
+use crate::events::window_sink_with;
+use crate::hardware::power_assertion::SleepAssertion;
+use crate::persistence::benchmark::{BenchmarkReport, BenchmarkSuiteRequest};
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::types::GenerationConfig;
+use crate::error::A2oError;
+
+// Wall-clock polling rather than one long sleep: the monotonic clock tokio sleeps on does
+// not advance while the Mac is asleep, so a single sleep would start the batch late
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NightBatchState {
+    Scheduled,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, Serialize)]
+pub struct NightBatchStatus {
+    pub batch_id: String,
+    pub name: Option<String>,
+    pub start_time: String,          // Local "HH:MM" as configured
+    pub scheduled_for_ms: u64,       // Next occurrence of start_time when scheduled
+    pub state: NightBatchState,
+    pub started_at_ms: Option<u64>,
+    pub finished_at_ms: Option<u64>,
+    pub report_uuid: Option<String>,
+    pub summary: Option<String>,
+    pub error: Option<String>,
+}
+
+struct NightBatch {
+    status: NightBatchStatus,
+    cancel_requested: Arc<AtomicBool>,
+}
+
+static NIGHT_BATCH: Mutex<Option<NightBatch>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn parse_start_time(start_time: &str) -> Result<NaiveTime, A2oError> {
+    NaiveTime::parse_from_str(start_time.trim(), "%H:%M")
+        .map_err(|_| A2oError::InvalidInput(format!("Invalid start time '{}', expected HH:MM", start_time)))
+}
+
+/// First moment at or after `now` whose time of day is `at`: today if it is still ahead,
+/// otherwise tomorrow
+pub fn next_start(now: NaiveDateTime, at: NaiveTime) -> NaiveDateTime {
+    let today = now.date().and_time(at);
+    if today >= now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
+
+fn update_status(batch_id: &str, update: impl FnOnce(&mut NightBatchStatus)) {
+    if let Ok(mut batch) = NIGHT_BATCH.lock() {
+        if let Some(batch) = batch.as_mut().filter(|batch| batch.status.batch_id == batch_id) {
+            update(&mut batch.status);
+        }
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let minutes = elapsed.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// One-line morning summary: how far the suite got and each model's mean throughput
+pub fn summarize_report(report: &BenchmarkReport, elapsed: Duration) -> String {
+    let outcome = if report.completed { "finished" } else { "stopped early" };
+    let mut summary = format!("{} {}: {} result(s) in {}", report.name, outcome, report.runs.len(), format_elapsed(elapsed));
+    let throughput: Vec<String> = report.models.iter()
+        .filter_map(|model| model.metrics.get("avg_tps").map(|tps| format!("Model {} {:.1} tok/s", model.model, tps.mean)))
+        .collect();
+    if !throughput.is_empty() {
+        summary.push_str(". ");
+        summary.push_str(&throughput.join(", "));
+    }
+    summary
+}
+
+#[cfg(target_os = "macos")]
+fn post_notification(title: &str, message: &str) {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!("display notification {} with title {}", quote(message), quote(title));
+    if let Err(e) = std::process::Command::new("osascript").args(["-e", &script]).output() {
+        println!("⚠️ Failed to post night batch notification: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn post_notification(_title: &str, _message: &str) {}
+
+async fn run_night_batch(
+    window: Window,
+    batch_id: String,
+    scheduled_for_ms: u64,
+    request: BenchmarkSuiteRequest,
+    config: GenerationConfig,
+    cancel_requested: Arc<AtomicBool>,
+) {
+    while now_ms() < scheduled_for_ms {
+        if cancel_requested.load(Ordering::SeqCst) {
+            return;
+        }
+        let remaining = Duration::from_millis(scheduled_for_ms - now_ms().min(scheduled_for_ms));
+        tokio::time::sleep(remaining.min(SCHEDULE_POLL_INTERVAL)).await;
+    }
+    if cancel_requested.load(Ordering::SeqCst) {
+        return;
+    }
+
+    println!("🌙 Night batch {} starting", batch_id);
+    update_status(&batch_id, |status| {
+        status.state = NightBatchState::Running;
+        status.started_at_ms = Some(now_ms());
+    });
+    let started = Instant::now();
+    let events = window_sink_with(window.clone(), config.event_sink.as_deref());
+    let assertion = SleepAssertion::prevent_idle_sleep("apples2oranges night batch")
+        .map_err(|e| println!("⚠️ Could not prevent idle sleep ({}); the Mac may sleep during the batch", e))
+        .ok();
+
+    let db = window.state::<SessionDatabase>();
+    let outcome = execute_benchmark_suite(window.clone(), db.inner(), request, config).await;
+    drop(assertion);
+
+    let cancelled = cancel_requested.load(Ordering::SeqCst);
+    let (summary, report_uuid, error) = match &outcome {
+        Ok(report) => (summarize_report(report, started.elapsed()), Some(report.uuid.clone()), None),
+        Err(e) => (format!("Night batch failed: {}", e), None, Some(e.to_string())),
+    };
+    println!("🌙 Night batch {}: {}", batch_id, summary);
+    update_status(&batch_id, |status| {
+        status.state = match (&outcome, cancelled) {
+            (_, true) => NightBatchState::Cancelled,
+            (Ok(_), false) => NightBatchState::Completed,
+            (Err(_), false) => NightBatchState::Failed,
+        };
+        status.finished_at_ms = Some(now_ms());
+        status.report_uuid = report_uuid;
+        status.summary = Some(summary.clone());
+        status.error = error;
+    });
+
+    if let Some(status) = NIGHT_BATCH.lock().ok().and_then(|batch| batch.as_ref().map(|batch| batch.status.clone())) {
+        let _ = events.emit("night_batch_summary", &status);
+    }
+    post_notification("Night batch", &summary);
+}
+
+/// Schedule `request` to run at the next local `start_time` ("HH:MM", e.g. "02:00").
+/// Only one night batch is kept; scheduling replaces one that has not started yet.
+#[tauri::command]
+pub fn schedule_night_batch(
+    window: Window,
+    request: BenchmarkSuiteRequest,
+    config: GenerationConfig,
+    start_time: String,
+) -> Result<NightBatchStatus, A2oError> {
+    let at = parse_start_time(&start_time)?;
+    let next = next_start(chrono::Local::now().naive_local(), at);
+    // A start time inside a DST gap (02:30 on the night clocks go forward) moves an hour later
+    let scheduled_for = chrono::Local.from_local_datetime(&next).earliest()
+        .or_else(|| chrono::Local.from_local_datetime(&(next + chrono::Duration::hours(1))).earliest())
+        .ok_or_else(|| A2oError::InvalidInput(format!("Start time {} does not exist today", start_time)))?;
+
+    let mut batch = NIGHT_BATCH.lock().map_err(|_| "Night batch state poisoned".to_string())?;
+    if let Some(existing) = batch.as_ref() {
+        if existing.status.state == NightBatchState::Running {
+            return Err(A2oError::InvalidInput("A night batch is already running".to_string()));
+        }
+        existing.cancel_requested.store(true, Ordering::SeqCst);
+    }
+
+    let status = NightBatchStatus {
+        batch_id: uuid::Uuid::new_v4().to_string(),
+        name: request.name.clone(),
+        start_time: at.format("%H:%M").to_string(),
+        scheduled_for_ms: scheduled_for.timestamp_millis().max(0) as u64,
+        state: NightBatchState::Scheduled,
+        started_at_ms: None,
+        finished_at_ms: None,
+        report_uuid: None,
+        summary: None,
+        error: None,
+    };
+    let cancel_requested = Arc::new(AtomicBool::new(false));
+    *batch = Some(NightBatch { status: status.clone(), cancel_requested: cancel_requested.clone() });
+    println!("🌙 Night batch {} scheduled for {}", status.batch_id, scheduled_for.format("%Y-%m-%d %H:%M"));
+
+    tauri::async_runtime::spawn(run_night_batch(
+        window,
+        status.batch_id.clone(),
+        status.scheduled_for_ms,
+        request,
+        config,
+        cancel_requested,
+    ));
+    Ok(status)
+}
+
+/// The scheduled, running or last finished night batch
+#[tauri::command]
+pub fn get_night_batch() -> Result<Option<NightBatchStatus>, A2oError> {
+    let batch = NIGHT_BATCH.lock().map_err(|_| "Night batch state poisoned".to_string())?;
+    Ok(batch.as_ref().map(|batch| batch.status.clone()))
+}
+
+/// Cancel a scheduled night batch, or stop a running one; the runs it completed are still
+/// saved
+#[tauri::command]
+pub fn cancel_night_batch() -> Result<Option<NightBatchStatus>, A2oError> {
+    let mut batch = NIGHT_BATCH.lock().map_err(|_| "Night batch state poisoned".to_string())?;
+    let Some(batch) = batch.as_mut() else {
+        return Ok(None);
+    };
+    match batch.status.state {
+        NightBatchState::Scheduled => {
+            println!("🌙 Night batch {} cancelled before it started", batch.status.batch_id);
+            batch.cancel_requested.store(true, Ordering::SeqCst);
+            batch.status.state = NightBatchState::Cancelled;
+            batch.status.finished_at_ms = Some(now_ms());
+        }
+        NightBatchState::Running => {
+            println!("🌙 Stopping night batch {}", batch.status.batch_id);
+            batch.cancel_requested.store(true, Ordering::SeqCst);
+            stop_generation(None)?;
+        }
+        _ => {}
+    }
+    Ok(Some(batch.status.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_next_start_is_today_or_tomorrow() {
+        let at = parse_start_time("02:00").unwrap();
+        let evening = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap().and_hms_opt(22, 30, 0).unwrap();
+        assert_eq!(next_start(evening, at), NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(2, 0, 0).unwrap());
+        let night = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(1, 15, 0).unwrap();
+        assert_eq!(next_start(night, at), NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(2, 0, 0).unwrap());
+        let exact = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(2, 0, 0).unwrap();
+        assert_eq!(next_start(exact, at), exact);
+
+        assert_eq!(parse_start_time(" 23:05 ").unwrap(), NaiveTime::from_hms_opt(23, 5, 0).unwrap());
+        assert_eq!(parse_start_time("2am").err().unwrap().code(), "invalid_input");
+        assert_eq!(parse_start_time("25:00").err().unwrap().code(), "invalid_input");
+        assert_eq!(format_elapsed(Duration::from_secs(3 * 3600 + 7 * 60 + 59)), "3h 07m");
+    }
+}
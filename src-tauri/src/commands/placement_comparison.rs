@@ -0,0 +1,260 @@
+// Contains the CPU vs GPU placement comparison command
+
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::commands::benchmark::wait_before_run;
+use crate::commands::generation::execute_generation_turn;
+use crate::events::window_sink_with;
+use crate::hardware::temperature::read_core_temperatures;
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_cache::ModelCache;
+use crate::persistence::database::SessionDatabase;
+use crate::telemetry::cooldown::measure_cooldown_baseline;
+use crate::telemetry::types::{GenerationConfig, GenerationResult, DEFAULT_COOLDOWN_MARGIN_C};
+use crate::error::A2oError;
+
+// More layers than any model has, i.e. offload everything (llama.cpp's `-ngl 999` idiom)
+const FULL_OFFLOAD_LAYERS: u32 = 999;
+const TEMPERATURE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One run of the model with a fixed placement
+#[derive(Debug, Clone, Serialize)]
+pub struct PlacementMeasurement {
+    pub placement: String,   // "cpu" | "gpu"
+    pub n_gpu_layers: u32,
+    pub run_id: String,
+    pub model_load_ms: f64,
+    pub ttft_ms: Option<u64>,
+    pub prefill_tps: Option<f64>,
+    pub avg_tps: Option<f64>,
+    pub output_tokens: usize,
+    pub total_energy_wh: Option<f64>,       // Only when telemetry was enabled
+    pub energy_per_token_wh: Option<f64>,
+    pub max_cpu_temp_c: Option<f64>,        // Peak over the run, polled once a second
+    pub max_gpu_temp_c: Option<f64>,
+    pub finish_reason: String,
+}
+
+impl PlacementMeasurement {
+    fn from_result(placement: &str, n_gpu_layers: u32, run_id: String, result: &GenerationResult, temps: PeakTemperatures) -> Self {
+        Self {
+            placement: placement.to_string(),
+            n_gpu_layers,
+            run_id,
+            model_load_ms: result.model_load_ms,
+            ttft_ms: result.ttft_ms,
+            prefill_tps: (result.prefill_ms > 0.0).then(|| result.input_tokens as f64 / (result.prefill_ms / 1000.0)),
+            avg_tps: result.avg_tps,
+            output_tokens: result.output_tokens,
+            total_energy_wh: result.energy.as_ref().map(|energy| energy.total_energy_wh),
+            energy_per_token_wh: result.energy.as_ref().and_then(|energy| energy.energy_per_token_wh),
+            max_cpu_temp_c: temps.cpu_c,
+            max_gpu_temp_c: temps.gpu_c,
+            finish_reason: result.finish_reason.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlacementComparisonReport {
+    pub model: String,   // "A" / "B"
+    pub model_path: String,
+    pub cpu: Option<PlacementMeasurement>,
+    pub gpu: Option<PlacementMeasurement>,
+    pub gpu_speedup: Option<f64>,              // GPU decode TPS / CPU decode TPS
+    pub gpu_energy_per_token_ratio: Option<f64>,   // GPU / CPU energy per token; below 1 = GPU more efficient
+    pub completed: bool,                       // False when a run was stopped
+}
+
+impl PlacementComparisonReport {
+    fn compare(&mut self) {
+        let ratio = |gpu: Option<f64>, cpu: Option<f64>| Some(gpu? / cpu?).filter(|ratio| ratio.is_finite());
+        let metric = |measurement: &Option<PlacementMeasurement>, get: fn(&PlacementMeasurement) -> Option<f64>| {
+            measurement.as_ref().and_then(get)
+        };
+        self.gpu_speedup = ratio(metric(&self.gpu, |m| m.avg_tps), metric(&self.cpu, |m| m.avg_tps));
+        self.gpu_energy_per_token_ratio = ratio(
+            metric(&self.gpu, |m| m.energy_per_token_wh),
+            metric(&self.cpu, |m| m.energy_per_token_wh),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct PeakTemperatures {
+    cpu_c: Option<f64>,
+    gpu_c: Option<f64>,
+}
+
+// Poll CPU/GPU max temperatures until `done` is set; the telemetry stream goes to the window,
+// so the comparison keeps its own peaks
+fn watch_peak_temperatures(done: Arc<AtomicBool>) -> (Arc<Mutex<PeakTemperatures>>, tokio::task::JoinHandle<()>) {
+    let peaks = Arc::new(Mutex::new(PeakTemperatures::default()));
+    let recorded = peaks.clone();
+    let handle = tokio::spawn(async move {
+        while !done.load(Ordering::Relaxed) {
+            if let Ok(temps) = read_core_temperatures().await {
+                let mut peaks = recorded.lock().unwrap();
+                peaks.cpu_c = Some(peaks.cpu_c.map_or(temps.cpu_temp_max, |max| max.max(temps.cpu_temp_max)));
+                if let Some(gpu) = temps.gpu_temp_max {
+                    peaks.gpu_c = Some(peaks.gpu_c.map_or(gpu, |max| max.max(gpu)));
+                }
+            }
+            tokio::time::sleep(TEMPERATURE_POLL_INTERVAL).await;
+        }
+    });
+    (peaks, handle)
+}
+
+/// Run the targeted model (A or B) twice with the same prompt: fully on the CPU, then fully
+/// offloaded to the GPU, and compare speed, energy and peak temperatures. The model is
+/// reloaded for each placement; between the runs the comparison pauses for `cooldown_secs`
+/// and, with wait_for_cpu_baseline_between_models, waits for the CPU to cool back to its
+/// starting temperature. Stopping a run ends the comparison early.
+#[tauri::command]
+pub async fn run_placement_comparison(
+    window: Window,
+    db: State<'_, SessionDatabase>,
+    model_cache: State<'_, ModelCache>,
+    config: GenerationConfig,
+    cooldown_secs: Option<f64>,
+) -> Result<PlacementComparisonReport, A2oError> {
+    let label = match config.target.as_str() {
+        label @ ("A" | "B") => label.to_string(),
+        "Both" => return Err(A2oError::InvalidInput("A placement comparison runs one model; target A or B".to_string())),
+        other => return Err(A2oError::InvalidInput(format!("Unknown target {}", other))),
+    };
+    let model_config = if label == "A" { &config.model_a } else { &config.model_b };
+    let model_config = model_config.clone()
+        .ok_or_else(|| A2oError::InvalidInput(format!("Model {} is not configured", label)))?;
+    let model_path = resolve_model_path(&model_config.model_path)
+        .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {}", model_config.model_path)))?;
+    let cooldown = match cooldown_secs {
+        Some(secs) if !secs.is_finite() || secs < 0.0 => {
+            return Err(A2oError::InvalidInput(format!("Invalid cooldown of {} s", secs)));
+        }
+        secs => Duration::from_secs_f64(secs.unwrap_or(0.0)),
+    };
+
+    let margin_c = config.wait_for_cpu_baseline_margin_c.unwrap_or(DEFAULT_COOLDOWN_MARGIN_C).clamp(-20.0, 20.0);
+    let unit = db.temperature_unit().unwrap_or_default();
+    let events = window_sink_with(window.clone(), config.event_sink.as_deref());
+    let baseline_c = if config.wait_for_cpu_baseline_between_models.unwrap_or(false) {
+        measure_cooldown_baseline(events.as_ref(), margin_c, unit).await
+    } else {
+        None
+    };
+
+    let mut report = PlacementComparisonReport {
+        model: label.clone(),
+        model_path: model_path.to_string_lossy().into_owned(),
+        cpu: None,
+        gpu: None,
+        gpu_speedup: None,
+        gpu_energy_per_token_ratio: None,
+        completed: true,
+    };
+    for (index, (placement, n_gpu_layers)) in [("cpu", 0), ("gpu", FULL_OFFLOAD_LAYERS)].into_iter().enumerate() {
+        if index > 0 {
+            if !cooldown.is_zero() {
+                tokio::time::sleep(cooldown).await;
+            }
+            if !wait_before_run(events.as_ref(), baseline_c, margin_c, false, unit).await {
+                report.completed = false;
+                break;
+            }
+        }
+        // One resident copy at a time; the cache keys models by their offload setting
+        model_cache.unload(Some(&model_path));
+
+        let mut turn_config = config.clone();
+        turn_config.target = label.clone();
+        turn_config.parallel = Some(false);
+        let mut placed = model_config.clone();
+        placed.n_gpu_layers = Some(n_gpu_layers);
+        if label == "A" { turn_config.model_a = Some(placed) } else { turn_config.model_b = Some(placed) }
+
+        println!("🧭 PLACEMENT COMPARISON: Model {} on {} ({} GPU layer(s))", label, placement, n_gpu_layers);
+        let done = Arc::new(AtomicBool::new(false));
+        let (peaks, watcher) = watch_peak_temperatures(done.clone());
+        let turn = execute_generation_turn(window.clone(), turn_config).await;
+        done.store(true, Ordering::Relaxed);
+        let _ = watcher.await;
+        let turn = match turn {
+            Ok(turn) => turn,
+            Err(A2oError::Cancelled) => {
+                report.completed = false;
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        let result = turn.results.iter()
+            .find(|result| result.model == label)
+            .ok_or_else(|| A2oError::Inference(format!("Model {} returned no result", label)))?;
+        let temps = *peaks.lock().unwrap();
+        let measurement = PlacementMeasurement::from_result(placement, n_gpu_layers, turn.run_id.clone(), result, temps);
+        let stopped = measurement.finish_reason == "user_stop";
+        if placement == "cpu" { report.cpu = Some(measurement) } else { report.gpu = Some(measurement) }
+        if stopped {
+            report.completed = false;
+            break;
+        }
+    }
+    model_cache.unload(Some(&model_path));
+
+    report.compare();
+    println!("🧭 PLACEMENT COMPARISON: Model {} GPU speedup {:?}x, GPU/CPU energy per token {:?}",
+             label, report.gpu_speedup, report.gpu_energy_per_token_ratio);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(placement: &str, avg_tps: Option<f64>, energy_per_token_wh: Option<f64>) -> PlacementMeasurement {
+        PlacementMeasurement {
+            placement: placement.to_string(),
+            n_gpu_layers: if placement == "gpu" { FULL_OFFLOAD_LAYERS } else { 0 },
+            run_id: String::new(),
+            model_load_ms: 900.0,
+            ttft_ms: Some(200),
+            prefill_tps: Some(150.0),
+            avg_tps,
+            output_tokens: 128,
+            total_energy_wh: None,
+            energy_per_token_wh,
+            max_cpu_temp_c: Some(70.0),
+            max_gpu_temp_c: None,
+            finish_reason: "eog".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_reports_gpu_over_cpu_ratios() {
+        let mut report = PlacementComparisonReport {
+            model: "A".to_string(),
+            model_path: "/models/a.gguf".to_string(),
+            cpu: Some(measurement("cpu", Some(10.0), Some(0.004))),
+            gpu: Some(measurement("gpu", Some(45.0), Some(0.001))),
+            gpu_speedup: None,
+            gpu_energy_per_token_ratio: None,
+            completed: true,
+        };
+        report.compare();
+        assert_eq!(report.gpu_speedup, Some(4.5));
+        assert_eq!(report.gpu_energy_per_token_ratio, Some(0.25));
+
+        // No telemetry, a zero-TPS CPU run, or a missing run leaves the ratios unset
+        report.cpu = Some(measurement("cpu", Some(0.0), None));
+        report.compare();
+        assert_eq!((report.gpu_speedup, report.gpu_energy_per_token_ratio), (None, None));
+        report.gpu = None;
+        report.compare();
+        assert_eq!(report.gpu_speedup, None);
+    }
+}
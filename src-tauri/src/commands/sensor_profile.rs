@@ -0,0 +1,134 @@
+// Contains export_sensor_profile Tauri command (sensor descriptions for contributing
+// sensor-mapping profiles for new Mac models)
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::hardware::environment::macos_version;
+use crate::hardware::read_temperature_readings;
+use crate::hardware::sensor_profile::{
+    active_sensor_profile, categorize_sensor, SensorCategory, SensorProfile, SENSOR_PROFILE_SCHEMA,
+};
+use crate::error::A2oError;
+
+/// Identifies the sensor export layout
+pub const SENSOR_EXPORT_SCHEMA: &str = "apples2oranges.sensor-export/v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredSensor {
+    pub name: String,
+    pub temperature_c: f64,
+    pub category: SensorCategory,
+    pub from_profile: bool,     // false: decided by the built-in heuristics
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorProfileExport {
+    pub schema: String,
+    pub app_version: String,
+    pub hardware: HardwareSummary,
+    pub os_version: Option<String>,
+    pub active_profile: Option<String>,
+    pub sensors: Vec<DiscoveredSensor>,
+    pub suggested_profile: SensorProfile,   // Starting point for sensor_profiles/<chip>.json
+}
+
+/// Describe every discovered temperature sensor and the chip, as pretty JSON to attach
+/// to a sensor profile contribution
+#[tauri::command]
+pub async fn export_sensor_profile() -> Result<String, A2oError> {
+    let readings = tauri::async_runtime::spawn_blocking(read_temperature_readings)
+        .await
+        .map_err(|e| format!("Sensor read task failed: {}", e))?
+        .map_err(A2oError::SensorUnavailable)?;
+    let export = build_sensor_export(&readings, hardware_summary(), macos_version(), active_sensor_profile());
+    println!("🗺️  Exported {} sensors for {}", export.sensors.len(), export.hardware.chip);
+    Ok(serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize sensor export: {}", e))?)
+}
+
+pub fn build_sensor_export(
+    readings: &[(String, f64)],
+    hardware: HardwareSummary,
+    os_version: Option<String>,
+    profile: Option<&SensorProfile>,
+) -> SensorProfileExport {
+    let sensors: Vec<DiscoveredSensor> = readings.iter()
+        .map(|(name, temperature_c)| {
+            let (category, from_profile) = categorize_sensor(name, profile);
+            DiscoveredSensor { name: name.clone(), temperature_c: *temperature_c, category, from_profile }
+        })
+        .collect();
+
+    let mut suggested_profile = SensorProfile {
+        schema: SENSOR_PROFILE_SCHEMA.to_string(),
+        name: hardware.chip.clone(),
+        chips: vec![hardware.chip.clone()],
+        ..Default::default()
+    };
+    for sensor in &sensors {
+        let patterns = match sensor.category {
+            SensorCategory::PCore => &mut suggested_profile.p_cores,
+            SensorCategory::ECore => &mut suggested_profile.e_cores,
+            SensorCategory::Gpu => &mut suggested_profile.gpu,
+            SensorCategory::Battery => &mut suggested_profile.battery,
+            SensorCategory::Ignore => &mut suggested_profile.ignore,
+            SensorCategory::Other => continue,
+        };
+        let pattern = sensor_name_pattern(&sensor.name);
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+
+    SensorProfileExport {
+        schema: SENSOR_EXPORT_SCHEMA.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        hardware,
+        os_version,
+        active_profile: profile.map(|profile| profile.name.clone()),
+        sensors,
+        suggested_profile,
+    }
+}
+
+// "pACC MTR Temp Sensor3" -> "pACC MTR Temp Sensor", so one pattern covers every instance
+fn sensor_name_pattern(name: &str) -> String {
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if trimmed.is_empty() { name } else { trimmed }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_lists_sensors_and_suggests_a_profile() {
+        let readings = vec![
+            ("pACC MTR Temp Sensor0".to_string(), 61.0),
+            ("pACC MTR Temp Sensor1".to_string(), 63.0),
+            ("GPU MTR Temp Sensor4".to_string(), 55.0),
+            ("PMU tdie2".to_string(), 58.0),
+        ];
+        let hardware = HardwareSummary {
+            chip: "M2 Pro".to_string(),
+            total_cores: 12,
+            p_cores: 8,
+            e_cores: 4,
+            memory_gb: Some(32.0),
+            os: "macos aarch64".to_string(),
+        };
+        let export = build_sensor_export(&readings, hardware, Some("14.6.1".to_string()), None);
+        assert_eq!(export.sensors.len(), 4);
+        assert_eq!(export.sensors[3].category, SensorCategory::Other);
+        assert!(export.active_profile.is_none());
+
+        let suggested = &export.suggested_profile;
+        assert_eq!(suggested.chips, vec!["M2 Pro".to_string()]);
+        assert_eq!(suggested.p_cores, vec!["pACC MTR Temp Sensor".to_string()]);
+        assert_eq!(suggested.gpu, vec!["GPU MTR Temp Sensor".to_string()]);
+        // Unmapped sensors are left for the contributor to assign
+        assert!(suggested.e_cores.is_empty());
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["sensors"][0]["category"], "p_core");
+    }
+}
@@ -0,0 +1,188 @@
+// Contains the cold-start vs warm-start comparison command
+
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::commands::generation::execute_generation_turn;
+use crate::inference::generation::resolve_model_path;
+use crate::inference::model_cache::ModelCache;
+use crate::inference::page_cache::evict_from_page_cache;
+use crate::telemetry::types::{GenerationConfig, GenerationResult};
+use crate::error::A2oError;
+
+/// Start-up timings of one run
+#[derive(Debug, Clone, Serialize)]
+pub struct StartMeasurement {
+    pub model_load_ms: f64,
+    pub model_reused: bool,
+    pub ttft_ms: Option<u64>,
+    pub time_to_first_token_ms: Option<f64>,   // Load plus TTFT: what the user waits for
+    pub prefill_ms: f64,
+    pub avg_tps: Option<f64>,
+    pub load_delta_gb: f64,
+    pub finish_reason: String,
+}
+
+impl StartMeasurement {
+    fn from_result(result: &GenerationResult) -> Self {
+        Self {
+            model_load_ms: result.model_load_ms,
+            model_reused: result.model_reused,
+            ttft_ms: result.ttft_ms,
+            time_to_first_token_ms: result.ttft_ms.map(|ttft| result.model_load_ms + ttft as f64),
+            prefill_ms: result.prefill_ms,
+            avg_tps: result.avg_tps,
+            load_delta_gb: result.memory.load_delta_gb,
+            finish_reason: result.finish_reason.clone(),
+        }
+    }
+}
+
+/// Cold and warm runs of one model, with cold minus warm deltas
+#[derive(Debug, Clone, Serialize)]
+pub struct StartComparison {
+    pub model: String,   // "A" / "B"
+    pub model_path: String,
+    pub page_cache_evicted: bool,   // False when the file could not be dropped from the OS cache
+    pub cold: StartMeasurement,
+    pub warm: StartMeasurement,
+    pub model_load_delta_ms: f64,
+    pub ttft_delta_ms: Option<f64>,
+    pub time_to_first_token_delta_ms: Option<f64>,
+    pub avg_tps_delta: Option<f64>,
+}
+
+impl StartComparison {
+    fn new(model: &str, model_path: String, page_cache_evicted: bool, cold: StartMeasurement, warm: StartMeasurement) -> Self {
+        let delta = |cold: Option<f64>, warm: Option<f64>| Some(cold? - warm?);
+        Self {
+            model: model.to_string(),
+            model_path,
+            page_cache_evicted,
+            model_load_delta_ms: cold.model_load_ms - warm.model_load_ms,
+            ttft_delta_ms: delta(cold.ttft_ms.map(|ttft| ttft as f64), warm.ttft_ms.map(|ttft| ttft as f64)),
+            time_to_first_token_delta_ms: delta(cold.time_to_first_token_ms, warm.time_to_first_token_ms),
+            avg_tps_delta: delta(cold.avg_tps, warm.avg_tps),
+            cold,
+            warm,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartComparisonReport {
+    pub run_ids: Vec<String>,
+    pub models: Vec<StartComparison>,   // In run order
+    pub completed: bool,                // False when a run was stopped
+}
+
+fn model_result(results: Vec<GenerationResult>, label: &str) -> Result<GenerationResult, A2oError> {
+    results.into_iter()
+        .find(|result| result.model == label)
+        .ok_or_else(|| A2oError::Inference(format!("Model {} returned no result", label)))
+}
+
+/// For each targeted model: unload it and drop its file from the page cache, run the turn
+/// (cold start), then repeat it at once with the model resident (warm start). Models run
+/// one after the other, never in parallel; stopping a run ends the comparison early.
+#[tauri::command]
+pub async fn run_start_comparison(
+    window: Window,
+    model_cache: State<'_, ModelCache>,
+    config: GenerationConfig,
+) -> Result<StartComparisonReport, A2oError> {
+    let labels: Vec<&str> = match config.target.as_str() {
+        "A" => vec!["A"],
+        "B" => vec!["B"],
+        "Both" => vec!["A", "B"],
+        other => return Err(A2oError::InvalidInput(format!("Unknown target {}", other))),
+    };
+
+    let mut report = StartComparisonReport { run_ids: Vec::new(), models: Vec::new(), completed: true };
+    for label in labels {
+        let model_config = if label == "A" { &config.model_a } else { &config.model_b };
+        let model_config = model_config.as_ref()
+            .ok_or_else(|| A2oError::InvalidInput(format!("Model {} is not configured", label)))?;
+        let model_path = resolve_model_path(&model_config.model_path)
+            .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {}", model_config.model_path)))?;
+
+        // Cold: nothing of the model left in the app or in the OS file cache
+        for unloaded in model_cache.unload(Some(&model_path)) {
+            println!("❄️ START COMPARISON: Unloaded resident {} for the cold run", unloaded.model_path);
+        }
+        let page_cache_evicted = match evict_from_page_cache(&model_path) {
+            Ok(()) => true,
+            Err(e) => {
+                println!("⚠️ START COMPARISON: {}; the cold run may read from cache", e);
+                false
+            }
+        };
+
+        let mut turn_config = config.clone();
+        turn_config.target = label.to_string();
+        turn_config.parallel = Some(false);
+        let mut runs = Vec::new();
+        for phase in ["cold", "warm"] {
+            println!("{} START COMPARISON: Model {} {} run", if phase == "cold" { "❄️" } else { "🔥" }, label, phase);
+            let turn = match execute_generation_turn(window.clone(), turn_config.clone()).await {
+                Ok(turn) => turn,
+                Err(A2oError::Cancelled) => {
+                    report.completed = false;
+                    return Ok(report);
+                }
+                Err(e) => return Err(e),
+            };
+            report.run_ids.push(turn.run_id);
+            let result = model_result(turn.results, label)?;
+            if result.finish_reason == "user_stop" {
+                report.completed = false;
+                return Ok(report);
+            }
+            runs.push(StartMeasurement::from_result(&result));
+        }
+
+        let warm = runs.pop().unwrap();
+        let cold = runs.pop().unwrap();
+        let comparison = StartComparison::new(label, model_path.to_string_lossy().into_owned(), page_cache_evicted, cold, warm);
+        println!("⏱️ START COMPARISON: Model {} load {:.0} ms cold vs {:.0} ms warm, first token {:?} ms later when cold",
+                 label, comparison.cold.model_load_ms, comparison.warm.model_load_ms, comparison.time_to_first_token_delta_ms);
+        report.models.push(comparison);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(model_load_ms: f64, ttft_ms: Option<u64>, avg_tps: Option<f64>) -> StartMeasurement {
+        StartMeasurement {
+            model_load_ms,
+            model_reused: model_load_ms < 1.0,
+            ttft_ms,
+            time_to_first_token_ms: ttft_ms.map(|ttft| model_load_ms + ttft as f64),
+            prefill_ms: 40.0,
+            avg_tps,
+            load_delta_gb: 4.0,
+            finish_reason: "eog".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_deltas_are_cold_minus_warm() {
+        let comparison = StartComparison::new(
+            "A",
+            "/models/a.gguf".to_string(),
+            true,
+            measurement(2_500.0, Some(300), Some(38.0)),
+            measurement(0.5, Some(120), Some(40.0)),
+        );
+        assert_eq!(comparison.model_load_delta_ms, 2_499.5);
+        assert_eq!(comparison.ttft_delta_ms, Some(180.0));
+        assert_eq!(comparison.time_to_first_token_delta_ms, Some(2_679.5));
+        assert_eq!(comparison.avg_tps_delta, Some(-2.0));
+
+        let no_tokens = StartComparison::new("B", String::new(), false, measurement(900.0, None, None), measurement(0.5, Some(90), None));
+        assert_eq!((no_tokens.ttft_delta_ms, no_tokens.avg_tps_delta), (None, None));
+    }
+}
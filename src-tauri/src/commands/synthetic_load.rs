@@ -0,0 +1,69 @@
+// Contains the synthetic load command
+
+use std::sync::{Arc, atomic::AtomicBool};
+use std::time::Duration;
+use tauri::Window;
+
+use crate::events::TauriWindowSink;
+use crate::telemetry::processor::{ActiveRunGuard, GLOBAL_STOP_SIGNAL};
+use crate::telemetry::synthetic_load::{self, SyntheticLoadProfile, SyntheticLoadReport};
+use crate::telemetry::types::{
+    TelemetryProfile, DEFAULT_TELEMETRY_SAMPLING_HZ, MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ
+};
+use crate::error::A2oError;
+
+const MIN_SYNTHETIC_LOAD_S: f64 = 1.0;
+const MAX_SYNTHETIC_LOAD_S: f64 = 600.0;
+
+/// Run a fixed matrix-multiply workload with no model loaded, sampling telemetry unless
+/// `sampling_hz` is 0, and report throughput next to the sensor readings. Holds the run
+/// guard so no generation can start meanwhile; stop_generation ends the load early.
+#[tauri::command]
+pub async fn run_synthetic_load(
+    window: Window,
+    profile: SyntheticLoadProfile,
+    duration: f64,
+    sampling_hz: Option<f32>,
+    telemetry_profile: Option<TelemetryProfile>,
+) -> Result<SyntheticLoadReport, A2oError> {
+    if !duration.is_finite() || !(MIN_SYNTHETIC_LOAD_S..=MAX_SYNTHETIC_LOAD_S).contains(&duration) {
+        return Err(A2oError::InvalidInput(format!(
+            "Synthetic load duration must be between {} and {} s, got {}",
+            MIN_SYNTHETIC_LOAD_S, MAX_SYNTHETIC_LOAD_S, duration
+        )));
+    }
+    if profile == SyntheticLoadProfile::Gpu {
+        return Err(A2oError::InvalidInput(
+            "GPU synthetic load needs Metal compute, which this build does not include".to_string()
+        ));
+    }
+    let _run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected synthetic load request: run {} in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+
+    let telemetry_profile = telemetry_profile.unwrap_or_default();
+    let sampling_hz = match sampling_hz {
+        Some(hz) if hz <= 0.0 => None,
+        hz => Some(telemetry_profile.clamp_sampling_hz(hz
+            .unwrap_or(DEFAULT_TELEMETRY_SAMPLING_HZ)
+            .clamp(MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ))),
+    };
+
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = Some(stop_signal.clone());
+    }
+    let report = synthetic_load::run_synthetic_load(
+        &TauriWindowSink::new(window),
+        profile,
+        Duration::from_secs_f64(duration),
+        sampling_hz,
+        telemetry_profile,
+        stop_signal,
+    ).await;
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = None;
+    }
+    Ok(report?)
+}
@@ -0,0 +1,38 @@
+// Contains the concurrent throughput benchmark command
+
+use std::sync::{Arc, atomic::AtomicBool};
+use tauri::{State, Window};
+
+use crate::events::window_sink_with;
+use crate::inference::model_cache::ModelCache;
+use crate::inference::throughput::{self, ThroughputBenchmarkRequest, ThroughputReport};
+use crate::telemetry::processor::{ActiveRunGuard, GLOBAL_STOP_SIGNAL};
+use crate::telemetry::types::ModelConfig;
+use crate::error::A2oError;
+
+/// Serve `request` against one model with several prompts decoded concurrently and report
+/// aggregate tokens/sec and package power, for comparing models as servers rather than as a
+/// single chat. Holds the run guard; stop_generation ends the benchmark early.
+#[tauri::command]
+pub async fn run_throughput_benchmark(
+    window: Window,
+    model_cache: State<'_, ModelCache>,
+    config: ModelConfig,
+    request: ThroughputBenchmarkRequest,
+) -> Result<ThroughputReport, A2oError> {
+    let _run_guard = ActiveRunGuard::acquire().map_err(|e| {
+        println!("⚠️ Rejected throughput benchmark request: run {} in progress", e.active_run_id);
+        A2oError::from(e)
+    })?;
+
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = Some(stop_signal.clone());
+    }
+    let events = window_sink_with(window, request.event_sink.as_deref());
+    let report = throughput::run_throughput_benchmark(events, &config, &request, &model_cache, stop_signal).await;
+    if let Ok(mut global_stop) = GLOBAL_STOP_SIGNAL.write() {
+        *global_stop = None;
+    }
+    report
+}
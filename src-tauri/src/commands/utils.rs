@@ -1,15 +1,36 @@
+use std::path::Path;
 use std::sync::atomic::Ordering;
 
 use crate::GLOBAL_STOP_SIGNAL;
+use crate::inference::resume::{checkpoint_from_stream, latest_checkpoint, ResumeCheckpoint};
+use crate::telemetry::processor::{request_model_stop, set_telemetry_paused, PARTIAL_OUTPUTS};
+use crate::telemetry::types::{PartialOutputResponse, TelemetryState};
+use crate::error::A2oError;
 
 #[tauri::command]
 pub fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Stop the current generation. With target "A" or "B" only that model stops and, in
+/// "Both" mode, the other one runs to completion; None or "Both" stops everything.
 #[tauri::command]
-pub fn stop_generation() -> Result<(), String> {
-    println!("🛑 Stop generation command received");
+pub fn stop_generation(target: Option<String>) -> Result<(), A2oError> {
+    println!("🛑 Stop generation command received (target: {})", target.as_deref().unwrap_or("all"));
+    match target.as_deref() {
+        None | Some("Both") => {}
+        Some(label @ ("A" | "B")) => {
+            if request_model_stop(label) {
+                println!("🛑 Stop signal set for Model {}", label);
+            } else {
+                println!("⚠️ No active generation of Model {} to stop", label);
+            }
+            return Ok(());
+        }
+        Some(other) => {
+            return Err(A2oError::InvalidInput(format!("Unknown stop target {}; expected A, B or Both", other)));
+        }
+    }
     
     // Signal the current generation to stop
     if let Ok(stop_signal_guard) = GLOBAL_STOP_SIGNAL.read() {
@@ -22,4 +43,52 @@ pub fn stop_generation() -> Result<(), String> {
     
     println!("⚠️ No active generation to stop");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Text Model `model` has generated so far in run `run_id` (the GenerationTurnResult run id).
+/// Only the latest run's output is kept: once another run starts, earlier run ids are not
+/// found.
+#[tauri::command]
+pub fn get_partial_output(run_id: String, model: String) -> Result<PartialOutputResponse, A2oError> {
+    let partial_guard = PARTIAL_OUTPUTS.read()
+        .map_err(|e| format!("Failed to read partial outputs: {}", e))?;
+
+    match partial_guard.as_ref() {
+        Some(partial) if partial.run_id == run_id => Ok(PartialOutputResponse {
+            run_id,
+            text: partial.outputs.get(&model).cloned().unwrap_or_default(),
+            model,
+            finished: partial.finished,
+        }),
+        _ => Err(A2oError::NotFound(format!("No output available for run {}; only the latest run is kept", run_id))),
+    }
+}
+
+/// Checkpoint of the model's most recent generation in this app session; pass it as
+/// GenerationConfig.resume to continue that generation
+#[tauri::command]
+pub fn get_resume_checkpoint(model: String) -> Result<ResumeCheckpoint, A2oError> {
+    latest_checkpoint(&model)
+        .ok_or_else(|| A2oError::NotFound(format!("No generation of Model {} to resume", model)))
+}
+
+/// Checkpoint rebuilt from an output stream file, to resume a run the app did not finish
+#[tauri::command]
+pub fn load_resume_checkpoint(path: String) -> Result<ResumeCheckpoint, A2oError> {
+    let path = Path::new(&path);
+    if !path.is_file() {
+        return Err(A2oError::NotFound(format!("No output stream at {}", path.display())));
+    }
+    Ok(checkpoint_from_stream(path)?)
+}
+
+/// Pause or resume hardware sampling, e.g. while loading a model or editing the prompt, so
+/// only the part of a run that matters is recorded. The state carries over to later runs;
+/// energy is not integrated across a pause.
+#[tauri::command]
+pub fn set_telemetry_state(paused: bool) -> Result<TelemetryState, A2oError> {
+    let monitoring = set_telemetry_paused(paused);
+    println!("{} Telemetry {} by request{}", if paused { "⏸️" } else { "▶️" },
+             if paused { "paused" } else { "resumed" }, if monitoring { "" } else { " (applies to the next run)" });
+    Ok(TelemetryState { paused, monitoring })
+}
@@ -0,0 +1,357 @@
+// Contains validate_generation_config Tauri command (dry-run checks before a run)
+
+use serde::Serialize;
+
+use crate::{GenerationConfig, ModelConfig, SamplerBuilder};
+use crate::inference::generation::resolve_model_path;
+use crate::inference::speculative::MAX_N_DRAFT;
+use crate::inference::gguf::GgufMetadata;
+use crate::inference::sampler_presets::expand_sampler_preset;
+use crate::inference::context_size::{resolve_context_size, system_memory_bytes, MEMORY_BUDGET_FRACTION};
+use crate::telemetry::heat_soak::HEAT_SOAK_TARGET_RANGE_C;
+use crate::telemetry::types::{
+    ContextSize, TelemetryProfile, MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ, MINIMAL_PROFILE_MAX_SAMPLING_HZ,
+    MIN_ROLLING_TPS_WINDOW_SECS, MAX_ROLLING_TPS_WINDOW_SECS
+};
+use crate::error::A2oError;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub severity: String,       // "error" | "warning"
+    pub model: Option<String>,  // "A" / "B", None for run-level issues
+    pub field: Option<String>,  // Config field the issue refers to, if any
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelMemoryEstimate {
+    pub model: String,
+    pub n_ctx: u32,                     // Resolved context size the estimate is based on
+    pub model_file_bytes: u64,
+    pub kv_cache_bytes: Option<u64>,    // None when GGUF metadata lacks shape info
+    pub estimated_total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigValidationReport {
+    pub valid: bool,            // False when any error-severity issue is present
+    pub issues: Vec<ValidationIssue>,
+    pub memory_estimates: Vec<ModelMemoryEstimate>,
+    pub system_memory_bytes: Option<u64>,
+}
+
+impl ValidationIssue {
+    fn error(model: Option<&str>, field: Option<&str>, message: String) -> Self {
+        Self {
+            severity: "error".to_string(),
+            model: model.map(str::to_string),
+            field: field.map(str::to_string),
+            message,
+        }
+    }
+
+    fn warning(model: Option<&str>, field: Option<&str>, message: String) -> Self {
+        Self {
+            severity: "warning".to_string(),
+            model: model.map(str::to_string),
+            field: field.map(str::to_string),
+            message,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn validate_generation_config(config: GenerationConfig) -> Result<ConfigValidationReport, A2oError> {
+    Ok(build_validation_report(&config, system_memory_bytes()))
+}
+
+/// Run all dry-run checks for a generation config
+pub fn build_validation_report(config: &GenerationConfig, system_memory_bytes: Option<u64>) -> ConfigValidationReport {
+    let mut issues = Vec::new();
+    let mut memory_estimates = Vec::new();
+
+    if config.chat_history.is_empty() {
+        issues.push(ValidationIssue::error(None, Some("chat_history"), "Chat history is empty".to_string()));
+    }
+
+    if let Some(hz) = config.telemetry_sampling_hz {
+        if !(MIN_TELEMETRY_SAMPLING_HZ..=MAX_TELEMETRY_SAMPLING_HZ).contains(&hz) {
+            issues.push(ValidationIssue::warning(None, Some("telemetry_sampling_hz"),
+                format!("Sampling rate {} Hz is outside {}-{} Hz and will be clamped",
+                        hz, MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ)));
+        }
+        if config.telemetry_profile == Some(TelemetryProfile::Minimal) && hz > MINIMAL_PROFILE_MAX_SAMPLING_HZ {
+            issues.push(ValidationIssue::warning(None, Some("telemetry_sampling_hz"),
+                format!("Minimal telemetry profile caps sampling at {} Hz; {} Hz will be reduced",
+                        MINIMAL_PROFILE_MAX_SAMPLING_HZ, hz)));
+        }
+    }
+
+    if let Some(secs) = config.rolling_tps_window_secs {
+        if !(MIN_ROLLING_TPS_WINDOW_SECS..=MAX_ROLLING_TPS_WINDOW_SECS).contains(&secs) {
+            issues.push(ValidationIssue::warning(None, Some("rolling_tps_window_secs"),
+                format!("Rolling TPS window {} s is outside {}-{} s and will be clamped",
+                        secs, MIN_ROLLING_TPS_WINDOW_SECS, MAX_ROLLING_TPS_WINDOW_SECS)));
+        }
+    }
+
+    if let Some(ms) = config.stall_threshold_ms {
+        if ms <= 0.0 {
+            issues.push(ValidationIssue::warning(None, Some("stall_threshold_ms"),
+                format!("Stall threshold {} ms is not positive; every inter-token gap will count as a stall", ms)));
+        }
+    }
+
+    if let Some(target_c) = config.heat_soak_target_c {
+        let (min_c, max_c) = HEAT_SOAK_TARGET_RANGE_C;
+        if !(min_c..=max_c).contains(&target_c) {
+            issues.push(ValidationIssue::error(None, Some("heat_soak_target_c"),
+                format!("Heat soak target {} °C is outside {}-{} °C", target_c, min_c, max_c)));
+        } else if config.wait_for_cpu_baseline_between_models.unwrap_or(false) {
+            issues.push(ValidationIssue::warning(None, Some("heat_soak_target_c"),
+                "Heat soak already normalizes each model's starting temperature; the cooldown wait only adds time".to_string()));
+        }
+    }
+
+    let models: Vec<(&str, Option<&ModelConfig>)> = match config.target.as_str() {
+        "A" => vec![("A", config.model_a.as_ref())],
+        "B" => vec![("B", config.model_b.as_ref())],
+        "Both" => vec![("A", config.model_a.as_ref()), ("B", config.model_b.as_ref())],
+        other => {
+            issues.push(ValidationIssue::error(None, Some("target"), format!("Invalid target: {}", other)));
+            Vec::new()
+        }
+    };
+
+    for (label, model_config) in models {
+        match model_config {
+            Some(model_config) => {
+                validate_model(label, model_config, system_memory_bytes, &mut issues, &mut memory_estimates);
+            }
+            None => {
+                issues.push(ValidationIssue::error(Some(label), None, format!("Model {} configuration missing", label)));
+            }
+        }
+    }
+
+    // A parallel Both run holds both models at once, so they share one budget
+    if config.target == "Both" && config.parallel == Some(true) && memory_estimates.len() == 2 {
+        let combined_bytes = memory_estimates.iter().map(|estimate| estimate.estimated_total_bytes).sum();
+        check_memory(None, Some("parallel"), "Combined estimated memory of both models", combined_bytes, system_memory_bytes, &mut issues);
+    }
+
+    ConfigValidationReport {
+        valid: !issues.iter().any(|issue| issue.severity == "error"),
+        issues,
+        memory_estimates,
+        system_memory_bytes,
+    }
+}
+
+fn validate_model(
+    label: &str,
+    model_config: &ModelConfig,
+    system_memory_bytes: Option<u64>,
+    issues: &mut Vec<ValidationIssue>,
+    memory_estimates: &mut Vec<ModelMemoryEstimate>,
+) {
+    // Check the parameters the run will use: preset values fill in as at inference time
+    let expanded = match expand_sampler_preset(model_config) {
+        Ok(expanded) => expanded,
+        Err(e) => {
+            issues.push(ValidationIssue::error(Some(label), Some("sampler_preset"), e));
+            model_config.clone()
+        }
+    };
+    let model_config = &expanded;
+
+    // Sampling parameter warnings (same checks as at inference time)
+    for warning in SamplerBuilder::validate_config(model_config) {
+        issues.push(ValidationIssue::warning(Some(label), None, warning));
+    }
+
+    if model_config.n_ctx == Some(ContextSize::Fixed(0)) {
+        issues.push(ValidationIssue::error(Some(label), Some("n_ctx"), "Context size must be greater than 0".to_string()));
+    }
+
+    if let Some(draft_path) = model_config.draft_model_path.as_deref().map(str::trim).filter(|path| !path.is_empty()) {
+        if resolve_model_path(draft_path).is_none() {
+            issues.push(ValidationIssue::error(Some(label), Some("draft_model_path"),
+                format!("Draft model file not found at {} or in parent directories", draft_path)));
+        }
+        if model_config.context_shift == Some(true) {
+            issues.push(ValidationIssue::warning(Some(label), Some("draft_model_path"),
+                "Speculative decoding is not supported with context shift; the draft model will be ignored".to_string()));
+        }
+    }
+    if let Some(n_draft) = model_config.n_draft.filter(|n| !(1..=MAX_N_DRAFT).contains(n)) {
+        issues.push(ValidationIssue::warning(Some(label), Some("n_draft"),
+            format!("n_draft {} is outside 1-{} and will be clamped", n_draft, MAX_N_DRAFT)));
+    }
+
+    let model_path = match resolve_model_path(&model_config.model_path) {
+        Some(path) => path,
+        None => {
+            issues.push(ValidationIssue::error(Some(label), Some("model_path"),
+                format!("Model file not found at {} or in parent directories", model_config.model_path)));
+            return;
+        }
+    };
+
+    let model_file_bytes = match std::fs::metadata(&model_path) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        Ok(_) => {
+            issues.push(ValidationIssue::error(Some(label), Some("model_path"),
+                format!("{} is not a file", model_path.display())));
+            return;
+        }
+        Err(e) => {
+            issues.push(ValidationIssue::error(Some(label), Some("model_path"),
+                format!("Failed to read {}: {}", model_path.display(), e)));
+            return;
+        }
+    };
+
+    let resolved_ctx = match resolve_context_size(model_config.n_ctx, &model_path) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            issues.push(ValidationIssue::error(Some(label), Some("n_ctx"), e.to_string()));
+            return;
+        }
+    };
+    if let Some(warning) = resolved_ctx.trained_context_warning {
+        issues.push(ValidationIssue::warning(Some(label), Some("n_ctx"), warning));
+    }
+    let n_ctx = resolved_ctx.n_ctx;
+    let kv_cache_bytes = match GgufMetadata::read_from_file(&model_path) {
+        Ok(metadata) => metadata.estimate_kv_cache_bytes(n_ctx as u64),
+        Err(e) => {
+            issues.push(ValidationIssue::warning(Some(label), Some("model_path"),
+                format!("Could not read GGUF metadata: {}", e)));
+            None
+        }
+    };
+
+    let estimated_total_bytes = model_file_bytes + kv_cache_bytes.unwrap_or(0);
+    check_memory(Some(label), Some("n_ctx"), "Estimated memory", estimated_total_bytes, system_memory_bytes, issues);
+
+    memory_estimates.push(ModelMemoryEstimate {
+        model: label.to_string(),
+        n_ctx,
+        model_file_bytes,
+        kv_cache_bytes,
+        estimated_total_bytes,
+    });
+}
+
+// Error past system memory, warning past the memory budget
+fn check_memory(
+    model: Option<&str>,
+    field: Option<&str>,
+    what: &str,
+    estimated_bytes: u64,
+    system_memory_bytes: Option<u64>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let Some(total_memory) = system_memory_bytes else { return };
+    let gb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    if estimated_bytes > total_memory {
+        issues.push(ValidationIssue::error(model, field,
+            format!("{} {:.1} GB exceeds system memory {:.1} GB",
+                    what, gb(estimated_bytes), gb(total_memory))));
+    } else if estimated_bytes as f64 > total_memory as f64 * MEMORY_BUDGET_FRACTION {
+        issues.push(ValidationIssue::warning(model, field,
+            format!("{} {:.1} GB is over {:.0}% of system memory {:.1} GB",
+                    what, gb(estimated_bytes), MEMORY_BUDGET_FRACTION * 100.0, gb(total_memory))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Message;
+
+    // A stand-in model file of `bytes` bytes; not GGUF, so only its size is estimated
+    fn model_file(name: &str, bytes: usize) -> String {
+        let path = std::env::temp_dir().join(format!("a2o-validation-{}-{}.gguf", std::process::id(), name));
+        std::fs::write(&path, vec![0u8; bytes]).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn model(model_path: &str) -> ModelConfig {
+        ModelConfig { model_path: model_path.to_string(), ..Default::default() }
+    }
+
+    fn config(target: &str, model_a: Option<ModelConfig>, model_b: Option<ModelConfig>) -> GenerationConfig {
+        GenerationConfig {
+            chat_history: vec![Message { role: "user".to_string(), content: "Hi".to_string(), model: None }],
+            target: target.to_string(),
+            model_a,
+            model_b,
+            ..Default::default()
+        }
+    }
+
+    fn errors(report: &ConfigValidationReport) -> Vec<(Option<&str>, Option<&str>)> {
+        report.issues.iter()
+            .filter(|issue| issue.severity == "error")
+            .map(|issue| (issue.model.as_deref(), issue.field.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_model_file_is_an_error() {
+        let report = build_validation_report(&config("A", Some(model("no-such-model.gguf")), None), None);
+        assert!(!report.valid);
+        assert_eq!(errors(&report), vec![(Some("A"), Some("model_path"))]);
+        assert!(report.memory_estimates.is_empty());
+
+        let report = build_validation_report(&config("Both", Some(model(&model_file("present", 16))), None), None);
+        assert_eq!(errors(&report), vec![(Some("B"), None)]);
+    }
+
+    #[test]
+    fn test_zero_context_size_is_an_error() {
+        let mut zero_ctx = model(&model_file("zero-ctx", 16));
+        zero_ctx.n_ctx = Some(ContextSize::Fixed(0));
+        let report = build_validation_report(&config("A", Some(zero_ctx), None), None);
+        assert!(!report.valid);
+        assert_eq!(errors(&report), vec![(Some("A"), Some("n_ctx"))]);
+    }
+
+    #[test]
+    fn test_unknown_sampler_preset_is_an_error() {
+        let mut preset = model(&model_file("preset", 16));
+        preset.sampler_preset = Some("no-such-preset".to_string());
+        let report = build_validation_report(&config("B", None, Some(preset)), None);
+        assert!(!report.valid);
+        assert_eq!(errors(&report), vec![(Some("B"), Some("sampler_preset"))]);
+    }
+
+    #[test]
+    fn test_memory_over_system_memory_is_an_error_and_over_budget_a_warning() {
+        let path = model_file("memory", 1000);
+        let over = build_validation_report(&config("A", Some(model(&path)), None), Some(900));
+        assert_eq!(errors(&over), vec![(Some("A"), Some("n_ctx"))]);
+        assert_eq!(over.memory_estimates[0].estimated_total_bytes, 1000);
+
+        let near = build_validation_report(&config("A", Some(model(&path)), None), Some(1200));
+        assert!(near.valid);
+        assert!(near.issues.iter().any(|issue| issue.severity == "warning" && issue.message.contains("75% of system memory")));
+
+        // Each model fits on its own; a parallel run needs room for both
+        let mut parallel = config("Both", Some(model(&path)), Some(model(&path)));
+        assert!(build_validation_report(&parallel, Some(1800)).valid);
+        parallel.parallel = Some(true);
+        let report = build_validation_report(&parallel, Some(1800));
+        assert_eq!(errors(&report), vec![(None, Some("parallel"))]);
+    }
+
+    #[test]
+    fn test_unknown_target_is_an_error() {
+        let report = build_validation_report(&config("C", Some(model(&model_file("target", 16))), None), None);
+        assert!(!report.valid);
+        assert_eq!(errors(&report), vec![(None, Some("target"))]);
+        assert!(report.memory_estimates.is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+// Crate-wide error type returned by Tauri commands. Serialized as `{ code, message }` (plus
+// variant details) so the frontend can tell failures apart without parsing messages.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::inference::memory_watermark::bytes_to_gb;
+use crate::persistence::disk_space::DiskSpaceWarning;
+use crate::telemetry::processor::RunInProgressError;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum A2oError {
+    #[error("{0}")]
+    ModelNotFound(String),
+    #[error("{0}")]
+    ModelLoad(String),
+    #[error("{0}")]
+    ModelInUse(String),          // Resident in the model cache or used by the active run
+    #[error("{0}")]
+    Tokenization(String),
+    #[error("{0}")]
+    Inference(String),
+    #[error("{0}")]
+    Telemetry(String),
+    #[error("{0}")]
+    SensorUnavailable(String),   // SMC, IOReport or HID sensors could not be opened
+    #[error("Database error: {0}")]
+    Database(String),            // rusqlite::Error is not Clone; jobs keep errors around
+    #[error("{0}")]
+    NotFound(String),            // Unknown session, experiment, conversation, ...
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    Io(String),
+    #[error("Generation cancelled")]
+    Cancelled,
+    #[error("{message}")]
+    RunInProgress { message: String, active_run_id: String },
+    #[error("{message}")]
+    InsufficientDiskSpace { message: String, required_bytes: u64, available_bytes: u64 },
+    #[error("{message}")]
+    ContextOverflow { message: String, model: String, prompt_tokens: usize, n_ctx: u32 },
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type A2oResult<T> = Result<T, A2oError>;
+
+impl A2oError {
+    /// Stable identifier the frontend matches on
+    pub fn code(&self) -> &'static str {
+        match self {
+            A2oError::ModelNotFound(_) => "model_not_found",
+            A2oError::ModelLoad(_) => "model_load_failed",
+            A2oError::ModelInUse(_) => "model_in_use",
+            A2oError::Tokenization(_) => "tokenization_failed",
+            A2oError::Inference(_) => "inference_failed",
+            A2oError::Telemetry(_) => "telemetry_failed",
+            A2oError::SensorUnavailable(_) => "sensor_unavailable",
+            A2oError::Database(_) => "database_error",
+            A2oError::NotFound(_) => "not_found",
+            A2oError::InvalidInput(_) => "invalid_input",
+            A2oError::Io(_) => "io_error",
+            A2oError::Cancelled => "cancelled",
+            A2oError::RunInProgress { .. } => "run_already_in_progress",
+            A2oError::InsufficientDiskSpace { .. } => "insufficient_disk_space",
+            A2oError::ContextOverflow { .. } => "context_overflow",
+            A2oError::Other(_) => "internal",
+        }
+    }
+}
+
+impl From<rusqlite::Error> for A2oError {
+    fn from(e: rusqlite::Error) -> Self {
+        A2oError::Database(e.to_string())
+    }
+}
+
+// Helpers that still report plain messages surface as `internal`
+impl From<String> for A2oError {
+    fn from(message: String) -> Self {
+        A2oError::Other(message)
+    }
+}
+
+impl From<RunInProgressError> for A2oError {
+    fn from(e: RunInProgressError) -> Self {
+        A2oError::RunInProgress { message: e.message, active_run_id: e.active_run_id }
+    }
+}
+
+impl From<DiskSpaceWarning> for A2oError {
+    fn from(warning: DiskSpaceWarning) -> Self {
+        A2oError::InsufficientDiskSpace {
+            message: format!("Not enough disk space for {} at {}: {:.2} GB needed, {:.2} GB free",
+                             warning.operation, warning.path,
+                             bytes_to_gb(warning.required_bytes), bytes_to_gb(warning.available_bytes)),
+            required_bytes: warning.required_bytes,
+            available_bytes: warning.available_bytes,
+        }
+    }
+}
+
+impl Serialize for A2oError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("A2oError", 5)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            A2oError::RunInProgress { active_run_id, .. } => {
+                state.serialize_field("active_run_id", active_run_id)?;
+            }
+            A2oError::InsufficientDiskSpace { required_bytes, available_bytes, .. } => {
+                state.serialize_field("required_bytes", required_bytes)?;
+                state.serialize_field("available_bytes", available_bytes)?;
+            }
+            A2oError::ContextOverflow { model, prompt_tokens, n_ctx, .. } => {
+                state.serialize_field("model", model)?;
+                state.serialize_field("prompt_tokens", prompt_tokens)?;
+                state.serialize_field("n_ctx", n_ctx)?;
+            }
+            _ => {}
+        }
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_errors_serialize_with_codes() {
+        let missing = A2oError::ModelNotFound("Model file not found at /models/a.gguf".to_string());
+        assert_eq!(serde_json::to_value(&missing).unwrap(), json!({
+            "code": "model_not_found",
+            "message": "Model file not found at /models/a.gguf",
+        }));
+        assert_eq!(serde_json::to_value(A2oError::Cancelled).unwrap()["code"], "cancelled");
+
+        let busy = A2oError::from(RunInProgressError {
+            message: "A generation run is already in progress".to_string(),
+            active_run_id: "run-1".to_string(),
+        });
+        let busy = serde_json::to_value(&busy).unwrap();
+        assert_eq!(busy["code"], "run_already_in_progress");
+        assert_eq!(busy["active_run_id"], "run-1");
+
+        let overflow = serde_json::to_value(A2oError::ContextOverflow {
+            message: "Model B prompt is 5000 tokens but n_ctx is 4096".to_string(),
+            model: "B".to_string(),
+            prompt_tokens: 5000,
+            n_ctx: 4096,
+        }).unwrap();
+        assert_eq!(overflow["code"], "context_overflow");
+        assert_eq!((overflow["prompt_tokens"].as_u64(), overflow["n_ctx"].as_u64()), (Some(5000), Some(4096)));
+
+        let db = A2oError::from(rusqlite::Error::QueryReturnedNoRows);
+        assert_eq!(db.code(), "database_error");
+        assert_eq!(A2oError::from("boom".to_string()).code(), "internal");
+    }
+}
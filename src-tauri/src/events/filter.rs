@@ -0,0 +1,193 @@
+// Per-stream event subscriptions: a client can switch event classes off or rate-limit them
+// (configure_event_stream), so minimal UIs and automated consumers are not flooded.
+// Streams are named by the window label ("main") or by an extra sink's spec ("ws:...").
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::events::{EventSink, SharedEventSink};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventClass {
+    Tokens,
+    Telemetry,
+    Debug,
+    PhaseMarkers,
+    Lifecycle,   // Run start/stop, manifests, warnings: always delivered
+}
+
+pub fn classify(event: &str) -> EventClass {
+    match event {
+        "new_token" | "input_tokens" | "output_tokens" | "user_input_tokens" | "system_prompt_tokens"
+        | "generation_progress" => EventClass::Tokens,
+        "telemetry_update" | "memory_usage" | "power_consumption_summary" | "session_energy_summary"
+        | "calibration_sample" => EventClass::Telemetry,
+        "debug_capture_started" | "decode_overhead" | "token_stability" | "context_size_resolved" => EventClass::Debug,
+        "prefill_progress" | "prefill_metrics" | "cooldown_update" | "generation_time" => EventClass::PhaseMarkers,
+        _ => EventClass::Lifecycle,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventClassOptions {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_per_second: Option<f64>,   // Per event name; events over the rate are dropped
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for EventClassOptions {
+    fn default() -> Self {
+        Self { enabled: true, max_per_second: None }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventStreamOptions {
+    #[serde(default)]
+    pub tokens: EventClassOptions,
+    #[serde(default)]
+    pub telemetry: EventClassOptions,
+    #[serde(default)]
+    pub debug: EventClassOptions,
+    #[serde(default)]
+    pub phase_markers: EventClassOptions,
+}
+
+impl EventStreamOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        let classes = [
+            ("tokens", &self.tokens),
+            ("telemetry", &self.telemetry),
+            ("debug", &self.debug),
+            ("phase_markers", &self.phase_markers),
+        ];
+        for (name, options) in classes {
+            if let Some(rate) = options.max_per_second {
+                if !rate.is_finite() || rate <= 0.0 {
+                    return Err(format!("{}.max_per_second must be a positive number, got {}", name, rate));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn class(&self, class: EventClass) -> Option<&EventClassOptions> {
+        match class {
+            EventClass::Tokens => Some(&self.tokens),
+            EventClass::Telemetry => Some(&self.telemetry),
+            EventClass::Debug => Some(&self.debug),
+            EventClass::PhaseMarkers => Some(&self.phase_markers),
+            EventClass::Lifecycle => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref STREAM_OPTIONS: Mutex<HashMap<String, EventStreamOptions>> = Mutex::new(HashMap::new());
+}
+
+/// Store the options of a stream; they apply from the next event on, including mid-run
+pub fn set_stream_options(stream_id: &str, options: EventStreamOptions) {
+    let mut streams = STREAM_OPTIONS.lock().unwrap();
+    if options == EventStreamOptions::default() {
+        streams.remove(stream_id);
+    } else {
+        streams.insert(stream_id.to_string(), options);
+    }
+}
+
+pub fn stream_options(stream_id: &str) -> EventStreamOptions {
+    STREAM_OPTIONS.lock().unwrap().get(stream_id).cloned().unwrap_or_default()
+}
+
+/// Applies a stream's options in front of another sink
+pub struct FilteredSink {
+    inner: SharedEventSink,
+    stream_id: String,
+    last_sent: Mutex<HashMap<String, Instant>>,   // Per event name, for rate limits
+}
+
+impl FilteredSink {
+    pub fn new(inner: SharedEventSink, stream_id: &str) -> Self {
+        Self { inner, stream_id: stream_id.to_string(), last_sent: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl EventSink for FilteredSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        let options = stream_options(&self.stream_id);
+        let Some(class_options) = options.class(classify(event)) else {
+            return self.inner.emit_value(event, payload);
+        };
+        if !class_options.enabled {
+            return Ok(());
+        }
+        // The final token event closes the stream on the client, so it is never dropped
+        let is_final = payload.get("finished").and_then(Value::as_bool) == Some(true);
+        if let (Some(rate), false) = (class_options.max_per_second, is_final) {
+            let interval = Duration::from_secs_f64(1.0 / rate);
+            let now = Instant::now();
+            let mut last_sent = self.last_sent.lock().unwrap();
+            if last_sent.get(event).is_some_and(|&last| now.duration_since(last) < interval) {
+                return Ok(());
+            }
+            last_sent.insert(event.to_string(), now);
+        }
+        self.inner.emit_value(event, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn emit_value(&self, event: &str, _payload: Value) -> Result<(), String> {
+            self.events.lock().unwrap().push(event.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_disabled_and_rate_limited_classes() {
+        let recording = Arc::new(RecordingSink::default());
+        let sink = FilteredSink::new(recording.clone(), "test-stream");
+        set_stream_options("test-stream", serde_json::from_value(serde_json::json!({
+            "telemetry": { "enabled": false },
+            "tokens": { "max_per_second": 0.001 },
+        })).unwrap());
+
+        sink.emit_value("telemetry_update", serde_json::json!({})).unwrap();
+        sink.emit_value("new_token", serde_json::json!({ "token": "a", "finished": false })).unwrap();
+        sink.emit_value("new_token", serde_json::json!({ "token": "b", "finished": false })).unwrap();
+        sink.emit_value("new_token", serde_json::json!({ "token": "", "finished": true })).unwrap();
+        sink.emit_value("generation_progress", serde_json::json!({})).unwrap();
+        sink.emit_value("generation_stopped", serde_json::json!({})).unwrap();
+        sink.emit_value("prefill_progress", serde_json::json!({})).unwrap();
+        assert_eq!(*recording.events.lock().unwrap(),
+                   vec!["new_token", "new_token", "generation_progress", "generation_stopped", "prefill_progress"]);
+
+        set_stream_options("test-stream", EventStreamOptions::default());
+        sink.emit_value("telemetry_update", serde_json::json!({})).unwrap();
+        assert_eq!(recording.events.lock().unwrap().last().unwrap(), "telemetry_update");
+
+        let invalid = EventStreamOptions { debug: EventClassOptions { enabled: true, max_per_second: Some(0.0) }, ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
+}
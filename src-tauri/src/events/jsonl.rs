@@ -0,0 +1,58 @@
+// JSONL file sink: one {"event", "payload", "timestamp_ms"} object per line
+
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::events::{envelope, EventSink};
+
+pub struct JsonlFileSink {
+    writer: Mutex<LineWriter<File>>,   // Flushed per line so consumers can tail the file
+}
+
+impl JsonlFileSink {
+    /// Append to `path`, creating it (and its parent directory) if needed
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create event log directory {}: {}", parent.display(), e))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .map_err(|e| format!("Failed to open event log {}: {}", path.display(), e))?;
+        Ok(Self { writer: Mutex::new(LineWriter::new(file)) })
+    }
+}
+
+impl EventSink for JsonlFileSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        let line = envelope(event, payload).to_string();
+        let mut writer = self.writer.lock().map_err(|_| "Event log writer poisoned".to_string())?;
+        writeln!(writer, "{}", line).map_err(|e| format!("Failed to write event log: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appends_one_event_per_line() {
+        let path = std::env::temp_dir().join(format!("a2o-events-{}/events.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = JsonlFileSink::append(&path).unwrap();
+        sink.emit_value("new_token", serde_json::json!({"token": "a\nb"})).unwrap();
+        sink.emit_value("generation_progress", serde_json::json!({"tokens_generated": 1})).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "new_token");
+        assert_eq!(lines[0]["payload"]["token"], "a\nb");
+        assert_eq!(lines[1]["event"], "generation_progress");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
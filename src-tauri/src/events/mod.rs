@@ -0,0 +1,179 @@
+// Event emission behind the EventSink trait, so inference and telemetry code does not
+// depend on a Tauri Window (CLI, server and test harnesses supply their own sinks)
+
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub mod filter;
+pub mod jsonl;
+pub mod window;
+pub mod websocket;
+
+pub use filter::FilteredSink;
+pub use jsonl::JsonlFileSink;
+pub use window::TauriWindowSink;
+pub use websocket::WebSocketSink;
+
+/// Destination for the named events the frontend listens to ("new_token", "telemetry_update", ...)
+pub trait EventSink: Send + Sync {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String>;
+}
+
+impl dyn EventSink + '_ {
+    /// Serialize `payload` and emit it as `event`
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) -> Result<(), String> {
+        let payload = serde_json::to_value(payload)
+            .map_err(|e| format!("Failed to serialize {} event: {}", event, e))?;
+        self.emit_value(event, payload)
+    }
+}
+
+pub type SharedEventSink = Arc<dyn EventSink>;
+
+/// Discards every event
+pub struct NullSink;
+
+impl EventSink for NullSink {
+    fn emit_value(&self, _event: &str, _payload: Value) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Emits every event to each sink; reports the first failure after trying all of them
+pub struct FanoutSink {
+    sinks: Vec<SharedEventSink>,
+}
+
+impl FanoutSink {
+    pub fn new(sinks: Vec<SharedEventSink>) -> Self {
+        Self { sinks }
+    }
+}
+
+impl EventSink for FanoutSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        let mut result = Ok(());
+        for sink in &self.sinks {
+            if let Err(e) = sink.emit_value(event, payload.clone()) {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+/// Line/message format used by the JSONL and WebSocket sinks
+pub(crate) fn envelope(event: &str, payload: Value) -> Value {
+    serde_json::json!({
+        "event": event,
+        "payload": payload,
+        "timestamp_ms": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    })
+}
+
+lazy_static! {
+    // WebSocket servers outlive a single run so clients stay connected between turns
+    static ref WEBSOCKET_SINKS: Mutex<HashMap<String, SharedEventSink>> = Mutex::new(HashMap::new());
+}
+
+/// Build a sink from a spec string: "null", "jsonl:<path>" (appends) or "ws:<host:port>"
+pub fn sink_from_spec(spec: &str) -> Result<SharedEventSink, String> {
+    let spec = spec.trim();
+    if spec == "null" {
+        return Ok(Arc::new(NullSink));
+    }
+    if let Some(path) = spec.strip_prefix("jsonl:") {
+        return Ok(Arc::new(JsonlFileSink::append(path)?));
+    }
+    if let Some(addr) = spec.strip_prefix("ws:") {
+        let mut servers = WEBSOCKET_SINKS.lock().unwrap();
+        if let Some(sink) = servers.get(addr) {
+            return Ok(sink.clone());
+        }
+        let sink: SharedEventSink = Arc::new(WebSocketSink::bind(addr)?);
+        servers.insert(addr.to_string(), sink.clone());
+        return Ok(sink);
+    }
+    Err(format!("Unknown event sink '{}' (expected null, jsonl:<path> or ws:<host:port>)", spec))
+}
+
+/// The window sink, teed to an extra sink when one is configured. Each goes through the
+/// event stream options of its stream (the window label, or the extra sink's spec).
+/// An invalid extra sink is logged and the run continues with the window only.
+pub fn window_sink_with(window: tauri::Window, extra_spec: Option<&str>) -> SharedEventSink {
+    let label = window.label().to_string();
+    let window_sink: SharedEventSink = Arc::new(FilteredSink::new(Arc::new(TauriWindowSink::new(window)), &label));
+    match extra_spec.map(sink_from_spec) {
+        None => window_sink,
+        Some(Ok(extra)) => {
+            let spec = extra_spec.unwrap_or_default().trim();
+            println!("📡 Teeing events to {}", spec);
+            let extra: SharedEventSink = Arc::new(FilteredSink::new(extra, spec));
+            Arc::new(FanoutSink::new(vec![window_sink, extra]))
+        }
+        Some(Err(e)) => {
+            println!("⚠️ {} - emitting to the window only", e);
+            window_sink
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+            self.events.lock().unwrap().push((event.to_string(), payload));
+            Ok(())
+        }
+    }
+
+    struct FailingSink;
+
+    impl EventSink for FailingSink {
+        fn emit_value(&self, event: &str, _payload: Value) -> Result<(), String> {
+            Err(format!("{} rejected", event))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct TokenLike {
+        token: String,
+        finished: bool,
+    }
+
+    #[test]
+    fn test_fanout_reaches_every_sink_despite_failures() {
+        let recording = Arc::new(RecordingSink::default());
+        let fanout: SharedEventSink = Arc::new(FanoutSink::new(vec![
+            Arc::new(FailingSink),
+            recording.clone(),
+        ]));
+
+        let result = fanout.emit("new_token", TokenLike { token: "hi".to_string(), finished: false });
+        assert_eq!(result, Err("new_token rejected".to_string()));
+        let events = recording.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "new_token");
+        assert_eq!(events[0].1["token"], "hi");
+    }
+
+    #[test]
+    fn test_sink_specs() {
+        assert!(sink_from_spec("null").unwrap().emit("anything", 1).is_ok());
+        assert!(sink_from_spec("kafka:localhost").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// WebSocket sink: broadcasts each event as a JSON text message to every connected client
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::Value;
+use tungstenite::{Message, WebSocket};
+
+use crate::events::{envelope, EventSink};
+
+// A client that cannot take a message within this time is dropped instead of stalling inference
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub struct WebSocketSink {
+    local_addr: std::net::SocketAddr,
+    clients: Arc<Mutex<Vec<WebSocket<TcpStream>>>>,
+}
+
+impl WebSocketSink {
+    /// Listen on `addr` (e.g. "127.0.0.1:8765"); clients are accepted on a background thread
+    pub fn bind(addr: &str) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| format!("Failed to bind event WebSocket on {}: {}", addr, e))?;
+        let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+
+        let accepted = clients.clone();
+        std::thread::Builder::new()
+            .name("event-websocket".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT));
+                    match tungstenite::accept(stream) {
+                        Ok(socket) => accepted.lock().unwrap().push(socket),
+                        Err(e) => println!("⚠️ Event WebSocket handshake failed: {}", e),
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start event WebSocket thread: {}", e))?;
+
+        println!("📡 Event WebSocket listening on ws://{}", local_addr);
+        Ok(Self { local_addr, clients })
+    }
+
+    pub fn local_addr(&self) -> std::net::SocketAddr {
+        self.local_addr
+    }
+}
+
+impl EventSink for WebSocketSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        let text = envelope(event, payload).to_string();
+        let mut clients = self.clients.lock().map_err(|_| "Event WebSocket clients poisoned".to_string())?;
+        // Disconnected or too-slow clients are dropped
+        clients.retain_mut(|client| client.send(Message::text(text.clone())).is_ok());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connected_clients_receive_events() {
+        let sink = WebSocketSink::bind("127.0.0.1:0").unwrap();
+        let (mut client, _) = tungstenite::connect(format!("ws://{}", sink.local_addr())).unwrap();
+
+        // The server registers the client on its accept thread
+        for _ in 0..100 {
+            if !sink.clients.lock().unwrap().is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        sink.emit_value("telemetry_update", serde_json::json!({"cpu_power_watts": 4.5})).unwrap();
+
+        let message: Value = serde_json::from_str(client.read().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(message["event"], "telemetry_update");
+        assert_eq!(message["payload"]["cpu_power_watts"], 4.5);
+    }
+}
@@ -0,0 +1,22 @@
+// Tauri window sink: what the desktop frontend listens to
+
+use serde_json::Value;
+use tauri::{Emitter, Window};
+
+use crate::events::EventSink;
+
+pub struct TauriWindowSink {
+    window: Window,
+}
+
+impl TauriWindowSink {
+    pub fn new(window: Window) -> Self {
+        Self { window }
+    }
+}
+
+impl EventSink for TauriWindowSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        self.window.emit(event, payload).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,260 @@
+// Aggregation of one sampling tick's raw readings into a TelemetryUpdate. Kept apart from
+// the sampling loop so recorded debug captures replay through exactly the same code.
+
+use serde::{Deserialize, Serialize};
+
+use crate::TelemetryUpdate;
+use crate::hardware::macmon::MacmonOutput;
+use crate::hardware::memory::MemoryPressureSample;
+use crate::hardware::rates::TelemetryRates;
+use crate::hardware::temperature::{TemperatureHistory, categorize_temperature_readings};
+use crate::utils::debug::DEBUG_LOGS;
+
+#[allow(unused_macros)]
+macro_rules! dprintln {
+    ($($arg:tt)*) => {
+        if DEBUG_LOGS { println!($($arg)*); }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuUtilizationSample {
+    pub p_cores: Vec<f64>,
+    pub e_cores: Vec<f64>,
+    pub overall: f64,
+}
+
+/// Everything read from the hardware on one tick, before any categorization or merging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawSample {
+    pub timestamp_ms: u64,
+    pub temperature_readings: Option<Result<Vec<(String, f64)>, String>>, // IOHID (sensor, °C); None when not read
+    pub macmon_line: Option<String>,
+    pub cpu_utilization: Option<CpuUtilizationSample>,
+    pub inference_cpu_utilization: Option<f64>,
+    pub gpu_utilization: Option<f64>,
+    #[serde(default)]
+    pub fan_rpm: Option<Vec<f64>>,  // Per-fan speed; captures predating fan telemetry have none
+    #[serde(default)]
+    pub thermal_pressure: Option<String>,  // OS thermal pressure level, e.g. "nominal", "heavy"
+    #[serde(default)]
+    pub memory_pressure: MemoryPressureSample,  // Swap, compressor and VM pressure level
+}
+
+fn parse_macmon_line(line: &str) -> Option<MacmonOutput> {
+    match serde_json::from_str::<MacmonOutput>(line) {
+        Ok(data) => {
+dprintln!("   ✅ Macmon data parsed successfully:");
+dprintln!("      CPU power: {:?} W", data.cpu_power);
+dprintln!("      GPU power: {:?} W", data.gpu_power);
+dprintln!("      ANE power: {:?} W", data.ane_power);
+dprintln!("      CPU temp: {:?}°C", data.temp.as_ref().and_then(|t| t.cpu_temp_avg));
+dprintln!("      GPU temp: {:?}°C", data.temp.as_ref().and_then(|t| t.gpu_temp_avg));
+dprintln!("      CPU freq: {:?} MHz", data.pcpu_usage.as_ref().map(|(freq, _)| freq));
+dprintln!("      GPU freq: {:?} MHz", data.gpu_usage.as_ref().map(|(freq, _)| freq));
+dprintln!("      RAM usage: {:?} bytes", data.memory.as_ref().and_then(|m| m.ram_usage));
+            Some(data)
+        }
+        Err(e) => {
+dprintln!("   ❌ Failed to parse macmon JSON: {}", e);
+dprintln!("      Raw line was: {}", line);
+            None
+        }
+    }
+}
+
+/// Combine SMC/IOHID temperatures, macmon power/frequency and utilization into one update.
+/// `temp_history` carries the thermal trend and `rates` the rate-of-change windows across ticks.
+pub fn aggregate_sample(raw: &RawSample, temp_history: &mut TemperatureHistory, rates: &mut TelemetryRates) -> TelemetryUpdate {
+    let timestamp = raw.timestamp_ms;
+    let core_temp_result = raw.temperature_readings.as_ref()
+        .map(|readings| readings.clone().and_then(|readings| categorize_temperature_readings(&readings)));
+    let macmon_data = raw.macmon_line.as_deref().and_then(parse_macmon_line);
+
+    let (p_core_utils, e_core_utils, overall_util) = match &raw.cpu_utilization {
+        Some(cpu) => (Some(cpu.p_cores.clone()), Some(cpu.e_cores.clone()), Some(cpu.overall)),
+        None => (None, None, None),
+    };
+    let inference_cpu_util = raw.inference_cpu_utilization;
+    let inference_cpu_share = match (inference_cpu_util, overall_util) {
+        (Some(inference), Some(overall)) if overall > 0.0 => Some((inference / overall * 100.0).min(100.0)),
+        _ => None,
+    };
+    let gpu_util = raw.gpu_utilization;
+    let fan_rpm = raw.fan_rpm.clone();
+    let thermal_pressure = raw.thermal_pressure.clone();
+    let memory = raw.memory_pressure.clone();
+
+dprintln!("🔍 TELEMETRY AGGREGATION: Combining SMC and macmon data...");
+    let mut telemetry = match core_temp_result {
+        Some(Ok(mut core_temps)) => {
+dprintln!("   ✅ Core temperature data available from SMC");
+            // Update thermal trend from history
+            temp_history.add_reading(timestamp, core_temps.cpu_temp_avg);
+            core_temps.thermal_trend = temp_history.get_trend(10000); // 10 second window
+            
+dprintln!("🔍 FINAL TELEMETRY VALUES:");
+            
+            let cpu_power = macmon_data.as_ref().and_then(|d| d.cpu_power);
+            let gpu_power = macmon_data.as_ref().and_then(|d| d.gpu_power);
+            let ane_power = macmon_data.as_ref().and_then(|d| d.ane_power);
+dprintln!("   Power: CPU={:?}W, GPU={:?}W, ANE={:?}W", cpu_power, gpu_power, ane_power);
+            
+            let legacy_cpu_temp = Some(core_temps.cpu_temp_avg);
+            let combined_gpu_temp = core_temps.gpu_temp_avg.or_else(|| 
+                macmon_data.as_ref()
+                    .and_then(|d| d.temp.as_ref())
+                    .and_then(|t| t.gpu_temp_avg)
+            );
+dprintln!("   Legacy temps: CPU={:?}°C, GPU={:?}°C", legacy_cpu_temp, combined_gpu_temp);
+            
+            let cpu_freq = macmon_data.as_ref()
+                .and_then(|d| d.pcpu_usage.as_ref())
+                .map(|(freq, _)| *freq);
+            let gpu_freq = macmon_data.as_ref()
+                .and_then(|d| d.gpu_usage.as_ref())
+                .map(|(freq, _)| *freq);
+dprintln!("   Frequencies: CPU={:?}MHz, GPU={:?}MHz", cpu_freq, gpu_freq);
+            
+            let ram_usage = macmon_data.as_ref()
+                .and_then(|d| d.memory.as_ref())
+                .and_then(|m| m.ram_usage)
+                .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0));
+dprintln!("   RAM usage: {:?}GB", ram_usage);
+            
+dprintln!("   Enhanced temps: CPU_avg={:?}°C, CPU_max={:?}°C, GPU_avg={:?}°C, GPU_max={:?}°C, Battery_avg={:?}°C", 
+                     Some(core_temps.cpu_temp_avg), Some(core_temps.cpu_temp_max), 
+                     core_temps.gpu_temp_avg, core_temps.gpu_temp_max, core_temps.battery_temp_avg);
+            
+dprintln!("   Core arrays: P_cores={}, E_cores={}, GPU_clusters={}", 
+                     core_temps.p_cores.len(), core_temps.e_cores.len(), core_temps.gpu_temps.len());
+            
+dprintln!("   CPU utilization: P_cores={:?}, E_cores={:?}, Overall={:?}%", 
+                     p_core_utils.as_ref().map(|u| u.len()), e_core_utils.as_ref().map(|u| u.len()), overall_util);
+            
+            TelemetryUpdate {
+                timestamp_ms: timestamp,
+                cpu_power_watts: cpu_power,
+                gpu_power_watts: gpu_power,
+                ane_power_watts: ane_power,
+                cpu_temp_celsius: legacy_cpu_temp, // Legacy compatibility
+                gpu_temp_celsius: combined_gpu_temp,
+                cpu_freq_mhz: cpu_freq,
+                gpu_freq_mhz: gpu_freq,
+                ram_usage_gb: ram_usage,
+                thermal_pressure: thermal_pressure.clone(),
+                swap_usage_gb: memory.swap_usage_gb,
+                compressed_memory_gb: memory.compressed_memory_gb,
+                memory_pressure_level: memory.memory_pressure_level.clone(),
+                ttft_ms: None,
+                current_tps: None,
+                instantaneous_tps: None,
+                rolling_tps: None,
+                generation_time_ms: None,
+                model: None,
+                // Enhanced temperature data
+                cpu_temp_avg: Some(core_temps.cpu_temp_avg),
+                cpu_temp_max: Some(core_temps.cpu_temp_max),
+                cpu_p_core_temps: Some(core_temps.p_cores.clone()),
+                cpu_e_core_temps: Some(core_temps.e_cores.clone()),
+                gpu_temp_avg: core_temps.gpu_temp_avg,
+                gpu_temp_max: core_temps.gpu_temp_max,
+                gpu_cluster_temps: Some(core_temps.gpu_temps.clone()),
+                battery_temp_avg: core_temps.battery_temp_avg,
+                // CPU utilization data
+                cpu_p_core_utilization: p_core_utils.clone(),
+                cpu_e_core_utilization: e_core_utils.clone(),
+                cpu_overall_utilization: overall_util,
+                inference_cpu_utilization: inference_cpu_util,
+                inference_cpu_share,
+                gpu_utilization: gpu_util,
+                fan_rpm: fan_rpm.clone(),
+                core_temperatures: Some(core_temps),
+                // Energy fields (initialized as None, will be filled by PowerCalculator)
+                total_energy_wh: None,
+                cpu_energy_wh: None,
+                gpu_energy_wh: None,
+                ane_energy_wh: None,
+                energy_rate_wh_per_token: None,
+                total_energy_lower_wh: None,
+                total_energy_upper_wh: None,
+                // Rates (filled below)
+                cpu_temp_rate_c_per_s: None,
+                gpu_temp_rate_c_per_s: None,
+                battery_temp_rate_c_per_s: None,
+                package_power_rate_w_per_s: None,
+            }
+        }
+        core_temp_result => {
+            if let Some(Err(e)) = core_temp_result {
+                println!("❌ SMC temperature read failed: {}", e);
+            }
+            // Fallback to macmon-only data (also the minimal profile's normal path)
+            TelemetryUpdate {
+                timestamp_ms: timestamp,
+                cpu_power_watts: macmon_data.as_ref().and_then(|d| d.cpu_power),
+                gpu_power_watts: macmon_data.as_ref().and_then(|d| d.gpu_power),
+                ane_power_watts: macmon_data.as_ref().and_then(|d| d.ane_power),
+                cpu_temp_celsius: macmon_data.as_ref()
+                    .and_then(|d| d.temp.as_ref())
+                    .and_then(|t| t.cpu_temp_avg),
+                gpu_temp_celsius: macmon_data.as_ref()
+                    .and_then(|d| d.temp.as_ref())
+                    .and_then(|t| t.gpu_temp_avg),
+                cpu_freq_mhz: macmon_data.as_ref()
+                    .and_then(|d| d.pcpu_usage.as_ref())
+                    .map(|(freq, _)| *freq),
+                gpu_freq_mhz: macmon_data.as_ref()
+                    .and_then(|d| d.gpu_usage.as_ref())
+                    .map(|(freq, _)| *freq),
+                ram_usage_gb: macmon_data.as_ref()
+                    .and_then(|d| d.memory.as_ref())
+                    .and_then(|m| m.ram_usage)
+                    .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0)),
+                thermal_pressure: thermal_pressure.clone(),
+                swap_usage_gb: memory.swap_usage_gb,
+                compressed_memory_gb: memory.compressed_memory_gb,
+                memory_pressure_level: memory.memory_pressure_level.clone(),
+                ttft_ms: None,
+                current_tps: None,
+                instantaneous_tps: None,
+                rolling_tps: None,
+                generation_time_ms: None,
+                model: None,
+                // Enhanced temperature data (unavailable in fallback)
+                cpu_temp_avg: macmon_data.as_ref().and_then(|d| d.temp.as_ref()).and_then(|t| t.cpu_temp_avg),
+                cpu_temp_max: None,
+                cpu_p_core_temps: None,
+                cpu_e_core_temps: None,
+                gpu_temp_avg: macmon_data.as_ref().and_then(|d| d.temp.as_ref()).and_then(|t| t.gpu_temp_avg),
+                gpu_temp_max: None,
+                gpu_cluster_temps: None,
+                battery_temp_avg: None,
+                // CPU utilization data (use fallback data)
+                cpu_p_core_utilization: p_core_utils.clone(),
+                cpu_e_core_utilization: e_core_utils.clone(),
+                cpu_overall_utilization: overall_util,
+                inference_cpu_utilization: inference_cpu_util,
+                inference_cpu_share,
+                gpu_utilization: gpu_util,
+                fan_rpm: fan_rpm.clone(),
+                core_temperatures: None,
+                // Energy fields (initialized as None, will be filled by PowerCalculator)
+                total_energy_wh: None,
+                cpu_energy_wh: None,
+                gpu_energy_wh: None,
+                ane_energy_wh: None,
+                energy_rate_wh_per_token: None,
+                total_energy_lower_wh: None,
+                total_energy_upper_wh: None,
+                // Rates (filled below)
+                cpu_temp_rate_c_per_s: None,
+                gpu_temp_rate_c_per_s: None,
+                battery_temp_rate_c_per_s: None,
+                package_power_rate_w_per_s: None,
+            }
+        }
+    };
+    rates.apply(&mut telemetry);
+    telemetry
+}
@@ -0,0 +1,54 @@
+// llama.cpp backend build info: binding versions (which pin the llama.cpp commit), the
+// ggml backends registered at runtime and the compile-time CPU features. Recorded with each
+// session so performance changes across app updates can be attributed to the engine.
+
+use std::ffi::CStr;
+
+use llama_cpp_sys_2::{ggml_backend_reg_count, ggml_backend_reg_get, ggml_backend_reg_name};
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::capabilities::platform_capabilities;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendInfo {
+    pub app_version: String,
+    pub build_profile: String,              // "release" or "debug"; debug builds are far slower
+    pub llama_cpp_2_version: String,        // Locked binding versions from Cargo.lock
+    pub llama_cpp_sys_2_version: String,    // Each release vendors one llama.cpp commit
+    pub backends: Vec<String>,              // ggml backends registered at runtime, e.g. "Metal", "BLAS", "CPU"
+    pub compile_features: Vec<String>,      // Enabled features from llama_print_system_info
+}
+
+lazy_static::lazy_static! {
+    static ref BACKEND_INFO: BackendInfo = probe_backend_info();
+}
+
+/// Build info of the linked llama.cpp, probed once
+pub fn backend_info() -> &'static BackendInfo {
+    &BACKEND_INFO
+}
+
+fn probe_backend_info() -> BackendInfo {
+    BackendInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        build_profile: env!("APP_BUILD_PROFILE").to_string(),
+        llama_cpp_2_version: env!("LLAMA_CPP_2_VERSION").to_string(),
+        llama_cpp_sys_2_version: env!("LLAMA_CPP_SYS_2_VERSION").to_string(),
+        backends: registered_ggml_backends(),
+        compile_features: platform_capabilities().llama_features.clone(),
+    }
+}
+
+fn registered_ggml_backends() -> Vec<String> {
+    let count = unsafe { ggml_backend_reg_count() };
+    (0..count)
+        .filter_map(|index| {
+            let reg = unsafe { ggml_backend_reg_get(index) };
+            if reg.is_null() {
+                return None;
+            }
+            let name = unsafe { ggml_backend_reg_name(reg) };
+            (!name.is_null()).then(|| unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned())
+        })
+        .collect()
+}
@@ -0,0 +1,146 @@
+// Startup capability probe: binary architecture, Rosetta translation, and what the linked
+// llama.cpp build supports (Metal, NEON, Accelerate). Any of these missing makes results
+// wildly unrepresentative of the hardware, so they are surfaced and stamped onto sessions.
+
+use std::ffi::CStr;
+
+use llama_cpp_sys_2::{llama_print_system_info, llama_supports_gpu_offload};
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::cpu_monitor::sysctl_u32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformCapabilities {
+    pub binary_arch: String,                // Architecture this build was compiled for
+    pub apple_silicon_host: Option<bool>,   // hw.optional.arm64; None when unknown
+    pub rosetta_translated: Option<bool>,   // sysctl.proc_translated; None off macOS
+    pub metal_available: bool,              // llama.cpp can offload layers to the GPU via Metal
+    pub llama_features: Vec<String>,        // Enabled features from llama_print_system_info
+    pub llama_system_info: String,
+    pub warnings: Vec<String>,              // Empty when results are representative
+}
+
+lazy_static::lazy_static! {
+    static ref PLATFORM_CAPABILITIES: PlatformCapabilities = probe_platform_capabilities();
+}
+
+/// Capabilities of this process, probed once
+pub fn platform_capabilities() -> &'static PlatformCapabilities {
+    &PLATFORM_CAPABILITIES
+}
+
+fn probe_platform_capabilities() -> PlatformCapabilities {
+    let info_ptr = unsafe { llama_print_system_info() };
+    let llama_system_info = if info_ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(info_ptr) }.to_string_lossy().trim().to_string()
+    };
+    // Metal is the only GPU backend llama.cpp builds on macOS
+    let metal_available = cfg!(target_os = "macos") && unsafe { llama_supports_gpu_offload() };
+
+    let mut capabilities = PlatformCapabilities {
+        binary_arch: std::env::consts::ARCH.to_string(),
+        apple_silicon_host: sysctl_u32("hw.optional.arm64").map(|value| value == 1),
+        // Missing on Intel Macs, which cannot translate; 0 when running natively
+        rosetta_translated: cfg!(target_os = "macos").then(|| sysctl_u32("sysctl.proc_translated") == Some(1)),
+        metal_available,
+        llama_features: parse_llama_system_info(&llama_system_info),
+        llama_system_info,
+        warnings: Vec::new(),
+    };
+    capabilities.warnings = capability_warnings(&capabilities, cfg!(target_os = "macos"));
+    capabilities
+}
+
+/// Enabled features from llama_print_system_info, in either the flat layout
+/// ("NEON = 1 | ARM_FMA = 1 | METAL = 1 | ...") or the per-backend one
+/// ("Metal : EMBED_LIBRARY = 1 | CPU : NEON = 1 | ACCELERATE = 1 | ...")
+pub fn parse_llama_system_info(info: &str) -> Vec<String> {
+    let mut features = Vec::new();
+    for segment in info.split('|') {
+        let mut setting = segment.trim();
+        if let Some((backend, rest)) = setting.split_once(':') {
+            let backend = backend.trim();
+            if !backend.is_empty() && !features.iter().any(|f: &String| f.eq_ignore_ascii_case(backend)) {
+                features.push(backend.to_string());
+            }
+            setting = rest.trim();
+        }
+        if let Some((name, value)) = setting.split_once('=') {
+            if value.trim() == "1" {
+                features.push(name.trim().to_string());
+            }
+        }
+    }
+    features
+}
+
+fn has_feature(capabilities: &PlatformCapabilities, feature: &str) -> bool {
+    capabilities.llama_features.iter().any(|f| f.eq_ignore_ascii_case(feature))
+}
+
+fn capability_warnings(capabilities: &PlatformCapabilities, macos: bool) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if capabilities.rosetta_translated == Some(true) {
+        warnings.push(format!(
+            "Running under Rosetta 2 translation ({} build on Apple Silicon); timings and power are not representative. Install the Apple Silicon (arm64) build.",
+            capabilities.binary_arch
+        ));
+    }
+    if macos && !capabilities.metal_available {
+        warnings.push("llama.cpp was built without Metal support; inference runs on the CPU only and GPU layer settings have no effect.".to_string());
+    }
+    // Only judge features when the system info could be parsed at all
+    if !capabilities.llama_features.is_empty() {
+        if capabilities.binary_arch == "aarch64" && !has_feature(capabilities, "NEON") {
+            warnings.push("llama.cpp was built without NEON; CPU inference is far slower than a standard build.".to_string());
+        }
+        if macos && !has_feature(capabilities, "ACCELERATE") {
+            warnings.push("llama.cpp was built without Accelerate; CPU matrix operations are slower than a standard build.".to_string());
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(info: &str) -> PlatformCapabilities {
+        PlatformCapabilities {
+            binary_arch: "aarch64".to_string(),
+            apple_silicon_host: Some(true),
+            rosetta_translated: Some(false),
+            metal_available: true,
+            llama_features: parse_llama_system_info(info),
+            llama_system_info: info.to_string(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_llama_system_info_layouts() {
+        let per_backend = "Metal : EMBED_LIBRARY = 1 | CPU : NEON = 1 | ARM_FMA = 1 | FP16_VA = 0 | ACCELERATE = 1 | ";
+        assert_eq!(parse_llama_system_info(per_backend), vec!["Metal", "EMBED_LIBRARY", "CPU", "NEON", "ARM_FMA", "ACCELERATE"]);
+        let flat = "AVX = 0 | AVX2 = 0 | NEON = 1 | ARM_FMA = 1 | METAL = 1 | BLAS = 1 | ";
+        assert_eq!(parse_llama_system_info(flat), vec!["NEON", "ARM_FMA", "METAL", "BLAS"]);
+        assert!(parse_llama_system_info("").is_empty());
+    }
+
+    #[test]
+    fn test_native_build_is_representative_and_rosetta_is_flagged() {
+        let native = capabilities("Metal : EMBED_LIBRARY = 1 | CPU : NEON = 1 | ACCELERATE = 1 | ");
+        assert!(capability_warnings(&native, true).is_empty());
+
+        let mut translated = capabilities("AVX = 1 | AVX2 = 1 | NEON = 0 | ");
+        translated.binary_arch = "x86_64".to_string();
+        translated.rosetta_translated = Some(true);
+        translated.metal_available = false;
+        let warnings = capability_warnings(&translated, true);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("Rosetta"));
+        assert!(warnings[1].contains("Metal"));
+        assert!(warnings[2].contains("Accelerate"));
+    }
+}
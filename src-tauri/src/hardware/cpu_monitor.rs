@@ -175,8 +175,13 @@ lazy_static::lazy_static! {
     };
 }
 
+/// Integer sysctl by name (None off macOS or when the key does not exist)
+pub(crate) fn sysctl_u32(name: &str) -> Option<u32> {
+    apple_silicon_detection::get_sysctl_u32(name)
+}
+
 // Chip detection and parsing functions
-fn get_apple_chip_name() -> Option<String> {
+pub(crate) fn get_apple_chip_name() -> Option<String> {
     let brand_string = apple_silicon_detection::get_sysctl_string("machdep.cpu.brand_string")?;
     parse_apple_chip_model(&brand_string)
 }
@@ -0,0 +1,162 @@
+// Contains macOS environment probes (OS version, power source, Low Power Mode, power
+// adapter, llama.cpp-related environment variables) recorded with each run, since all of
+// them change sustained clocks or the engine's setup and therefore results
+
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Ups,
+}
+
+// Environment variables read by ggml/llama.cpp, Metal or the BLAS and OpenMP runtimes
+const RELEVANT_ENV_PREFIXES: &[&str] = &["GGML_", "LLAMA_", "MTL_", "METAL_", "OMP_", "VECLIB_", "OPENBLAS_", "MKL_"];
+const RELEVANT_ENV_VARS: &[&str] = &["RAYON_NUM_THREADS", "TOKIO_WORKER_THREADS"];
+
+/// The connected power adapter as reported by `pmset -g ac`; a smaller adapter than the
+/// machine shipped with can cap sustained power even on AC
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerAdapter {
+    pub watts: Option<u32>,
+    pub name: Option<String>,            // e.g. "96W USB-C Power Adapter"
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,           // Adapter model id, e.g. "0x7019"
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Product version such as "14.6.1" (None off macOS or when sw_vers is unavailable)
+pub fn macos_version() -> Option<String> {
+    let version = command_output("sw_vers", &["-productVersion"])?;
+    let version = version.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+pub fn power_source() -> Option<PowerSource> {
+    parse_power_source(&command_output("pmset", &["-g", "batt"])?)
+}
+
+pub fn low_power_mode() -> Option<bool> {
+    parse_low_power_mode(&command_output("pmset", &["-g"])?)
+}
+
+pub fn power_adapter() -> Option<PowerAdapter> {
+    parse_power_adapter(&command_output("pmset", &["-g", "ac"])?)
+}
+
+/// Set environment variables that affect inference, e.g. GGML_METAL_* or OMP_NUM_THREADS
+pub fn relevant_env_vars() -> BTreeMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| is_relevant_env_var(name))
+        .collect()
+}
+
+fn is_relevant_env_var(name: &str) -> bool {
+    RELEVANT_ENV_VARS.contains(&name) || RELEVANT_ENV_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+// `pmset -g ac` prints " Wattage = 96W", " Name = ...", etc., or "No adapter attached."
+// The serial number is left out on purpose.
+fn parse_power_adapter(pmset_ac: &str) -> Option<PowerAdapter> {
+    let mut adapter = PowerAdapter::default();
+    for line in pmset_ac.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim() {
+            "Wattage" => adapter.watts = value.trim_end_matches('W').parse().ok(),
+            "Name" => adapter.name = Some(value.to_string()),
+            "Manufacturer" => adapter.manufacturer = Some(value.to_string()),
+            "Model" => adapter.model = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    (adapter != PowerAdapter::default()).then_some(adapter)
+}
+
+// First line of `pmset -g batt`: "Now drawing from 'AC Power'"
+fn parse_power_source(pmset_batt: &str) -> Option<PowerSource> {
+    let line = pmset_batt.lines().find(|line| line.contains("drawing from"))?;
+    if line.contains("'AC Power'") {
+        Some(PowerSource::Ac)
+    } else if line.contains("'Battery Power'") {
+        Some(PowerSource::Battery)
+    } else if line.contains("'UPS Power'") {
+        Some(PowerSource::Ups)
+    } else {
+        None
+    }
+}
+
+// `pmset -g` lists the active settings; "lowpowermode 1" (or "powermode 1" on newer
+// releases, where 2 is High Power Mode) means Low Power Mode is on
+fn parse_low_power_mode(pmset: &str) -> Option<bool> {
+    pmset.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next()?, parts.next()?) {
+                ("lowpowermode", value) | ("powermode", value) => value.parse::<u8>().ok(),
+                _ => None,
+            }
+        })
+        .next()
+        .map(|value| value == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_power_source() {
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged; 0:00 remaining present: true\n";
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t87%; discharging; 5:12 remaining present: true\n";
+        assert_eq!(parse_power_source(ac), Some(PowerSource::Ac));
+        assert_eq!(parse_power_source(battery), Some(PowerSource::Battery));
+        assert_eq!(parse_power_source(""), None);
+    }
+
+    #[test]
+    fn test_parse_low_power_mode() {
+        let on = "System-wide power settings:\nCurrently in use:\n standby              1\n lowpowermode         1\n sleep                1\n";
+        let off = "Currently in use:\n lowpowermode         0\n";
+        let high_power = "Currently in use:\n powermode            2\n";
+        assert_eq!(parse_low_power_mode(on), Some(true));
+        assert_eq!(parse_low_power_mode(off), Some(false));
+        assert_eq!(parse_low_power_mode(high_power), Some(false));
+        assert_eq!(parse_low_power_mode("Currently in use:\n sleep 1\n"), None);
+    }
+
+    #[test]
+    fn test_parse_power_adapter_and_env_filter() {
+        let pmset_ac = " Wattage = 96W\n Current = 4700mA\n Voltage = 20000mV\n AdapterID = 0x0000\n \
+                        Family Code = 0xe000400a\n Name = 96W USB-C Power Adapter\n Manufacturer = Apple Inc.\n \
+                        Model = 0x7019\n SerialNumber = C4H1234567\n";
+        let adapter = parse_power_adapter(pmset_ac).unwrap();
+        assert_eq!(adapter.watts, Some(96));
+        assert_eq!(adapter.name.as_deref(), Some("96W USB-C Power Adapter"));
+        assert_eq!(adapter.manufacturer.as_deref(), Some("Apple Inc."));
+        assert_eq!(adapter.model.as_deref(), Some("0x7019"));
+        assert_eq!(parse_power_adapter("No adapter attached.\n"), None);
+
+        assert!(is_relevant_env_var("GGML_METAL_NDEBUG"));
+        assert!(is_relevant_env_var("OMP_NUM_THREADS"));
+        assert!(is_relevant_env_var("RAYON_NUM_THREADS"));
+        assert!(!is_relevant_env_var("HOME"));
+        assert!(!is_relevant_env_var("AWS_SECRET_ACCESS_KEY"));
+    }
+}
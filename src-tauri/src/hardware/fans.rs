@@ -0,0 +1,187 @@
+// Fan speeds from the System Management Controller (AppleSMC user client), read from the
+// per-fan actual-RPM keys F0Ac, F1Ac. Fanless machines (e.g. MacBook Air) report none.
+
+use std::os::raw::{c_char, c_void};
+
+type CFDictionaryRef = *const c_void;
+type IOObject = u32;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOServiceMatching(name: *const c_char) -> CFDictionaryRef;
+    fn IOServiceGetMatchingService(main_port: u32, matching: CFDictionaryRef) -> IOObject;
+    fn IOServiceOpen(service: IOObject, owning_task: u32, connect_type: u32, connect: *mut IOObject) -> i32;
+    fn IOServiceClose(connect: IOObject) -> i32;
+    fn IOConnectCallStructMethod(
+        connection: IOObject,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> i32;
+    fn IOObjectRelease(object: IOObject) -> i32;
+}
+
+extern "C" {
+    static mach_task_self_: u32;
+}
+
+const K_IO_MAIN_PORT_DEFAULT: u32 = 0;
+const KERNEL_INDEX_SMC: u32 = 2;
+const SMC_CMD_READ_BYTES: u8 = 5;
+const SMC_CMD_READ_KEYINFO: u8 = 9;
+
+// Actual-speed keys of the fans monitored (F<n>Ac)
+const FAN_SPEED_KEYS: [&str; 2] = ["F0Ac", "F1Ac"];
+
+// Layout of the AppleSMC user client's struct method argument
+#[repr(C)]
+#[derive(Default)]
+struct SmcKeyDataVersion {
+    major: u8,
+    minor: u8,
+    build: u8,
+    reserved: u8,
+    release: u16,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SmcKeyDataPLimit {
+    version: u16,
+    length: u16,
+    cpu_p_limit: u32,
+    gpu_p_limit: u32,
+    mem_p_limit: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct SmcKeyInfo {
+    data_size: u32,
+    data_type: u32,
+    data_attributes: u8,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SmcKeyData {
+    key: u32,
+    version: SmcKeyDataVersion,
+    p_limit_data: SmcKeyDataPLimit,
+    key_info: SmcKeyInfo,
+    result: u8,
+    status: u8,
+    data8: u8,
+    data32: u32,
+    bytes: [u8; 32],
+}
+
+// SMC keys and data types are four ASCII characters packed big-endian
+fn four_char_code(code: &str) -> u32 {
+    code.bytes().fold(0, |acc, byte| (acc << 8) | byte as u32)
+}
+
+/// Decode an SMC fan speed value: "flt " (Apple Silicon, little-endian f32) or
+/// "fpe2" (Intel, unsigned 14.2 fixed point)
+fn decode_fan_rpm(data_type: u32, bytes: &[u8]) -> Option<f64> {
+    let value = if data_type == four_char_code("flt ") {
+        f32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as f64
+    } else if data_type == four_char_code("fpe2") {
+        u16::from_be_bytes(bytes.get(..2)?.try_into().ok()?) as f64 / 4.0
+    } else {
+        return None;
+    };
+    (value.is_finite() && value >= 0.0).then_some(value)
+}
+
+/// Open connection to the SMC, kept for the monitoring loop's lifetime
+pub struct FanSensors {
+    connection: IOObject,
+}
+
+impl FanSensors {
+    pub fn new() -> Result<Self, String> {
+        unsafe {
+            let class_name = std::ffi::CString::new("AppleSMC").unwrap();
+            let matching = IOServiceMatching(class_name.as_ptr());
+            if matching.is_null() {
+                return Err("Failed to create AppleSMC matching dictionary".to_string());
+            }
+            // IOServiceGetMatchingService consumes the matching dictionary
+            let service = IOServiceGetMatchingService(K_IO_MAIN_PORT_DEFAULT, matching);
+            if service == 0 {
+                return Err("AppleSMC service not found".to_string());
+            }
+            let mut connection: IOObject = 0;
+            let result = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+            IOObjectRelease(service);
+            if result != 0 {
+                return Err(format!("Failed to open AppleSMC (kern_return {})", result));
+            }
+            Ok(FanSensors { connection })
+        }
+    }
+
+    fn call(&self, input: &SmcKeyData) -> Option<SmcKeyData> {
+        let mut output = SmcKeyData::default();
+        let mut output_size = std::mem::size_of::<SmcKeyData>();
+        let result = unsafe {
+            IOConnectCallStructMethod(
+                self.connection,
+                KERNEL_INDEX_SMC,
+                input as *const SmcKeyData as *const c_void,
+                std::mem::size_of::<SmcKeyData>(),
+                &mut output as *mut SmcKeyData as *mut c_void,
+                &mut output_size,
+            )
+        };
+        (result == 0 && output.result == 0).then_some(output)
+    }
+
+    fn read_key(&self, key: &str) -> Option<f64> {
+        let key_info = self.call(&SmcKeyData {
+            key: four_char_code(key),
+            data8: SMC_CMD_READ_KEYINFO,
+            ..Default::default()
+        })?.key_info;
+        let value = self.call(&SmcKeyData {
+            key: four_char_code(key),
+            key_info,
+            data8: SMC_CMD_READ_BYTES,
+            ..Default::default()
+        })?;
+        let size = (key_info.data_size as usize).min(value.bytes.len());
+        decode_fan_rpm(key_info.data_type, &value.bytes[..size])
+    }
+
+    /// Current speed of each fan (RPM), in fan order; empty on fanless machines
+    pub fn read_rpm(&self) -> Vec<f64> {
+        FAN_SPEED_KEYS.iter().map_while(|key| self.read_key(key)).collect()
+    }
+}
+
+impl Drop for FanSensors {
+    fn drop(&mut self) {
+        unsafe {
+            IOServiceClose(self.connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_apple_silicon_and_intel_fan_values() {
+        assert_eq!(four_char_code("F0Ac"), 0x4630_4163);
+        assert_eq!(decode_fan_rpm(four_char_code("flt "), &1850.5f32.to_le_bytes()), Some(1850.5));
+        // 2400 RPM as 14.2 fixed point
+        assert_eq!(decode_fan_rpm(four_char_code("fpe2"), &[0x25, 0x80]), Some(2400.0));
+        assert_eq!(decode_fan_rpm(four_char_code("ui8 "), &[1]), None);
+        assert_eq!(decode_fan_rpm(four_char_code("flt "), &[0, 0]), None);
+        assert_eq!(decode_fan_rpm(four_char_code("flt "), &f32::NAN.to_le_bytes()), None);
+    }
+}
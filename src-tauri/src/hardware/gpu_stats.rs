@@ -0,0 +1,105 @@
+// GPU busy percentage from the IOAccelerator "PerformanceStatistics" registry property
+// (the same source asitop/powermetrics use for GPU utilization)
+
+use std::os::raw::{c_char, c_void};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuUtilization {
+    pub device: f64,            // Overall GPU busy percentage
+    pub renderer: Option<f64>,  // Shader/compute ("renderer") busy percentage
+    pub tiler: Option<f64>,     // Tiler busy percentage
+}
+
+type CFTypeRef = *const c_void;
+type CFDictionaryRef = *const c_void;
+type CFStringRef = *const c_void;
+type CFNumberRef = *const c_void;
+type IOObject = u32;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringCreateWithCString(alloc: CFTypeRef, cstr: *const c_char, encoding: u32) -> CFStringRef;
+    fn CFDictionaryGetValue(dict: CFDictionaryRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFNumberGetValue(number: CFNumberRef, the_type: i32, value_ptr: *mut c_void) -> bool;
+    fn CFRelease(cf: CFTypeRef);
+    static kCFAllocatorDefault: CFTypeRef;
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOServiceMatching(name: *const c_char) -> CFDictionaryRef;
+    fn IOServiceGetMatchingServices(main_port: u32, matching: CFDictionaryRef, existing: *mut IOObject) -> i32;
+    fn IOIteratorNext(iterator: IOObject) -> IOObject;
+    fn IORegistryEntryCreateCFProperty(entry: IOObject, key: CFStringRef, alloc: CFTypeRef, options: u32) -> CFTypeRef;
+    fn IOObjectRelease(object: IOObject) -> i32;
+}
+
+const K_IO_MAIN_PORT_DEFAULT: u32 = 0;
+const K_CFSTRING_ENCODING_UTF8: u32 = 0x08000100;
+const K_CFNUMBER_SINT64_TYPE: i32 = 4;
+
+fn cfstr(s: &str) -> CFStringRef {
+    let cstr = std::ffi::CString::new(s).unwrap();
+    unsafe { CFStringCreateWithCString(kCFAllocatorDefault, cstr.as_ptr(), K_CFSTRING_ENCODING_UTF8) }
+}
+
+/// Read the current GPU utilization of the first accelerator that reports it
+pub fn read_gpu_utilization() -> Result<GpuUtilization, String> {
+    unsafe {
+        let class_name = std::ffi::CString::new("IOAccelerator").unwrap();
+        let matching = IOServiceMatching(class_name.as_ptr());
+        if matching.is_null() {
+            return Err("Failed to create IOAccelerator matching dictionary".to_string());
+        }
+
+        // IOServiceGetMatchingServices consumes the matching dictionary
+        let mut iterator: IOObject = 0;
+        if IOServiceGetMatchingServices(K_IO_MAIN_PORT_DEFAULT, matching, &mut iterator) != 0 {
+            return Err("Failed to enumerate IOAccelerator services".to_string());
+        }
+
+        let stats_key = cfstr("PerformanceStatistics");
+        let mut result = None;
+        loop {
+            let service = IOIteratorNext(iterator);
+            if service == 0 {
+                break;
+            }
+            if result.is_none() {
+                let stats = IORegistryEntryCreateCFProperty(service, stats_key, kCFAllocatorDefault, 0);
+                if !stats.is_null() {
+                    result = read_statistics(stats);
+                    CFRelease(stats);
+                }
+            }
+            IOObjectRelease(service);
+        }
+
+        CFRelease(stats_key);
+        IOObjectRelease(iterator);
+        result.ok_or_else(|| "No IOAccelerator reported PerformanceStatistics".to_string())
+    }
+}
+
+unsafe fn read_statistics(stats: CFDictionaryRef) -> Option<GpuUtilization> {
+    Some(GpuUtilization {
+        device: percentage_value(stats, "Device Utilization %")?,
+        renderer: percentage_value(stats, "Renderer Utilization %"),
+        tiler: percentage_value(stats, "Tiler Utilization %"),
+    })
+}
+
+unsafe fn percentage_value(stats: CFDictionaryRef, key: &str) -> Option<f64> {
+    let cf_key = cfstr(key);
+    let number = CFDictionaryGetValue(stats, cf_key);
+    CFRelease(cf_key);
+    if number.is_null() {
+        return None;
+    }
+    let mut value: i64 = 0;
+    if !CFNumberGetValue(number, K_CFNUMBER_SINT64_TYPE, &mut value as *mut i64 as *mut c_void) {
+        return None;
+    }
+    Some((value as f64).clamp(0.0, 100.0))
+}
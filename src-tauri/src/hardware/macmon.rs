@@ -125,9 +125,13 @@ pub async fn start_macmon_monitoring(
                                         gpu_freq_mhz: gpu_freq,
                                         ram_usage_gb,
                                         thermal_pressure: None, // macmon doesn't provide this legacy field
+                                        swap_usage_gb: None,
+                                        compressed_memory_gb: None,
+                                        memory_pressure_level: None,
                                         ttft_ms: None,
                                         current_tps: None,
                                         instantaneous_tps: None,
+                                        rolling_tps: None,
                                         generation_time_ms: None,
                                         model: None,
                                         // Enhanced temperature data (unavailable in legacy mode)
@@ -143,6 +147,10 @@ pub async fn start_macmon_monitoring(
                                         cpu_p_core_utilization: None,
                                         cpu_e_core_utilization: None,
                                         cpu_overall_utilization: None,
+                                        inference_cpu_utilization: None,
+                                        inference_cpu_share: None,
+                                        gpu_utilization: None,
+                                        fan_rpm: None,
                                         core_temperatures: None, // Legacy macmon mode doesn't provide individual cores
                                         // Energy fields (initialized as None, will be filled by PowerCalculator)
             total_energy_wh: None,
@@ -150,6 +158,12 @@ pub async fn start_macmon_monitoring(
             gpu_energy_wh: None,
             ane_energy_wh: None,
             energy_rate_wh_per_token: None,
+            total_energy_lower_wh: None,
+            total_energy_upper_wh: None,
+            cpu_temp_rate_c_per_s: None,
+            gpu_temp_rate_c_per_s: None,
+            battery_temp_rate_c_per_s: None,
+            package_power_rate_w_per_s: None,
                                     };
 
                                     println!("🔋 Broadcasting hardware telemetry: {:?}", telemetry);
@@ -0,0 +1,142 @@
+// Memory pressure, swap and compressor usage. A model that does not fit in RAM gets
+// compressed and swapped by macOS and slows down several-fold; RAM usage alone hides that.
+
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::cpu_monitor::sysctl_u32;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryPressureSample {
+    pub swap_usage_gb: Option<f64>,
+    pub compressed_memory_gb: Option<f64>,
+    pub memory_pressure_level: Option<String>,   // "normal" / "warning" / "critical"
+}
+
+/// Name of a kern.memorystatus_vm_pressure_level value (the kernel's
+/// kVMPressureNormal/Warning/Critical)
+pub fn memory_pressure_name(level: u32) -> &'static str {
+    match level {
+        1 => "normal",
+        2 => "warning",
+        4 => "critical",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod mach {
+    use std::ffi::CString;
+    use std::os::raw::{c_int, c_void};
+
+    // <sys/sysctl.h> struct xsw_usage, returned by vm.swapusage
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Default)]
+    struct XswUsage {
+        total: u64,
+        avail: u64,
+        used: u64,
+        pagesize: u32,
+        encrypted: bool,
+    }
+
+    // <mach/vm_statistics.h> struct vm_statistics64
+    #[allow(dead_code)]
+    #[repr(C, align(8))]
+    #[derive(Default)]
+    struct VmStatistics64 {
+        free_count: u32,
+        active_count: u32,
+        inactive_count: u32,
+        wire_count: u32,
+        zero_fill_count: u64,
+        reactivations: u64,
+        pageins: u64,
+        pageouts: u64,
+        faults: u64,
+        cow_faults: u64,
+        lookups: u64,
+        hits: u64,
+        purges: u64,
+        purgeable_count: u32,
+        speculative_count: u32,
+        decompressions: u64,
+        compressions: u64,
+        swapins: u64,
+        swapouts: u64,
+        compressor_page_count: u32,
+        throttled_count: u32,
+        external_page_count: u32,
+        internal_page_count: u32,
+        total_uncompressed_pages_in_compressor: u64,
+    }
+
+    const HOST_VM_INFO64: c_int = 4;
+    const KERN_SUCCESS: c_int = 0;
+
+    extern "C" {
+        fn mach_host_self() -> u32;
+        fn host_statistics64(host: u32, flavor: c_int, info: *mut c_int, count: *mut u32) -> c_int;
+    }
+
+    pub fn swap_used_bytes() -> Option<u64> {
+        let name = CString::new("vm.swapusage").unwrap();
+        let mut usage = XswUsage::default();
+        let mut size = std::mem::size_of::<XswUsage>();
+        let result = unsafe {
+            libc::sysctlbyname(name.as_ptr(), &mut usage as *mut XswUsage as *mut c_void, &mut size, std::ptr::null_mut(), 0)
+        };
+        (result == 0).then_some(usage.used)
+    }
+
+    pub fn compressed_bytes() -> Option<u64> {
+        let mut stats = VmStatistics64::default();
+        let mut count = (std::mem::size_of::<VmStatistics64>() / std::mem::size_of::<c_int>()) as u32;
+        let result = unsafe {
+            host_statistics64(mach_host_self(), HOST_VM_INFO64, &mut stats as *mut VmStatistics64 as *mut c_int, &mut count)
+        };
+        if result != KERN_SUCCESS {
+            return None;
+        }
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        (page_size > 0).then(|| stats.compressor_page_count as u64 * page_size as u64)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod mach {
+    pub fn swap_used_bytes() -> Option<u64> {
+        None
+    }
+
+    pub fn compressed_bytes() -> Option<u64> {
+        None
+    }
+}
+
+/// Current swap, compressor and pressure readings; each is None when it cannot be read
+pub fn read_memory_pressure() -> MemoryPressureSample {
+    MemoryPressureSample {
+        swap_usage_gb: mach::swap_used_bytes().map(|bytes| bytes as f64 / BYTES_PER_GB),
+        compressed_memory_gb: mach::compressed_bytes().map(|bytes| bytes as f64 / BYTES_PER_GB),
+        memory_pressure_level: sysctl_u32("kern.memorystatus_vm_pressure_level")
+            .map(|level| memory_pressure_name(level).to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_pressure_names() {
+        assert_eq!(memory_pressure_name(1), "normal");
+        assert_eq!(memory_pressure_name(2), "warning");
+        assert_eq!(memory_pressure_name(4), "critical");
+        assert_eq!(memory_pressure_name(0), "unknown");
+        // Reading never fails outright, whatever the platform provides
+        let _ = read_memory_pressure();
+    }
+}
@@ -3,11 +3,23 @@
 pub mod temperature;
 pub mod cpu_monitor;
 pub mod macmon;
+pub mod process_cpu;
+pub mod gpu_stats;
+pub mod fans;
+pub mod environment;
+pub mod aggregation;
+pub mod rates;
+pub mod sensor_profile;
+pub mod capabilities;
+pub mod backend_info;
+pub mod thermal_pressure;
+pub mod memory;
+pub mod power_assertion;
 
 // Re-export temperature structs for external access
 pub use temperature::{
-    TemperatureInfo, CoreTemperatureData, ThermalTrend, 
-    IOHIDTemperatureSensors, TemperatureHistory, read_core_temperatures
+    TemperatureInfo, CoreTemperatureData, DieTemperatureData, ThermalTrend, 
+    IOHIDTemperatureSensors, TemperatureHistory, read_core_temperatures, read_temperature_readings
 };
 
 // Re-export CPU monitoring structs for external access - Priority 4.3
@@ -20,6 +32,15 @@ pub use macmon::{
     MacmonOutput, MemoryInfo, start_macmon_monitoring
 };
 
+pub use aggregation::{RawSample, CpuUtilizationSample, aggregate_sample};
+pub use rates::TelemetryRates;
+
+pub use process_cpu::{ProcessCpuMonitor, ProcessCpuAttribution};
+pub use gpu_stats::{GpuUtilization, read_gpu_utilization};
+pub use fans::FanSensors;
+pub use thermal_pressure::ThermalPressureMonitor;
+pub use memory::{MemoryPressureSample, read_memory_pressure};
+
 use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 use std::time::Duration;
 use tokio::io::{BufReader, AsyncBufReadExt};
@@ -30,8 +51,15 @@ use crate::{
     TelemetryUpdate, TelemetryBroadcaster,
     CURRENT_TELEMETRY
 };
-use crate::telemetry::types::{TelemetryCommand, TelemetryCommandBroadcaster};
-use crate::telemetry::power_calculator::PowerCalculator;
+use crate::telemetry::types::{
+    TelemetryCommandBroadcaster, TelemetryProfile, IntegrationMethod, DEFAULT_TELEMETRY_SAMPLING_HZ,
+    MIN_TELEMETRY_SAMPLING_HZ, MAX_TELEMETRY_SAMPLING_HZ
+};
+use crate::telemetry::power_calculator::ModelEnergyAccumulators;
+use crate::telemetry::idle_baseline::IdlePower;
+use crate::telemetry::processor::{MODEL_ENERGY, TELEMETRY_PAUSED};
+use crate::telemetry::phase::apply_phase_commands;
+use crate::telemetry::debug_capture::SharedDebugCapture;
 use crate::utils::debug::DEBUG_LOGS;
 
 #[allow(unused_macros)]
@@ -44,30 +72,71 @@ macro_rules! dprintln {
 
 
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_enhanced_monitoring(
     telemetry_broadcaster: TelemetryBroadcaster,
     stop_signal: Arc<AtomicBool>,
     command_receiver: Option<TelemetryCommandBroadcaster>,
     sampling_frequency_hz: Option<f32>,  // Sampling frequency in Hz (e.g., 1.0 = 1Hz = 1000ms interval)
+    profile: TelemetryProfile,
+    integration_method: IntegrationMethod,
+    debug_capture: Option<SharedDebugCapture>,  // Mirror every raw tick to a debug capture file
+    idle_power: Option<IdlePower>,              // Subtracted from power readings before energy integration
 ) -> Result<(), String> {
     // Calculate sampling interval from frequency (default 1Hz = 1000ms)
-    let sampling_hz = sampling_frequency_hz.unwrap_or(1.0).max(0.1).min(50.0); // Clamp between 0.1 and 50 Hz
+    let sampling_hz = profile.clamp_sampling_hz(sampling_frequency_hz
+        .unwrap_or(DEFAULT_TELEMETRY_SAMPLING_HZ)
+        .max(MIN_TELEMETRY_SAMPLING_HZ)
+        .min(MAX_TELEMETRY_SAMPLING_HZ)); // Clamp between 0.1 and 50 Hz (lower for the minimal profile)
+    // Minimal profile skips IOHID per-core temperature enumeration and utilization sampling
+    let minimal_profile = profile == TelemetryProfile::Minimal;
     let sampling_interval_ms = (1000.0 / sampling_hz) as u64;
     
 dprintln!("Starting enhanced monitoring with SMC temperature sensors...");
-dprintln!("📊 Telemetry sampling frequency: {:.1}Hz ({}ms interval)", sampling_hz, sampling_interval_ms);
+dprintln!("📊 Telemetry sampling frequency: {:.1}Hz ({}ms interval), profile {:?}", sampling_hz, sampling_interval_ms, profile);
     
     // Initialize temperature history tracking
     let mut temp_history = TemperatureHistory::new(60); // Keep 1 minute of history
+    let mut rates = TelemetryRates::new();
     
-    // Initialize CPU utilization monitor
-    let mut cpu_monitor = CpuUtilizationMonitor::new();
+    // Initialize CPU utilization monitor (not needed for the minimal profile)
+    let mut cpu_monitor = if minimal_profile { None } else { Some(CpuUtilizationMonitor::new()) };
+    let mut process_cpu_monitor = if minimal_profile { None } else { Some(ProcessCpuMonitor::new()) };
+
+    // SMC connection for fan speeds (skipped by the minimal profile)
+    let fan_sensors = if minimal_profile {
+        None
+    } else {
+        match FanSensors::new() {
+            Ok(sensors) => Some(sensors),
+            Err(e) => {
+                println!("⚠️ Fan speeds unavailable: {}", e);
+                None
+            }
+        }
+    };
     
-    // Initialize power calculator
-    let mut power_calculator = PowerCalculator::new();
+    // OS thermal pressure level; cheap enough for every profile
+    let thermal_pressure_monitor = match ThermalPressureMonitor::new() {
+        Ok(monitor) => Some(monitor),
+        Err(e) => {
+            println!("⚠️ Thermal pressure unavailable: {}", e);
+            None
+        }
+    };
+    let mut last_thermal_pressure: Option<&'static str> = None;
+    let mut last_memory_pressure: Option<String> = None;
+
+    // Initialize per-model energy accumulators
+    let mut energy_accumulators = ModelEnergyAccumulators::new(integration_method).with_idle_power(idle_power);
     
-    // Set up command receiver for power calculator reset
+    // Set up command receiver for model phase markers and pause/resume
     let mut command_rx = command_receiver.as_ref().map(|broadcaster| broadcaster.subscribe());
+    // Only a generation's monitors (the ones with a command channel) can be paused
+    let mut paused = command_rx.is_some() && TELEMETRY_PAUSED.load(Ordering::Relaxed);
+    if paused {
+        println!("⏸️ TELEMETRY: Starting paused");
+    }
     
     // Start both macmon for power/freq and SMC for detailed temperatures
     let mut macmon_child = None;
@@ -94,17 +163,17 @@ dprintln!("✅ Macmon started for power/frequency data");
     }
     
     while !stop_signal.load(Ordering::Relaxed) {
-        // Check for power calculator reset commands
+        // Apply (and acknowledge) model phase markers before taking this sample
         if let Some(ref mut rx) = command_rx {
-            while let Ok(command) = rx.try_recv() {
-                match command {
-                    TelemetryCommand::ResetPowerCalculator => {
-                        println!("🔄 POWER CALC: Received reset command - resetting power calculator for new model");
-                        power_calculator.reset();
-                        println!("🔄 POWER CALC: Power calculator reset completed");
-                    }
-                }
+            apply_phase_commands(rx, &mut energy_accumulators, &mut paused);
+        }
+        if paused {
+            // Discard macmon's output meanwhile so sampling resumes with fresh readings
+            if let Some(ref mut reader) = macmon_reader {
+                while let Ok(Ok(Some(_))) = tokio::time::timeout(Duration::ZERO, reader.next_line()).await {}
             }
+            tokio::time::sleep(Duration::from_millis(sampling_interval_ms)).await;
+            continue;
         }
         
         let timestamp = std::time::SystemTime::now()
@@ -112,14 +181,65 @@ dprintln!("✅ Macmon started for power/frequency data");
             .unwrap()
             .as_millis() as u64;
         
-        // Read enhanced core temperatures via SMC
-        let core_temp_result = read_core_temperatures().await;
+        // Read raw IOHID temperature sensors (categorized during aggregation)
+        let temperature_readings = if minimal_profile { None } else { Some(read_temperature_readings()) };
         
         // Get CPU utilization data
-        let (p_core_utils, e_core_utils, overall_util) = cpu_monitor.get_cpu_utilization().await;
+        let cpu_utilization = match cpu_monitor.as_mut() {
+            Some(monitor) => {
+                let (p_cores, e_cores, overall) = monitor.get_cpu_utilization().await;
+                Some(CpuUtilizationSample { p_cores, e_cores, overall })
+            }
+            None => None,
+        };
+
+        // Attribute part of the system-wide load to the app's own threads
+        let process_cpu = process_cpu_monitor.as_mut().and_then(|monitor| monitor.sample());
+
+        // GPU busy percentage from IOAccelerator statistics (skipped by the minimal profile)
+        let gpu_util = if minimal_profile {
+            None
+        } else {
+            match read_gpu_utilization() {
+                Ok(util) => {
+dprintln!("   GPU utilization: device {:.0}%, renderer {:?}%, tiler {:?}%", util.device, util.renderer, util.tiler);
+                    Some(util.device)
+                }
+                Err(e) => {
+dprintln!("⚠️ GPU utilization unavailable: {}", e);
+                    None
+                }
+            }
+        };
+
+        // Empty on fanless machines, which is reported as no reading
+        let fan_rpm = fan_sensors.as_ref().map(FanSensors::read_rpm).filter(|rpm| !rpm.is_empty());
+
+        let thermal_pressure = thermal_pressure_monitor.as_ref().and_then(ThermalPressureMonitor::read);
+        if thermal_pressure != last_thermal_pressure {
+            if let Some(level) = thermal_pressure {
+                println!("🌡️ Thermal pressure: {}", level);
+            }
+            last_thermal_pressure = thermal_pressure;
+        }
+
+        // Swap and compressor growth show a model spilling out of RAM
+        let memory_pressure = read_memory_pressure();
+        if memory_pressure.memory_pressure_level != last_memory_pressure {
+            if let Some(level) = &memory_pressure.memory_pressure_level {
+                println!("🧠 Memory pressure: {} (swap {:.2} GB, compressed {:.2} GB)", level,
+                         memory_pressure.swap_usage_gb.unwrap_or(0.0), memory_pressure.compressed_memory_gb.unwrap_or(0.0));
+            }
+            last_memory_pressure = memory_pressure.memory_pressure_level.clone();
+        }
+
+        if let Some(p) = process_cpu {
+dprintln!("   Inference-attributed CPU: {:.1}% across {} active threads",
+                     p.inference_utilization, p.active_threads);
+        }
         
-        // Try to get macmon data if available
-        let mut macmon_data: Option<MacmonOutput> = None;
+        // Try to get a macmon line if available (parsed during aggregation)
+        let mut macmon_line: Option<String> = None;
 dprintln!("🔍 MACMON DATA COLLECTION: Attempting to read macmon data...");
         
         if let Some(ref mut reader) = macmon_reader {
@@ -128,24 +248,7 @@ dprintln!("   📖 Macmon reader available - attempting non-blocking read with 1
             match tokio::time::timeout(Duration::from_millis(100), reader.next_line()).await {
                 Ok(Ok(Some(line))) => {
 dprintln!("   📖 Raw macmon line received: {}", line);
-                    match serde_json::from_str::<MacmonOutput>(&line) {
-                        Ok(data) => {
-dprintln!("   ✅ Macmon data parsed successfully:");
-dprintln!("      CPU power: {:?} W", data.cpu_power);
-dprintln!("      GPU power: {:?} W", data.gpu_power);
-dprintln!("      ANE power: {:?} W", data.ane_power);
-dprintln!("      CPU temp: {:?}°C", data.temp.as_ref().and_then(|t| t.cpu_temp_avg));
-dprintln!("      GPU temp: {:?}°C", data.temp.as_ref().and_then(|t| t.gpu_temp_avg));
-dprintln!("      CPU freq: {:?} MHz", data.pcpu_usage.as_ref().map(|(freq, _)| freq));
-dprintln!("      GPU freq: {:?} MHz", data.gpu_usage.as_ref().map(|(freq, _)| freq));
-dprintln!("      RAM usage: {:?} bytes", data.memory.as_ref().and_then(|m| m.ram_usage));
-                            macmon_data = Some(data);
-                        }
-                        Err(e) => {
-dprintln!("   ❌ Failed to parse macmon JSON: {}", e);
-dprintln!("      Raw line was: {}", line);
-                        }
-                    }
+                    macmon_line = Some(line);
                 }
                 Ok(Ok(None)) => {
 dprintln!("   📖 Macmon reader returned None (EOF)");
@@ -160,149 +263,32 @@ dprintln!("   ⏰ Macmon read timeout (no data available)");
         } else {
             println!("   ❌ No macmon reader available - running in SMC-only mode");
         }
+
+        let raw_sample = RawSample {
+            timestamp_ms: timestamp,
+            temperature_readings,
+            macmon_line,
+            cpu_utilization,
+            inference_cpu_utilization: process_cpu.map(|p| p.inference_utilization),
+            gpu_utilization: gpu_util,
+            fan_rpm,
+            thermal_pressure: thermal_pressure.map(str::to_string),
+            memory_pressure,
+        };
+        if let Some(capture) = &debug_capture {
+            capture.record(&raw_sample);
+        }
         
         // Create telemetry update combining both sources
-dprintln!("🔍 TELEMETRY AGGREGATION: Combining SMC and macmon data...");
-        let telemetry = match core_temp_result {
-            Ok(mut core_temps) => {
-dprintln!("   ✅ Core temperature data available from SMC");
-                // Update thermal trend from history
-                temp_history.add_reading(timestamp, core_temps.cpu_temp_avg);
-                core_temps.thermal_trend = temp_history.get_trend(10000); // 10 second window
-                
-dprintln!("🔍 FINAL TELEMETRY VALUES:");
-                
-                let cpu_power = macmon_data.as_ref().and_then(|d| d.cpu_power);
-                let gpu_power = macmon_data.as_ref().and_then(|d| d.gpu_power);
-                let ane_power = macmon_data.as_ref().and_then(|d| d.ane_power);
-dprintln!("   Power: CPU={:?}W, GPU={:?}W, ANE={:?}W", cpu_power, gpu_power, ane_power);
-                
-                let legacy_cpu_temp = Some(core_temps.cpu_temp_avg);
-                let combined_gpu_temp = core_temps.gpu_temp_avg.or_else(|| 
-                    macmon_data.as_ref()
-                        .and_then(|d| d.temp.as_ref())
-                        .and_then(|t| t.gpu_temp_avg)
-                );
-dprintln!("   Legacy temps: CPU={:?}°C, GPU={:?}°C", legacy_cpu_temp, combined_gpu_temp);
-                
-                let cpu_freq = macmon_data.as_ref()
-                    .and_then(|d| d.pcpu_usage.as_ref())
-                    .map(|(freq, _)| *freq);
-                let gpu_freq = macmon_data.as_ref()
-                    .and_then(|d| d.gpu_usage.as_ref())
-                    .map(|(freq, _)| *freq);
-dprintln!("   Frequencies: CPU={:?}MHz, GPU={:?}MHz", cpu_freq, gpu_freq);
-                
-                let ram_usage = macmon_data.as_ref()
-                    .and_then(|d| d.memory.as_ref())
-                    .and_then(|m| m.ram_usage)
-                    .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0));
-dprintln!("   RAM usage: {:?}GB", ram_usage);
-                
-dprintln!("   Enhanced temps: CPU_avg={:?}°C, CPU_max={:?}°C, GPU_avg={:?}°C, GPU_max={:?}°C, Battery_avg={:?}°C", 
-                         Some(core_temps.cpu_temp_avg), Some(core_temps.cpu_temp_max), 
-                         core_temps.gpu_temp_avg, core_temps.gpu_temp_max, core_temps.battery_temp_avg);
-                
-dprintln!("   Core arrays: P_cores={}, E_cores={}, GPU_clusters={}", 
-                         core_temps.p_cores.len(), core_temps.e_cores.len(), core_temps.gpu_temps.len());
-                
-dprintln!("   CPU utilization: P_cores={}, E_cores={}, Overall={:.1}%", 
-                         p_core_utils.len(), e_core_utils.len(), overall_util);
-                
-                TelemetryUpdate {
-                    timestamp_ms: timestamp,
-                    cpu_power_watts: cpu_power,
-                    gpu_power_watts: gpu_power,
-                    ane_power_watts: ane_power,
-                    cpu_temp_celsius: legacy_cpu_temp, // Legacy compatibility
-                    gpu_temp_celsius: combined_gpu_temp,
-                    cpu_freq_mhz: cpu_freq,
-                    gpu_freq_mhz: gpu_freq,
-                    ram_usage_gb: ram_usage,
-                    thermal_pressure: None,
-                    ttft_ms: None,
-                    current_tps: None,
-                    instantaneous_tps: None,
-                    generation_time_ms: None,
-                    model: None,
-                    // Enhanced temperature data
-                    cpu_temp_avg: Some(core_temps.cpu_temp_avg),
-                    cpu_temp_max: Some(core_temps.cpu_temp_max),
-                    cpu_p_core_temps: Some(core_temps.p_cores.clone()),
-                    cpu_e_core_temps: Some(core_temps.e_cores.clone()),
-                    gpu_temp_avg: core_temps.gpu_temp_avg,
-                    gpu_temp_max: core_temps.gpu_temp_max,
-                    gpu_cluster_temps: Some(core_temps.gpu_temps.clone()),
-                    battery_temp_avg: core_temps.battery_temp_avg,
-                    // CPU utilization data
-                    cpu_p_core_utilization: Some(p_core_utils.clone()),
-                    cpu_e_core_utilization: Some(e_core_utils.clone()),
-                    cpu_overall_utilization: Some(overall_util),
-                    core_temperatures: Some(core_temps),
-                    // Energy fields (initialized as None, will be filled by PowerCalculator)
-                    total_energy_wh: None,
-                    cpu_energy_wh: None,
-                    gpu_energy_wh: None,
-                    ane_energy_wh: None,
-                    energy_rate_wh_per_token: None,
-                }
-            }
-            Err(e) => {
-                println!("❌ SMC temperature read failed: {}", e);
-                // Fallback to macmon-only data
-                TelemetryUpdate {
-                    timestamp_ms: timestamp,
-                    cpu_power_watts: macmon_data.as_ref().and_then(|d| d.cpu_power),
-                    gpu_power_watts: macmon_data.as_ref().and_then(|d| d.gpu_power),
-                    ane_power_watts: macmon_data.as_ref().and_then(|d| d.ane_power),
-                    cpu_temp_celsius: macmon_data.as_ref()
-                        .and_then(|d| d.temp.as_ref())
-                        .and_then(|t| t.cpu_temp_avg),
-                    gpu_temp_celsius: macmon_data.as_ref()
-                        .and_then(|d| d.temp.as_ref())
-                        .and_then(|t| t.gpu_temp_avg),
-                    cpu_freq_mhz: macmon_data.as_ref()
-                        .and_then(|d| d.pcpu_usage.as_ref())
-                        .map(|(freq, _)| *freq),
-                    gpu_freq_mhz: macmon_data.as_ref()
-                        .and_then(|d| d.gpu_usage.as_ref())
-                        .map(|(freq, _)| *freq),
-                    ram_usage_gb: macmon_data.as_ref()
-                        .and_then(|d| d.memory.as_ref())
-                        .and_then(|m| m.ram_usage)
-                        .map(|bytes| bytes as f64 / (1024.0 * 1024.0 * 1024.0)),
-                    thermal_pressure: None,
-                    ttft_ms: None,
-                    current_tps: None,
-                    instantaneous_tps: None,
-                    generation_time_ms: None,
-                    model: None,
-                    // Enhanced temperature data (unavailable in fallback)
-                    cpu_temp_avg: macmon_data.as_ref().and_then(|d| d.temp.as_ref()).and_then(|t| t.cpu_temp_avg),
-                    cpu_temp_max: None,
-                    cpu_p_core_temps: None,
-                    cpu_e_core_temps: None,
-                    gpu_temp_avg: macmon_data.as_ref().and_then(|d| d.temp.as_ref()).and_then(|t| t.gpu_temp_avg),
-                    gpu_temp_max: None,
-                    gpu_cluster_temps: None,
-                    battery_temp_avg: None,
-                    // CPU utilization data (use fallback data)
-                    cpu_p_core_utilization: Some(p_core_utils.clone()),
-                    cpu_e_core_utilization: Some(e_core_utils.clone()),
-                    cpu_overall_utilization: Some(overall_util),
-                    core_temperatures: None,
-                    // Energy fields (initialized as None, will be filled by PowerCalculator)
-                    total_energy_wh: None,
-                    cpu_energy_wh: None,
-                    gpu_energy_wh: None,
-                    ane_energy_wh: None,
-                    energy_rate_wh_per_token: None,
-                }
-            }
-        };
+        let telemetry = aggregate_sample(&raw_sample, &mut temp_history, &mut rates);
         
-        // Update telemetry with power consumption calculation
-        let telemetry_with_energy = power_calculator.update_with_telemetry(telemetry);
+        // Update telemetry with the active model's power consumption calculation
+        let telemetry_with_energy = energy_accumulators.update_with_telemetry(telemetry);
+        if command_rx.is_some() {
+            if let Ok(mut totals) = MODEL_ENERGY.write() {
+                *totals = Some(energy_accumulators.summaries());
+            }
+        }
         
         // Store updated telemetry state for inference merging
         if let Ok(mut current) = CURRENT_TELEMETRY.write() {
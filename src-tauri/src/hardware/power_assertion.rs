@@ -0,0 +1,70 @@
+// IOKit power assertions, so an unattended batch keeps the Mac awake. The assertion only
+// blocks idle sleep; closing the lid or choosing Sleep still puts the machine to sleep.
+
+use std::os::raw::{c_char, c_void};
+
+type CFTypeRef = *const c_void;
+type CFStringRef = *const c_void;
+type IOPMAssertionID = u32;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringCreateWithCString(alloc: CFTypeRef, cstr: *const c_char, encoding: u32) -> CFStringRef;
+    fn CFRelease(cf: CFTypeRef);
+    static kCFAllocatorDefault: CFTypeRef;
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPMAssertionCreateWithName(
+        assertion_type: CFStringRef,
+        level: u32,
+        name: CFStringRef,
+        assertion_id: *mut IOPMAssertionID,
+    ) -> i32;
+    fn IOPMAssertionRelease(assertion_id: IOPMAssertionID) -> i32;
+}
+
+const K_CFSTRING_ENCODING_UTF8: u32 = 0x08000100;
+const K_IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+// kIOPMAssertionTypePreventUserIdleSystemSleep: the display may still sleep
+const PREVENT_IDLE_SYSTEM_SLEEP: &str = "PreventUserIdleSystemSleep";
+
+fn cfstr(s: &str) -> CFStringRef {
+    let cstr = std::ffi::CString::new(s).unwrap();
+    unsafe { CFStringCreateWithCString(kCFAllocatorDefault, cstr.as_ptr(), K_CFSTRING_ENCODING_UTF8) }
+}
+
+/// Held idle-sleep assertion; released when dropped. `pmset -g assertions` lists it
+/// under `reason`.
+pub struct SleepAssertion {
+    id: IOPMAssertionID,
+}
+
+impl SleepAssertion {
+    pub fn prevent_idle_sleep(reason: &str) -> Result<SleepAssertion, String> {
+        let mut id: IOPMAssertionID = 0;
+        let result = unsafe {
+            let assertion_type = cfstr(PREVENT_IDLE_SYSTEM_SLEEP);
+            let name = cfstr(reason);
+            let result = IOPMAssertionCreateWithName(assertion_type, K_IOPM_ASSERTION_LEVEL_ON, name, &mut id);
+            CFRelease(name);
+            CFRelease(assertion_type);
+            result
+        };
+        if result != 0 {
+            return Err(format!("IOPMAssertionCreateWithName failed with {:#x}", result));
+        }
+        println!("☕ Sleep prevention assertion {} taken: {}", id, reason);
+        Ok(SleepAssertion { id })
+    }
+}
+
+impl Drop for SleepAssertion {
+    fn drop(&mut self) {
+        unsafe {
+            IOPMAssertionRelease(self.id);
+        }
+        println!("☕ Sleep prevention assertion {} released", self.id);
+    }
+}
@@ -0,0 +1,184 @@
+// Per-thread CPU time of the app process, used to attribute part of the
+// system-wide CPU utilization to inference rather than background load
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessCpuAttribution {
+    pub inference_utilization: f64,     // App CPU (excluding the sampler thread) as % of all cores
+    pub active_threads: usize,          // App threads that used CPU during the interval
+}
+
+pub struct ProcessCpuMonitor {
+    previous: Option<(Instant, HashMap<u64, Duration>)>,
+    logical_cpus: usize,
+}
+
+impl ProcessCpuMonitor {
+    pub fn new() -> Self {
+        let logical_cpus = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self { previous: None, logical_cpus }
+    }
+
+    /// Sample per-thread CPU time and attribute the delta since the previous sample.
+    /// The first call only establishes a baseline and returns None.
+    pub fn sample(&mut self) -> Option<ProcessCpuAttribution> {
+        let now = Instant::now();
+        let current = read_thread_cpu_times()?;
+        let excluded = current_thread_id();
+
+        let attribution = self.previous.as_ref().map(|(last_sample, previous)| {
+            attribute(previous, &current, excluded, now.duration_since(*last_sample), self.logical_cpus)
+        });
+        self.previous = Some((now, current));
+        attribution
+    }
+}
+
+impl Default for ProcessCpuMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sum per-thread CPU deltas (threads that exited since the last sample are dropped,
+/// new threads count from zero) and normalize by wall time across all cores
+fn attribute(
+    previous: &HashMap<u64, Duration>,
+    current: &HashMap<u64, Duration>,
+    excluded: Option<u64>,
+    wall_time: Duration,
+    logical_cpus: usize,
+) -> ProcessCpuAttribution {
+    let mut busy = Duration::ZERO;
+    let mut active_threads = 0;
+    for (thread_id, cpu_time) in current {
+        if Some(*thread_id) == excluded {
+            continue;
+        }
+        let delta = cpu_time.saturating_sub(previous.get(thread_id).copied().unwrap_or_default());
+        if !delta.is_zero() {
+            busy += delta;
+            active_threads += 1;
+        }
+    }
+
+    let capacity_s = wall_time.as_secs_f64() * logical_cpus.max(1) as f64;
+    let inference_utilization = if capacity_s > 0.0 {
+        (busy.as_secs_f64() / capacity_s * 100.0).min(100.0)
+    } else {
+        0.0
+    };
+    ProcessCpuAttribution { inference_utilization, active_threads }
+}
+
+/// CPU time (user + system) of every thread in this process, keyed by thread id
+#[cfg(target_os = "macos")]
+#[allow(deprecated)] // libc's mach bindings are deprecated in favour of the mach2 crate
+fn read_thread_cpu_times() -> Option<HashMap<u64, Duration>> {
+    unsafe {
+        let task = libc::mach_task_self();
+        let mut threads: libc::thread_act_array_t = std::ptr::null_mut();
+        let mut thread_count: libc::mach_msg_type_number_t = 0;
+        if libc::task_threads(task, &mut threads, &mut thread_count) != libc::KERN_SUCCESS {
+            return None;
+        }
+
+        let mut times = HashMap::with_capacity(thread_count as usize);
+        for i in 0..thread_count as usize {
+            let thread = *threads.add(i);
+            if let (Some(id), Some(cpu_time)) = (thread_id(thread), thread_cpu_time(thread)) {
+                times.insert(id, cpu_time);
+            }
+            libc::mach_port_deallocate(task, thread);
+        }
+
+        libc::vm_deallocate(
+            task,
+            threads as libc::vm_address_t,
+            thread_count as libc::vm_size_t * std::mem::size_of::<libc::thread_act_t>() as libc::vm_size_t,
+        );
+        Some(times)
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn thread_id(thread: libc::thread_act_t) -> Option<u64> {
+    let mut info: libc::thread_identifier_info = std::mem::zeroed();
+    let mut count = libc::THREAD_IDENTIFIER_INFO_COUNT;
+    let result = libc::thread_info(
+        thread,
+        libc::THREAD_IDENTIFIER_INFO as libc::thread_flavor_t,
+        &mut info as *mut _ as libc::thread_info_t,
+        &mut count,
+    );
+    (result == libc::KERN_SUCCESS).then_some(info.thread_id)
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn thread_cpu_time(thread: libc::thread_act_t) -> Option<Duration> {
+    let mut info: libc::thread_basic_info = std::mem::zeroed();
+    let mut count = libc::THREAD_BASIC_INFO_COUNT;
+    let result = libc::thread_info(
+        thread,
+        libc::THREAD_BASIC_INFO as libc::thread_flavor_t,
+        &mut info as *mut _ as libc::thread_info_t,
+        &mut count,
+    );
+    if result != libc::KERN_SUCCESS {
+        return None;
+    }
+    let to_duration = |t: libc::time_value_t| {
+        Duration::from_secs(t.seconds as u64) + Duration::from_micros(t.microseconds as u64)
+    };
+    Some(to_duration(info.user_time) + to_duration(info.system_time))
+}
+
+/// Id of the calling thread, matching the ids returned by `read_thread_cpu_times`
+#[cfg(target_os = "macos")]
+fn current_thread_id() -> Option<u64> {
+    let mut id: u64 = 0;
+    let result = unsafe { libc::pthread_threadid_np(libc::pthread_self(), &mut id) };
+    (result == 0).then_some(id)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_thread_cpu_times() -> Option<HashMap<u64, Duration>> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+fn current_thread_id() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribution_excludes_sampler_thread_and_normalizes_by_cores() {
+        let previous = HashMap::from([(1, Duration::from_millis(100)), (2, Duration::from_millis(50))]);
+        let current = HashMap::from([
+            (1, Duration::from_millis(900)),  // +800ms
+            (2, Duration::from_millis(850)),  // sampler thread, excluded
+            (3, Duration::from_millis(800)),  // new thread, counted from zero
+            (4, Duration::ZERO),              // idle
+        ]);
+
+        // 1.6s of CPU over 1s on 4 cores = 40%
+        let attribution = attribute(&previous, &current, Some(2), Duration::from_secs(1), 4);
+        assert!((attribution.inference_utilization - 40.0).abs() < 1e-9);
+        assert_eq!(attribution.active_threads, 2);
+    }
+
+    #[test]
+    fn test_attribution_handles_zero_wall_time() {
+        let times = HashMap::from([(1, Duration::from_millis(10))]);
+        let attribution = attribute(&HashMap::new(), &times, None, Duration::ZERO, 8);
+        assert_eq!(attribution.inference_utilization, 0.0);
+    }
+}
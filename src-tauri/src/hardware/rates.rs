@@ -0,0 +1,115 @@
+// Rate-of-change metrics derived during aggregation. Each rate is the least-squares slope of
+// its signal over a short trailing window, which follows real heating and power ramps while
+// averaging out the sensor noise that differencing consecutive samples amplifies.
+
+use std::collections::VecDeque;
+
+use crate::TelemetryUpdate;
+
+// Trailing window each slope is fitted over
+pub const RATE_WINDOW_MS: u64 = 10_000;
+
+// Fewer readings than this in the window leave the rate unset
+const MIN_RATE_SAMPLES: usize = 3;
+
+/// Readings of one signal within the trailing window
+#[derive(Debug, Clone, Default)]
+struct SlopeWindow {
+    samples: VecDeque<(u64, f64)>,   // (timestamp_ms, value)
+}
+
+impl SlopeWindow {
+    /// Add this tick's reading (if any) and return the slope per second over the window
+    fn push(&mut self, timestamp_ms: u64, value: Option<f64>) -> Option<f64> {
+        // A clock that went backwards invalidates everything before it
+        if self.samples.back().is_some_and(|&(last_ms, _)| timestamp_ms < last_ms) {
+            self.samples.clear();
+        }
+        while self.samples.front().is_some_and(|&(ms, _)| timestamp_ms - ms > RATE_WINDOW_MS) {
+            self.samples.pop_front();
+        }
+        let value = value.filter(|value| value.is_finite())?;
+        self.samples.push_back((timestamp_ms, value));
+        self.slope_per_s()
+    }
+
+    fn slope_per_s(&self) -> Option<f64> {
+        if self.samples.len() < MIN_RATE_SAMPLES {
+            return None;
+        }
+        // Times relative to the first reading keep the sums well conditioned
+        let origin_ms = self.samples[0].0;
+        let n = self.samples.len() as f64;
+        let (sum_t, sum_v) = self.samples.iter()
+            .fold((0.0, 0.0), |(sum_t, sum_v), &(ms, value)| (sum_t + (ms - origin_ms) as f64 / 1000.0, sum_v + value));
+        let (mean_t, mean_v) = (sum_t / n, sum_v / n);
+        let (covariance, variance) = self.samples.iter().fold((0.0, 0.0), |(covariance, variance), &(ms, value)| {
+            let dt = (ms - origin_ms) as f64 / 1000.0 - mean_t;
+            (covariance + dt * (value - mean_v), variance + dt * dt)
+        });
+        (variance > 0.0).then(|| covariance / variance)
+    }
+}
+
+/// Per-signal windows carried across ticks by the monitor (and by debug capture replay)
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryRates {
+    cpu_temp: SlopeWindow,
+    gpu_temp: SlopeWindow,
+    battery_temp: SlopeWindow,
+    package_power: SlopeWindow,
+}
+
+impl TelemetryRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fill the update's rate fields from its readings and the ones before it
+    pub fn apply(&mut self, telemetry: &mut TelemetryUpdate) {
+        let timestamp_ms = telemetry.timestamp_ms;
+        let power = [telemetry.cpu_power_watts, telemetry.gpu_power_watts, telemetry.ane_power_watts];
+        let package_power = power.iter().any(Option::is_some).then(|| power.iter().flatten().sum());
+        telemetry.cpu_temp_rate_c_per_s = self.cpu_temp.push(timestamp_ms, telemetry.cpu_temp_avg);
+        telemetry.gpu_temp_rate_c_per_s = self.gpu_temp.push(timestamp_ms, telemetry.gpu_temp_avg);
+        telemetry.battery_temp_rate_c_per_s = self.battery_temp.push(timestamp_ms, telemetry.battery_temp_avg);
+        telemetry.package_power_rate_w_per_s = self.package_power.push(timestamp_ms, package_power);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp_ms: u64, cpu_temp: Option<f64>, cpu_w: Option<f64>) -> TelemetryUpdate {
+        TelemetryUpdate {
+            timestamp_ms,
+            cpu_temp_avg: cpu_temp,
+            cpu_power_watts: cpu_w,
+            ..TelemetryUpdate::default()
+        }
+    }
+
+    #[test]
+    fn test_rates_fit_a_noisy_ramp_over_the_window() {
+        let mut rates = TelemetryRates::new();
+        // Heating at 0.5 °C/s with ±0.3 °C sensor noise; power steps up by 1 W/s
+        let noise = [0.3, -0.3, 0.3, -0.3, 0.3, -0.3];
+        let mut last = TelemetryUpdate::default();
+        for (second, noise) in noise.iter().enumerate() {
+            last = sample(second as u64 * 1000, Some(50.0 + 0.5 * second as f64 + noise), Some(5.0 + second as f64));
+            rates.apply(&mut last);
+            if second < 2 {
+                assert_eq!(last.cpu_temp_rate_c_per_s, None);
+            }
+        }
+        assert!((last.cpu_temp_rate_c_per_s.unwrap() - 0.5).abs() < 0.15);
+        assert!((last.package_power_rate_w_per_s.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(last.gpu_temp_rate_c_per_s, None);
+
+        // After a long gap only the newest reading is left in the window
+        let mut resumed = sample(60_000, Some(70.0), Some(5.0));
+        rates.apply(&mut resumed);
+        assert_eq!((resumed.cpu_temp_rate_c_per_s, resumed.package_power_rate_w_per_s), (None, None));
+    }
+}
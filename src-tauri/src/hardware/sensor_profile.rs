@@ -0,0 +1,236 @@
+// Community sensor-mapping profiles: per-chip rules assigning IOHID temperature sensors to
+// chip areas, bundled from src-tauri/sensor_profiles/, with the built-in name heuristics
+// as the fallback for sensors no profile covers
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hardware::cpu_monitor::get_apple_chip_name;
+
+/// Identifies the profile layout (src-tauri/sensor_profiles/*.json)
+pub const SENSOR_PROFILE_SCHEMA: &str = "apples2oranges.sensor-profile/v1";
+
+/// (file name, contents) of every profile shipped with the app
+const BUNDLED_SENSOR_PROFILES: &[(&str, &str)] = &[
+    ("m1-m2-family.json", include_str!("../../sensor_profiles/m1-m2-family.json")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorCategory {
+    PCore,
+    ECore,
+    Gpu,
+    Battery,
+    Other,      // Unmapped; used for the P/E heuristic split when nothing else matched
+    Ignore,     // Not on the SoC (e.g. NAND); left out of every statistic
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensorProfile {
+    pub schema: String,
+    pub name: String,
+    pub chips: Vec<String>,             // "M3" covers "M3", "M3 Pro", "M3 Max"
+    #[serde(default)]
+    pub p_cores: Vec<String>,           // Sensor name substrings per category
+    #[serde(default)]
+    pub e_cores: Vec<String>,
+    #[serde(default)]
+    pub gpu: Vec<String>,
+    #[serde(default)]
+    pub battery: Vec<String>,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub dies: Vec<Vec<String>>,         // Multi-die chips: name substrings per die (index = die)
+}
+
+impl SensorProfile {
+    /// Category for a sensor name, or None when the profile has no rule for it
+    pub fn category_for(&self, sensor_name: &str) -> Option<SensorCategory> {
+        [
+            (&self.ignore, SensorCategory::Ignore),
+            (&self.p_cores, SensorCategory::PCore),
+            (&self.e_cores, SensorCategory::ECore),
+            (&self.gpu, SensorCategory::Gpu),
+            (&self.battery, SensorCategory::Battery),
+        ]
+        .into_iter()
+        .find(|(patterns, _)| patterns.iter().any(|pattern| sensor_name.contains(pattern.as_str())))
+        .map(|(_, category)| category)
+    }
+
+    /// Length of the most specific `chips` entry matching the chip name (None if none do)
+    fn chip_match_len(&self, chip: &str) -> Option<usize> {
+        self.chips.iter()
+            .filter(|entry| {
+                chip == entry.as_str()
+                    || chip.strip_prefix(entry.as_str()).is_some_and(|rest| rest.starts_with(' '))
+            })
+            .map(String::len)
+            .max()
+    }
+}
+
+/// Built-in categorization by IOHID naming patterns, used when no profile rule applies
+pub fn builtin_sensor_category(name: &str) -> SensorCategory {
+    let lower = name.to_lowercase();
+    if name.contains("pACC MTR Temp Sensor") || lower.contains("performance") {
+        SensorCategory::PCore
+    } else if name.contains("eACC MTR Temp Sensor") || lower.contains("efficiency") {
+        SensorCategory::ECore
+    } else if name.contains("GPU MTR Temp Sensor") || lower.contains("gpu") {
+        SensorCategory::Gpu
+    } else if name.contains("gas gauge battery") {
+        SensorCategory::Battery
+    } else {
+        SensorCategory::Other
+    }
+}
+
+/// Dies in the package: Ultra chips are two Max dies joined by UltraFusion
+pub fn chip_die_count(chip: &str) -> usize {
+    if chip.contains("Ultra") { 2 } else { 1 }
+}
+
+/// Die index for each reading. Profile `dies` rules decide first; otherwise repeated
+/// sensor names alternate between dies, as each die exposes the same sensor set.
+pub fn assign_dies(readings: &[(String, f64)], profile: Option<&SensorProfile>, die_count: usize) -> Vec<usize> {
+    if die_count <= 1 {
+        return vec![0; readings.len()];
+    }
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    readings.iter()
+        .map(|(name, _)| {
+            let occurrence = occurrences.entry(name.as_str()).or_insert(0);
+            let die = profile
+                .and_then(|profile| {
+                    profile.dies.iter()
+                        .take(die_count)
+                        .position(|patterns| patterns.iter().any(|pattern| name.contains(pattern.as_str())))
+                })
+                .unwrap_or(*occurrence % die_count);
+            *occurrence += 1;
+            die
+        })
+        .collect()
+}
+
+/// Profile rule first, then the built-in heuristics. Returns whether a profile decided.
+pub fn categorize_sensor(name: &str, profile: Option<&SensorProfile>) -> (SensorCategory, bool) {
+    match profile.and_then(|profile| profile.category_for(name)) {
+        Some(category) => (category, true),
+        None => (builtin_sensor_category(name), false),
+    }
+}
+
+fn parse_sensor_profile(file_name: &str, json: &str) -> Result<SensorProfile, String> {
+    let profile: SensorProfile = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid sensor profile {}: {}", file_name, e))?;
+    if profile.schema != SENSOR_PROFILE_SCHEMA {
+        return Err(format!("Sensor profile {} has unsupported schema '{}'", file_name, profile.schema));
+    }
+    if profile.chips.is_empty() {
+        return Err(format!("Sensor profile {} lists no chips", file_name));
+    }
+    Ok(profile)
+}
+
+/// Every bundled profile; invalid ones are logged and skipped
+pub fn bundled_sensor_profiles() -> Vec<SensorProfile> {
+    BUNDLED_SENSOR_PROFILES.iter()
+        .filter_map(|(file_name, json)| {
+            parse_sensor_profile(file_name, json)
+                .map_err(|e| println!("⚠️ {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+/// The profile whose `chips` entry matches the chip most specifically
+pub fn profile_for_chip(profiles: &[SensorProfile], chip: &str) -> Option<SensorProfile> {
+    profiles.iter()
+        .filter_map(|profile| profile.chip_match_len(chip).map(|len| (len, profile)))
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, profile)| profile.clone())
+}
+
+lazy_static::lazy_static! {
+    static ref ACTIVE_CHIP: Option<String> = get_apple_chip_name();
+    static ref ACTIVE_SENSOR_PROFILE: Option<SensorProfile> = {
+        let chip = ACTIVE_CHIP.as_ref()?;
+        let profile = profile_for_chip(&bundled_sensor_profiles(), chip);
+        match &profile {
+            Some(profile) => println!("🗺️  Using sensor profile '{}' for {}", profile.name, chip),
+            None => println!("🗺️  No sensor profile for {}, using built-in sensor heuristics", chip),
+        }
+        profile
+    };
+}
+
+/// Profile for this machine's chip, resolved once
+pub fn active_sensor_profile() -> Option<&'static SensorProfile> {
+    ACTIVE_SENSOR_PROFILE.as_ref()
+}
+
+/// Dies on this machine's chip (1 when the chip is unknown)
+pub fn active_die_count() -> usize {
+    ACTIVE_CHIP.as_deref().map_or(1, chip_die_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_profiles_parse_and_match_chip_families() {
+        let profiles = bundled_sensor_profiles();
+        assert_eq!(profiles.len(), BUNDLED_SENSOR_PROFILES.len());
+        assert_eq!(profile_for_chip(&profiles, "M2 Max").unwrap().name, "M1/M2 family");
+        assert!(profile_for_chip(&profiles, "M10").is_none());
+
+        let mut specific = profiles[0].clone();
+        specific.name = "M2 Max".to_string();
+        specific.chips = vec!["M2 Max".to_string()];
+        let with_specific = [profiles[0].clone(), specific];
+        assert_eq!(profile_for_chip(&with_specific, "M2 Max").unwrap().name, "M2 Max");
+        assert_eq!(profile_for_chip(&with_specific, "M2 Pro").unwrap().name, "M1/M2 family");
+    }
+
+    #[test]
+    fn test_profile_rules_take_precedence_over_builtin_heuristics() {
+        let profile = SensorProfile {
+            schema: SENSOR_PROFILE_SCHEMA.to_string(),
+            name: "test".to_string(),
+            chips: vec!["M9".to_string()],
+            p_cores: vec!["PMU tdie".to_string()],
+            ignore: vec!["GPU NAND".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(categorize_sensor("PMU tdie3", Some(&profile)), (SensorCategory::PCore, true));
+        assert_eq!(categorize_sensor("GPU NAND temp", Some(&profile)), (SensorCategory::Ignore, true));
+        assert_eq!(categorize_sensor("GPU MTR Temp Sensor1", Some(&profile)), (SensorCategory::Gpu, false));
+        assert_eq!(categorize_sensor("PMU tdie3", None), (SensorCategory::Other, false));
+        assert!(parse_sensor_profile("bad.json", r#"{"schema":"x","name":"n","chips":["M1"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_ultra_sensors_are_split_across_dies() {
+        assert_eq!(chip_die_count("M2 Ultra"), 2);
+        assert_eq!(chip_die_count("M2 Max"), 1);
+
+        let readings: Vec<(String, f64)> = ["pACC MTR Temp Sensor0", "GPU MTR Temp Sensor1", "pACC MTR Temp Sensor0", "GPU MTR Temp Sensor1"]
+            .iter()
+            .map(|name| (name.to_string(), 60.0))
+            .collect();
+        assert_eq!(assign_dies(&readings, None, 2), vec![0, 0, 1, 1]);
+        assert_eq!(assign_dies(&readings, None, 1), vec![0, 0, 0, 0]);
+
+        let profile = SensorProfile {
+            dies: vec![vec!["Sensor0".to_string()], vec!["Sensor1".to_string()]],
+            ..Default::default()
+        };
+        assert_eq!(assign_dies(&readings, Some(&profile), 2), vec![0, 1, 0, 1]);
+    }
+}
@@ -5,6 +5,10 @@ use std::ffi::CStr;
 use std::ptr;
 use serde::{Serialize, Deserialize};
 
+use crate::hardware::sensor_profile::{
+    active_die_count, active_sensor_profile, assign_dies, categorize_sensor, SensorCategory, SensorProfile,
+};
+
 // Temperature monitoring structs - Priority 4.2 extraction
 #[derive(Debug, Deserialize)]
 pub struct TemperatureInfo {
@@ -24,6 +28,29 @@ pub struct CoreTemperatureData {
     pub gpu_temp_max: Option<f64>,
     pub battery_temp_avg: Option<f64>,  // Battery temperature average
     pub thermal_trend: ThermalTrend,
+    pub dies: Option<Vec<DieTemperatureData>>,  // Per-die breakdown on multi-die (Ultra) chips
+}
+
+/// Sensors of one die on a multi-die chip. macmon only reports package-level power,
+/// so there is no per-die power to attach.
+#[derive(Debug, Clone, Serialize)]
+pub struct DieTemperatureData {
+    pub die: usize,
+    pub p_cores: Vec<f64>,
+    pub e_cores: Vec<f64>,
+    pub gpu_temps: Vec<f64>,
+    pub cpu_temp_avg: Option<f64>,
+    pub gpu_temp_avg: Option<f64>,
+}
+
+impl DieTemperatureData {
+    fn new(die: usize) -> Self {
+        Self { die, p_cores: Vec::new(), e_cores: Vec::new(), gpu_temps: Vec::new(), cpu_temp_avg: None, gpu_temp_avg: None }
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -268,6 +295,11 @@ impl TemperatureHistory {
 }
 
 pub async fn read_core_temperatures() -> Result<CoreTemperatureData, String> {
+    categorize_temperature_readings(&read_temperature_readings()?)
+}
+
+/// Raw (sensor name, °C) readings from every IOHID temperature sensor
+pub fn read_temperature_readings() -> Result<Vec<(String, f64)>, String> {
     println!("🔍 Starting IOHIDEventSystemClient temperature sensor detection...");
     
     let sensors = match IOHIDTemperatureSensors::new() {
@@ -290,7 +322,22 @@ pub async fn read_core_temperatures() -> Result<CoreTemperatureData, String> {
     };
     
     println!("📊 Found {} temperature sensors", temperature_readings.len());
-    
+    Ok(temperature_readings)
+}
+
+/// Group raw sensor readings by chip area and compute the CPU/GPU/battery statistics.
+/// Separate from the IOHID read so debug captures can be replayed through it.
+pub fn categorize_temperature_readings(temperature_readings: &[(String, f64)]) -> Result<CoreTemperatureData, String> {
+    categorize_temperature_readings_with_profile(temperature_readings, active_sensor_profile(), active_die_count())
+}
+
+/// As `categorize_temperature_readings`, with an explicit sensor profile (None: built-in
+/// heuristics only) and die count
+pub fn categorize_temperature_readings_with_profile(
+    temperature_readings: &[(String, f64)],
+    profile: Option<&SensorProfile>,
+    die_count: usize,
+) -> Result<CoreTemperatureData, String> {
     if temperature_readings.is_empty() {
         return Err("No temperature sensors found via IOHIDEventSystemClient".to_string());
     }
@@ -301,31 +348,44 @@ pub async fn read_core_temperatures() -> Result<CoreTemperatureData, String> {
     let mut gpu_temps = Vec::new();
     let mut battery_sensors = Vec::new();
     let mut other_temps = Vec::new();
+    let sensor_dies = assign_dies(temperature_readings, profile, die_count);
+    let mut dies: Vec<DieTemperatureData> = (0..die_count.max(1)).map(DieTemperatureData::new).collect();
     
     println!("🔍 SENSOR CATEGORIZATION: Starting categorization of {} sensors...", temperature_readings.len());
     
-    for (name, temp) in &temperature_readings {
-        println!("🏷️  DETAILED: Categorizing sensor '{}': {:.1}°C", name, temp);
+    for ((name, temp), &die) in temperature_readings.iter().zip(&sensor_dies) {
+        println!("🏷️  DETAILED: Categorizing sensor '{}' (die {}): {:.1}°C", name, die, temp);
         
-        // Categorize sensors by chip area based on naming patterns:
-        if name.contains("pACC MTR Temp Sensor") || name.to_lowercase().contains("performance") {
-            println!("   ⚡ MATCH: Performance area sensor -> adding {:.1}°C to p_cores", temp);
-            p_cores.push(*temp);
-        } else if name.contains("eACC MTR Temp Sensor") || name.to_lowercase().contains("efficiency") {
-            println!("   🔋 MATCH: Efficiency area sensor -> adding {:.1}°C to e_cores", temp);
-            e_cores.push(*temp);
-        } else if name.contains("GPU MTR Temp Sensor") || name.to_lowercase().contains("gpu") {
-            println!("   🎮 MATCH: GPU sensor -> adding {:.1}°C to gpu_temps", temp);
-            gpu_temps.push(*temp);
-        } else if name.contains("gas gauge battery") {
-            println!("   🔋 MATCH: Battery sensor -> adding {:.1}°C to battery_sensors", temp);
-            battery_sensors.push(*temp);
-        } else if name.to_lowercase().contains("cpu") || name.to_lowercase().contains("core") || name.to_lowercase().contains("processor") {
-            println!("   🏃 MATCH: Generic CPU sensor -> adding {:.1}°C to other_temps", temp);
-            other_temps.push(*temp);
-        } else {
-            println!("   ❓ NO MATCH: Other sensor -> adding {:.1}°C to other_temps", temp);
-            other_temps.push(*temp);
+        // Categorize sensors by chip area: sensor profile rules first, then naming patterns
+        let (category, from_profile) = categorize_sensor(name, profile);
+        let source = if from_profile { "profile" } else { "built-in" };
+        match category {
+            SensorCategory::PCore => {
+                println!("   ⚡ MATCH ({}): Performance area sensor -> adding {:.1}°C to p_cores", source, temp);
+                p_cores.push(*temp);
+                dies[die].p_cores.push(*temp);
+            }
+            SensorCategory::ECore => {
+                println!("   🔋 MATCH ({}): Efficiency area sensor -> adding {:.1}°C to e_cores", source, temp);
+                e_cores.push(*temp);
+                dies[die].e_cores.push(*temp);
+            }
+            SensorCategory::Gpu => {
+                println!("   🎮 MATCH ({}): GPU sensor -> adding {:.1}°C to gpu_temps", source, temp);
+                gpu_temps.push(*temp);
+                dies[die].gpu_temps.push(*temp);
+            }
+            SensorCategory::Battery => {
+                println!("   🔋 MATCH ({}): Battery sensor -> adding {:.1}°C to battery_sensors", source, temp);
+                battery_sensors.push(*temp);
+            }
+            SensorCategory::Ignore => {
+                println!("   🚫 IGNORED (profile): Sensor not on the SoC -> skipping {:.1}°C", temp);
+            }
+            SensorCategory::Other => {
+                println!("   ❓ NO MATCH: Other sensor -> adding {:.1}°C to other_temps", temp);
+                other_temps.push(*temp);
+            }
         }
     }
     
@@ -401,10 +461,27 @@ pub async fn read_core_temperatures() -> Result<CoreTemperatureData, String> {
              cpu_temp_avg, cpu_temp_max, cpu_temp_min, p_cores.len(), e_cores.len(), gpu_temps.len(), battery_sensors.len());
     
     println!("📊 Sensor breakdown:");
-    for (name, temp) in &temperature_readings {
+    for (name, temp) in temperature_readings {
         println!("   🌡️  {}: {:.1}°C", name, temp);
     }
     
+    // Per-die statistics, only when sensors actually landed on more than one die
+    for die in &mut dies {
+        let die_cpu_temps: Vec<f64> = die.p_cores.iter().chain(die.e_cores.iter()).copied().collect();
+        die.cpu_temp_avg = average(&die_cpu_temps);
+        die.gpu_temp_avg = average(&die.gpu_temps);
+    }
+    let populated_dies = dies.iter().filter(|die| die.cpu_temp_avg.is_some() || die.gpu_temp_avg.is_some()).count();
+    let dies = if populated_dies > 1 {
+        for die in &dies {
+            println!("   🧩 Die {}: p_cores={}, e_cores={}, gpu_temps={}, cpu_avg={:?}, gpu_avg={:?}",
+                     die.die, die.p_cores.len(), die.e_cores.len(), die.gpu_temps.len(), die.cpu_temp_avg, die.gpu_temp_avg);
+        }
+        Some(dies)
+    } else {
+        None
+    };
+    
     Ok(CoreTemperatureData {
         p_cores,
         e_cores,
@@ -416,5 +493,34 @@ pub async fn read_core_temperatures() -> Result<CoreTemperatureData, String> {
         gpu_temp_max,
         battery_temp_avg,
         thermal_trend: ThermalTrend::Stable, // Will be updated by history tracking
+        dies,
     })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ultra_readings_get_per_die_breakdown() {
+        let readings: Vec<(String, f64)> = [
+            ("pACC MTR Temp Sensor0", 60.0), ("eACC MTR Temp Sensor1", 50.0), ("GPU MTR Temp Sensor4", 54.0),
+            ("pACC MTR Temp Sensor0", 70.0), ("eACC MTR Temp Sensor1", 52.0), ("GPU MTR Temp Sensor4", 58.0),
+        ]
+        .iter()
+        .map(|(name, temp)| (name.to_string(), *temp))
+        .collect();
+
+        let ultra = categorize_temperature_readings_with_profile(&readings, None, 2).unwrap();
+        assert_eq!(ultra.p_cores, vec![60.0, 70.0]);
+        let dies = ultra.dies.unwrap();
+        assert_eq!(dies.len(), 2);
+        assert_eq!(dies[1].p_cores, vec![70.0]);
+        assert_eq!(dies[0].cpu_temp_avg, Some(55.0));
+        assert_eq!(dies[1].gpu_temp_avg, Some(58.0));
+
+        let single = categorize_temperature_readings_with_profile(&readings, None, 1).unwrap();
+        assert!(single.dies.is_none());
+        assert_eq!(single.cpu_temp_avg, ultra.cpu_temp_avg);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+// OS thermal pressure level (OSThermalNotification), read through the libSystem notify API
+// the same way ProcessInfo.thermalState is derived. Rises above "nominal" when macOS starts
+// throttling, which per-sensor temperatures alone do not show.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+
+// kOSThermalNotificationPressureLevelName
+const THERMAL_PRESSURE_NOTIFICATION: &str = "com.apple.system.thermalpressurelevel";
+const NOTIFY_STATUS_OK: u32 = 0;
+
+extern "C" {
+    fn notify_register_check(name: *const c_char, out_token: *mut c_int) -> u32;
+    fn notify_get_state(token: c_int, state: *mut u64) -> u32;
+    fn notify_cancel(token: c_int) -> u32;
+}
+
+/// Name of an OSThermalPressureLevel, as sent in `TelemetryUpdate.thermal_pressure`
+pub fn thermal_pressure_name(level: u64) -> &'static str {
+    match level {
+        0 => "nominal",
+        1 => "moderate",
+        2 => "heavy",
+        3 => "trapping",
+        4 => "sleeping",
+        _ => "unknown",
+    }
+}
+
+/// Registration with the thermal pressure notification; reading the level is a cheap state
+/// lookup, so it is done on every tick
+pub struct ThermalPressureMonitor {
+    token: c_int,
+}
+
+impl ThermalPressureMonitor {
+    pub fn new() -> Result<Self, String> {
+        let name = CString::new(THERMAL_PRESSURE_NOTIFICATION).unwrap();
+        let mut token: c_int = 0;
+        let status = unsafe { notify_register_check(name.as_ptr(), &mut token) };
+        if status != NOTIFY_STATUS_OK {
+            return Err(format!("notify_register_check failed with status {}", status));
+        }
+        Ok(Self { token })
+    }
+
+    pub fn read(&self) -> Option<&'static str> {
+        let mut level: u64 = 0;
+        let status = unsafe { notify_get_state(self.token, &mut level) };
+        (status == NOTIFY_STATUS_OK).then(|| thermal_pressure_name(level))
+    }
+}
+
+impl Drop for ThermalPressureMonitor {
+    fn drop(&mut self) {
+        unsafe {
+            notify_cancel(self.token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thermal_pressure_names() {
+        assert_eq!(thermal_pressure_name(0), "nominal");
+        assert_eq!(thermal_pressure_name(2), "heavy");
+        assert_eq!(thermal_pressure_name(4), "sleeping");
+        assert_eq!(thermal_pressure_name(42), "unknown");
+    }
+}
@@ -0,0 +1,63 @@
+// Experimental core-type preference for inference threads via pthread QoS classes
+//
+// macOS has no hard core pinning; QoS is the supported way to steer work. Background QoS
+// keeps threads on E-cores, user-interactive QoS lets the scheduler favour P-cores.
+// llama.cpp worker threads inherit the QoS of the thread that spawns them, so the class
+// is applied to the inference thread before the model and context are created.
+
+use crate::telemetry::types::CorePreference;
+
+/// Applies a QoS class to the current thread and restores the previous one on drop
+/// (inference runs on a pooled blocking thread that is reused afterwards)
+pub struct QosGuard {
+    #[cfg(target_os = "macos")]
+    previous: Option<libc::qos_class_t>,
+}
+
+impl QosGuard {
+    #[cfg(target_os = "macos")]
+    pub fn apply(preference: CorePreference) -> Self {
+        let qos_class = match preference {
+            CorePreference::Default => return Self { previous: None },
+            CorePreference::Performance => libc::qos_class_t::QOS_CLASS_USER_INTERACTIVE,
+            CorePreference::Efficiency => libc::qos_class_t::QOS_CLASS_BACKGROUND,
+        };
+
+        let mut previous = libc::qos_class_t::QOS_CLASS_UNSPECIFIED;
+        let mut relative_priority: libc::c_int = 0;
+        let read_result = unsafe {
+            libc::pthread_get_qos_class_np(libc::pthread_self(), &mut previous, &mut relative_priority)
+        };
+
+        let result = unsafe { libc::pthread_set_qos_class_self_np(qos_class, 0) };
+        if result != 0 {
+            println!("⚠️ Failed to set thread QoS for {:?} core preference (error {})", preference, result);
+            return Self { previous: None };
+        }
+
+        println!("🧵 Inference thread QoS set for {:?} core preference", preference);
+        Self { previous: if read_result == 0 { Some(previous) } else { None } }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn apply(preference: CorePreference) -> Self {
+        if preference != CorePreference::Default {
+            println!("⚠️ Core preference {:?} is only supported on macOS; ignoring", preference);
+        }
+        Self {}
+    }
+}
+
+impl Drop for QosGuard {
+    #[cfg(target_os = "macos")]
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            unsafe {
+                libc::pthread_set_qos_class_self_np(previous, 0);
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn drop(&mut self) {}
+}
@@ -0,0 +1,157 @@
+// Chat template application through llama.cpp's template engine.
+// Needs only the template string (not a loaded model), so prompt formatting can be
+// snapshot-tested against templates recorded from real GGUF files.
+
+use std::ffi::{CStr, CString};
+
+use llama_cpp_sys_2::{llama_chat_apply_template, llama_chat_message};
+
+/// One conversation turn, validated for the C API (no interior NUL bytes)
+pub struct ChatTurn {
+    role: CString,
+    content: CString,
+}
+
+impl ChatTurn {
+    pub fn new(role: &str, content: &str) -> Result<Self, String> {
+        Ok(Self {
+            role: CString::new(role).map_err(|e| format!("Invalid chat role '{}': {}", role, e))?,
+            content: CString::new(content).map_err(|e| format!("Invalid chat message for role '{}': {}", role, e))?,
+        })
+    }
+}
+
+/// Templates selectable by name as a per-model override, with the llama.cpp template
+/// each one maps to
+pub const BUILTIN_TEMPLATES: [(&str, &str); 3] = [
+    ("chatml", "chatml"),
+    ("llama3", "llama3"),
+    // Mistral Instruct v0.1/v0.2: Llama 2 style [INST] turns without a system block
+    ("mistral", "llama2"),
+];
+
+/// Template for a model's `chat_template_override`: a built-in name (case-insensitive) or
+/// a Jinja template, which llama.cpp matches against the templates it knows
+pub fn resolve_template_override(template: &str) -> Result<CString, String> {
+    let template = template.trim();
+    if let Some((_, llama_name)) = BUILTIN_TEMPLATES.iter().find(|(name, _)| name.eq_ignore_ascii_case(template)) {
+        return Ok(CString::new(*llama_name).unwrap());
+    }
+    if !template.contains("{{") && !template.contains("{%") {
+        let names: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|(name, _)| *name).collect();
+        return Err(format!("Unknown chat template '{}': use one of {} or a Jinja template", template, names.join(", ")));
+    }
+    CString::new(template).map_err(|e| format!("Invalid chat template override: {}", e))
+}
+
+/// Format `turns` with `template` (a GGUF Jinja template or a llama.cpp template name
+/// such as "chatml"); `add_assistant` appends the assistant header for generation
+pub fn format_chat_prompt(template: &CStr, turns: &[ChatTurn], add_assistant: bool) -> Result<String, String> {
+    let messages: Vec<llama_chat_message> = turns.iter()
+        .map(|turn| llama_chat_message { role: turn.role.as_ptr(), content: turn.content.as_ptr() })
+        .collect();
+
+    // llama.cpp returns the full length when the buffer is too small, so grow once and retry
+    let text_len: usize = turns.iter().map(|turn| turn.role.as_bytes().len() + turn.content.as_bytes().len()).sum();
+    let mut buf = vec![0u8; (text_len * 2).max(256)];
+    loop {
+        let buf_len = i32::try_from(buf.len()).map_err(|_| "Chat prompt too large".to_string())?;
+        let written = unsafe {
+            llama_chat_apply_template(template.as_ptr(), messages.as_ptr(), messages.len(), add_assistant, buf.as_mut_ptr().cast(), buf_len)
+        };
+        if written < 0 {
+            return Err("Chat template is not supported by llama.cpp".to_string());
+        }
+        let written = written as usize;
+        if written > buf.len() {
+            buf.resize(written, 0);
+            continue;
+        }
+        buf.truncate(written);
+        return String::from_utf8(buf).map_err(|e| format!("Chat template produced invalid UTF-8: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Templates as stored in `tokenizer.chat_template` of the respective GGUF files
+    const LLAMA_3_TEMPLATE: &str = "{% set loop_messages = messages %}{% for message in loop_messages %}{% set content = '<|start_header_id|>' + message['role'] + '<|end_header_id|>\n\n'+ message['content'] | trim + '<|eot_id|>' %}{% if loop.index0 == 0 %}{% set content = bos_token + content %}{% endif %}{{ content }}{% endfor %}{% if add_generation_prompt %}{{ '<|start_header_id|>assistant<|end_header_id|>\n\n' }}{% endif %}";
+    const CHATML_TEMPLATE: &str = "{% for message in messages %}{{'<|im_start|>' + message['role'] + '\n' + message['content'] + '<|im_end|>' + '\n'}}{% endfor %}{% if add_generation_prompt %}{{ '<|im_start|>assistant\n' }}{% endif %}";
+    // Mistral-7B-Instruct-v0.2
+    const MISTRAL_TEMPLATE: &str = "{{ bos_token }}{% for message in messages %}{% if (message['role'] == 'user') != (loop.index0 % 2 == 0) %}{{ raise_exception('Conversation roles must alternate user/assistant/user/assistant/...') }}{% endif %}{% if message['role'] == 'user' %}{{ '[INST] ' + message['content'] + ' [/INST]' }}{% elif message['role'] == 'assistant' %}{{ message['content'] + eos_token}}{% else %}{{ raise_exception('Only user and assistant roles are supported!') }}{% endif %}{% endfor %}";
+
+    fn conversation() -> Vec<ChatTurn> {
+        [
+            ("system", "You are terse."),
+            ("user", "What is 2+2?"),
+            ("assistant", "4"),
+            ("user", " And 3+3? "),
+        ]
+        .iter()
+        .map(|(role, content)| ChatTurn::new(role, content).unwrap())
+        .collect()
+    }
+
+    fn format(template: &str) -> String {
+        format_chat_prompt(&CString::new(template).unwrap(), &conversation(), true).unwrap()
+    }
+
+    #[test]
+    fn test_llama_3_snapshot() {
+        assert_eq!(
+            format(LLAMA_3_TEMPLATE),
+            "<|start_header_id|>system<|end_header_id|>\n\nYou are terse.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nWhat is 2+2?<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n4<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nAnd 3+3?<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_chatml_snapshot() {
+        assert_eq!(
+            format(CHATML_TEMPLATE),
+            "<|im_start|>system\nYou are terse.<|im_end|>\n\
+             <|im_start|>user\nWhat is 2+2?<|im_end|>\n\
+             <|im_start|>assistant\n4<|im_end|>\n\
+             <|im_start|>user\n And 3+3? <|im_end|>\n\
+             <|im_start|>assistant\n"
+        );
+    }
+
+    #[test]
+    fn test_mistral_snapshot() {
+        // No system role support: the system prompt is prepended to the first user turn
+        assert_eq!(
+            format(MISTRAL_TEMPLATE),
+            "[INST] You are terse.\nWhat is 2+2? [/INST]4</s>[INST]  And 3+3?  [/INST]"
+        );
+    }
+
+    #[test]
+    fn test_long_conversations_grow_the_buffer() {
+        let long = "x".repeat(10_000);
+        let turns = vec![ChatTurn::new("user", &long).unwrap()];
+        let prompt = format_chat_prompt(&CString::new(CHATML_TEMPLATE).unwrap(), &turns, true).unwrap();
+        assert_eq!(prompt, format!("<|im_start|>user\n{}<|im_end|>\n<|im_start|>assistant\n", long));
+    }
+
+    #[test]
+    fn test_template_overrides_resolve_names_and_jinja() {
+        assert_eq!(resolve_template_override("ChatML").unwrap().to_str().unwrap(), "chatml");
+        assert_eq!(resolve_template_override(" mistral ").unwrap().to_str().unwrap(), "llama2");
+        assert_eq!(resolve_template_override(CHATML_TEMPLATE).unwrap().to_str().unwrap(), CHATML_TEMPLATE);
+        let unknown = resolve_template_override("vicuna").unwrap_err();
+        assert!(unknown.contains("chatml, llama3, mistral"), "{}", unknown);
+        assert!(resolve_template_override("{{ a\0b }}").is_err());
+    }
+
+    #[test]
+    fn test_unsupported_template_and_nul_bytes_are_errors() {
+        assert!(format_chat_prompt(&CString::new("not a template").unwrap(), &conversation(), true).is_err());
+        assert!(ChatTurn::new("user", "a\0b").is_err());
+    }
+}
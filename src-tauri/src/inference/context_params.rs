@@ -0,0 +1,162 @@
+// Context parameters llama.cpp actually used, read back after the context exists. A
+// silent fallback (no Metal, partial offload, a clamped batch) would otherwise make two
+// runs look comparable when they were not.
+
+use serde::{Deserialize, Serialize};
+
+/// Layers placed on the GPU at load, as reported by llama.cpp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GpuOffload {
+    pub offloaded_layers: u32,
+    pub total_layers: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectiveContextParams {
+    pub n_ctx: u32,
+    pub n_batch: u32,
+    pub n_ubatch: u32,
+    pub n_threads: i32,
+    pub n_threads_batch: i32,
+    pub n_gpu_layers_requested: Option<u32>,   // None = llama.cpp default (offload everything)
+    pub gpu_offload_supported: bool,           // False when llama.cpp was built or started without a GPU backend
+    pub gpu_offload: Option<GpuOffload>,       // None when llama.cpp did not report it
+    pub flash_attention: Option<String>,       // "enabled" / "disabled", as llama.cpp resolved it
+    pub warnings: Vec<String>,
+}
+
+/// "load_tensors: offloaded 33/33 layers to GPU"
+pub fn parse_gpu_offload(log: &str) -> Option<GpuOffload> {
+    log.lines().rev().find_map(|line| {
+        let rest = &line[line.find("offloaded ")? + "offloaded ".len()..];
+        let (counts, _) = rest.split_once(" layers to GPU")?;
+        let (offloaded, total) = counts.split_once('/')?;
+        Some(GpuOffload { offloaded_layers: offloaded.trim().parse().ok()?, total_layers: total.trim().parse().ok()? })
+    })
+}
+
+/// From "Flash Attention was auto, set to enabled" when llama.cpp chose, else from the
+/// "flash_attn = ..." line of the context parameters (0/1 in older builds)
+pub fn parse_flash_attention(log: &str) -> Option<String> {
+    let resolved = log.lines().find_map(|line| {
+        line.split_once("Flash Attention was auto, set to ").map(|(_, value)| value.trim().to_string())
+    });
+    resolved.or_else(|| {
+        log.lines().find_map(|line| {
+            let (_, value) = line.split_once("flash_attn")?;
+            let value = value.trim_start().strip_prefix('=')?.trim();
+            Some(match value {
+                "0" | "false" => "disabled".to_string(),
+                "1" | "true" => "enabled".to_string(),
+                other => other.to_string(),
+            })
+        })
+    })
+}
+
+impl EffectiveContextParams {
+    /// Warnings for settings that differ from what was requested
+    pub fn check_fallbacks(&mut self, model_label: &str, requested_n_ctx: u32, requested_n_batch: u32) {
+        let wants_gpu = self.n_gpu_layers_requested != Some(0);
+        if wants_gpu && !self.gpu_offload_supported {
+            self.warnings.push(format!("Model {}: GPU offload unavailable, running on the CPU only", model_label));
+        } else if let Some(offload) = self.gpu_offload.filter(|_| wants_gpu) {
+            let expected = self.n_gpu_layers_requested.unwrap_or(u32::MAX).min(offload.total_layers);
+            if offload.offloaded_layers < expected {
+                self.warnings.push(format!("Model {}: only {}/{} layers offloaded to the GPU ({} requested)",
+                                           model_label, offload.offloaded_layers, offload.total_layers, expected));
+            }
+        }
+        if self.n_ctx != requested_n_ctx {
+            self.warnings.push(format!("Model {}: n_ctx {} requested, llama.cpp uses {}", model_label, requested_n_ctx, self.n_ctx));
+        }
+        if self.n_batch != requested_n_batch {
+            self.warnings.push(format!("Model {}: n_batch {} requested, llama.cpp uses {}", model_label, requested_n_batch, self.n_batch));
+        }
+    }
+}
+
+/// Differences between two models' effective parameters that make their speed or energy
+/// not directly comparable
+pub fn compare_effective_params(a: &EffectiveContextParams, b: &EffectiveContextParams) -> Vec<String> {
+    let mut differences = Vec::new();
+    let fully_offloaded = |params: &EffectiveContextParams| {
+        params.gpu_offload_supported
+            && params.gpu_offload.map_or(params.n_gpu_layers_requested != Some(0), |o| o.offloaded_layers >= o.total_layers)
+    };
+    if fully_offloaded(a) != fully_offloaded(b) {
+        differences.push(format!("GPU offload differs: Model A {}, Model B {}",
+                                 describe_offload(a), describe_offload(b)));
+    }
+    if a.flash_attention != b.flash_attention {
+        differences.push(format!("Flash attention differs: Model A {}, Model B {}",
+                                 a.flash_attention.as_deref().unwrap_or("unknown"), b.flash_attention.as_deref().unwrap_or("unknown")));
+    }
+    if (a.n_threads, a.n_threads_batch) != (b.n_threads, b.n_threads_batch) {
+        differences.push(format!("Thread counts differ: Model A {}/{}, Model B {}/{}",
+                                 a.n_threads, a.n_threads_batch, b.n_threads, b.n_threads_batch));
+    }
+    if a.n_ubatch != b.n_ubatch {
+        differences.push(format!("n_ubatch differs: Model A {}, Model B {}", a.n_ubatch, b.n_ubatch));
+    }
+    differences
+}
+
+fn describe_offload(params: &EffectiveContextParams) -> String {
+    match params.gpu_offload {
+        _ if !params.gpu_offload_supported => "CPU only".to_string(),
+        Some(offload) => format!("{}/{} layers", offload.offloaded_layers, offload.total_layers),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "load_tensors: offloading 32 repeating layers to GPU\n\
+                       load_tensors: offloaded 20/33 layers to GPU\n\
+                       llama_context: n_ctx         = 4096\n\
+                       llama_context: flash_attn    = auto\n\
+                       llama_context: Flash Attention was auto, set to enabled\n";
+
+    fn params() -> EffectiveContextParams {
+        EffectiveContextParams {
+            n_ctx: 4096,
+            n_batch: 512,
+            n_ubatch: 512,
+            n_threads: 4,
+            n_threads_batch: 4,
+            n_gpu_layers_requested: None,
+            gpu_offload_supported: true,
+            gpu_offload: parse_gpu_offload(LOG),
+            flash_attention: parse_flash_attention(LOG),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parses_llama_cpp_log_and_flags_fallbacks() {
+        assert_eq!(parse_gpu_offload(LOG), Some(GpuOffload { offloaded_layers: 20, total_layers: 33 }));
+        assert_eq!(parse_flash_attention(LOG).as_deref(), Some("enabled"));
+        assert_eq!(parse_flash_attention("llama_context: flash_attn    = 0\n").as_deref(), Some("disabled"));
+        assert_eq!(parse_gpu_offload("nothing here"), None);
+
+        let mut partial = params();
+        partial.check_fallbacks("A", 4096, 1024);
+        assert_eq!(partial.warnings.len(), 2);
+        assert!(partial.warnings[0].contains("only 20/33 layers"));
+        assert!(partial.warnings[1].contains("n_batch 1024 requested"));
+
+        let mut cpu_only = params();
+        cpu_only.gpu_offload_supported = false;
+        cpu_only.check_fallbacks("B", 4096, 512);
+        assert_eq!(cpu_only.warnings, vec!["Model B: GPU offload unavailable, running on the CPU only"]);
+
+        let mut full = params();
+        full.gpu_offload = Some(GpuOffload { offloaded_layers: 33, total_layers: 33 });
+        let differences = compare_effective_params(&full, &cpu_only);
+        assert_eq!(differences, vec!["GPU offload differs: Model A 33/33 layers, Model B CPU only"]);
+        assert!(compare_effective_params(&full, &full).is_empty());
+    }
+}
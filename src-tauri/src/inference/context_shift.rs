@@ -0,0 +1,64 @@
+// Context shifting for generations longer than n_ctx (llama.cpp's context shift, with
+// StreamingLLM-style attention sinks): when the KV cache is full, half of the tokens after
+// a kept prefix are evicted and the rest moved down, so generation continues over a sliding
+// window instead of failing at the context limit.
+
+use llama_cpp_2::context::LlamaContext;
+
+/// Leading tokens always kept, even without a system prompt; attention concentrates on the
+/// first few positions, and evicting them degrades the output far more than later tokens
+pub const ATTENTION_SINK_TOKENS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextShift {
+    pub keep: i32,      // Prefix left in place
+    pub discard: i32,   // Tokens evicted right after the prefix
+}
+
+/// Tokens at the start of `prompt` that are never evicted: its common prefix with the
+/// tokenized system-prompt-only conversation (at least ATTENTION_SINK_TOKENS), capped at
+/// half the context so every shift frees space
+pub fn kept_prefix_len<T: PartialEq>(prompt: &[T], system_prefix: &[T], n_ctx: usize) -> usize {
+    let system_len = prompt.iter().zip(system_prefix).take_while(|(a, b)| a == b).count();
+    system_len.max(ATTENTION_SINK_TOKENS).min(prompt.len()).min(n_ctx / 2)
+}
+
+/// Shift for a full cache of `n_past` tokens: evict half of what follows the kept prefix.
+/// None when there is nothing to evict.
+pub fn plan_context_shift(n_past: i32, n_keep: i32) -> Option<ContextShift> {
+    let discard = (n_past - n_keep) / 2;
+    (discard > 0).then_some(ContextShift { keep: n_keep, discard })
+}
+
+/// Evict the shift's tokens from sequence 0 and move the following ones (up to `n_past`)
+/// down into their place; the next token then goes at `n_past - discard`
+pub fn apply_context_shift(ctx: &mut LlamaContext, shift: ContextShift, n_past: i32) -> Result<(), String> {
+    let start = shift.keep as u32;
+    let end = (shift.keep + shift.discard) as u32;
+    let removed = ctx.clear_kv_cache_seq(Some(0), Some(start), Some(end))
+        .map_err(|e| format!("Failed to evict KV cache tokens {}..{}: {:?}", start, end, e))?;
+    if !removed {
+        return Err(format!("llama.cpp could not evict KV cache tokens {}..{}", start, end));
+    }
+    ctx.kv_cache_seq_add(0, Some(end), Some(n_past as u32), -shift.discard)
+        .map_err(|e| format!("Failed to shift KV cache positions: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_keeps_prefix_and_halves_the_rest() {
+        let prompt = [1, 10, 11, 12, 13, 14, 20, 21, 22, 23];
+        // System prompt shared up to index 6, where the user turn starts
+        assert_eq!(kept_prefix_len(&prompt, &[1, 10, 11, 12, 13, 14, 99], 4096), 6);
+        assert_eq!(kept_prefix_len(&prompt, &[], 4096), ATTENTION_SINK_TOKENS);
+        assert_eq!(kept_prefix_len(&prompt[..2], &[], 4096), 2);
+        assert_eq!(kept_prefix_len(&prompt, &prompt, 8), 4);
+
+        assert_eq!(plan_context_shift(4096, 100), Some(ContextShift { keep: 100, discard: 1998 }));
+        assert_eq!(plan_context_shift(4096, 4), Some(ContextShift { keep: 4, discard: 2046 }));
+        assert_eq!(plan_context_shift(5, 4), None);
+    }
+}
@@ -0,0 +1,166 @@
+// Resolves the context size for a run, including "auto" sizing from GGUF metadata
+
+use std::path::Path;
+use sysinfo::System;
+
+use crate::telemetry::types::ContextSize;
+use crate::error::A2oError;
+use crate::inference::generation::DEFAULT_N_CTX;
+use crate::inference::gguf::{GgufMetadata, RopeScaling};
+
+// Fraction of system RAM a single model (weights + KV cache) may occupy
+pub const MEMORY_BUDGET_FRACTION: f64 = 0.75;
+
+// Auto-sized contexts are rounded down to this granularity; when memory leaves room for
+// less than the minimum, auto sizing fails rather than overcommitting
+const AUTO_N_CTX_GRANULARITY: u64 = 256;
+const AUTO_MIN_N_CTX: u64 = 512;
+
+#[derive(Debug, Clone)]
+pub struct ResolvedContextSize {
+    pub requested: ContextSize,
+    pub n_ctx: u32,
+    pub trained_n_ctx: Option<u32>,
+    pub memory_limit_n_ctx: Option<u32>,
+    pub rope_scaling: Option<RopeScaling>,
+    pub trained_context_warning: Option<String>,    // n_ctx beyond what the model was trained for
+}
+
+/// Total system memory in bytes, if sysinfo can report it
+pub fn system_memory_bytes() -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_memory();
+    let total_memory = system.total_memory();
+    if total_memory > 0 { Some(total_memory) } else { None }
+}
+
+/// Resolve the requested context size for a model file
+///
+/// Fixed sizes pass through unchanged (metadata is still read for reporting);
+/// "auto" uses the trained context length clamped to the memory budget, and fails when the
+/// budget cannot hold even AUTO_MIN_N_CTX tokens.
+pub fn resolve_context_size(requested: Option<ContextSize>, model_path: &Path) -> Result<ResolvedContextSize, A2oError> {
+    let requested = requested.unwrap_or(ContextSize::Fixed(DEFAULT_N_CTX));
+    let metadata = GgufMetadata::read_from_file(model_path);
+    if let Err(e) = &metadata {
+        println!("⚠️ Could not read GGUF metadata for context sizing: {}", e);
+    }
+    let metadata = metadata.ok();
+
+    let trained_n_ctx = metadata.as_ref().and_then(|m| m.context_length());
+    let rope_scaling = metadata.as_ref().and_then(|m| m.rope_scaling());
+    let kv_bytes_per_token = metadata.as_ref().and_then(|m| m.estimate_kv_cache_bytes(1));
+    let model_file_bytes = std::fs::metadata(model_path).map(|m| m.len()).unwrap_or(0);
+    let budget_bytes = system_memory_bytes()
+        .map(|total| (total as f64 * MEMORY_BUDGET_FRACTION) as u64)
+        .map(|budget| budget.saturating_sub(model_file_bytes));
+
+    let memory_limit_n_ctx = memory_limited_n_ctx(kv_bytes_per_token, budget_bytes);
+    let n_ctx = match requested {
+        ContextSize::Fixed(n_ctx) => n_ctx,
+        ContextSize::Auto => auto_n_ctx(trained_n_ctx, memory_limit_n_ctx)?,
+    };
+
+    Ok(ResolvedContextSize {
+        requested,
+        n_ctx,
+        trained_n_ctx: trained_n_ctx.map(clamp_u32),
+        memory_limit_n_ctx: memory_limit_n_ctx.map(clamp_u32),
+        trained_context_warning: trained_context_warning(n_ctx, trained_n_ctx, rope_scaling.as_ref()),
+        rope_scaling,
+    })
+}
+
+/// Warning when n_ctx goes past the trained context without RoPE scaling to cover it.
+/// Output degrades past that point, which otherwise gets blamed on the model itself.
+pub fn trained_context_warning(n_ctx: u32, trained_n_ctx: Option<u64>, rope_scaling: Option<&RopeScaling>) -> Option<String> {
+    let trained = trained_n_ctx?;
+    if n_ctx as u64 <= trained {
+        return None;
+    }
+    match rope_scaling {
+        None => Some(format!(
+            "n_ctx {} exceeds the model's trained context of {} tokens and the model declares no RoPE scaling; output past {} tokens will degrade",
+            n_ctx, trained, trained
+        )),
+        Some(scaling) => {
+            // Scaled models usually report the extended length already; otherwise extend it here
+            let scaled = scaling.factor
+                .filter(|factor| *factor > 1.0)
+                .map(|factor| (scaling.original_context_length.unwrap_or(trained) as f64 * factor) as u64)
+                .unwrap_or(trained)
+                .max(trained);
+            (n_ctx as u64 > scaled).then(|| format!(
+                "n_ctx {} exceeds the {} tokens the model's {} RoPE scaling covers; output past {} tokens will degrade",
+                n_ctx, scaled, scaling.kind, scaled
+            ))
+        }
+    }
+}
+
+/// Largest context (rounded down to the granularity) whose KV cache fits in the budget
+fn memory_limited_n_ctx(kv_bytes_per_token: Option<u64>, budget_bytes: Option<u64>) -> Option<u64> {
+    let per_token = kv_bytes_per_token.filter(|bytes| *bytes > 0)?;
+    let budget = budget_bytes?;
+    Some(budget / per_token / AUTO_N_CTX_GRANULARITY * AUTO_N_CTX_GRANULARITY)
+}
+
+fn auto_n_ctx(trained_n_ctx: Option<u64>, memory_limit_n_ctx: Option<u64>) -> Result<u32, A2oError> {
+    let trained = trained_n_ctx.unwrap_or(DEFAULT_N_CTX as u64);
+    match memory_limit_n_ctx {
+        Some(limit) if limit < AUTO_MIN_N_CTX.min(trained) => Err(A2oError::InvalidInput(format!(
+            "Auto n_ctx: the memory budget only fits a {} token KV cache, below the {} token minimum; free memory or set a fixed n_ctx",
+            limit, AUTO_MIN_N_CTX
+        ))),
+        Some(limit) => Ok(clamp_u32(trained.min(limit))),
+        None => Ok(clamp_u32(trained)),
+    }
+}
+
+fn clamp_u32(value: u64) -> u32 {
+    value.min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_uses_trained_context_when_memory_allows() {
+        assert_eq!(auto_n_ctx(Some(8192), Some(65536)).unwrap(), 8192);
+    }
+
+    #[test]
+    fn test_auto_clamps_to_memory_limit() {
+        // 128 KiB per token with a 1 GiB budget -> 8192 tokens
+        let limit = memory_limited_n_ctx(Some(128 * 1024), Some(1024 * 1024 * 1024));
+        assert_eq!(limit, Some(8192));
+        assert_eq!(auto_n_ctx(Some(131072), limit).unwrap(), 8192);
+    }
+
+    #[test]
+    fn test_trained_context_warning_respects_rope_scaling() {
+        assert!(trained_context_warning(8192, Some(8192), None).is_none());
+        assert!(trained_context_warning(16384, None, None).is_none());
+        assert!(trained_context_warning(16384, Some(8192), None).unwrap().contains("no RoPE scaling"));
+
+        let yarn = RopeScaling { kind: "yarn".to_string(), factor: Some(4.0), original_context_length: Some(32768) };
+        assert!(trained_context_warning(131072, Some(32768), Some(&yarn)).is_none());
+        assert!(trained_context_warning(262144, Some(32768), Some(&yarn)).unwrap().contains("131072"));
+    }
+
+    #[test]
+    fn test_auto_falls_back_without_metadata() {
+        assert_eq!(auto_n_ctx(None, None).unwrap(), DEFAULT_N_CTX);
+    }
+
+    #[test]
+    fn test_auto_fails_when_memory_fits_less_than_the_minimum() {
+        let error = auto_n_ctx(Some(8192), Some(256)).unwrap_err();
+        assert_eq!(error.code(), "invalid_input");
+        assert!(error.to_string().contains("256 token KV cache"));
+        assert_eq!(auto_n_ctx(Some(8192), Some(AUTO_MIN_N_CTX)).unwrap(), AUTO_MIN_N_CTX as u32);
+        // A model trained on a shorter context than the minimum only needs that much
+        assert_eq!(auto_n_ctx(Some(256), Some(256)).unwrap(), 256);
+    }
+}
@@ -4,25 +4,50 @@ use llama_cpp_2::llama_backend::LlamaBackend;
 use llama_cpp_2::model::LlamaModel;
 use llama_cpp_2::context::params::LlamaContextParams;
 use llama_cpp_2::model::params::LlamaModelParams;
-use llama_cpp_2::model::{LlamaChatMessage, LlamaChatTemplate};
+use llama_cpp_2::model::LlamaChatTemplate;
 use llama_cpp_2::llama_batch::LlamaBatch;
 use llama_cpp_2::model::{AddBos, Special};
+use llama_cpp_2::token::LlamaToken;
 // Note: LlamaSampler now imported via SamplerBuilder
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::env;
 use std::num::NonZeroU32;
-use std::sync::atomic::Ordering;
-use std::time::Instant;
-use tauri::{Emitter, Window};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use encoding_rs;
 
 // Re-import types from parent module  
 use crate::{ModelConfig, TelemetryUpdate, TelemetryBroadcaster};
-use crate::{TokenEvent, InputTokenEvent, OutputTokenEvent, SystemPromptTokenEvent, GenerationTimeEvent, PowerConsumptionSummaryEvent};
-use crate::{CURRENT_TELEMETRY, GLOBAL_STOP_SIGNAL};
+use crate::{TokenEvent, InputTokenEvent, OutputTokenEvent, SystemPromptTokenEvent, GenerationTimeEvent, PowerConsumptionSummaryEvent, GenerationProgressEvent};
+use crate::CURRENT_TELEMETRY;
+use crate::telemetry::processor::{append_partial_output, model_stop_requested, MODEL_ENERGY};
 
 // Import the new SamplerBuilder for configurable sampling
 use crate::inference::sampler_builder::SamplerBuilder;
+use crate::inference::chat_template::{format_chat_prompt, resolve_template_override, ChatTurn};
+use crate::inference::progress::{ProgressTracker, RollingTpsWindow};
+use crate::inference::stability::InterTokenStats;
+use crate::inference::output_stream::OutputStreamWriter;
+use crate::inference::resume::{record_checkpoint, ResumeCheckpoint};
+use crate::error::A2oError;
+use crate::inference::pipeline::{DecodeTimings, EmitPipeline};
+use crate::inference::affinity::QosGuard;
+use crate::inference::prefill::{prefill_chunks, resolve_n_batch};
+use crate::inference::stop_sequences::{StopScan, StopSequenceMatcher};
+use crate::inference::loop_detection::LoopDetector;
+use crate::inference::model_cache::{ModelCache, ModelCacheKey};
+use crate::inference::llama_log::LogCapture;
+use crate::inference::context_shift::{apply_context_shift, kept_prefix_len, plan_context_shift};
+use crate::inference::context_params::{parse_flash_attention, parse_gpu_offload, EffectiveContextParams, GpuOffload};
+use crate::inference::memory_watermark::{bytes_to_gb, MemoryWatermark, GENERATION_MEMORY_SAMPLE_INTERVAL, LOAD_MEMORY_SAMPLE_INTERVAL};
+use crate::telemetry::calibration::OverheadCorrection;
+use crate::telemetry::power_calculator::PowerConsumptionSummary;
+use crate::telemetry::phase::InferencePhaseMarkers;
+use crate::inference::context_size::resolve_context_size;
+use crate::inference::prompt_budget::{prompt_budget, truncation_starts};
+use crate::inference::speculative::{resolve_n_draft, SpeculativeDecoder};
+use crate::telemetry::types::{ContextShiftEvent, ContextSizeResolvedEvent, ContextTruncatedEvent, DecodeOverheadEvent, GenerationResult, MemoryUsageEvent, PrefillMetricsEvent, PrefillProgressEvent, InferencePhase, ModelPromptBudget, PhaseEnergyBreakdown, PowerSampleQuality, SamplerSeed, SamplingParams, SpeculativeDecodingEvent, TokenStabilityEvent};
+use crate::events::SharedEventSink;
 use crate::utils::debug::DEBUG_LOGS;
 
 #[allow(unused_macros)]
@@ -32,27 +57,114 @@ macro_rules! dprintln {
     }
 }
 
-/// Convert Message sequence to LlamaChatMessage format with system prompt integration
-fn build_chat_message_sequence(
+/// Tokens generated per model per turn when ModelConfig.max_tokens is unset
+pub const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// Effective generation budget: the configured max_tokens (at least 1) or the default
+pub fn resolve_max_tokens(requested: Option<u32>) -> usize {
+    requested.unwrap_or(DEFAULT_MAX_TOKENS).max(1) as usize
+}
+
+/// Context size used when a ModelConfig leaves n_ctx unset
+pub const DEFAULT_N_CTX: u32 = 2048;
+
+/// Resolve a model path as given, or relative to the working directory and up to four of
+/// its parents (dev builds run from src-tauri/ while paths are often repo-relative)
+pub fn resolve_model_path(model_path: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(model_path);
+    if path.exists() {
+        return Some(path);
+    }
+    let mut search_dir = env::current_dir().ok()?;
+    for _ in 0..5 {
+        let candidate = search_dir.join(model_path);
+        if candidate.exists() {
+            return Some(candidate);
+        }
+        search_dir = search_dir.parent()?.to_path_buf();
+    }
+    None
+}
+
+// How often a model load checks the stop signal
+const MODEL_LOAD_STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// llama.cpp backend shared by every run. Initialised once so that a cancelled load still
+// finishing in the background never blocks the next run's backend initialisation.
+static LLAMA_BACKEND: OnceLock<LlamaBackend> = OnceLock::new();
+static LLAMA_BACKEND_INIT: Mutex<()> = Mutex::new(());
+
+// Held while a run checks the model cache, makes room and loads, so the two sides of a
+// parallel run don't evict each other's model or load the same file twice
+static MODEL_LOAD_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub(crate) fn llama_backend() -> Result<&'static LlamaBackend, String> {
+    if let Some(backend) = LLAMA_BACKEND.get() {
+        return Ok(backend);
+    }
+    // Both sides of a parallel run can get here at once, and llama.cpp may only be
+    // initialised once: the second caller waits and takes the first one's backend
+    let _init = LLAMA_BACKEND_INIT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(backend) = LLAMA_BACKEND.get() {
+        return Ok(backend);
+    }
+    let backend = LlamaBackend::init()
+        .map_err(|e| format!("Failed to initialize backend: {:?}", e))?;
+    Ok(LLAMA_BACKEND.get_or_init(|| backend))
+}
+
+/// Load the model on a blocking thread while watching the stop signal, so a stop during a
+/// multi-minute load returns at once. llama.cpp cannot interrupt the read itself: an
+/// abandoned load finishes in the background and its weights are freed as soon as it does.
+pub(crate) async fn load_model_cancellable(
+    backend: &'static LlamaBackend,
+    model_path: &Path,
+    model_label: &str,
+    n_gpu_layers: Option<u32>,
+) -> Result<(LlamaModel, Option<GpuOffload>), A2oError> {
+    let path = model_path.to_path_buf();
+    let mut params = LlamaModelParams::default();
+    if let Some(n_gpu_layers) = n_gpu_layers {
+        params = params.with_n_gpu_layers(n_gpu_layers);
+    }
+    let mut load = tokio::task::spawn_blocking(move || {
+        // llama.cpp reports the layers it actually offloaded only in its load log
+        let capture = LogCapture::start();
+        let loaded = LlamaModel::load_from_file(backend, &path, &params);
+        let log = capture.finish();
+        loaded
+            .map(|model| (model, parse_gpu_offload(&log)))
+            .map_err(|e| A2oError::ModelLoad(format!("Failed to load model: {:?}", e)))
+    });
+    let mut stop_poll = tokio::time::interval(MODEL_LOAD_STOP_POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            loaded = &mut load => return loaded.map_err(|e| A2oError::ModelLoad(format!("Model load task failed: {}", e)))?,
+            _ = stop_poll.tick() => {
+                if model_stop_requested(model_label) {
+                    println!("🛑 Stop signal detected, cancelled model load for Model {}", model_label);
+                    return Err(A2oError::Cancelled);
+                }
+            }
+        }
+    }
+}
+
+/// Convert Message sequence to chat turns with system prompt integration
+pub(crate) fn build_chat_message_sequence(
     chat_history: &[crate::Message],
     system_prompt: Option<&str>,
-) -> Result<Vec<LlamaChatMessage>, String> {
+) -> Result<Vec<ChatTurn>, String> {
     let mut chat_messages = Vec::new();
 
     // Add system message if provided
     if let Some(system) = system_prompt {
-        chat_messages.push(
-            LlamaChatMessage::new("system".to_string(), system.to_string())
-                .map_err(|e| format!("Failed to create system message: {:?}", e))?
-        );
+        chat_messages.push(ChatTurn::new("system", system)?);
     }
 
     // Convert conversation history
     for message in chat_history {
-        chat_messages.push(
-            LlamaChatMessage::new(message.role.clone(), message.content.clone())
-                .map_err(|e| format!("Failed to create chat message for role '{}': {:?}", message.role, e))?
-        );
+        chat_messages.push(ChatTurn::new(&message.role, &message.content)?);
     }
 
     if chat_messages.is_empty() {
@@ -63,18 +175,31 @@ fn build_chat_message_sequence(
     Ok(chat_messages)
 }
 
-/// Apply model's embedded chat template to format conversation
-fn apply_model_chat_template(
+/// Format the conversation with the model's chat template: `template_override` (a built-in
+/// name or Jinja template) when set, otherwise the one embedded in the GGUF
+pub(crate) fn apply_model_chat_template(
     model: &LlamaModel,
-    chat_messages: &[LlamaChatMessage],
+    chat_messages: &[ChatTurn],
+    template_override: Option<&str>,
 ) -> Result<String, String> {
-    // Get model's default embedded chat template from GGUF metadata
-    let template: LlamaChatTemplate = model.chat_template(None)
-        .map_err(|e| format!("Failed to get model's embedded chat template: {:?}", e))?;
-
-    // Apply template to conversation (add_ass=true for generation mode)
-    let formatted_prompt = model.apply_chat_template(&template, chat_messages, true)
-        .map_err(|e| format!("Failed to apply chat template: {:?}", e))?;
+    // add_ass=true appends the assistant header for generation mode
+    let formatted_prompt = match template_override.map(str::trim).filter(|template| !template.is_empty()) {
+        Some(template_override) => {
+            let template = resolve_template_override(template_override)?;
+            let is_jinja = template_override.contains("{{") || template_override.contains("{%");
+            println!("🎯 TEMPLATE OVERRIDE: Using {} instead of the embedded template",
+                     if is_jinja { "a custom Jinja template" } else { template_override });
+            format_chat_prompt(&template, chat_messages, true)
+                .map_err(|e| format!("Failed to apply chat template override: {}", e))?
+        }
+        None => {
+            // Get model's default embedded chat template from GGUF metadata
+            let template: LlamaChatTemplate = model.chat_template(None)
+                .map_err(|e| format!("Failed to get model's embedded chat template: {:?}. Set a chat template override (chatml, llama3, mistral) for this model.", e))?;
+            format_chat_prompt(template.as_c_str(), chat_messages, true)
+                .map_err(|e| format!("Failed to apply the model's embedded chat template: {}. Set a chat template override (chatml, llama3, mistral) for this model.", e))?
+        }
+    };
 
     println!("🎯 TEMPLATE APPLIED: Formatted {} messages into {} character prompt",
              chat_messages.len(), formatted_prompt.len());
@@ -82,65 +207,396 @@ fn apply_model_chat_template(
     Ok(formatted_prompt)
 }
 
-pub async fn run_model_inference(
-    window: &Window,
+/// Tokenize the conversation exactly as run_model_inference will for this model and size it
+/// against the model's context. Loads only the vocabulary, so it is cheap but still blocking.
+pub fn preflight_prompt_budget(
     model_config: &ModelConfig,
     chat_history: &[crate::Message],
-    model_label: &str,
-    telemetry_broadcaster: Option<TelemetryBroadcaster>,
     system_prompt: Option<&str>,
-) -> Result<String, String> {
+    model_label: &str,
+) -> Result<ModelPromptBudget, String> {
+    let backend = llama_backend()?;
+    let model_path = resolve_model_path(&model_config.model_path).ok_or_else(|| {
+        format!("Model file not found at {} or in parent directories", model_config.model_path)
+    })?;
+    let model = LlamaModel::load_from_file(backend, &model_path, &LlamaModelParams::default().with_vocab_only(true))
+        .map_err(|e| format!("Failed to load vocabulary for Model {}: {:?}", model_label, e))?;
+
+    let chat_messages = build_chat_message_sequence(chat_history, system_prompt)?;
+    let formatted_prompt = apply_model_chat_template(&model, &chat_messages, model_config.chat_template_override.as_deref())?;
+    let prompt_tokens = model.str_to_token(&formatted_prompt, AddBos::Always)
+        .map_err(|e| format!("Failed to tokenize formatted conversation: {:?}", e))?
+        .len();
+    let n_ctx = resolve_context_size(model_config.n_ctx, &model_path).map_err(|e| e.to_string())?.n_ctx;
+
+    Ok(prompt_budget(model_label, prompt_tokens, n_ctx, resolve_max_tokens(model_config.max_tokens)))
+}
+
+/// Merge inference metrics into the latest hardware telemetry and broadcast it
+fn broadcast_inference_telemetry(
+    broadcaster: &TelemetryBroadcaster,
+    ttft_ms: Option<u64>,
+    current_tps: Option<f64>,
+    instantaneous_tps: Option<f64>,
+    rolling_tps: Option<f64>,
+    model_label: &str,
+) {
+    let base_telemetry = CURRENT_TELEMETRY.read().ok().and_then(|current| current.clone());
+    let telemetry = match base_telemetry {
+        Some(base_telemetry) => {
+dprintln!("🔄 Inference telemetry: Merging with existing hardware telemetry");
+            base_telemetry.with_inference_data(ttft_ms, current_tps, instantaneous_tps, rolling_tps, Some(model_label.to_string()))
+        }
+        None => {
+dprintln!("⚠️ Inference telemetry: No current telemetry state available, using empty base");
+            TelemetryUpdate {
+                timestamp_ms: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+                cpu_power_watts: None,
+                gpu_power_watts: None,
+                ane_power_watts: None,
+                cpu_temp_celsius: None,
+                gpu_temp_celsius: None,
+                cpu_freq_mhz: None,
+                gpu_freq_mhz: None,
+                ram_usage_gb: None,
+                thermal_pressure: None,
+                swap_usage_gb: None,
+                compressed_memory_gb: None,
+                memory_pressure_level: None,
+                cpu_temp_avg: None,
+                cpu_temp_max: None,
+                cpu_p_core_temps: None,
+                cpu_e_core_temps: None,
+                gpu_temp_avg: None,
+                gpu_temp_max: None,
+                gpu_cluster_temps: None,
+                battery_temp_avg: None,
+                cpu_p_core_utilization: None,
+                cpu_e_core_utilization: None,
+                cpu_overall_utilization: None,
+                inference_cpu_utilization: None,
+                inference_cpu_share: None,
+                gpu_utilization: None,
+                fan_rpm: None,
+                ttft_ms,
+                current_tps,
+                instantaneous_tps,
+                rolling_tps,
+                generation_time_ms: None,
+                model: Some(model_label.to_string()),
+                core_temperatures: None,
+                // Energy fields (initialized as None, will be filled by PowerCalculator)
+                total_energy_wh: None,
+                cpu_energy_wh: None,
+                gpu_energy_wh: None,
+                ane_energy_wh: None,
+                energy_rate_wh_per_token: None,
+                total_energy_lower_wh: None,
+                total_energy_upper_wh: None,
+                cpu_temp_rate_c_per_s: None,
+                gpu_temp_rate_c_per_s: None,
+                battery_temp_rate_c_per_s: None,
+                package_power_rate_w_per_s: None,
+            }
+        }
+    };
+
+dprintln!("📈 Inference telemetry: Broadcasting merged telemetry: {:?}", telemetry);
+    match broadcaster.send(telemetry) {
+        Ok(receiver_count) => {
+dprintln!("📈 Inference telemetry: ✅ Broadcast successful to {} receivers", receiver_count);
+        }
+        Err(e) => {
+dprintln!("📈 Inference telemetry: ❌ Failed to broadcast: {}", e);
+        }
+    }
+}
+
+/// Per-run settings for the live decode speed and stability metrics
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeMetricsConfig {
+    pub rolling_tps_window: Duration,   // Window for rolling-window TPS
+    pub stall_threshold: Duration,      // Inter-token gap counted as a stall
+}
+
+/// Energy summary of one phase's totals: per-token cost over `tokens_generated`, the
+/// calibrated measurement overhead over `window` and the prefill/decode split when both
+/// phases were marked
+pub(crate) fn energy_summary_event(
+    energy: PowerConsumptionSummary,
+    model_label: &str,
+    tokens_generated: usize,
+    prefilled_tokens: usize,
+    measurement_overhead: Option<OverheadCorrection>,
+    window: Duration,
+) -> PowerConsumptionSummaryEvent {
+    let total_energy = energy.total_energy_wh;
+
+    // Calculate energy per token
+    let energy_per_token = if tokens_generated > 0 {
+        Some(total_energy / tokens_generated as f64)
+    } else {
+        None
+    };
+
+    // Calibrated telemetry overhead over the energy window
+    let measurement_overhead_wh = measurement_overhead
+        .map(|overhead| overhead.watts * window.as_secs_f64() / 3600.0);
+    let net_total_energy = match (measurement_overhead, measurement_overhead_wh) {
+        (Some(overhead), Some(overhead_wh)) if overhead.subtract => Some((total_energy - overhead_wh).max(0.0)),
+        _ => None,
+    };
+    let net_energy_per_token = net_total_energy
+        .filter(|_| tokens_generated > 0)
+        .map(|net| net / tokens_generated as f64);
+
+    println!("📊 ENERGY SUMMARY: Model {} - Total: {:.6}Wh, CPU: {:.6}Wh, GPU: {:.6}Wh, ANE: {:.6}Wh, Per Token: {:?}Wh", 
+             model_label, total_energy, energy.cpu_energy_wh, energy.gpu_energy_wh, energy.ane_energy_wh, energy_per_token);
+    println!("📊 ENERGY SUMMARY: Model {} - {:?} integration, bounds [{:.6}, {:.6}]Wh",
+             model_label, energy.integration_method, energy.energy_lower_bound_wh, energy.energy_upper_bound_wh);
+    // Per-token cost of prompt processing vs generation
+    let phase_breakdown = match (energy.prefill, energy.decode) {
+        (Some(prefill), Some(decode)) => Some(PhaseEnergyBreakdown {
+            prefill_energy_wh: prefill.energy_wh,
+            prefill_ms: prefill.duration_ms,
+            prefill_energy_per_token_wh: (prefilled_tokens > 0).then(|| prefill.energy_wh / prefilled_tokens as f64),
+            decode_energy_wh: decode.energy_wh,
+            decode_ms: decode.duration_ms,
+            decode_energy_per_token_wh: (tokens_generated > 0).then(|| decode.energy_wh / tokens_generated as f64),
+        }),
+        _ => None,
+    };
+    if let Some(breakdown) = &phase_breakdown {
+        println!("📊 ENERGY SUMMARY: Model {} - Prefill: {:.6}Wh over {}ms ({:?}Wh/prompt token), Decode: {:.6}Wh over {}ms ({:?}Wh/token)",
+                 model_label, breakdown.prefill_energy_wh, breakdown.prefill_ms, breakdown.prefill_energy_per_token_wh,
+                 breakdown.decode_energy_wh, breakdown.decode_ms, breakdown.decode_energy_per_token_wh);
+    }
+    if energy.sample_quality != PowerSampleQuality::default() {
+        println!("⚠️ ENERGY SUMMARY: Model {} - left out {} out-of-order sample(s), {} implausible reading(s), {} gap(s)",
+                 model_label, energy.sample_quality.discarded_samples, energy.sample_quality.rejected_power_readings,
+                 energy.sample_quality.series_resets);
+    }
+    if let Some(overhead_wh) = measurement_overhead_wh {
+        println!("📊 ENERGY SUMMARY: Model {} - Measurement overhead: {:.6}Wh, Net total: {:?}Wh",
+                 model_label, overhead_wh, net_total_energy);
+    }
+
+    PowerConsumptionSummaryEvent {
+        total_energy_wh: total_energy,
+        cpu_energy_wh: energy.cpu_energy_wh,
+        gpu_energy_wh: energy.gpu_energy_wh,
+        ane_energy_wh: energy.ane_energy_wh,
+        energy_per_token_wh: energy_per_token,
+        integration_method: energy.integration_method,
+        energy_lower_bound_wh: Some(energy.energy_lower_bound_wh),
+        energy_upper_bound_wh: Some(energy.energy_upper_bound_wh),
+        measurement_overhead_wh,
+        net_total_energy_wh: net_total_energy,
+        net_energy_per_token_wh: net_energy_per_token,
+        idle_power_subtracted_w: energy.idle_power_subtracted_w,
+        sample_quality: energy.sample_quality,
+        phase_breakdown,
+        model: model_label.to_string(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    }
+}
+
+/// One model's turn for run_model_inference: what to generate and how it is instrumented
+pub struct ModelRun<'a> {
+    pub model_config: &'a ModelConfig,
+    pub chat_history: &'a [crate::Message],
+    pub model_label: &'a str,
+    pub system_prompt: Option<&'a str>,
+    pub telemetry_broadcaster: Option<TelemetryBroadcaster>, // None runs without telemetry
+    pub phase_markers: Option<InferencePhaseMarkers>,        // None when energy isn't attributed per model
+    pub output_stream: Option<OutputStreamWriter>,
+    pub measurement_overhead: Option<OverheadCorrection>,
+    pub decode_metrics: DecodeMetricsConfig,
+}
+
+pub async fn run_model_inference(
+    events: &SharedEventSink,
+    run: ModelRun<'_>,
+    model_cache: &ModelCache,
+) -> Result<GenerationResult, A2oError> {
+    let ModelRun {
+        model_config, chat_history, model_label, system_prompt, telemetry_broadcaster, phase_markers,
+        mut output_stream, measurement_overhead, decode_metrics,
+    } = run;
     println!("=== STARTING INFERENCE for Model {} with {} messages ===", model_label, chat_history.len());
-    // Initialize the llama.cpp backend
-    let backend = LlamaBackend::init()
-        .map_err(|e| format!("Failed to initialize backend: {:?}", e))?;
+    // Energy is accumulated from the power calculator reset just before this call
+    let run_start = Instant::now();
+    // Set before the backend/context exist so llama.cpp worker threads inherit the QoS class
+    let _qos_guard = QosGuard::apply(model_config.core_preference.unwrap_or_default());
+    // Initialize the llama.cpp backend (once per process)
+    let backend = llama_backend()?;
     
-    let model_path = PathBuf::from(&model_config.model_path);
-    if !model_path.exists() {
-        // Try relative path resolution
-        let current_dir = env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
-        
-        let mut search_dir = current_dir.clone();
-        let mut found_path = None;
-        
-        for _ in 0..5 {
-            let potential_path = search_dir.join(&model_config.model_path);
-            if potential_path.exists() {
-                found_path = Some(potential_path);
-                break;
-            }
-            if let Some(parent) = search_dir.parent() {
-                search_dir = parent.to_path_buf();
-            } else {
-                break;
+    let model_path = resolve_model_path(&model_config.model_path).ok_or_else(|| {
+        A2oError::ModelNotFound(format!("Model file not found at {} or in parent directories", model_config.model_path))
+    })?;
+    
+    // Reuse the model from an earlier turn, or load it, offloading the configured number of
+    // layers; memory is sampled at a high rate until the context exists
+    match model_config.n_gpu_layers {
+        Some(n_gpu_layers) => println!("🎛️ MODEL {}: offloading {} layer(s) to GPU", model_label, n_gpu_layers),
+        None => println!("🎛️ MODEL {}: using default GPU offload", model_label),
+    }
+    let cache_key = ModelCacheKey { path: model_path.clone(), n_gpu_layers: model_config.n_gpu_layers };
+    let load_watermark = MemoryWatermark::start(LOAD_MEMORY_SAMPLE_INTERVAL);
+    let load_start = Instant::now();
+    let load_guard = MODEL_LOAD_LOCK.lock().await;
+    let cached_model = model_cache.get(&cache_key);
+    let model_reused = cached_model.is_some();
+    let (model, gpu_offload) = match cached_model {
+        Some(model) => {
+            println!("♻️ MODEL {}: reusing resident model {}", model_label, model_path.display());
+            (model, model_cache.gpu_offload(&cache_key))
+        }
+        None => {
+            let size_bytes = std::fs::metadata(&model_path).map(|metadata| metadata.len()).unwrap_or(0);
+            model_cache.make_room(size_bytes);
+            match load_model_cancellable(backend, &model_path, model_label, model_config.n_gpu_layers).await {
+                Ok((model, gpu_offload)) => {
+                    let model = Arc::new(model);
+                    model_cache.insert(cache_key, model.clone(), size_bytes, gpu_offload);
+                    (model, gpu_offload)
+                }
+                Err(e) => {
+                    if model_stop_requested(model_label) {
+                        let _ = events.emit("generation_stopped", TokenEvent {
+                            token: String::new(),
+                            model: model_label.to_string(),
+                            finished: true,
+                        });
+                    }
+                    return Err(e);
+                }
             }
         }
-        
-        let _model_path = found_path.ok_or_else(|| {
-            format!("Model file not found at {} or in parent directories", model_config.model_path)
-        })?;
+    };
+    drop(load_guard);
+    let model_load_ms = load_start.elapsed().as_secs_f64() * 1000.0;
+    if !model_reused {
+        println!("⏱️ MODEL {}: loaded in {:.0} ms", model_label, model_load_ms);
     }
     
-    // Load model with default parameters
-    let model_params = LlamaModelParams::default();
-    let model = LlamaModel::load_from_file(&backend, &model_path, &model_params)
-        .map_err(|e| format!("Failed to load model: {:?}", e))?;
-    
-    let n_ctx = model_config.n_ctx.unwrap_or(2048);
+    // Resolve n_ctx (including "auto") and report the value actually used so it lands in the session
+    let resolved_ctx = resolve_context_size(model_config.n_ctx, &model_path)?;
+    let n_ctx = resolved_ctx.n_ctx;
+    println!("📐 CONTEXT: Model {} requested n_ctx={}, using {} (trained: {:?}, memory limit: {:?})",
+             model_label, resolved_ctx.requested, n_ctx, resolved_ctx.trained_n_ctx, resolved_ctx.memory_limit_n_ctx);
+    if let Some(warning) = &resolved_ctx.trained_context_warning {
+        println!("⚠️ CONTEXT: Model {}: {}", model_label, warning);
+    }
+    let _ = events.emit("context_size_resolved", ContextSizeResolvedEvent {
+        model: model_label.to_string(),
+        requested: resolved_ctx.requested,
+        n_ctx,
+        trained_n_ctx: resolved_ctx.trained_n_ctx,
+        memory_limit_n_ctx: resolved_ctx.memory_limit_n_ctx,
+        rope_scaling: resolved_ctx.rope_scaling.as_ref().map(|scaling| scaling.kind.clone()),
+        trained_context_warning: resolved_ctx.trained_context_warning.clone(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    });
+    // Prefill decodes the prompt in chunks of n_batch tokens, so the context must accept that size
+    let n_batch = resolve_n_batch(model_config.n_batch, n_ctx);
     let ctx_params = LlamaContextParams::default()
-        .with_n_ctx(Some(NonZeroU32::new(n_ctx).unwrap()));
+        .with_n_ctx(Some(NonZeroU32::new(n_ctx).ok_or_else(|| A2oError::InvalidInput("n_ctx must be greater than 0".to_string()))?))
+        .with_n_batch(n_batch);
     
-    let mut ctx = model.new_context(&backend, ctx_params)
-        .map_err(|e| format!("Failed to create context: {:?}", e))?;
+    let (n_threads, n_threads_batch) = (ctx_params.n_threads(), ctx_params.n_threads_batch());
+    
+    let capture = LogCapture::start();
+    let ctx = model.new_context(backend, ctx_params);
+    let context_log = capture.finish();
+    let mut ctx = ctx.map_err(|e| A2oError::ModelLoad(format!("Failed to create context: {:?}", e)))?;
+    // Read back what llama.cpp settled on, flagging anything it silently changed
+    let mut effective_context = EffectiveContextParams {
+        n_ctx: ctx.n_ctx(),
+        n_batch: ctx.n_batch(),
+        n_ubatch: ctx.n_ubatch(),
+        n_threads,
+        n_threads_batch,
+        n_gpu_layers_requested: model_config.n_gpu_layers,
+        gpu_offload_supported: unsafe { llama_cpp_sys_2::llama_supports_gpu_offload() },
+        gpu_offload,
+        flash_attention: parse_flash_attention(&context_log),
+        warnings: Vec::new(),
+    };
+    effective_context.check_fallbacks(model_label, n_ctx, n_batch);
+    println!("⚙️ CONTEXT: Model {} effective n_ctx={}, n_batch={}, n_ubatch={}, threads={}/{}, offload {:?}, flash attention {:?}",
+             model_label, effective_context.n_ctx, effective_context.n_batch, effective_context.n_ubatch,
+             n_threads, n_threads_batch, effective_context.gpu_offload, effective_context.flash_attention);
+    for warning in &effective_context.warnings {
+        println!("⚠️ CONTEXT: {}", warning);
+    }
+
+    // Speculative decoding: the draft model is cached like any other, offloads as many layers
+    // as the main model and must share its vocabulary
+    let draft_model = match model_config.draft_model_path.as_deref().map(str::trim).filter(|path| !path.is_empty()) {
+        Some(draft_path) => {
+            let draft_path = resolve_model_path(draft_path).ok_or_else(|| {
+                A2oError::ModelNotFound(format!("Draft model file not found at {} or in parent directories", draft_path))
+            })?;
+            let draft_key = ModelCacheKey { path: draft_path.clone(), n_gpu_layers: model_config.n_gpu_layers };
+            let _load_guard = MODEL_LOAD_LOCK.lock().await;
+            let draft = match model_cache.get(&draft_key) {
+                Some(draft) => draft,
+                None => {
+                    let size_bytes = std::fs::metadata(&draft_path).map(|metadata| metadata.len()).unwrap_or(0);
+                    model_cache.make_room(size_bytes);
+                    let draft_load_start = Instant::now();
+                    let (draft, gpu_offload) = match load_model_cancellable(backend, &draft_path, model_label, model_config.n_gpu_layers).await {
+                        Ok(loaded) => loaded,
+                        Err(e) => {
+                            if model_stop_requested(model_label) {
+                                let _ = events.emit("generation_stopped", TokenEvent {
+                                    token: String::new(),
+                                    model: model_label.to_string(),
+                                    finished: true,
+                                });
+                            }
+                            return Err(e);
+                        }
+                    };
+                    println!("⏱️ MODEL {}: loaded draft model {} in {:.0} ms",
+                             model_label, draft_path.display(), draft_load_start.elapsed().as_secs_f64() * 1000.0);
+                    let draft = Arc::new(draft);
+                    model_cache.insert(draft_key, draft.clone(), size_bytes, gpu_offload);
+                    draft
+                }
+            };
+            if draft.n_vocab() != model.n_vocab() {
+                return Err(A2oError::InvalidInput(format!(
+                    "Model {}: draft model {} has a {}-token vocabulary but the model has {}; speculative decoding needs the same vocabulary",
+                    model_label, draft_path.display(), draft.n_vocab(), model.n_vocab()
+                )));
+            }
+            Some((draft, draft_path))
+        }
+        None => None,
+    };
+    let load_memory = load_watermark.finish();
+    println!("🧠 MEMORY: Model {} load peak {:.2} GB (+{:.2} GB over {:.2} GB before load)",
+             model_label, load_memory.peak_gb(), load_memory.delta_gb(load_memory.start_bytes),
+             bytes_to_gb(load_memory.start_bytes));
     
     // Phase 3: Efficient system prompt tokenization using already loaded model
     if let Some(system_prompt) = system_prompt {
         let system_tokens = model.str_to_token(system_prompt, AddBos::Never)
-            .map_err(|e| format!("Failed to tokenize system prompt: {:?}", e))?;
+            .map_err(|e| A2oError::Tokenization(format!("Failed to tokenize system prompt: {:?}", e)))?;
         
         println!("📊 SYSTEM PROMPT TOKENS: Tokenized '{}' into {} tokens", system_prompt.trim(), system_tokens.len());
-        let _ = window.emit("system_prompt_tokens", SystemPromptTokenEvent {
+        let _ = events.emit("system_prompt_tokens", SystemPromptTokenEvent {
             count: system_tokens.len(),
             timestamp_ms: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -152,8 +608,8 @@ pub async fn run_model_inference(
     // Phase 3.5: Tokenize only the last user message content for per-message UI token display
     if let Some(last_message) = chat_history.last() {
         let last_tokens = model.str_to_token(&last_message.content, AddBos::Always)
-            .map_err(|e| format!("Failed to tokenize last message: {:?}", e))?;
-        let _ = window.emit("user_input_tokens", InputTokenEvent {
+            .map_err(|e| A2oError::Tokenization(format!("Failed to tokenize last message: {:?}", e)))?;
+        let _ = events.emit("user_input_tokens", InputTokenEvent {
             count: last_tokens.len(),
             model: model_label.to_string(),
             timestamp_ms: std::time::SystemTime::now()
@@ -163,21 +619,69 @@ pub async fn run_model_inference(
         });
     }
 
-    // Phase 4: Convert conversation to chat message format
-    let chat_messages = build_chat_message_sequence(chat_history, system_prompt)?;
-
-    // Phase 5: Apply model's embedded chat template
-    let formatted_prompt = apply_model_chat_template(&model, &chat_messages)?;
+    // Phases 4-6: Build the chat sequence, apply the model's chat template (embedded, or the
+    // configured override) and tokenize the formatted conversation
+    let tokenize_conversation = |history: &[crate::Message]| -> Result<Vec<LlamaToken>, A2oError> {
+        let chat_messages = build_chat_message_sequence(history, system_prompt)?;
+        let formatted_prompt = apply_model_chat_template(&model, &chat_messages, model_config.chat_template_override.as_deref())?;
+        model.str_to_token(&formatted_prompt, AddBos::Always)
+            .map_err(|e| A2oError::Tokenization(format!("Failed to tokenize formatted conversation: {:?}", e)))
+    };
+    let mut tokens_list = tokenize_conversation(chat_history)?;
+    let mut prompt_messages = chat_history.len();
 
-    // Phase 6: Tokenize the formatted conversation
-    let tokens_list = model.str_to_token(&formatted_prompt, AddBos::Always)
-        .map_err(|e| format!("Failed to tokenize formatted conversation: {:?}", e))?;
+    // The prompt plus at least one generated token must fit the context; with auto_truncate the
+    // oldest turns are dropped (the system prompt stays) until it does
+    let kv_n_ctx = ctx.n_ctx();
+    if tokens_list.len() >= kv_n_ctx as usize {
+        let original_prompt_tokens = tokens_list.len();
+        let mut fitted = false;
+        if model_config.auto_truncate.unwrap_or(false) {
+            for start in truncation_starts(chat_history) {
+                let truncated = tokenize_conversation(&chat_history[start..])?;
+                if truncated.len() < kv_n_ctx as usize {
+                    tokens_list = truncated;
+                    prompt_messages = chat_history.len() - start;
+                    fitted = true;
+                    break;
+                }
+            }
+        }
+        if !fitted {
+            let remedy = if model_config.auto_truncate.unwrap_or(false) {
+                "even its last message does not fit; shorten it or the system prompt, or raise n_ctx"
+            } else {
+                "shorten the conversation, raise n_ctx or enable auto_truncate"
+            };
+            return Err(A2oError::ContextOverflow {
+                message: format!("Model {} prompt is {} tokens but n_ctx is {}; {}",
+                                 model_label, original_prompt_tokens, kv_n_ctx, remedy),
+                model: model_label.to_string(),
+                prompt_tokens: original_prompt_tokens,
+                n_ctx: kv_n_ctx,
+            });
+        }
+        let dropped_messages = chat_history.len() - prompt_messages;
+        println!("✂️ AUTO TRUNCATE: Model {} dropped the oldest {} message(s), prompt {} -> {} tokens (n_ctx {})",
+                 model_label, dropped_messages, original_prompt_tokens, tokens_list.len(), kv_n_ctx);
+        let _ = events.emit("context_truncated", ContextTruncatedEvent {
+            model: model_label.to_string(),
+            dropped_messages,
+            original_prompt_tokens,
+            prompt_tokens: tokens_list.len(),
+            n_ctx: kv_n_ctx,
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
+    }
     
     // Emit input token count immediately after tokenization
     let input_token_count = tokens_list.len();
     println!("📊 INPUT TOKENS: Model {} formatted conversation ({} messages) into {} tokens",
-             model_label, chat_messages.len(), input_token_count);
-    let _ = window.emit("input_tokens", InputTokenEvent {
+             model_label, prompt_messages + usize::from(system_prompt.is_some()), input_token_count);
+    let _ = events.emit("input_tokens", InputTokenEvent {
         count: input_token_count,
         model: model_label.to_string(),
         timestamp_ms: std::time::SystemTime::now()
@@ -188,31 +692,146 @@ pub async fn run_model_inference(
     
     // Clear the KV cache and create batch (following official example pattern)
     ctx.clear_kv_cache();
-    let mut batch = LlamaBatch::new(512, 1);
+    let mut batch = LlamaBatch::new(n_batch as usize, 1);
     
-    // Add all tokens to batch, following the official example pattern
-    let last_index: i32 = (tokens_list.len() - 1) as i32;
-    for (i, token) in (0_i32..).zip(tokens_list.iter()) {
-        // llama_decode will output logits only for the last token of the prompt
-        let is_last = i == last_index;
-        batch.add(*token, i, &[0], is_last)
-            .map_err(|e| format!("Failed to add token to batch: {:?}", e))?;
+    // Decode the prompt (prefill) chunk by chunk; a stop between chunks skips the rest and
+    // the decode loop below then ends the run as user_stop
+    let generation_watermark = MemoryWatermark::start(GENERATION_MEMORY_SAMPLE_INTERVAL);
+    let prefill_start = Instant::now();
+    if let Some(markers) = &phase_markers {
+        markers.begin(InferencePhase::Prefill);
     }
+    let last_index = tokens_list.len() - 1;
+    let mut prefilled_tokens = 0;
+    for chunk in prefill_chunks(tokens_list.len(), n_batch as usize) {
+        if model_stop_requested(model_label) {
+            println!("🛑 Stop signal detected, halting prefill for Model {} after {}/{} tokens",
+                     model_label, prefilled_tokens, input_token_count);
+            break;
+        }
+        batch.clear();
+        for (position, token) in chunk.clone().zip(&tokens_list[chunk.clone()]) {
+            // llama_decode will output logits only for the last token of the prompt
+            batch.add(*token, position as i32, &[0], position == last_index)
+                .map_err(|e| A2oError::Inference(format!("Failed to add token to batch: {:?}", e)))?;
+        }
+        ctx.decode(&mut batch)
+            .map_err(|e| A2oError::Inference(format!("Failed to decode batch: {:?}", e)))?;
+        prefilled_tokens = chunk.end;
+
+        if input_token_count > n_batch as usize {
+            let _ = events.emit("prefill_progress", PrefillProgressEvent {
+                model: model_label.to_string(),
+                processed_tokens: prefilled_tokens,
+                total_tokens: input_token_count,
+                elapsed_ms: prefill_start.elapsed().as_millis() as u64,
+                timestamp_ms: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+            });
+        }
+    }
+    let prefill_time = prefill_start.elapsed();
+    if let Some(markers) = &phase_markers {
+        markers.end(InferencePhase::Prefill);
+    }
+
+    // Prefill throughput differs widely between models, so it's reported apart from decode TPS
+    let prefill_secs = prefill_time.as_secs_f64();
+    let prefill_tps = (prefill_secs > 0.0).then(|| prefilled_tokens as f64 / prefill_secs);
+    println!("📥 PREFILL: Model {} processed {} prompt tokens in {:.1}ms ({:?} tokens/s)",
+             model_label, prefilled_tokens, prefill_secs * 1000.0, prefill_tps);
+    let _ = events.emit("prefill_metrics", PrefillMetricsEvent {
+        model: model_label.to_string(),
+        prompt_tokens: prefilled_tokens,
+        prefill_ms: prefill_secs * 1000.0,
+        prefill_tps,
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    });
     
-    // Decode the initial batch
-    ctx.decode(&mut batch)
-        .map_err(|e| format!("Failed to decode batch: {:?}", e))?;
-    
+    // Context shift: the system prompt (or at least the attention sinks) stays in the cache
+    let context_shift = model_config.context_shift.unwrap_or(false);
+    let kv_capacity = ctx.n_ctx() as i32;
+    let n_keep = if context_shift {
+        let system_prefix = match system_prompt {
+            Some(system) => build_chat_message_sequence(&[], Some(system))
+                .and_then(|system_only| apply_model_chat_template(&model, &system_only, model_config.chat_template_override.as_deref()))
+                .and_then(|formatted| model.str_to_token(&formatted, AddBos::Always).map_err(|e| format!("{:?}", e)))
+                .unwrap_or_else(|e| {
+                    println!("⚠️ CONTEXT SHIFT: Could not measure the system prompt ({}); keeping only the attention sinks", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        let n_keep = kept_prefix_len(&tokens_list, &system_prefix, kv_capacity as usize) as i32;
+        println!("🔁 CONTEXT SHIFT: Model {} enabled, keeping the first {} token(s) when the context fills", model_label, n_keep);
+        n_keep
+    } else {
+        0
+    };
+    let mut context_shifts = 0;
+    let mut discarded_tokens = 0;
+    let mut peak_context_tokens = tokens_list.len();
+
+    // The draft model gets its own context, prefilled with the same prompt
+    let resuming = model_config.resume_tokens.as_ref().is_some_and(|tokens| !tokens.is_empty());
+    let mut speculative = match &draft_model {
+        Some(_) if context_shift || resuming || n_batch < 2 => {
+            println!("⚠️ SPECULATIVE: Model {} runs without its draft model (not supported with context shift, resume or n_batch < 2)", model_label);
+            None
+        }
+        Some((draft, draft_path)) => {
+            let draft_start = Instant::now();
+            let draft_ctx_params = LlamaContextParams::default()
+                .with_n_ctx(NonZeroU32::new(n_ctx))
+                .with_n_batch(n_batch);
+            let draft_ctx = draft.new_context(backend, draft_ctx_params)
+                .map_err(|e| A2oError::ModelLoad(format!("Failed to create draft context: {:?}", e)))?;
+            let draft_name = draft_path.file_name().map_or_else(|| draft_path.display().to_string(), |name| name.to_string_lossy().into_owned());
+            let n_draft = resolve_n_draft(model_config.n_draft);
+            let decoder = SpeculativeDecoder::new(draft, draft_ctx, draft_name, n_draft, &tokens_list, n_batch as usize)?;
+            println!("🎯 SPECULATIVE: Model {} drafting {} token(s) per round with {} (draft prefill {:.1}ms)",
+                     model_label, n_draft, decoder.stats.draft_model, draft_start.elapsed().as_secs_f64() * 1000.0);
+            Some(decoder)
+        }
+        None => None,
+    };
+
     // Initialize variables following the official example
     let mut result = String::new();
-    let mut n_cur = batch.n_tokens();
-    let n_len = tokens_list.len() as i32 + 1024; // prompt + max generation tokens
+    let mut n_cur = tokens_list.len() as i32;
+    let max_tokens = resolve_max_tokens(model_config.max_tokens);
+    let n_len = tokens_list.len() as i32 + max_tokens as i32; // prompt + max generation tokens
     let mut _n_decode = 0;
+    let mut progress = ProgressTracker::new(max_tokens);
+    let mut stop_reason = "max_tokens";
+    // Text that may be the start of a stop sequence is held back until the next token decides it
+    let mut stop_matcher = StopSequenceMatcher::new(model_config.stop_sequences.as_deref().unwrap_or_default());
+    let mut matched_stop_sequence = None;
+    if let Some(stop_sequences) = model_config.stop_sequences.as_ref().filter(|s| !s.is_empty()) {
+        println!("🛑 Model {} stop sequences: {:?}", model_label, stop_sequences);
+    }
+    let mut loop_detector = model_config.loop_detection.map(LoopDetector::new);
+    if let Some(loop_detection) = model_config.loop_detection {
+        println!("🔂 Model {} loop detection: {}-token n-grams, {} repeats within {} tokens",
+                 model_label, loop_detection.ngram, loop_detection.max_repeats, loop_detection.window);
+    }
+    // Resuming: recorded tokens replace the sampled ones until they run out (see resume.rs)
+    let mut replay = model_config.resume_tokens.as_deref().unwrap_or_default().iter();
+    let mut resumed_tokens = 0;
+    let mut resume_diverged_at = None;
+    let mut generated_token_ids = Vec::new();
     
     // Timing for TTFT and TPS calculation
     let inference_start = Instant::now();
     let mut first_token_time: Option<Instant> = None;
     let mut last_token_time: Option<Instant> = None;
+    let mut rolling_tps_tracker = RollingTpsWindow::new(decode_metrics.rolling_tps_window);
+    let mut inter_token_stats = InterTokenStats::new(decode_metrics.stall_threshold);
     let mut tokens_generated = 0;
     
     // Initialize UTF-8 decoder for fallback
@@ -226,7 +845,8 @@ pub async fn run_model_inference(
     }
 
     // Create configured sampler from model configuration
-    let mut sampler = SamplerBuilder::create_from_config(model_config);
+    let mut sampler = SamplerBuilder::create_for_model(model_config, &model)
+        .map_err(|e| A2oError::InvalidInput(format!("Model {}: {}", model_label, e)))?;
 
     // Log the configuration for debugging and user feedback
     let config_description = SamplerBuilder::describe_config(model_config);
@@ -243,588 +863,321 @@ pub async fn run_model_inference(
              model_config.repeat_last_n,
              model_config.frequency_penalty,
              model_config.presence_penalty);
+    if model_config.penalize_newline == Some(false) || model_config.penalty_exempt_tokens.is_some() {
+        println!("🎛️ Model {} penalty exemptions: penalize_newline={:?}, exempt_tokens={:?}",
+                 model_label, model_config.penalize_newline, model_config.penalty_exempt_tokens);
+    }
     
-    // Main generation loop following official example pattern
-    while n_cur <= n_len {
-        // Check stop signal before processing each token
-        if let Ok(stop_signal_guard) = GLOBAL_STOP_SIGNAL.read() {
-            if let Some(stop_signal) = stop_signal_guard.as_ref() {
-                if stop_signal.load(Ordering::Relaxed) {
-                    println!("🛑 Stop signal detected, halting generation for Model {}", model_label);
-                    // Emit stopped event
-                    let _ = window.emit("generation_stopped", TokenEvent {
-                        token: String::new(),
-                        model: model_label.to_string(),
-                        finished: true,
-                    });
-                    break;
-                }
-            }
+    // Event emits and telemetry broadcasts run on a worker thread so the decode loop can
+    // sample and decode the next token while the previous one is still being published
+    let emit_pipeline = EmitPipeline::start(model_label);
+    let mut decode_timings = DecodeTimings::default();
+
+    if let Some(markers) = &phase_markers {
+        markers.begin(InferencePhase::Decode);
+    }
+
+    // Main generation loop following official example pattern; positions freed by context
+    // shifts do not extend the max_tokens budget
+    while n_cur + discarded_tokens <= n_len {
+        let iteration_start = Instant::now();
+
+        // Check this model's (or the global) stop signal before processing each token
+        if model_stop_requested(model_label) {
+            println!("🛑 Stop signal detected, halting generation for Model {}", model_label);
+            stop_reason = "user_stop";
+            // Emit stopped event after any queued token events
+            let job_events = events.clone();
+            let job_model = model_label.to_string();
+            emit_pipeline.submit(move || {
+                let _ = job_events.emit("generation_stopped", TokenEvent {
+                    token: String::new(),
+                    model: job_model,
+                    finished: true,
+                });
+            });
+            break;
         }
         
         // Sample the next token using proper LlamaSampler
-        let token = sampler.sample(&ctx, batch.n_tokens() - 1);
-        sampler.accept(token);
-        
+        let sample_start = Instant::now();
+        let token = match speculative.as_mut().and_then(|speculative| speculative.next_verified()) {
+            // Already sampled and accepted while verifying the draft
+            Some(verified) => verified,
+            None => {
+                let mut token = sampler.sample(&ctx, batch.n_tokens() - 1)?;
+                if let Some(&recorded) = replay.next() {
+                    let recorded = LlamaToken(recorded);
+                    if recorded != token && resume_diverged_at.is_none() {
+                        println!("⚠️ Model {} diverged from the resumed generation at token {}; the continuation will differ",
+                                 model_label, resumed_tokens + 1);
+                        resume_diverged_at = Some(resumed_tokens);
+                    }
+                    token = recorded;
+                    resumed_tokens += 1;
+                }
+                sampler.accept(token);
+                token
+            }
+        };
+        let sample_time = sample_start.elapsed();
         
         // Check for end of generation using proper method
         if model.is_eog_token(token) {
+            stop_reason = "eog";
             break;
         }
+        generated_token_ids.push(token.0);
+        // The token that completes a loop is still output; generation ends after it
+        let loop_detected = loop_detector.as_mut().is_some_and(|detector| detector.push(token));
         
         let output_bytes = model.token_to_bytes(token, Special::Tokenize)
-            .map_err(|e| format!("Failed to convert token to bytes: {:?}", e))?;
+            .map_err(|e| A2oError::Tokenization(format!("Failed to convert token to bytes: {:?}", e)))?;
         
-        // Try simple approach: convert bytes to string directly
-        match String::from_utf8(output_bytes.clone()) {
-            Ok(output_string) => {
+        // Convert bytes to string directly, falling back to the incremental decoder for invalid UTF-8
+        let output_string = match String::from_utf8(output_bytes) {
+            Ok(output_string) => output_string,
+            Err(e) => {
+                let mut output_string = String::with_capacity(32);
+                let (_decode_result, _bytes_read, _had_errors) = decoder.decode_to_string(e.as_bytes(), &mut output_string, false);
+                output_string
+            }
+        };
 dprintln!("🔍 Token decoded: '{}' (empty: {})", output_string, output_string.is_empty());
-                if !output_string.is_empty() {
-                    result.push_str(&output_string);
-                    tokens_generated += 1;
-                    
-                    // Record first token time for TTFT calculation
-                    if first_token_time.is_none() {
-                        let now = Instant::now();
-                        first_token_time = Some(now);
-                        last_token_time = Some(now);
-dprintln!("🚀 TTFT: First token detected! Token: '{}', Tokens generated: {}", output_string, tokens_generated);
-                        
-                        // Emit TTFT telemetry merged with current hardware data
-                        if let Some(broadcaster) = &telemetry_broadcaster {
-                            let ttft_ms = inference_start.elapsed().as_millis() as u64;
-dprintln!("🚀 TTFT: Calculated TTFT as {}ms", ttft_ms);
-
-                            // Get current telemetry state and merge with TTFT data
-                            let telemetry = if let Ok(current) = CURRENT_TELEMETRY.read() {
-                                if let Some(base_telemetry) = current.as_ref() {
-dprintln!("🔄 TTFT: Merging with existing hardware telemetry");
-                                    base_telemetry.with_inference_data(Some(ttft_ms), None, None, Some(model_label.to_string()))
-                                } else {
-dprintln!("⚠️ TTFT: No current telemetry state available, using empty base");
-                                    TelemetryUpdate {
-                                        timestamp_ms: std::time::SystemTime::now()
-                                            .duration_since(std::time::UNIX_EPOCH)
-                                            .unwrap()
-                                            .as_millis() as u64,
-                                        cpu_power_watts: None,
-                                        gpu_power_watts: None,
-                                        ane_power_watts: None,
-                                        cpu_temp_celsius: None,
-                                        gpu_temp_celsius: None,
-                                        cpu_freq_mhz: None,
-                                        gpu_freq_mhz: None,
-                                        ram_usage_gb: None,
-                                        thermal_pressure: None,
-                                        cpu_temp_avg: None,
-                                        cpu_temp_max: None,
-                                        cpu_p_core_temps: None,
-                                        cpu_e_core_temps: None,
-                                        gpu_temp_avg: None,
-                                        gpu_temp_max: None,
-                                        gpu_cluster_temps: None,
-                                        battery_temp_avg: None,
-                                        cpu_p_core_utilization: None,
-                                        cpu_e_core_utilization: None,
-                                        cpu_overall_utilization: None,
-                                        ttft_ms: Some(ttft_ms),
-                                        current_tps: None,
-                                        instantaneous_tps: None,
-                                        generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                        core_temperatures: None,
-                                        // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                        total_energy_wh: None,
-                                        cpu_energy_wh: None,
-                                        gpu_energy_wh: None,
-                                        ane_energy_wh: None,
-                                        energy_rate_wh_per_token: None,
-                                    }
-                                }
-                            } else {
-dprintln!("❌ TTFT: Failed to read current telemetry state");
-                                TelemetryUpdate {
-                                    timestamp_ms: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap()
-                                        .as_millis() as u64,
-                                    cpu_power_watts: None,
-                                    gpu_power_watts: None,
-                                    ane_power_watts: None,
-                                    cpu_temp_celsius: None,
-                                    gpu_temp_celsius: None,
-                                    cpu_freq_mhz: None,
-                                    gpu_freq_mhz: None,
-                                    ram_usage_gb: None,
-                                    thermal_pressure: None,
-                                    cpu_temp_avg: None,
-                                    cpu_temp_max: None,
-                                    cpu_p_core_temps: None,
-                                    cpu_e_core_temps: None,
-                                    gpu_temp_avg: None,
-                                    gpu_temp_max: None,
-                                    gpu_cluster_temps: None,
-                                    battery_temp_avg: None,
-                                    cpu_p_core_utilization: None,
-                                    cpu_e_core_utilization: None,
-                                    cpu_overall_utilization: None,
-                                    ttft_ms: Some(ttft_ms),
-                                    current_tps: None,
-                                    instantaneous_tps: None,
-                                    generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                    core_temperatures: None,
-                                    // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                    total_energy_wh: None,
-                                    cpu_energy_wh: None,
-                                    gpu_energy_wh: None,
-                                    ane_energy_wh: None,
-                                    energy_rate_wh_per_token: None,
-                                }
-                            };
-
-dprintln!("🚀 TTFT: Broadcasting merged telemetry: {:?}", telemetry);
-                            match broadcaster.send(telemetry) {
-                                Ok(receiver_count) => {
-dprintln!("🚀 TTFT: ✅ Broadcast successful to {} receivers", receiver_count);
-                                }
-                                Err(e) => {
-dprintln!("🚀 TTFT: ❌ Failed to broadcast TTFT telemetry: {}", e);
-                                }
-                            }
-                        } else {
-                            println!("❌ No telemetry broadcaster available for TTFT");
-                        }
-                    } else {
-dprintln!("🔄 Subsequent token: '{}', Tokens generated: {}", output_string, tokens_generated);
+
+        if !output_string.is_empty() {
+            let now = Instant::now();
+            // Only text confirmed not to be part of a stop sequence is recorded and emitted
+            let released = match stop_matcher.push(&output_string) {
+                StopScan::Continue { released } => released,
+                StopScan::Stopped { released, sequence } => {
+                    println!("🛑 Model {} generated stop sequence {:?}", model_label, sequence);
+                    stop_reason = "stop_sequence";
+                    matched_stop_sequence = Some(sequence);
+                    released
+                }
+            };
+            result.push_str(&released);
+            append_partial_output(model_label, &released);
+            if let Some(stream) = output_stream.as_mut() {
+                if let Err(e) = stream.write_token(&released, Some(token.0)) {
+                    println!("⚠️ {} - disabling output streaming for Model {}", e, model_label);
+                    output_stream = None;
+                }
+            }
+            let output_string = released;
+            tokens_generated += 1;
+            rolling_tps_tracker.record_token(now);
+
+            // TTFT on the first token, cumulative, instantaneous and rolling-window TPS afterwards
+            let mut ttft_ms = None;
+            let mut current_tps = None;
+            let mut instantaneous_tps = None;
+            let rolling_tps = rolling_tps_tracker.tps(now);
+            match first_token_time {
+                None => {
+                    first_token_time = Some(now);
+                    ttft_ms = Some(now.duration_since(inference_start).as_millis() as u64);
+dprintln!("🚀 TTFT: First token detected! Token: '{}', TTFT: {:?}ms", output_string, ttft_ms);
+                }
+                Some(first_token_instant) => {
+                    let elapsed_since_first = now.duration_since(first_token_instant).as_secs_f64();
+                    if elapsed_since_first > 0.0 {
+                        current_tps = Some((tokens_generated - 1) as f64 / elapsed_since_first);
                     }
-                    
-                    // Calculate and emit current TPS
-                    if let Some(first_token_instant) = first_token_time {
-                        if tokens_generated > 1 {
-                            let now = Instant::now();
-                            let elapsed_since_first = first_token_instant.elapsed().as_secs_f64();
-                            if elapsed_since_first > 0.0 {
-                                let current_tps = (tokens_generated - 1) as f64 / elapsed_since_first;
-
-                                // Calculate instantaneous TPS (time between last two tokens)
-                                let instantaneous_tps = if let Some(last_instant) = last_token_time {
-                                    let time_between_tokens = now.duration_since(last_instant).as_secs_f64();
-                                    if time_between_tokens > 0.0 {
-                                        Some(1.0 / time_between_tokens)
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                };
-
-                                // Update last token time for next calculation
-                                last_token_time = Some(now);
-                                
-                                // Emit TPS telemetry merged with current hardware data
-                                if let Some(broadcaster) = &telemetry_broadcaster {
-                                    // Get current telemetry state and merge with TPS data
-                                    let telemetry = if let Ok(current) = CURRENT_TELEMETRY.read() {
-                                        if let Some(base_telemetry) = current.as_ref() {
-dprintln!("🔄 TPS: Merging with existing hardware telemetry");
-                                            base_telemetry.with_inference_data(None, Some(current_tps), instantaneous_tps, Some(model_label.to_string()))
-                                        } else {
-dprintln!("⚠️ TPS: No current telemetry state available, using empty base");
-                                            TelemetryUpdate {
-                                                timestamp_ms: std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH)
-                                                    .unwrap()
-                                                    .as_millis() as u64,
-                                                cpu_power_watts: None,
-                                                gpu_power_watts: None,
-                                                ane_power_watts: None,
-                                                cpu_temp_celsius: None,
-                                                gpu_temp_celsius: None,
-                                                cpu_freq_mhz: None,
-                                                gpu_freq_mhz: None,
-                                                ram_usage_gb: None,
-                                                thermal_pressure: None,
-                                                cpu_temp_avg: None,
-                                                cpu_temp_max: None,
-                                                cpu_p_core_temps: None,
-                                                cpu_e_core_temps: None,
-                                                gpu_temp_avg: None,
-                                                gpu_temp_max: None,
-                                                gpu_cluster_temps: None,
-                                                battery_temp_avg: None,
-                                                cpu_p_core_utilization: None,
-                                                cpu_e_core_utilization: None,
-                                                cpu_overall_utilization: None,
-                                                ttft_ms: None,
-                                                current_tps: Some(current_tps),
-                                                instantaneous_tps,
-                                                generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                                core_temperatures: None,
-                                                // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                                total_energy_wh: None,
-                                                cpu_energy_wh: None,
-                                                gpu_energy_wh: None,
-                                                ane_energy_wh: None,
-                                                energy_rate_wh_per_token: None,
-                                            }
-                                        }
-                                    } else {
-dprintln!("❌ TPS: Failed to read current telemetry state");
-                                        TelemetryUpdate {
-                                            timestamp_ms: std::time::SystemTime::now()
-                                                .duration_since(std::time::UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64,
-                                            cpu_power_watts: None,
-                                            gpu_power_watts: None,
-                                            ane_power_watts: None,
-                                            cpu_temp_celsius: None,
-                                            gpu_temp_celsius: None,
-                                            cpu_freq_mhz: None,
-                                            gpu_freq_mhz: None,
-                                            ram_usage_gb: None,
-                                            thermal_pressure: None,
-                                            cpu_temp_avg: None,
-                                            cpu_temp_max: None,
-                                            cpu_p_core_temps: None,
-                                            cpu_e_core_temps: None,
-                                            gpu_temp_avg: None,
-                                            gpu_temp_max: None,
-                                            gpu_cluster_temps: None,
-                                            battery_temp_avg: None,
-                                            cpu_p_core_utilization: None,
-                                            cpu_e_core_utilization: None,
-                                            cpu_overall_utilization: None,
-                                            ttft_ms: None,
-                                            current_tps: Some(current_tps),
-                                            instantaneous_tps,
-                                            generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                            core_temperatures: None,
-                                            // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                            total_energy_wh: None,
-                                            cpu_energy_wh: None,
-                                            gpu_energy_wh: None,
-                                            ane_energy_wh: None,
-                                            energy_rate_wh_per_token: None,
-                                        }
-                                    };
-
-dprintln!("📈 TPS: Broadcasting merged telemetry: {:?}", telemetry);
-dprintln!("📈 TPS: Pre-broadcast receiver count: {}", broadcaster.receiver_count());
-                                    match broadcaster.send(telemetry) {
-                                        Ok(receiver_count) => {
-dprintln!("📈 TPS: ✅ Broadcast successful to {} receivers", receiver_count);
-dprintln!("📈 TPS: Post-broadcast receiver count: {}", broadcaster.receiver_count());
-                                        }
-                                        Err(e) => {
-dprintln!("📈 TPS: ❌ Failed to broadcast TPS telemetry: {}", e);
-                                        }
-                                    }
-                                }
-                            }
+                    // Instantaneous TPS (time between last two tokens)
+                    if let Some(last_instant) = last_token_time {
+                        let latency = now.duration_since(last_instant);
+                        inter_token_stats.record(latency);
+                        let time_between_tokens = latency.as_secs_f64();
+                        if time_between_tokens > 0.0 {
+                            instantaneous_tps = Some(1.0 / time_between_tokens);
                         }
                     }
-                    
-                    // Emit token event to frontend
-dprintln!("BACKEND EMIT: Model: {}, Token: '{}'", model_label, output_string);
-                    let _ = window.emit("new_token", TokenEvent {
-                        token: output_string,
-                        model: model_label.to_string(),
-                        finished: false,
-                    });
-                } else {
-                    println!("🔍 Skipping empty token");
+dprintln!("🔄 Subsequent token: '{}', Tokens generated: {}", output_string, tokens_generated);
                 }
             }
-            Err(_) => {
-                // For invalid UTF-8, fall back to incremental decoder
-                let mut output_string = String::with_capacity(32);
-                let (_decode_result, _bytes_read, _had_errors) = decoder.decode_to_string(&output_bytes, &mut output_string, false);
-                
-dprintln!("🔍 Fallback token decoded: '{}' (empty: {})", output_string, output_string.is_empty());
-                if !output_string.is_empty() {
-                    result.push_str(&output_string);
-                    tokens_generated += 1;
-                    
-                    // Record first token time for TTFT calculation
-                    if first_token_time.is_none() {
-                        let now = Instant::now();
-                        first_token_time = Some(now);
-                        last_token_time = Some(now);
-dprintln!("🚀 TTFT: First token detected! Token: '{}', Tokens generated: {}", output_string, tokens_generated);
-                        
-                        // Emit TTFT telemetry merged with current hardware data
-                        if let Some(broadcaster) = &telemetry_broadcaster {
-                            let ttft_ms = inference_start.elapsed().as_millis() as u64;
-dprintln!("🚀 TTFT: Calculated TTFT as {}ms", ttft_ms);
-
-                            // Get current telemetry state and merge with TTFT data
-                            let telemetry = if let Ok(current) = CURRENT_TELEMETRY.read() {
-                                if let Some(base_telemetry) = current.as_ref() {
-dprintln!("🔄 TTFT: Merging with existing hardware telemetry");
-                                    base_telemetry.with_inference_data(Some(ttft_ms), None, None, Some(model_label.to_string()))
-                                } else {
-dprintln!("⚠️ TTFT: No current telemetry state available, using empty base");
-                                    TelemetryUpdate {
-                                        timestamp_ms: std::time::SystemTime::now()
-                                            .duration_since(std::time::UNIX_EPOCH)
-                                            .unwrap()
-                                            .as_millis() as u64,
-                                        cpu_power_watts: None,
-                                        gpu_power_watts: None,
-                                        ane_power_watts: None,
-                                        cpu_temp_celsius: None,
-                                        gpu_temp_celsius: None,
-                                        cpu_freq_mhz: None,
-                                        gpu_freq_mhz: None,
-                                        ram_usage_gb: None,
-                                        thermal_pressure: None,
-                                        cpu_temp_avg: None,
-                                        cpu_temp_max: None,
-                                        cpu_p_core_temps: None,
-                                        cpu_e_core_temps: None,
-                                        gpu_temp_avg: None,
-                                        gpu_temp_max: None,
-                                        gpu_cluster_temps: None,
-                                        battery_temp_avg: None,
-                                        cpu_p_core_utilization: None,
-                                        cpu_e_core_utilization: None,
-                                        cpu_overall_utilization: None,
-                                        ttft_ms: Some(ttft_ms),
-                                        current_tps: None,
-                                        instantaneous_tps: None,
-                                        generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                        core_temperatures: None,
-                                        // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                        total_energy_wh: None,
-                                        cpu_energy_wh: None,
-                                        gpu_energy_wh: None,
-                                        ane_energy_wh: None,
-                                        energy_rate_wh_per_token: None,
-                                    }
-                                }
-                            } else {
-dprintln!("❌ TTFT: Failed to read current telemetry state");
-                                TelemetryUpdate {
-                                    timestamp_ms: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap()
-                                        .as_millis() as u64,
-                                    cpu_power_watts: None,
-                                    gpu_power_watts: None,
-                                    ane_power_watts: None,
-                                    cpu_temp_celsius: None,
-                                    gpu_temp_celsius: None,
-                                    cpu_freq_mhz: None,
-                                    gpu_freq_mhz: None,
-                                    ram_usage_gb: None,
-                                    thermal_pressure: None,
-                                    cpu_temp_avg: None,
-                                    cpu_temp_max: None,
-                                    cpu_p_core_temps: None,
-                                    cpu_e_core_temps: None,
-                                    gpu_temp_avg: None,
-                                    gpu_temp_max: None,
-                                    gpu_cluster_temps: None,
-                                    battery_temp_avg: None,
-                                    cpu_p_core_utilization: None,
-                                    cpu_e_core_utilization: None,
-                                    cpu_overall_utilization: None,
-                                    ttft_ms: Some(ttft_ms),
-                                    current_tps: None,
-                                    instantaneous_tps: None,
-                                    generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                    core_temperatures: None,
-                                    // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                    total_energy_wh: None,
-                                    cpu_energy_wh: None,
-                                    gpu_energy_wh: None,
-                                    ane_energy_wh: None,
-                                    energy_rate_wh_per_token: None,
-                                }
-                            };
-
-dprintln!("🚀 TTFT: Broadcasting merged telemetry: {:?}", telemetry);
-                            match broadcaster.send(telemetry) {
-                                Ok(receiver_count) => {
-dprintln!("🚀 TTFT: ✅ Broadcast successful to {} receivers", receiver_count);
-                                }
-                                Err(e) => {
-dprintln!("🚀 TTFT: ❌ Failed to broadcast TTFT telemetry: {}", e);
-                                }
-                            }
-                        } else {
-                            println!("❌ No telemetry broadcaster available for TTFT");
-                        }
-                    } else {
-dprintln!("🔄 Subsequent token: '{}', Tokens generated: {}", output_string, tokens_generated);
-                    }
-                    
-                    // Calculate and emit current TPS
-                    if let Some(first_token_instant) = first_token_time {
-                        if tokens_generated > 1 {
-                            let now = Instant::now();
-                            let elapsed_since_first = first_token_instant.elapsed().as_secs_f64();
-                            if elapsed_since_first > 0.0 {
-                                let current_tps = (tokens_generated - 1) as f64 / elapsed_since_first;
-
-                                // Calculate instantaneous TPS (time between last two tokens)
-                                let instantaneous_tps = if let Some(last_instant) = last_token_time {
-                                    let time_between_tokens = now.duration_since(last_instant).as_secs_f64();
-                                    if time_between_tokens > 0.0 {
-                                        Some(1.0 / time_between_tokens)
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    None
-                                };
-
-                                // Update last token time for next calculation
-                                last_token_time = Some(now);
-                                
-                                // Emit TPS telemetry merged with current hardware data
-                                if let Some(broadcaster) = &telemetry_broadcaster {
-                                    // Get current telemetry state and merge with TPS data
-                                    let telemetry = if let Ok(current) = CURRENT_TELEMETRY.read() {
-                                        if let Some(base_telemetry) = current.as_ref() {
-dprintln!("🔄 TPS: Merging with existing hardware telemetry");
-                                            base_telemetry.with_inference_data(None, Some(current_tps), instantaneous_tps, Some(model_label.to_string()))
-                                        } else {
-dprintln!("⚠️ TPS: No current telemetry state available, using empty base");
-                                            TelemetryUpdate {
-                                                timestamp_ms: std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH)
-                                                    .unwrap()
-                                                    .as_millis() as u64,
-                                                cpu_power_watts: None,
-                                                gpu_power_watts: None,
-                                                ane_power_watts: None,
-                                                cpu_temp_celsius: None,
-                                                gpu_temp_celsius: None,
-                                                cpu_freq_mhz: None,
-                                                gpu_freq_mhz: None,
-                                                ram_usage_gb: None,
-                                                thermal_pressure: None,
-                                                cpu_temp_avg: None,
-                                                cpu_temp_max: None,
-                                                cpu_p_core_temps: None,
-                                                cpu_e_core_temps: None,
-                                                gpu_temp_avg: None,
-                                                gpu_temp_max: None,
-                                                gpu_cluster_temps: None,
-                                                battery_temp_avg: None,
-                                                cpu_p_core_utilization: None,
-                                                cpu_e_core_utilization: None,
-                                                cpu_overall_utilization: None,
-                                                ttft_ms: None,
-                                                current_tps: Some(current_tps),
-                                                instantaneous_tps,
-                                                generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                                core_temperatures: None,
-                                                // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                                total_energy_wh: None,
-                                                cpu_energy_wh: None,
-                                                gpu_energy_wh: None,
-                                                ane_energy_wh: None,
-                                                energy_rate_wh_per_token: None,
-                                            }
-                                        }
-                                    } else {
-dprintln!("❌ TPS: Failed to read current telemetry state");
-                                        TelemetryUpdate {
-                                            timestamp_ms: std::time::SystemTime::now()
-                                                .duration_since(std::time::UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64,
-                                            cpu_power_watts: None,
-                                            gpu_power_watts: None,
-                                            ane_power_watts: None,
-                                            cpu_temp_celsius: None,
-                                            gpu_temp_celsius: None,
-                                            cpu_freq_mhz: None,
-                                            gpu_freq_mhz: None,
-                                            ram_usage_gb: None,
-                                            thermal_pressure: None,
-                                            cpu_temp_avg: None,
-                                            cpu_temp_max: None,
-                                            cpu_p_core_temps: None,
-                                            cpu_e_core_temps: None,
-                                            gpu_temp_avg: None,
-                                            gpu_temp_max: None,
-                                            gpu_cluster_temps: None,
-                                            battery_temp_avg: None,
-                                            cpu_p_core_utilization: None,
-                                            cpu_e_core_utilization: None,
-                                            cpu_overall_utilization: None,
-                                            ttft_ms: None,
-                                            current_tps: Some(current_tps),
-                                            instantaneous_tps,
-                                            generation_time_ms: None,
-                                        model: Some(model_label.to_string()),
-                                            core_temperatures: None,
-                                            // Energy fields (initialized as None, will be filled by PowerCalculator)
-                                            total_energy_wh: None,
-                                            cpu_energy_wh: None,
-                                            gpu_energy_wh: None,
-                                            ane_energy_wh: None,
-                                            energy_rate_wh_per_token: None,
-                                        }
-                                    };
-
-dprintln!("📈 TPS: Broadcasting merged telemetry: {:?}", telemetry);
-dprintln!("📈 TPS: Pre-broadcast receiver count: {}", broadcaster.receiver_count());
-                                    match broadcaster.send(telemetry) {
-                                        Ok(receiver_count) => {
-dprintln!("📈 TPS: ✅ Broadcast successful to {} receivers", receiver_count);
-dprintln!("📈 TPS: Post-broadcast receiver count: {}", broadcaster.receiver_count());
-                                        }
-                                        Err(e) => {
-dprintln!("📈 TPS: ❌ Failed to broadcast TPS telemetry: {}", e);
-                                        }
-                                    }
-                                }
-                            }
-                        }
+            last_token_time = Some(now);
+
+            // Hand telemetry merge/broadcast and the token event to the emit worker
+            let job_events = events.clone();
+            let job_broadcaster = telemetry_broadcaster.clone();
+            let job_model = model_label.to_string();
+            emit_pipeline.submit(move || {
+                if ttft_ms.is_some() || current_tps.is_some() {
+                    match &job_broadcaster {
+                        Some(broadcaster) => broadcast_inference_telemetry(broadcaster, ttft_ms, current_tps, instantaneous_tps, rolling_tps, &job_model),
+                        None if ttft_ms.is_some() => println!("❌ No telemetry broadcaster available for TTFT"),
+                        None => {}
                     }
-                    
-dprintln!("BACKEND EMIT (fallback): Model: {}, Token: '{}'", model_label, output_string);
-                    let _ = window.emit("new_token", TokenEvent {
-                        token: output_string,
-                        model: model_label.to_string(),
-                        finished: false,
-                    });
-                } else {
-                    println!("🔍 Skipping empty fallback token");
+                }
+
+                if output_string.is_empty() {
+                    return;
+                }
+dprintln!("BACKEND EMIT: Model: {}, Token: '{}'", job_model, output_string);
+                let _ = job_events.emit("new_token", TokenEvent {
+                    token: output_string,
+                    model: job_model,
+                    finished: false,
+                });
+            });
+            if matched_stop_sequence.is_some() {
+                break;
+            }
+        } else {
+            println!("🔍 Skipping empty token");
+            // Still recorded so the stream holds every token id needed to resume
+            if let Some(stream) = output_stream.as_mut() {
+                if let Err(e) = stream.write_token("", Some(token.0)) {
+                    println!("⚠️ {} - disabling output streaming for Model {}", e, model_label);
+                    output_stream = None;
                 }
             }
         }
+        if loop_detected {
+            println!("🔂 Model {} is repeating itself - stopping after {} tokens", model_label, tokens_generated);
+            stop_reason = "loop_detected";
+            break;
+        }
         
-        // Prepare for next iteration following official pattern
-        batch.clear();
-        batch.add(token, n_cur, &[0], true)
-            .map_err(|e| format!("Failed to add token to batch: {:?}", e))?;
-        
+        // Emit rate-limited progress/ETA updates for newly generated tokens
+        if tokens_generated > progress.tokens_generated() {
+            let now = Instant::now();
+            while progress.tokens_generated() < tokens_generated {
+                progress.record_token(now);
+            }
+            if progress.should_emit(now) {
+                let event = GenerationProgressEvent {
+                    model: model_label.to_string(),
+                    tokens_generated: progress.tokens_generated(),
+                    max_tokens: progress.max_tokens(),
+                    rolling_tps: progress.rolling_tps(),
+                    eta_ms: progress.eta_ms(),
+                    context_used_tokens: n_cur as usize,
+                    n_ctx,
+                    elapsed_ms: inference_start.elapsed().as_millis() as u64,
+                    draft_acceptance_rate: speculative.as_ref().and_then(|speculative| speculative.stats.acceptance_rate),
+                    timestamp_ms: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64,
+                };
+                let job_events = events.clone();
+                emit_pipeline.submit(move || {
+                    let _ = job_events.emit("generation_progress", event);
+                });
+            }
+        }
+
+        // Full cache: evict the oldest tokens after the kept prefix and slide the rest down
+        if context_shift && n_cur >= kv_capacity {
+            let shift = plan_context_shift(n_cur, n_keep)
+                .ok_or_else(|| A2oError::Inference(format!("Context of {} tokens is too small to shift", kv_capacity)))?;
+            apply_context_shift(&mut ctx, shift, n_cur).map_err(A2oError::Inference)?;
+            peak_context_tokens = peak_context_tokens.max(n_cur as usize);
+            n_cur -= shift.discard;
+            discarded_tokens += shift.discard;
+            context_shifts += 1;
+            println!("🔁 CONTEXT SHIFT: Model {} shift {} evicted {} token(s) after the first {} ({} generated so far)",
+                     model_label, context_shifts, shift.discard, shift.keep, tokens_generated);
+            let event = ContextShiftEvent {
+                model: model_label.to_string(),
+                shift: context_shifts,
+                kept_tokens: shift.keep as usize,
+                discarded_tokens: shift.discard as usize,
+                n_ctx: kv_capacity as u32,
+                tokens_generated,
+                timestamp_ms: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+            };
+            let job_events = events.clone();
+            emit_pipeline.submit(move || {
+                let _ = job_events.emit("context_shift", event);
+            });
+        }
+
+        // Decode the token for the next iteration; with a draft model this also drafts and
+        // verifies the tokens after it, or does nothing for an already verified draft token
+        let decode_start = Instant::now();
+        match speculative.as_mut() {
+            Some(speculative) => speculative.decode_after(&mut ctx, &mut batch, &mut sampler, token, n_cur, n_batch as usize)?,
+            None => {
+                batch.clear();
+                batch.add(token, n_cur, &[0], true)
+                    .map_err(|e| A2oError::Inference(format!("Failed to add token to batch: {:?}", e)))?;
+                ctx.decode(&mut batch)
+                    .map_err(|e| A2oError::Inference(format!("Failed to decode batch: {:?}", e)))?;
+            }
+        }
+        let decode_time = decode_start.elapsed();
         n_cur += 1;
         
-        // Decode the batch for next iteration
-        ctx.decode(&mut batch)
-            .map_err(|e| format!("Failed to decode batch: {:?}", e))?;
-        
         _n_decode += 1;
+        decode_timings.record(iteration_start.elapsed(), sample_time, decode_time);
+    }
+
+    // Generation ended without a stop sequence: text held back as a possible match is output after all
+    let held_back = stop_matcher.flush();
+    if !held_back.is_empty() {
+        result.push_str(&held_back);
+        append_partial_output(model_label, &held_back);
+        if let Some(stream) = output_stream.as_mut() {
+            if let Err(e) = stream.write_token(&held_back, None) {
+                println!("⚠️ {} - disabling output streaming for Model {}", e, model_label);
+                output_stream = None;
+            }
+        }
+        let job_events = events.clone();
+        let job_model = model_label.to_string();
+        emit_pipeline.submit(move || {
+            let _ = job_events.emit("new_token", TokenEvent {
+                token: held_back,
+                model: job_model,
+                finished: false,
+            });
+        });
     }
+
+    // Decode ends here; the marker is confirmed only before the energy summary reads the totals
+    let decode_end_ms = phase_markers.as_ref().map(|markers| markers.now_ms());
+
+    // Wait for queued token events so the final events below arrive after them
+    let emit_worker_time = emit_pipeline.finish();
+    println!("⚙️ DECODE OVERHEAD: Model {} - sample {:?}ms, decode {:?}ms, framework overhead {:?}ms per token ({} tokens, emit worker {:?}ms total)",
+             model_label,
+             decode_timings.sample_ms_per_token(),
+             decode_timings.decode_ms_per_token(),
+             decode_timings.overhead_ms_per_token(),
+             decode_timings.tokens(),
+             emit_worker_time.as_millis());
+    let _ = events.emit("decode_overhead", DecodeOverheadEvent {
+        model: model_label.to_string(),
+        tokens: decode_timings.tokens(),
+        sample_ms_per_token: decode_timings.sample_ms_per_token(),
+        decode_ms_per_token: decode_timings.decode_ms_per_token(),
+        overhead_ms_per_token: decode_timings.overhead_ms_per_token(),
+        emit_worker_ms_per_token: decode_timings.per_token_ms(emit_worker_time),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    });
     
     // No need to flush when using String::from_utf8 approach
+
+    // Close the on-disk output stream with the reason generation ended
+    if let Some(stream) = output_stream.take() {
+        if let Err(e) = stream.finish(stop_reason) {
+            println!("⚠️ {}", e);
+        }
+    }
     
     // Phase 2: Emit output token count after generation completes
     println!("📊 OUTPUT TOKENS: Model {} generated {} tokens", model_label, tokens_generated);
-    let _ = window.emit("output_tokens", OutputTokenEvent {
+    let _ = events.emit("output_tokens", OutputTokenEvent {
         count: tokens_generated,
         model: model_label.to_string(),
         timestamp_ms: std::time::SystemTime::now()
@@ -836,7 +1189,7 @@ dprintln!("BACKEND EMIT (fallback): Model: {}, Token: '{}'", model_label, output
     // Phase 3: Emit total generation time
     let total_generation_time_ms = inference_start.elapsed().as_millis() as u64;
     println!("⏱️ GENERATION TIME: Model {} took {} ms total", model_label, total_generation_time_ms);
-    let _ = window.emit("generation_time", GenerationTimeEvent {
+    let _ = events.emit("generation_time", GenerationTimeEvent {
         generation_time_ms: total_generation_time_ms,
         model: model_label.to_string(),
         timestamp_ms: std::time::SystemTime::now()
@@ -845,47 +1198,140 @@ dprintln!("BACKEND EMIT (fallback): Model: {}, Token: '{}'", model_label, output
             .as_millis() as u64,
     });
     
+    // Phase 3b: Emit inter-token latency stability (jitter and stalls)
+    let stability = inter_token_stats.summary();
+    println!("📉 TOKEN STABILITY: Model {} - {} intervals, mean {:?}ms, CV {:?}, longest stall {:?}ms, {} stalls > {:?}",
+             model_label, stability.intervals, stability.mean_ms, stability.cv,
+             stability.longest_stall_ms, stability.stall_count, decode_metrics.stall_threshold);
+    let _ = events.emit("token_stability", TokenStabilityEvent {
+        model: model_label.to_string(),
+        intervals: stability.intervals,
+        mean_inter_token_ms: stability.mean_ms,
+        inter_token_cv: stability.cv,
+        longest_stall_ms: stability.longest_stall_ms,
+        stall_count: stability.stall_count,
+        stall_threshold_ms: decode_metrics.stall_threshold.as_secs_f64() * 1000.0,
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    });
+    
+    // Phase 3b2: Emit draft acceptance for speculative decoding
+    let speculative_stats = speculative.take().map(|speculative| speculative.stats);
+    if let Some(stats) = &speculative_stats {
+        println!("🎯 SPECULATIVE: Model {} accepted {}/{} draft tokens ({:?}) over {} rounds",
+                 model_label, stats.accepted_tokens, stats.drafted_tokens, stats.acceptance_rate, stats.rounds);
+        let _ = events.emit("speculative_decoding", SpeculativeDecodingEvent {
+            model: model_label.to_string(),
+            stats: stats.clone(),
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
+    }
+
+    // Phase 3c: Emit memory high-water marks, both relative to the pre-load baseline
+    let generation_memory = generation_watermark.finish();
+    let baseline_bytes = load_memory.start_bytes;
+    let memory_summary = MemoryUsageEvent {
+        model: model_label.to_string(),
+        baseline_gb: bytes_to_gb(baseline_bytes),
+        load_peak_gb: load_memory.peak_gb(),
+        load_delta_gb: load_memory.delta_gb(baseline_bytes),
+        generation_peak_gb: generation_memory.peak_gb(),
+        generation_delta_gb: generation_memory.delta_gb(baseline_bytes),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    };
+    println!("🧠 MEMORY: Model {} generation peak {:.2} GB (+{:.2} GB over baseline)",
+             model_label, memory_summary.generation_peak_gb, memory_summary.generation_delta_gb);
+    let _ = events.emit("memory_usage", memory_summary.clone());
+    
     // Phase 4: Emit final power consumption summary with energy per token
     // Only emit when telemetry was enabled for this run (i.e., a broadcaster was provided)
-    if telemetry_broadcaster.is_some() {
-        if let Ok(current) = CURRENT_TELEMETRY.read() {
-            if let Some(telemetry) = current.as_ref() {
-                if let (Some(total_energy), Some(cpu_energy), Some(gpu_energy), Some(ane_energy)) = 
-                    (telemetry.total_energy_wh, telemetry.cpu_energy_wh, telemetry.gpu_energy_wh, telemetry.ane_energy_wh) {
-                    
-                    // Calculate energy per token
-                    let energy_per_token = if tokens_generated > 0 {
-                        Some(total_energy / tokens_generated as f64)
-                    } else {
-                        None
-                    };
-                    
-                    println!("📊 ENERGY SUMMARY: Model {} - Total: {:.6}Wh, CPU: {:.6}Wh, GPU: {:.6}Wh, ANE: {:.6}Wh, Per Token: {:?}Wh", 
-                             model_label, total_energy, cpu_energy, gpu_energy, ane_energy, energy_per_token);
-                             
-                    let _ = window.emit("power_consumption_summary", PowerConsumptionSummaryEvent {
-                        total_energy_wh: total_energy,
-                        cpu_energy_wh: cpu_energy,
-                        gpu_energy_wh: gpu_energy,
-                        ane_energy_wh: ane_energy,
-                        energy_per_token_wh: energy_per_token,
-                        model: model_label.to_string(),
-                        timestamp_ms: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_millis() as u64,
-                    });
-                }
-            }
+    let mut energy_summary = None;
+    if let (Some(markers), Some(decode_end_ms)) = (&phase_markers, decode_end_ms) {
+        markers.end_acknowledged(InferencePhase::Decode, decode_end_ms).await;
+    }
+    // Parallel runs have no per-model phase: both models' energy is only known together, and
+    // the caller reports it as combined_energy
+    if telemetry_broadcaster.is_some() && phase_markers.is_some() {
+        // Totals are keyed by model label so the other model's phase can't reset or absorb them
+        let model_energy = MODEL_ENERGY.read()
+            .ok()
+            .and_then(|totals| totals.as_ref().and_then(|totals| totals.get(model_label).cloned()));
+        if let Some(energy) = model_energy {
+            let summary = energy_summary_event(energy, model_label, tokens_generated, prefilled_tokens,
+                                               measurement_overhead, run_start.elapsed());
+            let _ = events.emit("power_consumption_summary", summary.clone());
+            energy_summary = Some(summary);
+        } else {
+            println!("⚠️ ENERGY SUMMARY: No energy recorded for Model {}", model_label);
         }
     }
     
     // Emit final event indicating completion
-    let _ = window.emit("new_token", TokenEvent {
+    let _ = events.emit("new_token", TokenEvent {
         token: String::new(),
         model: model_label.to_string(),
         finished: true,
     });
+
+    // Decode TPS between the first and last token, matching the live current_tps
+    let avg_tps = match (first_token_time, last_token_time) {
+        (Some(first), Some(last)) if last > first => Some((tokens_generated - 1) as f64 / last.duration_since(first).as_secs_f64()),
+        _ => None,
+    };
     
-    Ok(result)
+    // The context only grows between shifts, so the peak is the final fill or a pre-shift one
+    let context_used_tokens = peak_context_tokens.max(n_cur as usize);
+    println!("📐 CONTEXT: Model {} used {}/{} context tokens ({:.1}%)",
+             model_label, context_used_tokens, n_ctx, context_used_tokens as f64 / n_ctx as f64 * 100.0);
+
+    record_checkpoint(ResumeCheckpoint {
+        model: model_label.to_string(),
+        model_path: model_config.model_path.clone(),
+        seed: match model_config.seed {
+            Some(SamplerSeed::Fixed(seed)) => Some(seed),
+            _ => None,
+        },
+        tokens: generated_token_ids,
+        text: result.clone(),
+        finish_reason: Some(stop_reason.to_string()),
+    });
+    if resumed_tokens > 0 {
+        println!("⏯️ Model {} replayed {} token(s) of the resumed generation (diverged at {:?})",
+                 model_label, resumed_tokens, resume_diverged_at);
+    }
+
+    Ok(GenerationResult {
+        model: model_label.to_string(),
+        text: result,
+        finish_reason: stop_reason.to_string(),
+        truncated: stop_reason == "max_tokens",
+        stop_sequence: matched_stop_sequence,
+        input_tokens: input_token_count,
+        output_tokens: tokens_generated,
+        model_load_ms,
+        model_reused,
+        effective_context: Some(effective_context),
+        ttft_ms: first_token_time.map(|first| first.duration_since(inference_start).as_millis() as u64),
+        prefill_ms: prefill_secs * 1000.0,
+        generation_time_ms: total_generation_time_ms,
+        avg_tps,
+        energy: energy_summary,
+        combined_energy: None,
+        memory: memory_summary,
+        sampling: SamplingParams::from_model_config(model_config),
+        context_used_tokens,
+        n_ctx,
+        context_shifts,
+        resumed_tokens,
+        resume_diverged_at,
+        speculative: speculative_stats,
+    })
 }
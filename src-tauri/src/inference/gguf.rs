@@ -0,0 +1,358 @@
+// Minimal GGUF header reader for model metadata (no tensor data is loaded)
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use serde::Serialize;
+
+const GGUF_MAGIC: u32 = 0x4655_4747; // "GGUF" little-endian
+
+// Guard against corrupt headers claiming absurd string lengths
+const MAX_STRING_LEN: u64 = 16 * 1024 * 1024;
+
+// ggml tensors have at most 4 dimensions
+const MAX_TENSOR_DIMS: u32 = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum GgufValue {
+    UInt(u64),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array { item_type: u32, len: u64 }, // Array contents are skipped (e.g. tokenizer vocab)
+}
+
+/// RoPE scaling declared in the model metadata, which llama.cpp applies on load
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RopeScaling {
+    pub kind: String,                           // "linear", "yarn", "longrope", ...
+    pub factor: Option<f64>,
+    pub original_context_length: Option<u64>,   // Pre-extension context length (YaRN)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GgufMetadata {
+    pub version: u32,
+    pub tensor_count: u64,
+    pub values: HashMap<String, GgufValue>,
+}
+
+impl GgufMetadata {
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Failed to open GGUF file {}: {}", path.display(), e))?;
+        Self::read_from(&mut BufReader::new(file))
+    }
+
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, String> {
+        let magic = read_u32(reader)?;
+        if magic != GGUF_MAGIC {
+            return Err("Not a GGUF file (bad magic)".to_string());
+        }
+
+        let version = read_u32(reader)?;
+        // GGUF v1 used 32-bit counts; v2+ use 64-bit counts
+        let (tensor_count, kv_count) = if version == 1 {
+            (read_u32(reader)? as u64, read_u32(reader)? as u64)
+        } else {
+            (read_u64(reader)?, read_u64(reader)?)
+        };
+
+        let mut values = HashMap::new();
+        for _ in 0..kv_count {
+            let key = read_string(reader, version)?;
+            let value_type = read_u32(reader)?;
+            let value = read_value(reader, value_type, version)?;
+            values.insert(key, value);
+        }
+
+        Ok(Self { version, tensor_count, values })
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        match self.values.get(key)? {
+            GgufValue::UInt(v) => Some(*v),
+            GgufValue::Int(v) if *v >= 0 => Some(*v as u64),
+            _ => None,
+        }
+    }
+
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        match self.values.get(key)? {
+            GgufValue::Float(v) => Some(*v),
+            GgufValue::UInt(v) => Some(*v as f64),
+            GgufValue::Int(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.values.get(key)? {
+            GgufValue::String(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn architecture(&self) -> Option<&str> {
+        self.get_str("general.architecture")
+    }
+
+    /// Look up an architecture-scoped key such as `llama.context_length`
+    pub fn get_arch_u64(&self, suffix: &str) -> Option<u64> {
+        let arch = self.architecture()?;
+        self.get_u64(&format!("{}.{}", arch, suffix))
+    }
+
+    /// Context length the model was trained with
+    pub fn context_length(&self) -> Option<u64> {
+        self.get_arch_u64("context_length")
+    }
+
+    /// RoPE scaling from `<arch>.rope.scaling.*` (or the older `rope.scale_linear`);
+    /// None when the model declares none
+    pub fn rope_scaling(&self) -> Option<RopeScaling> {
+        let arch = self.architecture()?;
+        let factor = self.get_f64(&format!("{}.rope.scaling.factor", arch));
+        let original_context_length = self.get_arch_u64("rope.scaling.original_context_length");
+        match self.get_str(&format!("{}.rope.scaling.type", arch)) {
+            Some("none") => None,
+            Some(kind) => Some(RopeScaling { kind: kind.to_string(), factor, original_context_length }),
+            None => self.get_f64(&format!("{}.rope.scale_linear", arch))
+                .filter(|scale| *scale > 1.0)
+                .map(|scale| RopeScaling { kind: "linear".to_string(), factor: Some(scale), original_context_length }),
+        }
+    }
+
+    /// Quantization name from `general.file_type` (llama.cpp's LLAMA_FTYPE), e.g. "Q4_K_M"
+    pub fn quantization(&self) -> Option<String> {
+        let file_type = self.get_u64("general.file_type")?;
+        let name = match file_type {
+            0 => "F32",
+            1 => "F16",
+            2 => "Q4_0",
+            3 => "Q4_1",
+            7 => "Q8_0",
+            8 => "Q5_0",
+            9 => "Q5_1",
+            10 => "Q2_K",
+            11 => "Q3_K_S",
+            12 => "Q3_K_M",
+            13 => "Q3_K_L",
+            14 => "Q4_K_S",
+            15 => "Q4_K_M",
+            16 => "Q5_K_S",
+            17 => "Q5_K_M",
+            18 => "Q6_K",
+            19 => "IQ2_XXS",
+            20 => "IQ2_XS",
+            21 => "Q2_K_S",
+            22 => "IQ3_XS",
+            23 => "IQ3_XXS",
+            24 => "IQ1_S",
+            25 => "IQ4_NL",
+            26 => "IQ3_S",
+            27 => "IQ3_M",
+            28 => "IQ2_S",
+            29 => "IQ2_M",
+            30 => "IQ4_XS",
+            31 => "IQ1_M",
+            32 => "BF16",
+            36 => "TQ1_0",
+            37 => "TQ2_0",
+            other => return Some(format!("file_type {}", other)),
+        };
+        Some(name.to_string())
+    }
+
+    /// Vocabulary size from `<arch>.vocab_size`, or the length of the tokenizer's token list
+    pub fn vocab_size(&self) -> Option<u64> {
+        self.get_arch_u64("vocab_size").or_else(|| match self.values.get("tokenizer.ggml.tokens")? {
+            GgufValue::Array { len, .. } => Some(*len),
+            _ => None,
+        })
+    }
+
+    /// Estimated f16 KV-cache size in bytes for a given context size
+    pub fn estimate_kv_cache_bytes(&self, n_ctx: u64) -> Option<u64> {
+        let n_layer = self.get_arch_u64("block_count")?;
+        let n_embd = self.get_arch_u64("embedding_length")?;
+        let n_head = self.get_arch_u64("attention.head_count")?.max(1);
+        let n_head_kv = self.get_arch_u64("attention.head_count_kv").unwrap_or(n_head);
+        let kv_dim = n_embd / n_head * n_head_kv;
+        // K and V, 2 bytes per f16 element
+        Some(2 * n_layer * n_ctx * kv_dim * 2)
+    }
+}
+
+/// Total element count of the tensors described right after the metadata, i.e. the
+/// model's parameter count. `reader` must be positioned where `GgufMetadata::read_from` stopped.
+pub fn read_parameter_count<R: Read>(reader: &mut R, metadata: &GgufMetadata) -> Result<u64, String> {
+    let mut total: u64 = 0;
+    for _ in 0..metadata.tensor_count {
+        read_string(reader, metadata.version)?;   // Tensor name
+        let n_dims = read_u32(reader)?;
+        if n_dims > MAX_TENSOR_DIMS {
+            return Err(format!("GGUF tensor has {} dimensions", n_dims));
+        }
+        let mut elements: u64 = 1;
+        for _ in 0..n_dims {
+            elements = elements.saturating_mul(read_len(reader, metadata.version)?);
+        }
+        read_u32(reader)?;   // ggml type
+        read_u64(reader)?;   // Data offset
+        total = total.saturating_add(elements);
+    }
+    Ok(total)
+}
+
+fn read_exact<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N], String> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)
+        .map_err(|e| format!("Truncated GGUF header: {}", e))?;
+    Ok(buf)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, String> {
+    Ok(u32::from_le_bytes(read_exact::<R, 4>(reader)?))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, String> {
+    Ok(u64::from_le_bytes(read_exact::<R, 8>(reader)?))
+}
+
+fn read_len<R: Read>(reader: &mut R, version: u32) -> Result<u64, String> {
+    if version == 1 { Ok(read_u32(reader)? as u64) } else { read_u64(reader) }
+}
+
+fn read_string<R: Read>(reader: &mut R, version: u32) -> Result<String, String> {
+    let len = read_len(reader, version)?;
+    if len > MAX_STRING_LEN {
+        return Err(format!("GGUF string length {} exceeds limit", len));
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)
+        .map_err(|e| format!("Truncated GGUF string: {}", e))?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_value<R: Read>(reader: &mut R, value_type: u32, version: u32) -> Result<GgufValue, String> {
+    Ok(match value_type {
+        0 => GgufValue::UInt(read_exact::<R, 1>(reader)?[0] as u64),
+        1 => GgufValue::Int(read_exact::<R, 1>(reader)?[0] as i8 as i64),
+        2 => GgufValue::UInt(u16::from_le_bytes(read_exact::<R, 2>(reader)?) as u64),
+        3 => GgufValue::Int(i16::from_le_bytes(read_exact::<R, 2>(reader)?) as i64),
+        4 => GgufValue::UInt(read_u32(reader)? as u64),
+        5 => GgufValue::Int(i32::from_le_bytes(read_exact::<R, 4>(reader)?) as i64),
+        6 => GgufValue::Float(f32::from_le_bytes(read_exact::<R, 4>(reader)?) as f64),
+        7 => GgufValue::Bool(read_exact::<R, 1>(reader)?[0] != 0),
+        8 => GgufValue::String(read_string(reader, version)?),
+        9 => {
+            let item_type = read_u32(reader)?;
+            let len = read_len(reader, version)?;
+            for _ in 0..len {
+                read_value(reader, item_type, version)?;
+            }
+            GgufValue::Array { item_type, len }
+        }
+        10 => GgufValue::UInt(read_u64(reader)?),
+        11 => GgufValue::Int(read_u64(reader)? as i64),
+        12 => GgufValue::Float(f64::from_le_bytes(read_exact::<R, 8>(reader)?)),
+        other => return Err(format!("Unknown GGUF value type {}", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn build_test_header() -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&GGUF_MAGIC.to_le_bytes());
+        buf.extend_from_slice(&3u32.to_le_bytes()); // version
+        buf.extend_from_slice(&0u64.to_le_bytes()); // tensor_count
+        buf.extend_from_slice(&7u64.to_le_bytes()); // kv_count
+
+        push_string(&mut buf, "general.architecture");
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        push_string(&mut buf, "llama");
+
+        for (key, value) in [
+            ("llama.context_length", 8192u32),
+            ("llama.block_count", 32),
+            ("llama.embedding_length", 4096),
+            ("llama.attention.head_count", 32),
+            ("llama.attention.head_count_kv", 8),
+        ] {
+            push_string(&mut buf, key);
+            buf.extend_from_slice(&4u32.to_le_bytes());
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+
+        // String array, as used by tokenizer vocabularies
+        push_string(&mut buf, "tokenizer.ggml.tokens");
+        buf.extend_from_slice(&9u32.to_le_bytes());
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes());
+        push_string(&mut buf, "<s>");
+        push_string(&mut buf, "</s>");
+
+        buf
+    }
+
+    #[test]
+    fn test_reads_metadata_and_skips_arrays() {
+        let data = build_test_header();
+        let metadata = GgufMetadata::read_from(&mut data.as_slice()).unwrap();
+
+        assert_eq!(metadata.version, 3);
+        assert_eq!(metadata.architecture(), Some("llama"));
+        assert_eq!(metadata.context_length(), Some(8192));
+        assert!(matches!(
+            metadata.values.get("tokenizer.ggml.tokens"),
+            Some(GgufValue::Array { item_type: 8, len: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_kv_cache_estimate_uses_gqa_heads() {
+        let data = build_test_header();
+        let metadata = GgufMetadata::read_from(&mut data.as_slice()).unwrap();
+
+        // 2 (K,V) * 32 layers * 4096 ctx * (4096 / 32 * 8 = 1024) * 2 bytes = 512 MiB
+        assert_eq!(metadata.estimate_kv_cache_bytes(4096), Some(512 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_rope_scaling_from_metadata() {
+        let data = build_test_header();
+        let mut metadata = GgufMetadata::read_from(&mut data.as_slice()).unwrap();
+        assert_eq!(metadata.rope_scaling(), None);
+
+        metadata.values.insert("llama.rope.scale_linear".to_string(), GgufValue::Float(4.0));
+        assert_eq!(metadata.rope_scaling().unwrap().factor, Some(4.0));
+
+        metadata.values.insert("llama.rope.scaling.type".to_string(), GgufValue::String("yarn".to_string()));
+        metadata.values.insert("llama.rope.scaling.factor".to_string(), GgufValue::Float(4.0));
+        metadata.values.insert("llama.rope.scaling.original_context_length".to_string(), GgufValue::UInt(32768));
+        let scaling = metadata.rope_scaling().unwrap();
+        assert_eq!(scaling.kind, "yarn");
+        assert_eq!(scaling.original_context_length, Some(32768));
+
+        metadata.values.insert("llama.rope.scaling.type".to_string(), GgufValue::String("none".to_string()));
+        assert_eq!(metadata.rope_scaling(), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_magic() {
+        let data = vec![0u8; 24];
+        assert!(GgufMetadata::read_from(&mut data.as_slice()).is_err());
+    }
+}
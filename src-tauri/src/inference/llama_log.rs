@@ -0,0 +1,56 @@
+// llama.cpp log routing: lines are still written to stderr as before, and can also be
+// captured on the calling thread while it loads a model or creates a context, to read
+// back what llama.cpp actually did (layers offloaded, flash attention chosen, ...)
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::sync::Once;
+
+thread_local! {
+    static CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL: Once = Once::new();
+
+unsafe extern "C" fn log_callback(_level: llama_cpp_sys_2::ggml_log_level, text: *const c_char, _user_data: *mut c_void) {
+    if text.is_null() {
+        return;
+    }
+    let text = CStr::from_ptr(text).to_string_lossy();
+    eprint!("{}", text);
+    CAPTURE.with(|capture| {
+        if let Some(buffer) = capture.borrow_mut().as_mut() {
+            buffer.push_str(&text);
+        }
+    });
+}
+
+/// Route llama.cpp logs through `log_callback`; idempotent
+pub fn install_log_capture() {
+    INSTALL.call_once(|| unsafe {
+        llama_cpp_sys_2::llama_log_set(Some(log_callback), std::ptr::null_mut());
+    });
+}
+
+/// Collect what llama.cpp logs on this thread until `finish`. llama.cpp logs load and
+/// context creation synchronously, so the capture sees exactly the calls made in between.
+pub struct LogCapture;
+
+impl LogCapture {
+    pub fn start() -> Self {
+        install_log_capture();
+        CAPTURE.with(|capture| *capture.borrow_mut() = Some(String::new()));
+        Self
+    }
+
+    pub fn finish(self) -> String {
+        CAPTURE.with(|capture| capture.borrow_mut().take()).unwrap_or_default()
+    }
+}
+
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        CAPTURE.with(|capture| *capture.borrow_mut() = None);
+    }
+}
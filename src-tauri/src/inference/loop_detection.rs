@@ -0,0 +1,72 @@
+// Repeated-output detection: a model stuck in a loop keeps regenerating the same passage
+// until max_tokens, which during unattended batch runs wastes minutes per prompt. The
+// latest n tokens are looked up among the recent output; once they have occurred
+// `max_repeats` times, generation is ended with finish_reason "loop_detected".
+
+use std::collections::VecDeque;
+
+use crate::telemetry::types::LoopDetection;
+
+#[derive(Debug)]
+pub struct LoopDetector<T> {
+    ngram: usize,
+    window: usize,
+    max_repeats: usize,
+    recent: VecDeque<T>,
+}
+
+impl<T: Copy + PartialEq> LoopDetector<T> {
+    /// Settings are clamped so a loop is detectable: n-grams of at least 1 token, at least
+    /// 2 occurrences, and a window that can hold them all
+    pub fn new(config: LoopDetection) -> Self {
+        let ngram = config.ngram.max(1);
+        let max_repeats = config.max_repeats.max(2);
+        Self {
+            ngram,
+            window: config.window.max(ngram * max_repeats),
+            max_repeats,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Record a generated token; true when the output now ends in a loop
+    pub fn push(&mut self, token: T) -> bool {
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(token);
+        if self.recent.len() < self.ngram * self.max_repeats {
+            return false;
+        }
+
+        let recent = self.recent.make_contiguous();
+        let latest = &recent[recent.len() - self.ngram..];
+        recent.windows(self.ngram).filter(|ngram| *ngram == latest).count() >= self.max_repeats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(ngram: usize, window: usize, max_repeats: usize) -> LoopDetector<u32> {
+        LoopDetector::new(LoopDetection { ngram, window, max_repeats })
+    }
+
+    #[test]
+    fn test_detects_a_repeated_sentence() {
+        let sentence = [10, 11, 12, 13, 14];
+        let mut loop_detector = detector(4, 64, 3);
+        let tokens: Vec<u32> = (100..120).chain(sentence.iter().copied().cycle().take(20)).collect();
+        let detected_at = tokens.iter().position(|&token| loop_detector.push(token));
+        // Third occurrence of the 4-gram [10, 11, 12, 13]: two full sentences plus four tokens
+        assert_eq!(detected_at, Some(20 + 2 * sentence.len() + 3));
+
+        // Varied output and repeats spread wider than the window are not loops
+        let mut varied = detector(4, 64, 3);
+        assert!(!(0..500).any(|token| varied.push(token % 97)));
+        let mut narrow = detector(2, 8, 3);
+        let spread: Vec<u32> = [1, 2].iter().copied().chain(3..9).cycle().take(40).collect();
+        assert!(!spread.iter().any(|&token| narrow.push(token)));
+    }
+}
@@ -0,0 +1,114 @@
+// Unified memory high-water marks for model load and generation, sampled on a background
+// thread (fast during load, where allocations spike and settle within milliseconds)
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use sysinfo::System;
+
+pub const LOAD_MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+pub const GENERATION_MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+pub fn bytes_to_gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+fn used_memory_bytes(system: &mut System) -> u64 {
+    system.refresh_memory();
+    system.used_memory()
+}
+
+/// Peak system-wide used memory over one phase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryPhasePeak {
+    pub start_bytes: u64,
+    pub peak_bytes: u64,
+}
+
+impl MemoryPhasePeak {
+    pub fn peak_gb(&self) -> f64 {
+        bytes_to_gb(self.peak_bytes)
+    }
+
+    /// Growth over `baseline_bytes` (e.g. used memory before the model was loaded)
+    pub fn delta_gb(&self, baseline_bytes: u64) -> f64 {
+        bytes_to_gb(self.peak_bytes.saturating_sub(baseline_bytes))
+    }
+}
+
+/// Samples used memory until finished; dropping it without finishing just stops sampling
+pub struct MemoryWatermark {
+    start_bytes: u64,
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MemoryWatermark {
+    pub fn start(interval: Duration) -> Self {
+        let mut system = System::new();
+        let start_bytes = used_memory_bytes(&mut system);
+        let peak = Arc::new(AtomicU64::new(start_bytes));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_peak = peak.clone();
+        let thread_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("memory-watermark".to_string())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    thread_peak.fetch_max(used_memory_bytes(&mut system), Ordering::Relaxed);
+                    std::thread::sleep(interval);
+                }
+            })
+            .map_err(|e| println!("⚠️ Memory watermark sampler failed to start: {}", e))
+            .ok();
+
+        Self { start_bytes, peak, stop, handle }
+    }
+
+    /// Stop sampling and return the phase peak (including one final sample)
+    pub fn finish(mut self) -> MemoryPhasePeak {
+        self.stop_sampler();
+        let final_bytes = used_memory_bytes(&mut System::new());
+        MemoryPhasePeak {
+            start_bytes: self.start_bytes,
+            peak_bytes: self.peak.fetch_max(final_bytes, Ordering::Relaxed).max(final_bytes),
+        }
+    }
+
+    fn stop_sampler(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MemoryWatermark {
+    fn drop(&mut self) {
+        self.stop_sampler();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermark_tracks_peak_and_reports_growth_over_baseline() {
+        let watermark = MemoryWatermark::start(Duration::from_millis(1));
+        let buffer = vec![1u8; 64 * 1024 * 1024];
+        std::thread::sleep(Duration::from_millis(20));
+        let peak = watermark.finish();
+        drop(buffer);
+        assert!(peak.peak_bytes >= peak.start_bytes);
+
+        let phase = MemoryPhasePeak { start_bytes: 0, peak_bytes: 3 * 1024 * 1024 * 1024 };
+        assert_eq!(phase.peak_gb(), 3.0);
+        assert_eq!(phase.delta_gb(1024 * 1024 * 1024), 2.0);
+        assert_eq!(phase.delta_gb(4 * 1024 * 1024 * 1024), 0.0);
+    }
+}
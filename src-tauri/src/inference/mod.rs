@@ -4,6 +4,78 @@ pub mod generation;
 // New module for sampling configuration
 pub mod sampler_builder;
 
+// Generation progress and ETA tracking
+pub mod progress;
+
+// Real-time tee of generated text to disk
+pub mod output_stream;
+
+// GGUF header metadata reader
+pub mod gguf;
+
+// GGUF model inspection (architecture, parameters, quantization, template)
+pub mod model_info;
+
+// Chat template formatting via llama.cpp (model-free, snapshot-tested)
+pub mod chat_template;
+
+// SHA-256 model file identity
+pub mod model_hash;
+
+// Context size resolution ("auto" n_ctx)
+pub mod context_size;
+
+// Inter-token latency jitter and stall statistics
+pub mod stability;
+
+// Decode loop pipelining and per-token overhead timing
+pub mod pipeline;
+
+// Stop strings matched across token boundaries
+pub mod stop_sequences;
+
+// Experimental P-core/E-core preference via thread QoS
+pub mod affinity;
+
+// Peak unified memory during model load and generation
+pub mod memory_watermark;
+
+// Chunked, interruptible prompt processing
+pub mod prefill;
+
+// Named sampler presets expanded at run time
+pub mod sampler_presets;
+
+// Per-model prompt token budget checked before a comparison run
+pub mod prompt_budget;
+
+// Loaded models kept resident between turns
+pub mod model_cache;
+
+// llama.cpp log capture around model loads and context creation
+pub mod llama_log;
+
+// Effective llama.cpp context parameters and silent fallbacks
+pub mod context_params;
+
+// Page cache eviction for cold-start measurements
+pub mod page_cache;
+
+// KV cache eviction for generations longer than the context
+pub mod context_shift;
+
+// Ending generation when the output repeats itself
+pub mod loop_detection;
+
+// Replaying an interrupted generation to continue it
+pub mod resume;
+
+// Concurrent batched decoding for server-style throughput
+pub mod throughput;
+
+// Draft-model speculative decoding
+pub mod speculative;
+
 // Existing exports
 pub use generation::run_model_inference;
 
@@ -0,0 +1,145 @@
+// Loaded models kept resident between turns, so a multi-turn chat with a large GGUF pays
+// the load from disk once. Entries are keyed by file and load parameters; the least
+// recently used ones are unloaded when free memory would not fit the next load.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use llama_cpp_2::model::LlamaModel;
+use serde::Serialize;
+use sysinfo::System;
+
+use crate::inference::context_params::GpuOffload;
+use crate::inference::memory_watermark::bytes_to_gb;
+
+// Free memory wanted beyond the incoming model's file size, for its context and KV cache
+const LOAD_HEADROOM_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Everything that changes what `LlamaModel::load_from_file` produces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelCacheKey {
+    pub path: PathBuf,      // Resolved model path
+    pub n_gpu_layers: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CachedModelInfo {
+    pub model_path: String,
+    pub n_gpu_layers: Option<u32>,
+    pub size_bytes: u64,
+}
+
+struct CachedModel {
+    key: ModelCacheKey,
+    model: Arc<LlamaModel>,
+    size_bytes: u64,
+    gpu_offload: Option<GpuOffload>,   // Reported by llama.cpp when the model was loaded
+}
+
+impl CachedModel {
+    fn info(&self) -> CachedModelInfo {
+        CachedModelInfo {
+            model_path: self.key.path.to_string_lossy().into_owned(),
+            n_gpu_layers: self.key.n_gpu_layers,
+            size_bytes: self.size_bytes,
+        }
+    }
+}
+
+/// Managed as Tauri state; clones share the same cache. A model evicted while a run
+/// still uses it stays alive until that run drops its handle.
+#[derive(Clone, Default)]
+pub struct ModelCache {
+    entries: Arc<Mutex<Vec<CachedModel>>>,  // Least recently used first
+}
+
+impl ModelCache {
+    /// The resident model for `key`, marking it most recently used
+    pub fn get(&self, key: &ModelCacheKey) -> Option<Arc<LlamaModel>> {
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.iter().position(|entry| &entry.key == key)?;
+        let entry = entries.remove(index);
+        let model = entry.model.clone();
+        entries.push(entry);
+        Some(model)
+    }
+
+    pub fn insert(&self, key: ModelCacheKey, model: Arc<LlamaModel>, size_bytes: u64, gpu_offload: Option<GpuOffload>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| entry.key != key);
+        entries.push(CachedModel { key, model, size_bytes, gpu_offload });
+    }
+
+    /// GPU offload llama.cpp reported when the resident model for `key` was loaded
+    pub fn gpu_offload(&self, key: &ModelCacheKey) -> Option<GpuOffload> {
+        self.entries.lock().unwrap().iter().find(|entry| &entry.key == key)?.gpu_offload
+    }
+
+    /// Unload least recently used models until a model of `incoming_bytes` fits in free memory
+    pub fn make_room(&self, incoming_bytes: u64) -> Vec<CachedModelInfo> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.is_empty() {
+            return Vec::new();
+        }
+        let resident_sizes: Vec<u64> = entries.iter().map(|entry| entry.size_bytes).collect();
+        let count = lru_eviction_count(&resident_sizes, available_memory_bytes(), incoming_bytes + LOAD_HEADROOM_BYTES);
+        let evicted: Vec<CachedModelInfo> = entries.drain(..count).map(|entry| entry.info()).collect();
+        for info in &evicted {
+            println!("♻️ MODEL CACHE: Unloaded {} ({:.2} GB) to make room for a {:.2} GB model",
+                     info.model_path, bytes_to_gb(info.size_bytes), bytes_to_gb(incoming_bytes));
+        }
+        evicted
+    }
+
+    /// Unload every resident variant of `path`, or all models when None
+    pub fn unload(&self, path: Option<&Path>) -> Vec<CachedModelInfo> {
+        let mut entries = self.entries.lock().unwrap();
+        let (unloaded, kept): (Vec<CachedModel>, Vec<CachedModel>) = entries.drain(..)
+            .partition(|entry| path.map_or(true, |path| entry.key.path == path));
+        *entries = kept;
+        unloaded.iter().map(CachedModel::info).collect()
+    }
+
+    /// Resident models, most recently used first
+    pub fn loaded(&self) -> Vec<CachedModelInfo> {
+        self.entries.lock().unwrap().iter().rev().map(CachedModel::info).collect()
+    }
+}
+
+fn available_memory_bytes() -> u64 {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.available_memory()
+}
+
+// How many of the resident models (sizes in LRU order) to unload so that `needed_bytes`
+// are free; everything when even that is not enough
+fn lru_eviction_count(resident_sizes: &[u64], available_bytes: u64, needed_bytes: u64) -> usize {
+    let mut free = available_bytes;
+    let mut count = 0;
+    for size in resident_sizes {
+        if free >= needed_bytes {
+            break;
+        }
+        free += size;
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_lru_eviction_frees_oldest_models_until_load_fits() {
+        let resident = [8 * GB, 4 * GB, 13 * GB];
+        assert_eq!(lru_eviction_count(&resident, 20 * GB, 10 * GB), 0);
+        assert_eq!(lru_eviction_count(&resident, 6 * GB, 10 * GB), 1);
+        assert_eq!(lru_eviction_count(&resident, GB, 12 * GB), 2);
+        assert_eq!(lru_eviction_count(&resident, 0, 64 * GB), 3);
+        assert_eq!(lru_eviction_count(&[], 0, 10 * GB), 0);
+    }
+}
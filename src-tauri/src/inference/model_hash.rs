@@ -0,0 +1,95 @@
+// Streaming SHA-256 of model files (identifies a model without revealing its path or name)
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+const HASH_BUFFER_BYTES: usize = 1024 * 1024;
+
+// Bytes hashed from each end of the file by quick_fingerprint
+const FINGERPRINT_SAMPLE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Lowercase hex SHA-256 of everything `reader` yields
+pub fn sha256_reader<R: Read>(reader: &mut R) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; HASH_BUFFER_BYTES];
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| format!("Failed to read model file: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// SHA-256 of a model file; reads the whole file, so multi-GB models take a few seconds
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open model file {}: {}", path.display(), e))?;
+    sha256_reader(&mut BufReader::new(file))
+}
+
+/// Cheap fingerprint for recording with every saved session: SHA-256 over the file size,
+/// the first 16 MiB (the whole GGUF header and metadata) and the last 16 MiB
+pub fn quick_fingerprint_reader<R: Read + Seek>(reader: &mut R) -> Result<String, String> {
+    sampled_fingerprint(reader, FINGERPRINT_SAMPLE_BYTES)
+}
+
+fn sampled_fingerprint<R: Read + Seek>(reader: &mut R, sample_bytes: u64) -> Result<String, String> {
+    let size = reader.seek(SeekFrom::End(0)).map_err(|e| format!("Failed to seek model file: {}", e))?;
+    let mut sampled = size.to_le_bytes().to_vec();
+
+    let head_len = size.min(sample_bytes);
+    let tail_start = size.saturating_sub(sample_bytes).max(head_len);
+    for (start, len) in [(0, head_len), (tail_start, size - tail_start)] {
+        reader.seek(SeekFrom::Start(start)).map_err(|e| format!("Failed to seek model file: {}", e))?;
+        reader.by_ref().take(len).read_to_end(&mut sampled)
+            .map_err(|e| format!("Failed to read model file: {}", e))?;
+    }
+    sha256_reader(&mut sampled.as_slice())
+}
+
+pub fn quick_fingerprint(path: &Path) -> Result<String, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open model file {}: {}", path.display(), e))?;
+    quick_fingerprint_reader(&mut BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(sha256_reader(&mut "".as_bytes()).unwrap(),
+                   "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_reader(&mut "abc".as_bytes()).unwrap(),
+                   "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn test_quick_fingerprint_detects_edits_at_either_end_and_size_changes() {
+        let original = vec![7u8; 4096];
+        let fingerprint = |data: &[u8]| sampled_fingerprint(&mut std::io::Cursor::new(data), 1024).unwrap();
+        let base = fingerprint(&original);
+        assert_eq!(base, fingerprint(&original));
+
+        let mut head_edit = original.clone();
+        head_edit[100] = 8;
+        let mut tail_edit = original.clone();
+        *tail_edit.last_mut().unwrap() = 8;
+        let mut truncated = original.clone();
+        truncated.pop();
+        for changed in [head_edit, tail_edit, truncated] {
+            assert_ne!(base, fingerprint(&changed));
+        }
+
+        // Small files are sampled once, not twice
+        let mut sampled = 3u64.to_le_bytes().to_vec();
+        sampled.extend_from_slice(b"abc");
+        assert_eq!(fingerprint(b"abc"), sha256_reader(&mut sampled.as_slice()).unwrap());
+    }
+}
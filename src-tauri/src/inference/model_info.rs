@@ -0,0 +1,110 @@
+// GGUF model inspection without loading weights: what a file is (architecture, size,
+// quantization, context, template) so two models can be checked before comparing them
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use serde::Serialize;
+
+use crate::inference::gguf::{read_parameter_count, GgufMetadata};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub path: String,
+    pub file_size_bytes: u64,
+    pub gguf_version: u32,
+    pub name: Option<String>,            // general.name
+    pub architecture: Option<String>,
+    pub size_label: Option<String>,      // general.size_label, e.g. "8B"
+    pub parameter_count: u64,            // Summed from the tensor shapes
+    pub quantization: Option<String>,    // e.g. "Q4_K_M"
+    pub bits_per_weight: Option<f64>,    // File size over parameter count; includes metadata
+    pub context_length: Option<u64>,     // Trained context length
+    pub vocab_size: Option<u64>,
+    pub chat_template: Option<String>,   // tokenizer.chat_template, applied by llama.cpp
+    pub tensor_count: u64,
+}
+
+/// Read the GGUF header and tensor index of `path`; no tensor data is read
+pub fn inspect_model_file(path: &Path) -> Result<ModelInfo, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open GGUF file {}: {}", path.display(), e))?;
+    let file_size_bytes = file.metadata()
+        .map_err(|e| format!("Failed to stat {}: {}", path.display(), e))?
+        .len();
+    let mut reader = BufReader::new(file);
+    let metadata = GgufMetadata::read_from(&mut reader)?;
+    let parameter_count = read_parameter_count(&mut reader, &metadata)?;
+    Ok(model_info(path, file_size_bytes, &metadata, parameter_count))
+}
+
+fn model_info(path: &Path, file_size_bytes: u64, metadata: &GgufMetadata, parameter_count: u64) -> ModelInfo {
+    let string = |key: &str| metadata.get_str(key).map(str::to_string);
+    ModelInfo {
+        path: path.to_string_lossy().into_owned(),
+        file_size_bytes,
+        gguf_version: metadata.version,
+        name: string("general.name"),
+        architecture: string("general.architecture"),
+        size_label: string("general.size_label"),
+        parameter_count,
+        quantization: metadata.quantization(),
+        bits_per_weight: (parameter_count > 0).then(|| file_size_bytes as f64 * 8.0 / parameter_count as f64),
+        context_length: metadata.context_length(),
+        vocab_size: metadata.vocab_size(),
+        chat_template: string("tokenizer.chat_template"),
+        tensor_count: metadata.tensor_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    #[test]
+    fn test_inspects_header_and_tensor_index() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"GGUF");
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes()); // tensor_count
+        buf.extend_from_slice(&4u64.to_le_bytes()); // kv_count
+        for (key, value) in [("general.architecture", "qwen2"), ("tokenizer.chat_template", "{{ messages }}")] {
+            push_string(&mut buf, key);
+            buf.extend_from_slice(&8u32.to_le_bytes());
+            push_string(&mut buf, value);
+        }
+        for (key, value) in [("qwen2.context_length", 32768u32), ("general.file_type", 15)] {
+            push_string(&mut buf, key);
+            buf.extend_from_slice(&4u32.to_le_bytes());
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        for (name, dims) in [("token_embd.weight", vec![896u64, 1000]), ("output_norm.weight", vec![896])] {
+            push_string(&mut buf, name);
+            buf.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for dim in dims {
+                buf.extend_from_slice(&dim.to_le_bytes());
+            }
+            buf.extend_from_slice(&12u32.to_le_bytes()); // Q4_K
+            buf.extend_from_slice(&0u64.to_le_bytes());
+        }
+
+        let path = std::env::temp_dir().join(format!("a2o-model-info-{}.gguf", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &buf).unwrap();
+        let info = inspect_model_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        let info = info.unwrap();
+
+        assert_eq!(info.architecture.as_deref(), Some("qwen2"));
+        assert_eq!(info.parameter_count, 896 * 1000 + 896);
+        assert_eq!(info.quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(info.context_length, Some(32768));
+        assert_eq!(info.chat_template.as_deref(), Some("{{ messages }}"));
+        assert_eq!(info.vocab_size, None);
+        assert_eq!(info.file_size_bytes, buf.len() as u64);
+    }
+}
@@ -0,0 +1,116 @@
+// Tees a model's streamed output to a JSONL file as it is generated
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub struct OutputStreamWriter {
+    file: File,
+    path: PathBuf,
+    records_written: usize,    // Token records, including held-back text
+    tokens_generated: usize,   // Token records with a token id
+}
+
+impl OutputStreamWriter {
+    /// Create `<dir>/<timestamp>_<run_id>_model<label>.jsonl` and write the header record;
+    /// with the seed and the token ids the file is enough to resume the run (see resume.rs)
+    pub fn create(dir: &Path, run_id: &str, model_label: &str, model_path: &str, seed: Option<u32>) -> Result<Self, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create output stream directory {}: {}", dir.display(), e))?;
+
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let path = dir.join(format!("{}_{}_model{}.jsonl", timestamp, run_id, model_label));
+        let file = File::create(&path)
+            .map_err(|e| format!("Failed to create output stream file {}: {}", path.display(), e))?;
+
+        let mut writer = Self { file, path, records_written: 0, tokens_generated: 0 };
+        writer.write_record(serde_json::json!({
+            "type": "start",
+            "run_id": run_id,
+            "model": model_label,
+            "model_path": model_path,
+            "seed": seed,
+            "timestamp_ms": now_ms(),
+        }))?;
+        Ok(writer)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one decoded token; each record is written straight to the file so a crash keeps it.
+    /// `token_id` is None for text released after the last token (held back by stop matching).
+    pub fn write_token(&mut self, text: &str, token_id: Option<i32>) -> Result<(), String> {
+        self.records_written += 1;
+        if token_id.is_some() {
+            self.tokens_generated += 1;
+        }
+        let index = self.records_written;
+        self.write_record(serde_json::json!({
+            "type": "token",
+            "index": index,
+            "text": text,
+            "token_id": token_id,
+            "timestamp_ms": now_ms(),
+        }))
+    }
+
+    /// Write the closing record with the stop reason and the number of tokens generated
+    pub fn finish(mut self, stop_reason: &str) -> Result<(), String> {
+        let tokens_generated = self.tokens_generated;
+        self.write_record(serde_json::json!({
+            "type": "end",
+            "tokens_generated": tokens_generated,
+            "stop_reason": stop_reason,
+            "timestamp_ms": now_ms(),
+        }))?;
+        self.file.sync_all()
+            .map_err(|e| format!("Failed to sync output stream file: {}", e))
+    }
+
+    fn write_record(&mut self, record: serde_json::Value) -> Result<(), String> {
+        let mut line = record.to_string();
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to write output stream record: {}", e))
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_record_counts_tokens_not_held_back_text() {
+        let dir = std::env::temp_dir().join(format!("a2o-output-stream-{}", std::process::id()));
+        let mut writer = OutputStreamWriter::create(&dir, "run-1", "A", "qwen.gguf", Some(1234)).unwrap();
+        writer.write_token("Hello", Some(9906)).unwrap();
+        writer.write_token(",", Some(11)).unwrap();
+        writer.write_token(" wor", None).unwrap();
+        let path = writer.path().to_path_buf();
+        writer.finish("eog").unwrap();
+
+        let records: Vec<serde_json::Value> = fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!((records[0]["type"].as_str(), records[0]["seed"].as_u64()), (Some("start"), Some(1234)));
+        let tokens: Vec<(u64, &str, Option<i64>)> = records[1..4].iter()
+            .map(|record| (record["index"].as_u64().unwrap(), record["text"].as_str().unwrap(), record["token_id"].as_i64()))
+            .collect();
+        assert_eq!(tokens, vec![(1, "Hello", Some(9906)), (2, ",", Some(11)), (3, " wor", None)]);
+        assert_eq!(records[4]["type"], "end");
+        assert_eq!(records[4]["tokens_generated"], 2);
+        assert_eq!(records[4]["stop_reason"], "eog");
+    }
+}
@@ -0,0 +1,60 @@
+// Eviction of a model file from the OS page cache, so the next load reads it from disk
+// (a true cold start) instead of from memory left over by an earlier load.
+
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+/// Drop the cached pages of `path`. Pages still mapped by a resident model cannot be
+/// dropped, so unload the model first.
+pub fn evict_from_page_cache(path: &Path) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let len = file.metadata().map_err(|e| format!("Failed to stat {}: {}", path.display(), e))?.len();
+    if len == 0 {
+        return Ok(());
+    }
+    evict_fd(file.as_raw_fd(), len).map_err(|e| format!("Failed to evict {} from the page cache: {}", path.display(), e))
+}
+
+#[cfg(target_os = "linux")]
+fn evict_fd(fd: i32, _len: u64) -> std::io::Result<()> {
+    match unsafe { libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_DONTNEED) } {
+        0 => Ok(()),
+        errno => Err(std::io::Error::from_raw_os_error(errno)),
+    }
+}
+
+// macOS has no fadvise; invalidating a shared mapping of the whole file drops its pages
+#[cfg(not(target_os = "linux"))]
+fn evict_fd(fd: i32, len: u64) -> std::io::Result<()> {
+    let len = len as usize;
+    unsafe {
+        let addr = libc::mmap(std::ptr::null_mut(), len, libc::PROT_READ, libc::MAP_SHARED, fd, 0);
+        if addr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        let result = libc::msync(addr, len, libc::MS_INVALIDATE);
+        let error = std::io::Error::last_os_error();
+        libc::munmap(addr, len);
+        if result != 0 {
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evict_existing_and_missing_files() {
+        let path = std::env::temp_dir().join(format!("a2o-page-cache-{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&path, vec![7u8; 64 * 1024]).unwrap();
+        assert_eq!(evict_from_page_cache(&path), Ok(()));
+        // Eviction never changes the contents
+        assert_eq!(std::fs::read(&path).unwrap().len(), 64 * 1024);
+        std::fs::remove_file(&path).unwrap();
+        assert!(evict_from_page_cache(&path).is_err());
+    }
+}
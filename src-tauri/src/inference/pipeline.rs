@@ -0,0 +1,148 @@
+// Decode-loop pipelining: publishes token events on a worker thread and tracks
+// how much per-token time the app itself adds on top of sampling and decoding
+
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+type EmitJob = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs emit/telemetry work for already-sampled tokens while the decode loop
+/// moves on to the next token. Jobs run in submission order on a single thread.
+pub struct EmitPipeline {
+    sender: Option<mpsc::Sender<EmitJob>>,
+    worker: Option<JoinHandle<Duration>>,
+}
+
+impl EmitPipeline {
+    pub fn start(model_label: &str) -> Self {
+        let (sender, receiver) = mpsc::channel::<EmitJob>();
+        let worker = thread::Builder::new()
+            .name(format!("emit-pipeline-{}", model_label))
+            .spawn(move || {
+                let mut busy = Duration::ZERO;
+                for job in receiver {
+                    let started = Instant::now();
+                    job();
+                    busy += started.elapsed();
+                }
+                busy
+            });
+
+        match worker {
+            Ok(worker) => Self { sender: Some(sender), worker: Some(worker) },
+            Err(e) => {
+                println!("⚠️ Failed to start emit pipeline thread, emitting inline: {}", e);
+                Self { sender: None, worker: None }
+            }
+        }
+    }
+
+    /// Queue a job; runs it inline if the worker is unavailable
+    pub fn submit<F: FnOnce() + Send + 'static>(&self, job: F) {
+        match &self.sender {
+            Some(sender) => {
+                if let Err(mpsc::SendError(job)) = sender.send(Box::new(job)) {
+                    job();
+                }
+            }
+            None => job(),
+        }
+    }
+
+    /// Flush all queued jobs and return the total time the worker spent running them
+    pub fn finish(mut self) -> Duration {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Duration {
+        self.sender.take();
+        self.worker
+            .take()
+            .and_then(|worker| worker.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for EmitPipeline {
+    fn drop(&mut self) {
+        // Early returns still flush pending token events before the caller continues
+        self.shutdown();
+    }
+}
+
+/// Per-token timing breakdown of the decode loop
+#[derive(Debug, Default, Clone)]
+pub struct DecodeTimings {
+    tokens: usize,
+    iteration: Duration,
+    sample: Duration,
+    decode: Duration,
+}
+
+impl DecodeTimings {
+    /// Record one loop iteration and the sample/decode time spent inside it
+    pub fn record(&mut self, iteration: Duration, sample: Duration, decode: Duration) {
+        self.tokens += 1;
+        self.iteration += iteration;
+        self.sample += sample;
+        self.decode += decode;
+    }
+
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    pub fn sample_ms_per_token(&self) -> Option<f64> {
+        self.per_token_ms(self.sample)
+    }
+
+    pub fn decode_ms_per_token(&self) -> Option<f64> {
+        self.per_token_ms(self.decode)
+    }
+
+    /// Time on the decode thread that was neither sampling nor decoding
+    /// (stop checks, detokenization, bookkeeping, queueing emits)
+    pub fn overhead_ms_per_token(&self) -> Option<f64> {
+        let overhead = self.iteration.saturating_sub(self.sample + self.decode);
+        self.per_token_ms(overhead)
+    }
+
+    pub fn per_token_ms(&self, total: Duration) -> Option<f64> {
+        if self.tokens == 0 {
+            return None;
+        }
+        Some(total.as_secs_f64() * 1000.0 / self.tokens as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_pipeline_runs_jobs_in_order_before_finish_returns() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = EmitPipeline::start("test");
+        for i in 0..100 {
+            let seen = seen.clone();
+            pipeline.submit(move || seen.lock().unwrap().push(i));
+        }
+        pipeline.finish();
+        assert_eq!(*seen.lock().unwrap(), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_overhead_excludes_sample_and_decode() {
+        let mut timings = DecodeTimings::default();
+        assert!(timings.overhead_ms_per_token().is_none());
+
+        timings.record(Duration::from_millis(12), Duration::from_millis(1), Duration::from_millis(10));
+        timings.record(Duration::from_millis(14), Duration::from_millis(1), Duration::from_millis(10));
+
+        assert_eq!(timings.tokens(), 2);
+        assert!((timings.decode_ms_per_token().unwrap() - 10.0).abs() < 1e-9);
+        assert!((timings.overhead_ms_per_token().unwrap() - 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,49 @@
+// Chunked prompt processing (prefill): the prompt is decoded in batches so a stop can take
+// effect between chunks and progress can be reported on long prompts
+
+use std::ops::Range;
+
+/// Prompt tokens decoded per llama_decode call when ModelConfig.n_batch is unset
+pub const DEFAULT_N_BATCH: u32 = 512;
+
+/// Effective n_batch: the configured value, at least 1 and at most n_ctx (llama.cpp
+/// rejects a decode larger than the context's n_batch, and n_batch above n_ctx is wasted)
+pub fn resolve_n_batch(requested: Option<u32>, n_ctx: u32) -> u32 {
+    requested.unwrap_or(DEFAULT_N_BATCH).clamp(1, n_ctx.max(1))
+}
+
+/// Consecutive token index ranges covering `n_tokens`, each at most `chunk_size` long
+pub fn prefill_chunks(n_tokens: usize, chunk_size: usize) -> impl Iterator<Item = Range<usize>> {
+    let chunk_size = chunk_size.max(1);
+    (0..n_tokens).step_by(chunk_size).map(move |start| start..(start + chunk_size).min(n_tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefill_chunks_cover_prompt_in_order() {
+        let chunks: Vec<_> = prefill_chunks(1300, 512).collect();
+        assert_eq!(chunks, vec![0..512, 512..1024, 1024..1300]);
+        assert_eq!(prefill_chunks(1024, 512).count(), 2);
+        assert_eq!(prefill_chunks(0, 512).count(), 0);
+        assert_eq!(prefill_chunks(3, 0).count(), 3);
+    }
+
+    #[test]
+    fn test_long_prompts_are_split_by_n_batch() {
+        // Several thousand tokens: every position decoded exactly once, in order, no chunk over n_batch
+        for (prompt_tokens, requested) in [(4_000, None), (6_144, Some(2_048)), (7_777, Some(300))] {
+            let n_batch = resolve_n_batch(requested, 8_192) as usize;
+            let chunks: Vec<_> = prefill_chunks(prompt_tokens, n_batch).collect();
+            assert!(chunks.iter().all(|chunk| !chunk.is_empty() && chunk.len() <= n_batch));
+            let positions: Vec<usize> = chunks.iter().flat_map(|chunk| chunk.clone()).collect();
+            assert_eq!(positions, (0..prompt_tokens).collect::<Vec<_>>());
+            assert_eq!(chunks.len(), prompt_tokens.div_ceil(n_batch));
+        }
+        assert_eq!(resolve_n_batch(None, 8_192), DEFAULT_N_BATCH);
+        assert_eq!(resolve_n_batch(Some(4_096), 2_048), 2_048);
+        assert_eq!(resolve_n_batch(Some(0), 2_048), 1);
+    }
+}
@@ -0,0 +1,205 @@
+// Tracks generation progress for periodic generation_progress events
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Number of recent token timestamps used for the rolling TPS estimate
+const ROLLING_WINDOW_TOKENS: usize = 16;
+
+// Minimum spacing between two progress events
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct ProgressTracker {
+    max_tokens: usize,
+    tokens_generated: usize,
+    recent_tokens: VecDeque<Instant>,
+    last_emit: Option<Instant>,
+}
+
+impl ProgressTracker {
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            tokens_generated: 0,
+            recent_tokens: VecDeque::with_capacity(ROLLING_WINDOW_TOKENS + 1),
+            last_emit: None,
+        }
+    }
+
+    /// Record a newly generated token at the given instant
+    pub fn record_token(&mut self, now: Instant) {
+        self.tokens_generated += 1;
+        self.recent_tokens.push_back(now);
+        if self.recent_tokens.len() > ROLLING_WINDOW_TOKENS {
+            self.recent_tokens.pop_front();
+        }
+    }
+
+    pub fn tokens_generated(&self) -> usize {
+        self.tokens_generated
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Tokens per second over the most recent window of tokens
+    pub fn rolling_tps(&self) -> Option<f64> {
+        let first = self.recent_tokens.front()?;
+        let last = self.recent_tokens.back()?;
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        if self.recent_tokens.len() < 2 || elapsed <= 0.0 {
+            return None;
+        }
+        Some((self.recent_tokens.len() - 1) as f64 / elapsed)
+    }
+
+    /// Estimated milliseconds until the max_tokens budget is exhausted
+    /// (an upper bound, since generation can end early on an EOG token)
+    pub fn eta_ms(&self) -> Option<u64> {
+        let tps = self.rolling_tps()?;
+        let remaining = self.max_tokens.saturating_sub(self.tokens_generated);
+        Some((remaining as f64 / tps * 1000.0) as u64)
+    }
+
+    /// Returns true (and marks the emit) when enough time has passed since the last event
+    pub fn should_emit(&mut self, now: Instant) -> bool {
+        match self.last_emit {
+            Some(last) if now.duration_since(last) < PROGRESS_EMIT_INTERVAL => false,
+            _ => {
+                self.last_emit = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Tokens per second over a fixed wall-clock window (e.g. the last 5 seconds), so
+/// late-run slowdowns and stalls show up instead of being averaged away
+pub struct RollingTpsWindow {
+    window: Duration,
+    first_token: Option<Instant>,
+    recent_tokens: VecDeque<Instant>,
+}
+
+impl RollingTpsWindow {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            first_token: None,
+            recent_tokens: VecDeque::new(),
+        }
+    }
+
+    /// Record a token and drop tokens that fell out of the window
+    pub fn record_token(&mut self, now: Instant) {
+        self.first_token.get_or_insert(now);
+        self.recent_tokens.push_back(now);
+        let cutoff = now.checked_sub(self.window);
+        while let (Some(front), Some(cutoff)) = (self.recent_tokens.front(), cutoff) {
+            if *front > cutoff {
+                break;
+            }
+            self.recent_tokens.pop_front();
+        }
+    }
+
+    /// Tokens after the window start divided by the window length. Until a full window
+    /// has passed the window starts at the first token (which is not counted, like current_tps)
+    pub fn tps(&self, now: Instant) -> Option<f64> {
+        let first_token = self.first_token?;
+        let window_start = now.checked_sub(self.window)
+            .map_or(first_token, |start| start.max(first_token));
+        let elapsed = now.duration_since(window_start).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        let tokens = self.recent_tokens.iter().filter(|t| **t > window_start).count();
+        Some(tokens as f64 / elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_tps_needs_two_tokens() {
+        let mut tracker = ProgressTracker::new(100);
+        assert!(tracker.rolling_tps().is_none());
+        tracker.record_token(Instant::now());
+        assert!(tracker.rolling_tps().is_none());
+        assert!(tracker.eta_ms().is_none());
+    }
+
+    #[test]
+    fn test_rolling_tps_and_eta() {
+        let mut tracker = ProgressTracker::new(100);
+        let start = Instant::now();
+        // 11 tokens spaced 100ms apart -> 10 intervals over 1s = 10 TPS
+        for i in 0..11 {
+            tracker.record_token(start + Duration::from_millis(i * 100));
+        }
+        let tps = tracker.rolling_tps().unwrap();
+        assert!((tps - 10.0).abs() < 1e-9);
+        // 89 remaining tokens at 10 TPS = 8.9s
+        assert_eq!(tracker.eta_ms(), Some(8900));
+    }
+
+    #[test]
+    fn test_rolling_window_drops_old_tokens() {
+        let mut tracker = ProgressTracker::new(1000);
+        let start = Instant::now();
+        // A slow first token followed by a fast steady stream
+        tracker.record_token(start);
+        for i in 0..ROLLING_WINDOW_TOKENS as u64 {
+            tracker.record_token(start + Duration::from_secs(10) + Duration::from_millis(i * 50));
+        }
+        let tps = tracker.rolling_tps().unwrap();
+        assert!((tps - 20.0).abs() < 1e-9);
+        assert_eq!(tracker.tokens_generated(), ROLLING_WINDOW_TOKENS + 1);
+    }
+
+    #[test]
+    fn test_rolling_tps_window_reflects_late_slowdown() {
+        let mut window = RollingTpsWindow::new(Duration::from_secs(5));
+        let start = Instant::now();
+        // 10 s at 20 TPS, then 5 s at 4 TPS
+        for i in 0..=200u64 {
+            window.record_token(start + Duration::from_millis(i * 50));
+        }
+        let fast_end = start + Duration::from_secs(10);
+        assert!((window.tps(fast_end).unwrap() - 20.0).abs() < 1e-9);
+        for i in 1..=20u64 {
+            window.record_token(fast_end + Duration::from_millis(i * 250));
+        }
+        let slow_end = fast_end + Duration::from_secs(5);
+        assert!((window.tps(slow_end).unwrap() - 4.0).abs() < 1e-9);
+        // Only tokens inside the window are kept
+        assert_eq!(window.recent_tokens.len(), 20);
+    }
+
+    #[test]
+    fn test_rolling_tps_window_before_window_fills() {
+        let mut window = RollingTpsWindow::new(Duration::from_secs(5));
+        let start = Instant::now();
+        assert!(window.tps(start).is_none());
+        window.record_token(start);
+        assert!(window.tps(start).is_none());
+        // 10 tokens after the first one over 1 s
+        for i in 1..=10u64 {
+            window.record_token(start + Duration::from_millis(i * 100));
+        }
+        let tps = window.tps(start + Duration::from_secs(1)).unwrap();
+        assert!((tps - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_should_emit_is_rate_limited() {
+        let mut tracker = ProgressTracker::new(10);
+        let start = Instant::now();
+        assert!(tracker.should_emit(start));
+        assert!(!tracker.should_emit(start + Duration::from_millis(100)));
+        assert!(tracker.should_emit(start + PROGRESS_EMIT_INTERVAL));
+    }
+}
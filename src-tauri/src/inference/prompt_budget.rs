@@ -0,0 +1,78 @@
+// Prompt token budget preflight: the same conversation tokenizes to a different length per
+// model, so a comparison is only fair if every model has room for a full reply
+
+use crate::telemetry::types::{Message, ModelPromptBudget};
+
+pub fn prompt_budget(model: &str, prompt_tokens: usize, n_ctx: u32, max_generation_tokens: usize) -> ModelPromptBudget {
+    let context_left = (n_ctx as usize).saturating_sub(prompt_tokens);
+    ModelPromptBudget {
+        model: model.to_string(),
+        prompt_tokens,
+        n_ctx,
+        output_budget: context_left.min(max_generation_tokens),
+        will_truncate: context_left < max_generation_tokens,
+    }
+}
+
+/// Warning when some models will run out of context and others won't. When all or none
+/// truncate the outputs are still cut off alike, so there is nothing model-specific to report.
+pub fn truncation_mismatch_warning(budgets: &[ModelPromptBudget], max_generation_tokens: usize) -> Option<String> {
+    let truncating: Vec<&ModelPromptBudget> = budgets.iter().filter(|budget| budget.will_truncate).collect();
+    if truncating.is_empty() || truncating.len() == budgets.len() {
+        return None;
+    }
+    let details: Vec<String> = truncating.iter()
+        .map(|budget| format!("Model {} ({} prompt tokens of n_ctx {}, {} left for the reply)",
+                              budget.model, budget.prompt_tokens, budget.n_ctx, budget.output_budget))
+        .collect();
+    Some(format!(
+        "{} will run out of context before {} generated tokens while the other model will not; the outputs are not comparable",
+        details.join(", "), max_generation_tokens
+    ))
+}
+
+/// Where a history truncated for auto_truncate may start, fewest dropped messages first:
+/// at each later user message, so whole turns go and the conversation still opens with the
+/// user. The last message is always kept.
+pub fn truncation_starts(chat_history: &[Message]) -> Vec<usize> {
+    (1..chat_history.len())
+        .filter(|&start| chat_history[start].role == "user" || start == chat_history.len() - 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warns_only_when_one_model_truncates() {
+        // Same conversation: Model B's tokenizer needs more tokens and its context is smaller
+        let a = prompt_budget("A", 2_900, 4_096, 1_024);
+        let b = prompt_budget("B", 3_300, 4_096, 1_024);
+        assert!(!a.will_truncate);
+        assert_eq!(a.output_budget, 1_024);
+        assert!(b.will_truncate);
+        assert_eq!(b.output_budget, 796);
+
+        let warning = truncation_mismatch_warning(&[a.clone(), b.clone()], 1_024).unwrap();
+        assert!(warning.contains("Model B (3300 prompt tokens of n_ctx 4096, 796 left"));
+        assert!(!warning.contains("Model A"));
+
+        let both_truncate = prompt_budget("A", 3_500, 4_096, 1_024);
+        assert!(truncation_mismatch_warning(&[both_truncate, b], 1_024).is_none());
+        assert!(truncation_mismatch_warning(&[a.clone(), a], 1_024).is_none());
+
+        // A prompt larger than the context leaves nothing for the reply
+        assert_eq!(prompt_budget("A", 5_000, 4_096, 1_024).output_budget, 0);
+    }
+
+    #[test]
+    fn test_truncation_drops_whole_turns() {
+        let message = |role: &str| Message { role: role.to_string(), content: String::new(), model: None };
+        let history = [message("user"), message("assistant"), message("user"), message("assistant"), message("user")];
+        assert_eq!(truncation_starts(&history), vec![2, 4]);
+        // The last message can always start the history, whatever its role
+        assert_eq!(truncation_starts(&history[..4]), vec![2, 3]);
+        assert!(truncation_starts(&history[..1]).is_empty());
+    }
+}
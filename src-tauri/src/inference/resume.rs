@@ -0,0 +1,111 @@
+// Resuming an interrupted generation. llama.cpp does not expose the distribution sampler's
+// RNG state, so a run is resumed by replay rather than by restoring it: the same prompt is
+// prefilled with the same pinned seed, and for each recorded token the sampler still samples
+// (advancing its RNG and penalty history exactly as the original run did) before the recorded
+// token is forced in its place. Generation then continues from where the original stopped.
+//
+// With the same model file, llama.cpp build, backend, n_ctx/n_batch and GPU offload, every
+// replayed sample matches its recorded token and the continuation is the one the original run
+// would have produced. Any difference in those changes the logits, so the first mismatch is
+// reported as the divergence point: the replayed text is still the recorded one, but what
+// follows it is a different (equally valid) sample.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Enough of a generation to replay it: the seed it sampled with and the tokens it produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeCheckpoint {
+    pub model: String,                   // "A" / "B"
+    pub model_path: String,
+    pub seed: Option<u32>,               // None for output streams written before seeds were recorded
+    pub tokens: Vec<i32>,                // Generated token ids in order, EOG excluded
+    pub text: String,                    // Their decoded text
+    pub finish_reason: Option<String>,   // None when the run never finished (crash)
+}
+
+// Most recent generation per model label, for pause/resume within the app
+static LATEST_CHECKPOINTS: Mutex<BTreeMap<String, ResumeCheckpoint>> = Mutex::new(BTreeMap::new());
+
+pub fn record_checkpoint(checkpoint: ResumeCheckpoint) {
+    if let Ok(mut checkpoints) = LATEST_CHECKPOINTS.lock() {
+        checkpoints.insert(checkpoint.model.clone(), checkpoint);
+    }
+}
+
+pub fn latest_checkpoint(model_label: &str) -> Option<ResumeCheckpoint> {
+    LATEST_CHECKPOINTS.lock().ok()?.get(model_label).cloned()
+}
+
+/// Rebuild a checkpoint from an output stream JSONL file, e.g. after a crash. A truncated
+/// last line (the process died mid-write) is ignored.
+pub fn checkpoint_from_stream(path: &Path) -> Result<ResumeCheckpoint, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open output stream {}: {}", path.display(), e))?;
+    let mut start = None;
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut finish_reason = None;
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read output stream {}: {}", path.display(), e))?;
+        let Ok(record) = serde_json::from_str::<serde_json::Value>(&line) else { continue };
+        match record["type"].as_str() {
+            Some("start") => start = Some(record),
+            Some("token") => {
+                if let Some(token_id) = record["token_id"].as_i64() {
+                    tokens.push(token_id as i32);
+                }
+                text.push_str(record["text"].as_str().unwrap_or_default());
+            }
+            Some("end") => finish_reason = record["stop_reason"].as_str().map(str::to_string),
+            _ => {}
+        }
+    }
+    let start = start.ok_or_else(|| format!("{} is not an output stream (no start record)", path.display()))?;
+    if tokens.is_empty() && !text.is_empty() {
+        return Err(format!("{} has no token ids; it was written before resumable streams", path.display()));
+    }
+    Ok(ResumeCheckpoint {
+        model: start["model"].as_str().unwrap_or_default().to_string(),
+        model_path: start["model_path"].as_str().unwrap_or_default().to_string(),
+        seed: start["seed"].as_u64().map(|seed| seed as u32),
+        tokens,
+        text,
+        finish_reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::output_stream::OutputStreamWriter;
+
+    #[test]
+    fn test_checkpoint_from_interrupted_stream() {
+        let dir = std::env::temp_dir().join(format!("a2o-resume-{}", std::process::id()));
+        let mut stream = OutputStreamWriter::create(&dir, "run1", "B", "/models/b.gguf", Some(42)).unwrap();
+        stream.write_token("Hel", Some(101)).unwrap();
+        stream.write_token("", Some(7)).unwrap();      // Held back by the stop sequence matcher
+        stream.write_token("lo", Some(102)).unwrap();
+        let path = stream.path().to_path_buf();
+        drop(stream);
+        // A crash mid-write leaves a partial line behind
+        std::fs::write(&path, std::fs::read_to_string(&path).unwrap() + "{\"type\":\"tok").unwrap();
+
+        let checkpoint = checkpoint_from_stream(&path).unwrap();
+        assert_eq!(checkpoint, ResumeCheckpoint {
+            model: "B".to_string(),
+            model_path: "/models/b.gguf".to_string(),
+            seed: Some(42),
+            tokens: vec![101, 7, 102],
+            text: "Hello".to_string(),
+            finish_reason: None,
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
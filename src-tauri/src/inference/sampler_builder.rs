@@ -1,8 +1,88 @@
+use llama_cpp_2::context::LlamaContext;
+use llama_cpp_2::model::{AddBos, LlamaModel};
 use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
+use llama_cpp_2::token::data_array::LlamaTokenDataArray;
 use crate::ModelConfig;
+use crate::error::A2oError;
+use crate::telemetry::types::{SamplerSeed, DEFAULT_SAMPLER_SEED};
 
 pub struct SamplerBuilder;
 
+/// llama.cpp's grammars/json.gbnf, used for `grammar: "json"`
+const JSON_GBNF: &str = r#"root   ::= object
+value  ::= object | array | string | number | ("true" | "false" | "null") ws
+
+object ::=
+  "{" ws (
+            string ":" ws value
+    ("," ws string ":" ws value)*
+  )? "}" ws
+
+array  ::=
+  "[" ws (
+            value
+    ("," ws value)*
+  )? "]" ws
+
+string ::=
+  "\"" (
+    [^"\\\x7F\x00-\x1F] |
+    "\\" (["\\bfnrt] | "u" [0-9a-fA-F]{4})
+  )* "\"" ws
+
+number ::= ("-"? ([0-9] | [1-9] [0-9]{0,15})) ("." [0-9]+)? ([eE] [-+]? [0-9] [1-9]{0,15})? ws
+
+ws ::= | " " | "\n" [ \t]{0,20}
+"#;
+
+/// Sampler wrapper that can exempt specific tokens from repetition penalties
+///
+/// llama.cpp's penalties sampler has no exemption list, so when exemptions are
+/// configured the penalties run as a separate stage: exempt logits are saved,
+/// penalties applied, the saved logits restored, then the rest of the chain runs.
+pub struct ConfiguredSampler {
+    penalties: Option<LlamaSampler>,
+    chain: LlamaSampler,
+    exempt_tokens: Vec<LlamaToken>,
+}
+
+impl ConfiguredSampler {
+    /// Sample the token at batch index `idx`. Fails when the chain selects nothing, e.g.
+    /// when a grammar rejects every candidate.
+    pub fn sample(&mut self, ctx: &LlamaContext, idx: i32) -> Result<LlamaToken, A2oError> {
+        let penalties = match &self.penalties {
+            Some(penalties) => penalties,
+            None => return Ok(self.chain.sample(ctx, idx)),
+        };
+
+        let mut candidates = LlamaTokenDataArray::from_iter(ctx.candidates_ith(idx), false);
+        let saved_logits: Vec<(LlamaToken, f32)> = candidates.data.iter()
+            .filter(|data| self.exempt_tokens.contains(&data.id()))
+            .map(|data| (data.id(), data.logit()))
+            .collect();
+
+        candidates.apply_sampler(penalties);
+
+        for data in candidates.data.iter_mut() {
+            if let Some((_, logit)) = saved_logits.iter().find(|(id, _)| *id == data.id()) {
+                data.set_logit(*logit);
+            }
+        }
+
+        candidates.apply_sampler(&self.chain);
+        candidates.selected_token()
+            .ok_or_else(|| A2oError::Inference("Sampler selected no token (the grammar may reject every candidate)".to_string()))
+    }
+
+    pub fn accept(&mut self, token: LlamaToken) {
+        if let Some(penalties) = self.penalties.as_mut() {
+            penalties.accept(token);
+        }
+        self.chain.accept(token);
+    }
+}
+
 impl SamplerBuilder {
     /// Creates a configured LlamaSampler from ModelConfig
     /// 
@@ -17,6 +97,107 @@ impl SamplerBuilder {
     /// This order is important because each step affects the next.
     /// Changing the order can dramatically alter output quality.
     pub fn create_from_config(config: &ModelConfig) -> LlamaSampler {
+        Self::build_chain(config, true, None)
+    }
+
+    /// Creates a sampler for a loaded model, honoring repeat-penalty exemptions and the grammar
+    ///
+    /// Exemptions and grammars need the model's vocabulary (newline token, exempt strings,
+    /// grammar terminals), so they can only be resolved here rather than in `create_from_config`.
+    /// Fails when the grammar does not parse.
+    pub fn create_for_model(config: &ModelConfig, model: &LlamaModel) -> Result<ConfiguredSampler, String> {
+        let grammar = Self::grammar_sampler(config, model)?;
+        let exempt_tokens = if Self::penalties_enabled(config) {
+            Self::resolve_exempt_tokens(config, model)
+        } else {
+            Vec::new()
+        };
+
+        if exempt_tokens.is_empty() {
+            return Ok(ConfiguredSampler {
+                penalties: None,
+                chain: Self::build_chain(config, true, grammar),
+                exempt_tokens,
+            });
+        }
+
+        println!("🎛️ Exempting {} token(s) from repetition penalties", exempt_tokens.len());
+        Ok(ConfiguredSampler {
+            penalties: Some(Self::penalties_sampler(config)),
+            chain: Self::build_chain(config, false, grammar),
+            exempt_tokens,
+        })
+    }
+
+    /// GBNF source for the configured grammar: "json" names the built-in JSON grammar,
+    /// anything else is the grammar itself. None when unset or blank.
+    pub fn grammar_source(config: &ModelConfig) -> Option<&str> {
+        let grammar = config.grammar.as_deref().map(str::trim).filter(|grammar| !grammar.is_empty())?;
+        Some(if grammar.eq_ignore_ascii_case("json") { JSON_GBNF } else { grammar })
+    }
+
+    fn grammar_sampler(config: &ModelConfig, model: &LlamaModel) -> Result<Option<LlamaSampler>, String> {
+        let Some(grammar) = Self::grammar_source(config) else { return Ok(None) };
+        LlamaSampler::grammar(model, grammar, "root")
+            .map(Some)
+            .map_err(|e| format!("Invalid GBNF grammar: {:?}", e))
+    }
+
+    fn penalties_enabled(config: &ModelConfig) -> bool {
+        config.repeat_penalty.unwrap_or(1.0) != 1.0
+            || config.frequency_penalty.unwrap_or(0.0) != 0.0
+            || config.presence_penalty.unwrap_or(0.0) != 0.0
+    }
+
+    fn penalties_sampler(config: &ModelConfig) -> LlamaSampler {
+        LlamaSampler::penalties(
+            config.repeat_last_n.unwrap_or(64),
+            config.repeat_penalty.unwrap_or(1.0),
+            config.frequency_penalty.unwrap_or(0.0),
+            config.presence_penalty.unwrap_or(0.0),
+        )
+    }
+
+    /// Resolve the newline token (when penalize_newline is false) and exempt strings to token ids
+    fn resolve_exempt_tokens(config: &ModelConfig, model: &LlamaModel) -> Vec<LlamaToken> {
+        Self::exempt_token_ids(config, model.token_nl(), |text| {
+            model.str_to_token(text, AddBos::Never).map_err(|e| format!("{:?}", e))
+        })
+    }
+
+    // Exempt strings must each be a single token in the model's vocabulary; others are skipped
+    fn exempt_token_ids(
+        config: &ModelConfig,
+        newline: LlamaToken,
+        tokenize: impl Fn(&str) -> Result<Vec<LlamaToken>, String>,
+    ) -> Vec<LlamaToken> {
+        let mut exempt_tokens = Vec::new();
+
+        if !config.penalize_newline.unwrap_or(true) {
+            exempt_tokens.push(newline);
+        }
+
+        for text in config.penalty_exempt_tokens.iter().flatten() {
+            match tokenize(text) {
+                Ok(tokens) if tokens.len() == 1 => {
+                    if !exempt_tokens.contains(&tokens[0]) {
+                        exempt_tokens.push(tokens[0]);
+                    }
+                }
+                Ok(tokens) => {
+                    println!("⚠️ Penalty exemption '{}' is {} tokens for this model, expected 1 - ignoring",
+                             text.escape_debug(), tokens.len());
+                }
+                Err(e) => {
+                    println!("⚠️ Failed to tokenize penalty exemption '{}': {}", text.escape_debug(), e);
+                }
+            }
+        }
+
+        exempt_tokens
+    }
+
+    fn build_chain(config: &ModelConfig, include_penalties: bool, grammar: Option<LlamaSampler>) -> LlamaSampler {
         let mut sampler_chain = Vec::new();
 
         // Step 1: Apply penalties first (per llama.cpp standard order)
@@ -28,7 +209,7 @@ impl SamplerBuilder {
 
         // Only add penalties if any are actually enabled
         // Rationale: Avoid unnecessary computation for default disabled state
-        if repeat_penalty != 1.0 || frequency_penalty != 0.0 || presence_penalty != 0.0 {
+        if include_penalties && (repeat_penalty != 1.0 || frequency_penalty != 0.0 || presence_penalty != 0.0) {
             println!("🎛️ Adding penalties: repeat={}, freq={}, presence={}, window={}",
                      repeat_penalty, frequency_penalty, presence_penalty, repeat_last_n);
             sampler_chain.push(LlamaSampler::penalties(
@@ -39,6 +220,13 @@ impl SamplerBuilder {
             ));
         }
 
+        // Grammar constraint: masks every token the grammar does not allow next
+        // Rationale: Ahead of the filters so they only choose among valid continuations
+        if let Some(grammar) = grammar {
+            println!("🎛️ Adding grammar constraint");
+            sampler_chain.push(grammar);
+        }
+
         // Step 2: Apply top_k filtering
         // Rationale: Top-K creates a hard limit before probability-based filtering
         if let Some(k) = config.top_k {
@@ -78,8 +266,11 @@ impl SamplerBuilder {
 
         // Step 6: Add final distribution sampling for randomness
         // Rationale: Provides actual token selection from the filtered/scaled distribution
-        println!("🎛️ Adding distribution sampling with fixed seed for reproducibility");
-        sampler_chain.push(LlamaSampler::dist(1234)); // Fixed seed for reproducible results
+        // Seeds are pinned when a turn starts; "random" is only drawn here for configs
+        // that did not go through GenerationConfig::resolve_sampling
+        let seed = config.seed.unwrap_or(SamplerSeed::Fixed(DEFAULT_SAMPLER_SEED)).resolve();
+        println!("🎛️ Adding distribution sampling with seed {}", seed);
+        sampler_chain.push(LlamaSampler::dist(seed));
 
         // Chain all samplers or fallback to greedy
         // Rationale: If no configuration provided, default to deterministic greedy sampling
@@ -138,6 +329,13 @@ impl SamplerBuilder {
             }
         }
 
+        // Penalty exemptions only matter when a penalty is active
+        let has_exemptions = config.penalize_newline == Some(false)
+            || config.penalty_exempt_tokens.as_ref().is_some_and(|tokens| !tokens.is_empty());
+        if has_exemptions && !Self::penalties_enabled(config) {
+            warnings.push("Penalty exemptions have no effect while all repetition penalties are disabled".to_string());
+        }
+
         // Cross-parameter conflict detection
         if let (Some(top_p), Some(min_p)) = (config.top_p, config.min_p) {
             if min_p > top_p {
@@ -183,10 +381,44 @@ impl SamplerBuilder {
             }
         }
 
+        if Self::grammar_source(config).is_some() {
+            description.push("output constrained by a grammar".to_string());
+        }
+
         if description.is_empty() {
             "default configuration".to_string()
         } else {
             description.join(", ")
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stand-in vocabulary: "\n" is token 13, single words are one token, anything with a space two
+    fn tokenize(text: &str) -> Result<Vec<LlamaToken>, String> {
+        match text {
+            "" => Err("empty".to_string()),
+            "\n" => Ok(vec![LlamaToken(13)]),
+            text if text.contains(' ') => Ok(vec![LlamaToken(1), LlamaToken(2)]),
+            text => Ok(vec![LlamaToken(100 + text.len() as i32)]),
+        }
+    }
+
+    #[test]
+    fn test_exempt_tokens_resolve_to_single_token_ids() {
+        let config = ModelConfig {
+            penalize_newline: Some(false),
+            penalty_exempt_tokens: Some(vec!["\n".to_string(), "the".to_string(), "two words".to_string(), String::new(), "and".to_string()]),
+            ..ModelConfig::default()
+        };
+        // Newline once (explicit exemption deduplicated), "the"/"and" share an id here, multi-token and failing strings skipped
+        assert_eq!(SamplerBuilder::exempt_token_ids(&config, LlamaToken(13), tokenize), vec![LlamaToken(13), LlamaToken(103)]);
+
+        let penalized = ModelConfig { penalize_newline: None, ..config };
+        assert_eq!(SamplerBuilder::exempt_token_ids(&penalized, LlamaToken(13), tokenize), vec![LlamaToken(13), LlamaToken(103)]);
+        assert!(SamplerBuilder::exempt_token_ids(&ModelConfig::default(), LlamaToken(13), tokenize).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+// Named sampler presets shipped with the backend. A ModelConfig references a preset by name
+// (`sampler_preset`); it is expanded into concrete parameters at run time, so run manifests
+// and results record the exact values used even if a preset changes in a later release.
+
+use serde::Serialize;
+
+use crate::telemetry::types::{ModelConfig, SamplingParams};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SamplerPreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub sampling: SamplingParams,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn params(
+    temperature: f32,
+    top_k: i32,
+    top_p: f32,
+    min_p: f32,
+    repeat_penalty: f32,
+    repeat_last_n: i32,
+    frequency_penalty: f32,
+    presence_penalty: f32,
+) -> SamplingParams {
+    SamplingParams {
+        temperature: Some(temperature),
+        top_k: Some(top_k),
+        top_p: Some(top_p),
+        min_p: Some(min_p),
+        repeat_penalty: Some(repeat_penalty),
+        repeat_last_n: Some(repeat_last_n),
+        frequency_penalty: Some(frequency_penalty),
+        presence_penalty: Some(presence_penalty),
+        penalize_newline: Some(true),
+        seed: None,
+    }
+}
+
+pub fn sampler_presets() -> Vec<SamplerPreset> {
+    vec![
+        SamplerPreset {
+            name: "deterministic",
+            description: "Greedy decoding (always the most likely token); identical output for identical input",
+            sampling: params(0.0, 1, 1.0, 0.0, 1.0, 64, 0.0, 0.0),
+        },
+        SamplerPreset {
+            name: "balanced",
+            description: "The backend defaults: moderate randomness with top-k/top-p/min-p filtering",
+            sampling: SamplingParams::from_model_config(&ModelConfig::default()),
+        },
+        SamplerPreset {
+            name: "creative",
+            description: "Higher temperature with light repetition penalties for varied prose",
+            sampling: params(1.0, 40, 0.9, 0.05, 1.1, 64, 0.1, 0.2),
+        },
+        SamplerPreset {
+            name: "coding",
+            description: "Low temperature and tight filtering, with a long repeat window for code",
+            sampling: params(0.2, 10, 0.7, 0.1, 1.05, 128, 0.0, 0.0),
+        },
+    ]
+}
+
+pub fn find_sampler_preset(name: &str) -> Option<SamplerPreset> {
+    sampler_presets().into_iter().find(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Replace the sampling parameters with the referenced preset's. The preset name stays on
+/// the config so it is recorded alongside the expanded values.
+pub fn expand_sampler_preset(config: &ModelConfig) -> Result<ModelConfig, String> {
+    let Some(name) = config.sampler_preset.as_deref() else {
+        return Ok(config.clone());
+    };
+    let preset = find_sampler_preset(name).ok_or_else(|| {
+        let known: Vec<&str> = sampler_presets().iter().map(|preset| preset.name).collect();
+        format!("Unknown sampler preset '{}' (available: {})", name, known.join(", "))
+    })?;
+    Ok(preset.sampling.apply_to(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_is_expanded_into_concrete_parameters() {
+        let config = ModelConfig {
+            model_path: "/models/a.gguf".to_string(),
+            sampler_preset: Some("Coding".to_string()),
+            temperature: Some(0.9),
+            ..ModelConfig::default()
+        };
+        let expanded = expand_sampler_preset(&config).unwrap();
+        assert_eq!(expanded.temperature, Some(0.2));
+        assert_eq!(expanded.repeat_last_n, Some(128));
+        assert_eq!(expanded.sampler_preset.as_deref(), Some("Coding"));
+        assert_eq!(expanded.model_path, "/models/a.gguf");
+
+        let plain = ModelConfig::default();
+        assert_eq!(expand_sampler_preset(&plain).unwrap().temperature, plain.temperature);
+
+        let unknown = ModelConfig { sampler_preset: Some("spicy".to_string()), ..ModelConfig::default() };
+        assert!(expand_sampler_preset(&unknown).unwrap_err().contains("deterministic"));
+        assert_eq!(sampler_presets().len(), 4);
+    }
+}
@@ -0,0 +1,242 @@
+// Speculative decoding with a small draft model: the draft proposes n_draft tokens greedily,
+// the target model scores them all in one batch, and the longest prefix the target's own
+// sampler agrees with is kept, plus the target's token at the first disagreement (or one
+// bonus token when every draft token was accepted). The output is what the target model
+// would have generated alone; only the number of target decodes changes.
+
+use std::collections::VecDeque;
+
+use llama_cpp_2::context::LlamaContext;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::LlamaModel;
+use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
+use serde::Serialize;
+
+use crate::error::A2oError;
+use crate::inference::prefill::prefill_chunks;
+use crate::inference::sampler_builder::ConfiguredSampler;
+
+/// Draft tokens proposed per round when ModelConfig.n_draft is unset
+pub const DEFAULT_N_DRAFT: u32 = 5;
+/// Longer drafts are almost never accepted in full and only waste draft decodes
+pub const MAX_N_DRAFT: u32 = 16;
+
+pub fn resolve_n_draft(requested: Option<u32>) -> u32 {
+    requested.unwrap_or(DEFAULT_N_DRAFT).clamp(1, MAX_N_DRAFT)
+}
+
+/// Draft acceptance over a run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SpeculativeStats {
+    pub draft_model: String,
+    pub n_draft: u32,
+    pub rounds: usize,            // Target decodes that verified a draft
+    pub drafted_tokens: usize,
+    pub accepted_tokens: usize,
+    pub acceptance_rate: Option<f64>,
+}
+
+impl SpeculativeStats {
+    fn record_round(&mut self, drafted: usize, accepted: usize) {
+        self.rounds += 1;
+        self.drafted_tokens += drafted;
+        self.accepted_tokens += accepted;
+        self.acceptance_rate = (self.drafted_tokens > 0)
+            .then(|| self.accepted_tokens as f64 / self.drafted_tokens as f64);
+    }
+}
+
+/// Tokens the target samples over a verified draft: each draft token it agrees with, then its
+/// own token at the first disagreement, or a bonus token after a fully accepted draft.
+/// `sample(i)` samples (and accepts) the target's token from the logits at batch index i.
+pub fn verify_draft(
+    drafted: &[LlamaToken],
+    mut sample: impl FnMut(usize) -> Result<LlamaToken, A2oError>,
+) -> Result<Vec<LlamaToken>, A2oError> {
+    let mut verified = Vec::with_capacity(drafted.len() + 1);
+    for (i, &draft) in drafted.iter().enumerate() {
+        let token = sample(i)?;
+        verified.push(token);
+        if token != draft {
+            return Ok(verified);
+        }
+    }
+    verified.push(sample(drafted.len())?);
+    Ok(verified)
+}
+
+/// Draft model context kept in step with the target's KV cache, and the target tokens
+/// already verified but not yet output
+pub struct SpeculativeDecoder<'a> {
+    draft_model: &'a LlamaModel,
+    draft_ctx: LlamaContext<'a>,
+    draft_sampler: LlamaSampler,
+    draft_batch: LlamaBatch,
+    draft_pending: Vec<(LlamaToken, i32)>,   // In the target's cache but not yet in the draft's
+    n_draft: usize,
+    verified: VecDeque<LlamaToken>,
+    last_in_cache: bool,                     // The token last handed out is already in the target's cache
+    pub stats: SpeculativeStats,
+}
+
+impl<'a> SpeculativeDecoder<'a> {
+    /// Prefill the draft context with the prompt the target was prefilled with
+    pub fn new(
+        draft_model: &'a LlamaModel,
+        mut draft_ctx: LlamaContext<'a>,
+        draft_name: String,
+        n_draft: u32,
+        prompt: &[LlamaToken],
+        n_batch: usize,
+    ) -> Result<Self, A2oError> {
+        draft_ctx.clear_kv_cache();
+        let mut draft_batch = LlamaBatch::new(n_batch.max(n_draft as usize + 1), 1);
+        for chunk in prefill_chunks(prompt.len(), n_batch) {
+            draft_batch.clear();
+            for (position, token) in chunk.clone().zip(&prompt[chunk.clone()]) {
+                draft_batch.add(*token, position as i32, &[0], false)
+                    .map_err(|e| A2oError::Inference(format!("Failed to add token to draft batch: {:?}", e)))?;
+            }
+            draft_ctx.decode(&mut draft_batch)
+                .map_err(|e| A2oError::Inference(format!("Failed to decode draft prompt: {:?}", e)))?;
+        }
+        Ok(Self {
+            draft_model,
+            draft_ctx,
+            draft_sampler: LlamaSampler::greedy(),
+            draft_batch,
+            draft_pending: Vec::new(),
+            n_draft: n_draft as usize,
+            verified: VecDeque::new(),
+            last_in_cache: false,
+            stats: SpeculativeStats { draft_model: draft_name, n_draft, ..SpeculativeStats::default() },
+        })
+    }
+
+    /// Next token verified by the last round (already sampled and accepted by the target's
+    /// sampler), or None when the target must sample the next token itself
+    pub fn next_verified(&mut self) -> Option<LlamaToken> {
+        let token = self.verified.pop_front();
+        // Every verified token but the last is a draft token the target already decoded
+        self.last_in_cache = token.is_some() && !self.verified.is_empty();
+        token
+    }
+
+    /// Bring `token` (output at position `n_past`) into the target's cache and compute the
+    /// logits for what follows: nothing to do for an accepted draft token, otherwise one
+    /// draft-and-verify round, or a plain decode when there is no room left to draft into.
+    pub fn decode_after(
+        &mut self,
+        ctx: &mut LlamaContext,
+        batch: &mut LlamaBatch,
+        sampler: &mut ConfiguredSampler,
+        token: LlamaToken,
+        n_past: i32,
+        batch_capacity: usize,
+    ) -> Result<(), A2oError> {
+        if self.last_in_cache {
+            return Ok(());
+        }
+        let room = (ctx.n_ctx() as i32 - 1 - n_past).max(0) as usize;
+        let n_draft = self.n_draft.min(room).min(batch_capacity.saturating_sub(1));
+
+        let drafted = if n_draft > 0 { self.draft(token, n_past, n_draft)? } else { Vec::new() };
+        batch.clear();
+        batch.add(token, n_past, &[0], true)
+            .map_err(|e| A2oError::Inference(format!("Failed to add token to batch: {:?}", e)))?;
+        for (i, draft) in drafted.iter().enumerate() {
+            batch.add(*draft, n_past + 1 + i as i32, &[0], true)
+                .map_err(|e| A2oError::Inference(format!("Failed to add draft token to batch: {:?}", e)))?;
+        }
+        ctx.decode(batch)
+            .map_err(|e| A2oError::Inference(format!("Failed to decode batch: {:?}", e)))?;
+        if drafted.is_empty() {
+            // The draft cache still has to follow the target's
+            self.draft_pending.push((token, n_past));
+            return Ok(());
+        }
+
+        let verified = verify_draft(&drafted, |i| {
+            let sampled = sampler.sample(ctx, i as i32)?;
+            sampler.accept(sampled);
+            Ok(sampled)
+        })?;
+        let accepted = verified.len() - 1;
+        self.stats.record_round(drafted.len(), accepted);
+
+        // Rejected draft tokens leave both caches; the target keeps `token` and the accepted ones
+        let keep_until = (n_past + 1 + accepted as i32) as u32;
+        ctx.clear_kv_cache_seq(Some(0), Some(keep_until), None)
+            .map_err(|e| A2oError::Inference(format!("Failed to drop rejected draft tokens: {:?}", e)))?;
+        self.draft_ctx.clear_kv_cache_seq(Some(0), Some(keep_until), None)
+            .map_err(|e| A2oError::Inference(format!("Failed to drop rejected draft tokens: {:?}", e)))?;
+        // The last draft token was sampled but never decoded by the draft model
+        if accepted == drafted.len() {
+            self.draft_pending.push((drafted[accepted - 1], n_past + accepted as i32));
+        }
+        self.verified.extend(verified);
+        Ok(())
+    }
+
+    // Greedily draft up to `n_draft` tokens following `token` at position `n_past`
+    fn draft(&mut self, token: LlamaToken, n_past: i32, n_draft: usize) -> Result<Vec<LlamaToken>, A2oError> {
+        self.draft_batch.clear();
+        for (pending, position) in self.draft_pending.drain(..) {
+            self.draft_batch.add(pending, position, &[0], false)
+                .map_err(|e| A2oError::Inference(format!("Failed to add token to draft batch: {:?}", e)))?;
+        }
+        self.draft_batch.add(token, n_past, &[0], true)
+            .map_err(|e| A2oError::Inference(format!("Failed to add token to draft batch: {:?}", e)))?;
+
+        let mut drafted = Vec::with_capacity(n_draft);
+        loop {
+            self.draft_ctx.decode(&mut self.draft_batch)
+                .map_err(|e| A2oError::Inference(format!("Failed to decode draft batch: {:?}", e)))?;
+            let draft = self.draft_sampler.sample(&self.draft_ctx, self.draft_batch.n_tokens() - 1);
+            drafted.push(draft);
+            if drafted.len() == n_draft || self.draft_model.is_eog_token(draft) {
+                return Ok(drafted);
+            }
+            self.draft_batch.clear();
+            self.draft_batch.add(draft, n_past + drafted.len() as i32, &[0], true)
+                .map_err(|e| A2oError::Inference(format!("Failed to add token to draft batch: {:?}", e)))?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(ids: &[i32]) -> Vec<LlamaToken> {
+        ids.iter().map(|&id| LlamaToken(id)).collect()
+    }
+
+    #[test]
+    fn test_verify_keeps_agreeing_prefix_and_the_targets_token() {
+        // Target agrees with the first two draft tokens, then picks 9 instead of 3
+        let target = tokens(&[1, 2, 9, 4]);
+        let verified = verify_draft(&tokens(&[1, 2, 3, 4]), |i| Ok(target[i])).unwrap();
+        assert_eq!(verified, tokens(&[1, 2, 9]));
+
+        // Fully accepted draft earns a bonus token
+        let target = tokens(&[1, 2, 7]);
+        assert_eq!(verify_draft(&tokens(&[1, 2]), |i| Ok(target[i])).unwrap(), tokens(&[1, 2, 7]));
+
+        // First token rejected: the round still yields the target's own token
+        assert_eq!(verify_draft(&tokens(&[5]), |_| Ok(LlamaToken(6))).unwrap(), tokens(&[6]));
+
+        // A sampling failure ends the round instead of panicking
+        assert!(verify_draft(&tokens(&[5]), |_| Err(A2oError::Inference("no token".to_string()))).is_err());
+
+        let mut stats = SpeculativeStats::default();
+        stats.record_round(4, 2);
+        stats.record_round(2, 2);
+        assert_eq!(stats.rounds, 2);
+        assert_eq!(stats.acceptance_rate, Some(4.0 / 6.0));
+        assert_eq!(resolve_n_draft(None), DEFAULT_N_DRAFT);
+        assert_eq!(resolve_n_draft(Some(100)), MAX_N_DRAFT);
+        assert_eq!(resolve_n_draft(Some(0)), 1);
+    }
+}
@@ -0,0 +1,106 @@
+// Inter-token latency stability: jitter (coefficient of variation) and stalls
+
+use std::time::Duration;
+
+/// Streaming statistics over the gaps between consecutive generated tokens
+pub struct InterTokenStats {
+    stall_threshold: Duration,
+    count: usize,
+    mean_ms: f64,
+    m2: f64,                    // Sum of squared deviations (Welford)
+    longest_ms: f64,
+    stall_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilitySummary {
+    pub intervals: usize,
+    pub mean_ms: Option<f64>,
+    pub cv: Option<f64>,                // Std dev / mean of inter-token latency
+    pub longest_stall_ms: Option<f64>,  // Longest gap between two tokens
+    pub stall_count: usize,             // Gaps longer than the stall threshold
+}
+
+impl InterTokenStats {
+    pub fn new(stall_threshold: Duration) -> Self {
+        Self {
+            stall_threshold,
+            count: 0,
+            mean_ms: 0.0,
+            m2: 0.0,
+            longest_ms: 0.0,
+            stall_count: 0,
+        }
+    }
+
+    /// Record the latency between a token and the one before it
+    pub fn record(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.count += 1;
+        let delta = latency_ms - self.mean_ms;
+        self.mean_ms += delta / self.count as f64;
+        self.m2 += delta * (latency_ms - self.mean_ms);
+        self.longest_ms = self.longest_ms.max(latency_ms);
+        if latency > self.stall_threshold {
+            self.stall_count += 1;
+        }
+    }
+
+    pub fn summary(&self) -> StabilitySummary {
+        if self.count == 0 {
+            return StabilitySummary { intervals: 0, mean_ms: None, cv: None, longest_stall_ms: None, stall_count: 0 };
+        }
+        let std_dev_ms = (self.m2 / self.count as f64).sqrt();
+        StabilitySummary {
+            intervals: self.count,
+            mean_ms: Some(self.mean_ms),
+            cv: (self.mean_ms > 0.0).then(|| std_dev_ms / self.mean_ms),
+            longest_stall_ms: Some(self.longest_ms),
+            stall_count: self.stall_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steady_stream_has_no_jitter() {
+        let mut stats = InterTokenStats::new(Duration::from_millis(250));
+        for _ in 0..50 {
+            stats.record(Duration::from_millis(40));
+        }
+        let summary = stats.summary();
+        assert_eq!(summary.intervals, 50);
+        assert!((summary.mean_ms.unwrap() - 40.0).abs() < 1e-9);
+        assert!(summary.cv.unwrap().abs() < 1e-9);
+        assert_eq!(summary.stall_count, 0);
+    }
+
+    #[test]
+    fn test_stalls_and_cv() {
+        let mut stats = InterTokenStats::new(Duration::from_millis(250));
+        // 10, 30, 10, 30 ms: mean 20, population std dev 10 -> CV 0.5
+        for ms in [10, 30, 10, 30] {
+            stats.record(Duration::from_millis(ms));
+        }
+        let summary = stats.summary();
+        assert!((summary.cv.unwrap() - 0.5).abs() < 1e-9);
+
+        // Two stalls above the threshold; a gap exactly at the threshold is not a stall
+        for ms in [900, 250, 400] {
+            stats.record(Duration::from_millis(ms));
+        }
+        let summary = stats.summary();
+        assert_eq!(summary.stall_count, 2);
+        assert!((summary.longest_stall_ms.unwrap() - 900.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = InterTokenStats::new(Duration::from_millis(250)).summary();
+        assert_eq!(summary.intervals, 0);
+        assert!(summary.cv.is_none() && summary.longest_stall_ms.is_none());
+    }
+}
@@ -0,0 +1,107 @@
+// Stop sequence matching over streamed token text. A stop string can span several
+// tokens, so text that could still be the start of one is held back until the next
+// token confirms or rules it out; the stop string itself is never emitted.
+
+/// Outcome of feeding one token's text to the matcher
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopScan {
+    /// No stop sequence yet; `released` is safe to emit (may be empty while text is held back)
+    Continue { released: String },
+    /// `sequence` was generated; `released` is the text before it, anything after is dropped
+    Stopped { released: String, sequence: String },
+}
+
+#[derive(Debug, Default)]
+pub struct StopSequenceMatcher {
+    sequences: Vec<String>,
+    pending: String,   // Tail that may be the start of a stop sequence
+}
+
+impl StopSequenceMatcher {
+    pub fn new(sequences: &[String]) -> Self {
+        Self {
+            sequences: sequences.iter().filter(|s| !s.is_empty()).cloned().collect(),
+            pending: String::new(),
+        }
+    }
+
+    pub fn push(&mut self, text: &str) -> StopScan {
+        self.pending.push_str(text);
+
+        // Earliest match wins; the held-back tail had none on its own, so any match involves the new text
+        let earliest = self.sequences.iter()
+            .filter_map(|sequence| self.pending.find(sequence.as_str()).map(|index| (index, sequence)))
+            .min_by_key(|(index, _)| *index);
+        if let Some((index, sequence)) = earliest {
+            let sequence = sequence.clone();
+            let mut released = std::mem::take(&mut self.pending);
+            released.truncate(index);
+            return StopScan::Stopped { released, sequence };
+        }
+
+        let held = self.sequences.iter()
+            .map(|sequence| longest_prefix_suffix(&self.pending, sequence))
+            .max()
+            .unwrap_or(0);
+        let tail = self.pending.split_off(self.pending.len() - held);
+        StopScan::Continue { released: std::mem::replace(&mut self.pending, tail) }
+    }
+
+    /// Release held-back text when generation ends without a stop sequence
+    pub fn flush(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+// Length of the longest proper prefix of `sequence` that `text` ends with
+fn longest_prefix_suffix(text: &str, sequence: &str) -> usize {
+    (1..sequence.len())
+        .rev()
+        .filter(|&len| sequence.is_char_boundary(len) && len <= text.len())
+        .find(|&len| text.ends_with(&sequence[..len]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn released(scan: StopScan) -> String {
+        match scan {
+            StopScan::Continue { released } => released,
+            StopScan::Stopped { .. } => panic!("unexpected stop: {:?}", scan),
+        }
+    }
+
+    #[test]
+    fn test_stop_sequence_spanning_tokens_is_detected_and_not_emitted() {
+        let mut matcher = StopSequenceMatcher::new(&["</answer>".to_string(), "".to_string(), "\n\nUser:".to_string()]);
+
+        assert_eq!(released(matcher.push("The answer is 42")), "The answer is 42");
+        // "</" could start "</answer>": held back
+        assert_eq!(released(matcher.push(".</")), ".");
+        assert_eq!(released(matcher.push("ans")), "");
+        assert_eq!(
+            matcher.push("wer> trailing"),
+            StopScan::Stopped { released: String::new(), sequence: "</answer>".to_string() },
+        );
+
+        // A false start is released once it can no longer match
+        let mut matcher = StopSequenceMatcher::new(&["\n\nUser:".to_string()]);
+        assert_eq!(released(matcher.push("one\n")), "one");
+        assert_eq!(released(matcher.push("\nUse")), "");
+        assert_eq!(released(matcher.push("d")), "\n\nUsed");
+        assert_eq!(released(matcher.push(" it\n\nU")), " it");
+        assert_eq!(matcher.flush(), "\n\nU");
+
+        // Multi-byte text is split on character boundaries only
+        let mut matcher = StopSequenceMatcher::new(&["é!".to_string()]);
+        assert_eq!(released(matcher.push("caf")), "caf");
+        assert_eq!(released(matcher.push("é")), "");
+        assert_eq!(matcher.push("!"), StopScan::Stopped { released: String::new(), sequence: "é!".to_string() });
+
+        // No sequences: everything passes straight through
+        let mut matcher = StopSequenceMatcher::new(&[]);
+        assert_eq!(released(matcher.push("</")), "</");
+    }
+}
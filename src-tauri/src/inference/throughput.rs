@@ -0,0 +1,487 @@
+// Server-style throughput benchmark: several requests decoded concurrently by one context,
+// one llama.cpp sequence per stream, every active stream advancing one token per batched
+// decode. When a stream finishes, the next queued request takes its sequence (continuous
+// batching), so the benchmark measures what a Mac sustains serving parallel clients rather
+// than a single chat.
+
+use std::num::NonZeroU32;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::{Duration, Instant};
+
+use llama_cpp_2::context::LlamaContext;
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::{AddBos, LlamaModel};
+use llama_cpp_2::token::LlamaToken;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::error::A2oError;
+use crate::events::{EventSink, SharedEventSink};
+use crate::hardware::start_enhanced_monitoring;
+use crate::inference::context_size::resolve_context_size;
+use crate::inference::generation::{
+    apply_model_chat_template, build_chat_message_sequence, llama_backend, load_model_cancellable,
+    resolve_max_tokens, resolve_model_path
+};
+use crate::inference::model_cache::{ModelCache, ModelCacheKey};
+use crate::inference::prefill::{prefill_chunks, resolve_n_batch};
+use crate::inference::sampler_builder::{ConfiguredSampler, SamplerBuilder};
+use crate::telemetry::types::{IntegrationMethod, ModelConfig, TelemetryProfile, TelemetryUpdate, DEFAULT_TELEMETRY_SAMPLING_HZ};
+
+pub const MAX_THROUGHPUT_STREAMS: usize = 16;
+const DEFAULT_THROUGHPUT_STREAMS: usize = 4;
+
+// Label the benchmark's model load answers stop requests under
+const THROUGHPUT_LABEL: &str = "throughput";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThroughputBenchmarkRequest {
+    pub prompts: Vec<String>,
+    pub system_prompt: Option<String>,
+    pub concurrency: Option<usize>,      // Streams decoded together (default 4, at most 16)
+    pub total_requests: Option<usize>,   // Requests served, cycling through the prompts (default: max(prompts, concurrency))
+    pub event_sink: Option<String>,      // Extra event destination: "jsonl:<path>", "ws:<host:port>" or "null"
+}
+
+/// One served request
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamThroughput {
+    pub request: usize,          // Order the request was admitted in
+    pub prompt_index: usize,
+    pub prompt_tokens: usize,
+    pub output_tokens: usize,
+    pub queued_ms: f64,          // Benchmark start until a sequence was free for it
+    pub ttft_ms: Option<f64>,    // From admission: prefill plus the first sample
+    pub latency_ms: f64,         // From admission to its last token
+    pub decode_tps: Option<f64>, // This stream's own rate, first to last token
+    pub finish_reason: String,   // "eog" | "max_tokens" | "user_stop"
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThroughputReport {
+    pub model_path: String,
+    pub concurrency: usize,
+    pub n_ctx_per_stream: u32,
+    pub requests: Vec<StreamThroughput>,
+    pub total_prompt_tokens: usize,
+    pub total_output_tokens: usize,
+    pub wall_time_ms: f64,
+    pub aggregate_tps: Option<f64>,        // Output tokens of all streams / wall time
+    pub mean_stream_tps: Option<f64>,      // What each client sees, averaged over the requests
+    pub mean_ttft_ms: Option<f64>,
+    pub power_samples: usize,
+    pub avg_package_power_w: Option<f64>,  // Mean CPU+GPU+ANE power while decoding
+    pub energy_per_token_wh: Option<f64>,  // Package energy over the run / all output tokens
+    pub completed: bool,                   // False when stopped before every request was served
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Aggregate the served requests and the package power sampled over `wall_time`
+pub fn summarize_throughput(
+    model_path: String,
+    concurrency: usize,
+    n_ctx_per_stream: u32,
+    requests: Vec<StreamThroughput>,
+    wall_time: Duration,
+    package_power: &[f64],
+    completed: bool,
+) -> ThroughputReport {
+    let total_output_tokens: usize = requests.iter().map(|request| request.output_tokens).sum();
+    let wall_time_s = wall_time.as_secs_f64();
+    let avg_package_power_w = mean(package_power.iter().copied());
+    ThroughputReport {
+        model_path,
+        concurrency,
+        n_ctx_per_stream,
+        total_prompt_tokens: requests.iter().map(|request| request.prompt_tokens).sum(),
+        total_output_tokens,
+        wall_time_ms: wall_time_s * 1000.0,
+        aggregate_tps: (wall_time_s > 0.0 && total_output_tokens > 0).then(|| total_output_tokens as f64 / wall_time_s),
+        mean_stream_tps: mean(requests.iter().filter_map(|request| request.decode_tps)),
+        mean_ttft_ms: mean(requests.iter().filter_map(|request| request.ttft_ms)),
+        power_samples: package_power.len(),
+        avg_package_power_w,
+        energy_per_token_wh: avg_package_power_w
+            .filter(|_| total_output_tokens > 0)
+            .map(|watts| watts * wall_time_s / 3600.0 / total_output_tokens as f64),
+        requests,
+        completed,
+    }
+}
+
+// A request occupying one sequence of the context
+struct ActiveStream {
+    request: usize,
+    prompt_index: usize,
+    prompt_tokens: usize,
+    queued: Duration,
+    sampler: ConfiguredSampler,
+    n_past: i32,
+    next_token: LlamaToken,   // Sampled, goes into the next batched decode
+    logits_index: i32,
+    output_tokens: usize,
+    admitted: Instant,
+    first_token: Option<Instant>,
+    last_token: Option<Instant>,
+}
+
+impl ActiveStream {
+    /// Count a sampled token; Some(finish reason) when the stream is done
+    fn record(&mut self, eog: bool, now: Instant, max_tokens: usize, n_ctx_per_stream: i32) -> Option<&'static str> {
+        if eog {
+            return Some("eog");
+        }
+        self.output_tokens += 1;
+        self.first_token.get_or_insert(now);
+        self.last_token = Some(now);
+        // The token would be decoded at position n_past
+        (self.output_tokens >= max_tokens || self.n_past >= n_ctx_per_stream).then_some("max_tokens")
+    }
+
+    fn finish(self, reason: &str, now: Instant) -> StreamThroughput {
+        let decode_tps = match (self.first_token, self.last_token) {
+            (Some(first), Some(last)) if last > first => Some((self.output_tokens - 1) as f64 / last.duration_since(first).as_secs_f64()),
+            _ => None,
+        };
+        StreamThroughput {
+            request: self.request,
+            prompt_index: self.prompt_index,
+            prompt_tokens: self.prompt_tokens,
+            output_tokens: self.output_tokens,
+            queued_ms: self.queued.as_secs_f64() * 1000.0,
+            ttft_ms: self.first_token.map(|first| first.duration_since(self.admitted).as_secs_f64() * 1000.0),
+            latency_ms: self.last_token.unwrap_or(now).duration_since(self.admitted).as_secs_f64() * 1000.0,
+            decode_tps,
+            finish_reason: reason.to_string(),
+        }
+    }
+}
+
+// Emitted each time a request finishes
+#[derive(Clone, Serialize)]
+struct ThroughputProgressEvent {
+    completed_requests: usize,
+    total_requests: usize,
+    output_tokens: usize,
+    elapsed_ms: u64,
+}
+
+struct DecodePlan {
+    prompts: Vec<Vec<LlamaToken>>,
+    concurrency: usize,
+    total_requests: usize,
+    max_tokens: usize,
+    n_ctx_per_stream: u32,
+    n_batch: u32,
+}
+
+// Prefill `tokens` into sequence `seq` chunk by chunk; returns the batch index of the last
+// prompt token, whose logits the first sample reads
+fn prefill_sequence(ctx: &mut LlamaContext, batch: &mut LlamaBatch, tokens: &[LlamaToken], seq: usize, n_batch: u32) -> Result<i32, A2oError> {
+    let mut logits_index = 0;
+    for chunk in prefill_chunks(tokens.len(), n_batch as usize) {
+        batch.clear();
+        for (position, token) in chunk.clone().zip(&tokens[chunk.clone()]) {
+            batch.add(*token, position as i32, &[seq as i32], position == tokens.len() - 1)
+                .map_err(|e| A2oError::Inference(format!("Failed to add token to batch: {:?}", e)))?;
+        }
+        logits_index = batch.n_tokens() - 1;
+        ctx.decode(batch)
+            .map_err(|e| A2oError::Inference(format!("Failed to decode prompt of sequence {}: {:?}", seq, e)))?;
+    }
+    Ok(logits_index)
+}
+
+// The decode loop; blocking. Returns the served requests and whether all of them finished.
+fn decode_concurrently(
+    model: &LlamaModel,
+    model_config: &ModelConfig,
+    plan: &DecodePlan,
+    events: &dyn EventSink,
+    stop_signal: &AtomicBool,
+) -> Result<(Vec<StreamThroughput>, bool), A2oError> {
+    let backend = llama_backend()?;
+    let n_ctx_total = plan.n_ctx_per_stream * plan.concurrency as u32;
+    let ctx_params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(n_ctx_total))
+        .with_n_batch(plan.n_batch)
+        .with_n_seq_max(plan.concurrency as u32);
+    let mut ctx = model.new_context(backend, ctx_params)
+        .map_err(|e| A2oError::ModelLoad(format!("Failed to create a {}-sequence context: {:?}", plan.concurrency, e)))?;
+    let mut batch = LlamaBatch::new(plan.n_batch as usize, 1);
+    let n_ctx_per_stream = plan.n_ctx_per_stream as i32;
+
+    let started = Instant::now();
+    let mut streams: Vec<Option<ActiveStream>> = (0..plan.concurrency).map(|_| None).collect();
+    let mut served = Vec::with_capacity(plan.total_requests);
+    let mut next_request = 0;
+    let finish = |stream: ActiveStream, reason: &str, served: &mut Vec<StreamThroughput>| {
+        served.push(stream.finish(reason, Instant::now()));
+        let _ = events.emit("throughput_progress", ThroughputProgressEvent {
+            completed_requests: served.len(),
+            total_requests: plan.total_requests,
+            output_tokens: served.iter().map(|request| request.output_tokens).sum(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+    };
+
+    loop {
+        if stop_signal.load(Ordering::Relaxed) {
+            println!("🛑 THROUGHPUT: Stop signal detected after {} request(s)", served.len());
+            for stream in streams.iter_mut().filter_map(Option::take) {
+                finish(stream, "user_stop", &mut served);
+            }
+            return Ok((served, false));
+        }
+
+        // Give each free sequence the next queued request and sample its first token
+        for (seq, slot) in streams.iter_mut().enumerate() {
+            if slot.is_some() || next_request >= plan.total_requests {
+                continue;
+            }
+            let prompt_index = next_request % plan.prompts.len();
+            let tokens = &plan.prompts[prompt_index];
+            ctx.clear_kv_cache_seq(Some(seq as u32), None, None)
+                .map_err(|e| A2oError::Inference(format!("Failed to clear sequence {}: {:?}", seq, e)))?;
+            let queued = started.elapsed();
+            let admitted = Instant::now();
+            let logits_index = prefill_sequence(&mut ctx, &mut batch, tokens, seq, plan.n_batch)?;
+            let mut stream = ActiveStream {
+                request: next_request,
+                prompt_index,
+                prompt_tokens: tokens.len(),
+                queued,
+                sampler: SamplerBuilder::create_for_model(model_config, model).map_err(A2oError::InvalidInput)?,
+                n_past: tokens.len() as i32,
+                next_token: LlamaToken(0),
+                logits_index,
+                output_tokens: 0,
+                admitted,
+                first_token: None,
+                last_token: None,
+            };
+            next_request += 1;
+            let token = stream.sampler.sample(&ctx, stream.logits_index)?;
+            stream.sampler.accept(token);
+            match stream.record(model.is_eog_token(token), Instant::now(), plan.max_tokens, n_ctx_per_stream) {
+                Some(reason) => finish(stream, reason, &mut served),
+                None => {
+                    stream.next_token = token;
+                    *slot = Some(stream);
+                }
+            }
+        }
+        if streams.iter().all(Option::is_none) {
+            return Ok((served, true));
+        }
+
+        // One batched decode advances every active stream by the token it sampled last
+        batch.clear();
+        for (seq, stream) in streams.iter_mut().enumerate() {
+            let Some(stream) = stream else { continue };
+            stream.logits_index = batch.n_tokens();
+            batch.add(stream.next_token, stream.n_past, &[seq as i32], true)
+                .map_err(|e| A2oError::Inference(format!("Failed to add token to batch: {:?}", e)))?;
+            stream.n_past += 1;
+        }
+        ctx.decode(&mut batch)
+            .map_err(|e| A2oError::Inference(format!("Failed to decode batch of {} stream(s): {:?}", batch.n_tokens(), e)))?;
+
+        let now = Instant::now();
+        for slot in streams.iter_mut() {
+            let Some(stream) = slot.as_mut() else { continue };
+            let token = stream.sampler.sample(&ctx, stream.logits_index)?;
+            stream.sampler.accept(token);
+            match stream.record(model.is_eog_token(token), now, plan.max_tokens, n_ctx_per_stream) {
+                Some(reason) => {
+                    if let Some(stream) = slot.take() {
+                        finish(stream, reason, &mut served);
+                    }
+                }
+                None => stream.next_token = token,
+            }
+        }
+    }
+}
+
+/// Load the model (or reuse the resident one), then serve `request` with `concurrency`
+/// parallel streams while sampling package power. Each stream samples with `model_config`
+/// and generates up to its max_tokens.
+pub async fn run_throughput_benchmark(
+    events: SharedEventSink,
+    model_config: &ModelConfig,
+    request: &ThroughputBenchmarkRequest,
+    model_cache: &ModelCache,
+    stop_signal: Arc<AtomicBool>,
+) -> Result<ThroughputReport, A2oError> {
+    if request.prompts.iter().all(|prompt| prompt.trim().is_empty()) {
+        return Err(A2oError::InvalidInput("A throughput benchmark needs at least one prompt".to_string()));
+    }
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_THROUGHPUT_STREAMS);
+    if !(1..=MAX_THROUGHPUT_STREAMS).contains(&concurrency) {
+        return Err(A2oError::InvalidInput(format!("Concurrency must be between 1 and {}, got {}", MAX_THROUGHPUT_STREAMS, concurrency)));
+    }
+    let prompts: Vec<&String> = request.prompts.iter().filter(|prompt| !prompt.trim().is_empty()).collect();
+    let total_requests = request.total_requests.unwrap_or(prompts.len().max(concurrency)).max(1);
+    let backend = llama_backend()?;
+    let model_path = resolve_model_path(&model_config.model_path)
+        .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {} or in parent directories", model_config.model_path)))?;
+
+    // Telemetry starts first; the model load doubles as its warm-up
+    let monitor_stop = Arc::new(AtomicBool::new(false));
+    let (telemetry_tx, mut telemetry_rx) = broadcast::channel::<TelemetryUpdate>(1000);
+    let monitor_handle = {
+        let broadcaster = Arc::new(telemetry_tx);
+        let stop = monitor_stop.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_enhanced_monitoring(broadcaster, stop, None, Some(DEFAULT_TELEMETRY_SAMPLING_HZ), TelemetryProfile::default(), IntegrationMethod::default(), None, None).await {
+                println!("❌ Throughput benchmark monitoring error: {}", e);
+            }
+        })
+    };
+    let stop_monitoring = || monitor_stop.store(true, Ordering::Relaxed);
+
+    let cache_key = ModelCacheKey { path: model_path.clone(), n_gpu_layers: model_config.n_gpu_layers };
+    let model = match model_cache.get(&cache_key) {
+        Some(model) => model,
+        None => {
+            let size_bytes = std::fs::metadata(&model_path).map(|metadata| metadata.len()).unwrap_or(0);
+            model_cache.make_room(size_bytes);
+            match load_model_cancellable(backend, &model_path, THROUGHPUT_LABEL, model_config.n_gpu_layers).await {
+                Ok((model, gpu_offload)) => {
+                    let model = Arc::new(model);
+                    model_cache.insert(cache_key, model.clone(), size_bytes, gpu_offload);
+                    model
+                }
+                Err(e) => {
+                    stop_monitoring();
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    // Every prompt as a fresh single-turn chat, formatted as a generation turn would be
+    let tokenized: Result<Vec<Vec<LlamaToken>>, A2oError> = prompts.iter()
+        .map(|prompt| {
+            let history = [crate::Message { role: "user".to_string(), content: prompt.to_string(), model: None }];
+            let chat_messages = build_chat_message_sequence(&history, request.system_prompt.as_deref())?;
+            let formatted = apply_model_chat_template(&model, &chat_messages, model_config.chat_template_override.as_deref())?;
+            model.str_to_token(&formatted, AddBos::Always)
+                .map_err(|e| A2oError::Tokenization(format!("Failed to tokenize prompt: {:?}", e)))
+        })
+        .collect();
+    let tokenized = match tokenized {
+        Ok(tokenized) => tokenized,
+        Err(e) => {
+            stop_monitoring();
+            return Err(e);
+        }
+    };
+
+    // Each sequence gets room for the longest prompt plus a full reply, within the model's n_ctx
+    let max_tokens = resolve_max_tokens(model_config.max_tokens);
+    let longest_prompt = tokenized.iter().map(Vec::len).max().unwrap_or(0);
+    let n_ctx_limit = match resolve_context_size(model_config.n_ctx, &model_path) {
+        Ok(resolved) => resolved.n_ctx,
+        Err(e) => {
+            stop_monitoring();
+            return Err(e);
+        }
+    };
+    let n_ctx_per_stream = ((longest_prompt + max_tokens) as u32).min(n_ctx_limit);
+    if longest_prompt >= n_ctx_per_stream as usize {
+        stop_monitoring();
+        return Err(A2oError::ContextOverflow {
+            message: format!("The longest prompt is {} tokens but n_ctx is {}; shorten it or raise n_ctx", longest_prompt, n_ctx_per_stream),
+            model: THROUGHPUT_LABEL.to_string(),
+            prompt_tokens: longest_prompt,
+            n_ctx: n_ctx_per_stream,
+        });
+    }
+    let plan = DecodePlan {
+        prompts: tokenized,
+        concurrency,
+        total_requests,
+        max_tokens,
+        n_ctx_per_stream,
+        n_batch: resolve_n_batch(model_config.n_batch, n_ctx_per_stream * concurrency as u32).max(concurrency as u32),
+    };
+    println!("🚦 THROUGHPUT: {} request(s) over {} stream(s), {} prompt(s), n_ctx {} per stream, up to {} tokens each",
+             total_requests, concurrency, plan.prompts.len(), n_ctx_per_stream, max_tokens);
+
+    while telemetry_rx.try_recv().is_ok() {}
+    let started = Instant::now();
+    let decode = {
+        let events = events.clone();
+        let model_config = model_config.clone();
+        tokio::task::spawn_blocking(move || decode_concurrently(&model, &model_config, &plan, events.as_ref(), &stop_signal))
+    };
+    tokio::pin!(decode);
+    let mut package_power = Vec::new();
+    let decoded = loop {
+        tokio::select! {
+            finished = &mut decode => break finished,
+            received = telemetry_rx.recv() => match received {
+                Ok(telemetry) => {
+                    let _ = events.emit("telemetry_update", &telemetry);
+                    let parts = [telemetry.cpu_power_watts, telemetry.gpu_power_watts, telemetry.ane_power_watts];
+                    if parts.iter().any(Option::is_some) {
+                        package_power.push(parts.iter().flatten().sum());
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break (&mut decode).await,
+            },
+        }
+    };
+    let wall_time = started.elapsed();
+    stop_monitoring();
+    let _ = monitor_handle.await;
+    let (served, completed) = decoded.map_err(|e| A2oError::Inference(format!("Throughput benchmark task failed: {}", e)))??;
+
+    let report = summarize_throughput(model_path.to_string_lossy().into_owned(), concurrency, n_ctx_per_stream, served, wall_time, &package_power, completed);
+    println!("🚦 THROUGHPUT: {} output tokens in {:.1}s = {:?} tok/s aggregate, {:?} tok/s per stream, power {:?}W",
+             report.total_output_tokens, wall_time.as_secs_f64(), report.aggregate_tps, report.mean_stream_tps, report.avg_package_power_w);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn served(output_tokens: usize, decode_tps: Option<f64>, ttft_ms: Option<f64>) -> StreamThroughput {
+        StreamThroughput {
+            request: 0,
+            prompt_index: 0,
+            prompt_tokens: 30,
+            output_tokens,
+            queued_ms: 0.0,
+            ttft_ms,
+            latency_ms: 2_000.0,
+            decode_tps,
+            finish_reason: "max_tokens".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summary_aggregates_streams_and_power() {
+        let requests = vec![served(100, Some(20.0), Some(100.0)), served(60, Some(30.0), Some(300.0)), served(0, None, None)];
+        let report = summarize_throughput("/models/a.gguf".to_string(), 3, 1_054, requests, Duration::from_secs(4), &[10.0, 14.0], true);
+        assert_eq!((report.total_prompt_tokens, report.total_output_tokens), (90, 160));
+        assert_eq!(report.aggregate_tps, Some(40.0));
+        assert_eq!(report.mean_stream_tps, Some(25.0));
+        assert_eq!(report.mean_ttft_ms, Some(200.0));
+        assert_eq!(report.avg_package_power_w, Some(12.0));
+        // 12 W for 4 s over 160 tokens
+        assert!((report.energy_per_token_wh.unwrap() - 12.0 * 4.0 / 3600.0 / 160.0).abs() < 1e-12);
+
+        let idle = summarize_throughput(String::new(), 1, 512, vec![served(0, None, None)], Duration::ZERO, &[], false);
+        assert_eq!((idle.aggregate_tps, idle.energy_per_token_wh, idle.avg_package_power_w), (None, None, None));
+    }
+}
@@ -2,8 +2,11 @@ use tauri::Manager;
 
 // Module declarations for Phase 1 refactoring
 pub mod commands;
+pub mod error;
+pub mod events;
 pub mod hardware;
 pub mod inference;
+pub mod server;
 pub mod telemetry;
 pub mod utils;
 
@@ -13,7 +16,15 @@ pub mod persistence;
 // Re-export persistence commands for clean interface
 pub use persistence::{
     save_session, get_saved_sessions, load_session,
-    delete_saved_session, get_session_list, decompress_telemetry
+    delete_saved_session, get_session_list, decompress_telemetry,
+    check_session_reproducibility, get_run_manifest,
+    check_data_compatibility, migrate_sessions,
+    delete_sessions, tag_sessions, set_session_tags, search_sessions, export_sessions, export_session, import_session,
+    create_experiment, list_experiments, assign_sessions_to_experiment, get_experiment_stats,
+    get_telemetry_retention_policy, set_telemetry_retention_policy, export_session_webpage,
+    get_metric_history, get_temperature_unit, set_temperature_unit,
+    get_report_locale, set_report_locale, export_session_csv, export_telemetry, compare_sessions, get_telemetry_stats,
+    get_session_telemetry, append_telemetry_samples, resume_telemetry_recording, discard_telemetry_recording
 };
 
 
@@ -42,7 +53,7 @@ pub use hardware::macmon::{
 pub use telemetry::types::{
     TelemetryUpdate, TelemetryBroadcaster, ModelConfig, Message, GenerationConfig,
     TokenEvent, InputTokenEvent, OutputTokenEvent, SystemPromptTokenEvent, GenerationTimeEvent,
-    PowerConsumptionSummaryEvent, TelemetryCommand, TelemetryCommandBroadcaster
+    PowerConsumptionSummaryEvent, GenerationProgressEvent, ContextSize, ContextSizeResolvedEvent, DecodeOverheadEvent, GenerationResult, GenerationTurnResult, TelemetryProfile, IntegrationMethod, CorePreference, TelemetryCommand, TelemetryCommandBroadcaster
 };
 
 // Re-export from telemetry processor module - Step 4: Global State Migration
@@ -53,7 +64,7 @@ pub use hardware::start_enhanced_monitoring;
 
 
 // Re-export from commands utils module - Priority 4.6
-pub use commands::utils::{greet, stop_generation};
+pub use commands::utils::{greet, stop_generation, get_partial_output, get_resume_checkpoint, load_resume_checkpoint, set_telemetry_state};
 
 
 
@@ -65,6 +76,30 @@ pub use inference::sampler_builder::SamplerBuilder;
 
 // Re-export from commands module
 pub use commands::generation::run_generation_turn;
+pub use commands::validation::validate_generation_config;
+pub use commands::config::{get_default_configs, get_sampler_presets};
+pub use commands::calibration::{calibrate_measurement_overhead, get_measurement_overhead, clear_measurement_overhead};
+pub use commands::hardware_baseline::{measure_hardware_baseline, get_hardware_baseline};
+pub use commands::idle_baseline::{capture_baseline, get_idle_baseline};
+pub use commands::synthetic_load::run_synthetic_load;
+pub use commands::throughput::run_throughput_benchmark;
+pub use commands::start_comparison::run_start_comparison;
+pub use commands::model_info::inspect_model;
+pub use commands::model_cache::{unload_model, get_loaded_models};
+pub use commands::model_library::{get_model_library, rescan_model_library, get_model_library_settings, set_model_library_settings, delete_model_file};
+pub use commands::benchmark::{run_benchmark_suite, list_benchmark_reports};
+pub use commands::night_batch::{schedule_night_batch, get_night_batch, cancel_night_batch};
+pub use commands::api_server::{start_api_server, stop_api_server, get_api_server_status};
+pub use commands::placement_comparison::run_placement_comparison;
+pub use commands::jobs::{get_job_status, await_job, cancel_job};
+pub use commands::event_stream::configure_event_stream;
+pub use commands::conversation_import::import_conversation;
+pub use commands::conversation::{create_conversation, append_user_message, run_turn, export_conversation, delete_conversation};
+pub use commands::comparison_summary::render_comparison_summary;
+pub use commands::leaderboard::{prepare_benchmark_record, export_benchmark_record};
+pub use commands::debug_capture::load_debug_capture;
+pub use commands::sensor_profile::export_sensor_profile;
+pub use commands::hardware_info::{get_hardware_info, get_backend_info};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -84,6 +119,30 @@ pub fn run() {
 
             app.manage(session_db);
 
+            // Loaded models stay resident between turns until evicted or unloaded
+            app.manage(inference::model_cache::ModelCache::default());
+
+            // Pick up models added or removed while the app was closed, then keep watching if enabled
+            let watcher = commands::model_library::ModelLibraryWatcher::default();
+            if let Err(e) = commands::model_library::refresh_library(app.handle()) {
+                println!("⚠️ MODEL LIBRARY: Startup scan failed: {}", e);
+            }
+            if let Ok(settings) = app.state::<persistence::database::SessionDatabase>().model_library_settings() {
+                watcher.restart(app.handle(), &settings);
+            }
+            app.manage(watcher);
+
+            // Probe once at startup so a Rosetta / CPU-only build is flagged before any run
+            let capabilities = hardware::capabilities::platform_capabilities();
+            println!("🧭 Platform: {} build, Metal: {}, llama.cpp: {}",
+                     capabilities.binary_arch, capabilities.metal_available, capabilities.llama_features.join(", "));
+            for warning in &capabilities.warnings {
+                println!("⚠️ {}", warning);
+            }
+            let backend = hardware::backend_info::backend_info();
+            println!("🧭 llama.cpp: llama-cpp-sys-2 {} ({} build), backends: {}",
+                     backend.llama_cpp_sys_2_version, backend.build_profile, backend.backends.join(", "));
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -91,13 +150,95 @@ pub fn run() {
             commands::utils::greet,
             commands::generation::run_generation_turn,
             commands::utils::stop_generation,
+            commands::utils::get_partial_output,
+            commands::utils::get_resume_checkpoint,
+            commands::utils::load_resume_checkpoint,
+            commands::utils::set_telemetry_state,
+            commands::validation::validate_generation_config,
+            commands::config::get_default_configs,
+            commands::config::get_sampler_presets,
+            commands::calibration::calibrate_measurement_overhead,
+            commands::calibration::get_measurement_overhead,
+            commands::calibration::clear_measurement_overhead,
+            commands::hardware_baseline::measure_hardware_baseline,
+            commands::hardware_baseline::get_hardware_baseline,
+            commands::idle_baseline::capture_baseline,
+            commands::idle_baseline::get_idle_baseline,
+            commands::model_cache::unload_model,
+            commands::model_cache::get_loaded_models,
+            commands::conversation_import::import_conversation,
+            commands::conversation::create_conversation,
+            commands::conversation::append_user_message,
+            commands::conversation::run_turn,
+            commands::conversation::export_conversation,
+            commands::conversation::delete_conversation,
+            commands::comparison_summary::render_comparison_summary,
+            commands::leaderboard::prepare_benchmark_record,
+            commands::leaderboard::export_benchmark_record,
+            commands::debug_capture::load_debug_capture,
+            commands::sensor_profile::export_sensor_profile,
+            commands::hardware_info::get_hardware_info,
+            commands::hardware_info::get_backend_info,
             // New persistence commands
             persistence::save_session,
             persistence::get_saved_sessions,
             persistence::load_session,
             persistence::delete_saved_session,
             persistence::get_session_list,
-            persistence::decompress_telemetry
+            persistence::decompress_telemetry,
+            persistence::check_session_reproducibility,
+            persistence::get_run_manifest,
+            persistence::check_data_compatibility,
+            persistence::migrate_sessions,
+            persistence::delete_sessions,
+            persistence::tag_sessions,
+            persistence::set_session_tags,
+            persistence::search_sessions,
+            persistence::export_sessions,
+            persistence::export_session,
+            persistence::import_session,
+            persistence::create_experiment,
+            persistence::list_experiments,
+            persistence::assign_sessions_to_experiment,
+            persistence::get_experiment_stats,
+            persistence::get_telemetry_retention_policy,
+            persistence::set_telemetry_retention_policy,
+            persistence::export_session_webpage,
+            persistence::get_metric_history,
+            persistence::get_temperature_unit,
+            persistence::set_temperature_unit,
+            persistence::get_report_locale,
+            persistence::set_report_locale,
+            persistence::export_session_csv,
+            persistence::export_telemetry,
+            persistence::compare_sessions,
+            persistence::get_telemetry_stats,
+            persistence::get_session_telemetry,
+            persistence::append_telemetry_samples,
+            persistence::resume_telemetry_recording,
+            persistence::discard_telemetry_recording,
+            commands::model_library::get_model_library,
+            commands::model_library::rescan_model_library,
+            commands::model_library::get_model_library_settings,
+            commands::model_library::set_model_library_settings,
+            commands::model_library::delete_model_file,
+            commands::benchmark::run_benchmark_suite,
+            commands::benchmark::list_benchmark_reports,
+            commands::night_batch::schedule_night_batch,
+            commands::night_batch::get_night_batch,
+            commands::night_batch::cancel_night_batch,
+            commands::api_server::start_api_server,
+            commands::api_server::stop_api_server,
+            commands::api_server::get_api_server_status,
+            commands::placement_comparison::run_placement_comparison,
+            commands::jobs::get_job_status,
+            commands::jobs::await_job,
+            commands::jobs::cancel_job,
+            commands::event_stream::configure_event_stream,
+            commands::synthetic_load::run_synthetic_load,
+            commands::throughput::run_throughput_benchmark,
+            commands::start_comparison::run_start_comparison,
+            commands::model_info::inspect_model
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,161 @@
+// Statistical comparison of Model A and Model B over repeated runs: a bootstrap confidence
+// interval for the difference of the means and a permutation test of whether it could be
+// noise. Any repeated runs get an interval; the test needs MIN_RUNS_FOR_SIGNIFICANCE runs
+// per model, and single runs are left to the reader. Resampling uses a fixed seed, so the
+// same runs always give the same report.
+
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::benchmark::{percentile, BenchmarkRun, BENCHMARK_METRICS};
+
+// Resamples for the bootstrap interval and for the permutation test
+const RESAMPLES: usize = 2000;
+const RESAMPLE_SEED: u64 = 0x0a2_0a2_0a2;
+/// p-values below this mark a difference as significant
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+/// Runs each model needs before the difference gets an interval
+pub const MIN_RUNS_PER_MODEL: usize = 2;
+/// Runs each model needs before the difference is tested. With fewer, no split of the runs
+/// is rare enough to reach SIGNIFICANCE_LEVEL (3 vs 3 bottoms out at p = 0.1).
+pub const MIN_RUNS_FOR_SIGNIFICANCE: usize = 4;
+
+/// One metric compared between the two models; `difference` is B - A
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDifference {
+    pub metric: String,
+    pub runs_a: usize,
+    pub runs_b: usize,
+    pub mean_a: f64,
+    pub mean_b: f64,
+    pub difference: f64,
+    pub difference_percent: Option<f64>,   // Relative to A; None when A is 0
+    pub ci95_low: f64,                     // Bootstrap 95% interval of the difference
+    pub ci95_high: f64,
+    pub p_value: Option<f64>,              // Two-sided permutation test; None below MIN_RUNS_FOR_SIGNIFICANCE
+    pub significant: bool,                 // p_value < SIGNIFICANCE_LEVEL; false when untested
+}
+
+// SplitMix64: small, seedable and good enough for resampling indices
+struct Resampler(u64);
+
+impl Resampler {
+    fn next_index(&mut self, len: usize) -> usize {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) % len as u64) as usize
+    }
+
+    fn resample_mean(&mut self, values: &[f64]) -> f64 {
+        (0..values.len()).map(|_| values[self.next_index(values.len())]).sum::<f64>() / values.len() as f64
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Compare one metric's values for A and B, or None when either model has fewer than
+/// MIN_RUNS_PER_MODEL values. The permutation test only runs from MIN_RUNS_FOR_SIGNIFICANCE.
+pub fn compare_metric(metric: &str, a: &[f64], b: &[f64]) -> Option<MetricDifference> {
+    if a.len() < MIN_RUNS_PER_MODEL || b.len() < MIN_RUNS_PER_MODEL {
+        return None;
+    }
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let difference = mean_b - mean_a;
+    let mut rng = Resampler(RESAMPLE_SEED);
+
+    let mut bootstrap: Vec<f64> = (0..RESAMPLES)
+        .map(|_| rng.resample_mean(b) - rng.resample_mean(a))
+        .collect();
+    bootstrap.sort_by(|x, y| x.total_cmp(y));
+
+    // Shuffle the pooled runs between the models and count differences at least as large
+    let testable = a.len() >= MIN_RUNS_FOR_SIGNIFICANCE && b.len() >= MIN_RUNS_FOR_SIGNIFICANCE;
+    let p_value = testable.then(|| {
+        let mut pooled: Vec<f64> = a.iter().chain(b).copied().collect();
+        let mut as_extreme = 0;
+        for _ in 0..RESAMPLES {
+            for i in (1..pooled.len()).rev() {
+                pooled.swap(i, rng.next_index(i + 1));
+            }
+            let (perm_a, perm_b) = pooled.split_at(a.len());
+            if (mean(perm_b) - mean(perm_a)).abs() >= difference.abs() - 1e-12 {
+                as_extreme += 1;
+            }
+        }
+        (as_extreme + 1) as f64 / (RESAMPLES + 1) as f64
+    });
+
+    Some(MetricDifference {
+        metric: metric.to_string(),
+        runs_a: a.len(),
+        runs_b: b.len(),
+        mean_a,
+        mean_b,
+        difference,
+        difference_percent: (mean_a != 0.0).then(|| difference / mean_a * 100.0),
+        ci95_low: percentile(&bootstrap, 2.5),
+        ci95_high: percentile(&bootstrap, 97.5),
+        p_value,
+        significant: p_value.is_some_and(|p_value| p_value < SIGNIFICANCE_LEVEL),
+    })
+}
+
+/// A-vs-B differences of each benchmark metric both models recorded often enough. Runs the
+/// user stopped are left out, as in the per-model statistics.
+pub fn compare_benchmark_models(runs: &[BenchmarkRun]) -> Vec<MetricDifference> {
+    let values = |model: &str, metric: &str| -> Vec<f64> {
+        runs.iter()
+            .filter(|run| run.model == model && run.finish_reason != "user_stop")
+            .filter_map(|run| run.metric(metric))
+            .collect()
+    };
+    BENCHMARK_METRICS.iter()
+        .filter_map(|&metric| compare_metric(metric, &values("A", metric), &values("B", metric)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separated_runs_differ_significantly_and_overlapping_runs_do_not() {
+        let a = [40.1, 40.3, 39.8, 40.2, 40.0, 39.6];
+        let b = [44.9, 45.2, 45.1, 44.8, 45.0, 45.0];
+        let clear = compare_metric("avg_tps", &a, &b).unwrap();
+        assert!((clear.difference - 5.0).abs() < 1e-9);
+        assert!(clear.ci95_low > 4.0 && clear.ci95_high < 6.0);
+        assert!(clear.p_value.unwrap() < 0.01);
+        assert!(clear.significant);
+
+        let noisy = compare_metric("avg_tps", &[40.0, 44.0, 38.0, 43.0], &[41.0, 39.0, 44.5, 42.0]).unwrap();
+        assert!(noisy.ci95_low < 0.0 && noisy.ci95_high > 0.0);
+        assert!(!noisy.significant);
+        // Seeded resampling: the same runs always give the same result
+        assert_eq!(compare_metric("avg_tps", &a, &b), Some(clear));
+
+        assert_eq!(compare_metric("avg_tps", &[40.0], &b), None);
+    }
+
+    #[test]
+    fn test_few_runs_get_an_interval_but_no_verdict() {
+        let few = compare_metric("avg_tps", &[40.0, 40.2, 39.9], &[45.0, 45.1, 44.8]).unwrap();
+        assert!(few.ci95_low > 4.0 && few.ci95_high < 6.0);
+        assert_eq!(few.p_value, None);
+        assert!(!few.significant);
+    }
+
+    #[test]
+    fn test_minimum_runs_can_reach_significance() {
+        // Completely separated runs at the minimum count: the exact p is 2/70
+        let a = [40.0, 40.1, 39.9, 40.2];
+        let b = [45.0, 45.1, 44.9, 45.2];
+        assert_eq!(a.len(), MIN_RUNS_FOR_SIGNIFICANCE);
+        let difference = compare_metric("avg_tps", &a, &b).unwrap();
+        assert!(difference.p_value.unwrap() < SIGNIFICANCE_LEVEL);
+        assert!(difference.significant);
+    }
+}
@@ -0,0 +1,273 @@
+// Benchmark suites: a list of prompts run several times against Model A and/or B, with
+// the per-run results and their per-model statistics kept together as one report. For
+// "Both" suites the model order can alternate or be randomized per repetition, so the
+// model that runs second (on a warmer SoC) is not always the same one.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::analysis::MetricDifference;
+use crate::telemetry::types::GenerationResult;
+
+// Per-run fields aggregated per model, in display order
+pub const BENCHMARK_METRICS: [&str; 4] = ["ttft_ms", "avg_tps", "total_energy_wh", "energy_per_token_wh"];
+
+/// Which model of a "Both" suite runs first in each repetition
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelOrder {
+    #[default]
+    Fixed,        // A then B every time, as one "Both" turn
+    Alternating,  // AB, BA, AB, ...
+    Randomized,   // Each pair of repetitions runs AB once and BA once, in random order
+}
+
+/// Model labels in run order for each repetition. `coin` decides randomized pairs (true = AB
+/// first); an odd last repetition gets its own coin flip.
+pub fn model_orders(order: ModelOrder, repetitions: u32, mut coin: impl FnMut() -> bool) -> Vec<[&'static str; 2]> {
+    const AB: [&str; 2] = ["A", "B"];
+    const BA: [&str; 2] = ["B", "A"];
+    let mut orders = Vec::with_capacity(repetitions as usize);
+    for repetition in 0..repetitions {
+        orders.push(match order {
+            ModelOrder::Fixed => AB,
+            ModelOrder::Alternating => if repetition % 2 == 0 { AB } else { BA },
+            ModelOrder::Randomized if repetition % 2 == 1 => if orders[repetition as usize - 1] == AB { BA } else { AB },
+            ModelOrder::Randomized => if coin() { AB } else { BA },
+        });
+    }
+    orders
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarkSuiteRequest {
+    pub name: Option<String>,
+    pub prompts: Vec<String>,
+    pub repetitions: u32,
+    pub cooldown_secs: Option<f64>,   // Pause between consecutive runs, to let the SoC cool down
+    #[serde(default)]
+    pub wait_for_cpu_baseline: bool,  // Before each later run, wait for the CPU to cool back to its pre-suite temperature
+    pub cooldown_margin_c: Option<f64>,
+    #[serde(default)]
+    pub defer_on_thermal_pressure: bool,   // Hold runs while macOS reports serious or critical thermal pressure
+    #[serde(default)]
+    pub model_order: ModelOrder,           // "Both" suites only
+}
+
+/// One model's result for one prompt in one repetition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub prompt_index: usize,
+    pub repetition: u32,
+    pub model: String,   // "A" / "B"
+    #[serde(default)]
+    pub position: usize, // 0 when the model ran first in its repetition, 1 when second
+    pub ttft_ms: Option<f64>,
+    pub avg_tps: Option<f64>,
+    pub total_energy_wh: Option<f64>,        // Only when telemetry was enabled
+    pub energy_per_token_wh: Option<f64>,
+    pub output_tokens: usize,
+    pub finish_reason: String,
+}
+
+impl BenchmarkRun {
+    pub fn from_result(prompt_index: usize, repetition: u32, position: usize, result: &GenerationResult) -> Self {
+        Self {
+            prompt_index,
+            repetition,
+            model: result.model.clone(),
+            position,
+            ttft_ms: result.ttft_ms.map(|ttft| ttft as f64),
+            avg_tps: result.avg_tps,
+            total_energy_wh: result.energy.as_ref().map(|energy| energy.total_energy_wh),
+            energy_per_token_wh: result.energy.as_ref().and_then(|energy| energy.energy_per_token_wh),
+            output_tokens: result.output_tokens,
+            finish_reason: result.finish_reason.clone(),
+        }
+    }
+
+    pub(crate) fn metric(&self, metric: &str) -> Option<f64> {
+        match metric {
+            "ttft_ms" => self.ttft_ms,
+            "avg_tps" => self.avg_tps,
+            "total_energy_wh" => self.total_energy_wh,
+            "energy_per_token_wh" => self.energy_per_token_wh,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkStats {
+    pub count: usize,   // Runs that recorded the metric
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub stddev: f64,    // Sample standard deviation; 0 for a single run
+    #[serde(default)]
+    pub ci95_low: Option<f64>,    // 95% confidence interval of the mean (Student's t); None for a single run
+    #[serde(default)]
+    pub ci95_high: Option<f64>,
+}
+
+// Two-sided 95% critical values of Student's t for 1..=30 degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    T_CRITICAL_95.get(degrees_of_freedom.wrapping_sub(1)).copied().unwrap_or(1.96)
+}
+
+// Linear interpolation between the closest ranks of sorted values
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+impl BenchmarkStats {
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            sorted.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+        let stddev = variance.sqrt();
+        let half_width = (count > 1).then(|| t_critical_95(count - 1) * stddev / (count as f64).sqrt());
+        Some(Self {
+            count,
+            mean,
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            stddev,
+            ci95_low: half_width.map(|half_width| mean - half_width),
+            ci95_high: half_width.map(|half_width| mean + half_width),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkModelStats {
+    pub model: String,
+    pub runs: usize,
+    pub metrics: BTreeMap<String, BenchmarkStats>,   // Keyed by BENCHMARK_METRICS name
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub uuid: String,
+    pub name: String,
+    pub created_at: i64,
+    pub target: String,   // "A", "B" or "Both"
+    pub prompts: Vec<String>,
+    pub repetitions: u32,
+    pub cooldown_secs: Option<f64>,
+    #[serde(default)]
+    pub wait_for_cpu_baseline: bool,
+    #[serde(default)]
+    pub defer_on_thermal_pressure: bool,
+    #[serde(default)]
+    pub model_order: ModelOrder,
+    #[serde(default)]
+    pub thermal_wait_secs: f64,   // Total time runs were held back by cooldown or thermal pressure waits
+    pub completed: bool,  // False when the suite was stopped before its last run
+    pub runs: Vec<BenchmarkRun>,
+    pub models: Vec<BenchmarkModelStats>,
+    #[serde(default)]
+    pub differences: Vec<MetricDifference>,   // B vs A with significance, "Both" suites with repeated runs only
+}
+
+/// Statistics of each model over its runs. Runs the user stopped are left out, as are
+/// metrics a run did not record (e.g. energy without telemetry).
+pub fn aggregate_benchmark_runs(runs: &[BenchmarkRun]) -> Vec<BenchmarkModelStats> {
+    let mut per_model: BTreeMap<&str, Vec<&BenchmarkRun>> = BTreeMap::new();
+    for run in runs.iter().filter(|run| run.finish_reason != "user_stop") {
+        per_model.entry(run.model.as_str()).or_default().push(run);
+    }
+
+    per_model.into_iter()
+        .map(|(model, runs)| {
+            let metrics = BENCHMARK_METRICS.iter()
+                .filter_map(|&metric| {
+                    let values: Vec<f64> = runs.iter().filter_map(|run| run.metric(metric)).collect();
+                    BenchmarkStats::from_values(&values).map(|stats| (metric.to_string(), stats))
+                })
+                .collect();
+            BenchmarkModelStats { model: model.to_string(), runs: runs.len(), metrics }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: &str, ttft_ms: f64, avg_tps: f64, finish_reason: &str) -> BenchmarkRun {
+        BenchmarkRun {
+            prompt_index: 0,
+            repetition: 0,
+            model: model.to_string(),
+            position: 0,
+            ttft_ms: Some(ttft_ms),
+            avg_tps: Some(avg_tps),
+            total_energy_wh: None,
+            energy_per_token_wh: None,
+            output_tokens: 64,
+            finish_reason: finish_reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_benchmark_stats_per_model() {
+        let runs = vec![
+            run("A", 100.0, 40.0, "eog"),
+            run("A", 200.0, 44.0, "max_tokens"),
+            run("A", 300.0, 42.0, "eog"),
+            run("A", 400.0, 46.0, "eog"),
+            run("A", 5.0, 1.0, "user_stop"),
+            run("B", 250.0, 30.0, "eog"),
+        ];
+        let models = aggregate_benchmark_runs(&runs);
+        assert_eq!(models.iter().map(|m| m.model.as_str()).collect::<Vec<_>>(), vec!["A", "B"]);
+
+        let a = &models[0];
+        assert_eq!(a.runs, 4);
+        assert!(!a.metrics.contains_key("total_energy_wh"));
+        let ttft = &a.metrics["ttft_ms"];
+        assert_eq!(ttft.count, 4);
+        assert_eq!(ttft.mean, 250.0);
+        assert_eq!(ttft.p50, 250.0);
+        assert!((ttft.p95 - 385.0).abs() < 1e-9);
+        assert!((ttft.stddev - 129.099_444_873_580_55).abs() < 1e-9);
+        // mean ± t(3) * s / sqrt(4)
+        assert!((ttft.ci95_high.unwrap() - (250.0 + 3.182 * 129.099_444_873_580_55 / 2.0)).abs() < 1e-9);
+        assert!((ttft.mean - ttft.ci95_low.unwrap() - (ttft.ci95_high.unwrap() - ttft.mean)).abs() < 1e-9);
+
+        let b = &models[1].metrics["avg_tps"];
+        assert_eq!((b.count, b.p50, b.p95, b.stddev), (1, 30.0, 30.0, 0.0));
+        assert_eq!((b.ci95_low, b.ci95_high), (None, None));
+        assert_eq!(BenchmarkStats::from_values(&[]), None);
+    }
+
+    #[test]
+    fn test_model_orders_balance_each_pair_of_repetitions() {
+        assert_eq!(model_orders(ModelOrder::Fixed, 3, || false), vec![["A", "B"]; 3]);
+        assert_eq!(model_orders(ModelOrder::Alternating, 3, || false), vec![["A", "B"], ["B", "A"], ["A", "B"]]);
+
+        let mut flips = [false, true, false].into_iter();
+        let orders = model_orders(ModelOrder::Randomized, 5, || flips.next().unwrap());
+        assert_eq!(orders, vec![["B", "A"], ["A", "B"], ["A", "B"], ["B", "A"], ["B", "A"]]);
+        assert_eq!(flips.next(), None);
+    }
+}
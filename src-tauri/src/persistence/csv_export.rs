@@ -0,0 +1,94 @@
+// CSV export of a saved session's telemetry, one row per sample, formatted for the user's
+// report locale (decimal separator, delimiter, timestamps) and temperature unit
+
+use serde_json::Value;
+
+use crate::persistence::report_locale::ReportLocale;
+use crate::telemetry::temperature_unit::{TemperatureUnit, TEMPERATURE_FIELDS};
+
+// Exported telemetry columns: (field, header, decimals)
+const CSV_COLUMNS: [(&str, &str, usize); 8] = [
+    ("tps", "Tokens per second", 2),
+    ("cpu_power", "CPU power (W)", 3),
+    ("gpu_power", "GPU power (W)", 3),
+    ("ane_power", "ANE power (W)", 3),
+    ("cpu_temp", "CPU temperature", 1),
+    ("gpu_temp", "GPU temperature", 1),
+    ("ram_usage", "RAM usage (GB)", 2),
+    ("total_energy_wh", "Energy (Wh)", 5),
+];
+
+pub fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render telemetry samples (already decompressed) as CSV; missing values are empty cells
+pub fn render_telemetry_csv(telemetry: &[Value], locale: &ReportLocale, unit: TemperatureUnit) -> String {
+    let delimiter = locale.csv_delimiter();
+    let separator = delimiter.to_string();
+
+    let mut headers = vec!["Time".to_string(), "Timestamp (ms)".to_string(), "Model".to_string()];
+    headers.extend(CSV_COLUMNS.iter().map(|&(field, header, _)| {
+        if TEMPERATURE_FIELDS.contains(&field) {
+            format!("{} ({})", header, unit.symbol())
+        } else {
+            header.to_string()
+        }
+    }));
+    let mut out = headers.iter().map(|header| csv_field(header, delimiter)).collect::<Vec<_>>().join(&separator);
+    out.push('\n');
+
+    for point in telemetry {
+        let timestamp_ms = point.get("timestamp").and_then(Value::as_i64);
+        let mut row = vec![
+            timestamp_ms.map(|ms| locale.format_timestamp_ms(ms)).unwrap_or_default(),
+            timestamp_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            point.get("model").and_then(Value::as_str).unwrap_or_default().to_string(),
+        ];
+        row.extend(CSV_COLUMNS.iter().map(|&(field, _, decimals)| {
+            point.get(field).and_then(Value::as_f64)
+                .map(|value| if TEMPERATURE_FIELDS.contains(&field) { unit.from_celsius(value) } else { value })
+                .map(|value| locale.format_number(value, decimals))
+                .unwrap_or_default()
+        }));
+        out.push_str(&row.iter().map(|cell| csv_field(cell, delimiter)).collect::<Vec<_>>().join(&separator));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::report_locale::{DateFormat, DecimalSeparator};
+    use serde_json::json;
+
+    #[test]
+    fn test_european_csv_uses_semicolons_and_comma_decimals() {
+        let locale = ReportLocale {
+            decimal_separator: DecimalSeparator::Comma,
+            date_format: DateFormat::DayMonthYear,
+            local_time: false,
+        };
+        let telemetry = vec![
+            json!({ "timestamp": 1_741_943_100_000i64, "model": "A", "tps": 41.256, "cpu_power": 12.5, "cpu_temp": 50.0 }),
+            json!({ "timestamp": 1_741_943_101_000i64, "model": "B;q4", "gpu_power": null }),
+        ];
+        let csv = render_telemetry_csv(&telemetry, &locale, TemperatureUnit::Celsius);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Time;Timestamp (ms);Model;Tokens per second;CPU power (W)"));
+        assert!(lines[0].contains("CPU temperature (°C)"));
+        assert_eq!(lines[1], "14.03.2025 09:05:00 UTC;1741943100000;A;41,26;12,500;;;50,0;;;");
+        assert_eq!(lines[2], "14.03.2025 09:05:01 UTC;1741943101000;\"B;q4\";;;;;;;;");
+        assert_eq!(lines[0].matches(';').count(), lines[1].matches(';').count());
+
+        let fahrenheit = render_telemetry_csv(&telemetry[..1], &ReportLocale::default(), TemperatureUnit::Fahrenheit);
+        assert!(fahrenheit.contains("CPU temperature (°F)"));
+        assert!(fahrenheit.lines().nth(1).unwrap().ends_with(",A,41.26,12.500,,,122.0,,,"));
+    }
+}
@@ -1,9 +1,11 @@
-use rusqlite::{Connection, params, Result as SqlResult};
-use std::path::Path;
+use rusqlite::{Connection, OptionalExtension, params, Result as SqlResult};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 pub struct SessionDatabase {
     conn: Mutex<Connection>,
+    db_path: PathBuf,
+    previous_app_version: Option<String>,   // App version that opened the database before this one
 }
 
 impl SessionDatabase {
@@ -38,11 +40,107 @@ impl SessionDatabase {
         conn.execute("CREATE INDEX IF NOT EXISTS idx_sessions_name ON saved_sessions(name COLLATE NOCASE);", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_sessions_uuid ON saved_sessions(uuid);", [])?;
 
+        // Free-form labels for grouping sessions (e.g. one per sweep)
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS session_tags (
+                session_uuid TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (session_uuid, tag)
+            );
+        ", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_session_tags_tag ON session_tags(tag);", [])?;
+
+        // Full-text index over session names, prompts and model names, kept in step with
+        // saved_sessions by the methods that write it
+        conn.execute("
+            CREATE VIRTUAL TABLE IF NOT EXISTS session_search USING fts5(
+                uuid UNINDEXED,
+                name,
+                prompts,
+                models
+            );
+        ", [])?;
+
+        // Small key/value store for user preferences (JSON values)
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+        ", [])?;
+
+        // Experiments group related sessions (a sweep, a quant ladder)
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS experiments (
+                uuid TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                created_at INTEGER NOT NULL
+            );
+        ", [])?;
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS experiment_sessions (
+                experiment_uuid TEXT NOT NULL,
+                session_uuid TEXT NOT NULL,
+                PRIMARY KEY (experiment_uuid, session_uuid)
+            );
+        ", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_experiment_sessions_session ON experiment_sessions(session_uuid);", [])?;
+
+        // GGUF files found under the configured model directories
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS model_library (
+                path TEXT PRIMARY KEY,
+                file_name TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                modified_at INTEGER NOT NULL
+            );
+        ", [])?;
+
+        // Benchmark suite reports, stored whole as JSON
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS benchmark_reports (
+                uuid TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                report TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+        ", [])?;
+
+        // Saved telemetry, one row per sample; recordings of chats never saved are dropped
+        create_telemetry_samples_table(&conn)?;
+        delete_recordings(&conn)?;
+
+        // Versions the database and each session were written with
+        conn.execute("
+            CREATE TABLE IF NOT EXISTS app_metadata (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+        ", [])?;
+        add_missing_columns(&conn, "saved_sessions", &[
+            ("app_version", "TEXT"),
+            ("schema_version", "INTEGER"),
+            ("telemetry_schema_version", "INTEGER"),
+        ])?;
+        let previous_app_version = record_app_versions(&conn)?;
+        index_unindexed_sessions(&conn)?;
+
         Ok(SessionDatabase {
             conn: Mutex::new(conn),
+            db_path: db_path.to_path_buf(),
+            previous_app_version,
         })
     }
 
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    pub fn previous_app_version(&self) -> Option<&str> {
+        self.previous_app_version.as_deref()
+    }
+
     pub fn with_connection<F, R>(&self, f: F) -> SqlResult<R>
     where
         F: FnOnce(&Connection) -> SqlResult<R>,
@@ -52,7 +150,204 @@ impl SessionDatabase {
     }
 }
 
-use crate::persistence::{models::*, compression::*};
+// ALTER TABLE for columns added after the table was first created (NULL for existing rows)
+fn add_missing_columns(conn: &Connection, table: &str, columns: &[(&str, &str)]) -> SqlResult<()> {
+    let existing: Vec<String> = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<SqlResult<_>>()?;
+    for (name, column_type) in columns {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, column_type), [])?;
+        }
+    }
+    Ok(())
+}
+
+// Store the running app and schema versions; returns the app version recorded before
+fn record_app_versions(conn: &Connection) -> SqlResult<Option<String>> {
+    let previous = conn
+        .query_row("SELECT value FROM app_metadata WHERE key = 'app_version'", [], |row| row.get::<_, String>(0))
+        .optional()?;
+    let versions = [
+        ("app_version", env!("CARGO_PKG_VERSION").to_string()),
+        ("session_schema_version", SESSION_SCHEMA_VERSION.to_string()),
+        ("telemetry_schema_version", TELEMETRY_SCHEMA_VERSION.to_string()),
+    ];
+    for (key, value) in versions {
+        conn.execute(
+            "INSERT INTO app_metadata (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+    }
+    Ok(previous)
+}
+
+// Row of `SELECT id, uuid, name, session_data, compression_type, original_size, created_at, updated_at`
+fn session_from_row(row: &rusqlite::Row) -> SqlResult<SavedSession> {
+    Ok(SavedSession {
+        id: Some(row.get(0)?),
+        uuid: row.get(1)?,
+        name: row.get(2)?,
+        session_data: serde_json::from_str(&row.get::<_, String>(3)?).unwrap(),
+        compression_type: row.get(4)?,
+        original_size: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+// Row of `SELECT uuid, name, description, created_at, <session count>` from experiments
+fn experiment_from_row(row: &rusqlite::Row) -> SqlResult<Experiment> {
+    Ok(Experiment {
+        uuid: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        created_at: row.get(3)?,
+        session_count: row.get::<_, i64>(4)? as usize,
+    })
+}
+
+// Move the session's samples into telemetry_samples, leaving the marker in session_data.
+// A recording reference is resolved by copying the recording's rows. Returns the JSON size
+// of the samples moved, None when there was no raw or compressed telemetry to move.
+fn store_session_telemetry(conn: &Connection, uuid: &str, data: &mut serde_json::Value) -> SqlResult<Option<i64>> {
+    let recording = data.get("telemetry_data").and_then(recording_reference).map(recording_key);
+    if let Some(recording) = recording {
+        let samples = copy_samples(conn, &recording, uuid)?;
+        data["telemetry_data"] = stored_telemetry_marker(samples);
+        return Ok(None);
+    }
+    match upgrade_telemetry(data).map_err(rusqlite::Error::InvalidColumnName)? {
+        Some((points, original_size)) => {
+            insert_samples(conn, uuid, &points)?;
+            Ok(Some(original_size))
+        }
+        None => Ok(None),
+    }
+}
+
+// Put the session's samples back in place of the marker, as a plain array
+fn load_session_telemetry(conn: &Connection, session: &mut SavedSession) -> SqlResult<()> {
+    if session.session_data.get("telemetry_data").is_some_and(is_stored_telemetry_marker) {
+        session.session_data["telemetry_data"] = serde_json::Value::Array(load_samples(conn, &session.uuid)?);
+    }
+    Ok(())
+}
+
+fn session_exists(conn: &Connection, uuid: &str) -> SqlResult<bool> {
+    conn.query_row("SELECT 1 FROM saved_sessions WHERE uuid = ?1", [uuid], |_| Ok(()))
+        .optional()
+        .map(|row| row.is_some())
+}
+
+// Delete the session with its telemetry samples, tags, search entry and experiment
+// membership; false when it did not exist. Callers run it inside a transaction.
+fn delete_session_rows(conn: &Connection, uuid: &str) -> SqlResult<bool> {
+    let affected = conn.execute("DELETE FROM saved_sessions WHERE uuid = ?1", [uuid])?;
+    delete_samples(conn, uuid)?;
+    conn.execute("DELETE FROM session_tags WHERE session_uuid = ?1", [uuid])?;
+    conn.execute("DELETE FROM session_search WHERE uuid = ?1", [uuid])?;
+    conn.execute("DELETE FROM experiment_sessions WHERE session_uuid = ?1", [uuid])?;
+    Ok(affected > 0)
+}
+
+fn session_tags(conn: &Connection, uuid: &str) -> SqlResult<Vec<String>> {
+    conn.prepare("SELECT tag FROM session_tags WHERE session_uuid = ?1 ORDER BY tag")?
+        .query_map([uuid], |row| row.get(0))?
+        .collect()
+}
+
+// Searchable text of a session: the system prompt and user messages, and the model paths
+// of its configuration and replies
+fn session_search_text(data: &serde_json::Value) -> (String, String) {
+    let mut prompts: Vec<&str> = data.pointer("/configuration/system_prompt")
+        .and_then(|prompt| prompt.as_str())
+        .into_iter()
+        .collect();
+    let mut models: Vec<&str> = ["/configuration/model_a/model_path", "/configuration/model_b/model_path"].iter()
+        .filter_map(|pointer| data.pointer(pointer).and_then(|path| path.as_str()))
+        .collect();
+    for message in data.get("chat_history").and_then(|history| history.as_array()).into_iter().flatten() {
+        if message.get("role").and_then(|role| role.as_str()) == Some("user") {
+            prompts.extend(message.get("content").and_then(|content| content.as_str()));
+        }
+        if let Some(model) = message.get("model").and_then(|model| model.as_str()) {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        }
+    }
+    (prompts.join("\n"), models.join("\n"))
+}
+
+// (Re)write the session's row in the full-text index
+fn index_session(conn: &Connection, uuid: &str, name: &str, data: &serde_json::Value) -> SqlResult<()> {
+    let (prompts, models) = session_search_text(data);
+    conn.execute("DELETE FROM session_search WHERE uuid = ?1", [uuid])?;
+    conn.execute(
+        "INSERT INTO session_search (uuid, name, prompts, models) VALUES (?1, ?2, ?3, ?4)",
+        params![uuid, name, prompts, models],
+    )?;
+    Ok(())
+}
+
+// Index sessions saved before the full-text index existed
+fn index_unindexed_sessions(conn: &Connection) -> SqlResult<()> {
+    let pending: Vec<(String, String, String)> = conn
+        .prepare("SELECT uuid, name, session_data FROM saved_sessions WHERE uuid NOT IN (SELECT uuid FROM session_search)")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<SqlResult<_>>()?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let tx = conn.unchecked_transaction()?;
+    for (uuid, name, data) in &pending {
+        let data = serde_json::from_str(data).unwrap_or(serde_json::Value::Null);
+        index_session(&tx, uuid, name, &data)?;
+    }
+    tx.commit()?;
+    println!("🗄️ Indexed {} saved session(s) for search", pending.len());
+    Ok(())
+}
+
+// FTS5 query matching every whitespace-separated term as a prefix; terms are quoted so
+// user input cannot form FTS5 syntax
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query.split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+use crate::persistence::models::*;
+use crate::persistence::telemetry_store::{
+    append_samples, copy_samples, create_telemetry_samples_table, delete_recordings, delete_samples,
+    insert_samples, is_stored_telemetry_marker, load_samples, recording_key, recording_reference,
+    stored_telemetry_marker, telemetry_stats, ModelTelemetryStats
+};
+use crate::persistence::experiments::{aggregate_model_stats, Experiment, ExperimentStats};
+use crate::persistence::telemetry_policy::TelemetryRetentionPolicy;
+use crate::persistence::history::HistoryRange;
+use crate::persistence::report_locale::ReportLocale;
+use crate::persistence::model_library::{diff_library, ModelFile, ModelLibraryChange, ModelLibrarySettings};
+use crate::persistence::benchmark::BenchmarkReport;
+use crate::telemetry::hardware_baseline::HardwareBaseline;
+use crate::telemetry::idle_baseline::IdleBaseline;
+use crate::telemetry::temperature_unit::TemperatureUnit;
+use serde::{de::DeserializeOwned, Serialize};
+
+const TELEMETRY_POLICY_KEY: &str = "telemetry_retention_policy";
+const HARDWARE_BASELINE_KEY: &str = "hardware_baseline";
+const IDLE_BASELINE_KEY: &str = "idle_baseline";
+const TEMPERATURE_UNIT_KEY: &str = "temperature_unit";
+const REPORT_LOCALE_KEY: &str = "report_locale";
+const MODEL_LIBRARY_SETTINGS_KEY: &str = "model_library_settings";
+use crate::persistence::migration::{
+    CompatibilityReport, MigrationFailure, MigrationSummary, SessionVersionInfo,
+    SESSION_SCHEMA_VERSION, TELEMETRY_SCHEMA_VERSION,
+    session_schema_version, telemetry_schema_version, upgrade_session_schema, upgrade_telemetry
+};
 
 impl SessionDatabase {
     pub fn save_session(&self, request: CreateSessionRequest) -> SqlResult<SavedSession> {
@@ -62,33 +357,17 @@ impl SessionDatabase {
                 .map_err(|e| rusqlite::Error::InvalidColumnName(e))?;
 
             let mut session = SavedSession::new(request.name, request.session_data.clone());
+            let tx = conn.unchecked_transaction()?;
 
-            // Compress large telemetry data if present
-            let processed_data = if let Some(telemetry) = request.session_data.get("telemetry_data") {
-                if let Some(telemetry_array) = telemetry.as_array() {
-                match compress_telemetry_data(telemetry_array) {
-                    Ok(compressed) => {
-                        session.compression_type = "lz4".to_string();
-                        session.original_size = Some(serde_json::to_string(&telemetry).unwrap().len() as i64);
-
-                        let mut modified_data = request.session_data.clone();
-                        modified_data.as_object_mut().unwrap().insert("telemetry_data".to_string(), compressed);
-                        modified_data
-                    }
-                    Err(_) => request.session_data.clone()
-                }
-                } else {
-                    request.session_data.clone()
-                }
-            } else {
-                request.session_data.clone()
-            };
+            // Telemetry samples go to their own table; the rest is stored as JSON text
+            let mut processed_data = request.session_data;
+            session.original_size = store_session_telemetry(&tx, &session.uuid, &mut processed_data)?;
 
-            // Store as JSON text
-            conn.execute(
+            tx.execute(
                 "
-                INSERT INTO saved_sessions (uuid, name, session_data, compression_type, original_size, created_at, updated_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                INSERT INTO saved_sessions (uuid, name, session_data, compression_type, original_size, created_at, updated_at,
+                                            app_version, schema_version, telemetry_schema_version)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
                 ",
                 params![
                     session.uuid,
@@ -97,11 +376,16 @@ impl SessionDatabase {
                     session.compression_type,
                     session.original_size,
                     session.created_at,
-                    session.updated_at
+                    session.updated_at,
+                    env!("CARGO_PKG_VERSION"),
+                    session_schema_version(&processed_data),
+                    telemetry_schema_version(&processed_data)
                 ],
             )?;
 
-            session.id = Some(conn.last_insert_rowid());
+            session.id = Some(tx.last_insert_rowid());
+            index_session(&tx, &session.uuid, &session.name, &processed_data)?;
+            tx.commit()?;
             session.session_data = processed_data;
 
             Ok(session)
@@ -146,7 +430,7 @@ impl SessionDatabase {
             )?;
 
             let mut session_iter = stmt.query_map([uuid], |row| {
-                let session = SavedSession {
+                Ok(SavedSession {
                     id: Some(row.get(0)?),
                     uuid: row.get(1)?,
                     name: row.get(2)?,
@@ -155,16 +439,7 @@ impl SessionDatabase {
                     original_size: row.get(5)?,
                     created_at: row.get(6)?,
                     updated_at: row.get(7)?,
-                };
-
-                // Decompress telemetry data if needed
-                if session.compression_type == "lz4" {
-                    if let Some(_telemetry_data) = session.session_data.get("telemetry_data") {
-                        // Placeholder for potential future decompression logic on load if needed
-                    }
-                }
-
-                Ok(session)
+                })
             })?;
 
             match session_iter.next() {
@@ -174,10 +449,237 @@ impl SessionDatabase {
         })
     }
 
+    /// Delete the session and everything stored with it in one transaction
     pub fn delete_session(&self, uuid: &str) -> SqlResult<bool> {
         self.with_connection(|conn| {
-            let affected = conn.execute("DELETE FROM saved_sessions WHERE uuid = ?1", [uuid])?;
-            Ok(affected > 0)
+            let tx = conn.unchecked_transaction()?;
+            let deleted = delete_session_rows(&tx, uuid)?;
+            tx.commit()?;
+            Ok(deleted)
+        })
+    }
+
+    /// Delete the sessions and everything stored with them in one transaction
+    pub fn delete_sessions(&self, uuids: &[String]) -> SqlResult<BulkSessionResult> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut result = BulkSessionResult::default();
+            for uuid in uuids {
+                if delete_session_rows(&tx, uuid)? {
+                    result.affected.push(uuid.clone());
+                } else {
+                    result.missing.push(uuid.clone());
+                }
+            }
+            tx.commit()?;
+            Ok(result)
+        })
+    }
+
+    /// Add `tag` to the sessions in one transaction (already tagged sessions are unchanged)
+    pub fn tag_sessions(&self, uuids: &[String], tag: &str) -> SqlResult<BulkSessionResult> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut result = BulkSessionResult::default();
+            for uuid in uuids {
+                if session_exists(&tx, uuid)? {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO session_tags (session_uuid, tag) VALUES (?1, ?2)",
+                        params![uuid, tag],
+                    )?;
+                    result.affected.push(uuid.clone());
+                } else {
+                    result.missing.push(uuid.clone());
+                }
+            }
+            tx.commit()?;
+            Ok(result)
+        })
+    }
+
+    pub fn get_session_tags(&self, uuid: &str) -> SqlResult<Vec<String>> {
+        self.with_connection(|conn| session_tags(conn, uuid))
+    }
+
+    /// Replace the session's tags; false when the session does not exist
+    pub fn set_session_tags(&self, uuid: &str, tags: &[String]) -> SqlResult<bool> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            if !session_exists(&tx, uuid)? {
+                return Ok(false);
+            }
+            tx.execute("DELETE FROM session_tags WHERE session_uuid = ?1", [uuid])?;
+            for tag in tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO session_tags (session_uuid, tag) VALUES (?1, ?2)",
+                    params![uuid, tag],
+                )?;
+            }
+            tx.commit()?;
+            Ok(true)
+        })
+    }
+
+    /// Sessions matching every term of `query` (as a prefix, in the name, prompts or model
+    /// names) that carry all of `tags`. Best matches come first; without a query, the most
+    /// recently updated.
+    pub fn search_sessions(&self, query: &str, tags: &[String]) -> SqlResult<Vec<SessionSearchResult>> {
+        self.with_connection(|conn| {
+            let match_query = fts_query(query);
+            let mut sql = String::from("SELECT s.uuid, s.name, s.created_at, s.original_size FROM saved_sessions s");
+            let mut values: Vec<&dyn rusqlite::ToSql> = Vec::new();
+            if let Some(match_query) = &match_query {
+                sql.push_str(" JOIN session_search f ON f.uuid = s.uuid WHERE session_search MATCH ?");
+                values.push(match_query);
+            } else {
+                sql.push_str(" WHERE 1 = 1");
+            }
+            let tag_count = tags.len() as i64;
+            if !tags.is_empty() {
+                let placeholders = vec!["?"; tags.len()].join(", ");
+                sql.push_str(&format!(
+                    " AND s.uuid IN (SELECT session_uuid FROM session_tags WHERE tag IN ({}) \
+                     GROUP BY session_uuid HAVING COUNT(*) = ?)",
+                    placeholders
+                ));
+                values.extend(tags.iter().map(|tag| tag as &dyn rusqlite::ToSql));
+                values.push(&tag_count);
+            }
+            sql.push_str(if match_query.is_some() { " ORDER BY bm25(session_search)" } else { " ORDER BY s.updated_at DESC" });
+
+            let rows: Vec<(String, String, i64, Option<i64>)> = conn.prepare(&sql)?
+                .query_map(values.as_slice(), |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+                .collect::<SqlResult<_>>()?;
+            rows.into_iter()
+                .map(|(uuid, name, created_at, original_size)| Ok(SessionSearchResult {
+                    tags: session_tags(conn, &uuid)?,
+                    uuid,
+                    name,
+                    created_at,
+                    original_size,
+                }))
+                .collect()
+        })
+    }
+
+    /// Read the sessions with their tags and telemetry samples from one consistent snapshot,
+    /// in the order given; also returns the uuids that were not found
+    pub fn export_sessions(&self, uuids: &[String]) -> SqlResult<(Vec<ExportedSession>, Vec<String>)> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut exported = Vec::new();
+            let mut missing = Vec::new();
+            {
+                let mut stmt = tx.prepare(
+                    "
+                    SELECT id, uuid, name, session_data, compression_type, original_size, created_at, updated_at
+                    FROM saved_sessions
+                    WHERE uuid = ?1
+                    ",
+                )?;
+                for uuid in uuids {
+                    match stmt.query_row([uuid], session_from_row).optional()? {
+                        Some(mut session) => {
+                            load_session_telemetry(&tx, &mut session)?;
+                            exported.push(ExportedSession { session, tags: session_tags(&tx, uuid)? });
+                        }
+                        None => missing.push(uuid.clone()),
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok((exported, missing))
+        })
+    }
+
+    /// Insert a session exported from another database, with its tags, moving its telemetry
+    /// into telemetry_samples. A new uuid is assigned when the original one is already present here.
+    pub fn import_session(&self, exported: ExportedSession) -> SqlResult<SavedSession> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut session = exported.session;
+            if session_exists(&tx, &session.uuid)? {
+                session.uuid = uuid::Uuid::new_v4().to_string();
+            }
+            session.updated_at = chrono::Utc::now().timestamp();
+            if let Some(original_size) = store_session_telemetry(&tx, &session.uuid, &mut session.session_data)? {
+                session.compression_type = "none".to_string();
+                session.original_size = Some(original_size);
+            }
+            tx.execute(
+                "
+                INSERT INTO saved_sessions (uuid, name, session_data, compression_type, original_size, created_at, updated_at,
+                                            app_version, schema_version, telemetry_schema_version)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ",
+                params![
+                    session.uuid,
+                    session.name,
+                    serde_json::to_string(&session.session_data).unwrap(),
+                    session.compression_type,
+                    session.original_size,
+                    session.created_at,
+                    session.updated_at,
+                    env!("CARGO_PKG_VERSION"),
+                    session_schema_version(&session.session_data),
+                    telemetry_schema_version(&session.session_data)
+                ],
+            )?;
+            session.id = Some(tx.last_insert_rowid());
+            index_session(&tx, &session.uuid, &session.name, &session.session_data)?;
+            for tag in &exported.tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO session_tags (session_uuid, tag) VALUES (?1, ?2)",
+                    params![session.uuid, tag],
+                )?;
+            }
+            tx.commit()?;
+            Ok(session)
+        })
+    }
+
+    /// A saved session's telemetry samples in recorded order; None when the session does not exist
+    pub fn session_telemetry(&self, uuid: &str) -> SqlResult<Option<Vec<serde_json::Value>>> {
+        self.with_connection(|conn| {
+            if !session_exists(conn, uuid)? {
+                return Ok(None);
+            }
+            load_samples(conn, uuid).map(Some)
+        })
+    }
+
+    /// Add samples to an unsaved recording; returns how many it now holds
+    pub fn append_recording_samples(&self, recording_id: &str, points: &[serde_json::Value]) -> SqlResult<usize> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let total = append_samples(&tx, &recording_key(recording_id), points)?;
+            tx.commit()?;
+            Ok(total)
+        })
+    }
+
+    pub fn recording_samples(&self, recording_id: &str) -> SqlResult<Vec<serde_json::Value>> {
+        self.with_connection(|conn| load_samples(conn, &recording_key(recording_id)))
+    }
+
+    /// Start a recording from a saved session's samples, so saving it again keeps them;
+    /// returns how many were copied
+    pub fn resume_recording(&self, recording_id: &str, uuid: &str) -> SqlResult<usize> {
+        self.with_connection(|conn| copy_samples(conn, uuid, &recording_key(recording_id)))
+    }
+
+    pub fn discard_recording(&self, recording_id: &str) -> SqlResult<usize> {
+        self.with_connection(|conn| delete_samples(conn, &recording_key(recording_id)))
+    }
+
+    /// Per-model telemetry aggregates of a saved session, without loading its samples;
+    /// None when the session does not exist
+    pub fn telemetry_stats(&self, uuid: &str) -> SqlResult<Option<Vec<ModelTelemetryStats>>> {
+        self.with_connection(|conn| {
+            if !session_exists(conn, uuid)? {
+                return Ok(None);
+            }
+            telemetry_stats(conn, uuid).map(Some)
         })
     }
 
@@ -203,4 +705,608 @@ impl SessionDatabase {
             session_iter.collect()
         })
     }
-}
\ No newline at end of file
+
+    /// Sessions created within the range (inclusive), oldest first
+    pub fn sessions_in_range(&self, range: HistoryRange) -> SqlResult<Vec<SavedSession>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "
+                SELECT id, uuid, name, session_data, compression_type, original_size, created_at, updated_at
+                FROM saved_sessions
+                WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at <= ?2)
+                ORDER BY created_at ASC
+                ",
+            )?;
+            let session_iter = stmt.query_map(params![range.from, range.to], session_from_row)?;
+            session_iter.collect()
+        })
+    }
+
+    // A setting that is missing or no longer parses reads as None
+    fn setting<T: DeserializeOwned>(&self, key: &str) -> SqlResult<Option<T>> {
+        self.with_connection(|conn| {
+            let value: Option<String> = conn.query_row(
+                "SELECT value FROM app_settings WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            ).optional()?;
+            Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+        })
+    }
+
+    fn set_setting<T: Serialize>(&self, key: &str, value: &T) -> SqlResult<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?1, ?2)",
+                params![key, serde_json::to_string(value).unwrap()],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn telemetry_retention_policy(&self) -> SqlResult<TelemetryRetentionPolicy> {
+        // An unreadable policy falls back to keeping everything rather than losing data
+        Ok(self.setting(TELEMETRY_POLICY_KEY)?.unwrap_or_default())
+    }
+
+    pub fn set_telemetry_retention_policy(&self, policy: &TelemetryRetentionPolicy) -> SqlResult<()> {
+        self.set_setting(TELEMETRY_POLICY_KEY, policy)
+    }
+
+    pub fn hardware_baseline(&self) -> SqlResult<Option<HardwareBaseline>> {
+        self.setting(HARDWARE_BASELINE_KEY)
+    }
+
+    pub fn set_hardware_baseline(&self, baseline: &HardwareBaseline) -> SqlResult<()> {
+        self.set_setting(HARDWARE_BASELINE_KEY, baseline)
+    }
+
+    pub fn idle_baseline(&self) -> SqlResult<Option<IdleBaseline>> {
+        self.setting(IDLE_BASELINE_KEY)
+    }
+
+    pub fn set_idle_baseline(&self, baseline: &IdleBaseline) -> SqlResult<()> {
+        self.set_setting(IDLE_BASELINE_KEY, baseline)
+    }
+
+    pub fn temperature_unit(&self) -> SqlResult<TemperatureUnit> {
+        Ok(self.setting(TEMPERATURE_UNIT_KEY)?.unwrap_or_default())
+    }
+
+    pub fn set_temperature_unit(&self, unit: TemperatureUnit) -> SqlResult<()> {
+        self.set_setting(TEMPERATURE_UNIT_KEY, &unit)
+    }
+
+    pub fn report_locale(&self) -> SqlResult<ReportLocale> {
+        Ok(self.setting(REPORT_LOCALE_KEY)?.unwrap_or_default())
+    }
+
+    pub fn set_report_locale(&self, locale: &ReportLocale) -> SqlResult<()> {
+        self.set_setting(REPORT_LOCALE_KEY, locale)
+    }
+
+    pub fn model_library_settings(&self) -> SqlResult<ModelLibrarySettings> {
+        Ok(self.setting(MODEL_LIBRARY_SETTINGS_KEY)?.unwrap_or_default())
+    }
+
+    pub fn set_model_library_settings(&self, settings: &ModelLibrarySettings) -> SqlResult<()> {
+        self.set_setting(MODEL_LIBRARY_SETTINGS_KEY, settings)
+    }
+
+    /// Library entries sorted by path
+    pub fn model_library(&self) -> SqlResult<Vec<ModelFile>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare("SELECT path, file_name, size_bytes, modified_at FROM model_library ORDER BY path")?;
+            let files = stmt.query_map([], |row| Ok(ModelFile {
+                path: row.get(0)?,
+                file_name: row.get(1)?,
+                size_bytes: row.get::<_, i64>(2)? as u64,
+                modified_at: row.get(3)?,
+            }))?;
+            files.collect()
+        })
+    }
+
+    /// Replace the library with a fresh scan and return what changed
+    pub fn replace_model_library(&self, files: &[ModelFile]) -> SqlResult<ModelLibraryChange> {
+        let change = diff_library(&self.model_library()?, files);
+        if change.is_empty() {
+            return Ok(change);
+        }
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM model_library", [])?;
+            for file in files {
+                tx.execute(
+                    "INSERT INTO model_library (path, file_name, size_bytes, modified_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![file.path, file.file_name, file.size_bytes as i64, file.modified_at],
+                )?;
+            }
+            tx.commit()
+        })?;
+        Ok(change)
+    }
+
+    pub fn save_benchmark_report(&self, report: &BenchmarkReport) -> SqlResult<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO benchmark_reports (uuid, name, report, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![report.uuid, report.name, serde_json::to_string(report).unwrap(), report.created_at],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Stored benchmark reports, newest first; rows that no longer parse are skipped
+    pub fn benchmark_reports(&self) -> SqlResult<Vec<BenchmarkReport>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare("SELECT report FROM benchmark_reports ORDER BY created_at DESC")?;
+            let reports = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .collect::<SqlResult<Vec<String>>>()?;
+            Ok(reports.iter().filter_map(|report| serde_json::from_str(report).ok()).collect())
+        })
+    }
+
+    pub fn create_experiment(&self, name: &str, description: Option<&str>) -> SqlResult<Experiment> {
+        let experiment = Experiment {
+            uuid: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            created_at: chrono::Utc::now().timestamp(),
+            session_count: 0,
+        };
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO experiments (uuid, name, description, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![experiment.uuid, experiment.name, experiment.description, experiment.created_at],
+            )?;
+            Ok(experiment)
+        })
+    }
+
+    pub fn list_experiments(&self) -> SqlResult<Vec<Experiment>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "
+                SELECT e.uuid, e.name, e.description, e.created_at, COUNT(s.session_uuid)
+                FROM experiments e
+                LEFT JOIN experiment_sessions s ON s.experiment_uuid = e.uuid
+                GROUP BY e.uuid
+                ORDER BY e.created_at DESC
+                ",
+            )?;
+            let experiment_iter = stmt.query_map([], experiment_from_row)?;
+            experiment_iter.collect()
+        })
+    }
+
+    /// Add sessions to an experiment in one transaction; None if the experiment does not exist
+    pub fn assign_sessions_to_experiment(&self, experiment_uuid: &str, uuids: &[String]) -> SqlResult<Option<BulkSessionResult>> {
+        self.with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let exists = tx.query_row("SELECT 1 FROM experiments WHERE uuid = ?1", [experiment_uuid], |_| Ok(()))
+                .optional()?
+                .is_some();
+            if !exists {
+                return Ok(None);
+            }
+            let mut result = BulkSessionResult::default();
+            for uuid in uuids {
+                if session_exists(&tx, uuid)? {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO experiment_sessions (experiment_uuid, session_uuid) VALUES (?1, ?2)",
+                        params![experiment_uuid, uuid],
+                    )?;
+                    result.affected.push(uuid.clone());
+                } else {
+                    result.missing.push(uuid.clone());
+                }
+            }
+            tx.commit()?;
+            Ok(Some(result))
+        })
+    }
+
+    /// Per-model aggregates over the experiment's sessions; None if the experiment does not exist
+    pub fn experiment_stats(&self, experiment_uuid: &str) -> SqlResult<Option<ExperimentStats>> {
+        self.with_connection(|conn| {
+            let Some(experiment) = conn.query_row(
+                "
+                SELECT e.uuid, e.name, e.description, e.created_at, COUNT(s.session_uuid)
+                FROM experiments e
+                LEFT JOIN experiment_sessions s ON s.experiment_uuid = e.uuid
+                WHERE e.uuid = ?1
+                GROUP BY e.uuid
+                ",
+                [experiment_uuid],
+                experiment_from_row,
+            ).optional()? else {
+                return Ok(None);
+            };
+
+            let mut stmt = conn.prepare(
+                "
+                SELECT ss.session_data
+                FROM experiment_sessions es
+                JOIN saved_sessions ss ON ss.uuid = es.session_uuid
+                WHERE es.experiment_uuid = ?1
+                ",
+            )?;
+            let sessions: Vec<serde_json::Value> = stmt
+                .query_map([experiment_uuid], |row| row.get::<_, String>(0))?
+                .map(|data| data.map(|data| serde_json::from_str(&data).unwrap_or(serde_json::Value::Null)))
+                .collect::<SqlResult<_>>()?;
+
+            Ok(Some(ExperimentStats { experiment, models: aggregate_model_stats(&sessions) }))
+        })
+    }
+
+    /// Versions of every saved session; sessions saved before versions were recorded
+    /// are versioned from their data
+    pub fn session_versions(&self) -> SqlResult<Vec<SessionVersionInfo>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "
+                SELECT uuid, name, app_version, schema_version, telemetry_schema_version, session_data
+                FROM saved_sessions
+                ORDER BY updated_at DESC
+                ",
+            )?;
+
+            let version_iter = stmt.query_map([], |row| {
+                let schema_version: Option<i64> = row.get(3)?;
+                let telemetry_version: Option<i64> = row.get(4)?;
+                let (schema_version, telemetry_schema_version) = match (schema_version, telemetry_version) {
+                    (Some(schema), Some(telemetry)) => (schema, telemetry),
+                    _ => {
+                        let data: serde_json::Value = serde_json::from_str(&row.get::<_, String>(5)?)
+                            .unwrap_or(serde_json::Value::Null);
+                        (session_schema_version(&data), telemetry_schema_version(&data))
+                    }
+                };
+                Ok(SessionVersionInfo {
+                    uuid: row.get(0)?,
+                    name: row.get(1)?,
+                    app_version: row.get(2)?,
+                    schema_version,
+                    telemetry_schema_version,
+                })
+            })?;
+
+            version_iter.collect()
+        })
+    }
+
+    pub fn compatibility_report(&self) -> SqlResult<CompatibilityReport> {
+        let sessions = self.session_versions()?;
+        Ok(CompatibilityReport {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            previous_app_version: self.previous_app_version.clone(),
+            session_schema_version: SESSION_SCHEMA_VERSION,
+            telemetry_schema_version: TELEMETRY_SCHEMA_VERSION,
+            total_sessions: sessions.len(),
+            needs_migration: sessions.iter().filter(|s| s.needs_migration() && !s.is_newer()).cloned().collect(),
+            newer_than_app: sessions.iter().filter(|s| s.is_newer()).cloned().collect(),
+        })
+    }
+
+    /// Upgrade every session that needs it in place; per-session failures are reported, not fatal
+    pub fn migrate_sessions(&self) -> SqlResult<MigrationSummary> {
+        let pending: Vec<SessionVersionInfo> = self.session_versions()?
+            .into_iter()
+            .filter(|s| s.needs_migration() && !s.is_newer())
+            .collect();
+
+        self.with_connection(|conn| {
+            let mut summary = MigrationSummary::default();
+            for info in pending {
+                let tx = conn.unchecked_transaction()?;
+                let data: String = tx.query_row(
+                    "SELECT session_data FROM saved_sessions WHERE uuid = ?1", [&info.uuid], |row| row.get(0)
+                )?;
+                let migrated = serde_json::from_str::<serde_json::Value>(&data)
+                    .map_err(|e| format!("Invalid session JSON: {}", e))
+                    .and_then(|mut data| {
+                        upgrade_session_schema(&mut data)?;
+                        let telemetry = upgrade_telemetry(&mut data)?;
+                        Ok((data, telemetry))
+                    });
+
+                match migrated {
+                    Ok((data, telemetry)) => {
+                        let original_size = match telemetry {
+                            Some((points, original_size)) => {
+                                insert_samples(&tx, &info.uuid, &points)?;
+                                Some(original_size)
+                            }
+                            None => None,
+                        };
+                        // updated_at is left alone so the session list order does not change
+                        tx.execute(
+                            "
+                            UPDATE saved_sessions
+                            SET session_data = ?1,
+                                compression_type = CASE WHEN ?2 IS NULL THEN compression_type ELSE 'none' END,
+                                original_size = COALESCE(?2, original_size),
+                                schema_version = ?3,
+                                telemetry_schema_version = ?4
+                            WHERE uuid = ?5
+                            ",
+                            params![
+                                serde_json::to_string(&data).unwrap(),
+                                original_size,
+                                session_schema_version(&data),
+                                telemetry_schema_version(&data),
+                                info.uuid
+                            ],
+                        )?;
+                        index_session(&tx, &info.uuid, &info.name, &data)?;
+                        tx.commit()?;
+                        summary.migrated.push(info.uuid);
+                    }
+                    Err(error) => summary.failed.push(MigrationFailure { uuid: info.uuid, error }),
+                }
+            }
+            Ok(summary)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_db(name: &str) -> (SessionDatabase, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("a2o-db-{}-{}.sqlite", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        (SessionDatabase::new(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn test_legacy_rows_are_reported_and_migrated_in_place() {
+        let (db, path) = temp_db("migrate");
+        assert_eq!(db.previous_app_version(), None);
+
+        // A row written before version columns existed
+        db.with_connection(|conn| conn.execute(
+            "INSERT INTO saved_sessions (uuid, name, session_data, created_at, updated_at) VALUES ('old', 'Old', ?1, 1, 1)",
+            [json!({ "chat_history": [], "telemetry_data": [{ "timestamp": 1 }] }).to_string()],
+        )).unwrap();
+        db.save_session(CreateSessionRequest {
+            name: "New".to_string(),
+            session_data: json!({ "schema_version": SESSION_SCHEMA_VERSION, "chat_history": [] }),
+        }).unwrap();
+
+        let report = db.compatibility_report().unwrap();
+        assert_eq!(report.total_sessions, 2);
+        assert_eq!(report.needs_migration.len(), 1);
+        assert_eq!(report.needs_migration[0].uuid, "old");
+        assert_eq!(report.needs_migration[0].telemetry_schema_version, 0);
+
+        let summary = db.migrate_sessions().unwrap();
+        assert_eq!(summary.migrated, vec!["old".to_string()]);
+        assert!(summary.failed.is_empty());
+        assert!(db.compatibility_report().unwrap().needs_migration.is_empty());
+
+        let migrated = db.load_session("old").unwrap().unwrap();
+        assert_eq!(migrated.compression_type, "none");
+        assert_eq!(migrated.session_data["telemetry_data"], stored_telemetry_marker(1));
+        assert_eq!(db.session_telemetry("old").unwrap(), Some(vec![json!({ "timestamp": 1 })]));
+        assert_eq!(db.telemetry_stats("old").unwrap().unwrap()[0].samples, 1);
+        assert!(db.telemetry_stats("missing").unwrap().is_none());
+
+        // Reopening remembers the version that last opened the database
+        drop(db);
+        let reopened = SessionDatabase::new(&path).unwrap();
+        assert_eq!(reopened.previous_app_version(), Some(env!("CARGO_PKG_VERSION")));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bulk_tag_export_and_delete() {
+        let (db, path) = temp_db("bulk");
+        let uuids: Vec<String> = (0..3)
+            .map(|i| db.save_session(CreateSessionRequest {
+                name: format!("Sweep run {}", i),
+                session_data: json!({ "chat_history": [] }),
+            }).unwrap().uuid)
+            .collect();
+        let sweep = vec![uuids[0].clone(), uuids[1].clone(), "missing".to_string()];
+
+        let tagged = db.tag_sessions(&sweep, "sweep-1").unwrap();
+        assert_eq!(tagged.affected.len(), 2);
+        assert_eq!(tagged.missing, vec!["missing".to_string()]);
+        db.tag_sessions(&sweep, "sweep-1").unwrap();
+        db.tag_sessions(&uuids[..1], "baseline").unwrap();
+        assert_eq!(db.get_session_tags(&uuids[0]).unwrap(), vec!["baseline", "sweep-1"]);
+
+        let (exported, missing) = db.export_sessions(&sweep).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[1].session.uuid, uuids[1]);
+        assert_eq!(exported[1].tags, vec!["sweep-1"]);
+        assert_eq!(missing, vec!["missing".to_string()]);
+        let json = serde_json::to_value(&exported[0]).unwrap();
+        assert_eq!(json["name"], "Sweep run 0");
+        assert_eq!(json["tags"], json!(["baseline", "sweep-1"]));
+
+        let deleted = db.delete_sessions(&sweep).unwrap();
+        assert_eq!(deleted.affected, vec![uuids[0].clone(), uuids[1].clone()]);
+        assert_eq!(deleted.missing, vec!["missing".to_string()]);
+        assert_eq!(db.get_session_list().unwrap().len(), 1);
+        assert!(db.get_session_tags(&uuids[0]).unwrap().is_empty());
+
+        drop(db);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_search_sessions_by_text_and_tags() {
+        let (db, path) = temp_db("search");
+        let session = |name: &str, model: &str, prompt: &str| db.save_session(CreateSessionRequest {
+            name: name.to_string(),
+            session_data: json!({
+                "chat_history": [
+                    { "role": "user", "content": prompt },
+                    { "role": "assistant", "content": "ok", "model": "A" },
+                ],
+                "configuration": { "model_a": { "model_path": model }, "system_prompt": "Be brief." },
+            }),
+        }).unwrap().uuid;
+        let qwen = session("Quant ladder", "/models/qwen2.5-7b-instruct-q4_k_m.gguf", "Summarize the French revolution");
+        let llama = session("Thermal soak", "/models/llama-3.2-3b-q8_0.gguf", "Write a haiku about winter");
+
+        let names = |query: &str, tags: &[&str]| -> Vec<String> {
+            let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+            db.search_sessions(query, &tags).unwrap().into_iter().map(|hit| hit.name).collect()
+        };
+        assert_eq!(names("qwen", &[]), vec!["Quant ladder"]);
+        assert_eq!(names("revol", &[]), vec!["Quant ladder"]);
+        assert_eq!(names("thermal HAIKU", &[]), vec!["Thermal soak"]);
+        assert_eq!(names("q8_0", &[]), vec!["Thermal soak"]);
+        assert_eq!(names("brief", &[]).len(), 2);
+        assert!(names("qwen haiku", &[]).is_empty());
+        // FTS5 syntax in the query is matched literally
+        assert!(names("\"NEAR(", &[]).is_empty());
+
+        assert!(db.set_session_tags(&qwen, &["m3-max".to_string(), "sweep-1".to_string()]).unwrap());
+        assert!(db.set_session_tags(&llama, &["m3-max".to_string()]).unwrap());
+        assert!(!db.set_session_tags("missing", &[]).unwrap());
+        // Without a query the newest come first (explicit times: both were saved within a millisecond)
+        let touch = |uuid: &str, updated_at: i64| db.with_connection(|conn| conn.execute(
+            "UPDATE saved_sessions SET updated_at = ?2 WHERE uuid = ?1", params![uuid, updated_at]
+        )).unwrap();
+        touch(&qwen, 1_000);
+        touch(&llama, 2_000);
+        assert_eq!(names("", &["m3-max"]), vec!["Thermal soak", "Quant ladder"]);
+        touch(&qwen, 3_000);
+        assert_eq!(names("", &["m3-max"]), vec!["Quant ladder", "Thermal soak"]);
+        assert_eq!(names("", &["m3-max", "sweep-1"]), vec!["Quant ladder"]);
+        assert!(names("haiku", &["sweep-1"]).is_empty());
+        assert_eq!(db.search_sessions("ladder", &[]).unwrap()[0].tags, vec!["m3-max", "sweep-1"]);
+
+        // Tags are replaced, and deleted sessions leave the index
+        assert!(db.set_session_tags(&qwen, &["baseline".to_string()]).unwrap());
+        assert_eq!(db.get_session_tags(&qwen).unwrap(), vec!["baseline"]);
+        db.delete_session(&qwen).unwrap();
+        assert!(names("qwen", &[]).is_empty());
+
+        // Sessions saved before the index existed are indexed when the database is opened
+        db.with_connection(|conn| conn.execute("DELETE FROM session_search", [])).unwrap();
+        drop(db);
+        let reopened = SessionDatabase::new(&path).unwrap();
+        let hits = reopened.search_sessions("winter", &[]).unwrap();
+        assert_eq!(hits.iter().map(|hit| hit.uuid.clone()).collect::<Vec<_>>(), vec![llama]);
+        drop(reopened);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_experiment_groups_sessions_and_aggregates_stats() {
+        let (db, path) = temp_db("experiments");
+        let uuids: Vec<String> = [40.0, 44.0, 10.0].iter()
+            .map(|tps| db.save_session(CreateSessionRequest {
+                name: format!("{} tok/s", tps),
+                session_data: json!({ "chat_history": [], "summary_stats": { "A": { "avg_tps": tps } } }),
+            }).unwrap().uuid)
+            .collect();
+
+        let experiment = db.create_experiment("Q4 vs Q8", Some("Quant ladder")).unwrap();
+        assert!(db.assign_sessions_to_experiment("missing", &uuids).unwrap().is_none());
+        let assigned = db.assign_sessions_to_experiment(&experiment.uuid, &[uuids[0].clone(), uuids[1].clone(), "gone".to_string()])
+            .unwrap().unwrap();
+        assert_eq!(assigned.affected.len(), 2);
+        assert_eq!(assigned.missing, vec!["gone".to_string()]);
+
+        let listed = db.list_experiments().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].session_count, 2);
+
+        let stats = db.experiment_stats(&experiment.uuid).unwrap().unwrap();
+        assert_eq!(stats.experiment.description.as_deref(), Some("Quant ladder"));
+        assert_eq!(stats.models[0].metrics["avg_tps"].mean, 42.0);
+
+        // Deleting a session drops it from the experiment
+        db.delete_sessions(&uuids[..1]).unwrap();
+        let stats = db.experiment_stats(&experiment.uuid).unwrap().unwrap();
+        assert_eq!(stats.experiment.session_count, 1);
+        assert_eq!(stats.models[0].metrics["avg_tps"].mean, 44.0);
+        assert!(db.experiment_stats("missing").unwrap().is_none());
+
+        drop(db);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_session_file_round_trip_into_another_database() {
+        let (source, source_path) = temp_db("file-source");
+        let (target, target_path) = temp_db("file-target");
+        let saved = source.save_session(CreateSessionRequest {
+            name: "Laptop run".to_string(),
+            session_data: json!({ "chat_history": [], "telemetry_data": [{ "timestamp": 1 }, { "timestamp": 2 }] }),
+        }).unwrap();
+        source.tag_sessions(std::slice::from_ref(&saved.uuid), "m3-max").unwrap();
+
+        let (mut exported, _) = source.export_sessions(std::slice::from_ref(&saved.uuid)).unwrap();
+        let file = SessionFile {
+            schema: SESSION_FILE_SCHEMA.to_string(),
+            app_version: "0.0.0".to_string(),
+            exported_at: 0,
+            session: exported.pop().unwrap(),
+        };
+        let json = serde_json::to_string(&file).unwrap();
+        let file: SessionFile = serde_json::from_str(&json).unwrap();
+
+        let imported = target.import_session(file.session.clone()).unwrap();
+        assert_eq!(imported.uuid, saved.uuid);
+        assert_eq!(target.get_session_tags(&saved.uuid).unwrap(), vec!["m3-max"]);
+        let loaded = target.load_session(&saved.uuid).unwrap().unwrap();
+        assert_eq!(loaded.name, "Laptop run");
+        assert_eq!(loaded.compression_type, "none");
+        assert_eq!(target.session_telemetry(&saved.uuid).unwrap(),
+                   Some(vec![json!({ "timestamp": 1 }), json!({ "timestamp": 2 })]));
+        assert_eq!(target.telemetry_stats(&saved.uuid).unwrap().unwrap()[0].samples, 2);
+        // The stored session row only holds the marker
+        let stored: String = target.with_connection(|conn| conn.query_row(
+            "SELECT session_data FROM saved_sessions WHERE uuid = ?1", [&saved.uuid], |row| row.get(0)
+        )).unwrap();
+        assert!(!stored.contains("timestamp"));
+
+        // Importing the same file again keeps both copies
+        let again = target.import_session(file.session).unwrap();
+        assert_ne!(again.uuid, saved.uuid);
+        assert_eq!(target.get_session_list().unwrap().len(), 2);
+
+        drop(source);
+        drop(target);
+        std::fs::remove_file(&source_path).unwrap();
+        std::fs::remove_file(&target_path).unwrap();
+    }
+
+    #[test]
+    fn test_saving_a_recording_copies_its_flushed_samples() {
+        let (db, path) = temp_db("recording");
+        db.append_recording_samples("live", &[json!({ "timestamp": 1 })]).unwrap();
+        assert_eq!(db.append_recording_samples("live", &[json!({ "timestamp": 2 })]).unwrap(), 2);
+
+        let saved = db.save_session(CreateSessionRequest {
+            name: "Recorded".to_string(),
+            session_data: json!({ "chat_history": [], "telemetry_data": { "recording": "live", "samples": 2 } }),
+        }).unwrap();
+        assert_eq!(saved.session_data["telemetry_data"], stored_telemetry_marker(2));
+        let samples = Some(vec![json!({ "timestamp": 1 }), json!({ "timestamp": 2 })]);
+        assert_eq!(db.session_telemetry(&saved.uuid).unwrap(), samples);
+        assert!(db.session_telemetry("missing").unwrap().is_none());
+
+        // A loaded session resumes into a fresh recording; unsaved recordings are dropped on reopen
+        assert_eq!(db.resume_recording("next", &saved.uuid).unwrap(), 2);
+        assert_eq!(db.discard_recording("live").unwrap(), 2);
+        drop(db);
+        let reopened = SessionDatabase::new(&path).unwrap();
+        assert!(reopened.recording_samples("next").unwrap().is_empty());
+        assert_eq!(reopened.session_telemetry(&saved.uuid).unwrap(), samples);
+
+        drop(reopened);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+// Free disk space checks, so large writes (session saves, exports, output streams) are
+// refused up front instead of failing partway with an I/O error
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{Emitter, Runtime};
+
+use crate::error::A2oError;
+
+// Kept free beyond the expected size; SQLite's WAL and the OS need room too
+pub const DISK_HEADROOM_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Payload of `disk_space_warning`, emitted when a write is refused
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiskSpaceWarning {
+    pub operation: String,   // e.g. "save_session"
+    pub path: String,
+    pub required_bytes: u64, // Expected size plus headroom
+    pub available_bytes: u64,
+}
+
+/// Bytes available to this user on the volume holding `path`. The path does not need to
+/// exist yet; its nearest existing ancestor is measured.
+pub fn available_space(path: &Path) -> Result<u64, String> {
+    let existing = path.ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .unwrap_or_else(|| Path::new("."));
+    let c_path = CString::new(existing.as_os_str().as_bytes())
+        .map_err(|_| format!("Invalid path {}", existing.display()))?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return Err(format!("Failed to query free space of {}: {}", existing.display(), std::io::Error::last_os_error()));
+    }
+    // fsblkcnt_t is 32-bit on macOS and 64-bit elsewhere
+    #[allow(clippy::useless_conversion)]
+    let available = u64::from(stats.f_bavail) * u64::from(stats.f_frsize);
+    Ok(available)
+}
+
+// The warning for a write of `expected_bytes`, or None when it fits with headroom
+fn shortfall(operation: &str, path: &Path, expected_bytes: u64, available_bytes: u64) -> Option<DiskSpaceWarning> {
+    let required_bytes = expected_bytes.saturating_add(DISK_HEADROOM_BYTES);
+    (available_bytes < required_bytes).then(|| DiskSpaceWarning {
+        operation: operation.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        required_bytes,
+        available_bytes,
+    })
+}
+
+/// Check that writing `expected_bytes` under `path` leaves the headroom free. A volume
+/// whose free space cannot be read is let through; the write reports its own error.
+pub fn check_free_space(operation: &str, path: &Path, expected_bytes: u64) -> Option<DiskSpaceWarning> {
+    match available_space(path) {
+        Ok(available_bytes) => shortfall(operation, path, expected_bytes, available_bytes),
+        Err(e) => {
+            println!("⚠️ DISK SPACE: {}", e);
+            None
+        }
+    }
+}
+
+/// `check_free_space`, emitting `disk_space_warning` and failing when the write would not fit
+pub fn ensure_free_space<R: Runtime>(
+    emitter: &impl Emitter<R>,
+    operation: &str,
+    path: &Path,
+    expected_bytes: u64,
+) -> Result<(), A2oError> {
+    let Some(warning) = check_free_space(operation, path, expected_bytes) else {
+        return Ok(());
+    };
+    println!("💾 DISK SPACE: Refusing {} at {} ({} bytes needed, {} available)",
+             warning.operation, warning.path, warning.required_bytes, warning.available_bytes);
+    let _ = emitter.emit("disk_space_warning", &warning);
+    Err(A2oError::from(warning))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall_includes_headroom() {
+        let path = Path::new("/data/sessions.sqlite");
+        assert_eq!(shortfall("save_session", path, 100, DISK_HEADROOM_BYTES + 100), None);
+
+        let warning = shortfall("save_session", path, 100, DISK_HEADROOM_BYTES).unwrap();
+        assert_eq!(warning.required_bytes, DISK_HEADROOM_BYTES + 100);
+        assert_eq!(warning.available_bytes, DISK_HEADROOM_BYTES);
+        assert_eq!(warning.path, "/data/sessions.sqlite");
+
+        // Not-yet-created files are measured on their parent's volume
+        let missing = std::env::temp_dir().join("a2o-no-such-dir/session.a2o");
+        assert!(available_space(&missing).unwrap() > 0);
+    }
+}
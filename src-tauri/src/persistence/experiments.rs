@@ -0,0 +1,116 @@
+// Experiments: named groups of related sessions (one sweep, one quant ladder) and the
+// aggregate statistics of each model across a group's sessions
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+// summary_stats fields aggregated per model (as saved by the frontend)
+pub const AGGREGATED_METRICS: [&str; 6] = [
+    "avg_tps", "ttft_ms", "prefill_tps", "energy_per_token_wh", "total_energy_wh", "output_tokens",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Experiment {
+    pub uuid: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: i64,
+    pub session_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSummary {
+    pub count: usize,      // Sessions that recorded the metric
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl MetricSummary {
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        Some(Self {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperimentModelStats {
+    pub model: String,                              // "A" / "B"
+    pub sessions: usize,                            // Sessions with a summary for this model
+    pub metrics: BTreeMap<String, MetricSummary>,   // Keyed by summary_stats field
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperimentStats {
+    pub experiment: Experiment,
+    pub models: Vec<ExperimentModelStats>,
+}
+
+/// Aggregate each model's summary_stats across sessions. Sessions without a summary for
+/// a model, and metrics a session did not record, are left out rather than counted as 0.
+pub fn aggregate_model_stats(sessions: &[Value]) -> Vec<ExperimentModelStats> {
+    let mut per_model: BTreeMap<String, (usize, BTreeMap<&str, Vec<f64>>)> = BTreeMap::new();
+    for session in sessions {
+        let Some(summary_stats) = session.get("summary_stats").and_then(Value::as_object) else {
+            continue;
+        };
+        for (model, stats) in summary_stats {
+            let Some(stats) = stats.as_object() else {
+                continue;
+            };
+            let (count, values) = per_model.entry(model.clone()).or_default();
+            *count += 1;
+            for metric in AGGREGATED_METRICS {
+                if let Some(value) = stats.get(metric).and_then(Value::as_f64) {
+                    values.entry(metric).or_default().push(value);
+                }
+            }
+        }
+    }
+
+    per_model.into_iter()
+        .map(|(model, (sessions, values))| ExperimentModelStats {
+            model,
+            sessions,
+            metrics: values.into_iter()
+                .filter_map(|(metric, values)| MetricSummary::from_values(&values).map(|summary| (metric.to_string(), summary)))
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stats_aggregate_per_model_and_skip_missing_metrics() {
+        let sessions = vec![
+            json!({ "summary_stats": { "A": { "avg_tps": 40.0, "ttft_ms": 120 }, "B": { "avg_tps": 20.0 } } }),
+            json!({ "summary_stats": { "A": { "avg_tps": 44.0, "ttft_ms": 100, "energy_per_token_wh": null } } }),
+            json!({ "chat_history": [] }),
+        ];
+        let stats = aggregate_model_stats(&sessions);
+        assert_eq!(stats.len(), 2);
+
+        let a = &stats[0];
+        assert_eq!((a.model.as_str(), a.sessions), ("A", 2));
+        assert_eq!(a.metrics["avg_tps"], MetricSummary { count: 2, mean: 42.0, min: 40.0, max: 44.0 });
+        assert_eq!(a.metrics["ttft_ms"].mean, 110.0);
+        assert!(!a.metrics.contains_key("energy_per_token_wh"));
+
+        assert_eq!((stats[1].model.as_str(), stats[1].sessions), ("B", 1));
+        assert_eq!(stats[1].metrics["avg_tps"].count, 1);
+        assert!(MetricSummary::from_values(&[]).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+// Metric history: one summary metric followed across saved sessions over time,
+// e.g. energy per token of one model file, to spot setups that got slower
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::persistence::experiments::AGGREGATED_METRICS;
+use crate::persistence::models::SavedSession;
+use crate::telemetry::hardware_baseline::{normalize_metric, HardwareBaseline};
+
+/// Inclusive bounds on session creation time (Unix seconds); either side may be open
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HistoryRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricHistoryPoint {
+    pub session_uuid: String,
+    pub session_name: String,
+    pub created_at: i64,
+    pub model: String,               // "A" / "B"
+    pub model_path: Option<String>,
+    pub value: f64,
+    pub normalized_value: Option<f64>, // Relative to the recording machine's hardware baseline, if it had one
+}
+
+pub fn validate_history_metric(metric: &str) -> Result<(), String> {
+    if AGGREGATED_METRICS.contains(&metric) {
+        Ok(())
+    } else {
+        Err(format!("Unknown metric '{}'; expected one of: {}", metric, AGGREGATED_METRICS.join(", ")))
+    }
+}
+
+fn configured_model_path(session_data: &Value, model: &str) -> Option<String> {
+    let key = match model {
+        "A" => "model_a",
+        "B" => "model_b",
+        _ => return None,
+    };
+    session_data.pointer(&format!("/configuration/{}/model_path", key))?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// The metric's value for every model in every session, oldest first. `model_filter`
+/// matches (case-insensitively) anywhere in the model path; models without a recorded
+/// value are skipped.
+pub fn metric_history(sessions: &[SavedSession], metric: &str, model_filter: Option<&str>) -> Vec<MetricHistoryPoint> {
+    let filter = model_filter.map(str::trim).filter(|f| !f.is_empty()).map(str::to_lowercase);
+    let mut points: Vec<MetricHistoryPoint> = sessions.iter()
+        .flat_map(|session| {
+            let baseline: Option<HardwareBaseline> = session.session_data.get("hardware_baseline")
+                .and_then(|baseline| serde_json::from_value(baseline.clone()).ok());
+            let filter = &filter;
+            let summary_stats = session.session_data.get("summary_stats").and_then(Value::as_object);
+            summary_stats.into_iter().flatten().filter_map(move |(model, stats)| {
+                let value = stats.get(metric).and_then(Value::as_f64)?;
+                let model_path = configured_model_path(&session.session_data, model);
+                if let Some(filter) = filter {
+                    if !model_path.as_deref().is_some_and(|path| path.to_lowercase().contains(filter)) {
+                        return None;
+                    }
+                }
+                Some(MetricHistoryPoint {
+                    session_uuid: session.uuid.clone(),
+                    session_name: session.name.clone(),
+                    created_at: session.created_at,
+                    model: model.clone(),
+                    model_path,
+                    value,
+                    normalized_value: baseline.as_ref().and_then(|baseline| normalize_metric(metric, value, baseline)),
+                })
+            })
+        })
+        .collect();
+    points.sort_by_key(|point| point.created_at);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn session(created_at: i64, a_path: &str, a_value: Value, b_value: Value) -> SavedSession {
+        let mut session = SavedSession::new(format!("run {}", created_at), json!({
+            "configuration": { "model_a": { "model_path": a_path }, "model_b": { "model_path": "/m/qwen-q8.gguf" } },
+            "summary_stats": { "A": { "energy_per_token_wh": a_value }, "B": { "energy_per_token_wh": b_value } },
+        }));
+        session.created_at = created_at;
+        session
+    }
+
+    #[test]
+    fn test_history_is_ordered_filtered_and_skips_missing_values() {
+        let sessions = vec![
+            session(300, "/m/Llama-Q4.gguf", json!(0.012), json!(0.02)),
+            session(100, "/m/llama-q4.gguf", json!(0.010), json!(null)),
+            session(200, "/m/mistral.gguf", json!(0.030), json!(0.021)),
+        ];
+
+        let all = metric_history(&sessions, "energy_per_token_wh", None);
+        assert_eq!(all.len(), 5);
+        assert!(all.windows(2).all(|w| w[0].created_at <= w[1].created_at));
+
+        let llama = metric_history(&sessions, "energy_per_token_wh", Some("LLAMA-q4"));
+        assert_eq!(llama.iter().map(|p| (p.created_at, p.value)).collect::<Vec<_>>(), vec![(100, 0.010), (300, 0.012)]);
+        assert!(llama.iter().all(|p| p.model == "A"));
+
+        assert!(validate_history_metric("energy_per_token_wh").is_ok());
+        assert!(validate_history_metric("vibes").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+// Session/telemetry schema versions and in-place upgrades of records saved by older app versions
+//
+// Session schema:
+//   1 - chat_history, configuration, telemetry_data, summary_stats (sessions without
+//       a schema_version are treated as 1)
+//   2 - always carries model_fingerprints and run_manifest (empty/null when not recorded)
+// Telemetry schema:
+//   0 - telemetry_data stored as a raw JSON array
+//   1 - compressed envelope {compressed, original_length, data} from compress_telemetry_data
+//   2 - samples stored as rows of telemetry_samples; telemetry_data is a {store, samples} marker
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::persistence::compression::decompress_telemetry_data;
+use crate::persistence::telemetry_store::{is_stored_telemetry_marker, stored_telemetry_marker};
+use crate::persistence::run_manifest::SESSION_MANIFEST_KEY;
+
+pub const SESSION_SCHEMA_VERSION: i64 = 2;
+pub const TELEMETRY_SCHEMA_VERSION: i64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionVersionInfo {
+    pub uuid: String,
+    pub name: String,
+    pub app_version: Option<String>,   // None for sessions saved before versions were recorded
+    pub schema_version: i64,
+    pub telemetry_schema_version: i64,
+}
+
+impl SessionVersionInfo {
+    pub fn needs_migration(&self) -> bool {
+        self.schema_version < SESSION_SCHEMA_VERSION || self.telemetry_schema_version < TELEMETRY_SCHEMA_VERSION
+    }
+
+    /// Saved by a newer app; this version cannot read or downgrade it safely
+    pub fn is_newer(&self) -> bool {
+        self.schema_version > SESSION_SCHEMA_VERSION || self.telemetry_schema_version > TELEMETRY_SCHEMA_VERSION
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityReport {
+    pub app_version: String,
+    pub previous_app_version: Option<String>,  // App version that last opened the database
+    pub session_schema_version: i64,
+    pub telemetry_schema_version: i64,
+    pub total_sessions: usize,
+    pub needs_migration: Vec<SessionVersionInfo>,
+    pub newer_than_app: Vec<SessionVersionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationFailure {
+    pub uuid: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationSummary {
+    pub migrated: Vec<String>,
+    pub failed: Vec<MigrationFailure>,
+}
+
+pub fn session_schema_version(session_data: &Value) -> i64 {
+    session_data.get("schema_version").and_then(Value::as_i64).unwrap_or(1)
+}
+
+pub fn telemetry_schema_version(session_data: &Value) -> i64 {
+    match session_data.get("telemetry_data") {
+        None => TELEMETRY_SCHEMA_VERSION,
+        Some(Value::Array(_)) => 0,
+        Some(telemetry) if is_stored_telemetry_marker(telemetry) => TELEMETRY_SCHEMA_VERSION,
+        Some(_) => 1,
+    }
+}
+
+/// Bring the session-level fields up to SESSION_SCHEMA_VERSION (idempotent).
+/// Newer sessions are left untouched.
+pub fn upgrade_session_schema(session_data: &mut Value) -> Result<(), String> {
+    if session_schema_version(session_data) > SESSION_SCHEMA_VERSION {
+        return Err(format!(
+            "Session schema v{} is newer than this app supports (v{})",
+            session_schema_version(session_data), SESSION_SCHEMA_VERSION
+        ));
+    }
+    let obj = session_data.as_object_mut().ok_or("Session data must be an object")?;
+    // 1 -> 2: provenance fields are always present
+    obj.entry("model_fingerprints").or_insert_with(|| Value::Object(Default::default()));
+    obj.entry(SESSION_MANIFEST_KEY).or_insert(Value::Null);
+    obj.insert("schema_version".to_string(), Value::from(SESSION_SCHEMA_VERSION));
+    Ok(())
+}
+
+/// Take the samples out of raw or compressed telemetry, leaving the telemetry_samples marker
+/// in their place. Returns the samples and their JSON size for the caller to write to the
+/// table, None when telemetry was already current.
+pub fn upgrade_telemetry(session_data: &mut Value) -> Result<Option<(Vec<Value>, i64)>, String> {
+    let points = match session_data.get("telemetry_data") {
+        None => return Ok(None),
+        Some(telemetry) if is_stored_telemetry_marker(telemetry) => return Ok(None),
+        Some(Value::Array(points)) => points.clone(),
+        Some(compressed) => decompress_telemetry_data(compressed)
+            .map_err(|e| format!("Failed to decompress telemetry: {}", e))?,
+    };
+    let original_size = serde_json::to_string(&points).map_err(|e| e.to_string())?.len() as i64;
+    session_data["telemetry_data"] = stored_telemetry_marker(points.len());
+    Ok(Some((points, original_size)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::compression::compress_telemetry_data;
+    use serde_json::json;
+
+    #[test]
+    fn test_legacy_session_is_upgraded_to_current_versions() {
+        let mut session = json!({
+            "chat_history": [{ "role": "user", "content": "hi" }],
+            "telemetry_data": [{ "timestamp": 1, "cpu_power": 4.2 }, { "timestamp": 2, "cpu_power": null }],
+        });
+        assert_eq!(session_schema_version(&session), 1);
+        assert_eq!(telemetry_schema_version(&session), 0);
+
+        upgrade_session_schema(&mut session).unwrap();
+        let (points, _) = upgrade_telemetry(&mut session).unwrap().unwrap();
+        assert_eq!(session_schema_version(&session), SESSION_SCHEMA_VERSION);
+        assert_eq!(telemetry_schema_version(&session), TELEMETRY_SCHEMA_VERSION);
+        assert_eq!(session["model_fingerprints"], json!({}));
+        assert!(session[SESSION_MANIFEST_KEY].is_null());
+        assert_eq!(points.len(), 2);
+        assert_eq!(session["telemetry_data"], stored_telemetry_marker(2));
+
+        // Already current: nothing further to do
+        let before = session.clone();
+        upgrade_session_schema(&mut session).unwrap();
+        assert!(upgrade_telemetry(&mut session).unwrap().is_none());
+        assert_eq!(session, before);
+
+        // Compressed telemetry from v1 is moved to the table too
+        let mut compressed = json!({ "telemetry_data": compress_telemetry_data(&points).unwrap() });
+        assert_eq!(telemetry_schema_version(&compressed), 1);
+        assert_eq!(upgrade_telemetry(&mut compressed).unwrap().unwrap().0.len(), 2);
+        assert_eq!(telemetry_schema_version(&compressed), TELEMETRY_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_newer_sessions_are_not_downgraded() {
+        let mut session = json!({ "schema_version": SESSION_SCHEMA_VERSION + 1, "chat_history": [] });
+        assert!(upgrade_session_schema(&mut session).is_err());
+        assert_eq!(session_schema_version(&session), SESSION_SCHEMA_VERSION + 1);
+    }
+}
@@ -1,52 +1,556 @@
 pub mod database;
 pub mod compression;
 pub mod models;
+pub mod reproducibility;
+pub mod migration;
+pub mod run_manifest;
+pub mod experiments;
+pub mod telemetry_policy;
+pub mod web_export;
+pub mod report_locale;
+pub mod csv_export;
+pub mod telemetry_export;
+pub mod history;
+pub mod session_comparison;
+pub mod model_library;
+pub mod benchmark;
+pub mod analysis;
+pub mod disk_space;
+pub mod telemetry_store;
 
-use tauri::State;
+use tauri::{AppHandle, State};
+use crate::error::A2oError;
 use crate::persistence::{database::SessionDatabase, models::*};
+use crate::persistence::reproducibility::{check_reproducibility, record_model_fingerprints, SessionReproducibilityReport};
+use crate::persistence::run_manifest::{manifest_from_session, RunManifest};
+use crate::persistence::migration::{upgrade_session_schema, CompatibilityReport, MigrationSummary};
+use crate::persistence::experiments::{Experiment, ExperimentStats};
+use crate::persistence::telemetry_policy::{downsample_telemetry, prune_telemetry, TelemetryRetentionPolicy};
+use crate::persistence::web_export::render_session_page;
+use crate::persistence::csv_export::render_telemetry_csv;
+use crate::persistence::telemetry_export::{render_telemetry_export, TelemetryExportFormat};
+use crate::persistence::report_locale::ReportLocale;
+use crate::persistence::history::{metric_history, validate_history_metric, HistoryRange, MetricHistoryPoint};
+use crate::persistence::session_comparison::{compare_session_metrics, SessionComparison};
+use crate::persistence::disk_space::ensure_free_space;
+use crate::persistence::telemetry_store::{recording_reference, ModelTelemetryStats};
+use crate::telemetry::temperature_unit::TemperatureUnit;
+use crate::hardware::backend_info::backend_info;
+use crate::hardware::capabilities::platform_capabilities;
 
 #[tauri::command]
 pub async fn save_session(
+    app: AppHandle,
     db: State<'_, SessionDatabase>,
-    request: CreateSessionRequest
-) -> Result<SavedSession, String> {
-    db.save_session(request).map_err(|e| e.to_string())
+    mut request: CreateSessionRequest
+) -> Result<SavedSession, A2oError> {
+    record_model_fingerprints(&mut request.session_data);
+    if let Some(obj) = request.session_data.as_object_mut() {
+        // Stamp what the recorded results ran on (Rosetta / CPU-only builds skew everything)
+        let capabilities = serde_json::to_value(platform_capabilities()).map_err(|e| e.to_string())?;
+        obj.insert("platform_capabilities".to_string(), capabilities);
+        // And which llama.cpp build produced them, so changes across app updates can be attributed
+        let backend = serde_json::to_value(backend_info()).map_err(|e| e.to_string())?;
+        obj.insert("backend_info".to_string(), backend);
+    }
+    // This machine's reference benchmark, so the session can be compared across machines
+    if let Some(baseline) = db.hardware_baseline()? {
+        let baseline = serde_json::to_value(baseline).map_err(|e| e.to_string())?;
+        if let Some(obj) = request.session_data.as_object_mut() {
+            obj.insert("hardware_baseline".to_string(), baseline);
+        }
+    }
+    // Samples flushed during the run are copied into the session inside SQLite, unless the
+    // retention policy has to rewrite them first
+    let policy = db.telemetry_retention_policy()?;
+    let recording = request.session_data.get("telemetry_data").and_then(recording_reference).map(str::to_string);
+    let rewrites_samples = policy.fields.is_some() || policy.max_samples.is_some();
+    if let Some(recording_id) = recording.filter(|_| rewrites_samples) {
+        request.session_data["telemetry_data"] = serde_json::Value::Array(db.recording_samples(&recording_id)?);
+    }
+    // Apply the user's telemetry field whitelist and record what it removed
+    if let Some(report) = prune_telemetry(&mut request.session_data, &policy) {
+        println!("✂️ Telemetry policy dropped {} field(s) from {} samples ({} -> {} bytes)",
+                 report.dropped_fields.len(), report.samples, report.bytes_before, report.bytes_after);
+        let report = serde_json::to_value(report).map_err(|e| e.to_string())?;
+        if let Some(obj) = request.session_data.as_object_mut() {
+            obj.insert("telemetry_pruning".to_string(), report);
+        }
+    }
+    // And its sample cap, averaging the oldest samples of long runs together
+    if let Some(report) = downsample_telemetry(&mut request.session_data, &policy) {
+        println!("✂️ Telemetry policy downsampled {} samples to {} (cap {})",
+                 report.samples_before, report.samples_after, report.max_samples);
+        let report = serde_json::to_value(report).map_err(|e| e.to_string())?;
+        if let Some(obj) = request.session_data.as_object_mut() {
+            obj.insert("telemetry_downsampling".to_string(), report);
+        }
+    }
+    upgrade_session_schema(&mut request.session_data)?;
+    // Uncompressed size: an upper bound on what the database grows by
+    let expected_bytes = serde_json::to_vec(&request.session_data).map_or(0, |data| data.len() as u64);
+    ensure_free_space(&app, "save_session", db.path(), expected_bytes)?;
+    db.save_session(request).map_err(A2oError::from)
+}
+
+#[tauri::command]
+pub async fn get_telemetry_retention_policy(
+    db: State<'_, SessionDatabase>
+) -> Result<TelemetryRetentionPolicy, A2oError> {
+    db.telemetry_retention_policy().map_err(A2oError::from)
+}
+
+/// Set which telemetry fields saved sessions keep (`fields: null` keeps all) and how many
+/// samples (`max_samples: null` keeps all). Only affects sessions saved afterwards.
+#[tauri::command]
+pub async fn set_telemetry_retention_policy(
+    db: State<'_, SessionDatabase>,
+    policy: TelemetryRetentionPolicy
+) -> Result<TelemetryRetentionPolicy, A2oError> {
+    let policy = policy.normalized();
+    db.set_telemetry_retention_policy(&policy)?;
+    Ok(policy)
+}
+
+#[tauri::command]
+pub async fn get_temperature_unit(
+    db: State<'_, SessionDatabase>
+) -> Result<TemperatureUnit, A2oError> {
+    db.temperature_unit().map_err(A2oError::from)
+}
+
+/// Set the unit summaries, reports, alerts and exports show temperatures in.
+/// Recorded telemetry stays in Celsius.
+#[tauri::command]
+pub async fn set_temperature_unit(
+    db: State<'_, SessionDatabase>,
+    unit: TemperatureUnit
+) -> Result<TemperatureUnit, A2oError> {
+    db.set_temperature_unit(unit)?;
+    Ok(unit)
+}
+
+#[tauri::command]
+pub async fn get_report_locale(
+    db: State<'_, SessionDatabase>
+) -> Result<ReportLocale, A2oError> {
+    db.report_locale().map_err(A2oError::from)
+}
+
+/// Set the decimal separator and timestamp layout used by CSV and HTML exports
+#[tauri::command]
+pub async fn set_report_locale(
+    db: State<'_, SessionDatabase>,
+    locale: ReportLocale
+) -> Result<ReportLocale, A2oError> {
+    db.set_report_locale(&locale)?;
+    Ok(locale)
 }
 
 #[tauri::command]
 pub async fn get_saved_sessions(
     db: State<'_, SessionDatabase>
-) -> Result<Vec<SavedSession>, String> {
-    db.get_all_sessions().map_err(|e| e.to_string())
+) -> Result<Vec<SavedSession>, A2oError> {
+    db.get_all_sessions().map_err(A2oError::from)
 }
 
 #[tauri::command]
 pub async fn load_session(
     db: State<'_, SessionDatabase>,
     uuid: String
-) -> Result<Option<SavedSession>, String> {
-    db.load_session(&uuid).map_err(|e| e.to_string())
+) -> Result<Option<SavedSession>, A2oError> {
+    db.load_session(&uuid).map_err(A2oError::from)
+}
+
+/// A saved session's telemetry samples; `load_session` only returns the marker for them
+#[tauri::command]
+pub async fn get_session_telemetry(
+    db: State<'_, SessionDatabase>,
+    uuid: String
+) -> Result<Vec<serde_json::Value>, A2oError> {
+    db.session_telemetry(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))
+}
+
+/// Flush samples of the chat being recorded; saving it references the recording instead
+/// of sending the samples. Returns how many samples the recording holds.
+#[tauri::command]
+pub async fn append_telemetry_samples(
+    db: State<'_, SessionDatabase>,
+    recording_id: String,
+    samples: Vec<serde_json::Value>
+) -> Result<usize, A2oError> {
+    db.append_recording_samples(&recording_id, &samples).map_err(A2oError::from)
+}
+
+/// Continue recording from a loaded session's samples, so saving again keeps them
+#[tauri::command]
+pub async fn resume_telemetry_recording(
+    db: State<'_, SessionDatabase>,
+    recording_id: String,
+    uuid: String
+) -> Result<usize, A2oError> {
+    db.resume_recording(&recording_id, &uuid).map_err(A2oError::from)
+}
+
+/// Drop a recording's samples (the chat was cleared without saving)
+#[tauri::command]
+pub async fn discard_telemetry_recording(
+    db: State<'_, SessionDatabase>,
+    recording_id: String
+) -> Result<usize, A2oError> {
+    db.discard_recording(&recording_id).map_err(A2oError::from)
 }
 
 #[tauri::command]
 pub async fn delete_saved_session(
     db: State<'_, SessionDatabase>,
     uuid: String
-) -> Result<bool, String> {
-    db.delete_session(&uuid).map_err(|e| e.to_string())
+) -> Result<bool, A2oError> {
+    db.delete_session(&uuid).map_err(A2oError::from)
+}
+
+/// Delete many sessions at once (e.g. after a sweep) in a single transaction
+#[tauri::command]
+pub async fn delete_sessions(
+    db: State<'_, SessionDatabase>,
+    uuids: Vec<String>
+) -> Result<BulkSessionResult, A2oError> {
+    let result = db.delete_sessions(&uuids)?;
+    println!("🗄️ Deleted {} session(s), {} not found", result.affected.len(), result.missing.len());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn tag_sessions(
+    db: State<'_, SessionDatabase>,
+    uuids: Vec<String>,
+    tag: String
+) -> Result<BulkSessionResult, A2oError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(A2oError::InvalidInput("Tag is empty".to_string()));
+    }
+    db.tag_sessions(&uuids, tag).map_err(A2oError::from)
+}
+
+// Trimmed, sorted tags without blanks or duplicates
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags.iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Replace a session's tags (blank and duplicate tags are dropped)
+#[tauri::command]
+pub async fn set_session_tags(
+    db: State<'_, SessionDatabase>,
+    uuid: String,
+    tags: Vec<String>
+) -> Result<Vec<String>, A2oError> {
+    let tags = normalize_tags(tags);
+    if !db.set_session_tags(&uuid, &tags)? {
+        return Err(A2oError::NotFound(format!("Session {} not found", uuid)));
+    }
+    Ok(tags)
+}
+
+/// Full-text search over session names, prompts and model names, narrowed to sessions
+/// carrying all of `tags`; an empty query lists every session with those tags
+#[tauri::command]
+pub async fn search_sessions(
+    db: State<'_, SessionDatabase>,
+    query: String,
+    tags: Option<Vec<String>>
+) -> Result<Vec<SessionSearchResult>, A2oError> {
+    let tags = normalize_tags(tags.unwrap_or_default());
+    db.search_sessions(&query, &tags).map_err(A2oError::from)
+}
+
+/// Write the sessions, with their tags, to one JSON file at `path`
+#[tauri::command]
+pub async fn export_sessions(
+    app: AppHandle,
+    db: State<'_, SessionDatabase>,
+    uuids: Vec<String>,
+    path: String
+) -> Result<BulkSessionResult, A2oError> {
+    let (sessions, missing) = db.export_sessions(&uuids)?;
+    let affected = sessions.iter().map(|exported| exported.session.uuid.clone()).collect();
+    let export = SessionExport {
+        schema: SESSION_EXPORT_SCHEMA.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        temperature_unit: db.temperature_unit()?,
+        sessions,
+    };
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize sessions: {}", e))?;
+    ensure_free_space(&app, "export_sessions", std::path::Path::new(&path), json.len() as u64)?;
+    std::fs::write(&path, json)
+        .map_err(|e| A2oError::Io(format!("Failed to write session export {}: {}", path, e)))?;
+    println!("🗄️ Exported {} session(s) to {}", export.sessions.len(), path);
+    Ok(BulkSessionResult { affected, missing })
+}
+
+/// Write one session, with its tags and telemetry samples, to a portable `.a2o` file
+/// (extension added when `path` has none) and return the written path
+#[tauri::command]
+pub async fn export_session(
+    app: AppHandle,
+    db: State<'_, SessionDatabase>,
+    uuid: String,
+    path: String
+) -> Result<String, A2oError> {
+    let (mut sessions, _) = db.export_sessions(std::slice::from_ref(&uuid))?;
+    let session = sessions.pop().ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))?;
+    let mut path = std::path::PathBuf::from(path);
+    if path.extension().is_none() {
+        path.set_extension(SESSION_FILE_EXTENSION);
+    }
+    let file = SessionFile {
+        schema: SESSION_FILE_SCHEMA.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().timestamp(),
+        session,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+    ensure_free_space(&app, "export_session", &path, json.len() as u64)?;
+    std::fs::write(&path, json)
+        .map_err(|e| A2oError::Io(format!("Failed to write session file {}: {}", path.display(), e)))?;
+    println!("🗄️ Exported session {} to {}", uuid, path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Import a session from a `.a2o` file, upgrading older schemas on the way in. The session
+/// keeps its uuid unless one already exists here, in which case it gets a new one.
+#[tauri::command]
+pub async fn import_session(
+    app: AppHandle,
+    db: State<'_, SessionDatabase>,
+    path: String
+) -> Result<SavedSession, A2oError> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| A2oError::Io(format!("Failed to read session file {}: {}", path, e)))?;
+    let mut file: SessionFile = serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid session file {}: {}", path, e))?;
+    if file.schema != SESSION_FILE_SCHEMA {
+        return Err(A2oError::InvalidInput(format!("Unsupported session file schema: {}", file.schema)));
+    }
+    let session = &mut file.session.session;
+    // Telemetry is moved into telemetry_samples as the session is inserted
+    upgrade_session_schema(&mut session.session_data)?;
+    ensure_free_space(&app, "import_session", db.path(), json.len() as u64)?;
+    let imported = db.import_session(file.session)?;
+    println!("🗄️ Imported session {} (exported by v{}) from {}", imported.uuid, file.app_version, path);
+    Ok(imported)
+}
+
+// A loaded session's telemetry samples, read from telemetry_samples
+fn session_telemetry(db: &SessionDatabase, session: &SavedSession) -> Result<Vec<serde_json::Value>, A2oError> {
+    Ok(db.session_telemetry(&session.uuid)?.unwrap_or_default())
+}
+
+/// Compare two saved sessions model by model: headline metrics with deltas (B - A) and
+/// both telemetry streams aligned on elapsed time
+#[tauri::command]
+pub async fn compare_sessions(
+    db: State<'_, SessionDatabase>,
+    uuid_a: String,
+    uuid_b: String
+) -> Result<SessionComparison, A2oError> {
+    let load = |uuid: &str| db.load_session(uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)));
+    let (session_a, session_b) = (load(&uuid_a)?, load(&uuid_b)?);
+    let telemetry_a = session_telemetry(&db, &session_a)?;
+    let telemetry_b = session_telemetry(&db, &session_b)?;
+    Ok(compare_session_metrics(&session_a, &telemetry_a, &session_b, &telemetry_b))
+}
+
+/// Write the session's telemetry as CSV to `path`, formatted for the report locale,
+/// and return the number of samples written
+#[tauri::command]
+pub async fn export_session_csv(
+    db: State<'_, SessionDatabase>,
+    uuid: String,
+    path: String
+) -> Result<usize, A2oError> {
+    let session = db.load_session(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))?;
+    let telemetry = session_telemetry(&db, &session)?;
+    let unit = db.temperature_unit()?;
+    let locale = db.report_locale()?;
+    std::fs::write(&path, render_telemetry_csv(&telemetry, &locale, unit))
+        .map_err(|e| A2oError::Io(format!("Failed to write CSV export {}: {}", path, e)))?;
+    println!("📄 Exported {} telemetry samples of session {} to {}", telemetry.len(), uuid, path);
+    Ok(telemetry.len())
+}
+
+/// Write every field of the session's telemetry samples, unformatted, to `path` as CSV or
+/// JSON Lines for analysis tools, and return the number of samples written
+#[tauri::command]
+pub async fn export_telemetry(
+    app: AppHandle,
+    db: State<'_, SessionDatabase>,
+    uuid: String,
+    format: TelemetryExportFormat,
+    path: String
+) -> Result<usize, A2oError> {
+    let session = db.load_session(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))?;
+    let telemetry = session_telemetry(&db, &session)?;
+    let export = render_telemetry_export(&telemetry, format);
+    ensure_free_space(&app, "export_telemetry", std::path::Path::new(&path), export.len() as u64)?;
+    std::fs::write(&path, export)
+        .map_err(|e| A2oError::Io(format!("Failed to write telemetry export {}: {}", path, e)))?;
+    println!("📄 Exported {} telemetry samples of session {} as {:?} to {}", telemetry.len(), uuid, format, path);
+    Ok(telemetry.len())
+}
+
+/// Write a read-only, self-contained `index.html` for the session into `dir`
+/// (created if needed) and return the page's path
+#[tauri::command]
+pub async fn export_session_webpage(
+    db: State<'_, SessionDatabase>,
+    uuid: String,
+    dir: String
+) -> Result<String, A2oError> {
+    let session = db.load_session(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))?;
+    let telemetry = session_telemetry(&db, &session)?;
+
+    let dir = std::path::Path::new(&dir);
+    std::fs::create_dir_all(dir)
+        .map_err(|e| A2oError::Io(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let page_path = dir.join("index.html");
+    let unit = db.temperature_unit()?;
+    let locale = db.report_locale()?;
+    std::fs::write(&page_path, render_session_page(&session, &telemetry, unit, &locale))
+        .map_err(|e| A2oError::Io(format!("Failed to write {}: {}", page_path.display(), e)))?;
+    println!("🌐 Exported session {} as a web page to {}", uuid, page_path.display());
+    Ok(page_path.to_string_lossy().into_owned())
+}
+
+/// One summary metric (e.g. "energy_per_token_wh") across saved sessions in a time range,
+/// oldest first, optionally limited to models whose path contains `model_filter`
+#[tauri::command]
+pub async fn get_metric_history(
+    db: State<'_, SessionDatabase>,
+    metric: String,
+    model_filter: Option<String>,
+    range: Option<HistoryRange>
+) -> Result<Vec<MetricHistoryPoint>, A2oError> {
+    validate_history_metric(&metric).map_err(A2oError::InvalidInput)?;
+    let sessions = db.sessions_in_range(range.unwrap_or_default())?;
+    Ok(metric_history(&sessions, &metric, model_filter.as_deref()))
+}
+
+/// Create a named group for related sessions (e.g. one sweep or one quant ladder)
+#[tauri::command]
+pub async fn create_experiment(
+    db: State<'_, SessionDatabase>,
+    name: String,
+    description: Option<String>
+) -> Result<Experiment, A2oError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(A2oError::InvalidInput("Experiment name is empty".to_string()));
+    }
+    let description = description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    db.create_experiment(name, description).map_err(A2oError::from)
+}
+
+#[tauri::command]
+pub async fn list_experiments(
+    db: State<'_, SessionDatabase>
+) -> Result<Vec<Experiment>, A2oError> {
+    db.list_experiments().map_err(A2oError::from)
+}
+
+#[tauri::command]
+pub async fn assign_sessions_to_experiment(
+    db: State<'_, SessionDatabase>,
+    experiment_id: String,
+    uuids: Vec<String>
+) -> Result<BulkSessionResult, A2oError> {
+    db.assign_sessions_to_experiment(&experiment_id, &uuids)?
+        .ok_or_else(|| A2oError::NotFound(format!("Experiment {} not found", experiment_id)))
+}
+
+/// Per-model mean/min/max of the summary metrics across the experiment's sessions
+#[tauri::command]
+pub async fn get_experiment_stats(
+    db: State<'_, SessionDatabase>,
+    experiment_id: String
+) -> Result<ExperimentStats, A2oError> {
+    db.experiment_stats(&experiment_id)?
+        .ok_or_else(|| A2oError::NotFound(format!("Experiment {} not found", experiment_id)))
 }
 
 #[tauri::command]
 pub async fn get_session_list(
     db: State<'_, SessionDatabase>
-) -> Result<Vec<(String, String, i64, Option<i64>)>, String> {
-    db.get_session_list().map_err(|e| e.to_string())
+) -> Result<Vec<(String, String, i64, Option<i64>)>, A2oError> {
+    db.get_session_list().map_err(A2oError::from)
+}
+
+/// Per-model peak temperatures, average power and TPS of a saved session, aggregated in SQL
+/// over its telemetry samples
+#[tauri::command]
+pub async fn get_telemetry_stats(
+    db: State<'_, SessionDatabase>,
+    uuid: String
+) -> Result<Vec<ModelTelemetryStats>, A2oError> {
+    db.telemetry_stats(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))
+}
+
+/// Report whether the model files a saved session used are still present and unchanged
+#[tauri::command]
+pub async fn check_session_reproducibility(
+    db: State<'_, SessionDatabase>,
+    uuid: String
+) -> Result<SessionReproducibilityReport, A2oError> {
+    let session = db.load_session(&uuid)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", uuid)))?;
+    Ok(check_reproducibility(&uuid, &session.session_data))
+}
+
+/// Run provenance (resolved config, environment, model fingerprints) stored with a saved session
+#[tauri::command]
+pub async fn get_run_manifest(
+    db: State<'_, SessionDatabase>,
+    session_id: String
+) -> Result<Option<RunManifest>, A2oError> {
+    let session = db.load_session(&session_id)?
+        .ok_or_else(|| A2oError::NotFound(format!("Session {} not found", session_id)))?;
+    manifest_from_session(&session.session_data).map_err(A2oError::from)
+}
+
+/// Startup check: app/schema versions and the saved sessions that need `migrate_sessions`
+#[tauri::command]
+pub async fn check_data_compatibility(
+    db: State<'_, SessionDatabase>
+) -> Result<CompatibilityReport, A2oError> {
+    db.compatibility_report().map_err(A2oError::from)
+}
+
+/// Upgrade sessions saved by older app versions to the current schemas, in place
+#[tauri::command]
+pub async fn migrate_sessions(
+    db: State<'_, SessionDatabase>
+) -> Result<MigrationSummary, A2oError> {
+    let summary = db.migrate_sessions()?;
+    println!("🗄️ Migrated {} session(s), {} failed", summary.migrated.len(), summary.failed.len());
+    Ok(summary)
 }
 
 #[tauri::command]
 pub async fn decompress_telemetry(
     compressed_data: serde_json::Value
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<serde_json::Value>, A2oError> {
     use crate::persistence::compression::decompress_telemetry_data;
-    decompress_telemetry_data(&compressed_data).map_err(|e| e.to_string())
+    decompress_telemetry_data(&compressed_data).map_err(|e| A2oError::InvalidInput(e.to_string()))
 }
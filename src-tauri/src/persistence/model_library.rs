@@ -0,0 +1,220 @@
+// Model library: the GGUF files found under the user's model directories, kept in the
+// database so the model picker can offer them without browsing
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+// How deep below a configured directory to look (e.g. <dir>/<publisher>/<repo>/model.gguf)
+const MAX_SCAN_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelLibrarySettings {
+    pub directories: Vec<String>,
+    #[serde(default)]
+    pub watch: bool,   // Rescan on changes instead of only on request
+}
+
+impl ModelLibrarySettings {
+    /// Trimmed, non-empty, de-duplicated directories
+    pub fn normalized(mut self) -> Self {
+        let mut seen = Vec::new();
+        for directory in self.directories {
+            let directory = directory.trim().to_string();
+            if !directory.is_empty() && !seen.contains(&directory) {
+                seen.push(directory);
+            }
+        }
+        self.directories = seen;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelFile {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub modified_at: i64,   // Unix seconds
+}
+
+/// Payload of `model_library_changed`; `changed` covers files still being written
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModelLibraryChange {
+    pub added: Vec<ModelFile>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ModelFile>,
+}
+
+impl ModelLibraryChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn is_gguf(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("gguf"))
+}
+
+fn scan_directory(dir: &Path, depth: usize, found: &mut Vec<ModelFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if !hidden && depth < MAX_SCAN_DEPTH {
+                scan_directory(&path, depth + 1, found);
+            }
+        } else if !hidden && is_gguf(&path) {
+            let modified_at = metadata.modified().ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |age| age.as_secs() as i64);
+            found.push(ModelFile {
+                path: path.to_string_lossy().into_owned(),
+                file_name: entry.file_name().to_string_lossy().into_owned(),
+                size_bytes: metadata.len(),
+                modified_at,
+            });
+        }
+    }
+}
+
+/// GGUF files under the directories (hidden entries skipped), sorted by path.
+/// Missing or unreadable directories contribute nothing.
+pub fn scan_model_directories(directories: &[String]) -> Vec<ModelFile> {
+    let mut found = Vec::new();
+    for directory in directories {
+        scan_directory(Path::new(directory), 0, &mut found);
+    }
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    found.dedup_by(|a, b| a.path == b.path);
+    found
+}
+
+/// Where a file named `file_name` goes in `trash_dir`: its own name, or with a numeric
+/// suffix when the Trash already holds a file of that name
+#[cfg(any(target_os = "macos", test))]
+fn trash_destination(trash_dir: &Path, file_name: &str) -> std::path::PathBuf {
+    let candidate = trash_dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let name = Path::new(file_name);
+    let stem = name.file_stem().map_or_else(|| file_name.to_string(), |stem| stem.to_string_lossy().into_owned());
+    let extension = name.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| trash_dir.join(format!("{} {}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Move a file to the user's Trash (recoverable, unlike a delete). Files on the home
+/// volume are renamed into ~/.Trash; anything else is handed to Finder, which picks the
+/// right volume's Trash.
+#[cfg(target_os = "macos")]
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    let file_name = path.file_name()
+        .ok_or_else(|| format!("{} is not a file", path.display()))?
+        .to_string_lossy()
+        .into_owned();
+    if let Some(home) = std::env::var_os("HOME") {
+        let trash_dir = Path::new(&home).join(".Trash");
+        if trash_dir.is_dir() && std::fs::rename(path, trash_destination(&trash_dir, &file_name)).is_ok() {
+            return Ok(());
+        }
+    }
+
+    let script = format!(
+        "tell application \"Finder\" to delete POSIX file \"{}\"",
+        path.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\""),
+    );
+    let output = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to move {} to the Trash: {}", path.display(), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    Err(format!("Moving {} to the Trash is only supported on macOS", path.display()))
+}
+
+pub fn diff_library(previous: &[ModelFile], current: &[ModelFile]) -> ModelLibraryChange {
+    let previous: BTreeMap<&str, &ModelFile> = previous.iter().map(|file| (file.path.as_str(), file)).collect();
+    let current_paths: BTreeMap<&str, &ModelFile> = current.iter().map(|file| (file.path.as_str(), file)).collect();
+    let mut change = ModelLibraryChange::default();
+    for file in current {
+        match previous.get(file.path.as_str()) {
+            None => change.added.push(file.clone()),
+            Some(&old) if old != file => change.changed.push(file.clone()),
+            Some(_) => {}
+        }
+    }
+    change.removed = previous.keys()
+        .filter(|path| !current_paths.contains_key(*path))
+        .map(|path| path.to_string())
+        .collect();
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_finds_nested_ggufs_and_diff_reports_changes() {
+        let root = std::env::temp_dir().join(format!("a2o-models-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("publisher/repo")).unwrap();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        std::fs::write(root.join("a.gguf"), b"GGUF").unwrap();
+        std::fs::write(root.join("publisher/repo/b.GGUF"), b"GGUF").unwrap();
+        std::fs::write(root.join("notes.txt"), b"").unwrap();
+        std::fs::write(root.join(".cache/c.gguf"), b"GGUF").unwrap();
+
+        let directories = vec![root.to_string_lossy().into_owned(), "/does/not/exist".to_string()];
+        let before = scan_model_directories(&directories);
+        assert_eq!(before.iter().map(|file| file.file_name.as_str()).collect::<Vec<_>>(), vec!["a.gguf", "b.GGUF"]);
+        assert_eq!(before[0].size_bytes, 4);
+
+        std::fs::remove_file(root.join("a.gguf")).unwrap();
+        std::fs::write(root.join("publisher/repo/b.GGUF"), b"GGUF v3").unwrap();
+        std::fs::write(root.join("d.gguf"), b"GGUF").unwrap();
+        let after = scan_model_directories(&directories);
+        let change = diff_library(&before, &after);
+        assert_eq!(change.added.len(), 1);
+        assert_eq!(change.added[0].file_name, "d.gguf");
+        assert_eq!(change.removed, vec![before[0].path.clone()]);
+        assert_eq!(change.changed.len(), 1);
+        assert_eq!(change.changed[0].size_bytes, 7);
+        assert!(diff_library(&after, &after).is_empty());
+
+        let settings = ModelLibrarySettings { directories: vec![" /models ".into(), "".into(), "/models".into()], watch: true };
+        assert_eq!(settings.normalized().directories, vec!["/models".to_string()]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_trash_destination_avoids_existing_names() {
+        let trash = std::env::temp_dir().join(format!("a2o-trash-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&trash);
+        std::fs::create_dir_all(&trash).unwrap();
+        assert_eq!(trash_destination(&trash, "model.gguf"), trash.join("model.gguf"));
+
+        std::fs::write(trash.join("model.gguf"), b"").unwrap();
+        std::fs::write(trash.join("model 2.gguf"), b"").unwrap();
+        assert_eq!(trash_destination(&trash, "model.gguf"), trash.join("model 3.gguf"));
+        std::fs::remove_dir_all(&trash).unwrap();
+    }
+}
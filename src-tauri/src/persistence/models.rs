@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::Utc;
+use crate::telemetry::temperature_unit::TemperatureUnit;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedSession {
@@ -14,6 +15,55 @@ pub struct SavedSession {
     pub updated_at: i64,
 }
 
+/// Identifies the bulk export file layout for external tooling
+pub const SESSION_EXPORT_SCHEMA: &str = "apples2oranges.session-export/v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    #[serde(flatten)]
+    pub session: SavedSession,
+    pub tags: Vec<String>,
+}
+
+/// Single-session file (`.a2o`) for moving a session between machines; telemetry stays
+/// compressed as stored
+pub const SESSION_FILE_SCHEMA: &str = "apples2oranges.session-file/v1";
+pub const SESSION_FILE_EXTENSION: &str = "a2o";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub schema: String,
+    pub app_version: String,
+    pub exported_at: i64,
+    pub session: ExportedSession,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExport {
+    pub schema: String,
+    pub app_version: String,
+    pub exported_at: i64,
+    pub temperature_unit: TemperatureUnit,  // Display preference; session telemetry stays Celsius
+    pub sessions: Vec<ExportedSession>,
+}
+
+/// Outcome of a bulk operation; unknown uuids are reported rather than failing the batch
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkSessionResult {
+    pub affected: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+/// Session list entry returned by a search, with the session's tags
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSearchResult {
+    pub uuid: String,
+    pub name: String,
+    pub created_at: i64,
+    pub original_size: Option<i64>,
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateSessionRequest {
     pub name: String,
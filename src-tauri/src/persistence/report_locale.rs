@@ -0,0 +1,98 @@
+// Report locale: decimal separator and timestamp layout used by generated reports (CSV and
+// HTML exports), so they import cleanly into spreadsheets set up for the user's region.
+// Stored data is unaffected.
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalSeparator {
+    #[default]
+    Point,
+    Comma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFormat {
+    #[default]
+    Iso,            // 2025-03-14 09:05:00
+    DayMonthYear,   // 14.03.2025 09:05:00
+    MonthDayYear,   // 03/14/2025 09:05:00
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ReportLocale {
+    pub decimal_separator: DecimalSeparator,
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub local_time: bool,   // Timestamps in the machine's time zone instead of UTC
+}
+
+impl ReportLocale {
+    pub fn decimal_char(&self) -> char {
+        match self.decimal_separator {
+            DecimalSeparator::Point => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+
+    /// CSV field delimiter; spreadsheets expecting comma decimals split fields on semicolons
+    pub fn csv_delimiter(&self) -> char {
+        match self.decimal_separator {
+            DecimalSeparator::Point => ',',
+            DecimalSeparator::Comma => ';',
+        }
+    }
+
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value);
+        match self.decimal_separator {
+            DecimalSeparator::Point => formatted,
+            DecimalSeparator::Comma => formatted.replace('.', ","),
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self.date_format {
+            DateFormat::Iso => "%Y-%m-%d %H:%M:%S",
+            DateFormat::DayMonthYear => "%d.%m.%Y %H:%M:%S",
+            DateFormat::MonthDayYear => "%m/%d/%Y %H:%M:%S",
+        }
+    }
+
+    /// A Unix timestamp in milliseconds; UTC ones are suffixed " UTC"
+    pub fn format_timestamp_ms(&self, timestamp_ms: i64) -> String {
+        let Some(utc) = DateTime::<Utc>::from_timestamp_millis(timestamp_ms) else {
+            return String::new();
+        };
+        if self.local_time {
+            utc.with_timezone(&Local).format(self.pattern()).to_string()
+        } else {
+            format!("{} UTC", utc.format(self.pattern()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comma_locale_formats_numbers_and_dates() {
+        let european = ReportLocale {
+            decimal_separator: DecimalSeparator::Comma,
+            date_format: DateFormat::DayMonthYear,
+            local_time: false,
+        };
+        assert_eq!(european.format_number(41.256, 2), "41,26");
+        assert_eq!(european.csv_delimiter(), ';');
+        assert_eq!(european.format_timestamp_ms(1_741_943_100_000), "14.03.2025 09:05:00 UTC");
+
+        let default = ReportLocale::default();
+        assert_eq!(default.format_number(41.256, 2), "41.26");
+        assert_eq!(default.csv_delimiter(), ',');
+        assert_eq!(default.format_timestamp_ms(1_741_943_100_000), "2025-03-14 09:05:00 UTC");
+    }
+}
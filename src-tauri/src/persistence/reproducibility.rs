@@ -0,0 +1,164 @@
+// Model file fingerprints recorded with saved sessions, and the check that a
+// reloaded session still points at the same model files
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::inference::model_hash::quick_fingerprint;
+
+// Configuration keys of the models a session can reference
+const MODEL_SLOTS: [&str; 2] = ["model_a", "model_b"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelFingerprint {
+    pub path: String,
+    pub size_bytes: u64,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelFileStatus {
+    Unchanged,
+    Changed,
+    Missing,
+    NotRecorded,   // Session saved before fingerprints were recorded
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelReproducibility {
+    pub slot: String,
+    pub path: String,
+    pub status: ModelFileStatus,
+    pub recorded: Option<ModelFingerprint>,
+    pub current: Option<ModelFingerprint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReproducibilityReport {
+    pub uuid: String,
+    pub comparable: bool,          // Every referenced model file is unchanged
+    pub models: Vec<ModelReproducibility>,
+}
+
+pub fn fingerprint_model(path: &str) -> Result<ModelFingerprint, String> {
+    let size_bytes = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to stat {}: {}", path, e))?
+        .len();
+    Ok(ModelFingerprint {
+        path: path.to_string(),
+        size_bytes,
+        fingerprint: quick_fingerprint(Path::new(path))?,
+    })
+}
+
+fn model_paths(session_data: &Value) -> Vec<(&'static str, String)> {
+    MODEL_SLOTS.iter()
+        .filter_map(|slot| {
+            let path = session_data.pointer(&format!("/configuration/{}/model_path", slot))?.as_str()?;
+            (!path.is_empty()).then(|| (*slot, path.to_string()))
+        })
+        .collect()
+}
+
+/// Add `model_fingerprints` for every model the session configuration references.
+/// Unreadable models are skipped; they show up as not recorded when checked.
+pub fn record_model_fingerprints(session_data: &mut Value) {
+    let mut fingerprints = serde_json::Map::new();
+    for (slot, path) in model_paths(session_data) {
+        match fingerprint_model(&path) {
+            Ok(fingerprint) => {
+                fingerprints.insert(slot.to_string(), serde_json::to_value(fingerprint).unwrap());
+            }
+            Err(e) => println!("⚠️ Could not fingerprint {} for session: {}", slot, e),
+        }
+    }
+    if let Some(obj) = session_data.as_object_mut() {
+        obj.insert("model_fingerprints".to_string(), Value::Object(fingerprints));
+    }
+}
+
+pub fn check_reproducibility(uuid: &str, session_data: &Value) -> SessionReproducibilityReport {
+    let models: Vec<ModelReproducibility> = model_paths(session_data).into_iter()
+        .map(|(slot, path)| {
+            let recorded: Option<ModelFingerprint> = session_data.get("model_fingerprints")
+                .and_then(|fingerprints| fingerprints.get(slot))
+                .and_then(|value| serde_json::from_value(value.clone()).ok());
+            let current = Path::new(&path).exists().then(|| fingerprint_model(&path).ok()).flatten();
+            let status = match (&recorded, &current) {
+                (_, None) => ModelFileStatus::Missing,
+                (None, Some(_)) => ModelFileStatus::NotRecorded,
+                (Some(recorded), Some(current)) if recorded.size_bytes == current.size_bytes
+                    && recorded.fingerprint == current.fingerprint => ModelFileStatus::Unchanged,
+                (Some(_), Some(_)) => ModelFileStatus::Changed,
+            };
+            ModelReproducibility { slot: slot.to_string(), path, status, recorded, current }
+        })
+        .collect();
+
+    SessionReproducibilityReport {
+        uuid: uuid.to_string(),
+        comparable: models.iter().all(|model| model.status == ModelFileStatus::Unchanged),
+        models,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_model(name: &str, contents: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("a2o-repro-{}-{}.gguf", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn session_for(model_a: &str, model_b: &str) -> Value {
+        json!({
+            "chat_history": [],
+            "configuration": {
+                "model_a": { "model_path": model_a },
+                "model_b": { "model_path": model_b },
+            }
+        })
+    }
+
+    #[test]
+    fn test_reports_unchanged_changed_and_missing_models() {
+        let model_a = temp_model("a", b"GGUF model a");
+        let model_b = temp_model("b", b"GGUF model b");
+        let mut session = session_for(&model_a, &model_b);
+        record_model_fingerprints(&mut session);
+
+        let report = check_reproducibility("uuid", &session);
+        assert!(report.comparable);
+        assert_eq!(report.models.len(), 2);
+
+        std::fs::write(&model_b, b"GGUF model b, requantized").unwrap();
+        let report = check_reproducibility("uuid", &session);
+        assert!(!report.comparable);
+        assert_eq!(report.models[0].status, ModelFileStatus::Unchanged);
+        assert_eq!(report.models[1].status, ModelFileStatus::Changed);
+
+        std::fs::remove_file(&model_a).unwrap();
+        std::fs::remove_file(&model_b).unwrap();
+        let report = check_reproducibility("uuid", &session);
+        assert_eq!(report.models[0].status, ModelFileStatus::Missing);
+        assert!(report.models[0].recorded.is_some() && report.models[0].current.is_none());
+    }
+
+    #[test]
+    fn test_sessions_without_fingerprints_are_not_comparable() {
+        let model_a = temp_model("legacy", b"GGUF legacy");
+        let session = json!({ "configuration": { "model_a": { "model_path": model_a }, "model_b": { "model_path": "" } } });
+
+        let report = check_reproducibility("uuid", &session);
+        assert_eq!(report.models.len(), 1);
+        assert_eq!(report.models[0].status, ModelFileStatus::NotRecorded);
+        assert!(!report.comparable);
+        std::fs::remove_file(&model_a).unwrap();
+    }
+}
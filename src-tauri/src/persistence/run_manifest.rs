@@ -0,0 +1,265 @@
+// Machine-readable run manifest: resolved configuration, environment and model
+// fingerprints captured at run start, written next to the app data and saved with sessions
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::hardware::backend_info::{backend_info, BackendInfo};
+use crate::hardware::environment::{low_power_mode, macos_version, power_adapter, power_source, relevant_env_vars, PowerAdapter, PowerSource};
+use crate::inference::context_params::{compare_effective_params, EffectiveContextParams};
+use crate::persistence::reproducibility::{fingerprint_model, ModelFingerprint};
+use crate::telemetry::types::{GenerationConfig, GenerationResult, IntegrationMethod, ModelConfig, TelemetryProfile};
+
+/// Identifies the manifest layout for external tooling
+pub const RUN_MANIFEST_SCHEMA: &str = "apples2oranges.run-manifest/v1";
+
+/// Key under which the frontend stores the manifest in `session_data`
+pub const SESSION_MANIFEST_KEY: &str = "run_manifest";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunEnvironment {
+    pub hardware: HardwareSummary,
+    pub os_version: Option<String>,
+    pub power_source: Option<PowerSource>,
+    pub low_power_mode: Option<bool>,
+    #[serde(default)]
+    pub power_adapter: Option<PowerAdapter>,
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,    // GGML_*, OMP_NUM_THREADS, ... as set for this process
+    #[serde(default)]
+    pub build: Option<BackendInfo>,            // llama.cpp bindings, backends and compile features
+}
+
+/// Run settings after defaults and clamping, i.e. what the run actually used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedRunConfig {
+    pub target: String,
+    #[serde(default)]
+    pub parallel: bool,                         // "Both" ran A and B concurrently
+    pub telemetry_enabled: bool,
+    pub telemetry_sampling_hz: f32,
+    pub telemetry_profile: TelemetryProfile,
+    pub energy_integration_method: IntegrationMethod,
+    pub rolling_tps_window_secs: f64,
+    pub stall_threshold_ms: f64,
+    pub measurement_overhead_watts: Option<f64>,
+    pub subtract_measurement_overhead: bool,
+    #[serde(default)]
+    pub idle_baseline_watts: Option<f64>,       // Idle power subtracted from readings, when enabled
+    pub wait_for_cpu_baseline_between_models: bool,
+    pub wait_for_cpu_baseline_margin_c: f64,
+    #[serde(default)]
+    pub heat_soak_target_c: Option<f64>,        // CPU temperature each model's run started from, when soaking
+    #[serde(default)]
+    pub heat_soak_band_c: Option<f64>,
+    pub system_prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunModelManifest {
+    pub label: String,                          // "A" / "B"
+    pub config: ModelConfig,
+    pub fingerprint: Option<ModelFingerprint>,  // None when the file could not be read
+    #[serde(default)]
+    pub effective_context: Option<EffectiveContextParams>,   // Filled in once the model has run
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub schema: String,
+    pub run_id: String,
+    pub app_version: String,
+    pub created_at_ms: u64,
+    pub environment: RunEnvironment,
+    pub config: ResolvedRunConfig,
+    pub models: Vec<RunModelManifest>,
+    #[serde(default)]
+    pub context_warnings: Vec<String>,         // Fallbacks and A/B parameter mismatches
+}
+
+pub fn collect_environment() -> RunEnvironment {
+    RunEnvironment {
+        hardware: hardware_summary(),
+        os_version: macos_version(),
+        power_source: power_source(),
+        low_power_mode: low_power_mode(),
+        power_adapter: power_adapter(),
+        env_vars: relevant_env_vars(),
+        build: Some(backend_info().clone()),
+    }
+}
+
+/// Models the run's target will execute, in run order
+fn target_models(config: &GenerationConfig) -> Vec<(&'static str, &ModelConfig)> {
+    let mut models = Vec::new();
+    if matches!(config.target.as_str(), "A" | "Both") {
+        if let Some(model_a) = &config.model_a {
+            models.push(("A", model_a));
+        }
+    }
+    if matches!(config.target.as_str(), "B" | "Both") {
+        if let Some(model_b) = &config.model_b {
+            models.push(("B", model_b));
+        }
+    }
+    models
+}
+
+/// Build the manifest for a run; fingerprints each model file, so call off the async runtime
+pub fn build_run_manifest(
+    run_id: &str,
+    config: &GenerationConfig,
+    resolved: ResolvedRunConfig,
+    environment: RunEnvironment,
+) -> RunManifest {
+    let models = target_models(config).into_iter()
+        .map(|(label, model)| RunModelManifest {
+            label: label.to_string(),
+            config: model.clone(),
+            fingerprint: fingerprint_model(&model.model_path)
+                .map_err(|e| println!("⚠️ Run manifest: could not fingerprint Model {}: {}", label, e))
+                .ok(),
+            effective_context: None,
+        })
+        .collect();
+
+    RunManifest {
+        schema: RUN_MANIFEST_SCHEMA.to_string(),
+        run_id: run_id.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        environment,
+        config: resolved,
+        models,
+        context_warnings: Vec::new(),
+    }
+}
+
+/// Store the context parameters each model actually ran with, plus their fallback warnings
+/// and, for A/B runs, the differences that make the two not directly comparable
+pub fn record_effective_context(manifest: &mut RunManifest, results: &[GenerationResult]) {
+    for model in &mut manifest.models {
+        let Some(params) = results.iter()
+            .find(|result| result.model == model.label)
+            .and_then(|result| result.effective_context.clone())
+        else {
+            continue;
+        };
+        manifest.context_warnings.extend(params.warnings.iter().cloned());
+        model.effective_context = Some(params);
+    }
+    let params = |label: &str| manifest.models.iter()
+        .find(|model| model.label == label)
+        .and_then(|model| model.effective_context.as_ref());
+    if let (Some(a), Some(b)) = (params("A"), params("B")) {
+        let differences = compare_effective_params(a, b);
+        manifest.context_warnings.extend(differences);
+    }
+}
+
+/// Write `<dir>/<run_id>.json` and return its path
+pub fn write_run_manifest(dir: &Path, manifest: &RunManifest) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create run manifest directory {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.json", manifest.run_id));
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize run manifest: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write run manifest {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// The manifest stored with a saved session (None for sessions saved before manifests existed)
+pub fn manifest_from_session(session_data: &Value) -> Result<Option<RunManifest>, String> {
+    match session_data.get(SESSION_MANIFEST_KEY) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid run manifest in session: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn resolved() -> ResolvedRunConfig {
+        ResolvedRunConfig {
+            parallel: false,
+            target: "Both".to_string(),
+            telemetry_enabled: true,
+            telemetry_sampling_hz: 2.0,
+            telemetry_profile: TelemetryProfile::default(),
+            energy_integration_method: IntegrationMethod::default(),
+            rolling_tps_window_secs: 5.0,
+            stall_threshold_ms: 250.0,
+            measurement_overhead_watts: None,
+            subtract_measurement_overhead: false,
+            idle_baseline_watts: None,
+            wait_for_cpu_baseline_between_models: false,
+            wait_for_cpu_baseline_margin_c: 2.0,
+            heat_soak_target_c: None,
+            heat_soak_band_c: None,
+            system_prompt: None,
+        }
+    }
+
+    fn environment() -> RunEnvironment {
+        RunEnvironment {
+            hardware: HardwareSummary {
+                chip: "M2".to_string(),
+                total_cores: 8,
+                p_cores: 4,
+                e_cores: 4,
+                memory_gb: Some(16.0),
+                os: "macos aarch64".to_string(),
+            },
+            os_version: Some("14.6.1".to_string()),
+            power_source: Some(PowerSource::Ac),
+            low_power_mode: Some(false),
+            power_adapter: None,
+            env_vars: BTreeMap::from([("GGML_METAL_NDEBUG".to_string(), "1".to_string())]),
+            build: None,
+        }
+    }
+
+    #[test]
+    fn test_manifest_covers_target_models_and_round_trips_through_session() {
+        let model_path = std::env::temp_dir().join(format!("a2o-manifest-{}.gguf", std::process::id()));
+        std::fs::write(&model_path, b"GGUF manifest model").unwrap();
+        let config: GenerationConfig = serde_json::from_value(json!({
+            "chat_history": [],
+            "target": "A",
+            "model_a": { "model_path": model_path.to_string_lossy() },
+            "model_b": { "model_path": "/missing/model-b.gguf" },
+        })).unwrap();
+
+        let manifest = build_run_manifest("run-1", &config, resolved(), environment());
+        assert_eq!(manifest.schema, RUN_MANIFEST_SCHEMA);
+        assert_eq!(manifest.models.len(), 1);
+        assert_eq!(manifest.models[0].label, "A");
+        assert_eq!(manifest.models[0].fingerprint.as_ref().unwrap().size_bytes, 19);
+
+        let dir = std::env::temp_dir().join(format!("a2o-manifests-{}", std::process::id()));
+        let path = write_run_manifest(&dir, &manifest).unwrap();
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["environment"]["power_source"], "ac");
+        assert_eq!(written["environment"]["env_vars"]["GGML_METAL_NDEBUG"], "1");
+
+        let session = json!({ "chat_history": [], SESSION_MANIFEST_KEY: written });
+        let restored = manifest_from_session(&session).unwrap().unwrap();
+        assert_eq!(restored.run_id, "run-1");
+        assert!(manifest_from_session(&json!({ "chat_history": [] })).unwrap().is_none());
+
+        std::fs::remove_file(&model_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+// Side-by-side comparison of two saved sessions: per model label, the headline metrics of
+// each session's telemetry with their deltas, plus both streams aligned on elapsed time
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::persistence::models::SavedSession;
+
+// Compared metrics, in display order. Temperatures are Celsius, as recorded.
+pub const COMPARED_METRICS: [&str; 10] = [
+    "tps_mean", "tps_median", "ttft_ms", "peak_power_w", "total_energy_wh", "energy_per_token_wh",
+    "cpu_temp_mean", "cpu_temp_max", "gpu_temp_mean", "gpu_temp_max",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedSession {
+    pub uuid: String,
+    pub name: String,
+    pub created_at: i64,
+}
+
+/// One metric of one model in both sessions; `delta` is B - A
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub session_a: Option<f64>,
+    pub session_b: Option<f64>,
+    pub delta: Option<f64>,
+    pub delta_percent: Option<f64>,   // Relative to session A; None when A is 0
+}
+
+/// Both sessions' samples of a model averaged per second since that model's first sample
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlignedSample {
+    pub elapsed_s: i64,
+    pub tps_a: Option<f64>,
+    pub tps_b: Option<f64>,
+    pub power_a: Option<f64>,   // CPU + GPU + ANE (W)
+    pub power_b: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelComparison {
+    pub model: String,   // "A" / "B"
+    pub metrics: Vec<MetricDelta>,
+    pub aligned: Vec<AlignedSample>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionComparison {
+    pub session_a: ComparedSession,
+    pub session_b: ComparedSession,
+    pub models: Vec<ModelComparison>,
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] })
+}
+
+fn max(values: &[f64]) -> Option<f64> {
+    values.iter().cloned().reduce(f64::max)
+}
+
+fn field_values(samples: &[&Value], field: &str) -> Vec<f64> {
+    samples.iter().filter_map(|point| point.get(field).and_then(Value::as_f64)).collect()
+}
+
+// Package power of a sample; None when no rail was measured
+fn sample_power(point: &Value) -> Option<f64> {
+    let rails: Vec<f64> = ["cpu_power", "gpu_power", "ane_power"].iter()
+        .filter_map(|field| point.get(field).and_then(Value::as_f64))
+        .collect();
+    (!rails.is_empty()).then(|| rails.iter().sum())
+}
+
+/// The compared metrics of one model in one session. TTFT and token counts come from the
+/// saved summary_stats, which hold them per generation; everything else from telemetry.
+fn model_metrics(telemetry: &[Value], summary: Option<&Value>, model: &str) -> BTreeMap<&'static str, f64> {
+    let samples: Vec<&Value> = telemetry.iter()
+        .filter(|point| point.get("model").and_then(Value::as_str) == Some(model))
+        .collect();
+    // Prefill and idle samples report 0 TPS
+    let tps: Vec<f64> = field_values(&samples, "tps").into_iter().filter(|&tps| tps > 0.0).collect();
+    let power: Vec<f64> = samples.iter().filter_map(|point| sample_power(point)).collect();
+    let cpu_temps = field_values(&samples, "cpu_temp");
+    let gpu_temps = field_values(&samples, "gpu_temp");
+    let summary_value = |field: &str| summary.and_then(|stats| stats.get(field)).and_then(Value::as_f64);
+
+    // Energy is cumulative within a run
+    let total_energy = max(&field_values(&samples, "total_energy_wh")).or_else(|| summary_value("total_energy_wh"));
+    let energy_per_token = summary_value("energy_per_token_wh").or_else(|| {
+        let tokens = summary_value("output_tokens").filter(|&tokens| tokens > 0.0)?;
+        Some(total_energy? / tokens)
+    });
+
+    [
+        ("tps_mean", mean(&tps)),
+        ("tps_median", median(&tps)),
+        ("ttft_ms", summary_value("ttft_ms")),
+        ("peak_power_w", max(&power)),
+        ("total_energy_wh", total_energy),
+        ("energy_per_token_wh", energy_per_token),
+        ("cpu_temp_mean", mean(&cpu_temps)),
+        ("cpu_temp_max", max(&cpu_temps)),
+        ("gpu_temp_mean", mean(&gpu_temps)),
+        ("gpu_temp_max", max(&gpu_temps)),
+    ]
+    .into_iter()
+    .filter_map(|(metric, value)| value.map(|value| (metric, value)))
+    .collect()
+}
+
+// Per-second (tps, power) averages of a model's samples, keyed by seconds since its first sample
+fn per_second(telemetry: &[Value], model: &str) -> BTreeMap<i64, (Option<f64>, Option<f64>)> {
+    let samples: Vec<(i64, &Value)> = telemetry.iter()
+        .filter(|point| point.get("model").and_then(Value::as_str) == Some(model))
+        .filter_map(|point| Some((point.get("timestamp")?.as_i64()?, point)))
+        .collect();
+    let Some(start) = samples.iter().map(|&(timestamp, _)| timestamp).min() else {
+        return BTreeMap::new();
+    };
+    let mut buckets: BTreeMap<i64, (Vec<f64>, Vec<f64>)> = BTreeMap::new();
+    for (timestamp, point) in samples {
+        let (tps, power) = buckets.entry((timestamp - start) / 1000).or_default();
+        tps.extend(point.get("tps").and_then(Value::as_f64));
+        power.extend(sample_power(point));
+    }
+    buckets.into_iter()
+        .map(|(second, (tps, power))| (second, (mean(&tps), mean(&power))))
+        .collect()
+}
+
+fn align(telemetry_a: &[Value], telemetry_b: &[Value], model: &str) -> Vec<AlignedSample> {
+    let a = per_second(telemetry_a, model);
+    let b = per_second(telemetry_b, model);
+    let seconds: BTreeSet<i64> = a.keys().chain(b.keys()).cloned().collect();
+    seconds.into_iter()
+        .map(|second| {
+            let (tps_a, power_a) = a.get(&second).cloned().unwrap_or_default();
+            let (tps_b, power_b) = b.get(&second).cloned().unwrap_or_default();
+            AlignedSample { elapsed_s: second, tps_a, tps_b, power_a, power_b }
+        })
+        .collect()
+}
+
+fn model_labels(session: &SavedSession, telemetry: &[Value]) -> BTreeSet<String> {
+    let summarized = session.session_data.get("summary_stats")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|stats| stats.keys().cloned());
+    let recorded = telemetry.iter()
+        .filter_map(|point| point.get("model").and_then(Value::as_str).map(str::to_string));
+    summarized.chain(recorded).collect()
+}
+
+fn compared_session(session: &SavedSession) -> ComparedSession {
+    ComparedSession { uuid: session.uuid.clone(), name: session.name.clone(), created_at: session.created_at }
+}
+
+/// Compare each model label present in either session (telemetry already decompressed)
+pub fn compare_session_metrics(
+    session_a: &SavedSession,
+    telemetry_a: &[Value],
+    session_b: &SavedSession,
+    telemetry_b: &[Value],
+) -> SessionComparison {
+    let mut labels = model_labels(session_a, telemetry_a);
+    labels.extend(model_labels(session_b, telemetry_b));
+
+    let models = labels.into_iter()
+        .map(|model| {
+            let summary = |session: &SavedSession| session.session_data.pointer(&format!("/summary_stats/{}", model)).cloned();
+            let a = model_metrics(telemetry_a, summary(session_a).as_ref(), &model);
+            let b = model_metrics(telemetry_b, summary(session_b).as_ref(), &model);
+            let metrics = COMPARED_METRICS.iter()
+                .map(|&metric| {
+                    let (value_a, value_b) = (a.get(metric).cloned(), b.get(metric).cloned());
+                    let delta = value_a.zip(value_b).map(|(a, b)| b - a);
+                    MetricDelta {
+                        metric: metric.to_string(),
+                        session_a: value_a,
+                        session_b: value_b,
+                        delta,
+                        delta_percent: delta.zip(value_a.filter(|&a| a != 0.0)).map(|(delta, a)| delta / a * 100.0),
+                    }
+                })
+                .collect();
+            let aligned = align(telemetry_a, telemetry_b, &model);
+            ModelComparison { model, metrics, aligned }
+        })
+        .collect();
+
+    SessionComparison {
+        session_a: compared_session(session_a),
+        session_b: compared_session(session_b),
+        models,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_metrics_and_streams_are_aligned_per_model() {
+        let session_a = SavedSession::new("Q4".to_string(), json!({ "summary_stats": { "A": { "ttft_ms": 200, "output_tokens": 10 } } }));
+        let session_b = SavedSession::new("Q8".to_string(), json!({ "summary_stats": { "A": { "ttft_ms": 250, "energy_per_token_wh": 0.002 } } }));
+        let telemetry_a = vec![
+            json!({ "timestamp": 10_000, "model": "A", "tps": 0.0, "cpu_power": 4.0, "gpu_power": 6.0, "cpu_temp": 50.0 }),
+            json!({ "timestamp": 10_500, "model": "A", "tps": 40.0, "cpu_power": 5.0, "gpu_power": 9.0, "cpu_temp": 54.0 }),
+            json!({ "timestamp": 11_200, "model": "A", "tps": 44.0, "gpu_power": 8.0, "total_energy_wh": 0.01 }),
+            json!({ "timestamp": 11_400, "model": "A", "tps": 48.0, "total_energy_wh": 0.02 }),
+        ];
+        let telemetry_b = vec![
+            json!({ "timestamp": 50_000, "model": "A", "tps": 30.0, "gpu_power": 12.0 }),
+            json!({ "timestamp": 50_100, "model": "B", "tps": 99.0 }),
+        ];
+
+        let comparison = compare_session_metrics(&session_a, &telemetry_a, &session_b, &telemetry_b);
+        assert_eq!(comparison.session_b.name, "Q8");
+        assert_eq!(comparison.models.iter().map(|m| m.model.as_str()).collect::<Vec<_>>(), vec!["A", "B"]);
+
+        let a = &comparison.models[0];
+        let metric = |name: &str| a.metrics.iter().find(|m| m.metric == name).unwrap().clone();
+        assert_eq!(a.metrics.len(), COMPARED_METRICS.len());
+        assert_eq!(metric("tps_mean").session_a, Some(44.0));
+        assert_eq!(metric("tps_median").session_a, Some(44.0));
+        assert_eq!(metric("tps_mean").delta, Some(-14.0));
+        assert_eq!(metric("ttft_ms"), MetricDelta {
+            metric: "ttft_ms".to_string(),
+            session_a: Some(200.0),
+            session_b: Some(250.0),
+            delta: Some(50.0),
+            delta_percent: Some(25.0),
+        });
+        assert_eq!(metric("peak_power_w").session_a, Some(14.0));
+        assert_eq!(metric("energy_per_token_wh").session_a, Some(0.002));
+        assert_eq!(metric("cpu_temp_max").session_a, Some(54.0));
+        assert_eq!(metric("cpu_temp_max").delta, None);
+
+        assert_eq!(a.aligned, vec![
+            AlignedSample { elapsed_s: 0, tps_a: Some(20.0), tps_b: Some(30.0), power_a: Some(12.0), power_b: Some(12.0) },
+            AlignedSample { elapsed_s: 1, tps_a: Some(46.0), tps_b: None, power_a: Some(8.0), power_b: None },
+        ]);
+        assert_eq!(comparison.models[1].metrics[0].session_a, None);
+        assert_eq!(comparison.models[1].metrics[0].session_b, Some(99.0));
+    }
+}
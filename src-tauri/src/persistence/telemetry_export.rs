@@ -0,0 +1,121 @@
+// Raw telemetry export for analysis tools (pandas, Excel): every field of every sample,
+// unformatted (Celsius, milliseconds, '.' decimals), as CSV or JSON Lines. The localized
+// report CSV with its fixed column set is csv_export.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::persistence::csv_export::csv_field;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryExportFormat {
+    Csv,
+    Jsonl,
+}
+
+// Leading CSV columns; the rest follow in the order they first appear (object keys sorted,
+// array elements by index)
+const LEADING_COLUMNS: [&str; 2] = ["timestamp", "model"];
+
+// Flatten nested values into columns: objects as "parent.child", arrays as "name_0", "name_1", ...
+fn flatten_into(prefix: &str, value: &Value, row: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_into(&name, field, row);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_into(&format!("{}_{}", prefix, index), item, row);
+            }
+        }
+        scalar => row.push((prefix.to_string(), scalar.clone())),
+    }
+}
+
+fn flatten_sample(point: &Value) -> Vec<(String, Value)> {
+    let mut row = Vec::new();
+    flatten_into("", point, &mut row);
+    row
+}
+
+fn csv_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// One CSV row per sample with a column for every field seen in any sample; samples
+/// missing a field get an empty cell
+pub fn render_telemetry_export_csv(telemetry: &[Value]) -> String {
+    let rows: Vec<HashMap<String, Value>> = telemetry.iter()
+        .map(|point| flatten_sample(point).into_iter().collect())
+        .collect();
+
+    let mut columns: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    let leading = LEADING_COLUMNS.iter()
+        .filter(|column| rows.iter().any(|row| row.contains_key(**column)))
+        .map(|column| column.to_string());
+    let rest = telemetry.iter().flat_map(|point| flatten_sample(point).into_iter().map(|(name, _)| name));
+    for column in leading.chain(rest) {
+        if seen.insert(column.clone()) {
+            columns.push(column);
+        }
+    }
+
+    let mut out = columns.iter().map(|column| csv_field(column, ',')).collect::<Vec<_>>().join(",");
+    out.push('\n');
+    for row in &rows {
+        let cells: Vec<String> = columns.iter()
+            .map(|column| csv_field(&csv_cell(row.get(column)), ','))
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// One JSON object per line, each sample as it was recorded
+pub fn render_telemetry_jsonl(telemetry: &[Value]) -> String {
+    telemetry.iter().map(|point| format!("{}\n", point)).collect()
+}
+
+pub fn render_telemetry_export(telemetry: &[Value], format: TelemetryExportFormat) -> String {
+    match format {
+        TelemetryExportFormat::Csv => render_telemetry_export_csv(telemetry),
+        TelemetryExportFormat::Jsonl => render_telemetry_jsonl(telemetry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_exports_every_field_as_csv_and_jsonl() {
+        let telemetry = vec![
+            json!({ "cpu_power": 4.5, "timestamp": 1000, "model": "A", "cpu_p_core_temps": [51.5, 52.0], "tps": null }),
+            json!({ "timestamp": 1500, "model": "B, q4", "gpu_power": 2.25, "core_temperatures": { "thermal_trend": "Stable" } }),
+        ];
+
+        let csv = render_telemetry_export(&telemetry, TelemetryExportFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "timestamp,model,cpu_p_core_temps_0,cpu_p_core_temps_1,cpu_power,tps,core_temperatures.thermal_trend,gpu_power");
+        assert_eq!(lines[1], "1000,A,51.5,52.0,4.5,,,");
+        assert_eq!(lines[2], "1500,\"B, q4\",,,,,Stable,2.25");
+
+        let jsonl = render_telemetry_export(&telemetry, TelemetryExportFormat::Jsonl);
+        let parsed: Vec<Value> = jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(parsed, telemetry);
+        assert_eq!(render_telemetry_export(&[], TelemetryExportFormat::Csv), "\n");
+    }
+}
@@ -0,0 +1,253 @@
+// Telemetry retention policy: a user-chosen whitelist of per-sample telemetry fields kept
+// when a session is saved. Long runs carry a dozen per-core arrays per sample, most of
+// which users who only look at power and TPS never need. Optionally also a cap on the
+// number of samples, reached by downsampling the oldest data of multi-hour runs.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Fields every sample keeps regardless of policy: charts and per-model attribution need them
+pub const ALWAYS_KEPT_FIELDS: [&str; 2] = ["timestamp", "model"];
+
+// Smallest sample cap accepted; below this the charts of a run lose their shape
+pub const MIN_MAX_SAMPLES: usize = 100;
+
+// Per-sample count of raw samples averaged into it by downsampling (absent means 1)
+pub const MERGED_SAMPLES_FIELD: &str = "merged_samples";
+
+// Per-sample [min, max] of each averaged field over the raw samples merged into it, so
+// peaks survive downsampling
+pub const MERGED_RANGE_FIELD: &str = "merged_range";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryRetentionPolicy {
+    pub fields: Option<Vec<String>>,  // None keeps every field
+    #[serde(default)]
+    pub max_samples: Option<usize>,   // None keeps every sample
+}
+
+impl TelemetryRetentionPolicy {
+    /// Trim and de-duplicate the whitelist, dropping blank entries
+    pub fn normalized(self) -> Self {
+        let fields = self.fields.map(|fields| {
+            fields.iter()
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        });
+        let max_samples = self.max_samples.map(|max| max.max(MIN_MAX_SAMPLES));
+        Self { fields, max_samples }
+    }
+
+    fn keeps(&self, field: &str) -> bool {
+        match &self.fields {
+            None => true,
+            Some(fields) => ALWAYS_KEPT_FIELDS.contains(&field) || fields.iter().any(|f| f == field),
+        }
+    }
+}
+
+/// What the policy removed from a session's telemetry, stored alongside it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetryPruneReport {
+    pub dropped_fields: Vec<String>,
+    pub samples: usize,
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+}
+
+/// Remove non-whitelisted fields from every telemetry sample in place.
+/// Returns None when the session has no raw telemetry or nothing was dropped.
+pub fn prune_telemetry(session_data: &mut Value, policy: &TelemetryRetentionPolicy) -> Option<TelemetryPruneReport> {
+    policy.fields.as_ref()?;
+    let Some(Value::Array(points)) = session_data.get_mut("telemetry_data") else {
+        return None;
+    };
+
+    let bytes_before = serde_json::to_string(points).map(|s| s.len()).unwrap_or(0);
+    let mut dropped_fields = BTreeSet::new();
+    for point in points.iter_mut() {
+        if let Some(obj) = point.as_object_mut() {
+            obj.retain(|field, _| {
+                let keep = policy.keeps(field);
+                if !keep {
+                    dropped_fields.insert(field.clone());
+                }
+                keep
+            });
+        }
+    }
+    if dropped_fields.is_empty() {
+        return None;
+    }
+
+    Some(TelemetryPruneReport {
+        dropped_fields: dropped_fields.into_iter().collect(),
+        samples: points.len(),
+        bytes_before,
+        bytes_after: serde_json::to_string(points).map(|s| s.len()).unwrap_or(0),
+    })
+}
+
+/// How far the sample cap downsampled a session's telemetry, stored alongside it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetryDownsampleReport {
+    pub max_samples: usize,
+    pub samples_before: usize,
+    pub samples_after: usize,
+}
+
+fn weight(point: &Value) -> f64 {
+    point.get(MERGED_SAMPLES_FIELD).and_then(Value::as_f64).unwrap_or(1.0)
+}
+
+// A field's [min, max] over the raw samples behind `point`: its merged range, or the value itself
+fn field_range(point: &Map<String, Value>, field: &str, value: f64) -> (f64, f64) {
+    let range = point.get(MERGED_RANGE_FIELD).and_then(|ranges| ranges.get(field)).and_then(Value::as_array);
+    match range.map(|range| (range.first().and_then(Value::as_f64), range.get(1).and_then(Value::as_f64))) {
+        Some((Some(min), Some(max))) => (min, max),
+        _ => (value, value),
+    }
+}
+
+// Average two consecutive samples, weighted by how many raw samples each already stands for,
+// and keep each averaged field's min and max in MERGED_RANGE_FIELD. Non-numeric fields
+// (arrays, strings) keep the newer sample's value.
+fn merge_samples(older: &Value, newer: &Value) -> Value {
+    let (Some(a), Some(b)) = (older.as_object(), newer.as_object()) else {
+        return newer.clone();
+    };
+    let (weight_a, weight_b) = (weight(older), weight(newer));
+    let number = |like: &Value, x: f64| if like.is_f64() { Value::from(x) } else { Value::from(x.round() as i64) };
+    let mut merged = b.clone();
+    let mut ranges = Map::new();
+    for (field, value) in b {
+        if field == MERGED_SAMPLES_FIELD || field == MERGED_RANGE_FIELD {
+            continue;
+        }
+        if let (Some(x), Some(y)) = (a.get(field).and_then(Value::as_f64), value.as_f64()) {
+            let mean = (x * weight_a + y * weight_b) / (weight_a + weight_b);
+            merged.insert(field.clone(), number(value, mean));
+            let ((min_a, max_a), (min_b, max_b)) = (field_range(a, field, x), field_range(b, field, y));
+            ranges.insert(field.clone(), Value::from(vec![number(value, min_a.min(min_b)), number(value, max_a.max(max_b))]));
+        }
+    }
+    merged.insert(MERGED_SAMPLES_FIELD.to_string(), Value::from((weight_a + weight_b) as u64));
+    merged.insert(MERGED_RANGE_FIELD.to_string(), Value::Object(ranges));
+    Value::Object(merged)
+}
+
+/// Bring `points` down to `max_samples` by repeatedly averaging neighbouring samples in the
+/// older half, so the oldest data ends up coarsest. Samples of different models are never
+/// merged. Returns whether anything was merged.
+pub fn downsample_oldest(points: &mut Vec<Value>, max_samples: usize) -> bool {
+    let before = points.len();
+    while points.len() > max_samples {
+        let half = points.len() / 2;
+        let mut merged = Vec::with_capacity(half);
+        let mut i = 0;
+        while i < half {
+            if i + 1 < half && points[i].get("model") == points[i + 1].get("model") {
+                merged.push(merge_samples(&points[i], &points[i + 1]));
+                i += 2;
+            } else {
+                merged.push(points[i].clone());
+                i += 1;
+            }
+        }
+        if merged.len() == half {
+            break; // Models alternate every sample: nothing left to merge
+        }
+        points.splice(..half, merged);
+    }
+    points.len() < before
+}
+
+/// Apply the policy's sample cap to a session's raw telemetry.
+/// Returns None when there is no cap, no raw telemetry or it already fits.
+pub fn downsample_telemetry(session_data: &mut Value, policy: &TelemetryRetentionPolicy) -> Option<TelemetryDownsampleReport> {
+    let max_samples = policy.max_samples?;
+    let Some(Value::Array(points)) = session_data.get_mut("telemetry_data") else {
+        return None;
+    };
+    let samples_before = points.len();
+    if !downsample_oldest(points, max_samples) {
+        return None;
+    }
+    Some(TelemetryDownsampleReport { max_samples, samples_before, samples_after: points.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_policy_keeps_whitelisted_and_required_fields() {
+        let mut session = json!({
+            "telemetry_data": [
+                { "timestamp": 1, "model": "A", "cpu_power": 4.2, "tps": 30.0, "cpu_p_core_temps": [50.0, 51.0] },
+                { "timestamp": 2, "model": "A", "cpu_power": 4.4, "tps": 31.0, "gpu_cluster_temps": [45.0] },
+            ]
+        });
+
+        // Keeping everything is a no-op
+        assert!(prune_telemetry(&mut session, &TelemetryRetentionPolicy::default()).is_none());
+
+        let policy = TelemetryRetentionPolicy {
+            fields: Some(vec![" cpu_power ".to_string(), "tps".to_string(), "tps".to_string(), "".to_string()]),
+            ..Default::default()
+        }.normalized();
+        assert_eq!(policy.fields, Some(vec!["cpu_power".to_string(), "tps".to_string()]));
+
+        let report = prune_telemetry(&mut session, &policy).unwrap();
+        assert_eq!(report.dropped_fields, vec!["cpu_p_core_temps".to_string(), "gpu_cluster_temps".to_string()]);
+        assert_eq!(report.samples, 2);
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(session["telemetry_data"][0], json!({ "timestamp": 1, "model": "A", "cpu_power": 4.2, "tps": 30.0 }));
+
+        // Already pruned: nothing more to report
+        assert!(prune_telemetry(&mut session, &policy).is_none());
+    }
+
+    #[test]
+    fn test_sample_cap_downsamples_oldest_and_keeps_models_apart() {
+        let points: Vec<Value> = (0..400)
+            .map(|i| json!({ "timestamp": i * 1000, "model": if i < 300 { "A" } else { "B" }, "tps": i as f64, "thermal_pressure": "nominal" }))
+            .collect();
+        let mut session = json!({ "telemetry_data": points });
+        let policy = TelemetryRetentionPolicy { max_samples: Some(10), ..Default::default() }.normalized();
+        assert_eq!(policy.max_samples, Some(MIN_MAX_SAMPLES));
+
+        let report = downsample_telemetry(&mut session, &policy).unwrap();
+        assert_eq!(report.samples_before, 400);
+        assert!(report.samples_after <= MIN_MAX_SAMPLES);
+        let points = session["telemetry_data"].as_array().unwrap();
+        // Every raw sample is accounted for, and the weighted TPS mean is unchanged
+        let weights: Vec<f64> = points.iter().map(weight).collect();
+        assert_eq!(weights.iter().sum::<f64>(), 400.0);
+        let mean = points.iter().zip(&weights).map(|(p, w)| p["tps"].as_f64().unwrap() * w).sum::<f64>() / 400.0;
+        assert!((mean - 199.5).abs() < 1e-9);
+        // Oldest data is coarsest, the newest samples are untouched
+        assert!(weights[0] > 1.0);
+        assert_eq!(points.last().unwrap(), &json!({ "timestamp": 399_000, "model": "B", "tps": 399.0, "thermal_pressure": "nominal" }));
+        // No merged sample mixes the models
+        let model_a: f64 = points.iter().zip(&weights).filter(|(p, _)| p["model"] == "A").map(|(_, w)| w).sum();
+        assert_eq!(model_a, 300.0);
+        // The ranges of the merged samples still cover every raw value
+        assert_eq!(points[0][MERGED_RANGE_FIELD]["tps"][0], 0.0);
+        let peak_a = points.iter()
+            .filter(|p| p["model"] == "A")
+            .map(|p| p[MERGED_RANGE_FIELD]["tps"][1].as_f64().unwrap_or_else(|| p["tps"].as_f64().unwrap()))
+            .fold(f64::MIN, f64::max);
+        assert_eq!(peak_a, 299.0);
+        assert_eq!(points[0][MERGED_RANGE_FIELD]["timestamp"][0], 0);
+
+        assert!(downsample_telemetry(&mut session, &policy).is_none());
+    }
+}
@@ -0,0 +1,347 @@
+// Saved telemetry as rows of the telemetry_samples table, one per sample, instead of a
+// compressed JSON blob inside session_data. The headline metrics get their own columns so
+// they can be queried in SQL; every other field of a sample is kept in the `extra` JSON
+// column, so a session's samples load back as they were saved (minus null fields, which
+// the compressed format dropped as well).
+//
+// Samples of the chat being recorded are flushed here during the run, under a
+// `recording:<id>` key, so saving a session copies rows instead of shipping the whole
+// array from the frontend.
+
+use rusqlite::{params, params_from_iter, Connection, Result as SqlResult};
+use rusqlite::types::Value as SqlValue;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Stand-in for the samples in session_data["telemetry_data"]
+pub const TELEMETRY_STORE_NAME: &str = "telemetry_samples";
+
+// Rows per INSERT statement (15 parameters each, well under SQLite's variable limit)
+const INSERT_BATCH_ROWS: usize = 256;
+
+// (sample field, column) for the fields stored in their own REAL column
+const METRIC_COLUMNS: &[(&str, &str)] = &[
+    ("cpu_power", "cpu_power_w"),
+    ("gpu_power", "gpu_power_w"),
+    ("ane_power", "ane_power_w"),
+    ("cpu_temp", "cpu_temp_c"),
+    ("gpu_temp", "gpu_temp_c"),
+    ("cpu_freq", "cpu_freq_mhz"),
+    ("gpu_freq", "gpu_freq_mhz"),
+    ("ram_usage", "ram_usage_gb"),
+    ("tps", "tps"),
+    ("total_energy_wh", "total_energy_wh"),
+];
+
+pub fn create_telemetry_samples_table(conn: &Connection) -> SqlResult<()> {
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS telemetry_samples (
+            session_id TEXT NOT NULL,
+            seq INTEGER NOT NULL,
+            timestamp_ms INTEGER,
+            model TEXT,
+            cpu_power_w REAL,
+            gpu_power_w REAL,
+            ane_power_w REAL,
+            cpu_temp_c REAL,
+            gpu_temp_c REAL,
+            cpu_freq_mhz REAL,
+            gpu_freq_mhz REAL,
+            ram_usage_gb REAL,
+            tps REAL,
+            total_energy_wh REAL,
+            extra TEXT,
+            PRIMARY KEY (session_id, seq)
+        );
+    ", [])?;
+    Ok(())
+}
+
+/// What session_data["telemetry_data"] holds once the samples are in the table
+pub fn stored_telemetry_marker(samples: usize) -> Value {
+    serde_json::json!({ "store": TELEMETRY_STORE_NAME, "samples": samples })
+}
+
+pub fn is_stored_telemetry_marker(telemetry: &Value) -> bool {
+    telemetry.get("store").and_then(Value::as_str) == Some(TELEMETRY_STORE_NAME)
+}
+
+// Rows of an unsaved recording are keyed by this prefix and the recording id
+const RECORDING_KEY_PREFIX: &str = "recording:";
+
+pub fn recording_key(recording_id: &str) -> String {
+    format!("{}{}", RECORDING_KEY_PREFIX, recording_id)
+}
+
+/// The recording a save request's session_data["telemetry_data"] refers to, if it is a
+/// `{"recording": id}` reference rather than the samples themselves
+pub fn recording_reference(telemetry: &Value) -> Option<&str> {
+    telemetry.get("recording").and_then(Value::as_str)
+}
+
+// One sample as row parameters, in column order after session_id and seq
+fn sample_row(point: &Value) -> Vec<SqlValue> {
+    let mut fields: Map<String, Value> = point.as_object().cloned().unwrap_or_default();
+    fields.retain(|_, v| !v.is_null());
+
+    let timestamp = match fields.get("timestamp").and_then(Value::as_i64) {
+        Some(ms) => {
+            fields.remove("timestamp");
+            SqlValue::Integer(ms)
+        }
+        None => SqlValue::Null,
+    };
+    let model = match fields.get("model").and_then(Value::as_str).map(str::to_string) {
+        Some(model) => {
+            fields.remove("model");
+            SqlValue::Text(model)
+        }
+        None => SqlValue::Null,
+    };
+    let mut row = vec![timestamp, model];
+    for (field, _) in METRIC_COLUMNS {
+        row.push(match fields.get(*field).and_then(Value::as_f64) {
+            Some(value) => {
+                fields.remove(*field);
+                SqlValue::Real(value)
+            }
+            None => SqlValue::Null,
+        });
+    }
+    row.push(if fields.is_empty() {
+        SqlValue::Null
+    } else {
+        SqlValue::Text(Value::Object(fields).to_string())
+    });
+    row
+}
+
+/// Replace the session's samples with `points`, written in multi-row batches. Call inside
+/// the transaction that writes the session row.
+pub fn insert_samples(conn: &Connection, session_id: &str, points: &[Value]) -> SqlResult<usize> {
+    delete_samples(conn, session_id)?;
+    write_samples(conn, session_id, 0, points)
+}
+
+/// Add `points` after the samples already stored under `session_id`; returns the new total
+pub fn append_samples(conn: &Connection, session_id: &str, points: &[Value]) -> SqlResult<usize> {
+    let stored = count_samples(conn, session_id)?;
+    write_samples(conn, session_id, stored, points)?;
+    Ok(stored + points.len())
+}
+
+// Write `points` as rows numbered from `first_seq`
+fn write_samples(conn: &Connection, session_id: &str, first_seq: usize, points: &[Value]) -> SqlResult<usize> {
+    let columns = 4 + METRIC_COLUMNS.len() + 1;
+    for (batch_index, batch) in points.chunks(INSERT_BATCH_ROWS).enumerate() {
+        let placeholders = vec![format!("({})", vec!["?"; columns].join(", ")); batch.len()].join(", ");
+        let sql = format!(
+            "INSERT INTO telemetry_samples (session_id, seq, timestamp_ms, model, {}, extra) VALUES {}",
+            METRIC_COLUMNS.iter().map(|(_, column)| *column).collect::<Vec<_>>().join(", "),
+            placeholders
+        );
+        let mut values = Vec::with_capacity(batch.len() * columns);
+        for (i, point) in batch.iter().enumerate() {
+            values.push(SqlValue::Text(session_id.to_string()));
+            values.push(SqlValue::Integer((first_seq + batch_index * INSERT_BATCH_ROWS + i) as i64));
+            values.extend(sample_row(point));
+        }
+        conn.prepare_cached(&sql)?.execute(params_from_iter(values))?;
+    }
+    Ok(points.len())
+}
+
+/// The session's samples in recorded order, as the JSON objects they were saved from
+pub fn load_samples(conn: &Connection, session_id: &str) -> SqlResult<Vec<Value>> {
+    let sql = format!(
+        "SELECT timestamp_ms, model, {}, extra FROM telemetry_samples WHERE session_id = ?1 ORDER BY seq",
+        METRIC_COLUMNS.iter().map(|(_, column)| *column).collect::<Vec<_>>().join(", ")
+    );
+    let mut stmt = conn.prepare_cached(&sql)?;
+    let rows = stmt.query_map([session_id], |row| {
+        let mut point = match row.get::<_, Option<String>>(METRIC_COLUMNS.len() + 2)? {
+            Some(extra) => serde_json::from_str::<Map<String, Value>>(&extra).unwrap_or_default(),
+            None => Map::new(),
+        };
+        if let Some(ms) = row.get::<_, Option<i64>>(0)? {
+            point.insert("timestamp".to_string(), Value::from(ms));
+        }
+        if let Some(model) = row.get::<_, Option<String>>(1)? {
+            point.insert("model".to_string(), Value::from(model));
+        }
+        for (i, (field, _)) in METRIC_COLUMNS.iter().enumerate() {
+            if let Some(value) = row.get::<_, Option<f64>>(i + 2)? {
+                point.insert(field.to_string(), Value::from(value));
+            }
+        }
+        Ok(Value::Object(point))
+    })?;
+    rows.collect()
+}
+
+pub fn count_samples(conn: &Connection, session_id: &str) -> SqlResult<usize> {
+    conn.query_row("SELECT COUNT(*) FROM telemetry_samples WHERE session_id = ?1", [session_id],
+                   |row| row.get::<_, i64>(0)).map(|count| count as usize)
+}
+
+/// Replace the samples under `to` with a copy of those under `from`, without leaving SQLite
+pub fn copy_samples(conn: &Connection, from: &str, to: &str) -> SqlResult<usize> {
+    delete_samples(conn, to)?;
+    let sql = format!(
+        "INSERT INTO telemetry_samples (session_id, seq, timestamp_ms, model, {columns}, extra)
+         SELECT ?2, seq, timestamp_ms, model, {columns}, extra FROM telemetry_samples WHERE session_id = ?1",
+        columns = METRIC_COLUMNS.iter().map(|(_, column)| *column).collect::<Vec<_>>().join(", ")
+    );
+    conn.execute(&sql, [from, to])
+}
+
+pub fn delete_samples(conn: &Connection, session_id: &str) -> SqlResult<usize> {
+    conn.execute("DELETE FROM telemetry_samples WHERE session_id = ?1", [session_id])
+}
+
+/// Drop the rows of every unsaved recording (left behind when the app last closed)
+pub fn delete_recordings(conn: &Connection) -> SqlResult<usize> {
+    conn.execute("DELETE FROM telemetry_samples WHERE session_id LIKE ?1",
+                 [format!("{}%", RECORDING_KEY_PREFIX)])
+}
+
+/// Per-model aggregates of a saved session's telemetry, computed in SQL. Peaks include the
+/// maxima kept for downsampled samples.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelTelemetryStats {
+    pub model: Option<String>,               // None for samples recorded outside a model's run
+    pub samples: usize,
+    pub duration_ms: Option<i64>,            // Last minus first timestamp
+    pub max_cpu_temp_c: Option<f64>,
+    pub max_gpu_temp_c: Option<f64>,
+    pub avg_package_power_w: Option<f64>,    // CPU + GPU + ANE, over samples with any power reading
+    pub peak_package_power_w: Option<f64>,   // Downsampled samples add each rail's maximum: an upper bound
+    pub avg_tps: Option<f64>,
+    pub total_energy_wh: Option<f64>,        // Largest cumulative energy reading
+}
+
+pub fn telemetry_stats(conn: &Connection, session_id: &str) -> SqlResult<Vec<ModelTelemetryStats>> {
+    let mut stmt = conn.prepare_cached("
+        SELECT model,
+               COUNT(*),
+               MAX(timestamp_ms) - MIN(timestamp_ms),
+               MAX(COALESCE(json_extract(extra, '$.merged_range.cpu_temp[1]'), cpu_temp_c)),
+               MAX(COALESCE(json_extract(extra, '$.merged_range.gpu_temp[1]'), gpu_temp_c)),
+               AVG(package_w),
+               MAX(peak_package_w),
+               AVG(tps),
+               MAX(total_energy_wh)
+        FROM (
+            SELECT *,
+                   CASE WHEN cpu_power_w IS NULL AND gpu_power_w IS NULL AND ane_power_w IS NULL THEN NULL
+                        ELSE COALESCE(cpu_power_w, 0) + COALESCE(gpu_power_w, 0) + COALESCE(ane_power_w, 0)
+                   END AS package_w,
+                   CASE WHEN cpu_power_w IS NULL AND gpu_power_w IS NULL AND ane_power_w IS NULL THEN NULL
+                        ELSE COALESCE(json_extract(extra, '$.merged_range.cpu_power[1]'), cpu_power_w, 0)
+                           + COALESCE(json_extract(extra, '$.merged_range.gpu_power[1]'), gpu_power_w, 0)
+                           + COALESCE(json_extract(extra, '$.merged_range.ane_power[1]'), ane_power_w, 0)
+                   END AS peak_package_w
+            FROM telemetry_samples
+            WHERE session_id = ?1
+        )
+        GROUP BY model
+        ORDER BY model
+    ")?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        Ok(ModelTelemetryStats {
+            model: row.get(0)?,
+            samples: row.get::<_, i64>(1)? as usize,
+            duration_ms: row.get(2)?,
+            max_cpu_temp_c: row.get(3)?,
+            max_gpu_temp_c: row.get(4)?,
+            avg_package_power_w: row.get(5)?,
+            peak_package_power_w: row.get(6)?,
+            avg_tps: row.get(7)?,
+            total_energy_wh: row.get(8)?,
+        })
+    })?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_samples_round_trip_and_aggregate_in_sql() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_telemetry_samples_table(&conn).unwrap();
+        let points: Vec<Value> = (0..600)
+            .map(|i| json!({
+                "timestamp": 1_000 + i * 100,
+                "model": if i < 300 { "A" } else { "B" },
+                "cpu_power": 4.0,
+                "gpu_power": if i % 2 == 0 { Value::Null } else { json!(2.0) },
+                "cpu_temp": 50.0 + (i % 300) as f64 / 10.0,
+                "cpu_p_core_temps": [61.5, 62.0],
+                "memory_pressure": "normal",
+            }))
+            .collect();
+        assert_eq!(insert_samples(&conn, "s1", &points).unwrap(), 600);
+
+        let loaded = load_samples(&conn, "s1").unwrap();
+        assert_eq!(loaded.len(), 600);
+        assert_eq!(loaded[1], json!({
+            "timestamp": 1_100, "model": "A", "cpu_power": 4.0, "gpu_power": 2.0, "cpu_temp": 50.1,
+            "cpu_p_core_temps": [61.5, 62.0], "memory_pressure": "normal",
+        }));
+        assert!(loaded[0].get("gpu_power").is_none());
+        assert_eq!(loaded[599]["timestamp"], 60_900);
+
+        let stats = telemetry_stats(&conn, "s1").unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].model.as_deref(), Some("A"));
+        assert_eq!(stats[0].samples, 300);
+        assert_eq!(stats[0].duration_ms, Some(29_900));
+        assert_eq!(stats[0].max_cpu_temp_c, Some(79.9));
+        assert_eq!(stats[0].avg_package_power_w, Some(5.0));
+        assert_eq!(stats[0].peak_package_power_w, Some(6.0));
+
+        // Saving again replaces the samples
+        insert_samples(&conn, "s1", &points[..2]).unwrap();
+        assert_eq!(load_samples(&conn, "s1").unwrap().len(), 2);
+        assert_eq!(delete_samples(&conn, "s1").unwrap(), 2);
+        assert!(telemetry_stats(&conn, "s1").unwrap().is_empty());
+
+        // A downsampled sample reports its peak, not its average
+        insert_samples(&conn, "s2", &[json!({
+            "timestamp": 1, "model": "A", "cpu_temp": 60.0, "cpu_power": 6.0, "gpu_power": 1.75, "merged_samples": 2,
+            "merged_range": { "cpu_temp": [55.0, 65.0], "cpu_power": [3.0, 9.0], "gpu_power": [1.0, 2.5] },
+        })]).unwrap();
+        let stats = &telemetry_stats(&conn, "s2").unwrap()[0];
+        assert_eq!(stats.max_cpu_temp_c, Some(65.0));
+        assert_eq!(stats.peak_package_power_w, Some(11.5));
+    }
+
+    #[test]
+    fn test_recording_is_flushed_in_batches_and_copied_on_save() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_telemetry_samples_table(&conn).unwrap();
+        let recording = recording_key("r1");
+        let point = |i: i64| json!({ "timestamp": i, "model": "A", "tps": 20.0 });
+
+        assert_eq!(append_samples(&conn, &recording, &[point(1), point(2)]).unwrap(), 2);
+        assert_eq!(append_samples(&conn, &recording, &[point(3)]).unwrap(), 3);
+        let timestamps = |key: &str| load_samples(&conn, key).unwrap().iter()
+            .map(|p| p["timestamp"].as_i64().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps(&recording), vec![1, 2, 3]);
+
+        // Saving copies the rows; the recording keeps going for a later save
+        assert_eq!(copy_samples(&conn, &recording, "s1").unwrap(), 3);
+        append_samples(&conn, &recording, &[point(4)]).unwrap();
+        assert_eq!(timestamps("s1"), vec![1, 2, 3]);
+        assert_eq!(copy_samples(&conn, &recording, "s1").unwrap(), 4);
+
+        assert_eq!(recording_reference(&json!({ "recording": "r1" })), Some("r1"));
+        assert_eq!(recording_reference(&json!([point(1)])), None);
+        assert_eq!(delete_recordings(&conn).unwrap(), 4);
+        assert_eq!(count_samples(&conn, "s1").unwrap(), 4);
+    }
+}
@@ -0,0 +1,278 @@
+// Read-only web export: one self-contained HTML page (data inlined, no server or network)
+// rendering a saved session's charts and transcript, for sharing with people without the app
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::persistence::models::SavedSession;
+use crate::persistence::report_locale::ReportLocale;
+use crate::telemetry::temperature_unit::{add_converted_fields, TemperatureUnit, TEMPERATURE_FIELDS};
+
+// Telemetry series charted on the page: (field, title, unit)
+const CHARTED_METRICS: [(&str, &str, &str); 6] = [
+    ("tps", "Tokens per second", "tok/s"),
+    ("cpu_power", "CPU power", "W"),
+    ("gpu_power", "GPU power", "W"),
+    ("cpu_temp", "CPU temperature", "°C"),
+    ("gpu_temp", "GPU temperature", "°C"),
+    ("ram_usage", "RAM usage", "GB"),
+];
+
+const PAGE_STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 0 auto; max-width: 960px; padding: 24px; color: #1f2937; }
+h1 { font-size: 22px; margin-bottom: 4px; }
+h2 { font-size: 16px; margin-top: 32px; border-bottom: 1px solid #e5e7eb; padding-bottom: 4px; }
+.meta { color: #6b7280; font-size: 13px; }
+table { border-collapse: collapse; font-size: 13px; }
+td, th { border: 1px solid #e5e7eb; padding: 4px 10px; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+.chart { margin: 12px 0; }
+.chart h3 { font-size: 13px; margin: 0 0 4px; }
+.legend span { font-size: 12px; margin-right: 12px; }
+.msg { border-radius: 8px; padding: 8px 12px; margin: 8px 0; white-space: pre-wrap; font-size: 14px; }
+.msg.user { background: #eff6ff; }
+.msg.assistant { background: #f9fafb; border: 1px solid #e5e7eb; }
+.msg .who { font-size: 11px; color: #6b7280; margin-bottom: 4px; }
+"#;
+
+// Renders from the inlined JSON with textContent only, so transcript text is never parsed as HTML
+const PAGE_SCRIPT: &str = r#"
+const data = JSON.parse(document.getElementById('session-data').textContent);
+const num = (v, digits) => v.toFixed(digits).replace('.', data.decimal_separator);
+const COLORS = { A: '#2563eb', B: '#ea580c' };
+const el = (tag, attrs = {}, text) => {
+  const node = document.createElement(tag);
+  Object.entries(attrs).forEach(([k, v]) => node.setAttribute(k, v));
+  if (text !== undefined) node.textContent = text;
+  return node;
+};
+const svgEl = (tag, attrs) => {
+  const node = document.createElementNS('http://www.w3.org/2000/svg', tag);
+  Object.entries(attrs).forEach(([k, v]) => node.setAttribute(k, v));
+  return node;
+};
+
+const summary = document.getElementById('summary');
+const models = Object.keys(data.summary_stats || {}).sort();
+const metrics = [...new Set(models.flatMap(m => Object.keys(data.summary_stats[m] || {})))]
+  .filter(k => models.some(m => typeof data.summary_stats[m][k] === 'number'));
+if (metrics.length) {
+  const table = el('table');
+  const head = el('tr');
+  head.appendChild(el('th', {}, 'Metric'));
+  models.forEach(m => head.appendChild(el('th', {}, `Model ${m}` + (data.models[m] ? ` (${data.models[m]})` : ''))));
+  table.appendChild(head);
+  metrics.forEach(k => {
+    const row = el('tr');
+    row.appendChild(el('td', {}, k));
+    models.forEach(m => {
+      const v = data.summary_stats[m][k];
+      row.appendChild(el('td', {}, typeof v === 'number' ? (Number.isInteger(v) ? String(v) : num(v, 3)) : '—'));
+    });
+    table.appendChild(row);
+  });
+  summary.appendChild(table);
+} else {
+  summary.appendChild(el('p', { class: 'meta' }, 'No summary statistics recorded.'));
+}
+
+const charts = document.getElementById('charts');
+const t0 = data.telemetry.length ? data.telemetry[0].timestamp : 0;
+data.metrics.forEach(([field, title, unit]) => {
+  const series = {};
+  data.telemetry.forEach(p => {
+    if (typeof p[field] !== 'number') return;
+    const model = p.model || 'A';
+    (series[model] = series[model] || []).push([(p.timestamp - t0) / 1000, p[field]]);
+  });
+  const all = Object.values(series).flat();
+  if (!all.length) return;
+  const [W, H, P] = [900, 160, 36];
+  const xMax = Math.max(...all.map(p => p[0]), 1);
+  const yMin = Math.min(...all.map(p => p[1]), 0);
+  const yMax = Math.max(...all.map(p => p[1]), yMin + 1);
+  const x = v => P + (v / xMax) * (W - 2 * P);
+  const y = v => H - P / 2 - ((v - yMin) / (yMax - yMin)) * (H - P);
+  const box = el('div', { class: 'chart' });
+  box.appendChild(el('h3', {}, `${title} (${unit})`));
+  const svg = svgEl('svg', { viewBox: `0 0 ${W} ${H}`, width: '100%' });
+  svg.appendChild(svgEl('line', { x1: P, y1: y(yMin), x2: W - P, y2: y(yMin), stroke: '#d1d5db' }));
+  [[yMax, y(yMax)], [yMin, y(yMin)]].forEach(([v, py]) => {
+    const label = svgEl('text', { x: 2, y: py + 4, 'font-size': 10, fill: '#6b7280' });
+    label.textContent = num(v, 1);
+    svg.appendChild(label);
+  });
+  const end = svgEl('text', { x: W - P, y: H - 2, 'font-size': 10, fill: '#6b7280', 'text-anchor': 'end' });
+  end.textContent = `${num(xMax, 0)} s`;
+  svg.appendChild(end);
+  Object.entries(series).forEach(([model, points]) => {
+    svg.appendChild(svgEl('polyline', {
+      points: points.map(([px, py]) => `${x(px).toFixed(1)},${y(py).toFixed(1)}`).join(' '),
+      fill: 'none', stroke: COLORS[model] || '#6b7280', 'stroke-width': 1.5,
+    }));
+  });
+  box.appendChild(svg);
+  const legend = el('div', { class: 'legend' });
+  Object.keys(series).sort().forEach(m => {
+    const item = el('span', {}, `■ Model ${m}`);
+    item.style.color = COLORS[m] || '#6b7280';
+    legend.appendChild(item);
+  });
+  box.appendChild(legend);
+  charts.appendChild(box);
+});
+if (!charts.children.length) charts.appendChild(el('p', { class: 'meta' }, 'No telemetry recorded.'));
+
+const transcript = document.getElementById('transcript');
+(data.chat_history || []).forEach(msg => {
+  const role = msg.role === 'user' ? 'user' : 'assistant';
+  const box = el('div', { class: `msg ${role}` });
+  const stats = [msg.avg_tps && `${num(msg.avg_tps, 1)} tok/s`, msg.ttft_ms && `TTFT ${msg.ttft_ms} ms`]
+    .filter(Boolean).join(' · ');
+  const who = role === 'user' ? 'User' : `Model ${msg.model || ''}`;
+  box.appendChild(el('div', { class: 'who' }, stats ? `${who} — ${stats}` : who));
+  box.appendChild(el('div', {}, msg.content || ''));
+  transcript.appendChild(box);
+});
+if (!transcript.children.length) transcript.appendChild(el('p', { class: 'meta' }, 'No messages.'));
+"#;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// `<` only occurs inside JSON strings, where \u003c is equivalent; this keeps
+// "</script>" in a transcript from closing the data block
+fn embed_json(value: &Value) -> String {
+    value.to_string().replace('<', "\\u003c")
+}
+
+// File name only: full paths reveal the exporter's home directory layout
+fn model_file_name(session_data: &Value, key: &str) -> Option<String> {
+    let path = session_data.pointer(&format!("/configuration/{}/model_path", key))?.as_str()?;
+    Path::new(path).file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+// Charted series with temperatures switched to their `_f` copies when showing Fahrenheit
+fn charted_metrics(unit: TemperatureUnit) -> Vec<(String, &'static str, &'static str)> {
+    CHARTED_METRICS.iter()
+        .map(|&(field, title, metric_unit)| {
+            if unit == TemperatureUnit::Fahrenheit && TEMPERATURE_FIELDS.contains(&field) {
+                (format!("{}_f", field), title, unit.symbol())
+            } else {
+                (field.to_string(), title, metric_unit)
+            }
+        })
+        .collect()
+}
+
+/// Render a saved session (with its telemetry already decompressed) as a standalone page,
+/// temperatures shown in `unit` and numbers and dates formatted for `locale`
+pub fn render_session_page(session: &SavedSession, telemetry: &[Value], unit: TemperatureUnit, locale: &ReportLocale) -> String {
+    let data = &session.session_data;
+    let telemetry: Vec<Value> = telemetry.iter()
+        .map(|point| {
+            let mut point = point.clone();
+            add_converted_fields(&mut point, unit);
+            point
+        })
+        .collect();
+    let page_data = json!({
+        "name": session.name,
+        "created_at": session.created_at,
+        "models": {
+            "A": model_file_name(data, "model_a"),
+            "B": model_file_name(data, "model_b"),
+        },
+        "summary_stats": data.get("summary_stats").cloned().unwrap_or(Value::Null),
+        "chat_history": data.get("chat_history").cloned().unwrap_or(Value::Array(Vec::new())),
+        "telemetry": telemetry,
+        "metrics": charted_metrics(unit),
+        "temperature_unit": unit,
+        "decimal_separator": locale.decimal_char(),
+    });
+    let created = locale.format_timestamp_ms(session.created_at * 1000);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} — apples2oranges</title>
+<style>{style}</style>
+</head>
+<body>
+<h1>{title}</h1>
+<div class="meta">Recorded {created} · exported from apples2oranges {version} · read-only</div>
+<h2>Summary</h2>
+<div id="summary"></div>
+<h2>Telemetry</h2>
+<div id="charts"></div>
+<h2>Transcript</h2>
+<div id="transcript"></div>
+<script type="application/json" id="session-data">{data}</script>
+<script>{script}</script>
+</body>
+</html>
+"#,
+        title = escape_html(&session.name),
+        style = PAGE_STYLE,
+        created = created,
+        version = env!("CARGO_PKG_VERSION"),
+        data = embed_json(&page_data),
+        script = PAGE_SCRIPT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_inlines_data_and_escapes_untrusted_text() {
+        let session = SavedSession::new(
+            "Q4 <vs> Q8".to_string(),
+            json!({
+                "chat_history": [{ "role": "assistant", "model": "A", "content": "</script><script>alert(1)</script>" }],
+                "configuration": { "model_a": { "model_path": "/Users/me/models/llama-q4.gguf" } },
+                "summary_stats": { "A": { "avg_tps": 41.5 } },
+            }),
+        );
+        let telemetry = vec![json!({ "timestamp": 1000, "model": "A", "tps": 40.0 })];
+        let page = render_session_page(&session, &telemetry, TemperatureUnit::Celsius, &ReportLocale::default());
+
+        assert!(page.contains("<title>Q4 &lt;vs&gt; Q8 — apples2oranges</title>"));
+        // The only closing script tags are the page's own two
+        assert_eq!(page.matches("</script>").count(), 2);
+        assert!(page.contains("\\u003c/script>\\u003cscript>alert(1)"));
+        assert!(page.contains("\"llama-q4.gguf\""));
+        assert!(!page.contains("/Users/me"));
+
+        // The inlined block round-trips to the original data
+        let start = page.find("id=\"session-data\">").unwrap() + "id=\"session-data\">".len();
+        let end = start + page[start..].find("</script>").unwrap();
+        let data: Value = serde_json::from_str(&page[start..end]).unwrap();
+        assert_eq!(data["telemetry"][0]["tps"], 40.0);
+        assert_eq!(data["chat_history"][0]["content"], "</script><script>alert(1)</script>");
+        assert_eq!(data["metrics"][3][2], "°C");
+    }
+
+    #[test]
+    fn test_fahrenheit_page_charts_converted_temperatures() {
+        let session = SavedSession::new("run".to_string(), json!({}));
+        let telemetry = vec![json!({ "timestamp": 1000, "model": "A", "cpu_temp": 50.0 })];
+        let page = render_session_page(&session, &telemetry, TemperatureUnit::Fahrenheit, &ReportLocale::default());
+        let start = page.find("id=\"session-data\">").unwrap() + "id=\"session-data\">".len();
+        let end = start + page[start..].find("</script>").unwrap();
+        let data: Value = serde_json::from_str(&page[start..end]).unwrap();
+        assert_eq!(data["metrics"][3], json!(["cpu_temp_f", "CPU temperature", "°F"]));
+        // Recorded Celsius stays in the data next to the converted copy
+        assert_eq!(data["telemetry"][0]["cpu_temp"], 50.0);
+        assert_eq!(data["telemetry"][0]["cpu_temp_f"], 122.0);
+    }
+}
@@ -0,0 +1,228 @@
+// Just enough HTTP/1.1 for the API server: one request per connection, bodies sized by
+// Content-Length (no chunked uploads), and the connection closed after the response
+
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,   // Without the query string
+    pub headers: Vec<(String, String)>,   // Names lowercased
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// First value of the header `name` (lowercase)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Error answered with `status` and an OpenAI-style error body
+#[derive(Debug)]
+pub struct HttpError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl HttpError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+}
+
+// Method, path, headers and Content-Length of a request head
+type RequestHead = (String, String, Vec<(String, String)>, usize);
+
+// Parse a request head (everything before the blank line)
+fn parse_head(head: &str) -> Result<RequestHead, HttpError> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(HttpError::new(400, format!("Malformed request line '{}'", request_line)));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::new(505, format!("Unsupported HTTP version {}", version)));
+    }
+
+    let mut content_length = 0;
+    let mut headers = Vec::new();
+    for line in lines.filter(|line| !line.is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(HttpError::new(400, format!("Malformed header '{}'", line)));
+        };
+        let value = value.trim();
+        headers.push((name.trim().to_ascii_lowercase(), value.to_string()));
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse()
+                .map_err(|_| HttpError::new(400, format!("Invalid Content-Length '{}'", value)))?;
+        } else if name.eq_ignore_ascii_case("transfer-encoding") && !value.eq_ignore_ascii_case("identity") {
+            return Err(HttpError::new(411, "Chunked request bodies are not supported; send Content-Length"));
+        }
+    }
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    Ok((method.to_ascii_uppercase(), path, headers, content_length))
+}
+
+fn find_head_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// Read one request from `stream`
+pub async fn read_request<R: AsyncRead + Unpin>(stream: &mut R) -> Result<HttpRequest, HttpError> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(end) = find_head_end(&buf) {
+            break end;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(HttpError::new(431, "Request headers too large"));
+        }
+        let read = stream.read(&mut chunk).await.map_err(|e| HttpError::new(400, e.to_string()))?;
+        if read == 0 {
+            return Err(HttpError::new(400, "Connection closed before the request was complete"));
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buf[..head_end]).map_err(|_| HttpError::new(400, "Request head is not UTF-8"))?;
+    let (method, path, headers, content_length) = parse_head(head)?;
+    if content_length > MAX_BODY_BYTES {
+        return Err(HttpError::new(413, format!("Request body over {} bytes", MAX_BODY_BYTES)));
+    }
+
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await.map_err(|e| HttpError::new(400, e.to_string()))?;
+        if read == 0 {
+            return Err(HttpError::new(400, "Connection closed before the request body was complete"));
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+    Ok(HttpRequest { method, path, headers, body })
+}
+
+// Host header names the server answers to; anything else may be a DNS-rebound page
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    name.eq_ignore_ascii_case("localhost") || name == "127.0.0.1"
+}
+
+/// Reject anything a web page could send: requests carrying an Origin (browsers add it to
+/// cross-origin fetches and form posts), a Host other than localhost/127.0.0.1 (DNS
+/// rebinding), no or a wrong bearer token, and POST bodies that are not declared as JSON
+pub fn authorize(request: &HttpRequest, token: &str) -> Result<(), HttpError> {
+    if request.header("origin").is_some() {
+        return Err(HttpError::new(403, "Browser requests are not accepted"));
+    }
+    if !request.header("host").is_some_and(is_loopback_host) {
+        return Err(HttpError::new(403, "Host must be localhost or 127.0.0.1"));
+    }
+    let presented = request.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+    // Compare every byte so the time taken does not reveal the matching prefix
+    let matches = presented.is_some_and(|presented| {
+        presented.len() == token.len()
+            && presented.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+    });
+    if !matches {
+        return Err(HttpError::new(401, "Missing or invalid API token; send Authorization: Bearer <token>"));
+    }
+    let is_json = request.header("content-type")
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"));
+    if request.method == "POST" && !is_json {
+        return Err(HttpError::new(415, "Content-Type must be application/json"));
+    }
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        499 => "Client Closed Request",
+        500 => "Internal Server Error",
+        505 => "HTTP Version Not Supported",
+        _ => "",
+    }
+}
+
+/// Complete HTTP response with a JSON body
+pub fn json_response(status: u16, body: &Value) -> Vec<u8> {
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason_phrase(status), body.len(), body
+    ).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_head_and_body() {
+        tauri::async_runtime::block_on(async {
+            let raw = b"post /v1/chat/completions?x=1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 7\r\n\r\n{\"a\":1}trailing";
+            let request = read_request(&mut &raw[..]).await.unwrap();
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/v1/chat/completions");
+            assert_eq!(request.body, b"{\"a\":1}");
+            assert_eq!(request.header("host"), Some("localhost"));
+
+            let no_body = read_request(&mut &b"GET /v1/models HTTP/1.1\r\n\r\n"[..]).await.unwrap();
+            assert!(no_body.body.is_empty());
+
+            let truncated = read_request(&mut &b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nabc"[..]).await.unwrap_err();
+            assert_eq!(truncated.status, 400);
+            let chunked = read_request(&mut &b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"[..]).await.unwrap_err();
+            assert_eq!(chunked.status, 411);
+            assert_eq!(read_request(&mut &b"nonsense\r\n\r\n"[..]).await.unwrap_err().status, 400);
+        });
+
+        let response = String::from_utf8(json_response(404, &serde_json::json!({ "ok": false }))).unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(response.ends_with("Content-Length: 12\r\nConnection: close\r\n\r\n{\"ok\":false}"));
+    }
+
+    #[test]
+    fn test_authorize_rejects_browser_and_unauthenticated_requests() {
+        let request = |headers: &[(&str, &str)]| HttpRequest {
+            method: "POST".to_string(),
+            path: "/v1/chat/completions".to_string(),
+            headers: headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+            body: Vec::new(),
+        };
+        let valid = [("host", "127.0.0.1:8080"), ("authorization", "Bearer secret"), ("content-type", "application/json; charset=utf-8")];
+        assert!(authorize(&request(&valid), "secret").is_ok());
+        assert!(authorize(&request(&[("host", "localhost"), valid[1], valid[2]]), "secret").is_ok());
+
+        let status = |headers: &[(&str, &str)]| authorize(&request(headers), "secret").unwrap_err().status;
+        assert_eq!(status(&[valid[0], valid[1], valid[2], ("origin", "https://evil.example")]), 403);
+        assert_eq!(status(&[("host", "evil.example:8080"), valid[1], valid[2]]), 403);
+        assert_eq!(status(&[valid[1], valid[2]]), 403);
+        assert_eq!(status(&[valid[0], valid[2]]), 401);
+        assert_eq!(status(&[valid[0], ("authorization", "Bearer secreT"), valid[2]]), 401);
+        assert_eq!(status(&[valid[0], valid[1], ("content-type", "text/plain")]), 415);
+        assert_eq!(status(&[valid[0], valid[1]]), 415);
+    }
+}
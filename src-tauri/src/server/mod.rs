@@ -0,0 +1,166 @@
+// Optional local HTTP server with an OpenAI-compatible `/v1/chat/completions` endpoint, so
+// scripts and evaluation harnesses can drive the same instrumented inference pipeline as the
+// UI. Each request runs as a Model A generation turn: telemetry, events and the run manifest
+// are recorded exactly as for a turn started from the app.
+//
+// Listens on 127.0.0.1 only and serves one request per connection. Every request must carry
+// the bearer token generated at app launch, and requests that look like they come from a web
+// page (an Origin header, or a Host other than localhost) are refused, so a site open in a
+// browser cannot start runs. Requests are answered in turn; one arriving while a run is
+// active gets 409 like any overlapping run.
+
+pub mod http;
+pub mod openai;
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde_json::json;
+use tauri::{Manager, Window};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::commands::generation::execute_generation_turn;
+use crate::error::A2oError;
+use crate::persistence::database::SessionDatabase;
+use crate::server::http::{authorize, json_response, read_request, HttpError, HttpRequest};
+use crate::server::openai::{completion_response, error_body, generation_config, ChatCompletionRequest};
+
+// A client has this long to send its request; the generation itself is not time-limited
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct RunningServer {
+    local_addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+static API_SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+static API_TOKEN: OnceLock<String> = OnceLock::new();
+
+/// Bearer token clients must send; a new one is generated each time the app starts
+pub fn api_server_token() -> &'static str {
+    API_TOKEN.get_or_init(|| format!("a2o-{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple()))
+}
+
+/// Address the server is listening on, if it is running
+pub fn api_server_address() -> Option<SocketAddr> {
+    API_SERVER.lock().ok().and_then(|server| server.as_ref().map(|server| server.local_addr))
+}
+
+/// Start listening on 127.0.0.1:`port` (0 picks a free port); requests run against `window`
+pub async fn start_api_server(window: Window, port: u16) -> Result<SocketAddr, A2oError> {
+    if let Some(addr) = api_server_address() {
+        return Err(A2oError::InvalidInput(format!("API server is already listening on http://{}", addr)));
+    }
+    let listener = TcpListener::bind(("127.0.0.1", port)).await
+        .map_err(|e| A2oError::Io(format!("Failed to bind API server on port {}: {}", port, e)))?;
+    let local_addr = listener.local_addr().map_err(|e| A2oError::Io(e.to_string()))?;
+
+    let (shutdown, mut shutdown_rx) = oneshot::channel();
+    {
+        let mut server = API_SERVER.lock().map_err(|_| "API server state poisoned".to_string())?;
+        if let Some(running) = server.as_ref() {
+            return Err(A2oError::InvalidInput(format!("API server is already listening on http://{}", running.local_addr)));
+        }
+        *server = Some(RunningServer { local_addr, shutdown });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        tauri::async_runtime::spawn(handle_connection(stream, window.clone()));
+                    }
+                    Err(e) => println!("⚠️ API server accept failed: {}", e),
+                },
+            }
+        }
+        println!("🔌 API server on http://{} stopped", local_addr);
+    });
+    println!("🔌 API server listening on http://{}/v1/chat/completions", local_addr);
+    Ok(local_addr)
+}
+
+/// Stop accepting connections; requests already running finish. Returns false when the
+/// server was not running.
+pub fn stop_api_server() -> bool {
+    let running = API_SERVER.lock().ok().and_then(|mut server| server.take());
+    match running {
+        Some(running) => {
+            let _ = running.shutdown.send(());
+            true
+        }
+        None => false,
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, window: Window) {
+    let response = match tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => {
+            println!("🔌 API {} {}", request.method, request.path);
+            let routed = match authorize(&request, api_server_token()) {
+                Ok(()) => route(request, &window).await,
+                Err(error) => {
+                    println!("⚠️ API request refused: {}", error.message);
+                    Err(error)
+                }
+            };
+            match routed {
+                Ok(body) => json_response(200, &body),
+                Err(error) => json_response(error.status, &error_body(&error)),
+            }
+        }
+        Ok(Err(error)) => json_response(error.status, &error_body(&error)),
+        Err(_) => return,
+    };
+    let _ = stream.write_all(&response).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn route(request: HttpRequest, window: &Window) -> Result<serde_json::Value, HttpError> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/v1/chat/completions") => chat_completion(&request.body, window).await,
+        ("GET", "/v1/models") => list_models(window),
+        (_, "/v1/chat/completions" | "/v1/models") => {
+            Err(HttpError::new(405, format!("{} is not allowed on {}", request.method, request.path)))
+        }
+        _ => Err(HttpError::new(404, format!("No route for {} {}", request.method, request.path))),
+    }
+}
+
+// Models are looked up by file name (or stem) in the model library only, so a client cannot
+// make the app open arbitrary files
+fn resolve_model(model: &str, window: &Window) -> Result<String, HttpError> {
+    let library = window.state::<SessionDatabase>().model_library().map_err(A2oError::from)?;
+    library.into_iter()
+        .find(|file| file.file_name == model || Path::new(&file.file_name).file_stem().is_some_and(|stem| stem == model))
+        .map(|file| file.path)
+        .ok_or_else(|| HttpError::new(404, format!("Model '{}' is not in the model library", model)))
+}
+
+async fn chat_completion(body: &[u8], window: &Window) -> Result<serde_json::Value, HttpError> {
+    let request: ChatCompletionRequest = serde_json::from_slice(body)
+        .map_err(|e| HttpError::new(400, format!("Invalid chat completion request: {}", e)))?;
+    let model = request.model.clone();
+    let model_path = resolve_model(&model, window)?;
+    let config = generation_config(request, model_path)?;
+
+    let turn = execute_generation_turn(window.clone(), config).await?;
+    let result = turn.results.first()
+        .ok_or_else(|| HttpError::new(500, "The generation turn produced no result"))?;
+    Ok(completion_response(&turn.run_id, &model, chrono::Utc::now().timestamp(), result))
+}
+
+fn list_models(window: &Window) -> Result<serde_json::Value, HttpError> {
+    let library = window.state::<SessionDatabase>().model_library().map_err(A2oError::from)?;
+    let data: Vec<_> = library.iter()
+        .map(|file| json!({ "id": file.file_name, "object": "model", "created": file.modified_at, "owned_by": "local" }))
+        .collect();
+    Ok(json!({ "object": "list", "data": data }))
+}
@@ -0,0 +1,228 @@
+// OpenAI chat completion request/response shapes and their mapping onto a single-model
+// generation turn. Only what evaluation harnesses commonly send is accepted; streaming is not.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::A2oError;
+use crate::server::http::HttpError;
+use crate::telemetry::types::{GenerationConfig, GenerationResult, Message, ModelConfig, SamplerSeed};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContentPart {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    pub content: MessageContent,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum StopField {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,   // File name (or stem) of a model library entry
+    pub messages: Vec<ChatCompletionMessage>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub max_completion_tokens: Option<u32>,   // Newer name for max_tokens
+    pub stop: Option<StopField>,
+    pub seed: Option<u32>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    #[serde(default)]
+    pub stream: bool,
+    // llama.cpp server extensions
+    pub top_k: Option<i32>,
+    pub min_p: Option<f32>,
+    pub repeat_penalty: Option<f32>,
+}
+
+impl From<A2oError> for HttpError {
+    fn from(error: A2oError) -> Self {
+        let status = match error {
+            A2oError::InvalidInput(_) => 400,
+            A2oError::ModelNotFound(_) | A2oError::NotFound(_) => 404,
+            A2oError::RunInProgress { .. } | A2oError::ModelInUse(_) => 409,
+            A2oError::Cancelled => 499,
+            _ => 500,
+        };
+        HttpError::new(status, error.to_string())
+    }
+}
+
+/// OpenAI error body for `error`
+pub fn error_body(error: &HttpError) -> Value {
+    let kind = if error.status < 500 { "invalid_request_error" } else { "server_error" };
+    json!({ "error": { "message": error.message, "type": kind, "code": error.status } })
+}
+
+fn message_text(content: MessageContent) -> Result<String, HttpError> {
+    match content {
+        MessageContent::Text(text) => Ok(text),
+        MessageContent::Parts(parts) => parts.into_iter()
+            .map(|part| match (part.kind.as_str(), part.text) {
+                ("text", Some(text)) => Ok(text),
+                (kind, _) => Err(HttpError::new(400, format!("Unsupported message content part '{}'", kind))),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|texts| texts.join("")),
+    }
+}
+
+/// Generation turn for `request` on Model A with the model at `model_path`. Leading system
+/// messages become the system prompt; the rest are the chat history.
+pub fn generation_config(request: ChatCompletionRequest, model_path: String) -> Result<GenerationConfig, HttpError> {
+    if request.stream {
+        return Err(HttpError::new(400, "Streaming responses are not supported; set stream to false"));
+    }
+    if request.messages.is_empty() {
+        return Err(HttpError::new(400, "messages must not be empty"));
+    }
+
+    let mut system_prompts = Vec::new();
+    let mut chat_history = Vec::new();
+    for message in request.messages {
+        let content = message_text(message.content)?;
+        match message.role.as_str() {
+            "system" | "developer" if chat_history.is_empty() => system_prompts.push(content),
+            "system" | "user" | "assistant" => chat_history.push(Message { role: message.role, content, model: None }),
+            other => return Err(HttpError::new(400, format!("Unsupported message role '{}'", other))),
+        }
+    }
+    if chat_history.is_empty() {
+        return Err(HttpError::new(400, "messages need at least one user message"));
+    }
+
+    let stop_sequences = request.stop.map(|stop| match stop {
+        StopField::One(sequence) => vec![sequence],
+        StopField::Many(sequences) => sequences,
+    });
+    let defaults = ModelConfig::default();
+    let model_a = ModelConfig {
+        model_path,
+        temperature: request.temperature.or(defaults.temperature),
+        top_k: request.top_k.or(defaults.top_k),
+        top_p: request.top_p.or(defaults.top_p),
+        min_p: request.min_p.or(defaults.min_p),
+        repeat_penalty: request.repeat_penalty.or(defaults.repeat_penalty),
+        frequency_penalty: request.frequency_penalty.or(defaults.frequency_penalty),
+        presence_penalty: request.presence_penalty.or(defaults.presence_penalty),
+        max_tokens: request.max_completion_tokens.or(request.max_tokens),
+        stop_sequences: stop_sequences.filter(|sequences| !sequences.is_empty()),
+        seed: request.seed.map(SamplerSeed::Fixed),
+        ..defaults
+    };
+
+    Ok(GenerationConfig {
+        chat_history,
+        target: "A".to_string(),
+        model_a: Some(model_a),
+        system_prompt: (!system_prompts.is_empty()).then(|| system_prompts.join("\n\n")),
+        ..GenerationConfig::default()
+    })
+}
+
+/// OpenAI finish_reason for a GenerationResult finish_reason
+pub fn finish_reason(reason: &str) -> &'static str {
+    match reason {
+        "max_tokens" => "length",
+        _ => "stop",
+    }
+}
+
+/// chat.completion body for `result`; the run's telemetry summary is added under
+/// `a2o_telemetry` so harnesses can join it with the recorded run
+pub fn completion_response(run_id: &str, model: &str, created: i64, result: &GenerationResult) -> Value {
+    json!({
+        "id": format!("chatcmpl-{}", run_id),
+        "object": "chat.completion",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": result.text },
+            "finish_reason": finish_reason(&result.finish_reason),
+        }],
+        "usage": {
+            "prompt_tokens": result.input_tokens,
+            "completion_tokens": result.output_tokens,
+            "total_tokens": result.input_tokens + result.output_tokens,
+        },
+        "a2o_telemetry": {
+            "run_id": run_id,
+            "ttft_ms": result.ttft_ms,
+            "prefill_ms": result.prefill_ms,
+            "generation_time_ms": result.generation_time_ms,
+            "avg_tps": result.avg_tps,
+            "total_energy_wh": result.energy.as_ref().map(|energy| energy.total_energy_wh),
+            "energy_per_token_wh": result.energy.as_ref().and_then(|energy| energy.energy_per_token_wh),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: Value) -> ChatCompletionRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_request_maps_to_a_model_a_turn() {
+        let config = generation_config(request(json!({
+            "model": "qwen.gguf",
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": [{ "type": "text", "text": "Hi " }, { "type": "text", "text": "there" }] },
+                { "role": "assistant", "content": "Hello" },
+                { "role": "user", "content": "Bye" },
+            ],
+            "temperature": 0.0,
+            "max_tokens": 64,
+            "stop": "###",
+            "seed": 7,
+        })), "/models/qwen.gguf".to_string()).unwrap();
+        assert_eq!(config.target, "A");
+        assert_eq!(config.system_prompt.as_deref(), Some("Be brief."));
+        assert_eq!(config.chat_history.len(), 3);
+        assert_eq!(config.chat_history[0].content, "Hi there");
+        let model = config.model_a.unwrap();
+        assert_eq!(model.model_path, "/models/qwen.gguf");
+        assert_eq!(model.temperature, Some(0.0));
+        assert_eq!(model.top_k, ModelConfig::default().top_k);
+        assert_eq!(model.max_tokens, Some(64));
+        assert_eq!(model.stop_sequences, Some(vec!["###".to_string()]));
+        assert_eq!(model.seed, Some(SamplerSeed::Fixed(7)));
+
+        let messages = json!([{ "role": "user", "content": "Hi" }]);
+        let streamed = generation_config(request(json!({ "model": "m", "messages": messages, "stream": true })), String::new());
+        assert_eq!(streamed.unwrap_err().status, 400);
+        let tool = generation_config(request(json!({ "model": "m", "messages": [{ "role": "tool", "content": "{}" }] })), String::new());
+        assert_eq!(tool.unwrap_err().status, 400);
+        let only_system = generation_config(request(json!({ "model": "m", "messages": [{ "role": "system", "content": "x" }] })), String::new());
+        assert_eq!(only_system.unwrap_err().status, 400);
+
+        assert_eq!(finish_reason("max_tokens"), "length");
+        assert_eq!(finish_reason("stop_sequence"), "stop");
+        assert_eq!(HttpError::from(A2oError::ModelNotFound("x".to_string())).status, 404);
+    }
+}
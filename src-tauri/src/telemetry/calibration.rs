@@ -0,0 +1,246 @@
+// Measurement-overhead self-calibration
+// Measures what the app's own telemetry (sensor scans, sysinfo refreshes, event emission)
+// costs in CPU time and package power while inference is idle.
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::events::EventSink;
+use crate::hardware::start_enhanced_monitoring;
+use crate::telemetry::types::{IntegrationMethod, TelemetryProfile, TelemetryUpdate};
+
+// Time given to macmon/SMC to start producing samples before a phase is measured
+const CALIBRATION_WARMUP: Duration = Duration::from_secs(2);
+
+// Low sampling rate the target rate is compared against to get power cost per Hz
+const REFERENCE_SAMPLING_HZ: f32 = 1.0;
+
+// Used as the reference instead when the target rate is itself close to 1 Hz
+const HIGH_REFERENCE_SAMPLING_HZ: f32 = 10.0;
+
+// Used when the profile caps the rate so a higher reference is not possible
+const LOW_REFERENCE_SAMPLING_HZ: f32 = 0.25;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationPhase {
+    pub sampling_hz: Option<f32>,           // None = telemetry not running
+    pub duration_s: f64,
+    pub process_cpu_percent: f64,           // App process CPU time / wall time (100 = one core)
+    pub avg_package_power_w: Option<f64>,   // Mean CPU+GPU+ANE power over the phase
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OverheadCalibration {
+    pub calibrated_at_ms: u64,
+    pub profile: TelemetryProfile,          // Overhead only applies to runs using the same profile
+    pub target_sampling_hz: f32,
+    pub quiet: CalibrationPhase,
+    pub reference: CalibrationPhase,
+    pub target: CalibrationPhase,
+    pub cpu_overhead_percent: f64,          // Extra app CPU at the target rate vs no telemetry
+    pub power_per_hz_w: Option<f64>,        // Package power added per 1 Hz of sampling
+    pub power_overhead_w: Option<f64>,      // Estimated telemetry power at the target rate
+}
+
+impl OverheadCalibration {
+    /// Estimated telemetry power overhead when sampling at `sampling_hz`
+    pub fn power_overhead_at(&self, sampling_hz: f32) -> Option<f64> {
+        self.power_per_hz_w.map(|per_hz| (per_hz * sampling_hz as f64).max(0.0))
+    }
+}
+
+/// Overhead applied to a run's power summary
+#[derive(Debug, Clone, Copy)]
+pub struct OverheadCorrection {
+    pub watts: f64,
+    pub subtract: bool,     // When false the overhead is only reported
+}
+
+/// Run the three calibration phases: no telemetry, reference rate, target rate
+pub async fn run_overhead_calibration(
+    events: &dyn EventSink,
+    profile: TelemetryProfile,
+    target_sampling_hz: f32,
+    phase_duration: Duration,
+) -> OverheadCalibration {
+    let target_sampling_hz = profile.clamp_sampling_hz(target_sampling_hz);
+    let reference_hz = if target_sampling_hz > REFERENCE_SAMPLING_HZ * 2.0 {
+        REFERENCE_SAMPLING_HZ
+    } else if profile.clamp_sampling_hz(HIGH_REFERENCE_SAMPLING_HZ) > target_sampling_hz {
+        HIGH_REFERENCE_SAMPLING_HZ
+    } else {
+        LOW_REFERENCE_SAMPLING_HZ
+    };
+
+    println!("🧪 CALIBRATION: Measuring idle app overhead ({:?} profile, target {:.2}Hz, reference {:.2}Hz, {:?} per phase)",
+             profile, target_sampling_hz, reference_hz, phase_duration);
+    let quiet = measure_phase(events, profile, None, phase_duration).await;
+    let reference = measure_phase(events, profile, Some(reference_hz), phase_duration).await;
+    let target = measure_phase(events, profile, Some(target_sampling_hz), phase_duration).await;
+
+    let power_per_hz_w = power_per_hz(&reference, &target);
+    let calibration = OverheadCalibration {
+        calibrated_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        profile,
+        target_sampling_hz,
+        cpu_overhead_percent: (target.process_cpu_percent - quiet.process_cpu_percent).max(0.0),
+        power_per_hz_w,
+        power_overhead_w: power_per_hz_w.map(|per_hz| (per_hz * target_sampling_hz as f64).max(0.0)),
+        quiet,
+        reference,
+        target,
+    };
+
+    println!("🧪 CALIBRATION: CPU overhead {:.2}%, power overhead {:?}W at {:.1}Hz",
+             calibration.cpu_overhead_percent, calibration.power_overhead_w, target_sampling_hz);
+    calibration
+}
+
+/// Slope of package power against sampling rate between two monitored phases
+fn power_per_hz(a: &CalibrationPhase, b: &CalibrationPhase) -> Option<f64> {
+    let (hz_a, hz_b) = (a.sampling_hz? as f64, b.sampling_hz? as f64);
+    let (power_a, power_b) = (a.avg_package_power_w?, b.avg_package_power_w?);
+    if (hz_b - hz_a).abs() < f64::EPSILON {
+        return None;
+    }
+    Some((power_b - power_a) / (hz_b - hz_a))
+}
+
+async fn measure_phase(
+    events: &dyn EventSink,
+    profile: TelemetryProfile,
+    sampling_hz: Option<f32>,
+    duration: Duration,
+) -> CalibrationPhase {
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    let (telemetry_tx, mut telemetry_rx) = broadcast::channel::<TelemetryUpdate>(1000);
+    let telemetry_broadcaster = Arc::new(telemetry_tx);
+
+    let monitor_handle = sampling_hz.map(|hz| {
+        let broadcaster = telemetry_broadcaster.clone();
+        let stop = stop_signal.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_enhanced_monitoring(broadcaster, stop, None, Some(hz), profile, IntegrationMethod::default(), None, None).await {
+                println!("❌ Calibration monitoring error: {}", e);
+            }
+        })
+    });
+
+    if monitor_handle.is_some() {
+        tokio::time::sleep(CALIBRATION_WARMUP).await;
+        while telemetry_rx.try_recv().is_ok() {}
+    }
+
+    let cpu_start = process_cpu_time();
+    let wall_start = Instant::now();
+    let deadline = tokio::time::Instant::now() + duration;
+    let mut power_samples = Vec::new();
+
+    loop {
+        match tokio::time::timeout_at(deadline, telemetry_rx.recv()).await {
+            Ok(Ok(telemetry)) => {
+                // Emit like a real run so event serialization is part of the measured cost
+                let _ = events.emit("calibration_sample", &telemetry);
+                if let Some(power) = package_power(&telemetry) {
+                    power_samples.push(power);
+                }
+            }
+            Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+            Ok(Err(broadcast::error::RecvError::Closed)) => {
+                tokio::time::sleep_until(deadline).await;
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+
+    let wall_time = wall_start.elapsed();
+    let cpu_time = process_cpu_time().saturating_sub(cpu_start);
+
+    stop_signal.store(true, Ordering::Relaxed);
+    if let Some(handle) = monitor_handle {
+        let _ = handle.await;
+    }
+
+    let phase = CalibrationPhase {
+        sampling_hz,
+        duration_s: wall_time.as_secs_f64(),
+        process_cpu_percent: cpu_percent(cpu_time, wall_time),
+        avg_package_power_w: mean(&power_samples),
+        samples: power_samples.len(),
+    };
+    println!("🧪 CALIBRATION: Phase {:?}Hz - CPU {:.2}%, power {:?}W ({} samples)",
+             phase.sampling_hz, phase.process_cpu_percent, phase.avg_package_power_w, phase.samples);
+    phase
+}
+
+fn package_power(telemetry: &TelemetryUpdate) -> Option<f64> {
+    let parts = [telemetry.cpu_power_watts, telemetry.gpu_power_watts, telemetry.ane_power_watts];
+    if parts.iter().all(|p| p.is_none()) {
+        return None;
+    }
+    Some(parts.iter().flatten().sum())
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn cpu_percent(cpu_time: Duration, wall_time: Duration) -> f64 {
+    let wall_s = wall_time.as_secs_f64();
+    if wall_s <= 0.0 {
+        return 0.0;
+    }
+    cpu_time.as_secs_f64() / wall_s * 100.0
+}
+
+/// User + system CPU time consumed by this process so far
+fn process_cpu_time() -> Duration {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    if result != 0 {
+        return Duration::ZERO;
+    }
+    let to_duration = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+    to_duration(usage.ru_utime) + to_duration(usage.ru_stime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(sampling_hz: Option<f32>, avg_package_power_w: Option<f64>) -> CalibrationPhase {
+        CalibrationPhase {
+            sampling_hz,
+            duration_s: 10.0,
+            process_cpu_percent: 0.0,
+            avg_package_power_w,
+            samples: 10,
+        }
+    }
+
+    #[test]
+    fn test_power_per_hz_is_slope_between_phases() {
+        // 1 Hz -> 2.0 W, 11 Hz -> 2.5 W: 0.05 W per Hz
+        let slope = power_per_hz(&phase(Some(1.0), Some(2.0)), &phase(Some(11.0), Some(2.5))).unwrap();
+        assert!((slope - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_power_per_hz_needs_two_monitored_rates() {
+        assert!(power_per_hz(&phase(None, None), &phase(Some(10.0), Some(2.0))).is_none());
+        assert!(power_per_hz(&phase(Some(1.0), Some(2.0)), &phase(Some(1.0), Some(2.0))).is_none());
+        assert!(power_per_hz(&phase(Some(1.0), None), &phase(Some(10.0), Some(2.0))).is_none());
+    }
+}
@@ -0,0 +1,246 @@
+// Thermal waits between runs: let the CPU cool back to a baseline temperature and hold
+// off while macOS reports serious thermal pressure, so consecutive runs (Model A then B,
+// or the runs of a benchmark suite) start from comparable thermal states
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::events::EventSink;
+use crate::hardware::temperature::read_core_temperatures;
+use crate::hardware::thermal_pressure::ThermalPressureMonitor;
+use crate::telemetry::processor::GLOBAL_STOP_SIGNAL;
+use crate::telemetry::temperature_unit::TemperatureUnit;
+
+const COOLDOWN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub const MAX_COOLDOWN_WAIT: Duration = Duration::from_secs(300);   // Safety cap
+pub const MAX_THERMAL_PRESSURE_WAIT: Duration = Duration::from_secs(900);
+
+#[derive(Clone, Serialize)]
+pub struct CooldownUpdateEvent {
+    pub state: String,              // "started" | "progress" | "complete" | "timeout" | "canceled"
+    pub baseline_c: Option<f64>,    // Baseline CPU max temp (°C)
+    pub margin_c: f64,              // Allowed margin above baseline (°C)
+    pub threshold_c: Option<f64>,   // Baseline + margin target (°C)
+    pub current_c: Option<f64>,     // Current CPU max temp (°C)
+    pub elapsed_s: Option<u64>,     // Seconds since start of cooldown
+    pub timestamp_ms: u64,          // Event timestamp
+}
+
+/// Cooldown update with its temperatures also given in the user's preferred unit
+#[derive(Clone, Serialize)]
+pub struct CooldownAlert {
+    #[serde(flatten)]
+    update: CooldownUpdateEvent,
+    unit: TemperatureUnit,
+    baseline: Option<f64>,
+    margin: f64,
+    threshold: Option<f64>,
+    current: Option<f64>,
+}
+
+impl CooldownUpdateEvent {
+    pub fn in_unit(self, unit: TemperatureUnit) -> CooldownAlert {
+        CooldownAlert {
+            unit,
+            baseline: self.baseline_c.map(|c| unit.from_celsius(c)),
+            margin: unit.delta_from_celsius(self.margin_c),
+            threshold: self.threshold_c.map(|c| unit.from_celsius(c)),
+            current: self.current_c.map(|c| unit.from_celsius(c)),
+            update: self,
+        }
+    }
+}
+
+/// Payload of `thermal_pressure_wait`, emitted while a run is deferred
+#[derive(Clone, Serialize)]
+pub struct ThermalPressureWaitEvent {
+    pub state: String,              // "waiting" | "cleared" | "timeout" | "canceled"
+    pub pressure: Option<String>,   // OS thermal pressure level, see thermal_pressure_name
+    pub elapsed_s: u64,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Complete,
+    Timeout,
+    Canceled,
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn stop_requested() -> bool {
+    GLOBAL_STOP_SIGNAL.read().ok()
+        .and_then(|guard| guard.as_ref().map(|stop_signal| stop_signal.load(Ordering::Relaxed)))
+        .unwrap_or(false)
+}
+
+/// Thermal pressure levels at which macOS is throttling hard (ProcessInfo's "serious"
+/// and "critical" states)
+pub fn is_serious_thermal_pressure(level: &str) -> bool {
+    matches!(level, "heavy" | "trapping" | "sleeping")
+}
+
+/// CPU max temperature to cool back down to, read before the first run; emits the
+/// "started" cooldown update
+pub async fn measure_cooldown_baseline(events: &dyn EventSink, margin_c: f64, unit: TemperatureUnit) -> Option<f64> {
+    println!("🌡️ Measuring baseline CPU temperature...");
+    match read_core_temperatures().await {
+        Ok(core_temp) => {
+            let baseline = core_temp.cpu_temp_max;
+            println!("🌡️ Baseline CPU max recorded: {:.1}°C", baseline);
+            let _ = events.emit("cooldown_update", CooldownUpdateEvent {
+                state: "started".to_string(),
+                baseline_c: Some(baseline),
+                margin_c,
+                threshold_c: Some(baseline + margin_c),
+                current_c: None,
+                elapsed_s: Some(0),
+                timestamp_ms: now_ms(),
+            }.in_unit(unit));
+            Some(baseline)
+        }
+        Err(e) => {
+            println!("⚠️ Failed to read baseline CPU temperature: {}. Proceeding without cooldown.", e);
+            None
+        }
+    }
+}
+
+/// Poll the CPU max temperature until it is back within `margin_c` of `baseline_c`,
+/// emitting `cooldown_update` as it goes. A stop request or a failed sensor read ends
+/// the wait early, as does `MAX_COOLDOWN_WAIT`.
+pub async fn wait_for_cpu_cooldown(events: &dyn EventSink, baseline_c: f64, margin_c: f64, unit: TemperatureUnit) -> WaitOutcome {
+    let threshold = baseline_c + margin_c;
+    println!("🧊 Waiting for CPU to cool to baseline + {:.1}°C (≤ {:.1}°C)...", margin_c, threshold);
+    let start_wait = Instant::now();
+    let emit = |state: &str, current_c: Option<f64>, elapsed_s: u64| {
+        let _ = events.emit("cooldown_update", CooldownUpdateEvent {
+            state: state.to_string(),
+            baseline_c: Some(baseline_c),
+            margin_c,
+            threshold_c: Some(threshold),
+            current_c,
+            elapsed_s: Some(elapsed_s),
+            timestamp_ms: now_ms(),
+        }.in_unit(unit));
+    };
+
+    loop {
+        if stop_requested() {
+            println!("🛑 Cooldown wait canceled by stop signal");
+            emit("canceled", None, start_wait.elapsed().as_secs());
+            return WaitOutcome::Canceled;
+        }
+
+        match read_core_temperatures().await {
+            Ok(core_temp) => {
+                let current_max = core_temp.cpu_temp_max;
+                let elapsed = start_wait.elapsed().as_secs();
+                println!("🌡️ Current CPU max: {:.1}°C (target ≤ {:.1}°C)", current_max, threshold);
+                emit("progress", Some(current_max), elapsed);
+                if current_max <= threshold {
+                    println!("✅ CPU cooled to within target threshold.");
+                    emit("complete", Some(current_max), elapsed);
+                    return WaitOutcome::Complete;
+                }
+            }
+            Err(e) => {
+                println!("⚠️ Failed to read CPU temperature during cooldown wait: {}. Proceeding without further wait.", e);
+                emit("canceled", None, start_wait.elapsed().as_secs());
+                return WaitOutcome::Canceled;
+            }
+        }
+
+        if start_wait.elapsed() >= MAX_COOLDOWN_WAIT {
+            println!("⏱️ Cooldown wait timed out after {} seconds.", MAX_COOLDOWN_WAIT.as_secs());
+            emit("timeout", None, MAX_COOLDOWN_WAIT.as_secs());
+            return WaitOutcome::Timeout;
+        }
+
+        tokio::time::sleep(COOLDOWN_POLL_INTERVAL).await;
+    }
+}
+
+/// Wait while the OS thermal pressure is serious or critical, emitting
+/// `thermal_pressure_wait`; returns at once when the level is lower or cannot be read
+pub async fn wait_for_thermal_pressure(events: &dyn EventSink, max_wait: Duration) -> WaitOutcome {
+    let monitor = match ThermalPressureMonitor::new() {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            println!("⚠️ Thermal pressure unavailable ({}); not deferring the run", e);
+            return WaitOutcome::Complete;
+        }
+    };
+    let start_wait = Instant::now();
+    let emit = |state: &str, pressure: Option<&str>| {
+        let _ = events.emit("thermal_pressure_wait", ThermalPressureWaitEvent {
+            state: state.to_string(),
+            pressure: pressure.map(str::to_string),
+            elapsed_s: start_wait.elapsed().as_secs(),
+            timestamp_ms: now_ms(),
+        });
+    };
+
+    let mut waited = false;
+    loop {
+        let pressure = monitor.read();
+        if !pressure.is_some_and(is_serious_thermal_pressure) {
+            if waited {
+                println!("✅ Thermal pressure back to {}; resuming", pressure.unwrap_or("unknown"));
+                emit("cleared", pressure);
+            }
+            return WaitOutcome::Complete;
+        }
+        if stop_requested() {
+            println!("🛑 Thermal pressure wait canceled by stop signal");
+            emit("canceled", pressure);
+            return WaitOutcome::Canceled;
+        }
+        if start_wait.elapsed() >= max_wait {
+            println!("⏱️ Thermal pressure still {} after {} seconds; running anyway", pressure.unwrap_or("unknown"), max_wait.as_secs());
+            emit("timeout", pressure);
+            return WaitOutcome::Timeout;
+        }
+        if !waited {
+            println!("🔥 Thermal pressure is {}; deferring the next run", pressure.unwrap_or("unknown"));
+            waited = true;
+        }
+        emit("waiting", pressure);
+        tokio::time::sleep(COOLDOWN_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serious_thermal_pressure_levels() {
+        assert!(!is_serious_thermal_pressure("nominal"));
+        assert!(!is_serious_thermal_pressure("moderate"));
+        assert!(is_serious_thermal_pressure("heavy"));
+        assert!(is_serious_thermal_pressure("trapping"));
+        assert!(is_serious_thermal_pressure("sleeping"));
+        assert!(!is_serious_thermal_pressure("unknown"));
+
+        let alert = serde_json::to_value(CooldownUpdateEvent {
+            state: "progress".to_string(),
+            baseline_c: Some(50.0),
+            margin_c: 2.0,
+            threshold_c: Some(52.0),
+            current_c: Some(60.0),
+            elapsed_s: Some(3),
+            timestamp_ms: 0,
+        }.in_unit(TemperatureUnit::Fahrenheit)).unwrap();
+        assert_eq!(alert["threshold_c"], 52.0);
+        assert_eq!(alert["current"], 140.0);
+    }
+}
@@ -0,0 +1,195 @@
+// Developer debug capture: every raw sampling tick (IOHID sensor readings, macmon line,
+// utilization) mirrored to a JSONL file, and replay of a capture through the aggregation
+// pipeline to debug sensor categorization on hardware the maintainers don't own
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::TelemetryUpdate;
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::hardware::aggregation::{aggregate_sample, RawSample};
+use crate::hardware::rates::TelemetryRates;
+use crate::hardware::temperature::TemperatureHistory;
+use crate::telemetry::power_calculator::PowerCalculator;
+use crate::telemetry::types::{IntegrationMethod, TelemetryProfile};
+
+/// Identifies the capture layout (header line, then one RawSample per line)
+pub const DEBUG_CAPTURE_SCHEMA: &str = "apples2oranges.debug-capture/v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugCaptureHeader {
+    pub schema: String,
+    pub app_version: String,
+    pub created_at_ms: u64,
+    pub run_id: String,
+    pub sampling_hz: f32,
+    pub profile: TelemetryProfile,
+    pub integration_method: IntegrationMethod,
+    pub hardware: HardwareSummary,
+}
+
+impl DebugCaptureHeader {
+    pub fn new(run_id: &str, sampling_hz: f32, profile: TelemetryProfile, integration_method: IntegrationMethod) -> Self {
+        Self {
+            schema: DEBUG_CAPTURE_SCHEMA.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            run_id: run_id.to_string(),
+            sampling_hz,
+            profile,
+            integration_method,
+            hardware: hardware_summary(),
+        }
+    }
+}
+
+pub struct DebugCaptureWriter {
+    path: PathBuf,
+    writer: Mutex<LineWriter<File>>,   // Flushed per line so a crash keeps everything up to it
+}
+
+pub type SharedDebugCapture = Arc<DebugCaptureWriter>;
+
+impl DebugCaptureWriter {
+    /// Create `<dir>/debug-capture-<local timestamp>.jsonl` and write the header line
+    pub fn create(dir: &Path, header: &DebugCaptureHeader) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create debug capture directory {}: {}", dir.display(), e))?;
+        let path = dir.join(format!("debug-capture-{}.jsonl", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+        let file = File::create(&path)
+            .map_err(|e| format!("Failed to create debug capture {}: {}", path.display(), e))?;
+        let capture = Self { path, writer: Mutex::new(LineWriter::new(file)) };
+        capture.write_line(&serde_json::to_string(header).map_err(|e| e.to_string())?)?;
+        Ok(capture)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one tick's raw readings; failures are logged and never interrupt sampling
+    pub fn record(&self, sample: &RawSample) {
+        let result = serde_json::to_string(sample)
+            .map_err(|e| e.to_string())
+            .and_then(|line| self.write_line(&line));
+        if let Err(e) = result {
+            println!("⚠️ Debug capture write failed: {}", e);
+        }
+    }
+
+    fn write_line(&self, line: &str) -> Result<(), String> {
+        let mut writer = self.writer.lock().map_err(|_| "Debug capture writer poisoned".to_string())?;
+        writeln!(writer, "{}", line).map_err(|e| format!("Failed to write debug capture: {}", e))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugCaptureReplay {
+    pub header: DebugCaptureHeader,
+    pub samples: Vec<TelemetryUpdate>,     // Aggregated as during the run, energy integrated over the whole capture
+    pub skipped_lines: Vec<String>,        // "line N: error" for lines that could not be parsed
+}
+
+/// Re-run every captured tick through aggregation and energy integration. Model phase
+/// markers are not captured, so energy accumulates across the whole capture.
+pub fn replay_debug_capture(path: &Path) -> Result<DebugCaptureReplay, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open debug capture {}: {}", path.display(), e))?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header_line = lines.next()
+        .ok_or_else(|| "Debug capture is empty".to_string())?
+        .map_err(|e| format!("Failed to read debug capture: {}", e))?;
+    let header: DebugCaptureHeader = serde_json::from_str(&header_line)
+        .map_err(|e| format!("Invalid debug capture header: {}", e))?;
+    if header.schema != DEBUG_CAPTURE_SCHEMA {
+        return Err(format!("Unsupported debug capture schema '{}'", header.schema));
+    }
+
+    let mut temp_history = TemperatureHistory::new(60);
+    let mut rates = TelemetryRates::new();
+    let mut power_calculator = PowerCalculator::with_method(header.integration_method);
+    let mut samples = Vec::new();
+    let mut skipped_lines = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line_number = index + 2;
+        let line = line.map_err(|e| format!("Failed to read debug capture: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RawSample>(&line) {
+            Ok(raw) => {
+                let telemetry = aggregate_sample(&raw, &mut temp_history, &mut rates);
+                samples.push(power_calculator.update_with_telemetry(telemetry));
+            }
+            Err(e) => skipped_lines.push(format!("line {}: {}", line_number, e)),
+        }
+    }
+
+    Ok(DebugCaptureReplay { header, samples, skipped_lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::aggregation::CpuUtilizationSample;
+    use crate::hardware::memory::MemoryPressureSample;
+
+    #[test]
+    fn test_capture_replays_through_aggregation() {
+        let dir = std::env::temp_dir().join(format!("a2o-debug-capture-{}", std::process::id()));
+        let header = DebugCaptureHeader::new("run-1", 1.0, TelemetryProfile::Full, IntegrationMethod::Trapezoid);
+        let capture = DebugCaptureWriter::create(&dir, &header).unwrap();
+
+        let readings = vec![
+            ("pACC MTR Temp Sensor0".to_string(), 61.0),
+            ("eACC MTR Temp Sensor1".to_string(), 49.0),
+            ("GPU MTR Temp Sensor4".to_string(), 55.0),
+            ("gas gauge battery".to_string(), 31.0),
+        ];
+        for tick in 0..3u64 {
+            capture.record(&RawSample {
+                timestamp_ms: 1_000 * (tick + 1),
+                temperature_readings: Some(Ok(readings.clone())),
+                macmon_line: Some(r#"{"timestamp":"t","cpu_power":12.5,"gpu_power":3.0,"ane_power":0.0}"#.to_string()),
+                cpu_utilization: Some(CpuUtilizationSample { p_cores: vec![80.0], e_cores: vec![20.0], overall: 50.0 }),
+                inference_cpu_utilization: Some(40.0),
+                gpu_utilization: None,
+                fan_rpm: Some(vec![1850.0]),
+                thermal_pressure: Some("moderate".to_string()),
+                memory_pressure: MemoryPressureSample {
+                    swap_usage_gb: Some(1.5),
+                    compressed_memory_gb: Some(0.75),
+                    memory_pressure_level: Some("warning".to_string()),
+                },
+            });
+        }
+        let path = capture.path().to_path_buf();
+        drop(capture);
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not json").unwrap();
+
+        let replay = replay_debug_capture(&path).unwrap();
+        assert_eq!(replay.header.run_id, "run-1");
+        assert_eq!(replay.samples.len(), 3);
+        assert_eq!(replay.skipped_lines.len(), 1);
+        let sample = &replay.samples[2];
+        assert_eq!(sample.cpu_p_core_temps, Some(vec![61.0]));
+        assert_eq!(sample.battery_temp_avg, Some(31.0));
+        assert_eq!(sample.cpu_power_watts, Some(12.5));
+        assert_eq!(sample.fan_rpm, Some(vec![1850.0]));
+        assert_eq!(sample.thermal_pressure.as_deref(), Some("moderate"));
+        assert_eq!(sample.swap_usage_gb, Some(1.5));
+        assert_eq!(sample.memory_pressure_level.as_deref(), Some("warning"));
+        assert_eq!(sample.inference_cpu_share, Some(80.0));
+        assert!(sample.total_energy_wh.unwrap() > 0.0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
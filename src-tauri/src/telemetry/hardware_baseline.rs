@@ -0,0 +1,240 @@
+// Hardware capability baseline: a short, fixed micro-benchmark (reference model, prompt,
+// greedy sampling, token budget) measuring what this machine does in TPS and power.
+// Sessions saved afterwards carry the baseline, so metrics from different machines can be
+// compared relative to each machine's own reference result.
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::commands::comparison_summary::{hardware_summary, HardwareSummary};
+use crate::error::A2oError;
+use crate::events::{NullSink, SharedEventSink};
+use crate::hardware::start_enhanced_monitoring;
+use crate::inference::model_cache::ModelCache;
+use crate::inference::generation::{resolve_model_path, run_model_inference, DecodeMetricsConfig, ModelRun};
+use crate::persistence::reproducibility::{fingerprint_model, ModelFingerprint};
+use crate::telemetry::types::{
+    ContextSize, GenerationResult, IntegrationMethod, ModelConfig, TelemetryProfile, TelemetryUpdate,
+    DEFAULT_ROLLING_TPS_WINDOW_SECS, DEFAULT_STALL_THRESHOLD_MS
+};
+
+/// Baselines are only comparable when produced by the same benchmark revision
+pub const BASELINE_BENCHMARK_VERSION: u32 = 1;
+
+const BASELINE_PROMPT: &str = "Explain in a few sentences why the sky is blue.";
+const BASELINE_MAX_TOKENS: u32 = 128;
+const BASELINE_N_CTX: u32 = 1024;
+const BASELINE_SAMPLING_HZ: f32 = 2.0;
+
+// Time given to macmon/SMC to start producing samples before the benchmark starts
+const BASELINE_WARMUP: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareBaseline {
+    pub benchmark_version: u32,
+    pub measured_at_ms: u64,
+    pub hardware: HardwareSummary,
+    pub reference_model: ModelFingerprint,  // Baselines from different models are not comparable
+    pub prompt_tokens: usize,
+    pub output_tokens: usize,
+    pub ttft_ms: Option<u64>,
+    pub prefill_tps: Option<f64>,
+    pub decode_tps: Option<f64>,
+    pub avg_package_power_w: Option<f64>,   // Mean CPU+GPU+ANE power from prefill to the last token
+    pub energy_per_token_wh: Option<f64>,   // Package energy over the generation / output tokens
+}
+
+/// Summarize a finished benchmark generation and the power sampled during it
+pub fn baseline_from_result(
+    result: &GenerationResult,
+    power_samples: &[f64],
+    reference_model: ModelFingerprint,
+    hardware: HardwareSummary,
+) -> HardwareBaseline {
+    let avg_package_power_w = (!power_samples.is_empty())
+        .then(|| power_samples.iter().sum::<f64>() / power_samples.len() as f64);
+    let energy_per_token_wh = avg_package_power_w
+        .filter(|_| result.output_tokens > 0)
+        .map(|watts| watts * (result.generation_time_ms as f64 / 3_600_000.0) / result.output_tokens as f64);
+    let prefill_tps = (result.prefill_ms > 0.0).then(|| result.input_tokens as f64 / (result.prefill_ms / 1000.0));
+
+    HardwareBaseline {
+        benchmark_version: BASELINE_BENCHMARK_VERSION,
+        measured_at_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        hardware,
+        reference_model,
+        prompt_tokens: result.input_tokens,
+        output_tokens: result.output_tokens,
+        ttft_ms: result.ttft_ms,
+        prefill_tps,
+        decode_tps: result.avg_tps,
+        avg_package_power_w,
+        energy_per_token_wh,
+    }
+}
+
+/// A session metric relative to the baseline of the machine it was recorded on
+/// (1.0 = same as the reference benchmark). None for metrics without a baseline counterpart.
+pub fn normalize_metric(metric: &str, value: f64, baseline: &HardwareBaseline) -> Option<f64> {
+    let reference = match metric {
+        "avg_tps" => baseline.decode_tps,
+        "prefill_tps" => baseline.prefill_tps,
+        "ttft_ms" => baseline.ttft_ms.map(|ms| ms as f64),
+        "energy_per_token_wh" => baseline.energy_per_token_wh,
+        _ => None,
+    }?;
+    (reference > 0.0).then(|| value / reference)
+}
+
+/// Run the benchmark with `model_path` as the reference model. Blocking: loads and runs the model.
+pub fn run_hardware_baseline(model_path: &str, model_cache: &ModelCache) -> Result<HardwareBaseline, A2oError> {
+    let resolved_path = resolve_model_path(model_path)
+        .ok_or_else(|| A2oError::ModelNotFound(format!("Model file not found at {} or in parent directories", model_path)))?;
+    let reference_model = fingerprint_model(&resolved_path.to_string_lossy())?;
+    let model_config = ModelConfig {
+        model_path: model_path.to_string(),
+        temperature: Some(0.0),     // Greedy, so every run generates the same tokens
+        n_ctx: Some(ContextSize::Fixed(BASELINE_N_CTX)),
+        max_tokens: Some(BASELINE_MAX_TOKENS),
+        ..ModelConfig::default()
+    };
+    let chat_history = vec![crate::Message {
+        role: "user".to_string(),
+        content: BASELINE_PROMPT.to_string(),
+        model: None,
+    }];
+    let decode_metrics = DecodeMetricsConfig {
+        rolling_tps_window: Duration::from_secs_f64(DEFAULT_ROLLING_TPS_WINDOW_SECS),
+        stall_threshold: Duration::from_secs_f64(DEFAULT_STALL_THRESHOLD_MS / 1000.0),
+    };
+
+    tauri::async_runtime::handle().block_on(async move {
+        println!("🏋️ BASELINE: Running hardware benchmark with {}", model_path);
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let (telemetry_tx, mut telemetry_rx) = broadcast::channel::<TelemetryUpdate>(1000);
+        let telemetry_broadcaster = Arc::new(telemetry_tx);
+        let monitor_handle = {
+            let broadcaster = telemetry_broadcaster.clone();
+            let stop = stop_signal.clone();
+            tokio::spawn(async move {
+                if let Err(e) = start_enhanced_monitoring(broadcaster, stop, None, Some(BASELINE_SAMPLING_HZ), TelemetryProfile::default(), IntegrationMethod::default(), None, None).await {
+                    println!("❌ Baseline monitoring error: {}", e);
+                }
+            })
+        };
+        tokio::time::sleep(BASELINE_WARMUP).await;
+        while telemetry_rx.try_recv().is_ok() {}
+
+        // Output goes nowhere: the benchmark must not show up in the chat
+        let events: SharedEventSink = Arc::new(NullSink);
+        let result = run_model_inference(&events, ModelRun {
+            model_config: &model_config,
+            chat_history: &chat_history,
+            model_label: "baseline",
+            system_prompt: None,
+            telemetry_broadcaster: None,
+            phase_markers: None,
+            output_stream: None,
+            measurement_overhead: None,
+            decode_metrics,
+        }, model_cache).await;
+
+        let finished_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        stop_signal.store(true, Ordering::Relaxed);
+        let _ = monitor_handle.await;
+        let result = result?;
+
+        // Only samples from prefill to the last token; model loading is not part of the baseline
+        let window_start_ms = finished_ms.saturating_sub(result.generation_time_ms + result.prefill_ms as u64);
+        let mut power_samples = Vec::new();
+        loop {
+            match telemetry_rx.try_recv() {
+                Ok(telemetry) if telemetry.timestamp_ms >= window_start_ms => {
+                    let parts = [telemetry.cpu_power_watts, telemetry.gpu_power_watts, telemetry.ane_power_watts];
+                    if parts.iter().any(Option::is_some) {
+                        power_samples.push(parts.iter().flatten().sum());
+                    }
+                }
+                Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+
+        let baseline = baseline_from_result(&result, &power_samples, reference_model, hardware_summary());
+        println!("🏋️ BASELINE: decode {:?} tok/s, prefill {:?} tok/s, power {:?}W ({} samples)",
+                 baseline.decode_tps, baseline.prefill_tps, baseline.avg_package_power_w, power_samples.len());
+        Ok(baseline)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::telemetry::types::{MemoryUsageEvent, SamplingParams};
+
+    #[test]
+    fn test_baseline_summary_and_normalization() {
+        let result = GenerationResult {
+            model: "baseline".to_string(),
+            text: "Rayleigh scattering.".to_string(),
+            finish_reason: "max_tokens".to_string(),
+            truncated: true,
+            stop_sequence: None,
+            input_tokens: 20,
+            output_tokens: 128,
+            model_load_ms: 900.0,
+            model_reused: false,
+            effective_context: None,
+            ttft_ms: Some(50),
+            prefill_ms: 40.0,
+            generation_time_ms: 3_600,
+            avg_tps: Some(40.0),
+            energy: None,
+            combined_energy: None,
+            memory: MemoryUsageEvent {
+                model: "baseline".to_string(),
+                baseline_gb: 8.0,
+                load_peak_gb: 9.0,
+                load_delta_gb: 1.0,
+                generation_peak_gb: 9.1,
+                generation_delta_gb: 1.1,
+                timestamp_ms: 0,
+            },
+            sampling: SamplingParams::default(),
+            context_used_tokens: 148,
+            n_ctx: 1024,
+            context_shifts: 0,
+            resumed_tokens: 0,
+            resume_diverged_at: None,
+            speculative: None,
+        };
+        let reference_model = ModelFingerprint { path: "tiny.gguf".to_string(), size_bytes: 1, fingerprint: "abc".to_string() };
+        let hardware = HardwareSummary { chip: "M2".to_string(), total_cores: 8, p_cores: 4, e_cores: 4, memory_gb: Some(16.0), os: "macos aarch64".to_string() };
+
+        let baseline = baseline_from_result(&result, &[9.0, 11.0], reference_model.clone(), hardware.clone());
+        assert_eq!(baseline.prefill_tps, Some(500.0));
+        assert_eq!(baseline.avg_package_power_w, Some(10.0));
+        // 10 W for 3.6 s = 0.01 Wh over 128 tokens
+        assert!((baseline.energy_per_token_wh.unwrap() - 0.01 / 128.0).abs() < 1e-12);
+
+        // A session twice as fast as this machine's reference, at half the energy per token
+        assert_eq!(normalize_metric("avg_tps", 80.0, &baseline), Some(2.0));
+        assert_eq!(normalize_metric("energy_per_token_wh", 0.005 / 128.0, &baseline).map(|r| (r * 1e6).round() / 1e6), Some(0.5));
+        assert_eq!(normalize_metric("output_tokens", 10.0, &baseline), None);
+
+        // No power readings (e.g. no sensors): TPS still recorded, energy unknown
+        let unpowered = baseline_from_result(&result, &[], reference_model, hardware);
+        assert_eq!(unpowered.energy_per_token_wh, None);
+        assert_eq!(normalize_metric("energy_per_token_wh", 1.0, &unpowered), None);
+    }
+}
@@ -0,0 +1,138 @@
+// Heat-soak normalization: before each model's run, bring the CPU to a target temperature
+// band, heating it with the synthetic matmul load when it is too cool and idling when it is
+// too hot, so A and B start from the same thermal state whichever runs first. Cooldown
+// waiting only removes the second model's handicap; soaking also removes the first one's
+// advantage of starting cold.
+
+use std::sync::{Arc, atomic::AtomicBool};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::events::EventSink;
+use crate::hardware::temperature::read_core_temperatures;
+use crate::telemetry::cooldown::WaitOutcome;
+use crate::telemetry::processor::{model_stop_requested, GLOBAL_STOP_SIGNAL};
+use crate::telemetry::synthetic_load::{run_workers, SyntheticLoadProfile};
+use crate::telemetry::temperature_unit::TemperatureUnit;
+
+pub const DEFAULT_HEAT_SOAK_BAND_C: f64 = 2.0;
+pub const MAX_HEAT_SOAK_WAIT: Duration = Duration::from_secs(600);   // Safety cap
+pub const HEAT_SOAK_TARGET_RANGE_C: (f64, f64) = (30.0, 95.0);
+
+// Load bursts are short so the temperature is rechecked before overshooting the band
+const HEAT_SOAK_BURST: Duration = Duration::from_secs(2);
+const HEAT_SOAK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoakAction {
+    Heat,     // Below the band: run the load
+    Cool,     // Above the band: stay idle
+    InBand,
+}
+
+/// What to do at `current_c` to reach `target_c` ± `band_c`
+pub fn soak_action(current_c: f64, target_c: f64, band_c: f64) -> SoakAction {
+    if current_c < target_c - band_c {
+        SoakAction::Heat
+    } else if current_c > target_c + band_c {
+        SoakAction::Cool
+    } else {
+        SoakAction::InBand
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct HeatSoakUpdateEvent {
+    pub model: String,             // Model about to run
+    pub state: String,             // "heating" | "cooling" | "complete" | "timeout" | "canceled"
+    pub target_c: f64,             // CPU max temperature the run should start at
+    pub band_c: f64,               // Allowed deviation either side
+    pub current_c: Option<f64>,
+    pub current: Option<f64>,      // current_c in the user's temperature unit
+    pub unit: TemperatureUnit,
+    pub elapsed_s: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Heat or idle until the CPU max temperature is within `band_c` of `target_c`, emitting
+/// `heat_soak_update` as it goes. A stop request, a failed sensor read or MAX_HEAT_SOAK_WAIT
+/// ends the soak early; the run then starts wherever the temperature is.
+pub async fn soak_to_band(events: &dyn EventSink, model: &str, target_c: f64, band_c: f64, unit: TemperatureUnit) -> WaitOutcome {
+    println!("♨️ HEAT SOAK: Bringing CPU to {:.1}±{:.1}°C before Model {}", target_c, band_c, model);
+    let start = Instant::now();
+    let emit = |state: &str, current_c: Option<f64>| {
+        let _ = events.emit("heat_soak_update", HeatSoakUpdateEvent {
+            model: model.to_string(),
+            state: state.to_string(),
+            target_c,
+            band_c,
+            current_c,
+            current: current_c.map(|c| unit.from_celsius(c)),
+            unit,
+            elapsed_s: start.elapsed().as_secs(),
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
+    };
+    // The load workers watch the run's stop signal, so a stop ends a burst at once
+    let stop_signal = GLOBAL_STOP_SIGNAL.read().ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_else(|| Arc::new(AtomicBool::new(false)));
+
+    loop {
+        if model_stop_requested(model) {
+            println!("🛑 HEAT SOAK: Canceled by stop signal");
+            emit("canceled", None);
+            return WaitOutcome::Canceled;
+        }
+        let current_c = match read_core_temperatures().await {
+            Ok(temps) => temps.cpu_temp_max,
+            Err(e) => {
+                println!("⚠️ HEAT SOAK: Failed to read CPU temperature ({}); starting the run as is", e);
+                emit("canceled", None);
+                return WaitOutcome::Canceled;
+            }
+        };
+        let action = soak_action(current_c, target_c, band_c);
+        if action == SoakAction::InBand {
+            println!("✅ HEAT SOAK: CPU at {:.1}°C after {}s", current_c, start.elapsed().as_secs());
+            emit("complete", Some(current_c));
+            return WaitOutcome::Complete;
+        }
+        if start.elapsed() >= MAX_HEAT_SOAK_WAIT {
+            println!("⏱️ HEAT SOAK: CPU still at {:.1}°C after {} seconds; running anyway", current_c, MAX_HEAT_SOAK_WAIT.as_secs());
+            emit("timeout", Some(current_c));
+            return WaitOutcome::Timeout;
+        }
+        match action {
+            SoakAction::Heat => {
+                emit("heating", Some(current_c));
+                let threads = SyntheticLoadProfile::CpuAll.worker_threads();
+                let stop = stop_signal.clone();
+                let _ = tokio::task::spawn_blocking(move || run_workers(threads, HEAT_SOAK_BURST, stop)).await;
+            }
+            _ => {
+                emit("cooling", Some(current_c));
+                tokio::time::sleep(HEAT_SOAK_POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soak_action_targets_the_band() {
+        assert_eq!(soak_action(45.0, 60.0, 2.0), SoakAction::Heat);
+        assert_eq!(soak_action(58.0, 60.0, 2.0), SoakAction::InBand);
+        assert_eq!(soak_action(62.0, 60.0, 2.0), SoakAction::InBand);
+        assert_eq!(soak_action(62.5, 60.0, 2.0), SoakAction::Cool);
+        // A zero band still accepts the exact target
+        assert_eq!(soak_action(60.0, 60.0, 0.0), SoakAction::InBand);
+    }
+}
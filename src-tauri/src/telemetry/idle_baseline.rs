@@ -0,0 +1,148 @@
+// Idle power baseline: package power and temperatures sampled with inference idle, so runs
+// can report the energy the model itself added on top of whatever else the Mac was doing
+// (background apps, display, the telemetry sampling itself).
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::events::EventSink;
+use crate::hardware::start_enhanced_monitoring;
+use crate::telemetry::types::{IntegrationMethod, TelemetryProfile, TelemetryUpdate, DEFAULT_TELEMETRY_SAMPLING_HZ};
+
+// Time given to macmon/SMC to start producing samples before the capture starts
+const IDLE_BASELINE_WARMUP: Duration = Duration::from_secs(2);
+
+/// Mean idle draw per power channel, subtracted from each reading when enabled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct IdlePower {
+    pub cpu_w: f64,
+    pub gpu_w: f64,
+    pub ane_w: f64,
+}
+
+impl IdlePower {
+    pub fn total_w(&self) -> f64 {
+        self.cpu_w + self.gpu_w + self.ane_w
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleBaseline {
+    pub captured_at_ms: u64,
+    pub duration_s: f64,
+    pub sampling_hz: f32,
+    pub samples: usize,                 // Samples with at least one power reading
+    pub power: IdlePower,
+    pub avg_cpu_temp_c: Option<f64>,
+    pub avg_gpu_temp_c: Option<f64>,
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Average the idle samples; None when none of them had a power reading. A channel that
+/// never reported counts as drawing nothing.
+pub fn baseline_from_samples(samples: &[TelemetryUpdate], duration_s: f64, sampling_hz: f32, captured_at_ms: u64) -> Option<IdleBaseline> {
+    let powered: Vec<&TelemetryUpdate> = samples.iter()
+        .filter(|sample| [sample.cpu_power_watts, sample.gpu_power_watts, sample.ane_power_watts].iter().any(Option::is_some))
+        .collect();
+    if powered.is_empty() {
+        return None;
+    }
+    let channel = |get: fn(&TelemetryUpdate) -> Option<f64>| mean(powered.iter().filter_map(|sample| get(sample))).unwrap_or(0.0);
+    Some(IdleBaseline {
+        captured_at_ms,
+        duration_s,
+        sampling_hz,
+        samples: powered.len(),
+        power: IdlePower {
+            cpu_w: channel(|sample| sample.cpu_power_watts),
+            gpu_w: channel(|sample| sample.gpu_power_watts),
+            ane_w: channel(|sample| sample.ane_power_watts),
+        },
+        avg_cpu_temp_c: mean(samples.iter().filter_map(|sample| sample.cpu_temp_avg)),
+        avg_gpu_temp_c: mean(samples.iter().filter_map(|sample| sample.gpu_temp_avg)),
+    })
+}
+
+/// Sample power and temperatures for `duration` with inference idle. Each sample is emitted
+/// as `idle_baseline_sample` so the UI can show progress.
+pub async fn capture_idle_baseline(events: &dyn EventSink, duration: Duration) -> Result<IdleBaseline, String> {
+    println!("🔋 IDLE BASELINE: Sampling idle power for {:?}", duration);
+    let stop_signal = Arc::new(AtomicBool::new(false));
+    let (telemetry_tx, mut telemetry_rx) = broadcast::channel::<TelemetryUpdate>(1000);
+    let telemetry_broadcaster = Arc::new(telemetry_tx);
+    let monitor_handle = {
+        let stop = stop_signal.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_enhanced_monitoring(telemetry_broadcaster, stop, None, Some(DEFAULT_TELEMETRY_SAMPLING_HZ), TelemetryProfile::default(), IntegrationMethod::default(), None, None).await {
+                println!("❌ Idle baseline monitoring error: {}", e);
+            }
+        })
+    };
+    tokio::time::sleep(IDLE_BASELINE_WARMUP).await;
+    while telemetry_rx.try_recv().is_ok() {}
+
+    let started = tokio::time::Instant::now();
+    let deadline = started + duration;
+    let mut samples = Vec::new();
+    loop {
+        match tokio::time::timeout_at(deadline, telemetry_rx.recv()).await {
+            Ok(Ok(telemetry)) => {
+                let _ = events.emit("idle_baseline_sample", &telemetry);
+                samples.push(telemetry);
+            }
+            Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+            Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break,
+        }
+    }
+    let duration_s = started.elapsed().as_secs_f64();
+    stop_signal.store(true, Ordering::Relaxed);
+    let _ = monitor_handle.await;
+
+    let captured_at_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let baseline = baseline_from_samples(&samples, duration_s, DEFAULT_TELEMETRY_SAMPLING_HZ, captured_at_ms)
+        .ok_or_else(|| format!("No power readings in {} idle sample(s); is macmon installed?", samples.len()))?;
+    println!("🔋 IDLE BASELINE: CPU {:.2}W, GPU {:.2}W, ANE {:.2}W, CPU temp {:?}°C ({} samples)",
+             baseline.power.cpu_w, baseline.power.gpu_w, baseline.power.ane_w, baseline.avg_cpu_temp_c, baseline.samples);
+    Ok(baseline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_w: Option<f64>, gpu_w: Option<f64>, cpu_temp: Option<f64>) -> TelemetryUpdate {
+        TelemetryUpdate {
+            cpu_power_watts: cpu_w,
+            gpu_power_watts: gpu_w,
+            cpu_temp_avg: cpu_temp,
+            ..TelemetryUpdate::default()
+        }
+    }
+
+    #[test]
+    fn test_baseline_averages_each_channel() {
+        let samples = [
+            sample(Some(1.0), Some(0.2), Some(40.0)),
+            sample(Some(2.0), None, Some(42.0)),
+            sample(None, None, Some(44.0)),     // Temperature only: not a power sample
+        ];
+        let baseline = baseline_from_samples(&samples, 10.0, 1.0, 5).unwrap();
+        assert_eq!(baseline.samples, 2);
+        assert_eq!(baseline.power, IdlePower { cpu_w: 1.5, gpu_w: 0.2, ane_w: 0.0 });
+        assert!((baseline.power.total_w() - 1.7).abs() < 1e-9);
+        assert_eq!(baseline.avg_cpu_temp_c, Some(42.0));
+        assert_eq!(baseline.avg_gpu_temp_c, None);
+
+        assert!(baseline_from_samples(&samples[2..], 10.0, 1.0, 5).is_none());
+    }
+}
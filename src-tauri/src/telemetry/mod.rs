@@ -4,7 +4,16 @@
 pub mod types;
 pub mod processor;
 pub mod power_calculator;
+pub mod calibration;
+pub mod phase;
+pub mod debug_capture;
+pub mod hardware_baseline;
+pub mod temperature_unit;
+pub mod synthetic_load;
+pub mod cooldown;
+pub mod heat_soak;
+pub mod idle_baseline;
 
 // Re-export all types for external access
 pub use types::*;
-pub use power_calculator::{PowerCalculator, PowerConsumptionSummary};
+pub use power_calculator::{PowerCalculator, PowerConsumptionSummary, ModelEnergyAccumulators};
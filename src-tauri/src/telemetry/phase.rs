@@ -0,0 +1,241 @@
+// Acknowledged model phase markers for per-model energy attribution
+// The generation task waits until the telemetry monitor has applied a marker
+// before starting (or after finishing) a model, so no sample lands in the wrong model.
+
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::telemetry::power_calculator::ModelEnergyAccumulators;
+use crate::telemetry::types::{InferencePhase, TelemetryCommand, TelemetryCommandBroadcaster};
+
+/// Channel the monitor uses to confirm a phase marker was applied
+pub type PhaseAck = mpsc::UnboundedSender<()>;
+
+// Markers are re-sent in case the monitor subscribed after the first send
+const PHASE_MARKER_ATTEMPTS: usize = 3;
+
+// Extra wait on top of one sampling interval (the monitor applies commands once per loop)
+const PHASE_ACK_SLACK: Duration = Duration::from_millis(500);
+
+/// Ack timeout for a monitor sampling every `sampling_interval`
+pub fn phase_ack_timeout(sampling_interval: Duration) -> Duration {
+    sampling_interval + PHASE_ACK_SLACK
+}
+
+/// Start attributing energy to `model`; returns false if no monitor confirmed the marker
+pub async fn begin_model_phase(broadcaster: &TelemetryCommandBroadcaster, model: &str, ack_timeout: Duration) -> bool {
+    send_acknowledged(broadcaster, ack_timeout, |ack| TelemetryCommand::BeginModelPhase {
+        model: model.to_string(),
+        ack: Some(ack),
+    }).await
+}
+
+/// Stop attributing energy to `model`; returns false if no monitor confirmed the marker
+pub async fn end_model_phase(broadcaster: &TelemetryCommandBroadcaster, model: &str, ack_timeout: Duration) -> bool {
+    send_acknowledged(broadcaster, ack_timeout, |ack| TelemetryCommand::EndModelPhase {
+        model: model.to_string(),
+        ack: Some(ack),
+    }).await
+}
+
+/// Prefill/decode markers sent from inside a model's run. They carry the time they happened,
+/// so only the last one needs to wait for the monitor (before the run reads its totals).
+#[derive(Clone)]
+pub struct InferencePhaseMarkers {
+    broadcaster: TelemetryCommandBroadcaster,
+    model: String,
+    ack_timeout: Duration,
+}
+
+impl InferencePhaseMarkers {
+    pub fn new(broadcaster: TelemetryCommandBroadcaster, model: &str, ack_timeout: Duration) -> Self {
+        Self { broadcaster, model: model.to_string(), ack_timeout }
+    }
+
+    pub fn begin(&self, phase: InferencePhase) {
+        let _ = self.broadcaster.send(TelemetryCommand::BeginInferencePhase {
+            model: self.model.clone(),
+            phase,
+            timestamp_ms: now_ms(),
+            ack: None,
+        });
+    }
+
+    pub fn end(&self, phase: InferencePhase) {
+        let _ = self.broadcaster.send(TelemetryCommand::EndInferencePhase {
+            model: self.model.clone(),
+            phase,
+            timestamp_ms: now_ms(),
+            ack: None,
+        });
+    }
+
+    /// The time to stamp a marker sent later with
+    pub fn now_ms(&self) -> u64 {
+        now_ms()
+    }
+
+    /// End `phase` as of `timestamp_ms` and wait until the monitor has applied it
+    pub async fn end_acknowledged(&self, phase: InferencePhase, timestamp_ms: u64) -> bool {
+        send_acknowledged(&self.broadcaster, self.ack_timeout, |ack| TelemetryCommand::EndInferencePhase {
+            model: self.model.clone(),
+            phase,
+            timestamp_ms,
+            ack: Some(ack),
+        }).await
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+async fn send_acknowledged<F>(broadcaster: &TelemetryCommandBroadcaster, ack_timeout: Duration, make_command: F) -> bool
+where
+    F: Fn(PhaseAck) -> TelemetryCommand,
+{
+    let (ack_tx, mut ack_rx) = mpsc::unbounded_channel();
+    for attempt in 1..=PHASE_MARKER_ATTEMPTS {
+        let command = make_command(ack_tx.clone());
+        if let Err(e) = broadcaster.send(command) {
+            println!("⚠️ Phase marker send failed (attempt {}/{}): {}", attempt, PHASE_MARKER_ATTEMPTS, e);
+            tokio::time::sleep(ack_timeout).await;
+            continue;
+        }
+        if let Ok(Some(())) = tokio::time::timeout(ack_timeout, ack_rx.recv()).await {
+            return true;
+        }
+        println!("⚠️ Phase marker not acknowledged within {:?} (attempt {}/{})", ack_timeout, attempt, PHASE_MARKER_ATTEMPTS);
+    }
+    println!("⚠️ Phase marker was never applied - per-model energy totals may be incomplete");
+    false
+}
+
+/// Apply pending phase markers to the accumulators and acknowledge each one, and track
+/// pause/resume in `paused`. Called by the monitor before taking a sample.
+pub fn apply_phase_commands(rx: &mut broadcast::Receiver<TelemetryCommand>, accumulators: &mut ModelEnergyAccumulators, paused: &mut bool) {
+    loop {
+        let command = match rx.try_recv() {
+            Ok(command) => command,
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                println!("⚠️ POWER CALC: Skipped {} phase markers", skipped);
+                continue;
+            }
+            Err(_) => break,
+        };
+        let ack = match command {
+            TelemetryCommand::BeginModelPhase { model, ack } => {
+                println!("🔄 POWER CALC: Model {} phase started - attributing energy to Model {}", model, model);
+                accumulators.begin_phase(&model);
+                ack
+            }
+            TelemetryCommand::EndModelPhase { model, ack } => {
+                println!("🔄 POWER CALC: Model {} phase ended", model);
+                accumulators.end_phase(&model);
+                ack
+            }
+            TelemetryCommand::BeginInferencePhase { model, phase, timestamp_ms, ack } => {
+                accumulators.begin_inference_phase(&model, phase, timestamp_ms);
+                ack
+            }
+            TelemetryCommand::EndInferencePhase { model, phase, timestamp_ms, ack } => {
+                accumulators.end_inference_phase(&model, phase, timestamp_ms);
+                ack
+            }
+            TelemetryCommand::Pause => {
+                if !*paused {
+                    println!("⏸️ TELEMETRY: Sampling paused");
+                    accumulators.break_series();
+                }
+                *paused = true;
+                None
+            }
+            TelemetryCommand::Resume => {
+                if *paused {
+                    println!("▶️ TELEMETRY: Sampling resumed");
+                }
+                *paused = false;
+                None
+            }
+        };
+        if let Some(ack) = ack {
+            let _ = ack.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use crate::telemetry::types::{IntegrationMethod, TelemetryUpdate};
+
+    // Mock monitor: applies markers, then takes a sample whose power is whatever the
+    // "hardware" currently draws, as fast as possible (well above any real sampling rate)
+    async fn mock_monitor(
+        broadcaster: TelemetryCommandBroadcaster,
+        power_w: Arc<AtomicU64>,
+        stop: Arc<AtomicBool>,
+    ) -> ModelEnergyAccumulators {
+        let mut rx = broadcaster.subscribe();
+        let mut accumulators = ModelEnergyAccumulators::new(IntegrationMethod::Trapezoid);
+        let mut timestamp_ms = 0;
+        let mut paused = false;
+        while !stop.load(Ordering::Relaxed) {
+            apply_phase_commands(&mut rx, &mut accumulators, &mut paused);
+            timestamp_ms += 1000;
+            accumulators.update_with_telemetry(TelemetryUpdate {
+                timestamp_ms,
+                cpu_power_watts: Some(power_w.load(Ordering::SeqCst) as f64),
+                ..TelemetryUpdate::default()
+            });
+            tokio::task::yield_now().await;
+        }
+        accumulators
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_acknowledged_markers_never_mix_model_samples() {
+        let (tx, _) = broadcast::channel::<TelemetryCommand>(16);
+        let broadcaster = Arc::new(tx);
+        let power_w = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let monitor = tokio::spawn(mock_monitor(broadcaster.clone(), power_w.clone(), stop.clone()));
+        let timeout = Duration::from_millis(200);
+
+        // Each round's A draws 10 W and B draws 20 W; the hardware switches power
+        // right after the previous marker is acknowledged, as inference would
+        for round in 0..20 {
+            for (model, watts) in [(format!("A{}", round), 10), (format!("B{}", round), 20)] {
+                power_w.store(watts, Ordering::SeqCst);
+                assert!(begin_model_phase(&broadcaster, &model, timeout).await);
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                assert!(end_model_phase(&broadcaster, &model, timeout).await);
+            }
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        let summaries = monitor.await.unwrap().summaries();
+        assert_eq!(summaries.len(), 40);
+
+        // A fire-and-forget marker applied late lets the next model's power leak into
+        // this model's intervals, which would separate the lower and upper bounds
+        for (model, summary) in &summaries {
+            assert!(summary.total_energy_wh > 0.0, "Model {} got no samples", model);
+            assert!((summary.energy_upper_bound_wh - summary.energy_lower_bound_wh).abs() < 1e-12,
+                    "Model {} mixed with other samples: {:?}", model, summary);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unacknowledged_marker_reports_failure() {
+        let (tx, _rx) = broadcast::channel::<TelemetryCommand>(16);
+        let broadcaster = Arc::new(tx);
+        // A subscriber exists but never applies markers
+        assert!(!begin_model_phase(&broadcaster, "A", Duration::from_millis(10)).await);
+    }
+}
@@ -1,6 +1,19 @@
-// Power consumption calculation using trapezoidal rule integration
+// Power consumption calculation by integrating sampled power (rectangle, trapezoid or monotone cubic)
+use std::collections::BTreeMap;
 use serde::Serialize;
-use crate::telemetry::types::TelemetryUpdate;
+use crate::telemetry::idle_baseline::IdlePower;
+use crate::telemetry::types::{InferencePhase, IntegrationMethod, PhaseEnergy, PowerSampleQuality, TelemetryUpdate};
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+// Readings above this are sensor or parsing glitches; no Apple Silicon package draws near it
+const MAX_PLAUSIBLE_POWER_W: f64 = 1000.0;
+
+// A gap this many times the previous sampling interval (and at least MIN_SAMPLE_GAP_MS), or the
+// clock jumping back as far, means sleep or a clock reset: the series restarts there instead
+// of integrating across
+const SAMPLE_GAP_INTERVALS: u64 = 10;
+const MIN_SAMPLE_GAP_MS: u64 = 10_000;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct PowerConsumptionSummary {
@@ -12,6 +25,120 @@ pub struct PowerConsumptionSummary {
     pub peak_power_watts: f64,
     pub duration_seconds: f64,
     pub energy_per_token_wh: Option<f64>,
+    pub integration_method: IntegrationMethod,
+    pub energy_lower_bound_wh: f64,
+    pub energy_upper_bound_wh: f64,
+    pub sample_quality: PowerSampleQuality,
+    pub prefill: Option<PhaseEnergy>,
+    pub decode: Option<PhaseEnergy>,
+    pub idle_power_subtracted_w: Option<f64>,   // Idle baseline removed from every reading; energies are marginal
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PowerSample {
+    time_h: f64,
+    watts: f64,
+}
+
+/// Streaming energy integrator for one power channel.
+/// The monotone cubic needs the sample after an interval to fix the tangent at its end,
+/// so the newest interval stays provisional (using the secant as end tangent) until then.
+#[derive(Debug, Clone)]
+pub struct EnergyIntegrator {
+    method: IntegrationMethod,
+    last: Option<PowerSample>,
+    pending: Option<(PowerSample, PowerSample, f64)>, // Unsettled interval and its start tangent
+    settled_wh: f64,
+    pending_wh: f64,
+    lower_wh: f64,
+    upper_wh: f64,
+}
+
+impl EnergyIntegrator {
+    pub fn new(method: IntegrationMethod) -> Self {
+        Self {
+            method,
+            last: None,
+            pending: None,
+            settled_wh: 0.0,
+            pending_wh: 0.0,
+            lower_wh: 0.0,
+            upper_wh: 0.0,
+        }
+    }
+
+    /// Add a power reading; `None` breaks the series so no energy is assumed across the gap
+    pub fn push(&mut self, timestamp_ms: u64, watts: Option<f64>) {
+        let Some(watts) = watts else {
+            self.settle_pending();
+            self.last = None;
+            return;
+        };
+        let sample = PowerSample { time_h: timestamp_ms as f64 / MS_PER_HOUR, watts };
+
+        let Some(prev) = self.last else {
+            self.last = Some(sample);
+            return;
+        };
+        let dt = sample.time_h - prev.time_h;
+        if dt <= 0.0 {
+            return;
+        }
+
+        // Holding the lower/higher endpoint bounds any waveform that is monotone between samples
+        self.lower_wh += prev.watts.min(sample.watts) * dt;
+        self.upper_wh += prev.watts.max(sample.watts) * dt;
+
+        match self.method {
+            IntegrationMethod::Rectangle => self.settled_wh += sample.watts * dt,
+            IntegrationMethod::Trapezoid => self.settled_wh += (prev.watts + sample.watts) * dt / 2.0,
+            IntegrationMethod::MonotoneCubic => {
+                let slope = (sample.watts - prev.watts) / dt;
+                let start_tangent = match self.pending.take() {
+                    Some((a, b, tangent_a)) => {
+                        let tangent_b = pchip_tangent(b.time_h - a.time_h, dt, (b.watts - a.watts) / (b.time_h - a.time_h), slope);
+                        self.settled_wh += hermite_energy(a, b, tangent_a, tangent_b);
+                        tangent_b
+                    }
+                    None => slope,
+                };
+                self.pending = Some((prev, sample, start_tangent));
+                self.pending_wh = hermite_energy(prev, sample, start_tangent, slope);
+            }
+        }
+        self.last = Some(sample);
+    }
+
+    fn settle_pending(&mut self) {
+        self.settled_wh += self.pending_wh;
+        self.pending_wh = 0.0;
+        self.pending = None;
+    }
+
+    pub fn energy_wh(&self) -> f64 {
+        self.settled_wh + self.pending_wh
+    }
+
+    pub fn bounds_wh(&self) -> (f64, f64) {
+        (self.lower_wh, self.upper_wh)
+    }
+}
+
+/// Fritsch-Carlson tangent at a point between intervals of width h0, h1 with secants d0, d1
+fn pchip_tangent(h0: f64, h1: f64, d0: f64, d1: f64) -> f64 {
+    if d0 == 0.0 || d1 == 0.0 || d0.signum() != d1.signum() {
+        return 0.0;
+    }
+    let w0 = 2.0 * h1 + h0;
+    let w1 = h1 + 2.0 * h0;
+    (w0 + w1) / (w0 / d0 + w1 / d1)
+}
+
+/// Integral of the cubic Hermite segment between two samples, clamped to the samples' range
+fn hermite_energy(a: PowerSample, b: PowerSample, tangent_a: f64, tangent_b: f64) -> f64 {
+    let dt = b.time_h - a.time_h;
+    let energy = (a.watts + b.watts) * dt / 2.0 + (tangent_a - tangent_b) * dt * dt / 12.0;
+    energy.clamp(a.watts.min(b.watts) * dt, a.watts.max(b.watts) * dt)
 }
 
 #[derive(Debug, Clone)]
@@ -21,46 +148,114 @@ pub struct PowerCalculator {
     cumulative_gpu_energy_wh: f64,
     cumulative_ane_energy_wh: f64,
     session_start_timestamp: Option<u64>,
+    method: IntegrationMethod,
+    cpu: EnergyIntegrator,
+    gpu: EnergyIntegrator,
+    ane: EnergyIntegrator,
+    sample_quality: PowerSampleQuality,
+    last_interval_ms: Option<u64>,  // Since the previous sample; None at the start of a series
+    last_power_w: Option<f64>,      // Total of the previous sample's plausible readings
+    open_inference_phase: Option<(InferencePhase, u64, f64)>,  // Phase, start time and energy at start
+    prefill: Option<PhaseEnergy>,
+    decode: Option<PhaseEnergy>,
+    idle_power: Option<IdlePower>,
 }
 
 impl PowerCalculator {
-    /// Create a new PowerCalculator instance
+    /// Create a new PowerCalculator instance using the default (trapezoid) integration
     pub fn new() -> Self {
+        Self::with_method(IntegrationMethod::default())
+    }
+
+    pub fn with_method(method: IntegrationMethod) -> Self {
         Self {
             previous_telemetry: None,
             cumulative_cpu_energy_wh: 0.0,
             cumulative_gpu_energy_wh: 0.0,
             cumulative_ane_energy_wh: 0.0,
             session_start_timestamp: None,
+            method,
+            cpu: EnergyIntegrator::new(method),
+            gpu: EnergyIntegrator::new(method),
+            ane: EnergyIntegrator::new(method),
+            sample_quality: PowerSampleQuality::default(),
+            last_interval_ms: None,
+            last_power_w: None,
+            open_inference_phase: None,
+            prefill: None,
+            decode: None,
+            idle_power: None,
         }
     }
 
-    /// Calculate power consumption using trapezoidal rule
-    /// Returns updated telemetry with cumulative energy values
-    pub fn update_with_telemetry(&mut self, mut telemetry: TelemetryUpdate) -> TelemetryUpdate {
+    /// Subtract an idle baseline from each channel's readings (clamped at zero), so the
+    /// integrated energy is what the workload added on top of idle
+    pub fn with_idle_power(mut self, idle_power: Option<IdlePower>) -> Self {
+        self.idle_power = idle_power;
+        self
+    }
+
+    /// Integrate the new power readings
+    /// Returns updated telemetry with cumulative energy values and their uncertainty bounds.
+    /// Out-of-order samples, implausible readings and long gaps are left out and counted.
+    pub fn update_with_telemetry(&mut self, telemetry: TelemetryUpdate) -> TelemetryUpdate {
         // Initialize session start time
         if self.session_start_timestamp.is_none() {
             self.session_start_timestamp = Some(telemetry.timestamp_ms);
         }
 
-        // Calculate energy consumption if we have previous reading
-        if let Some(prev) = &self.previous_telemetry {
-            let dt_hours = (telemetry.timestamp_ms - prev.timestamp_ms) as f64 / 3_600_000.0;
-
-            // Trapezoidal rule integration: E = (P1 + P2) * dt / 2
-            if let (Some(p1), Some(p2)) = (prev.cpu_power_watts, telemetry.cpu_power_watts) {
-                self.cumulative_cpu_energy_wh += (p1 + p2) * dt_hours / 2.0;
+        if let Some(previous_ms) = self.previous_telemetry.as_ref().map(|previous| previous.timestamp_ms) {
+            let max_gap_ms = self.last_interval_ms
+                .map(|interval| (interval * SAMPLE_GAP_INTERVALS).max(MIN_SAMPLE_GAP_MS));
+            if max_gap_ms.is_some_and(|max_gap_ms| telemetry.timestamp_ms.abs_diff(previous_ms) > max_gap_ms) {
+                println!("⚠️ POWER: {} ms jump between samples - restarting energy integration without the gap",
+                         telemetry.timestamp_ms as i64 - previous_ms as i64);
+                self.sample_quality.series_resets += 1;
+                self.last_interval_ms = None;
+                for channel in [&mut self.cpu, &mut self.gpu, &mut self.ane] {
+                    channel.push(telemetry.timestamp_ms, None);
+                }
+            } else if telemetry.timestamp_ms <= previous_ms {
+                self.sample_quality.discarded_samples += 1;
+                return self.with_energy_fields(telemetry);
+            } else {
+                self.last_interval_ms = Some(telemetry.timestamp_ms - previous_ms);
             }
+        }
 
-            if let (Some(p1), Some(p2)) = (prev.gpu_power_watts, telemetry.gpu_power_watts) {
-                self.cumulative_gpu_energy_wh += (p1 + p2) * dt_hours / 2.0;
-            }
+        let idle = self.idle_power.unwrap_or_default();
+        let cpu_watts = self.plausible_power(telemetry.cpu_power_watts).map(|watts| (watts - idle.cpu_w).max(0.0));
+        let gpu_watts = self.plausible_power(telemetry.gpu_power_watts).map(|watts| (watts - idle.gpu_w).max(0.0));
+        let ane_watts = self.plausible_power(telemetry.ane_power_watts).map(|watts| (watts - idle.ane_w).max(0.0));
+        self.cpu.push(telemetry.timestamp_ms, cpu_watts);
+        self.gpu.push(telemetry.timestamp_ms, gpu_watts);
+        self.ane.push(telemetry.timestamp_ms, ane_watts);
+        self.last_power_w = [cpu_watts, gpu_watts, ane_watts].iter().any(Option::is_some)
+            .then(|| [cpu_watts, gpu_watts, ane_watts].iter().flatten().sum());
+        self.cumulative_cpu_energy_wh = self.cpu.energy_wh();
+        self.cumulative_gpu_energy_wh = self.gpu.energy_wh();
+        self.cumulative_ane_energy_wh = self.ane.energy_wh();
 
-            if let (Some(p1), Some(p2)) = (prev.ane_power_watts, telemetry.ane_power_watts) {
-                self.cumulative_ane_energy_wh += (p1 + p2) * dt_hours / 2.0;
-            }
+        let telemetry = self.with_energy_fields(telemetry);
+
+        // Store current telemetry for next calculation
+        self.previous_telemetry = Some(telemetry.clone());
+
+        telemetry
+    }
+
+    // A bad reading breaks the channel's series like a missing one, so no energy is assumed around it
+    fn plausible_power(&mut self, watts: Option<f64>) -> Option<f64> {
+        let watts = watts?;
+        if watts.is_finite() && (0.0..=MAX_PLAUSIBLE_POWER_W).contains(&watts) {
+            Some(watts)
+        } else {
+            self.sample_quality.rejected_power_readings += 1;
+            None
         }
+    }
 
+    fn with_energy_fields(&self, mut telemetry: TelemetryUpdate) -> TelemetryUpdate {
         // Update telemetry with cumulative energy values
         telemetry.total_energy_wh = Some(
             self.cumulative_cpu_energy_wh + self.cumulative_gpu_energy_wh + self.cumulative_ane_energy_wh
@@ -68,20 +263,70 @@ impl PowerCalculator {
         telemetry.cpu_energy_wh = Some(self.cumulative_cpu_energy_wh);
         telemetry.gpu_energy_wh = Some(self.cumulative_gpu_energy_wh);
         telemetry.ane_energy_wh = Some(self.cumulative_ane_energy_wh);
+        let (lower, upper) = self.total_bounds_wh();
+        telemetry.total_energy_lower_wh = Some(lower);
+        telemetry.total_energy_upper_wh = Some(upper);
+        telemetry
+    }
 
-        // Store current telemetry for next calculation
-        self.previous_telemetry = Some(telemetry.clone());
+    /// Start a prefill/decode phase at `timestamp_ms`, closing any other one still open
+    pub fn begin_inference_phase(&mut self, phase: InferencePhase, timestamp_ms: u64) {
+        if let Some((open_phase, _, _)) = self.open_inference_phase {
+            self.end_inference_phase(open_phase, timestamp_ms);
+        }
+        self.open_inference_phase = Some((phase, timestamp_ms, self.energy_at(timestamp_ms)));
+    }
 
-        telemetry
+    pub fn end_inference_phase(&mut self, phase: InferencePhase, timestamp_ms: u64) {
+        let Some((open_phase, start_ms, start_energy_wh)) = self.open_inference_phase else {
+            return;
+        };
+        if open_phase != phase {
+            return;
+        }
+        self.open_inference_phase = None;
+        let energy_wh = (self.energy_at(timestamp_ms) - start_energy_wh).max(0.0);
+        let totals = match phase {
+            InferencePhase::Prefill => &mut self.prefill,
+            InferencePhase::Decode => &mut self.decode,
+        }.get_or_insert_with(PhaseEnergy::default);
+        totals.energy_wh += energy_wh;
+        totals.duration_ms += timestamp_ms.saturating_sub(start_ms);
     }
 
-    /// Reset the calculator state for a new session
-    pub fn reset(&mut self) {
+    // Cumulative energy at a marker between samples: the latest sample's power is held from it
+    // (backwards too, for a marker applied after a later sample), so phases need not align with samples
+    fn energy_at(&self, timestamp_ms: u64) -> f64 {
+        let total = self.cumulative_cpu_energy_wh + self.cumulative_gpu_energy_wh + self.cumulative_ane_energy_wh;
+        match (self.previous_telemetry.as_ref(), self.last_power_w) {
+            (Some(previous), Some(watts)) => {
+                (total + watts * (timestamp_ms as f64 - previous.timestamp_ms as f64) / MS_PER_HOUR).max(0.0)
+            }
+            _ => total,
+        }
+    }
+
+    fn total_bounds_wh(&self) -> (f64, f64) {
+        [&self.cpu, &self.gpu, &self.ane]
+            .iter()
+            .map(|channel| channel.bounds_wh())
+            .fold((0.0, 0.0), |(lower, upper), (l, u)| (lower + l, upper + u))
+    }
+
+    /// End the series at the last sample, e.g. when sampling pauses: the next sample starts a
+    /// new one, so no energy is assumed for the time in between
+    pub fn break_series(&mut self) {
+        for channel in [&mut self.cpu, &mut self.gpu, &mut self.ane] {
+            channel.push(0, None);
+        }
         self.previous_telemetry = None;
-        self.cumulative_cpu_energy_wh = 0.0;
-        self.cumulative_gpu_energy_wh = 0.0;
-        self.cumulative_ane_energy_wh = 0.0;
-        self.session_start_timestamp = None;
+        self.last_interval_ms = None;
+        self.last_power_w = None;
+    }
+
+    /// Reset the calculator state for a new session (keeps the integration method and idle baseline)
+    pub fn reset(&mut self) {
+        *self = Self::with_method(self.method).with_idle_power(self.idle_power);
     }
 
     /// Get a summary of power consumption for the current session
@@ -90,6 +335,7 @@ impl PowerCalculator {
         let energy_per_token = total_tokens.map(|tokens| {
             if tokens > 0 { total_energy / tokens as f64 } else { 0.0 }
         });
+        let (lower, upper) = self.total_bounds_wh();
 
         PowerConsumptionSummary {
             total_energy_wh: total_energy,
@@ -100,6 +346,13 @@ impl PowerCalculator {
             peak_power_watts: 0.0,    // TODO: Track maximum power reading
             duration_seconds: 0.0,    // TODO: Calculate from timestamps
             energy_per_token_wh: energy_per_token,
+            integration_method: self.method,
+            energy_lower_bound_wh: lower,
+            energy_upper_bound_wh: upper,
+            sample_quality: self.sample_quality,
+            prefill: self.prefill,
+            decode: self.decode,
+            idle_power_subtracted_w: self.idle_power.map(|idle| idle.total_w()),
         }
     }
 }
@@ -110,6 +363,90 @@ impl Default for PowerCalculator {
     }
 }
 
+/// Energy accumulators keyed by model label, switched by phase markers.
+/// Samples only count towards the model whose phase is active, so A's totals
+/// survive B's run and both are available for the session summary.
+#[derive(Debug, Clone)]
+pub struct ModelEnergyAccumulators {
+    method: IntegrationMethod,
+    idle_power: Option<IdlePower>,
+    active_model: Option<String>,
+    models: BTreeMap<String, PowerCalculator>,
+}
+
+impl ModelEnergyAccumulators {
+    pub fn new(method: IntegrationMethod) -> Self {
+        Self { method, idle_power: None, active_model: None, models: BTreeMap::new() }
+    }
+
+    /// Subtract `idle_power` from the readings of every model's run
+    pub fn with_idle_power(mut self, idle_power: Option<IdlePower>) -> Self {
+        self.idle_power = idle_power;
+        self
+    }
+
+    /// Start attributing samples to `model`, restarting its totals if it ran before
+    pub fn begin_phase(&mut self, model: &str) {
+        self.models.insert(model.to_string(), PowerCalculator::with_method(self.method).with_idle_power(self.idle_power));
+        self.active_model = Some(model.to_string());
+    }
+
+    /// Stop attributing samples to `model`; its totals are kept
+    pub fn end_phase(&mut self, model: &str) {
+        if self.active_model.as_deref() == Some(model) {
+            self.active_model = None;
+        }
+    }
+
+    /// Mark a prefill/decode boundary; ignored unless `model`'s phase is active
+    pub fn begin_inference_phase(&mut self, model: &str, phase: InferencePhase, timestamp_ms: u64) {
+        if let Some(calculator) = self.active_calculator(model) {
+            calculator.begin_inference_phase(phase, timestamp_ms);
+        }
+    }
+
+    pub fn end_inference_phase(&mut self, model: &str, phase: InferencePhase, timestamp_ms: u64) {
+        if let Some(calculator) = self.active_calculator(model) {
+            calculator.end_inference_phase(phase, timestamp_ms);
+        }
+    }
+
+    fn active_calculator(&mut self, model: &str) -> Option<&mut PowerCalculator> {
+        if self.active_model.as_deref() != Some(model) {
+            return None;
+        }
+        self.models.get_mut(model)
+    }
+
+    /// Break the active model's series; see PowerCalculator::break_series
+    pub fn break_series(&mut self) {
+        if let Some(calculator) = self.active_model.as_ref().and_then(|model| self.models.get_mut(model)) {
+            calculator.break_series();
+        }
+    }
+
+    pub fn active_model(&self) -> Option<&str> {
+        self.active_model.as_deref()
+    }
+
+    /// Feed a sample to the active model's calculator. Outside a phase the sample
+    /// is passed through without energy fields.
+    pub fn update_with_telemetry(&mut self, telemetry: TelemetryUpdate) -> TelemetryUpdate {
+        match self.active_model.as_ref().and_then(|model| self.models.get_mut(model)) {
+            Some(calculator) => calculator.update_with_telemetry(telemetry),
+            None => telemetry,
+        }
+    }
+
+    /// Current totals for every model that has run in this session
+    pub fn summaries(&self) -> BTreeMap<String, PowerConsumptionSummary> {
+        self.models
+            .iter()
+            .map(|(model, calculator)| (model.clone(), calculator.get_summary(None)))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,9 +463,13 @@ mod tests {
             gpu_freq_mhz: None,
             ram_usage_gb: None,
             thermal_pressure: None,
+            swap_usage_gb: None,
+            compressed_memory_gb: None,
+            memory_pressure_level: None,
             ttft_ms: None,
             current_tps: None,
             instantaneous_tps: None,
+            rolling_tps: None,
             generation_time_ms: None,
             model: None,
             cpu_temp_avg: None,
@@ -142,12 +483,22 @@ mod tests {
             cpu_p_core_utilization: None,
             cpu_e_core_utilization: None,
             cpu_overall_utilization: None,
+            inference_cpu_utilization: None,
+            inference_cpu_share: None,
+            gpu_utilization: None,
+            fan_rpm: None,
             core_temperatures: None,
             total_energy_wh: None,
             cpu_energy_wh: None,
             gpu_energy_wh: None,
             ane_energy_wh: None,
             energy_rate_wh_per_token: None,
+            total_energy_lower_wh: None,
+            total_energy_upper_wh: None,
+            cpu_temp_rate_c_per_s: None,
+            gpu_temp_rate_c_per_s: None,
+            battery_temp_rate_c_per_s: None,
+            package_power_rate_w_per_s: None,
         }
     }
 
@@ -176,6 +527,25 @@ mod tests {
         assert_eq!(calculator.session_start_timestamp, Some(1000));
     }
 
+    #[test]
+    fn test_idle_baseline_is_subtracted_per_channel() {
+        let idle = IdlePower { cpu_w: 2.0, gpu_w: 1.0, ane_w: 0.0 };
+        let mut calculator = PowerCalculator::new().with_idle_power(Some(idle));
+        calculator.update_with_telemetry(create_test_telemetry(0, Some(12.0), Some(0.5), None));
+        let result = calculator.update_with_telemetry(create_test_telemetry(3600000, Some(12.0), Some(0.5), None));
+
+        // 10W above idle on the CPU for an hour; the GPU below its idle draw counts as zero
+        assert!((result.cpu_energy_wh.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(result.gpu_energy_wh, Some(0.0));
+        assert!((result.total_energy_wh.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(calculator.get_summary(None).idle_power_subtracted_w, Some(3.0));
+
+        // Kept across a reset
+        calculator.reset();
+        assert_eq!(calculator.get_summary(None).idle_power_subtracted_w, Some(3.0));
+        assert_eq!(PowerCalculator::new().get_summary(None).idle_power_subtracted_w, None);
+    }
+
     #[test]
     fn test_trapezoidal_rule_calculation() {
         let mut calculator = PowerCalculator::new();
@@ -291,6 +661,197 @@ mod tests {
         assert!(summary.energy_per_token_wh.is_none());
     }
 
+    fn integrate(method: IntegrationMethod, waveform: impl Fn(f64) -> f64, step_s: f64, duration_s: f64) -> EnergyIntegrator {
+        let mut integrator = EnergyIntegrator::new(method);
+        let steps = (duration_s / step_s).round() as usize;
+        for i in 0..=steps {
+            let t = i as f64 * step_s;
+            integrator.push((t * 1000.0).round() as u64, Some(waveform(t)));
+        }
+        integrator
+    }
+
+    const ALL_METHODS: [IntegrationMethod; 3] = [
+        IntegrationMethod::Rectangle,
+        IntegrationMethod::Trapezoid,
+        IntegrationMethod::MonotoneCubic,
+    ];
+
+    #[test]
+    fn test_methods_on_linear_ramp() {
+        // 10W -> 20W over 10s: exactly 150 J
+        let ramp = |t: f64| 10.0 + t;
+        let truth_wh = 150.0 / 3600.0;
+
+        let trapezoid = integrate(IntegrationMethod::Trapezoid, ramp, 1.0, 10.0);
+        let cubic = integrate(IntegrationMethod::MonotoneCubic, ramp, 1.0, 10.0);
+        let rectangle = integrate(IntegrationMethod::Rectangle, ramp, 1.0, 10.0);
+
+        assert!((trapezoid.energy_wh() - truth_wh).abs() < 1e-12);
+        assert!((cubic.energy_wh() - truth_wh).abs() < 1e-12);
+        // Holding each sample over the preceding second overestimates a rising ramp by 0.5W * 10s
+        assert!((rectangle.energy_wh() - 155.0 / 3600.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_monotone_cubic_is_closest_on_smooth_waveform() {
+        // Rising quarter sine sampled sparsely: 100 J + 5 * 20/pi J
+        let wave = |t: f64| 10.0 + 5.0 * (std::f64::consts::PI * t / 20.0).sin();
+        let truth_wh = (100.0 + 100.0 / std::f64::consts::PI) / 3600.0;
+
+        let errors: Vec<f64> = ALL_METHODS
+            .iter()
+            .map(|method| (integrate(*method, wave, 2.0, 10.0).energy_wh() - truth_wh).abs())
+            .collect();
+
+        assert!(errors[2] < errors[1], "cubic {} vs trapezoid {}", errors[2], errors[1]);
+        assert!(errors[1] < errors[0], "trapezoid {} vs rectangle {}", errors[1], errors[0]);
+    }
+
+    #[test]
+    fn test_spike_stays_within_uncertainty_bounds() {
+        // 5W baseline with a single 30W sample at t=4s
+        let spike = |t: f64| if (t - 4.0).abs() < 1e-9 { 30.0 } else { 5.0 };
+
+        for method in ALL_METHODS {
+            let integrator = integrate(method, spike, 1.0, 8.0);
+            let (lower, upper) = integrator.bounds_wh();
+            let energy = integrator.energy_wh();
+            assert!((lower - 40.0 / 3600.0).abs() < 1e-12);
+            assert!((upper - 90.0 / 3600.0).abs() < 1e-12);
+            assert!(energy >= lower - 1e-12 && energy <= upper + 1e-12, "{:?}: {} outside [{}, {}]", method, energy, lower, upper);
+        }
+    }
+
+    #[test]
+    fn test_summary_reports_method_and_bounds() {
+        let mut calculator = PowerCalculator::with_method(IntegrationMethod::MonotoneCubic);
+        calculator.update_with_telemetry(create_test_telemetry(0, Some(10.0), None, None));
+        let result = calculator.update_with_telemetry(create_test_telemetry(3600000, Some(20.0), None, None));
+        assert_eq!(result.total_energy_lower_wh, Some(10.0));
+        assert_eq!(result.total_energy_upper_wh, Some(20.0));
+
+        calculator.reset();
+        let summary = calculator.get_summary(None);
+        assert_eq!(summary.integration_method, IntegrationMethod::MonotoneCubic);
+        assert_eq!(summary.energy_upper_bound_wh, 0.0);
+    }
+
+    #[test]
+    fn test_model_accumulators_keep_both_totals() {
+        let mut accumulators = ModelEnergyAccumulators::new(IntegrationMethod::Trapezoid);
+
+        // Prewarm sample before any phase is not attributed
+        let idle = accumulators.update_with_telemetry(create_test_telemetry(0, Some(50.0), None, None));
+        assert!(idle.total_energy_wh.is_none());
+
+        accumulators.begin_phase("A");
+        accumulators.update_with_telemetry(create_test_telemetry(3600000, Some(10.0), None, None));
+        accumulators.update_with_telemetry(create_test_telemetry(7200000, Some(10.0), None, None));
+        accumulators.end_phase("A");
+
+        // Cooldown between models
+        accumulators.update_with_telemetry(create_test_telemetry(10800000, Some(50.0), None, None));
+
+        accumulators.begin_phase("B");
+        accumulators.update_with_telemetry(create_test_telemetry(14400000, Some(20.0), None, None));
+        let result = accumulators.update_with_telemetry(create_test_telemetry(18000000, Some(20.0), None, None));
+        assert_eq!(result.cpu_energy_wh, Some(20.0));
+
+        let summaries = accumulators.summaries();
+        assert_eq!(summaries["A"].total_energy_wh, 10.0);
+        assert_eq!(summaries["B"].total_energy_wh, 20.0);
+        assert_eq!(accumulators.active_model(), Some("B"));
+    }
+
+    #[test]
+    fn test_paused_time_is_not_integrated() {
+        let mut accumulators = ModelEnergyAccumulators::new(IntegrationMethod::Trapezoid);
+        accumulators.begin_phase("A");
+        accumulators.update_with_telemetry(create_test_telemetry(0, Some(10.0), None, None));
+        accumulators.update_with_telemetry(create_test_telemetry(3600000, Some(10.0), None, None));
+
+        // Paused for an hour: the next sample starts a new series
+        accumulators.break_series();
+        accumulators.update_with_telemetry(create_test_telemetry(7200000, Some(30.0), None, None));
+        let result = accumulators.update_with_telemetry(create_test_telemetry(10800000, Some(30.0), None, None));
+        assert_eq!(result.cpu_energy_wh, Some(40.0));
+        assert_eq!(accumulators.summaries()["A"].sample_quality.series_resets, 0);
+    }
+
+    #[test]
+    fn test_ending_another_models_phase_is_ignored() {
+        let mut accumulators = ModelEnergyAccumulators::new(IntegrationMethod::Trapezoid);
+        accumulators.begin_phase("B");
+        accumulators.end_phase("A");
+        assert_eq!(accumulators.active_model(), Some("B"));
+
+        // Re-running a model restarts its totals
+        accumulators.update_with_telemetry(create_test_telemetry(0, Some(10.0), None, None));
+        accumulators.update_with_telemetry(create_test_telemetry(3600000, Some(10.0), None, None));
+        accumulators.begin_phase("B");
+        assert_eq!(accumulators.summaries()["B"].total_energy_wh, 0.0);
+    }
+
+    #[test]
+    fn test_bad_samples_are_left_out_and_counted() {
+        let mut calculator = PowerCalculator::new();
+        calculator.update_with_telemetry(create_test_telemetry(0, Some(10.0), None, None));
+        calculator.update_with_telemetry(create_test_telemetry(1000, Some(10.0), None, None));
+
+        // Duplicate and out-of-order timestamps change nothing
+        let duplicate = calculator.update_with_telemetry(create_test_telemetry(1000, Some(500.0), None, None));
+        calculator.update_with_telemetry(create_test_telemetry(500, Some(500.0), None, None));
+        assert_eq!(duplicate.cpu_energy_wh, Some(10.0 / 3600.0));
+
+        // A garbage reading contributes nothing on either side of it
+        calculator.update_with_telemetry(create_test_telemetry(2000, Some(f64::NAN), Some(1.0e9), None));
+        calculator.update_with_telemetry(create_test_telemetry(3000, Some(10.0), Some(-3.0), None));
+        calculator.update_with_telemetry(create_test_telemetry(4000, Some(10.0), None, None));
+        assert!((calculator.get_summary(None).cpu_energy_wh - 20.0 / 3600.0).abs() < 1e-12);
+
+        // Sleep: two minutes without samples, then the clock resets to an earlier value
+        calculator.update_with_telemetry(create_test_telemetry(124000, Some(10.0), None, None));
+        calculator.update_with_telemetry(create_test_telemetry(125000, Some(10.0), None, None));
+        calculator.update_with_telemetry(create_test_telemetry(5000, Some(10.0), None, None));
+        let result = calculator.update_with_telemetry(create_test_telemetry(6000, Some(10.0), None, None));
+        assert!((result.cpu_energy_wh.unwrap() - 40.0 / 3600.0).abs() < 1e-12);
+
+        let summary = calculator.get_summary(None);
+        assert_eq!(summary.sample_quality, PowerSampleQuality {
+            discarded_samples: 2,
+            rejected_power_readings: 3,
+            series_resets: 2,
+        });
+    }
+
+    #[test]
+    fn test_inference_phases_split_energy_between_samples() {
+        let mut accumulators = ModelEnergyAccumulators::new(IntegrationMethod::Trapezoid);
+        accumulators.begin_phase("A");
+        accumulators.update_with_telemetry(create_test_telemetry(0, Some(10.0), None, None));
+        // Prefill from 0.5 s to 1.5 s at 10 W; the 30 W sample at 2 s arrives before the late end marker
+        accumulators.begin_inference_phase("A", InferencePhase::Prefill, 500);
+        accumulators.update_with_telemetry(create_test_telemetry(1000, Some(10.0), None, None));
+        accumulators.update_with_telemetry(create_test_telemetry(2000, Some(30.0), None, None));
+        accumulators.end_inference_phase("A", InferencePhase::Prefill, 1500);
+        // Markers for a model whose phase isn't active are ignored
+        accumulators.begin_inference_phase("B", InferencePhase::Decode, 1500);
+        accumulators.begin_inference_phase("A", InferencePhase::Decode, 2000);
+        accumulators.update_with_telemetry(create_test_telemetry(3000, Some(30.0), None, None));
+        accumulators.update_with_telemetry(create_test_telemetry(4000, Some(30.0), None, None));
+        accumulators.end_inference_phase("A", InferencePhase::Decode, 4000);
+
+        let summary = &accumulators.summaries()["A"];
+        let prefill = summary.prefill.unwrap();
+        let decode = summary.decode.unwrap();
+        assert_eq!((prefill.duration_ms, decode.duration_ms), (1000, 2000));
+        // Held backwards from the 2 s sample, so the late marker sees 30 W over the last 0.5 s
+        assert!((prefill.energy_wh * 3600.0 - 10.0).abs() < 1e-9, "{}", prefill.energy_wh * 3600.0);
+        assert!((decode.energy_wh * 3600.0 - 60.0).abs() < 1e-9, "{}", decode.energy_wh * 3600.0);
+        assert!(decode.energy_wh + prefill.energy_wh <= summary.total_energy_wh + 1e-12);
+    }
+
     #[test]
     fn test_short_time_interval_accuracy() {
         let mut calculator = PowerCalculator::new();
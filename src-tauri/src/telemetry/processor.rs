@@ -1,13 +1,201 @@
 // Telemetry processor module - Step 4: Global State Migration
 // Contains global state management for telemetry and generation control
 
-use std::sync::{Arc, atomic::AtomicBool, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}, RwLock};
 
 // Import telemetry data structures from types module
-use crate::telemetry::types::TelemetryUpdate;
+use crate::telemetry::types::{TelemetryCommand, TelemetryCommandBroadcaster, TelemetryUpdate};
+use crate::telemetry::calibration::OverheadCalibration;
+use crate::telemetry::power_calculator::PowerConsumptionSummary;
 
 // Shared state for current telemetry data
 pub static CURRENT_TELEMETRY: RwLock<Option<TelemetryUpdate>> = RwLock::new(None);
 
 // Global stop signal for generation control
-pub static GLOBAL_STOP_SIGNAL: RwLock<Option<Arc<AtomicBool>>> = RwLock::new(None);
\ No newline at end of file
+pub static GLOBAL_STOP_SIGNAL: RwLock<Option<Arc<AtomicBool>>> = RwLock::new(None);
+
+// Command channel of the current generation's telemetry monitors, for pause/resume
+pub static TELEMETRY_COMMANDS: RwLock<Option<TelemetryCommandBroadcaster>> = RwLock::new(None);
+
+// Telemetry paused by the user; a generation's monitors start in this state
+pub static TELEMETRY_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Pause or resume telemetry sampling, for this and later generations. Returns whether a
+/// running monitor received the change.
+pub fn set_telemetry_paused(paused: bool) -> bool {
+    TELEMETRY_PAUSED.store(paused, Ordering::Relaxed);
+    let command = if paused { TelemetryCommand::Pause } else { TelemetryCommand::Resume };
+    TELEMETRY_COMMANDS.read().ok()
+        .and_then(|commands| commands.as_ref().map(|broadcaster| broadcaster.send(command).is_ok()))
+        .unwrap_or(false)
+}
+
+// Per-model stop signals of the current generation, keyed by model label ("A" / "B"); a
+// model stops when either its own signal or the global one is set
+pub static MODEL_STOP_SIGNALS: RwLock<Option<HashMap<String, Arc<AtomicBool>>>> = RwLock::new(None);
+
+// Most recent measurement-overhead calibration, applied to later runs' power summaries
+pub static OVERHEAD_CALIBRATION: RwLock<Option<OverheadCalibration>> = RwLock::new(None);
+
+// Per-model energy totals of the current session, keyed by model label ("A" / "B")
+pub static MODEL_ENERGY: RwLock<Option<BTreeMap<String, PowerConsumptionSummary>>> = RwLock::new(None);
+
+// Id of the generation run currently holding the single-flight guard
+pub static ACTIVE_RUN_ID: RwLock<Option<String>> = RwLock::new(None);
+
+// Resolved model files the active run uses; cleared with ACTIVE_RUN_ID
+pub static ACTIVE_RUN_MODELS: RwLock<Vec<PathBuf>> = RwLock::new(Vec::new());
+
+// Text generated so far in the current (or most recent) run, for get_partial_output; a new
+// run replaces it
+pub static PARTIAL_OUTPUTS: RwLock<Option<PartialOutputs>> = RwLock::new(None);
+
+#[derive(Debug, Clone)]
+pub struct PartialOutputs {
+    pub run_id: String,
+    pub outputs: HashMap<String, String>, // Keyed by model label ("A" / "B")
+    pub finished: bool,
+}
+
+/// Start a fresh partial-output buffer for a new run, dropping the previous run's text
+pub fn begin_partial_outputs(run_id: &str) {
+    if let Ok(mut partial) = PARTIAL_OUTPUTS.write() {
+        *partial = Some(PartialOutputs {
+            run_id: run_id.to_string(),
+            outputs: HashMap::new(),
+            finished: false,
+        });
+    }
+}
+
+/// Append decoded text for a model to the active run's buffer
+pub fn append_partial_output(model_label: &str, text: &str) {
+    if let Ok(mut partial) = PARTIAL_OUTPUTS.write() {
+        if let Some(partial) = partial.as_mut() {
+            partial.outputs.entry(model_label.to_string()).or_default().push_str(text);
+        }
+    }
+}
+
+/// Mark the run's buffer as complete; the text stays available until the next run starts
+pub fn finish_partial_outputs(run_id: &str) {
+    if let Ok(mut partial) = PARTIAL_OUTPUTS.write() {
+        if let Some(partial) = partial.as_mut() {
+            if partial.run_id == run_id {
+                partial.finished = true;
+            }
+        }
+    }
+}
+
+/// Give each model of a new generation its own, unset stop signal
+pub fn begin_model_stop_signals(model_labels: &[&str]) {
+    let signals = model_labels.iter()
+        .map(|label| (label.to_string(), Arc::new(AtomicBool::new(false))))
+        .collect();
+    *MODEL_STOP_SIGNALS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(signals);
+}
+
+pub fn clear_model_stop_signals() {
+    *MODEL_STOP_SIGNALS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Stop one model of the current generation; false when no generation runs that model
+pub fn request_model_stop(model_label: &str) -> bool {
+    let signals = MODEL_STOP_SIGNALS.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match signals.as_ref().and_then(|signals| signals.get(model_label)) {
+        Some(signal) => {
+            signal.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Whether `model_label` should stop: its own signal or the global one is set
+pub fn model_stop_requested(model_label: &str) -> bool {
+    let global = GLOBAL_STOP_SIGNAL.read().ok()
+        .and_then(|guard| guard.as_ref().map(|stop_signal| stop_signal.load(Ordering::Relaxed)))
+        .unwrap_or(false);
+    global || MODEL_STOP_SIGNALS.read().ok()
+        .and_then(|signals| signals.as_ref()?.get(model_label).map(|signal| signal.load(Ordering::Relaxed)))
+        .unwrap_or(false)
+}
+
+/// Returned when a run is requested while another one is active; surfaces to the
+/// frontend as `A2oError::RunInProgress`
+#[derive(Debug, Clone)]
+pub struct RunInProgressError {
+    pub message: String,
+    pub active_run_id: String,
+}
+
+/// Single-flight guard for generation runs
+///
+/// Only one guard can exist at a time; the active run id is cleared when the
+/// guard is dropped, including on early returns and errors.
+pub struct ActiveRunGuard {
+    run_id: String,
+}
+
+impl ActiveRunGuard {
+    pub fn acquire() -> Result<Self, RunInProgressError> {
+        let mut active = ACTIVE_RUN_ID.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(active_run_id) = active.as_ref() {
+            return Err(RunInProgressError {
+                message: format!("A generation run is already in progress ({})", active_run_id),
+                active_run_id: active_run_id.clone(),
+            });
+        }
+
+        let run_id = uuid::Uuid::new_v4().to_string();
+        *active = Some(run_id.clone());
+        Ok(Self { run_id })
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Record the model files this run uses, so they cannot be deleted under it
+    pub fn set_model_paths(&self, paths: Vec<PathBuf>) {
+        *ACTIVE_RUN_MODELS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = paths;
+    }
+}
+
+/// Model files used by the run in progress (empty when idle)
+pub fn active_run_model_paths() -> Vec<PathBuf> {
+    ACTIVE_RUN_MODELS.read().map(|paths| paths.clone()).unwrap_or_default()
+}
+
+impl Drop for ActiveRunGuard {
+    fn drop(&mut self) {
+        let mut active = ACTIVE_RUN_ID.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if active.as_deref() == Some(self.run_id.as_str()) {
+            *active = None;
+            ACTIVE_RUN_MODELS.write().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_stop_only_stops_that_model() {
+        assert!(!request_model_stop("A"));
+
+        begin_model_stop_signals(&["A", "B"]);
+        assert!(request_model_stop("A"));
+        assert!(model_stop_requested("A"));
+        assert!(!model_stop_requested("B"));
+        assert!(!request_model_stop("C"));
+
+        clear_model_stop_signals();
+        assert!(!model_stop_requested("A"));
+    }
+}
@@ -0,0 +1,263 @@
+// Synthetic load: a known workload (repeated f32 matrix multiplies) run without any model,
+// so the telemetry setup can be checked and sensors compared against a fixed load
+
+use std::sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::events::EventSink;
+use crate::hardware::start_enhanced_monitoring;
+use crate::telemetry::types::{IntegrationMethod, TelemetryProfile, TelemetryUpdate};
+
+// Square matrix size; 256x256 f32 operands stay in the L2 cache so the load is compute-bound
+const MATRIX_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyntheticLoadProfile {
+    CpuSingle,   // One worker thread
+    CpuAll,      // One worker per logical core
+    Gpu,         // Needs Metal compute, which this build does not link
+}
+
+impl SyntheticLoadProfile {
+    pub fn worker_threads(self) -> usize {
+        match self {
+            SyntheticLoadProfile::CpuSingle => 1,
+            SyntheticLoadProfile::CpuAll | SyntheticLoadProfile::Gpu => {
+                std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyntheticLoadReport {
+    pub profile: SyntheticLoadProfile,
+    pub backend: String,                    // "accelerate" (cblas_sgemm) or "rust"
+    pub threads: usize,
+    pub duration_s: f64,
+    pub iterations: u64,                    // Matrix multiplies completed over all threads
+    pub gflops: f64,
+    pub sampling_hz: Option<f32>,           // None = telemetry not running
+    pub samples: usize,
+    pub avg_cpu_power_w: Option<f64>,
+    pub avg_gpu_power_w: Option<f64>,
+    pub avg_package_power_w: Option<f64>,   // Mean CPU+GPU+ANE power over the load
+    pub max_cpu_temp_c: Option<f64>,
+    pub avg_cpu_utilization: Option<f64>,
+}
+
+#[cfg(target_os = "macos")]
+mod accelerate {
+    const CBLAS_ROW_MAJOR: i32 = 101;
+    const CBLAS_NO_TRANS: i32 = 111;
+
+    #[link(name = "Accelerate", kind = "framework")]
+    extern "C" {
+        fn cblas_sgemm(
+            order: i32, trans_a: i32, trans_b: i32,
+            m: i32, n: i32, k: i32,
+            alpha: f32, a: *const f32, lda: i32,
+            b: *const f32, ldb: i32,
+            beta: f32, c: *mut f32, ldc: i32,
+        );
+    }
+
+    /// c = a * b for square row-major matrices of side n
+    pub fn sgemm(a: &[f32], b: &[f32], c: &mut [f32], n: usize) {
+        assert!(a.len() >= n * n && b.len() >= n * n && c.len() >= n * n);
+        let n = n as i32;
+        unsafe {
+            cblas_sgemm(CBLAS_ROW_MAJOR, CBLAS_NO_TRANS, CBLAS_NO_TRANS,
+                        n, n, n, 1.0, a.as_ptr(), n, b.as_ptr(), n, 0.0, c.as_mut_ptr(), n);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+const BACKEND: &str = "accelerate";
+#[cfg(not(target_os = "macos"))]
+const BACKEND: &str = "rust";
+
+#[cfg(target_os = "macos")]
+fn matmul(a: &[f32], b: &[f32], c: &mut [f32], n: usize) {
+    accelerate::sgemm(a, b, c, n);
+}
+
+// i-k-j loop order keeps the inner loop on contiguous rows so it vectorizes
+#[cfg(not(target_os = "macos"))]
+fn matmul(a: &[f32], b: &[f32], c: &mut [f32], n: usize) {
+    c[..n * n].fill(0.0);
+    for i in 0..n {
+        for k in 0..n {
+            let a_ik = a[i * n + k];
+            let b_row = &b[k * n..(k + 1) * n];
+            let c_row = &mut c[i * n..(i + 1) * n];
+            for (c_ij, b_kj) in c_row.iter_mut().zip(b_row) {
+                *c_ij += a_ik * b_kj;
+            }
+        }
+    }
+}
+
+/// Run `threads` matmul workers until `duration` has passed or `stop_signal` is set;
+/// returns the number of multiplies completed
+pub fn run_workers(threads: usize, duration: Duration, stop_signal: Arc<AtomicBool>) -> u64 {
+    let iterations = Arc::new(AtomicU64::new(0));
+    let deadline = Instant::now() + duration;
+    let workers: Vec<_> = (0..threads.max(1))
+        .map(|worker| {
+            let iterations = iterations.clone();
+            let stop_signal = stop_signal.clone();
+            std::thread::spawn(move || {
+                let n = MATRIX_SIZE;
+                let a: Vec<f32> = (0..n * n).map(|i| ((i + worker) % 17) as f32 * 0.01).collect();
+                let b: Vec<f32> = (0..n * n).map(|i| ((i * 7) % 13) as f32 * 0.01).collect();
+                let mut c = vec![0.0f32; n * n];
+                while Instant::now() < deadline && !stop_signal.load(Ordering::Relaxed) {
+                    matmul(&a, &b, &mut c, n);
+                    std::hint::black_box(&mut c);
+                    iterations.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    iterations.load(Ordering::Relaxed)
+}
+
+fn gflops(iterations: u64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        return 0.0;
+    }
+    let flops_per_multiply = 2.0 * (MATRIX_SIZE as f64).powi(3);
+    iterations as f64 * flops_per_multiply / seconds / 1e9
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Run the load for `duration`, sampling telemetry at `sampling_hz` when set. Samples are
+/// emitted as `telemetry_update` like a generation run, so the dashboard shows them live.
+pub async fn run_synthetic_load(
+    events: &dyn EventSink,
+    profile: SyntheticLoadProfile,
+    duration: Duration,
+    sampling_hz: Option<f32>,
+    telemetry_profile: TelemetryProfile,
+    stop_signal: Arc<AtomicBool>,
+) -> Result<SyntheticLoadReport, String> {
+    if profile == SyntheticLoadProfile::Gpu {
+        return Err("GPU synthetic load needs Metal compute, which this build does not include".to_string());
+    }
+
+    let monitor_stop = Arc::new(AtomicBool::new(false));
+    let (telemetry_tx, mut telemetry_rx) = broadcast::channel::<TelemetryUpdate>(1000);
+    let telemetry_broadcaster = Arc::new(telemetry_tx);
+    let monitor_handle = sampling_hz.map(|hz| {
+        let broadcaster = telemetry_broadcaster.clone();
+        let stop = monitor_stop.clone();
+        tokio::spawn(async move {
+            if let Err(e) = start_enhanced_monitoring(broadcaster, stop, None, Some(hz), telemetry_profile, IntegrationMethod::default(), None, None).await {
+                println!("❌ Synthetic load monitoring error: {}", e);
+            }
+        })
+    });
+
+    let threads = profile.worker_threads();
+    println!("🔥 SYNTHETIC LOAD: {:?} on {} thread(s) ({} backend) for {:?}", profile, threads, BACKEND, duration);
+    let started = Instant::now();
+    let workers_stop = stop_signal.clone();
+    let mut workers = tokio::task::spawn_blocking(move || run_workers(threads, duration, workers_stop));
+
+    let mut cpu_power = Vec::new();
+    let mut gpu_power = Vec::new();
+    let mut package_power = Vec::new();
+    let mut cpu_utilization = Vec::new();
+    let mut max_cpu_temp_c: Option<f64> = None;
+    let mut samples = 0;
+    let iterations = loop {
+        tokio::select! {
+            finished = &mut workers => {
+                break finished.map_err(|e| format!("Synthetic load workers failed: {}", e))?;
+            }
+            received = telemetry_rx.recv(), if monitor_handle.is_some() => {
+                let telemetry = match received {
+                    Ok(telemetry) => telemetry,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break (&mut workers).await.map_err(|e| format!("Synthetic load workers failed: {}", e))?;
+                    }
+                };
+                let _ = events.emit("telemetry_update", &telemetry);
+                samples += 1;
+                cpu_power.extend(telemetry.cpu_power_watts);
+                gpu_power.extend(telemetry.gpu_power_watts);
+                let parts = [telemetry.cpu_power_watts, telemetry.gpu_power_watts, telemetry.ane_power_watts];
+                if parts.iter().any(|p| p.is_some()) {
+                    package_power.push(parts.iter().flatten().sum());
+                }
+                cpu_utilization.extend(telemetry.cpu_overall_utilization);
+                if let Some(temp) = telemetry.cpu_temp_max.or(telemetry.cpu_temp_celsius) {
+                    max_cpu_temp_c = Some(max_cpu_temp_c.map_or(temp, |max| max.max(temp)));
+                }
+            }
+        }
+    };
+    let elapsed = started.elapsed();
+
+    monitor_stop.store(true, Ordering::Relaxed);
+    if let Some(handle) = monitor_handle {
+        let _ = handle.await;
+    }
+
+    let report = SyntheticLoadReport {
+        profile,
+        backend: BACKEND.to_string(),
+        threads,
+        duration_s: elapsed.as_secs_f64(),
+        iterations,
+        gflops: gflops(iterations, elapsed),
+        sampling_hz,
+        samples,
+        avg_cpu_power_w: mean(&cpu_power),
+        avg_gpu_power_w: mean(&gpu_power),
+        avg_package_power_w: mean(&package_power),
+        max_cpu_temp_c,
+        avg_cpu_utilization: mean(&cpu_utilization),
+    };
+    println!("🔥 SYNTHETIC LOAD: {} multiplies in {:.1}s ({:.1} GFLOPS), package power {:?}W ({} samples)",
+             report.iterations, report.duration_s, report.gflops, report.avg_package_power_w, report.samples);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workers_multiply_until_deadline_or_stop() {
+        let n = 3;
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        let mut c = [0.0; 9];
+        matmul(&a, &identity, &mut c, n);
+        assert_eq!(c, a);
+
+        let iterations = run_workers(1, Duration::from_millis(50), Arc::new(AtomicBool::new(false)));
+        assert!(iterations > 0);
+        assert_eq!(run_workers(2, Duration::from_secs(60), Arc::new(AtomicBool::new(true))), 0);
+
+        // One 256^3 multiply per second is 2 * 256^3 flops
+        assert!((gflops(1, Duration::from_secs(1)) - 0.033_554_432).abs() < 1e-12);
+    }
+}
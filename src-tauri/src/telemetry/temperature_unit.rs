@@ -0,0 +1,96 @@
+// Temperature display unit preference. Telemetry is always recorded and stored in Celsius;
+// the preference only changes what summaries, reports, alerts and exports present.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Telemetry point fields holding a Celsius temperature
+pub const TEMPERATURE_FIELDS: [&str; 8] = [
+    "cpu_temp",
+    "gpu_temp",
+    "cpu_temp_avg",
+    "cpu_temp_max",
+    "cpu_temp_min",
+    "gpu_temp_avg",
+    "gpu_temp_max",
+    "battery_temp_avg",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// A Celsius reading in this unit
+    pub fn from_celsius(&self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// A Celsius difference (e.g. a cooldown margin) in this unit
+    pub fn delta_from_celsius(&self, delta_celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => delta_celsius,
+            TemperatureUnit::Fahrenheit => delta_celsius * 9.0 / 5.0,
+        }
+    }
+
+    /// e.g. "71.6°F"
+    pub fn format(&self, celsius: f64) -> String {
+        format!("{:.1}{}", self.from_celsius(celsius), self.symbol())
+    }
+}
+
+/// Add `<field>_f` next to every Celsius field of a telemetry point when the preference is
+/// Fahrenheit; the Celsius values are left as recorded
+pub fn add_converted_fields(point: &mut Value, unit: TemperatureUnit) {
+    if unit == TemperatureUnit::Celsius {
+        return;
+    }
+    let Some(fields) = point.as_object_mut() else {
+        return;
+    };
+    for field in TEMPERATURE_FIELDS {
+        if let Some(celsius) = fields.get(field).and_then(Value::as_f64) {
+            fields.insert(format!("{}_f", field), Value::from(unit.from_celsius(celsius)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fahrenheit_conversion_and_convenience_fields() {
+        let fahrenheit = TemperatureUnit::Fahrenheit;
+        assert_eq!(fahrenheit.from_celsius(100.0), 212.0);
+        assert_eq!(fahrenheit.delta_from_celsius(5.0), 9.0);
+        assert_eq!(fahrenheit.format(22.0), "71.6°F");
+        assert_eq!(TemperatureUnit::Celsius.format(22.0), "22.0°C");
+        assert_eq!(serde_json::to_value(fahrenheit).unwrap(), json!("fahrenheit"));
+
+        let mut point = json!({ "timestamp": 1, "cpu_temp": 50.0, "gpu_temp": null, "tps": 30.0 });
+        add_converted_fields(&mut point, TemperatureUnit::Celsius);
+        assert!(point.get("cpu_temp_f").is_none());
+        add_converted_fields(&mut point, fahrenheit);
+        assert_eq!(point["cpu_temp"], 50.0);
+        assert_eq!(point["cpu_temp_f"], 122.0);
+        assert!(point.get("gpu_temp_f").is_none());
+        assert!(point.get("tps_f").is_none());
+    }
+}
@@ -1,14 +1,119 @@
 // Contains all telemetry-related data structures, event types, and configuration structures
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tokio::sync::broadcast;
 
 // Import from hardware temperature module for TelemetryUpdate
 use crate::hardware::temperature::CoreTemperatureData;
+use crate::telemetry::power_calculator::PowerConsumptionSummary;
+use crate::telemetry::phase::PhaseAck;
+use crate::inference::generation::DEFAULT_N_CTX;
+use crate::inference::sampler_presets::expand_sampler_preset;
+use crate::inference::context_params::EffectiveContextParams;
+use crate::inference::resume::ResumeCheckpoint;
+use crate::inference::speculative::SpeculativeStats;
+
+// Shared telemetry/run defaults (also reported to the frontend via get_default_configs)
+pub const DEFAULT_TELEMETRY_SAMPLING_HZ: f32 = 1.0;
+pub const MIN_TELEMETRY_SAMPLING_HZ: f32 = 0.1;
+pub const MAX_TELEMETRY_SAMPLING_HZ: f32 = 50.0;
+pub const DEFAULT_COOLDOWN_MARGIN_C: f64 = 2.0;
+pub const DEFAULT_ROLLING_TPS_WINDOW_SECS: f64 = 5.0;
+pub const MIN_ROLLING_TPS_WINDOW_SECS: f64 = 0.5;
+pub const MAX_ROLLING_TPS_WINDOW_SECS: f64 = 60.0;
+pub const DEFAULT_STALL_THRESHOLD_MS: f64 = 250.0;
+pub const DEFAULT_SAMPLER_SEED: u32 = 1234;
+
+// Highest sampling rate allowed for the minimal-overhead telemetry profile
+pub const MINIMAL_PROFILE_MAX_SAMPLING_HZ: f32 = 1.0;
+
+/// What the telemetry monitor collects on each sample
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryProfile {
+    /// Per-core temperatures, per-core utilization, power and frequencies
+    #[default]
+    Full,
+    /// Package power and aggregate temperatures only, capped at a low rate
+    Minimal,
+}
+
+impl TelemetryProfile {
+    /// Clamp a requested sampling rate to what this profile allows
+    pub fn clamp_sampling_hz(&self, sampling_hz: f32) -> f32 {
+        match self {
+            TelemetryProfile::Full => sampling_hz,
+            TelemetryProfile::Minimal => sampling_hz.min(MINIMAL_PROFILE_MAX_SAMPLING_HZ),
+        }
+    }
+}
+
+/// How power samples are integrated into energy
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationMethod {
+    /// Each sample's power held over the interval that ends at it
+    Rectangle,
+    /// Linear interpolation between consecutive samples
+    #[default]
+    Trapezoid,
+    /// Monotone (Fritsch-Carlson) cubic Hermite interpolation; never overshoots the samples
+    MonotoneCubic,
+}
+
+/// Power samples the energy integration left out, so a summary shows how much it can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PowerSampleQuality {
+    pub discarded_samples: u32,         // Duplicate or out-of-order timestamps
+    pub rejected_power_readings: u32,   // Negative, non-finite or implausibly high channel readings
+    pub series_resets: u32,             // Gaps or backwards clock jumps (e.g. sleep) not integrated across
+}
+
+/// Part of one model's inference, marked by the inference loop for the energy breakdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InferencePhase {
+    Prefill,    // Prompt processing
+    Decode,     // Token generation
+}
+
+/// Energy and wall time between an inference phase's begin and end markers
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PhaseEnergy {
+    pub energy_wh: f64,
+    pub duration_ms: u64,
+}
+
+/// Experimental: which core type llama.cpp threads should prefer (macOS QoS classes)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorePreference {
+    #[default]
+    Default,        // Leave scheduling to the OS
+    Performance,    // User-interactive QoS, favours P-cores
+    Efficiency,     // Background QoS, confined to E-cores
+}
+
+/// Loop detection settings: generation ends once the latest `ngram` tokens have occurred
+/// `max_repeats` times within the last `window` generated tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoopDetection {
+    pub ngram: usize,
+    pub window: usize,
+    pub max_repeats: usize,
+}
+
+impl Default for LoopDetection {
+    fn default() -> Self {
+        Self { ngram: 16, window: 512, max_repeats: 4 }
+    }
+}
 
 // Configuration structures for model and generation settings
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ModelConfig {
     pub model_path: String,
@@ -21,9 +126,26 @@ pub struct ModelConfig {
     pub repeat_last_n: Option<i32>,   // i32 required by llama-cpp-2 API
     pub frequency_penalty: Option<f32>, // f32 required by llama-cpp-2 API
     pub presence_penalty: Option<f32>, // f32 required by llama-cpp-2 API
+    pub penalize_newline: Option<bool>,            // false exempts the newline token from penalties
+    pub penalty_exempt_tokens: Option<Vec<String>>, // Token strings exempt from penalties
+    pub sampler_preset: Option<String>,  // Named backend preset; replaces the sampling fields above at run time
     // Context configuration
-    pub n_ctx: Option<u32>,
+    pub n_ctx: Option<ContextSize>,  // Token count or "auto" (sized from GGUF metadata)
+    pub n_batch: Option<u32>,        // Prompt tokens per prefill decode (default 512, capped at n_ctx)
+    pub n_gpu_layers: Option<u32>,   // Layers offloaded to the GPU (Metal); 0 = CPU only, None = llama.cpp default
+    pub max_tokens: Option<u32>,     // Tokens generated per turn (default 1024)
+    pub stop_sequences: Option<Vec<String>>, // Generation ends when the output contains any of these
+    pub chat_template_override: Option<String>, // "chatml" / "llama3" / "mistral" or a Jinja template; None = GGUF template
+    pub context_shift: Option<bool>, // Evict old KV entries (keeping the system prompt) instead of failing at n_ctx
+    pub auto_truncate: Option<bool>, // Drop the oldest turns (keeping the system prompt) when the prompt does not fit n_ctx
+    pub loop_detection: Option<LoopDetection>, // End generation as "loop_detected" when the output repeats; None = off
+    pub grammar: Option<String>,     // GBNF grammar (root rule "root") or "json"; None = unconstrained
     pub telemetry_sampling_hz: Option<f32>,  // Telemetry sampling frequency in Hz (e.g., 1.0 = 1Hz = every 1000ms)
+    pub core_preference: Option<CorePreference>, // Experimental P-core/E-core preference for inference threads
+    pub seed: Option<SamplerSeed>,   // Distribution sampler seed or "random" (default 1234)
+    pub resume_tokens: Option<Vec<i32>>, // Set from GenerationConfig.resume: tokens replayed before generating further
+    pub draft_model_path: Option<String>, // Small model with the same vocabulary for speculative decoding (offloaded like n_gpu_layers); None = off
+    pub n_draft: Option<u32>,        // Draft tokens proposed per verification (default 5)
 }
 
 impl Default for ModelConfig {
@@ -39,13 +161,200 @@ impl Default for ModelConfig {
             repeat_last_n: Some(64),      // Standard window size
             frequency_penalty: Some(0.0),  // Disabled by default
             presence_penalty: Some(0.0),   // Disabled by default
-            n_ctx: Some(4096),            // Reasonable context size
-            telemetry_sampling_hz: Some(1.0),
+            penalize_newline: Some(true),  // Matches plain llama.cpp penalties behavior
+            penalty_exempt_tokens: None,
+            sampler_preset: None,
+            n_ctx: Some(ContextSize::Fixed(DEFAULT_N_CTX)), // Same size runs fall back to without one
+            n_batch: None,
+            n_gpu_layers: None,
+            max_tokens: None,
+            stop_sequences: None,
+            chat_template_override: None,
+            context_shift: None,
+            auto_truncate: None,
+            loop_detection: None,
+            grammar: None,
+            telemetry_sampling_hz: Some(DEFAULT_TELEMETRY_SAMPLING_HZ),
+            core_preference: None,
+            seed: None,
+            resume_tokens: None,
+            draft_model_path: None,
+            n_draft: None,
+        }
+    }
+}
+
+/// Sampling parameters for one turn. As a per-turn override, None keeps the stored
+/// model config's value; on a GenerationResult, the values the turn actually used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    pub temperature: Option<f32>,
+    pub top_k: Option<i32>,
+    pub top_p: Option<f32>,
+    pub min_p: Option<f32>,
+    pub repeat_penalty: Option<f32>,
+    pub repeat_last_n: Option<i32>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    pub penalize_newline: Option<bool>,
+    pub seed: Option<SamplerSeed>,   // On a GenerationResult, the fixed seed the turn used
+}
+
+impl SamplingParams {
+    pub fn from_model_config(config: &ModelConfig) -> Self {
+        Self {
+            temperature: config.temperature,
+            top_k: config.top_k,
+            top_p: config.top_p,
+            min_p: config.min_p,
+            repeat_penalty: config.repeat_penalty,
+            repeat_last_n: config.repeat_last_n,
+            frequency_penalty: config.frequency_penalty,
+            presence_penalty: config.presence_penalty,
+            penalize_newline: config.penalize_newline,
+            seed: config.seed,
+        }
+    }
+
+    /// Copy of `config` with every set parameter replacing the stored one
+    pub fn apply_to(&self, config: &ModelConfig) -> ModelConfig {
+        ModelConfig {
+            temperature: self.temperature.or(config.temperature),
+            top_k: self.top_k.or(config.top_k),
+            top_p: self.top_p.or(config.top_p),
+            min_p: self.min_p.or(config.min_p),
+            repeat_penalty: self.repeat_penalty.or(config.repeat_penalty),
+            repeat_last_n: self.repeat_last_n.or(config.repeat_last_n),
+            frequency_penalty: self.frequency_penalty.or(config.frequency_penalty),
+            presence_penalty: self.presence_penalty.or(config.presence_penalty),
+            penalize_newline: self.penalize_newline.or(config.penalize_newline),
+            seed: self.seed.or(config.seed),
+            ..config.clone()
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Requested context size: a fixed token count, or "auto" to size from the model's
+/// trained context length clamped to what fits in memory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContextSize {
+    Fixed(u32),
+    Auto,
+}
+
+impl std::fmt::Display for ContextSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextSize::Fixed(n_ctx) => write!(f, "{}", n_ctx),
+            ContextSize::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl Serialize for ContextSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ContextSize::Fixed(n_ctx) => serializer.serialize_u32(*n_ctx),
+            ContextSize::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContextSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ContextSizeRepr {
+            Fixed(u32),
+            Named(String),
+        }
+
+        match ContextSizeRepr::deserialize(deserializer)? {
+            ContextSizeRepr::Fixed(n_ctx) => Ok(ContextSize::Fixed(n_ctx)),
+            ContextSizeRepr::Named(name) if name.eq_ignore_ascii_case("auto") => Ok(ContextSize::Auto),
+            ContextSizeRepr::Named(name) => Err(serde::de::Error::custom(
+                format!("invalid n_ctx '{}': expected a number or \"auto\"", name)
+            )),
+        }
+    }
+}
+
+/// Seed of the final distribution sampler: a fixed value, or "random" for a fresh seed
+/// every turn. Resolved to a fixed value when the turn starts, so results record it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerSeed {
+    Fixed(u32),
+    Random,
+}
+
+impl SamplerSeed {
+    /// The seed to sample with; a Random seed is drawn on each call
+    pub fn resolve(self) -> u32 {
+        use std::hash::{BuildHasher, Hasher};
+        match self {
+            SamplerSeed::Fixed(seed) => seed,
+            SamplerSeed::Random => std::collections::hash_map::RandomState::new().build_hasher().finish() as u32,
+        }
+    }
+}
+
+impl std::fmt::Display for SamplerSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SamplerSeed::Fixed(seed) => write!(f, "{}", seed),
+            SamplerSeed::Random => write!(f, "random"),
+        }
+    }
+}
+
+impl Serialize for SamplerSeed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SamplerSeed::Fixed(seed) => serializer.serialize_u32(*seed),
+            SamplerSeed::Random => serializer.serialize_str("random"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SamplerSeed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SamplerSeedRepr {
+            Fixed(u32),
+            Named(String),
+        }
+
+        match SamplerSeedRepr::deserialize(deserializer)? {
+            SamplerSeedRepr::Fixed(seed) => Ok(SamplerSeed::Fixed(seed)),
+            SamplerSeedRepr::Named(name) if name.eq_ignore_ascii_case("random") => Ok(SamplerSeed::Random),
+            SamplerSeedRepr::Named(name) => Err(serde::de::Error::custom(
+                format!("invalid seed '{}': expected a number or \"random\"", name)
+            )),
+        }
+    }
+}
+
+// Canonical backend defaults returned by get_default_configs
+#[derive(Debug, Clone, Serialize)]
+pub struct DefaultConfigs {
+    pub model: ModelConfig,                     // Sampling and context defaults (model_path empty)
+    pub fallback_n_ctx: u32,                    // Context size used when n_ctx is omitted
+    pub max_generation_tokens: u32,
+    pub telemetry_sampling_hz: f32,
+    pub telemetry_sampling_hz_min: f32,
+    pub telemetry_sampling_hz_max: f32,
+    pub run_without_telemetry: bool,
+    pub telemetry_profile: TelemetryProfile,
+    pub minimal_profile_max_sampling_hz: f32,
+    pub energy_integration_method: IntegrationMethod,
+    pub rolling_tps_window_secs: f64,
+    pub stall_threshold_ms: f64,
+    pub wait_for_cpu_baseline_between_models: bool,
+    pub wait_for_cpu_baseline_margin_c: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Message {
     pub role: String,
@@ -53,10 +362,11 @@ pub struct Message {
     pub model: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[allow(dead_code)]
 pub struct GenerationConfig {
-    pub chat_history: Vec<Message>,
+    #[serde(default)]
+    pub chat_history: Vec<Message>,   // Filled from the backend history by run_turn
     pub target: String, // "A", "B", or "Both"
     pub model_a: Option<ModelConfig>,
     pub model_b: Option<ModelConfig>,
@@ -64,7 +374,78 @@ pub struct GenerationConfig {
     pub telemetry_sampling_hz: Option<f32>,  // Global telemetry sampling frequency for this generation
     pub wait_for_cpu_baseline_between_models: Option<bool>, // New option to control cooldown between A and B
     pub wait_for_cpu_baseline_margin_c: Option<f64>, // Tolerance margin in °C above baseline
+    pub heat_soak_target_c: Option<f64>, // Heat (or cool) the CPU to this max temperature before each model's run; None = off
+    pub heat_soak_band_c: Option<f64>,   // Allowed deviation from heat_soak_target_c (default 2 °C)
     pub run_without_telemetry: Option<bool>, // When true, skip starting telemetry collection/emission
+    pub stream_output_to_disk: Option<bool>, // When true, tee each model's output into a JSONL file as it streams
+    pub stream_output_dir: Option<String>,   // Overrides the default <app data>/streams directory
+    pub subtract_measurement_overhead: Option<bool>, // Subtract calibrated telemetry overhead from energy summaries
+    pub subtract_idle_baseline: Option<bool>, // Subtract the captured idle power from readings, so energy is the model's marginal cost
+    pub telemetry_profile: Option<TelemetryProfile>, // "full" (default) or "minimal" for the lowest overhead
+    pub energy_integration_method: Option<IntegrationMethod>, // "trapezoid" (default), "rectangle" or "monotone_cubic"
+    pub rolling_tps_window_secs: Option<f64>, // Window for the rolling TPS metric (default 5 s)
+    pub stall_threshold_ms: Option<f64>,      // Inter-token gap counted as a stall (default 250 ms)
+    pub event_sink: Option<String>,           // Extra event destination: "jsonl:<path>", "ws:<host:port>" or "null"
+    pub debug_capture: Option<bool>,          // Developer option: mirror raw sensor readings and macmon lines to a capture file
+    pub debug_capture_dir: Option<String>,    // Overrides the default <app data>/debug_captures directory
+    pub sampling_overrides_a: Option<SamplingParams>, // This turn only; the stored model_a config is unchanged
+    pub sampling_overrides_b: Option<SamplingParams>, // This turn only; the stored model_b config is unchanged
+    pub parallel: Option<bool>,               // "Both" only: generate A and B concurrently instead of A then B
+    // Sequential "Both" only: unload the other model before each side runs, so its resident
+    // weights stay out of this model's memory, power and thermal readings. Costs a reload of
+    // both models from disk every turn; off (the default) keeps both cached between turns.
+    pub isolate_models: Option<bool>,
+    pub resume: Option<ResumeCheckpoint>,     // Continue this interrupted generation; chat_history must be the one it started from
+}
+
+impl GenerationConfig {
+    /// Resolve model_a/model_b to the sampling they run with: expand any named preset,
+    /// then fold in this turn's overrides and the seed and tokens of a resumed generation
+    pub fn resolve_sampling(&mut self) -> Result<(), String> {
+        let resume = self.resume.take();
+        if let Some(checkpoint) = &resume {
+            let targeted = matches!((self.target.as_str(), checkpoint.model.as_str()), ("A", "A") | ("B", "B") | ("Both", "A" | "B"));
+            if !targeted {
+                return Err(format!("Cannot resume Model {}'s generation in a turn targeting {}", checkpoint.model, self.target));
+            }
+        }
+        let sides = [
+            ("A", &mut self.model_a, self.sampling_overrides_a.take()),
+            ("B", &mut self.model_b, self.sampling_overrides_b.take()),
+        ];
+        for (label, model, overrides) in sides {
+            let Some(model) = model.as_mut() else { continue };
+            model.resume_tokens = None;
+            if let Some(preset) = &model.sampler_preset {
+                println!("🎛️ Model {} using sampler preset '{}'", label, preset);
+            }
+            *model = expand_sampler_preset(model).map_err(|e| format!("Model {}: {}", label, e))?;
+            if let Some(overrides) = overrides {
+                println!("🎛️ Model {} sampling overrides for this turn: {:?}", label, overrides);
+                *model = overrides.apply_to(model);
+            }
+            // Pin the seed for this turn so results and manifests record the one used
+            let seed = model.seed.unwrap_or(SamplerSeed::Fixed(DEFAULT_SAMPLER_SEED));
+            let resolved = seed.resolve();
+            if seed == SamplerSeed::Random {
+                println!("🎲 Model {} using random seed {}", label, resolved);
+            }
+            model.seed = Some(SamplerSeed::Fixed(resolved));
+
+            if let Some(checkpoint) = resume.as_ref().filter(|checkpoint| checkpoint.model == label) {
+                if checkpoint.model_path != model.model_path {
+                    return Err(format!("Model {}: the generation to resume ran {}, not {}", label, checkpoint.model_path, model.model_path));
+                }
+                match checkpoint.seed {
+                    Some(seed) => model.seed = Some(SamplerSeed::Fixed(seed)),
+                    None => println!("⚠️ Model {} resuming without a recorded seed; the continuation will differ from the original", label),
+                }
+                println!("⏯️ Model {} resuming after {} recorded token(s)", label, checkpoint.tokens.len());
+                model.resume_tokens = Some(checkpoint.tokens.clone());
+            }
+        }
+        Ok(())
+    }
 }
 
 // Event structures for token streaming and telemetry
@@ -103,6 +484,112 @@ pub struct GenerationTimeEvent {
     pub timestamp_ms: u64,
 }
 
+// Structured outcome of one model's generation
+#[derive(Clone, Serialize)]
+pub struct GenerationResult {
+    pub model: String,
+    pub text: String,
+    pub finish_reason: String,                  // "eog" | "max_tokens" | "stop_sequence" | "loop_detected" | "user_stop"
+    pub truncated: bool,                        // Output was cut off by the max_tokens budget
+    pub stop_sequence: Option<String>,          // The stop sequence that ended generation
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub model_load_ms: f64,                     // Loading weights; near zero when the model was resident
+    pub model_reused: bool,                     // Taken from the model cache instead of loaded from disk
+    pub effective_context: Option<EffectiveContextParams>, // What llama.cpp actually used
+    pub ttft_ms: Option<u64>,
+    pub prefill_ms: f64,
+    pub generation_time_ms: u64,
+    pub avg_tps: Option<f64>,                   // Decode TPS from first to last token
+    pub energy: Option<PowerConsumptionSummaryEvent>, // Only when telemetry was enabled; None for parallel runs
+    pub combined_energy: Option<PowerConsumptionSummaryEvent>, // Parallel runs: both models together ("A+B")
+    pub memory: MemoryUsageEvent,
+    pub sampling: SamplingParams,               // Effective sampling parameters, including per-turn overrides
+    pub context_used_tokens: usize,             // Peak context usage: prompt plus decoded tokens
+    pub n_ctx: u32,
+    pub context_shifts: usize,                  // KV cache evictions in context-shift mode
+    pub resumed_tokens: usize,                  // Recorded tokens replayed before generating
+    pub resume_diverged_at: Option<usize>,      // First replayed token the sampler did not reproduce
+    pub speculative: Option<SpeculativeStats>,  // Draft acceptance when speculative decoding ran
+}
+
+// Result of a run_generation_turn job (see await_job): one result per model, in run order
+#[derive(Clone, Serialize)]
+pub struct GenerationTurnResult {
+    pub run_id: String,
+    pub results: Vec<GenerationResult>,
+}
+
+// Draft acceptance of a speculatively decoded generation, emitted when it ends
+#[derive(Clone, Serialize)]
+pub struct SpeculativeDecodingEvent {
+    pub model: String,
+    pub stats: SpeculativeStats,
+    pub timestamp_ms: u64,
+}
+
+// Prompt processing (prefill) speed, reported separately from decode TPS
+#[derive(Clone, Serialize)]
+pub struct PrefillMetricsEvent {
+    pub model: String,
+    pub prompt_tokens: usize,
+    pub prefill_ms: f64,
+    pub prefill_tps: Option<f64>,
+    pub timestamp_ms: u64,
+}
+
+// Emitted each time a context-shift run evicts part of a full KV cache
+#[derive(Clone, Serialize)]
+pub struct ContextShiftEvent {
+    pub model: String,
+    pub shift: usize,               // 1 for the first shift of the run
+    pub kept_tokens: usize,         // Prefix never evicted (system prompt / attention sinks)
+    pub discarded_tokens: usize,
+    pub n_ctx: u32,
+    pub tokens_generated: usize,
+    pub timestamp_ms: u64,
+}
+
+// Emitted when auto_truncate dropped the oldest turns so the prompt fits the context
+#[derive(Clone, Serialize)]
+pub struct ContextTruncatedEvent {
+    pub model: String,
+    pub dropped_messages: usize,
+    pub original_prompt_tokens: usize,
+    pub prompt_tokens: usize,       // After dropping
+    pub n_ctx: u32,
+    pub timestamp_ms: u64,
+}
+
+// Emitted after each prefill chunk on prompts longer than one chunk
+#[derive(Clone, Serialize)]
+pub struct PrefillProgressEvent {
+    pub model: String,
+    pub processed_tokens: usize,
+    pub total_tokens: usize,
+    pub elapsed_ms: u64,            // Time since prefill start
+    pub timestamp_ms: u64,
+}
+
+// One model's templated prompt measured against its own context window
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelPromptBudget {
+    pub model: String,
+    pub prompt_tokens: usize,       // Conversation tokenized with this model's template and vocab
+    pub n_ctx: u32,
+    pub output_budget: usize,       // Reply tokens that fit: n_ctx - prompt, at most the generation limit
+    pub will_truncate: bool,        // The context fills before the generation limit is reached
+}
+
+// Emitted before a "Both" run; tokenizers differ, so one model can run out of context where the other doesn't
+#[derive(Clone, Serialize)]
+pub struct PromptBudgetEvent {
+    pub budgets: Vec<ModelPromptBudget>,
+    pub max_generation_tokens: usize,
+    pub warning: Option<String>,    // Set when some but not all models will truncate
+    pub timestamp_ms: u64,
+}
+
 #[derive(Clone, Serialize)]
 pub struct PowerConsumptionSummaryEvent {
     pub total_energy_wh: f64,
@@ -110,12 +597,131 @@ pub struct PowerConsumptionSummaryEvent {
     pub gpu_energy_wh: f64,
     pub ane_energy_wh: f64,
     pub energy_per_token_wh: Option<f64>,
+    pub integration_method: IntegrationMethod,
+    pub energy_lower_bound_wh: Option<f64>,     // Bounds from holding the lower/higher sample over each interval
+    pub energy_upper_bound_wh: Option<f64>,
+    pub measurement_overhead_wh: Option<f64>,   // Calibrated telemetry overhead over the run, if calibrated
+    pub net_total_energy_wh: Option<f64>,       // Total minus overhead (only when subtraction is enabled)
+    pub net_energy_per_token_wh: Option<f64>,
+    pub idle_power_subtracted_w: Option<f64>,   // Idle baseline removed from readings; the energies above are marginal
+    pub sample_quality: PowerSampleQuality,
+    pub phase_breakdown: Option<PhaseEnergyBreakdown>, // Only for runs with phase markers (not parallel runs)
+    pub model: String,
+    pub timestamp_ms: u64,
+}
+
+// Prompt processing vs generation efficiency within one model's run
+#[derive(Clone, Serialize)]
+pub struct PhaseEnergyBreakdown {
+    pub prefill_energy_wh: f64,
+    pub prefill_ms: u64,
+    pub prefill_energy_per_token_wh: Option<f64>,   // Per prompt token
+    pub decode_energy_wh: f64,
+    pub decode_ms: u64,
+    pub decode_energy_per_token_wh: Option<f64>,    // Per generated token
+}
+
+// Unified memory high-water marks (system-wide used memory), load and generation separately
+#[derive(Clone, Serialize)]
+pub struct MemoryUsageEvent {
+    pub model: String,
+    pub baseline_gb: f64,               // Used memory before the model was loaded
+    pub load_peak_gb: f64,              // Peak while loading weights and allocating the context
+    pub load_delta_gb: f64,             // load_peak_gb - baseline_gb
+    pub generation_peak_gb: f64,        // Peak from prefill to the last token
+    pub generation_delta_gb: f64,       // generation_peak_gb - baseline_gb
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SessionEnergySummaryEvent {
+    pub run_id: String,
+    pub models: BTreeMap<String, PowerConsumptionSummary>, // Keyed by model label ("A" / "B")
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct GenerationStartedEvent {
+    pub run_id: String,
+    pub target: String,
+    pub seeds: BTreeMap<String, u32>,   // Sampler seed per model label ("A" / "B")
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DebugCaptureStartedEvent {
+    pub run_id: String,
+    pub path: String,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TelemetryState {
+    pub paused: bool,
+    pub monitoring: bool,   // A generation's monitor is running and received the change
+}
+
+#[derive(Clone, Serialize)]
+pub struct PartialOutputResponse {
+    pub run_id: String,
+    pub model: String,
+    pub text: String,
+    pub finished: bool,     // False while the run is still generating
+}
+
+#[derive(Clone, Serialize)]
+pub struct ContextSizeResolvedEvent {
+    pub model: String,
+    pub requested: ContextSize,
+    pub n_ctx: u32,                         // Context size actually used for this run
+    pub trained_n_ctx: Option<u32>,         // From GGUF metadata, when readable
+    pub memory_limit_n_ctx: Option<u32>,    // Largest context that fits the memory budget
+    pub rope_scaling: Option<String>,       // RoPE scaling type declared by the model, if any
+    pub trained_context_warning: Option<String>, // Set when n_ctx exceeds the trained context
+    pub timestamp_ms: u64,
+}
+
+// Per-token decode loop timing, so app overhead can be separated from model speed
+#[derive(Clone, Serialize)]
+pub struct DecodeOverheadEvent {
+    pub model: String,
+    pub tokens: usize,
+    pub sample_ms_per_token: Option<f64>,
+    pub decode_ms_per_token: Option<f64>,
+    pub overhead_ms_per_token: Option<f64>,     // Decode-thread time outside sampling and decoding
+    pub emit_worker_ms_per_token: Option<f64>,  // Emit/telemetry work moved off the decode thread
+    pub timestamp_ms: u64,
+}
+
+// Inter-token latency stability for a finished generation (smoothness, not just average speed)
+#[derive(Clone, Serialize)]
+pub struct TokenStabilityEvent {
+    pub model: String,
+    pub intervals: usize,                       // Inter-token gaps measured (tokens - 1)
+    pub mean_inter_token_ms: Option<f64>,
+    pub inter_token_cv: Option<f64>,            // Coefficient of variation of inter-token latency
+    pub longest_stall_ms: Option<f64>,          // Longest gap between two tokens
+    pub stall_count: usize,                     // Gaps longer than stall_threshold_ms
+    pub stall_threshold_ms: f64,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct GenerationProgressEvent {
     pub model: String,
+    pub tokens_generated: usize,
+    pub max_tokens: usize,
+    pub rolling_tps: Option<f64>,   // TPS over the most recent tokens
+    pub eta_ms: Option<u64>,        // Upper-bound estimate until max_tokens is reached
+    pub context_used_tokens: usize, // Prompt plus decoded tokens held in the context
+    pub n_ctx: u32,
+    pub elapsed_ms: u64,            // Time since inference start
+    pub draft_acceptance_rate: Option<f64>, // Share of draft tokens accepted so far (speculative decoding only)
     pub timestamp_ms: u64,
 }
 
 // Primary telemetry data structure
-#[derive(Clone, Serialize, Debug)]
+#[derive(Clone, Serialize, Debug, Default)]
 pub struct TelemetryUpdate {
     pub timestamp_ms: u64,
     pub cpu_power_watts: Option<f64>,
@@ -127,9 +733,13 @@ pub struct TelemetryUpdate {
     pub gpu_freq_mhz: Option<f64>,
     pub ram_usage_gb: Option<f64>,
     pub thermal_pressure: Option<String>,   // Kept for backward compatibility
+    pub swap_usage_gb: Option<f64>,
+    pub compressed_memory_gb: Option<f64>,  // Held by the memory compressor
+    pub memory_pressure_level: Option<String>, // "normal" / "warning" / "critical"
     pub ttft_ms: Option<u64>,
     pub current_tps: Option<f64>,
     pub instantaneous_tps: Option<f64>,
+    pub rolling_tps: Option<f64>,           // TPS over the configured rolling window (e.g. last 5 s)
     pub generation_time_ms: Option<u64>,    // Total generation time from start to finish
     pub model: Option<String>,
     // Enhanced temperature data
@@ -145,6 +755,10 @@ pub struct TelemetryUpdate {
     pub cpu_p_core_utilization: Option<Vec<f64>>, // P-core utilization percentages
     pub cpu_e_core_utilization: Option<Vec<f64>>, // E-core utilization percentages
     pub cpu_overall_utilization: Option<f64>,     // Overall CPU utilization percentage
+    pub inference_cpu_utilization: Option<f64>,   // App's own threads (minus the sampler) as % of all cores
+    pub inference_cpu_share: Option<f64>,         // Share of system-wide busy CPU attributed to the app
+    pub gpu_utilization: Option<f64>,             // GPU busy percentage (IOAccelerator statistics)
+    pub fan_rpm: Option<Vec<f64>>,                // Per-fan speed (SMC F0Ac, F1Ac); None when fanless
     pub core_temperatures: Option<CoreTemperatureData>,
     // Power consumption calculation fields
     pub total_energy_wh: Option<f64>,           // Total energy consumed (Watt-hours)
@@ -152,12 +766,28 @@ pub struct TelemetryUpdate {
     pub gpu_energy_wh: Option<f64>,             // GPU energy consumed
     pub ane_energy_wh: Option<f64>,             // ANE energy consumed
     pub energy_rate_wh_per_token: Option<f64>,  // Energy per token (for efficiency metrics)
+    pub total_energy_lower_wh: Option<f64>,     // Integration uncertainty bounds on total_energy_wh
+    pub total_energy_upper_wh: Option<f64>,
+    // Rates of change, fitted over the last 10 s of samples
+    pub cpu_temp_rate_c_per_s: Option<f64>,
+    pub gpu_temp_rate_c_per_s: Option<f64>,
+    pub battery_temp_rate_c_per_s: Option<f64>,
+    pub package_power_rate_w_per_s: Option<f64>,   // CPU + GPU + ANE
 }
 
 // Control commands for telemetry system
 #[derive(Clone, Serialize, Debug)]
 pub enum TelemetryCommand {
-    ResetPowerCalculator,  // Reset cumulative energy calculation
+    // Attribute energy to this model from the next sample (restarts its totals)
+    BeginModelPhase { model: String, #[serde(skip)] ack: Option<PhaseAck> },
+    // Stop attributing energy to this model, keeping its totals
+    EndModelPhase { model: String, #[serde(skip)] ack: Option<PhaseAck> },
+    // Prefill/decode boundaries within the model's phase, stamped when they happened
+    BeginInferencePhase { model: String, phase: InferencePhase, timestamp_ms: u64, #[serde(skip)] ack: Option<PhaseAck> },
+    EndInferencePhase { model: String, phase: InferencePhase, timestamp_ms: u64, #[serde(skip)] ack: Option<PhaseAck> },
+    // Stop sampling until Resume; energy is not integrated across the pause
+    Pause,
+    Resume,
 }
 
 // Type alias for telemetry broadcasting
@@ -168,7 +798,7 @@ pub type TelemetryCommandBroadcaster = Arc<broadcast::Sender<TelemetryCommand>>;
 
 impl TelemetryUpdate {
     // Helper function to merge telemetry data with inference metrics
-    pub fn with_inference_data(&self, ttft_ms: Option<u64>, current_tps: Option<f64>, instantaneous_tps: Option<f64>, model: Option<String>) -> Self {
+    pub fn with_inference_data(&self, ttft_ms: Option<u64>, current_tps: Option<f64>, instantaneous_tps: Option<f64>, rolling_tps: Option<f64>, model: Option<String>) -> Self {
         TelemetryUpdate {
             timestamp_ms: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -183,6 +813,9 @@ impl TelemetryUpdate {
             gpu_freq_mhz: self.gpu_freq_mhz,
             ram_usage_gb: self.ram_usage_gb,
             thermal_pressure: self.thermal_pressure.clone(),
+            swap_usage_gb: self.swap_usage_gb,
+            compressed_memory_gb: self.compressed_memory_gb,
+            memory_pressure_level: self.memory_pressure_level.clone(),
             cpu_temp_avg: self.cpu_temp_avg,
             cpu_temp_max: self.cpu_temp_max,
             cpu_p_core_temps: self.cpu_p_core_temps.clone(),
@@ -194,10 +827,15 @@ impl TelemetryUpdate {
             cpu_p_core_utilization: self.cpu_p_core_utilization.clone(),
             cpu_e_core_utilization: self.cpu_e_core_utilization.clone(),
             cpu_overall_utilization: self.cpu_overall_utilization,
+            inference_cpu_utilization: self.inference_cpu_utilization,
+            inference_cpu_share: self.inference_cpu_share,
+            gpu_utilization: self.gpu_utilization,
+            fan_rpm: self.fan_rpm.clone(),
             core_temperatures: self.core_temperatures.clone(),
             ttft_ms,
             current_tps,
             instantaneous_tps,
+            rolling_tps,
             generation_time_ms: self.generation_time_ms,
             model,
             // Preserve energy fields
@@ -206,6 +844,59 @@ impl TelemetryUpdate {
             gpu_energy_wh: self.gpu_energy_wh,
             ane_energy_wh: self.ane_energy_wh,
             energy_rate_wh_per_token: self.energy_rate_wh_per_token,
+            total_energy_lower_wh: self.total_energy_lower_wh,
+            total_energy_upper_wh: self.total_energy_upper_wh,
+            cpu_temp_rate_c_per_s: self.cpu_temp_rate_c_per_s,
+            gpu_temp_rate_c_per_s: self.gpu_temp_rate_c_per_s,
+            battery_temp_rate_c_per_s: self.battery_temp_rate_c_per_s,
+            package_power_rate_w_per_s: self.package_power_rate_w_per_s,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sampling_overrides_apply_to_one_side_for_the_turn() {
+        let mut config: GenerationConfig = serde_json::from_value(json!({
+            "chat_history": [],
+            "target": "Both",
+            "model_a": { "model_path": "/models/a.gguf", "temperature": 0.7, "top_k": 40 },
+            "model_b": { "model_path": "/models/b.gguf", "temperature": 0.7 },
+            "sampling_overrides_a": { "temperature": 1.2 },
+        })).unwrap();
+
+        config.resolve_sampling().unwrap();
+        let model_a = config.model_a.as_ref().unwrap();
+        assert_eq!(model_a.temperature, Some(1.2));
+        assert_eq!(model_a.top_k, Some(40));
+        assert_eq!(model_a.model_path, "/models/a.gguf");
+        assert_eq!(config.model_b.as_ref().unwrap().temperature, Some(0.7));
+        assert!(config.sampling_overrides_a.is_none());
+
+        let effective = SamplingParams::from_model_config(model_a);
+        assert_eq!(effective.temperature, Some(1.2));
+        assert_eq!(effective.top_k, Some(40));
+    }
+
+    #[test]
+    fn test_seeds_are_pinned_when_the_turn_starts() {
+        let mut config: GenerationConfig = serde_json::from_value(json!({
+            "target": "Both",
+            "model_a": { "model_path": "/models/a.gguf", "seed": "random" },
+            "model_b": { "model_path": "/models/b.gguf" },
+            "sampling_overrides_b": { "seed": 42 },
+        })).unwrap();
+        assert_eq!(config.model_a.as_ref().unwrap().seed, Some(SamplerSeed::Random));
+
+        config.resolve_sampling().unwrap();
+        assert!(matches!(config.model_a.as_ref().unwrap().seed, Some(SamplerSeed::Fixed(_))));
+        assert_eq!(config.model_b.as_ref().unwrap().seed, Some(SamplerSeed::Fixed(42)));
+        assert_eq!(SamplingParams::from_model_config(&ModelConfig::default()).seed, None);
+        assert!(serde_json::from_value::<SamplerSeed>(json!("lucky")).is_err());
+        assert_eq!(serde_json::to_value(SamplerSeed::Random).unwrap(), json!("random"));
+    }
+}
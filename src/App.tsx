@@ -1,10 +1,12 @@
 import { useEffect, useRef, useCallback, useState } from "react";
-import { invoke } from "@tauri-apps/api/core";
 import { AnalysisDashboard } from './components/charts/analysis/AnalysisDashboard';
 import { SessionSaveDialog } from './components/ui/SessionSaveDialog';
 import { UnsavedChangesDialog } from './components/ui/UnsavedChangesDialog';
 import { subscribeToSessions, getAllSessions, sessionStorage } from './utils/sessionStorage';
-import { SessionPersistence } from './services/sessionPersistence';
+import { SessionPersistence, type SessionReproducibilityReport, type CompatibilityReport } from './services/sessionPersistence';
+import { ReproducibilityBanner } from './components/ui/ReproducibilityBanner';
+import { DataCompatibilityBanner } from './components/ui/DataCompatibilityBanner';
+import { PlatformWarningBanner } from './components/ui/PlatformWarningBanner';
 import { useSessionState } from './hooks/useSessionState';
 import { useOverlayTelemetry } from './hooks/useOverlayTelemetry';
 import { useTauriEventListeners } from './hooks/useTauriEventListeners';
@@ -18,6 +20,8 @@ import { useChatStore } from './stores/chatStore';
 import { useModelStore, type ModelConfig } from './stores/modelStore';
 import { useTelemetryStore } from './stores/telemetryStore';
 import { useUIStore } from './stores/uiStore';
+import type { ModelPerformance } from './types/telemetry';
+import type { ImportedConversation } from './components/chat/ConversationImportButton';
 import "./App.css";
 
 
@@ -51,10 +55,17 @@ function App() {
     systemPrompt, setSystemPrompt,
     telemetry_sampling_hz, setTelemetrySamplingHz,
     run_without_telemetry, setRunWithoutTelemetry,
+    telemetry_profile, setTelemetryProfile,
+    energy_integration_method, setEnergyIntegrationMethod,
+    rolling_tps_window_secs, setRollingTpsWindowSecs,
     chart_refresh_ms, setChartRefreshMs,
     inputTokenCounts: _inputTokenCounts,  // Aggregate per-model token counts for analytics
     outputTokenCounts: _outputTokenCounts, // Aggregate per-model token counts for analytics
     systemPromptTokenCount, setSystemPromptTokenCount,
+    resolvedContextSizes,
+    contextWarnings,
+    runManifest,
+    promptBudget,
     resetTokenCounts,
     updateInputTokenCount,
     updateOutputTokenCount,
@@ -73,6 +84,8 @@ function App() {
     clearSummaryStats,
     getLatestTelemetry,
     transformTelemetryData,
+    flushTelemetrySamples,
+    resumeTelemetryRecording,
     updateSummaryStats,
     setTelemetryData,
     setSummaryStats,
@@ -147,6 +160,8 @@ function App() {
 
   // Defer-baseline state for session restoration
   const [isRestoringSession, setIsRestoringSession] = useState(false);
+  const [reproducibilityReport, setReproducibilityReport] = useState<SessionReproducibilityReport | null>(null);
+  const [compatibilityReport, setCompatibilityReport] = useState<CompatibilityReport | null>(null);
   const [pendingBaseline, setPendingBaseline] = useState<{
     uuid: string;
     expectedChatLen: number;
@@ -172,6 +187,35 @@ function App() {
     };
     hydrateFromLastSession();
   }, []);
+
+  // Startup compatibility check: flag sessions saved by older (or newer) app versions
+  useEffect(() => {
+    SessionPersistence.checkDataCompatibility()
+      .then(setCompatibilityReport)
+      .catch((err) => console.warn('Session compatibility check failed:', err));
+  }, []);
+
+  // Apply the retention policy's sample cap to the in-memory telemetry of a run too
+  useEffect(() => {
+    SessionPersistence.getTelemetryRetentionPolicy()
+      .then((policy) => {
+        if (policy.max_samples) {
+          useTelemetryStore.getState().setTelemetrySampleCap(policy.max_samples);
+        }
+      })
+      .catch((err) => console.warn('Telemetry retention policy load failed:', err));
+  }, []);
+
+  // First launch: no hardware baseline yet, so saved sessions can't be normalized across machines
+  useEffect(() => {
+    SessionPersistence.getHardwareBaseline()
+      .then((baseline) => {
+        if (!baseline) {
+          console.info('No hardware baseline recorded; run measureHardwareBaseline with a small reference model to enable cross-machine normalization');
+        }
+      })
+      .catch((err) => console.warn('Hardware baseline check failed:', err));
+  }, []);
   
   
   // Helper function to generate unique message IDs
@@ -198,7 +242,7 @@ function App() {
   const validateSessionData = (sessionData: any): string[] => {
     const errors: string[] = [];
 
-    if (!sessionData.chat_history?.length && !sessionData.telemetry_data?.length) {
+    if (!sessionData.chat_history?.length && !sessionData.telemetry_data?.samples) {
       errors.push("Session must contain either chat messages or telemetry data");
     }
 
@@ -218,6 +262,10 @@ function App() {
     console.log('💾 Saving session to database:', sessionName);
 
     try {
+      // The samples are already in the database: flush the rest and reference the recording
+      const recordedSamples = await flushTelemetrySamples();
+      const { recordingId } = useTelemetryStore.getState();
+
       // Capture complete current state with data filtering
       const cleanChatHistory = chatHistory.map(msg => ({
         id: msg.id,
@@ -232,7 +280,7 @@ function App() {
       }));
 
       const completeSessionData = {
-        schema_version: 1,
+        schema_version: 2,
         session_metadata: {
           saved_at: Date.now(),
           app_version: "1.0.0",
@@ -244,13 +292,20 @@ function App() {
           model_a: modelA,
           model_b: modelB,
           system_prompt: systemPrompt,
+          resolved_n_ctx: resolvedContextSizes,
+          context_warnings: contextWarnings,
+          prompt_budget: promptBudget,
           telemetry_sampling_hz,
           wait_for_cpu_baseline_between_models: (modelA as any).wait_for_cpu_baseline_between_models || (modelB as any).wait_for_cpu_baseline_between_models || false,
           wait_for_cpu_baseline_margin_c: (modelA as any).wait_for_cpu_baseline_margin_c ?? (modelB as any).wait_for_cpu_baseline_margin_c ?? 2.0,
           run_without_telemetry,
+          telemetry_profile,
+          energy_integration_method,
+          rolling_tps_window_secs,
         },
-        telemetry_data: transformTelemetryData(),
+        telemetry_data: { recording: recordingId, samples: recordedSamples },
         summary_stats: summaryStats,
+        run_manifest: runManifest,
       };
 
       // Validate before save
@@ -297,17 +352,25 @@ function App() {
           const peakCpuTemp = validCpuTemps.length > 0 ? Math.max(...validCpuTemps) : 0;
           
           // Create model performance from summary stats
-          const modelPerformance: { [model: string]: { ttft_ms: number; avg_tps: number } } = {};
+          const modelPerformance: { [model: string]: ModelPerformance } = {};
           if (summaryStats.A) {
             modelPerformance['A'] = {
               ttft_ms: summaryStats.A.ttft_ms || 0,
-              avg_tps: summaryStats.A.avg_tps || 0
+              avg_tps: summaryStats.A.avg_tps || 0,
+              prefill_tps: summaryStats.A.prefill_tps,
+              inter_token_cv: summaryStats.A.inter_token_cv,
+              longest_stall_ms: summaryStats.A.longest_stall_ms,
+              stall_count: summaryStats.A.stall_count
             };
           }
           if (summaryStats.B) {
             modelPerformance['B'] = {
               ttft_ms: summaryStats.B.ttft_ms || 0,
-              avg_tps: summaryStats.B.avg_tps || 0
+              avg_tps: summaryStats.B.avg_tps || 0,
+              prefill_tps: summaryStats.B.prefill_tps,
+              inter_token_cv: summaryStats.B.inter_token_cv,
+              longest_stall_ms: summaryStats.B.longest_stall_ms,
+              stall_count: summaryStats.B.stall_count
             };
           }
           
@@ -380,34 +443,6 @@ function App() {
     }
   };
 
-  // Helper function for telemetry decompression
-  const decompressTelemetryData = async (compressedData: any): Promise<any[]> => {
-    try {
-      console.log('🔄 Decompressing telemetry data:', compressedData);
-      
-      // Call the Tauri command to decompress the data
-      const decompressed = await invoke('decompress_telemetry', { 
-        compressedData: compressedData 
-      });
-      
-      console.log('✅ Telemetry decompression successful, points:', Array.isArray(decompressed) ? decompressed.length : 0);
-      return decompressed as any[];
-    } catch (error) {
-      console.error('❌ Failed to decompress telemetry data:', error);
-      // Fallback: try to handle the data as-is if it's already an array
-      if (Array.isArray(compressedData)) {
-        console.log('📄 Falling back to treating data as already decompressed array');
-        return compressedData;
-      }
-      // Another fallback: extract data field if it exists
-      if (compressedData && compressedData.data) {
-        console.log('📄 Falling back to extracting data field');
-        return compressedData.data;
-      }
-      throw error;
-    }
-  };
-
 // Session loading function
   const handleSessionLoad = async (sessionUuid: string) => {
     const txnId = `load_${sessionUuid}_${Date.now()}`;
@@ -431,6 +466,7 @@ function App() {
 
       // Clear current state first
       setChatHistory([]);
+      setReproducibilityReport(null);
       clearTelemetryData();
       clearSummaryStats();
       setStreamingResponses({});
@@ -447,6 +483,9 @@ function App() {
         if (config.model_b) { setModelB(config.model_b); }
         if (config.system_prompt !== undefined) { setSystemPrompt(config.system_prompt); }
         if (config.telemetry_sampling_hz) { setTelemetrySamplingHz(config.telemetry_sampling_hz); }
+        if (config.telemetry_profile) { setTelemetryProfile(config.telemetry_profile); }
+        if (config.energy_integration_method) { setEnergyIntegrationMethod(config.energy_integration_method); }
+        if (config.rolling_tps_window_secs) { setRollingTpsWindowSecs(config.rolling_tps_window_secs); }
       }
 
       // Restore target from metadata
@@ -454,30 +493,21 @@ function App() {
         setTarget(sessionData.session_metadata.target);
       }
 
-      // Restore telemetry data if present
+      // Restore telemetry data if present (samples live in their own table, not session_data)
+      let telemetryData: any[] = [];
       if (sessionData.telemetry_data) {
         try {
-          let telemetryData: any[];
-          
-          // Check if data is in compressed format or raw array format
-          if (sessionData.telemetry_data.compressed !== undefined) {
-            // New compressed format: {compressed: boolean, original_length: number, data: string}
-            telemetryData = await decompressTelemetryData(sessionData.telemetry_data);
-          } else if (Array.isArray(sessionData.telemetry_data)) {
-            // Raw array format (legacy or uncompressed)
-            telemetryData = sessionData.telemetry_data;
-          } else {
-            // Try to decompress in case it's an unknown compressed format
-            telemetryData = await decompressTelemetryData(sessionData.telemetry_data);
-          }
+          telemetryData = await SessionPersistence.getSessionTelemetry(savedSession.uuid);
 
           // Validate and restore telemetry data
-          if (Array.isArray(telemetryData) && telemetryData.length > 0) {
+          if (telemetryData.length > 0) {
             setTelemetryData(telemetryData);
             expectedTelemetryLen = telemetryData.length;
           } else {
             expectedTelemetryLen = 0;
           }
+          // Saving this session again keeps its samples
+          await resumeTelemetryRecording(savedSession.uuid);
         } catch (error) {
           console.error(`[${txnId}] ❌ Failed to restore telemetry data:`, error);
           // Continue loading other session data even if telemetry fails
@@ -498,17 +528,17 @@ function App() {
         expectedChatLen,
         expectedTelemetryLen,
       });
+
+      // Warn if the referenced model files are missing or changed (fingerprinting runs in the background)
+      SessionPersistence.checkReproducibility(savedSession.uuid)
+        .then(setReproducibilityReport)
+        .catch((error) => console.warn(`[${txnId}] Reproducibility check failed:`, error));
       
       // Create telemetry session for analysis mode when loading
       try {
         if (sessionData.telemetry_data) {
-          // Get the telemetry data that was already processed above
-          let telemetryDataForAnalysis: any[] = [];
-          if (sessionData.telemetry_data.compressed !== undefined) {
-            telemetryDataForAnalysis = await decompressTelemetryData(sessionData.telemetry_data);
-          } else if (Array.isArray(sessionData.telemetry_data)) {
-            telemetryDataForAnalysis = sessionData.telemetry_data;
-          }
+          // Reuse the telemetry data that was already loaded above
+          const telemetryDataForAnalysis = telemetryData;
           
           if (telemetryDataForAnalysis.length > 0) {
             // Calculate summary stats
@@ -531,13 +561,17 @@ function App() {
             const peakCpuTemp = validCpuTemps.length > 0 ? Math.max(...validCpuTemps) : 0;
             
             // Create model performance from saved summary stats
-            const modelPerformance: { [model: string]: { ttft_ms: number; avg_tps: number } } = {};
+            const modelPerformance: { [model: string]: ModelPerformance } = {};
             if (sessionData.summary_stats) {
               Object.entries(sessionData.summary_stats).forEach(([model, stats]: [string, any]) => {
                 if (stats.ttft_ms !== undefined && stats.avg_tps !== undefined) {
                   modelPerformance[model] = {
                     ttft_ms: stats.ttft_ms,
-                    avg_tps: stats.avg_tps
+                    avg_tps: stats.avg_tps,
+                    prefill_tps: stats.prefill_tps,
+                    inter_token_cv: stats.inter_token_cv,
+                    longest_stall_ms: stats.longest_stall_ms,
+                    stall_count: stats.stall_count
                   };
                 }
               });
@@ -638,20 +672,11 @@ function App() {
         const telemetrySessions = await Promise.all(
           savedSessions.map(async (savedSession) => {
             try {
-              // Extract telemetry data from session
+              // Telemetry samples are stored apart from session_data
               const sessionData = savedSession.session_data;
-              let telemetryData: any[] = [];
-              
-              if (sessionData.telemetry_data) {
-                // Handle different telemetry data formats
-                if (sessionData.telemetry_data.compressed !== undefined) {
-                  // Compressed format
-                  telemetryData = await decompressTelemetryData(sessionData.telemetry_data);
-                } else if (Array.isArray(sessionData.telemetry_data)) {
-                  // Raw array format
-                  telemetryData = sessionData.telemetry_data;
-                }
-              }
+              const telemetryData: any[] = sessionData.telemetry_data
+                ? await SessionPersistence.getSessionTelemetry(savedSession.uuid)
+                : [];
               
               // Skip sessions without telemetry data
               if (telemetryData.length === 0) {
@@ -678,7 +703,7 @@ function App() {
               const peakCpuTemp = validCpuTemps.length > 0 ? Math.max(...validCpuTemps) : 0;
               
               // Calculate model performance
-              const modelPerformance: { [model: string]: { ttft_ms: number; avg_tps: number } } = {};
+              const modelPerformance: { [model: string]: ModelPerformance } = {};
               
               // Use summary stats if available, otherwise calculate from telemetry
               if (sessionData.summary_stats) {
@@ -686,7 +711,11 @@ function App() {
                   if (stats.ttft_ms !== undefined && stats.avg_tps !== undefined) {
                     modelPerformance[model] = {
                       ttft_ms: stats.ttft_ms,
-                      avg_tps: stats.avg_tps
+                      avg_tps: stats.avg_tps,
+                      prefill_tps: stats.prefill_tps,
+                      inter_token_cv: stats.inter_token_cv,
+                      longest_stall_ms: stats.longest_stall_ms,
+                      stall_count: stats.stall_count
                     };
                   }
                 });
@@ -827,6 +856,7 @@ function App() {
     clearTelemetryData();
     clearSummaryStats(); // Clear performance summary in telemetry
     setStreamingResponses({}); // Clear any active streaming
+    setReproducibilityReport(null);
     
     // Clear overlay telemetry data
     overlayTelemetry.clearAllTelemetry();
@@ -845,7 +875,7 @@ function App() {
   };
   
   // Initialize chat handlers
-  const { handleSendPrompt, handleStopGeneration, rerunFromMessage } = useChatHandlers({ setContextValidationWarnings });
+  const { handleSendPrompt, handleStopGeneration, handleStopModel, rerunFromMessage } = useChatHandlers({ setContextValidationWarnings });
 
   // New Chat handler: stop inference immediately, then gate and clear session
   const handleNewChat = () => {
@@ -860,6 +890,19 @@ function App() {
     handlePotentialClose(clearSessionData, 'new-chat');
   };
 
+  // Import handler: replace the chat (after the unsaved-changes gate) with an imported conversation
+  const handleImportConversation = (conversation: ImportedConversation) => {
+    handlePotentialClose(() => {
+      clearSessionData();
+      setChatHistory(conversation.messages.map((message, index) => ({
+        id: `msg_${Date.now()}_import_${index}`,
+        role: message.role,
+        content: message.content,
+        model: message.model ?? undefined,
+      })));
+    }, 'new-chat');
+  };
+
   // Mode switching handlers
   const handleSwitchToChat = () => {
     setAppMode('chat');
@@ -965,10 +1008,16 @@ function App() {
             canSave: false,
           } : sessionState}
           onNewChat={handleNewChat}
+          onImportConversation={handleImportConversation}
         />
 
         {/* Main Content Area */}
         <div className="flex-1 flex flex-col min-w-0 min-h-0">
+          <PlatformWarningBanner />
+          <DataCompatibilityBanner report={compatibilityReport} onResolved={setCompatibilityReport} />
+          {appMode === 'chat' && (
+            <ReproducibilityBanner report={reproducibilityReport} onDismiss={() => setReproducibilityReport(null)} />
+          )}
           {appMode === 'chat' ? (
             // Chat Mode
             <ChatView
@@ -985,6 +1034,7 @@ function App() {
               onTargetChange={handleTargetChange}
               onSendPrompt={handleSendPrompt}
               onStopGeneration={handleStopGeneration}
+              onStopModel={handleStopModel}
               onStartEditingMessage={startEditingMessage}
               onSaveEditedMessage={saveEditedMessage}
               onCancelEditingMessage={cancelEditingMessage}
@@ -1036,6 +1086,12 @@ function App() {
             telemetrySamplingHz={telemetry_sampling_hz}
             runWithoutTelemetry={run_without_telemetry}
             onRunWithoutTelemetryChange={setRunWithoutTelemetry}
+            telemetryProfile={telemetry_profile}
+            onTelemetryProfileChange={setTelemetryProfile}
+            energyIntegrationMethod={energy_integration_method}
+            onEnergyIntegrationMethodChange={setEnergyIntegrationMethod}
+            rollingTpsWindowSecs={rolling_tps_window_secs}
+            onRollingTpsWindowSecsChange={setRollingTpsWindowSecs}
             chartRefreshMs={chart_refresh_ms}
             onChartRefreshMsChange={setChartRefreshMs}
             telemetryData={telemetryData}
@@ -70,7 +70,12 @@ export const SessionSelector: React.FC<SessionSelectorProps> = ({
                   {Object.entries(selectedSession.summary.model_performance).map(([model, perf]) => (
                     <div key={model} className="flex justify-between">
                       <span>{model}:</span>
-                      <span>TTFT {perf.ttft_ms}ms, TPS {perf.avg_tps.toFixed(2)}</span>
+                      <span>
+                        TTFT {perf.ttft_ms}ms, TPS {perf.avg_tps.toFixed(2)}
+                        {perf.prefill_tps !== undefined && `, prefill ${perf.prefill_tps.toFixed(1)} tok/s`}
+                        {perf.inter_token_cv !== undefined && `, CV ${perf.inter_token_cv.toFixed(2)}`}
+                        {perf.longest_stall_ms !== undefined && `, max stall ${perf.longest_stall_ms.toFixed(0)}ms (${perf.stall_count ?? 0})`}
+                      </span>
                     </div>
                   ))}
                 </div>
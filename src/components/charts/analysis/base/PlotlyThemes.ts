@@ -41,6 +41,11 @@ export const VARIABLE_COLORS = {
   cpu_overall_utilization: '#4299e1',
   cpu_freq: '#48bb78',
   gpu_freq: '#ed64a6',
+  gpu_utilization: '#d53f8c',
+  fan_rpm: '#718096',
+  cpu_temp_rate: '#c53030',
+  gpu_temp_rate: '#c05621',
+  package_power_rate: '#553c9a',
 } as const;
 
 // Generate colors for dynamic datasets
@@ -14,6 +14,11 @@ export const TELEMETRY_VARIABLES: VariableConfig[] = [
   { key: 'cpu_overall_utilization', label: 'CPU Utilization', unit: '%', color: '#4299e1' },
   { key: 'cpu_freq', label: 'CPU Frequency', unit: 'MHz', color: '#48bb78' },
   { key: 'gpu_freq', label: 'GPU Frequency', unit: 'MHz', color: '#ed64a6' },
+  { key: 'gpu_utilization', label: 'GPU Utilization', unit: '%', color: '#d53f8c' },
+  { key: 'fan_rpm', label: 'Fan Speed (fastest fan)', unit: 'RPM', color: '#718096' },
+  { key: 'cpu_temp_rate', label: 'CPU Heating Rate', unit: '°C/s', color: '#c53030' },
+  { key: 'gpu_temp_rate', label: 'GPU Heating Rate', unit: '°C/s', color: '#c05621' },
+  { key: 'package_power_rate', label: 'Power Ramp', unit: 'W/s', color: '#553c9a' },
 ];
 
 // Get variable configuration by key
@@ -38,6 +43,11 @@ export const extractVariableValues = (
       case 'cpu_overall_utilization': return point.cpu_overall_utilization;
       case 'cpu_freq': return point.cpu_freq;
       case 'gpu_freq': return point.gpu_freq;
+      case 'gpu_utilization': return point.gpu_utilization ?? null;
+      case 'fan_rpm': return point.fan_rpm?.length ? Math.max(...point.fan_rpm) : null;
+      case 'cpu_temp_rate': return point.cpu_temp_rate_c_per_s ?? null;
+      case 'gpu_temp_rate': return point.gpu_temp_rate_c_per_s ?? null;
+      case 'package_power_rate': return point.package_power_rate_w_per_s ?? null;
       default: return null;
     }
   });
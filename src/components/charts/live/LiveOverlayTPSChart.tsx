@@ -38,6 +38,24 @@ export const LiveOverlayTPSChart: React.FC<LiveOverlayTPSChartProps> = ({
       });
     }
     
+    // Model A rolling-window TPS dataset
+    const modelARollingTpsData = modelAData
+      .filter(d => d.rolling_tps !== null)
+      .map(d => ({ x: d.relative_time_seconds, y: d.rolling_tps! }));
+    
+    if (modelARollingTpsData.length > 0) {
+      datasets.push({
+        label: 'Model A - TPS (Rolling)',
+        data: modelARollingTpsData,
+        borderColor: '#65a30d',
+        backgroundColor: 'rgba(101, 163, 13, 0.1)',
+        borderWidth: 2,
+        tension: 0.1,
+        pointRadius: 0,
+        pointHoverRadius: 4,
+      });
+    }
+    
     // Model A Instantaneous TPS dataset
     const modelAInstantTpsData = modelAData
       .filter(d => d.instantaneous_tps !== null)
@@ -75,6 +93,24 @@ export const LiveOverlayTPSChart: React.FC<LiveOverlayTPSChartProps> = ({
       });
     }
     
+    // Model B rolling-window TPS dataset
+    const modelBRollingTpsData = modelBData
+      .filter(d => d.rolling_tps !== null)
+      .map(d => ({ x: d.relative_time_seconds, y: d.rolling_tps! }));
+    
+    if (modelBRollingTpsData.length > 0) {
+      datasets.push({
+        label: 'Model B - TPS (Rolling)',
+        data: modelBRollingTpsData,
+        borderColor: '#c026d3',
+        backgroundColor: 'rgba(192, 38, 211, 0.1)',
+        borderWidth: 2,
+        tension: 0.1,
+        pointRadius: 0,
+        pointHoverRadius: 4,
+      });
+    }
+    
     // Model B Instantaneous TPS dataset
     const modelBInstantTpsData = modelBData
       .filter(d => d.instantaneous_tps !== null)
@@ -1,7 +1,7 @@
 import { BaseTransformer, PlotlyTrace } from './BaseTransformer';
 
 /**
- * TPS chart transformer - handles average and instantaneous TPS data conversion
+ * TPS chart transformer - handles average, rolling-window and instantaneous TPS data conversion
  * Preserves exact original transformation logic from ChartModal
  */
 export class TPSChartTransformer extends BaseTransformer {
@@ -25,6 +25,16 @@ export class TPSChartTransformer extends BaseTransformer {
         ));
       }
 
+      // Rolling-window TPS trace
+      const rollingData = this.filterDataByField(modelAData, 'rolling_tps');
+      if (rollingData.length > 0) {
+        traces.push(this.createModelATrace(
+          rollingData,
+          'Model A - TPS (Rolling)',
+          'tertiary'
+        ));
+      }
+
       // Instantaneous TPS trace (dashed line, smaller markers)
       const instantData = this.filterDataByField(modelAData, 'instantaneous_tps');
       if (instantData.length > 0) {
@@ -52,6 +62,16 @@ export class TPSChartTransformer extends BaseTransformer {
         ));
       }
 
+      // Rolling-window TPS trace
+      const rollingData = this.filterDataByField(modelBData, 'rolling_tps');
+      if (rollingData.length > 0) {
+        traces.push(this.createModelBTrace(
+          rollingData,
+          'Model B - TPS (Rolling)',
+          'tertiary'
+        ));
+      }
+
       // Instantaneous TPS trace (dashed line, smaller markers)
       const instantData = this.filterDataByField(modelBData, 'instantaneous_tps');
       if (instantData.length > 0) {
@@ -1,5 +1,6 @@
 import React from 'react';
 import { ModelTargetSelector } from '../ui/ModelTargetSelector';
+import type { SamplingParams } from '../../types/telemetry';
 
 interface ChatInputProps {
   prompt: string;
@@ -7,10 +8,14 @@ interface ChatInputProps {
   isLoading: boolean;
   isStopping: boolean;
   availableTargets: ('A' | 'B' | 'Both')[];
+  samplingOverrides: { A?: SamplingParams; B?: SamplingParams };
+  storedTemperatures: { A?: number; B?: number };
+  onSamplingOverrideChange: (model: 'A' | 'B', overrides: SamplingParams | undefined) => void;
   onPromptChange: (value: string) => void;
   onTargetChange: (target: 'A' | 'B' | 'Both') => void;
   onSendPrompt: () => Promise<void>;
   onStopGeneration: () => Promise<void>;
+  onStopModel: (model: 'A' | 'B') => Promise<void>;
 }
 
 /**
@@ -20,6 +25,8 @@ interface ChatInputProps {
  * - Multi-line textarea with proper validation
  * - Model target selector with availability checking
  * - Send/Stop button states with loading indicators
+ * - Per-model stop in "Both" mode, letting the other model finish
+ * - One-turn temperature override per model (stored config unchanged)
  * - Responsive design and accessibility features
  * - Form submission handling and keyboard shortcuts
  */
@@ -29,10 +36,14 @@ export const ChatInput: React.FC<ChatInputProps> = ({
   isLoading,
   isStopping,
   availableTargets,
+  samplingOverrides,
+  storedTemperatures,
+  onSamplingOverrideChange,
   onPromptChange,
   onTargetChange,
   onSendPrompt,
-  onStopGeneration
+  onStopGeneration,
+  onStopModel
 }) => {
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -136,9 +147,52 @@ export const ChatInput: React.FC<ChatInputProps> = ({
                 </span>
               </button>
             )}
+
+            {/* Stop a single model while the other keeps generating */}
+            {isLoading && target === 'Both' && !isStopping ? (
+              <div className="flex gap-1">
+                {(['A', 'B'] as const).map((model) => (
+                  <button
+                    key={model}
+                    type="button"
+                    onClick={() => void onStopModel(model)}
+                    className="flex-1 px-2 py-1 text-xs border border-red-300 text-red-600 rounded hover:bg-red-50 transition-colors"
+                    aria-label={`Stop model ${model} only`}
+                  >
+                    Stop {model}
+                  </button>
+                ))}
+              </div>
+            ) : null}
           </div>
         </div>
         
+        {/* Temperature for the next turn only, per targeted model */}
+        <div className="mt-2 flex flex-wrap items-center gap-3 text-xs text-gray-600">
+          {(['A', 'B'] as const)
+            .filter((model) => target === model || target === 'Both')
+            .map((model) => (
+              <label key={model} className="flex items-center gap-1">
+                Temp {model} this turn
+                <input
+                  type="number"
+                  step="0.05"
+                  min="0"
+                  max="2"
+                  value={samplingOverrides[model]?.temperature ?? ''}
+                  placeholder={storedTemperatures[model]?.toString() ?? ''}
+                  onChange={(e) => {
+                    const value = e.target.value === '' ? undefined : Number(e.target.value);
+                    onSamplingOverrideChange(model, value === undefined || Number.isNaN(value) ? undefined : { temperature: value });
+                  }}
+                  disabled={isLoading}
+                  className="w-16 px-1 py-0.5 border border-gray-300 rounded focus:outline-none focus:ring-1 focus:ring-blue-500"
+                  aria-label={`Temperature override for model ${model}, this turn only`}
+                />
+              </label>
+            ))}
+        </div>
+
         {/* Accessibility hint for keyboard users */}
         <div className="mt-2 text-xs text-gray-500">
           <span className="hidden sm:inline">Press Ctrl+Enter to send • </span>
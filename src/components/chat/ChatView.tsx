@@ -3,6 +3,7 @@ import { MessageListContainer } from './MessageList';
 import { ChatInput } from './ChatInput';
 import { type Message } from './MessageItem';
 import { useModelStore } from '../../stores/modelStore';
+import { useChatStore } from '../../stores/chatStore';
 import { InfoIcon } from '../ui/InfoIcon';
 
 interface ChatViewProps {
@@ -24,6 +25,7 @@ interface ChatViewProps {
   onTargetChange: (target: 'A' | 'B' | 'Both') => void;
   onSendPrompt: () => Promise<void>;
   onStopGeneration: () => Promise<void>;
+  onStopModel: (model: 'A' | 'B') => Promise<void>;
   onStartEditingMessage: (messageId: string, content: string) => void;
   onSaveEditedMessage: (messageId: string, newContent: string) => void;
   onCancelEditingMessage: () => void;
@@ -60,6 +62,7 @@ export const ChatView: React.FC<ChatViewProps> = ({
   onTargetChange,
   onSendPrompt,
   onStopGeneration,
+  onStopModel,
   onStartEditingMessage,
   onSaveEditedMessage,
   onCancelEditingMessage,
@@ -70,10 +73,15 @@ export const ChatView: React.FC<ChatViewProps> = ({
   getAvailableTargets
 }) => {
   const { inputTokenCounts, outputTokenCounts, modelA, modelB } = useModelStore();
+  const { samplingOverrides, setSamplingOverride } = useChatStore();
 
   const formatContext = (model: 'A' | 'B') => {
     const used = (inputTokenCounts[model] ?? 0) + (outputTokenCounts[model] ?? 0);
-    const ctxSize = (model === 'A' ? modelA.n_ctx : modelB.n_ctx) ?? 2048;
+    const nCtx = model === 'A' ? modelA.n_ctx : modelB.n_ctx;
+    if (nCtx === 'auto') {
+      return { label: `${used} / auto` };
+    }
+    const ctxSize = nCtx ?? 2048;
     const pct = ctxSize > 0 ? Math.min(100, Math.round((used / ctxSize) * 1000) / 10) : 0;
     return { label: `${used} / ${ctxSize} | ${pct}%` };
   };
@@ -147,10 +155,14 @@ export const ChatView: React.FC<ChatViewProps> = ({
         isLoading={isLoading}
         isStopping={isStopping}
         availableTargets={getAvailableTargets()}
+        samplingOverrides={samplingOverrides}
+        storedTemperatures={{ A: modelA.temperature, B: modelB.temperature }}
+        onSamplingOverrideChange={setSamplingOverride}
         onPromptChange={onPromptChange}
         onTargetChange={onTargetChange}
         onSendPrompt={onSendPrompt}
         onStopGeneration={onStopGeneration}
+        onStopModel={onStopModel}
       />
     </div>
   );
@@ -0,0 +1,125 @@
+import React, { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { errorMessage } from '../../types/errors';
+
+export type ConversationFormat = 'chatgpt' | 'lmstudio' | 'ollama';
+
+export interface ImportedMessage {
+  role: string;
+  content: string;
+  model?: string | null;
+}
+
+export interface ImportedConversation {
+  title: string | null;
+  messages: ImportedMessage[];
+}
+
+const FORMAT_OPTIONS: { format: ConversationFormat; label: string; extensions: string[] }[] = [
+  { format: 'chatgpt', label: 'ChatGPT export (conversations.json)', extensions: ['json'] },
+  { format: 'lmstudio', label: 'LM Studio conversation', extensions: ['json'] },
+  { format: 'ollama', label: 'Ollama messages JSON / Modelfile', extensions: ['json', 'txt', 'modelfile'] },
+];
+
+interface ConversationImportButtonProps {
+  onImport: (conversation: ImportedConversation) => void;
+  disabled?: boolean;
+}
+
+/**
+ * ConversationImportButton Component
+ *
+ * Imports a conversation exported from ChatGPT, LM Studio or Ollama so it can be
+ * replayed through both models. Exports with several conversations show a picker.
+ */
+export const ConversationImportButton: React.FC<ConversationImportButtonProps> = ({
+  onImport,
+  disabled = false,
+}) => {
+  const [menuOpen, setMenuOpen] = useState(false);
+  const [choices, setChoices] = useState<ImportedConversation[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleFormatSelect = async (option: typeof FORMAT_OPTIONS[number]) => {
+    setMenuOpen(false);
+    setError(null);
+    try {
+      const selected = await open({
+        title: `Import ${option.label}`,
+        multiple: false,
+        directory: false,
+        filters: [{ name: option.label, extensions: option.extensions }],
+      });
+      if (typeof selected !== 'string') return;
+
+      const conversations = await invoke<ImportedConversation[]>('import_conversation', {
+        path: selected,
+        format: option.format,
+      });
+      if (conversations.length === 1) {
+        onImport(conversations[0]);
+      } else {
+        setChoices(conversations);
+      }
+    } catch (err) {
+      console.error('Conversation import error:', err);
+      setError(errorMessage(err));
+    }
+  };
+
+  return (
+    <div className="relative">
+      <button
+        onClick={() => setMenuOpen(!menuOpen)}
+        disabled={disabled}
+        className="px-3 py-2 h-8 rounded text-sm font-medium transition-colors flex items-center justify-center bg-gray-200 hover:bg-gray-300 disabled:opacity-50"
+        title="Import a conversation from another tool"
+      >
+        Import
+      </button>
+
+      {menuOpen && (
+        <div className="absolute right-0 mt-1 w-72 bg-white border rounded shadow-lg z-50">
+          {FORMAT_OPTIONS.map((option) => (
+            <button
+              key={option.format}
+              onClick={() => handleFormatSelect(option)}
+              className="block w-full text-left px-3 py-2 text-sm hover:bg-gray-100"
+            >
+              {option.label}
+            </button>
+          ))}
+        </div>
+      )}
+
+      {choices && (
+        <div className="absolute right-0 mt-1 w-80 max-h-80 overflow-y-auto bg-white border rounded shadow-lg z-50">
+          <div className="flex items-center justify-between px-3 py-2 border-b">
+            <span className="text-sm font-medium text-gray-800">Choose a conversation ({choices.length})</span>
+            <button onClick={() => setChoices(null)} className="text-sm text-blue-600 hover:text-blue-700">Cancel</button>
+          </div>
+          {choices.map((conversation, index) => (
+            <button
+              key={index}
+              onClick={() => { setChoices(null); onImport(conversation); }}
+              className="block w-full text-left px-3 py-2 text-sm hover:bg-gray-100"
+            >
+              <div className="truncate">{conversation.title || `Conversation ${index + 1}`}</div>
+              <div className="text-xs text-gray-500">{conversation.messages.length} messages</div>
+            </button>
+          ))}
+        </div>
+      )}
+
+      {error && (
+        <div className="absolute right-0 mt-1 w-72 p-2 bg-red-50 border border-red-200 rounded text-xs text-red-700 z-50">
+          {error}
+          <button onClick={() => setError(null)} className="ml-2 text-red-800 underline">Dismiss</button>
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default ConversationImportButton;
@@ -1,5 +1,6 @@
 import React from 'react';
 import { MessageEditor } from './MessageEditor';
+import type { GenerationFinishReason, SamplingParams } from '../../types/telemetry';
 
 export interface Message {
   id: string;
@@ -11,6 +12,10 @@ export interface Message {
   avg_tps?: number;
   token_count?: number;
   generation_time_ms?: number;
+  finish_reason?: GenerationFinishReason; // Why generation ended (from the final GenerationResult)
+  truncated?: boolean;                    // Cut off by the max_tokens budget
+  sampling?: SamplingParams;              // Effective sampling parameters for this turn
+  sampling_overridden?: boolean;          // Turn used one-off overrides instead of the stored config
 }
 
 interface MessageItemProps {
@@ -165,7 +170,21 @@ export const MessageItem: React.FC<MessageItemProps> = ({
               {message.token_count !== undefined && (
                 `${message.token_count} tokens`
               )}
+              {message.finish_reason === 'user_stop' && " | stopped"}
+              {message.finish_reason === 'stop_sequence' && " | stop sequence"}
+              {message.finish_reason === 'loop_detected' && " | stopped: repeating output"}
+              {message.sampling_overridden && message.sampling?.temperature != null && (
+                ` | temp ${message.sampling.temperature} (this turn)`
+              )}
             </span>
+            {message.truncated && (
+              <span
+                className="ml-2 text-xs text-amber-700"
+                title="Generation hit the max token limit before the model finished"
+              >
+                truncated
+              </span>
+            )}
           </div>
         )}
       </div>
@@ -1,9 +1,11 @@
 import React from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
+import { invoke } from '@tauri-apps/api/core';
 import { CustomDropdown } from '../ui/CustomDropdown';
 import { CONTEXT_LENGTH_OPTIONS, getContextLengthOption } from '../../utils/contextLengthOptions';
 import { type ModelConfig, SAMPLING_PARAMETERS } from '../../stores/modelStore';
 import { SamplingParameterControl } from './SamplingParameterControl';
+import { useModelLibrary } from '../../hooks/useModelLibrary';
 
 
 interface ModelConfigPanelProps {
@@ -56,6 +58,8 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
 }) => {
   // State for collapsible sections
   const [samplingExpanded, setSamplingExpanded] = React.useState(false);
+  // GGUFs from the model directories, offered as path suggestions
+  const libraryModels = useModelLibrary();
 
   // Existing handlers remain unchanged
   const handlePathChange = (path: string) => {
@@ -79,6 +83,72 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
     }
   };
 
+  const handleGpuLayersInput = (value: string) => {
+    const numValue = parseInt(value, 10);
+    if (!isNaN(numValue) && numValue >= 0) {
+      onModelChange({ ...modelConfig, n_gpu_layers: numValue });
+    } else if (value === '') {
+      onModelChange({ ...modelConfig, n_gpu_layers: undefined });
+    }
+  };
+
+  const handleDraftCountInput = (value: string) => {
+    const numValue = parseInt(value, 10);
+    if (!isNaN(numValue) && numValue >= 1) {
+      onModelChange({ ...modelConfig, n_draft: Math.min(numValue, 16) });
+    } else if (value === '') {
+      onModelChange({ ...modelConfig, n_draft: undefined });
+    }
+  };
+
+  const handleMaxTokensInput = (value: string) => {
+    const numValue = parseInt(value, 10);
+    if (!isNaN(numValue) && numValue > 0) {
+      onModelChange({ ...modelConfig, max_tokens: numValue });
+    } else if (value === '') {
+      onModelChange({ ...modelConfig, max_tokens: undefined });
+    }
+  };
+
+  const handleSeedInput = (value: string) => {
+    const numValue = parseInt(value, 10);
+    if (!isNaN(numValue) && numValue >= 0) {
+      onModelChange({ ...modelConfig, seed: numValue });
+    } else if (value === '') {
+      onModelChange({ ...modelConfig, seed: undefined });
+    }
+  };
+
+  // One stop sequence per line; "\n" stands for a newline inside a sequence.
+  // The raw text is kept locally so blank lines survive while typing.
+  const [stopSequencesText, setStopSequencesText] = React.useState(() =>
+    (modelConfig.stop_sequences ?? [])
+      .map((sequence) => sequence.replace(/\n/g, '\\n'))
+      .join('\n')
+  );
+
+  const handleStopSequencesInput = (value: string) => {
+    setStopSequencesText(value);
+    const sequences = value
+      .split('\n')
+      .map((line) => line.replace(/\\n/g, '\n'))
+      .filter((sequence) => sequence.length > 0);
+    onModelChange({ ...modelConfig, stop_sequences: sequences.length > 0 ? sequences : undefined });
+  };
+
+  // Chat template override: a built-in name, or a custom Jinja template for GGUFs whose
+  // embedded template is broken or missing
+  const builtinTemplates = ['chatml', 'llama3', 'mistral'];
+  const templateOverride = modelConfig.chat_template_override;
+  const templateChoice = templateOverride === undefined
+    ? 'embedded'
+    : builtinTemplates.includes(templateOverride) ? templateOverride : 'custom';
+
+  const handleTemplateChoice = (choice: string) => {
+    const chat_template_override = choice === 'embedded' ? undefined : choice === 'custom' ? '' : choice;
+    onModelChange({ ...modelConfig, chat_template_override });
+  };
+
   // File picker for model path via Tauri (macOS Finder)
   const handleBrowseClick = async () => {
     try {
@@ -99,6 +169,15 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
     }
   };
 
+  // Models stay resident between turns; this frees the memory without waiting for eviction
+  const handleUnloadClick = async () => {
+    try {
+      await invoke('unload_model', { modelPath: modelConfig.model_path });
+    } catch (err) {
+      console.error('Model unload error:', err);
+    }
+  };
+
   // New handler for sampling parameter changes
   const handleSamplingParameterChange = (parameterKey: string, value: number | undefined) => {
     onModelChange({
@@ -221,7 +300,13 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
             placeholder={`models/model-${modelId.toLowerCase()}.gguf`}
             title={modelConfig.model_path}
             aria-describedby={`model-${modelId.toLowerCase()}-path-help`}
+            list={`model-${modelId.toLowerCase()}-library`}
           />
+          <datalist id={`model-${modelId.toLowerCase()}-library`}>
+            {libraryModels.map((model) => (
+              <option key={model.path} value={model.path}>{model.file_name}</option>
+            ))}
+          </datalist>
           <button
             type="button"
             onClick={handleBrowseClick}
@@ -234,6 +319,18 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
               <path d="M2 6a2 2 0 012-2h3l2 2h5a2 2 0 012 2v6a2 2 0 01-2 2H4a2 2 0 01-2-2V6z" />
             </svg>
           </button>
+          <button
+            type="button"
+            onClick={handleUnloadClick}
+            disabled={isLoading || !modelConfig.model_path}
+            className="inline-flex items-center justify-center w-7 h-7 rounded bg-gray-100 hover:bg-gray-200 text-gray-600 disabled:bg-gray-200 disabled:text-gray-400 border border-gray-200"
+            title="Unload model from memory"
+            aria-label="Unload model from memory"
+          >
+            <svg className="w-4 h-4" fill="currentColor" viewBox="0 0 20 20" aria-hidden="true">
+              <path fillRule="evenodd" d="M9 2a1 1 0 00-.894.553L7.382 4H4a1 1 0 000 2v10a2 2 0 002 2h8a2 2 0 002-2V6a1 1 0 100-2h-3.382l-.724-1.447A1 1 0 0011 2H9zM7 8a1 1 0 012 0v6a1 1 0 11-2 0V8zm5-1a1 1 0 00-1 1v6a1 1 0 102 0V8a1 1 0 00-1-1z" clipRule="evenodd" />
+            </svg>
+          </button>
         </div>
         <div 
           id={`model-${modelId.toLowerCase()}-path-help`}
@@ -271,6 +368,220 @@ export const ModelConfigPanel: React.FC<ModelConfigPanelProps> = ({
         )}
       </div>
 
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-gpu-layers`}
+        >
+          GPU Layers
+        </label>
+        <input
+          id={`model-${modelId.toLowerCase()}-gpu-layers`}
+          type="number"
+          min={0}
+          value={modelConfig.n_gpu_layers ?? ''}
+          onChange={(e) => handleGpuLayersInput(e.target.value)}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder="Default (all layers)"
+        />
+        <div className="text-xs text-gray-500 mt-1">
+          Layers offloaded to the GPU; 0 runs the model on the CPU only
+        </div>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-max-tokens`}
+        >
+          Max Tokens
+        </label>
+        <input
+          id={`model-${modelId.toLowerCase()}-max-tokens`}
+          type="number"
+          min={1}
+          value={modelConfig.max_tokens ?? ''}
+          onChange={(e) => handleMaxTokensInput(e.target.value)}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder="Default (1024)"
+        />
+        <label className="flex items-center gap-1 mt-1 text-xs text-gray-600">
+          <input
+            type="checkbox"
+            checked={modelConfig.context_shift ?? false}
+            onChange={(e) => onModelChange({ ...modelConfig, context_shift: e.target.checked || undefined })}
+            disabled={isLoading}
+            className="rounded border-gray-300 text-blue-600"
+          />
+          Context shift: keep generating past n_ctx by evicting the oldest tokens (system prompt kept)
+        </label>
+        <label className="flex items-center gap-1 mt-1 text-xs text-gray-600">
+          <input
+            type="checkbox"
+            checked={modelConfig.auto_truncate ?? false}
+            onChange={(e) => onModelChange({ ...modelConfig, auto_truncate: e.target.checked || undefined })}
+            disabled={isLoading}
+            className="rounded border-gray-300 text-blue-600"
+          />
+          Auto-truncate: drop the oldest turns when the conversation no longer fits n_ctx (system prompt kept)
+        </label>
+        <label className="flex items-center gap-1 mt-1 text-xs text-gray-600">
+          <input
+            type="checkbox"
+            checked={modelConfig.loop_detection !== undefined}
+            onChange={(e) => onModelChange({ ...modelConfig, loop_detection: e.target.checked ? {} : undefined })}
+            disabled={isLoading}
+            className="rounded border-gray-300 text-blue-600"
+          />
+          Loop detection: stop when the same 16 tokens repeat 4 times within the last 512
+        </label>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-draft-model`}
+        >
+          Draft Model
+        </label>
+        <input
+          id={`model-${modelId.toLowerCase()}-draft-model`}
+          type="text"
+          value={modelConfig.draft_model_path ?? ''}
+          onChange={(e) => onModelChange({ ...modelConfig, draft_model_path: e.target.value || undefined })}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder="None (no speculative decoding)"
+        />
+        <input
+          id={`model-${modelId.toLowerCase()}-n-draft`}
+          type="number"
+          min={1}
+          max={16}
+          value={modelConfig.n_draft ?? ''}
+          onChange={(e) => handleDraftCountInput(e.target.value)}
+          disabled={isLoading || !modelConfig.draft_model_path}
+          className="w-full mt-1 p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder="Draft tokens per round (default 5)"
+        />
+        <div className="text-xs text-gray-500 mt-1">
+          A small GGUF with the same vocabulary proposes tokens for this model to verify; output is unchanged
+        </div>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-seed`}
+        >
+          Seed
+        </label>
+        <div className="flex items-center gap-2">
+          <input
+            id={`model-${modelId.toLowerCase()}-seed`}
+            type="number"
+            min={0}
+            value={typeof modelConfig.seed === 'number' ? modelConfig.seed : ''}
+            onChange={(e) => handleSeedInput(e.target.value)}
+            disabled={isLoading || modelConfig.seed === 'random'}
+            className="flex-1 p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+            placeholder={modelConfig.seed === 'random' ? 'New seed each turn' : 'Default (1234)'}
+          />
+          <label className="flex items-center gap-1 text-xs text-gray-600">
+            <input
+              type="checkbox"
+              checked={modelConfig.seed === 'random'}
+              onChange={(e) => onModelChange({ ...modelConfig, seed: e.target.checked ? 'random' : undefined })}
+              disabled={isLoading}
+              className="rounded border-gray-300 text-blue-600"
+            />
+            Random
+          </label>
+        </div>
+        <div className="text-xs text-gray-500 mt-1">
+          Pin for reproducible A/B runs; the seed used is reported with each result
+        </div>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-stop-sequences`}
+        >
+          Stop Sequences
+        </label>
+        <textarea
+          id={`model-${modelId.toLowerCase()}-stop-sequences`}
+          rows={2}
+          value={stopSequencesText}
+          onChange={(e) => handleStopSequencesInput(e.target.value)}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs font-mono focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder="One per line, e.g. </answer>"
+        />
+        <div className="text-xs text-gray-500 mt-1">
+          Generation stops when the reply contains one; write \n for a newline
+        </div>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-grammar`}
+        >
+          Grammar (GBNF)
+        </label>
+        <textarea
+          id={`model-${modelId.toLowerCase()}-grammar`}
+          rows={2}
+          value={modelConfig.grammar ?? ''}
+          onChange={(e) => onModelChange({ ...modelConfig, grammar: e.target.value.trim() ? e.target.value : undefined })}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs font-mono focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+          placeholder={'json, or e.g. root ::= "yes" | "no"'}
+        />
+        <div className="text-xs text-gray-500 mt-1">
+          Constrains the output to the grammar; "json" uses llama.cpp's JSON grammar
+        </div>
+      </div>
+
+      <div>
+        <label
+          className="block text-xs font-medium text-gray-600 mb-1"
+          htmlFor={`model-${modelId.toLowerCase()}-chat-template`}
+        >
+          Chat Template
+        </label>
+        <select
+          id={`model-${modelId.toLowerCase()}-chat-template`}
+          value={templateChoice}
+          onChange={(e) => handleTemplateChoice(e.target.value)}
+          disabled={isLoading}
+          className="w-full p-2 border border-gray-300 rounded text-xs focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+        >
+          <option value="embedded">Embedded (from GGUF)</option>
+          <option value="chatml">ChatML</option>
+          <option value="llama3">Llama 3</option>
+          <option value="mistral">Mistral Instruct</option>
+          <option value="custom">Custom Jinja template</option>
+        </select>
+        {templateChoice === 'custom' && (
+          <textarea
+            rows={3}
+            value={templateOverride ?? ''}
+            onChange={(e) => onModelChange({ ...modelConfig, chat_template_override: e.target.value })}
+            disabled={isLoading}
+            className="w-full mt-1 p-2 border border-gray-300 rounded text-xs font-mono focus:outline-none focus:ring-1 focus:ring-blue-500 disabled:bg-gray-50 disabled:text-gray-500 transition-colors"
+            placeholder="{% for message in messages %}..."
+          />
+        )}
+        <div className="text-xs text-gray-500 mt-1">
+          Override when the model's embedded template is broken or missing
+        </div>
+      </div>
+
       {/* NEW: Sampling Parameters Section */}
       <div className="border-t pt-4">
         {/* Header with collapsible toggle */}
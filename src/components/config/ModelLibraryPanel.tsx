@@ -0,0 +1,155 @@
+import React, { useEffect, useState } from 'react';
+import { open } from '@tauri-apps/plugin-dialog';
+import { ModelLibrary, type ModelLibrarySettings } from '../../services/modelLibrary';
+import { useModelLibrary } from '../../hooks/useModelLibrary';
+import { errorMessage } from '../../types/errors';
+
+interface ModelLibraryPanelProps {
+  isLoading: boolean;
+  className?: string;
+}
+
+/**
+ * ModelLibraryPanel Component
+ *
+ * Directories scanned for GGUF files (offered in the model path pickers), with an
+ * option to watch them so added or removed models show up without a manual rescan.
+ */
+export const ModelLibraryPanel: React.FC<ModelLibraryPanelProps> = ({ isLoading, className = "" }) => {
+  const [collapsed, setCollapsed] = useState<boolean>(true);
+  const [settings, setSettings] = useState<ModelLibrarySettings>({ directories: [], watch: false });
+  const [deleteError, setDeleteError] = useState<string | null>(null);
+  const models = useModelLibrary();
+
+  useEffect(() => {
+    ModelLibrary.getSettings()
+      .then(setSettings)
+      .catch((err) => console.error('Failed to load model library settings:', err));
+  }, []);
+
+  const saveSettings = async (next: ModelLibrarySettings) => {
+    try {
+      setSettings(await ModelLibrary.setSettings(next));
+    } catch (err) {
+      console.error('Failed to save model library settings:', err);
+    }
+  };
+
+  const handleAddDirectory = async () => {
+    try {
+      const selected = await open({ title: 'Select a model directory', directory: true, multiple: false });
+      if (typeof selected === 'string') {
+        await saveSettings({ ...settings, directories: [...settings.directories, selected] });
+      }
+    } catch (err) {
+      console.error('Directory picker error:', err);
+    }
+  };
+
+  const handleRescan = async () => {
+    try {
+      await ModelLibrary.rescan();
+    } catch (err) {
+      console.error('Model library rescan error:', err);
+    }
+  };
+
+  const handleMoveToTrash = async (path: string, fileName: string) => {
+    if (!confirm(`Move "${fileName}" to the Trash?`)) return;
+    setDeleteError(null);
+    try {
+      await ModelLibrary.deleteModelFile(path);
+    } catch (err) {
+      setDeleteError(errorMessage(err));
+    }
+  };
+
+  return (
+    <div className={`border rounded-lg p-4 space-y-3 ${className}`}>
+      <div className="flex items-center justify-between mb-1">
+        <h4 className="font-medium text-gray-800">Model Library</h4>
+        <button
+          onClick={() => setCollapsed(!collapsed)}
+          className="text-sm text-blue-600 hover:text-blue-700"
+          aria-expanded={!collapsed}
+        >
+          {collapsed ? 'Show' : 'Hide'}
+        </button>
+      </div>
+
+      {!collapsed && (
+        <div className="space-y-3">
+          <div className="space-y-1">
+            {settings.directories.length === 0 && (
+              <div className="text-xs text-gray-500">No model directories configured</div>
+            )}
+            {settings.directories.map((directory) => (
+              <div key={directory} className="flex items-center gap-2">
+                <span className="flex-1 text-xs text-gray-700 truncate" title={directory}>{directory}</span>
+                <button
+                  onClick={() => saveSettings({ ...settings, directories: settings.directories.filter((d) => d !== directory) })}
+                  disabled={isLoading}
+                  className="text-xs text-red-600 hover:text-red-700 disabled:text-gray-400"
+                >
+                  Remove
+                </button>
+              </div>
+            ))}
+          </div>
+
+          <div className="flex gap-2">
+            <button
+              onClick={handleAddDirectory}
+              disabled={isLoading}
+              className="px-3 py-1 text-xs bg-blue-100 text-blue-700 rounded hover:bg-blue-200 transition-colors disabled:bg-gray-100 disabled:text-gray-400"
+            >
+              Add Directory
+            </button>
+            <button
+              onClick={handleRescan}
+              disabled={isLoading || settings.directories.length === 0}
+              className="px-3 py-1 text-xs bg-gray-100 text-gray-700 rounded hover:bg-gray-200 transition-colors disabled:text-gray-400"
+            >
+              Rescan
+            </button>
+          </div>
+
+          <div className={`flex items-start gap-2 ${isLoading ? 'opacity-50' : ''}`}>
+            <input
+              id="watch-model-directories"
+              type="checkbox"
+              className="mt-0.5 rounded border-gray-300 text-blue-600 shadow-sm focus:border-blue-300 focus:ring focus:ring-blue-200 focus:ring-opacity-50"
+              checked={settings.watch}
+              onChange={(e) => saveSettings({ ...settings, watch: e.target.checked })}
+              disabled={isLoading}
+            />
+            <label htmlFor="watch-model-directories" className="text-sm font-medium text-gray-800">
+              Watch directories for new models
+            </label>
+          </div>
+
+          <div className="text-xs text-gray-500">{models.length} GGUF model(s) found</div>
+          {models.length > 0 && (
+            <div className="space-y-1 max-h-40 overflow-y-auto">
+              {models.map((model) => (
+                <div key={model.path} className="flex items-center gap-2">
+                  <span className="flex-1 text-xs text-gray-700 truncate" title={model.path}>{model.file_name}</span>
+                  <button
+                    onClick={() => handleMoveToTrash(model.path, model.file_name)}
+                    disabled={isLoading}
+                    className="text-xs text-red-600 hover:text-red-700 disabled:text-gray-400"
+                  >
+                    Move to Trash
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+          {deleteError && <div className="text-xs text-red-600">{deleteError}</div>}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default ModelLibraryPanel;
@@ -1,8 +1,29 @@
 import React, { useEffect, useState } from 'react';
 import { CustomDropdown } from '../ui/CustomDropdown';
 import { SmartTooltip } from '../ui/SmartTooltip';
+import type { EnergyIntegrationMethod } from '../../stores/modelStore';
+import type { TemperatureUnit } from '../../types/telemetry';
+import { SessionPersistence } from '../../services/sessionPersistence';
 
 
+const ENERGY_INTEGRATION_OPTIONS: { id: string; label: string; subtitle: string; data: EnergyIntegrationMethod }[] = [
+  { id: 'ei-trapezoid', label: 'Trapezoid', subtitle: 'Linear between samples (default)', data: 'trapezoid' },
+  { id: 'ei-rectangle', label: 'Rectangle', subtitle: 'Each sample held over its interval', data: 'rectangle' },
+  { id: 'ei-monotone-cubic', label: 'Monotone cubic', subtitle: 'Smooth, never overshoots samples', data: 'monotone_cubic' },
+];
+
+const TEMPERATURE_UNIT_OPTIONS: { id: string; label: string; data: TemperatureUnit }[] = [
+  { id: 'temp-unit-celsius', label: 'Celsius (°C)', data: 'celsius' },
+  { id: 'temp-unit-fahrenheit', label: 'Fahrenheit (°F)', data: 'fahrenheit' },
+];
+
+const ROLLING_TPS_WINDOW_OPTIONS = [
+  { id: 'tps-window-2', label: '2 s', data: 2 },
+  { id: 'tps-window-5', label: '5 s - Default', data: 5 },
+  { id: 'tps-window-10', label: '10 s', data: 10 },
+  { id: 'tps-window-30', label: '30 s', data: 30 },
+];
+
 const SAMPLING_FREQUENCY_OPTIONS = [
   { id: 'freq-0.5', label: '0.5 Hz (2s)', data: 0.5 },
   { id: 'freq-1.0', label: '1.0 Hz (1s) - Default', data: 1.0 },
@@ -42,12 +63,27 @@ interface TelemetryConfigPanelProps {
   // New: allow running inference without telemetry
   runWithoutTelemetry: boolean;
   onRunWithoutTelemetryChange: (checked: boolean) => void;
+  // Minimal-overhead profile: package power + aggregate temps only, capped at 1 Hz
+  telemetryProfile: 'full' | 'minimal';
+  onTelemetryProfileChange: (profile: 'full' | 'minimal') => void;
+  // How sampled power is integrated into energy (summary also reports uncertainty bounds)
+  energyIntegrationMethod: EnergyIntegrationMethod;
+  onEnergyIntegrationMethodChange: (method: EnergyIntegrationMethod) => void;
+  // Wall-clock window for the rolling TPS metric (seconds)
+  rollingTpsWindowSecs: number;
+  onRollingTpsWindowSecsChange: (secs: number) => void;
   // Option: automatically wait for CPU to cool back to baseline between model A and B
   waitForCpuBaselineBetweenModels: boolean;
   onWaitForCpuBaselineBetweenModelsChange: (checked: boolean) => void;
   // Tolerance config (°C)
   cpuBaselineToleranceC: number;
   onCpuBaselineToleranceCChange: (value: number) => void;
+  // Option: run A and B at the same time for "Both" instead of one after the other
+  runModelsInParallel: boolean;
+  onRunModelsInParallelChange: (checked: boolean) => void;
+  // Heat-soak target (°C) reached before each model's run; undefined disables it
+  heatSoakTargetC?: number;
+  onHeatSoakTargetCChange: (value: number | undefined) => void;
 }
 
 /**
@@ -67,18 +103,48 @@ export const TelemetryConfigPanel: React.FC<TelemetryConfigPanelProps> = ({
   className = "",
   runWithoutTelemetry,
   onRunWithoutTelemetryChange,
+  telemetryProfile,
+  onTelemetryProfileChange,
+  energyIntegrationMethod,
+  onEnergyIntegrationMethodChange,
+  rollingTpsWindowSecs,
+  onRollingTpsWindowSecsChange,
   chartRefreshMs,
   onChartRefreshMsChange,
   waitForCpuBaselineBetweenModels,
   onWaitForCpuBaselineBetweenModelsChange,
   cpuBaselineToleranceC,
   onCpuBaselineToleranceCChange,
+  runModelsInParallel,
+  onRunModelsInParallelChange,
+  heatSoakTargetC,
+  onHeatSoakTargetCChange,
 }) => {
   const [collapsed, setCollapsed] = useState<boolean>(true);
   const [toleranceRaw, setToleranceRaw] = useState<string>(
     Number.isFinite(cpuBaselineToleranceC) ? String(cpuBaselineToleranceC) : ''
   );
   const [toleranceError, setToleranceError] = useState<string>('');
+  const [heatSoakRaw, setHeatSoakRaw] = useState<string>(
+    heatSoakTargetC !== undefined ? String(heatSoakTargetC) : ''
+  );
+  const [heatSoakError, setHeatSoakError] = useState<string>('');
+  // Stored by the backend, which applies it to summaries, reports, alerts and exports
+  const [temperatureUnit, setTemperatureUnit] = useState<TemperatureUnit>('celsius');
+
+  useEffect(() => {
+    SessionPersistence.getTemperatureUnit()
+      .then(setTemperatureUnit)
+      .catch((err) => console.error('Failed to load temperature unit:', err));
+  }, []);
+
+  const handleTemperatureUnitSelect = async (unit: TemperatureUnit) => {
+    try {
+      setTemperatureUnit(await SessionPersistence.setTemperatureUnit(unit));
+    } catch (err) {
+      console.error('Failed to save temperature unit:', err);
+    }
+  };
 
   // Keep local input in sync when external value changes
   useEffect(() => {
@@ -86,6 +152,10 @@ export const TelemetryConfigPanel: React.FC<TelemetryConfigPanelProps> = ({
       setToleranceRaw(String(cpuBaselineToleranceC));
     }
   }, [cpuBaselineToleranceC]);
+
+  useEffect(() => {
+    setHeatSoakRaw(heatSoakTargetC !== undefined ? String(heatSoakTargetC) : '');
+  }, [heatSoakTargetC]);
   const handleSamplingFrequencySelect = (option: any) => {
     if (option?.data !== undefined) {
       onTelemetrySamplingHzChange(option.data);
@@ -193,6 +263,31 @@ export const TelemetryConfigPanel: React.FC<TelemetryConfigPanelProps> = ({
             />
           </div>
 
+          {/* Rolling TPS window configuration */}
+          <div>
+            <div className="flex items-center gap-2 mb-1">
+              <label className="text-sm font-medium text-gray-800">Rolling TPS Window</label>
+              <SmartTooltip
+                title="Rolling TPS window"
+                description={`Rolling TPS counts tokens generated over the last N seconds, so slowdowns late in a run show up on the live TPS chart instead of being averaged away.`}
+                preferredPosition="top"
+              >
+                <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+                  <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+                </svg>
+              </SmartTooltip>
+            </div>
+            <CustomDropdown
+              label=""
+              placeholder="Select rolling window"
+              options={ROLLING_TPS_WINDOW_OPTIONS}
+              selectedOption={ROLLING_TPS_WINDOW_OPTIONS.find(option => option.data === rollingTpsWindowSecs)
+                ?? { id: `tps-window-custom-${rollingTpsWindowSecs}`, label: `${rollingTpsWindowSecs} s`, data: rollingTpsWindowSecs }}
+              onSelect={(option: any) => { if (option && typeof option.data === 'number') onRollingTpsWindowSecsChange(option.data); }}
+              disabled={isLoading || runWithoutTelemetry}
+            />
+          </div>
+
       {/* Run without telemetry option */}
       <div className="pt-2">
         <div className={`flex items-start gap-2 ${isLoading ? 'opacity-50' : ''}`}>
@@ -221,6 +316,84 @@ export const TelemetryConfigPanel: React.FC<TelemetryConfigPanelProps> = ({
         </div>
       </div>
 
+      {/* Minimal-overhead telemetry profile */}
+      <div className="pt-2">
+        <div className={`flex items-start gap-2 ${isLoading || runWithoutTelemetry ? 'opacity-50' : ''}`}>
+          <input
+            id="minimal-telemetry-profile"
+            type="checkbox"
+            className="mt-0.5 rounded border-gray-300 text-blue-600 shadow-sm focus:border-blue-300 focus:ring focus:ring-blue-200 focus:ring-opacity-50"
+            checked={telemetryProfile === 'minimal'}
+            onChange={(e) => onTelemetryProfileChange(e.target.checked ? 'minimal' : 'full')}
+            disabled={isLoading || runWithoutTelemetry}
+          />
+          <div className="flex-1">
+            <div className="flex items-center gap-2">
+              <label htmlFor="minimal-telemetry-profile" className="text-sm font-medium text-gray-800">Minimal-overhead telemetry</label>
+              <SmartTooltip
+                title="Minimal-overhead telemetry profile"
+                description={`Collects only package power and aggregate temperatures, at no more than 1 Hz. Per-core temperatures and CPU utilization are skipped. Use this for the most measurement-sensitive comparisons.`}
+                preferredPosition="top"
+              >
+                <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+                  <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+                </svg>
+              </SmartTooltip>
+            </div>
+          </div>
+        </div>
+      </div>
+
+      {/* Energy integration method */}
+      <div className="pt-2">
+        <div className={`flex items-center gap-2 mb-1 ${isLoading || runWithoutTelemetry ? 'opacity-50' : ''}`}>
+          <span className="text-sm font-medium text-gray-800">Energy integration</span>
+          <SmartTooltip
+            title="Energy integration method"
+            description={`How power samples are turned into energy. Trapezoid interpolates linearly between samples; rectangle holds each sample over its interval; monotone cubic follows curved power draw without overshooting spikes. Energy summaries also report lower/upper bounds from holding the lower or higher sample of each interval.`}
+            preferredPosition="top"
+          >
+            <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+              <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+            </svg>
+          </SmartTooltip>
+        </div>
+        <CustomDropdown
+          label=""
+          placeholder="Select integration method"
+          options={ENERGY_INTEGRATION_OPTIONS}
+          selectedOption={ENERGY_INTEGRATION_OPTIONS.find(option => option.data === energyIntegrationMethod) ?? null}
+          onSelect={(option) => { if (option) onEnergyIntegrationMethodChange(option.data); }}
+          disabled={isLoading || runWithoutTelemetry}
+          className=""
+        />
+      </div>
+
+      {/* Temperature display unit */}
+      <div className="pt-2">
+        <div className="flex items-center gap-2 mb-1">
+          <span className="text-sm font-medium text-gray-800">Temperature unit</span>
+          <SmartTooltip
+            title="Temperature unit"
+            description={`Unit used for temperatures in comparison summaries, web exports and cooldown alerts. Recorded telemetry is always stored in Celsius.`}
+            preferredPosition="top"
+          >
+            <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+              <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+            </svg>
+          </SmartTooltip>
+        </div>
+        <CustomDropdown
+          label=""
+          placeholder="Select temperature unit"
+          options={TEMPERATURE_UNIT_OPTIONS}
+          selectedOption={TEMPERATURE_UNIT_OPTIONS.find(option => option.data === temperatureUnit) ?? null}
+          onSelect={(option) => { if (option) handleTemperatureUnitSelect(option.data); }}
+          disabled={isLoading}
+          className=""
+        />
+      </div>
+
       {/* Cooldown between models option */}
       <div className="pt-2">
         <div className={`flex items-start gap-2 ${isLoading ? 'opacity-50' : ''}`}>
@@ -295,6 +468,79 @@ Negative values (e.g., -1.0°C): require CPU to cool below the baseline by that
             </div>
           </div>
         </div>
+
+      {/* Heat-soak target before each model */}
+      <div className="pt-2">
+        <div className={`flex items-center gap-2 ${isLoading ? 'opacity-50' : ''}`}>
+          <label className="text-sm font-medium text-gray-800">Heat-soak target (°C):</label>
+          <input
+            type="text"
+            inputMode="decimal"
+            placeholder="off"
+            value={heatSoakRaw}
+            onChange={(e) => {
+              const raw = e.target.value;
+              setHeatSoakRaw(raw);
+              const trimmed = raw.trim();
+              if (trimmed === '') {
+                setHeatSoakError('');
+                onHeatSoakTargetCChange(undefined);
+                return;
+              }
+              const v = parseFloat(trimmed);
+              if (isNaN(v)) return;
+              if (v < 30 || v > 95) {
+                setHeatSoakError('Target must be between 30 and 95°C.');
+                return;
+              }
+              setHeatSoakError('');
+              onHeatSoakTargetCChange(v);
+            }}
+            disabled={isLoading}
+            className={`w-20 px-2 py-1 border rounded text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 ${heatSoakError ? 'border-red-300' : ''}`}
+          />
+          <SmartTooltip
+            title="Heat-Soak Normalization"
+            description={`When set, the CPU is brought into the target ±2°C band before each model's run: short bursts of synthetic load heat it up, or the app waits for it to cool down. Both models then start from the same thermal state, independent of run order. Gives up after 10 minutes and runs anyway. Leave empty to disable.`}
+            preferredPosition="top"
+          >
+            <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+              <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+            </svg>
+          </SmartTooltip>
+        </div>
+        {heatSoakError && (
+          <div className="mt-1 text-xs text-red-600">{heatSoakError}</div>
+        )}
+      </div>
+
+      {/* Parallel "Both" runs */}
+      <div className="pt-2">
+        <div className={`flex items-start gap-2 ${isLoading ? 'opacity-50' : ''}`}>
+          <input
+            id="parallel-enabled"
+            type="checkbox"
+            className="mt-0.5 rounded border-gray-300 text-blue-600 shadow-sm focus:border-blue-300 focus:ring focus:ring-blue-200 focus:ring-opacity-50"
+            checked={runModelsInParallel}
+            onChange={(e) => onRunModelsInParallelChange(e.target.checked)}
+            disabled={isLoading}
+          />
+          <div className="flex-1">
+            <div className="flex items-center gap-2">
+              <label htmlFor="parallel-enabled" className="text-sm font-medium text-gray-800">Run both models in parallel</label>
+              <SmartTooltip
+                title="Head-to-Head Mode"
+                description={`When enabled, "Both" runs load and generate with Model A and Model B at the same time, so you can watch them race and see the combined hardware load. The models compete for CPU, GPU and memory bandwidth, so speeds are lower than in sequential runs, and energy is reported for both models together (A+B) rather than per model. The cooldown wait does not apply.`}
+                preferredPosition="top"
+              >
+                <svg className="w-4 h-4 text-gray-400 cursor-help" fill="currentColor" viewBox="0 0 20 20">
+                  <path fillRule="evenodd" d="M18 10a8 8 0 11-16 0 8 8 0 0116 0zm-8-3a1 1 0 00-.867.5 1 1 0 11-1.731-1A3 3 0 0113 8a3.001 3.001 0 01-2 2.83V11a1 1 0 11-2 0v-1a1 1 0 011-1 1 1 0 100-2zm0 8a1 1 0 100-2 1 1 0 000 2z" clipRule="evenodd" />
+                </svg>
+              </SmartTooltip>
+            </div>
+          </div>
+        </div>
+      </div>
         </div>
       )}
     </div>
@@ -4,6 +4,7 @@ import { useModelStore } from '../../stores/modelStore';
 import { useTelemetryStore } from '../../stores/telemetryStore';
 import { useUIStore } from '../../stores/uiStore';
 import { SmartTooltip } from '../ui/SmartTooltip';
+import { ConversationImportButton, type ImportedConversation } from '../chat/ConversationImportButton';
 import type { SessionStateResult } from '../../hooks/useSessionState';
 
 interface AppHeaderProps {
@@ -13,6 +14,7 @@ interface AppHeaderProps {
   onHandleViewModeChange: (newViewMode: 'single' | 'dual') => void;
   sessionState: SessionStateResult;
   onNewChat?: () => void; // Optional explicit handler for New Chat to support immediate stop
+  onImportConversation?: (conversation: ImportedConversation) => void; // Replace the chat with an imported conversation
 }
 
 /**
@@ -28,8 +30,9 @@ export const AppHeader: React.FC<AppHeaderProps> = ({
   onHandleViewModeChange,
   sessionState,
   onNewChat,
+  onImportConversation,
 }) => {
-  const { chatHistory } = useChatStore();
+  const { chatHistory, isLoading } = useChatStore();
   const { appMode } = useModelStore();
   const { telemetryData, setSessionSaveDialogOpen } = useTelemetryStore();
   const { viewMode } = useUIStore();
@@ -95,6 +98,11 @@ export const AppHeader: React.FC<AppHeaderProps> = ({
               </>
             )}
             
+            {/* Import conversation from other tools */}
+            {onImportConversation && (
+              <ConversationImportButton onImport={onImportConversation} disabled={isLoading} />
+            )}
+
             {/* New Chat Button */}
             <button
               onClick={() => (onNewChat ? onNewChat() : onHandlePotentialClose(onClearSession, 'new-chat'))}
@@ -3,8 +3,9 @@ import { ResizeHandle } from '../ui/ResizeHandle';
 import { SystemPromptEditor } from '../config/SystemPromptEditor';
 import { ModelConfigPanel } from '../config/ModelConfigPanel';
 import { TelemetryConfigPanel } from '../config/TelemetryConfigPanel';
+import { ModelLibraryPanel } from '../config/ModelLibraryPanel';
 import { TelemetryDashboard } from '../telemetry/TelemetryDashboard';
-import { type ModelConfig } from '../../stores/modelStore';
+import { type ModelConfig, type EnergyIntegrationMethod } from '../../stores/modelStore';
 import { type TelemetryData } from '../../stores/telemetryStore';
 import type { TelemetryDataPoint, TelemetryDataPointWithRelativeTime } from '../../types/telemetry';
 
@@ -28,6 +29,12 @@ interface RightSidebarProps {
   telemetrySamplingHz: number;
   runWithoutTelemetry: boolean;
   onRunWithoutTelemetryChange: (checked: boolean) => void;
+  telemetryProfile: 'full' | 'minimal';
+  onTelemetryProfileChange: (profile: 'full' | 'minimal') => void;
+  energyIntegrationMethod: EnergyIntegrationMethod;
+  onEnergyIntegrationMethodChange: (method: EnergyIntegrationMethod) => void;
+  rollingTpsWindowSecs: number;
+  onRollingTpsWindowSecsChange: (secs: number) => void;
   // Live chart refresh
   chartRefreshMs: number;
   onChartRefreshMsChange: (ms: number) => void;
@@ -35,8 +42,8 @@ interface RightSidebarProps {
   // Telemetry state
   telemetryData: TelemetryData[];
   summaryStats: {
-    A?: { ttft_ms?: number; avg_tps?: number; energy_per_token_wh?: number };
-    B?: { ttft_ms?: number; avg_tps?: number; energy_per_token_wh?: number };
+    A?: { ttft_ms?: number; avg_tps?: number; prefill_tps?: number; energy_per_token_wh?: number; inter_token_cv?: number; longest_stall_ms?: number; stall_count?: number; stall_threshold_ms?: number };
+    B?: { ttft_ms?: number; avg_tps?: number; prefill_tps?: number; energy_per_token_wh?: number; inter_token_cv?: number; longest_stall_ms?: number; stall_count?: number; stall_threshold_ms?: number };
   };
   
   // Context validation state
@@ -126,6 +133,12 @@ export const RightSidebar: React.FC<RightSidebarProps> = ({
   onTelemetrySamplingHzChange,
   runWithoutTelemetry,
   onRunWithoutTelemetryChange,
+  telemetryProfile,
+  onTelemetryProfileChange,
+  energyIntegrationMethod,
+  onEnergyIntegrationMethodChange,
+  rollingTpsWindowSecs,
+  onRollingTpsWindowSecsChange,
   chartRefreshMs,
   onChartRefreshMsChange,
   onAddTelemetryData,
@@ -213,6 +226,12 @@ export const RightSidebar: React.FC<RightSidebarProps> = ({
                 isLoading={isLoading}
                 runWithoutTelemetry={runWithoutTelemetry}
                 onRunWithoutTelemetryChange={onRunWithoutTelemetryChange}
+                telemetryProfile={telemetryProfile}
+                onTelemetryProfileChange={onTelemetryProfileChange}
+                energyIntegrationMethod={energyIntegrationMethod}
+                onEnergyIntegrationMethodChange={onEnergyIntegrationMethodChange}
+                rollingTpsWindowSecs={rollingTpsWindowSecs}
+                onRollingTpsWindowSecsChange={onRollingTpsWindowSecsChange}
                 chartRefreshMs={chartRefreshMs}
                 onChartRefreshMsChange={onChartRefreshMsChange}
                 waitForCpuBaselineBetweenModels={modelA.wait_for_cpu_baseline_between_models === true || modelB.wait_for_cpu_baseline_between_models === true ? true : false}
@@ -233,8 +252,22 @@ export const RightSidebar: React.FC<RightSidebarProps> = ({
                   onModelAChange(updatedA);
                   onModelBChange(updatedB);
                 }}
+                runModelsInParallel={modelA.run_models_in_parallel === true || modelB.run_models_in_parallel === true}
+                onRunModelsInParallelChange={(checked) => {
+                  // Mirrored to both model configs like the cooldown option
+                  onModelAChange({ ...modelA, run_models_in_parallel: checked });
+                  onModelBChange({ ...modelB, run_models_in_parallel: checked });
+                }}
+                heatSoakTargetC={modelA.heat_soak_target_c ?? modelB.heat_soak_target_c}
+                onHeatSoakTargetCChange={(target) => {
+                  onModelAChange({ ...modelA, heat_soak_target_c: target });
+                  onModelBChange({ ...modelB, heat_soak_target_c: target });
+                }}
               />
 
+              {/* Model directories offered in the path pickers */}
+              <ModelLibraryPanel isLoading={isLoading} />
+
               {/* Model A Configuration */}
               <ModelConfigPanel
                 modelId="A"
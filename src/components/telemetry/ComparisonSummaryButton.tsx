@@ -0,0 +1,95 @@
+import React, { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useTelemetryStore } from '../../stores/telemetryStore';
+import { useModelStore } from '../../stores/modelStore';
+import { errorMessage } from '../../types/errors';
+
+type SummaryFormat = 'markdown' | 'text' | 'json';
+
+const FORMAT_OPTIONS: { format: SummaryFormat; label: string }[] = [
+  { format: 'markdown', label: 'Markdown' },
+  { format: 'text', label: 'Plain text' },
+  { format: 'json', label: 'JSON comparison card' },
+];
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+/**
+ * ComparisonSummaryButton Component
+ *
+ * Copies a compact summary of the current comparison (models, key metrics, hardware)
+ * to the clipboard as Markdown, plain text or a JSON comparison card.
+ */
+export const ComparisonSummaryButton: React.FC = () => {
+  const { summaryStats, telemetryData } = useTelemetryStore();
+  const { modelA, modelB, telemetry_sampling_hz } = useModelStore();
+  const [format, setFormat] = useState<SummaryFormat>('markdown');
+  const [status, setStatus] = useState<string | null>(null);
+
+  const entries = (['A', 'B'] as const)
+    .filter((label) => summaryStats[label])
+    .map((label) => {
+      const stats = summaryStats[label]!;
+      const modelPath = label === 'A' ? modelA.model_path : modelB.model_path;
+      // Celsius as recorded; the backend converts to the preferred unit
+      const cpuTemps = telemetryData
+        .filter((point) => point.model === label && point.cpu_temp != null)
+        .map((point) => point.cpu_temp as number);
+      return {
+        label,
+        model: modelPath ? fileName(modelPath) : `Model ${label}`,
+        ttft_ms: stats.ttft_ms ?? null,
+        decode_tps: stats.avg_tps ?? null,
+        prefill_tps: stats.prefill_tps ?? null,
+        output_tokens: stats.output_tokens ?? null,
+        total_energy_wh: stats.total_energy_wh ?? null,
+        energy_per_token_wh: stats.energy_per_token_wh ?? null,
+        inter_token_cv: stats.inter_token_cv ?? null,
+        finish_reason: stats.finish_reason ?? null,
+        peak_cpu_temp_c: cpuTemps.length > 0 ? Math.max(...cpuTemps) : null,
+      };
+    });
+
+  const handleCopy = async () => {
+    try {
+      const summary = await invoke<string>('render_comparison_summary', {
+        models: entries,
+        telemetrySamplingHz: telemetry_sampling_hz,
+        format,
+      });
+      await navigator.clipboard.writeText(summary);
+      setStatus('Copied to clipboard');
+    } catch (err) {
+      console.error('Comparison summary error:', err);
+      setStatus(`Copy failed: ${errorMessage(err)}`);
+    }
+    setTimeout(() => setStatus(null), 2500);
+  };
+
+  if (entries.length === 0) return null;
+
+  return (
+    <div className="space-y-1">
+      <div className="flex gap-2">
+        <select
+          value={format}
+          onChange={(e) => setFormat(e.target.value as SummaryFormat)}
+          className="flex-1 px-2 py-1 text-xs border border-gray-300 rounded"
+        >
+          {FORMAT_OPTIONS.map((option) => (
+            <option key={option.format} value={option.format}>{option.label}</option>
+          ))}
+        </select>
+        <button
+          onClick={handleCopy}
+          className="px-3 py-1 text-xs bg-blue-100 text-blue-700 rounded hover:bg-blue-200 transition-colors"
+        >
+          Copy Summary
+        </button>
+      </div>
+      {status && <div className="text-xs text-gray-500">{status}</div>}
+    </div>
+  );
+};
+
+export default ComparisonSummaryButton;
@@ -10,6 +10,7 @@ import {
   Legend,
 } from 'chart.js';
 import { useTelemetryStore } from '../../stores/telemetryStore';
+import { temperatureSymbol } from '../../types/telemetry';
 
 ChartJS.register(CategoryScale, LinearScale, PointElement, LineElement, Tooltip, Legend);
 
@@ -17,29 +18,31 @@ export const CooldownPanel: React.FC = () => {
   const {
     cooldownActive,
     cooldownStatus,
-    cooldownBaselineC,
-    cooldownThresholdC,
+    cooldownBaseline,
+    cooldownThreshold,
     cooldownPoints,
-    cooldownMarginC,
+    cooldownMargin,
+    cooldownUnit,
   } = useTelemetryStore();
+  const symbol = temperatureSymbol(cooldownUnit);
 
   const isVisible = cooldownActive || cooldownStatus === 'progress' || cooldownStatus === 'started' || cooldownStatus === 'complete';
 
   const { labels, datasets } = useMemo(() => {
-    const haveMeta = cooldownBaselineC !== null && cooldownThresholdC !== null && cooldownBaselineC !== undefined && cooldownThresholdC !== undefined;
+    const haveMeta = cooldownBaseline !== null && cooldownThreshold !== null && cooldownBaseline !== undefined && cooldownThreshold !== undefined;
     const hasPoints = cooldownPoints.length > 0;
     const length = hasPoints ? cooldownPoints.length : (haveMeta ? 2 : 0);
     const labels = Array.from({ length }, (_, idx) => `${idx}s`);
 
     const current = hasPoints ? cooldownPoints.map(p => p.value) : [];
-    const baseline = haveMeta && length > 0 ? Array.from({ length }, () => cooldownBaselineC as number) : [];
-    const threshold = haveMeta && length > 0 ? Array.from({ length }, () => cooldownThresholdC as number) : [];
+    const baseline = haveMeta && length > 0 ? Array.from({ length }, () => cooldownBaseline as number) : [];
+    const threshold = haveMeta && length > 0 ? Array.from({ length }, () => cooldownThreshold as number) : [];
 
     return {
       labels,
       datasets: [
         {
-          label: 'Current Max CPU Temp (°C)',
+          label: `Current Max CPU Temp (${symbol})`,
           data: current,
           borderColor: 'rgb(59, 130, 246)', // blue-500
           backgroundColor: 'rgba(59, 130, 246, 0.2)',
@@ -47,14 +50,14 @@ export const CooldownPanel: React.FC = () => {
           pointRadius: 0,
         },
         ...(baseline.length > 0 ? [{
-          label: 'Baseline (°C)',
+          label: `Baseline (${symbol})`,
           data: baseline,
           borderColor: 'rgb(16, 185, 129)', // emerald-500
           borderDash: [6, 6],
           pointRadius: 0,
         } as any] : []),
         ...(threshold.length > 0 ? [{
-          label: `Threshold (baseline + ${cooldownMarginC.toFixed(1)}°C)`,
+          label: `Threshold (baseline + ${cooldownMargin.toFixed(1)}${symbol})`,
           data: threshold,
           borderColor: 'rgb(234, 88, 12)', // orange-600
           borderDash: [6, 6],
@@ -62,7 +65,7 @@ export const CooldownPanel: React.FC = () => {
         } as any] : []),
       ],
     };
-  }, [cooldownPoints, cooldownBaselineC, cooldownThresholdC, cooldownMarginC]);
+  }, [cooldownPoints, cooldownBaseline, cooldownThreshold, cooldownMargin, symbol]);
 
   if (!isVisible) return null;
 
@@ -80,15 +83,15 @@ export const CooldownPanel: React.FC = () => {
             </span>
           </div>
           <div className="text-xs text-gray-500 flex flex-wrap gap-x-3 gap-y-1 justify-end">
-            {cooldownBaselineC !== null && cooldownThresholdC !== null && (
+            {cooldownBaseline !== null && cooldownThreshold !== null && (
               <>
-                <span className="whitespace-nowrap">Baseline: {cooldownBaselineC?.toFixed(1)}°C</span>
+                <span className="whitespace-nowrap">Baseline: {cooldownBaseline?.toFixed(1)}{symbol}</span>
                 <span className="hidden sm:inline text-gray-400">•</span>
-                <span className="whitespace-nowrap">Threshold: {cooldownThresholdC?.toFixed(1)}°C</span>
+                <span className="whitespace-nowrap">Threshold: {cooldownThreshold?.toFixed(1)}{symbol}</span>
                 {cooldownPoints.length > 0 && (
                   <>
                     <span className="hidden sm:inline text-gray-400">•</span>
-                    <span className="whitespace-nowrap">Current: {cooldownPoints[cooldownPoints.length - 1].value.toFixed(1)}°C</span>
+                    <span className="whitespace-nowrap">Current: {cooldownPoints[cooldownPoints.length - 1].value.toFixed(1)}{symbol}</span>
                   </>
                 )}
               </>
@@ -122,7 +125,7 @@ export const CooldownPanel: React.FC = () => {
             interaction: { mode: 'nearest', axis: 'x', intersect: false },
             scales: {
               x: { grid: { display: false }, ticks: { display: true, maxTicksLimit: 8, font: { size: 10 } } },
-              y: { grid: { display: true }, ticks: { display: true, font: { size: 10 } }, title: { display: true, text: '°C' } },
+              y: { grid: { display: true }, ticks: { display: true, font: { size: 10 } }, title: { display: true, text: symbol } },
             },
           }}
           />
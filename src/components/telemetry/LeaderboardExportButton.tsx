@@ -0,0 +1,125 @@
+import React, { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import { useTelemetryStore } from '../../stores/telemetryStore';
+import { useModelStore } from '../../stores/modelStore';
+import { errorMessage } from '../../types/errors';
+
+// Opaque to the frontend: shown for review and passed back unchanged on export
+type BenchmarkRecord = Record<string, unknown>;
+
+/**
+ * LeaderboardExportButton Component
+ *
+ * Opt-in export of an anonymized benchmark record (chip, model hash, quantization,
+ * TPS, energy/token) for community leaderboards. The backend builds the record,
+ * the user reviews the exact JSON, and only then chooses where to save it.
+ */
+export const LeaderboardExportButton: React.FC = () => {
+  const { summaryStats } = useTelemetryStore();
+  const { modelA, modelB } = useModelStore();
+  const [record, setRecord] = useState<BenchmarkRecord | null>(null);
+  const [preparing, setPreparing] = useState<'A' | 'B' | null>(null);
+  const [status, setStatus] = useState<string | null>(null);
+
+  const candidates = (['A', 'B'] as const).filter((label) => {
+    const config = label === 'A' ? modelA : modelB;
+    return summaryStats[label]?.avg_tps !== undefined && !!config.model_path;
+  });
+
+  const handlePrepare = async (label: 'A' | 'B') => {
+    const config = label === 'A' ? modelA : modelB;
+    const stats = summaryStats[label]!;
+    setPreparing(label);
+    setStatus(null);
+    try {
+      const prepared = await invoke<BenchmarkRecord>('prepare_benchmark_record', {
+        modelPath: config.model_path,
+        metrics: {
+          decode_tps: stats.avg_tps ?? null,
+          prefill_tps: stats.prefill_tps ?? null,
+          ttft_ms: stats.ttft_ms ?? null,
+          energy_per_token_wh: stats.energy_per_token_wh ?? null,
+          output_tokens: stats.output_tokens ?? null,
+          n_ctx: typeof config.n_ctx === 'number' ? config.n_ctx : null,
+        },
+      });
+      setRecord(prepared);
+    } catch (err) {
+      console.error('Benchmark record error:', err);
+      setStatus(`Could not prepare record: ${errorMessage(err)}`);
+    } finally {
+      setPreparing(null);
+    }
+  };
+
+  const handleExport = async () => {
+    if (!record) return;
+    try {
+      const path = await save({
+        title: 'Export benchmark record',
+        defaultPath: 'benchmark-record.json',
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (!path) return;
+      await invoke('export_benchmark_record', { record, path });
+      setRecord(null);
+      setStatus('Benchmark record exported');
+    } catch (err) {
+      console.error('Benchmark export error:', err);
+      setStatus(`Export failed: ${errorMessage(err)}`);
+    }
+  };
+
+  if (candidates.length === 0) return null;
+
+  return (
+    <div className="space-y-1">
+      <div className="flex gap-2">
+        {candidates.map((label) => (
+          <button
+            key={label}
+            onClick={() => handlePrepare(label)}
+            disabled={preparing !== null}
+            className="flex-1 px-3 py-1 text-xs bg-gray-100 text-gray-700 rounded hover:bg-gray-200 transition-colors disabled:opacity-50"
+            title="Build an anonymized record for a community leaderboard (hashes the model file)"
+          >
+            {preparing === label ? 'Hashing model…' : `Leaderboard Record (${label})`}
+          </button>
+        ))}
+      </div>
+      {status && <div className="text-xs text-gray-500">{status}</div>}
+
+      {record && (
+        <div className="fixed inset-0 bg-black bg-opacity-40 flex items-center justify-center z-50">
+          <div className="bg-white rounded-lg shadow-xl w-[32rem] max-w-full p-4 space-y-3">
+            <h3 className="font-medium text-gray-800">Review benchmark record</h3>
+            <p className="text-xs text-gray-600">
+              This is exactly what will be saved. It contains no file paths, model names, prompts or outputs.
+              Nothing is uploaded; you can submit the file yourself.
+            </p>
+            <pre className="text-xs bg-gray-50 border rounded p-2 max-h-80 overflow-auto">
+              {JSON.stringify(record, null, 2)}
+            </pre>
+            <div className="flex justify-end gap-2">
+              <button
+                onClick={() => setRecord(null)}
+                className="px-3 py-1 text-sm bg-gray-100 text-gray-700 rounded hover:bg-gray-200"
+              >
+                Cancel
+              </button>
+              <button
+                onClick={handleExport}
+                className="px-3 py-1 text-sm bg-blue-600 text-white rounded hover:bg-blue-700"
+              >
+                Export…
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default LeaderboardExportButton;
@@ -17,8 +17,8 @@ interface TelemetryDashboardProps {
   isLoading: boolean;
   telemetryData: TelemetryData[];
   summaryStats: {
-    A?: { ttft_ms?: number; avg_tps?: number; energy_per_token_wh?: number };
-    B?: { ttft_ms?: number; avg_tps?: number; energy_per_token_wh?: number };
+    A?: { ttft_ms?: number; avg_tps?: number; prefill_tps?: number; energy_per_token_wh?: number; prefill_energy_per_token_wh?: number; decode_energy_per_token_wh?: number; inter_token_cv?: number; longest_stall_ms?: number; stall_count?: number; stall_threshold_ms?: number; load_memory_delta_gb?: number; generation_memory_delta_gb?: number; context_used_tokens?: number; peak_context_used_tokens?: number; n_ctx?: number };
+    B?: { ttft_ms?: number; avg_tps?: number; prefill_tps?: number; energy_per_token_wh?: number; prefill_energy_per_token_wh?: number; decode_energy_per_token_wh?: number; inter_token_cv?: number; longest_stall_ms?: number; stall_count?: number; stall_threshold_ms?: number; load_memory_delta_gb?: number; generation_memory_delta_gb?: number; context_used_tokens?: number; peak_context_used_tokens?: number; n_ctx?: number };
   };
   overlayTelemetry: {
     getOverlayChartData: () => {
@@ -54,6 +54,8 @@ interface TelemetryDashboardProps {
  * - Interactive chart selection and data visualization
  */
 import { CooldownPanel } from './CooldownPanel';
+import { ComparisonSummaryButton } from './ComparisonSummaryButton';
+import { LeaderboardExportButton } from './LeaderboardExportButton';
 
 export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
   telemetryData,
@@ -130,9 +132,35 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
               {summaryStats.A.avg_tps && (
                 <div className="text-green-700">Avg TPS: {summaryStats.A.avg_tps.toFixed(2)}</div>
               )}
+              {summaryStats.A.prefill_tps !== undefined && (
+                <div className="text-green-700">Prefill: {summaryStats.A.prefill_tps.toFixed(1)} tok/s</div>
+              )}
               {summaryStats.A.energy_per_token_wh && (
                 <div className="text-green-700">Energy per Token: {(summaryStats.A.energy_per_token_wh * 1000).toFixed(3)}mWh</div>
               )}
+              {summaryStats.A.prefill_energy_per_token_wh !== undefined && summaryStats.A.decode_energy_per_token_wh !== undefined && (
+                <div className="text-green-700">
+                  Prefill / Decode: {(summaryStats.A.prefill_energy_per_token_wh * 1000).toFixed(3)} / {(summaryStats.A.decode_energy_per_token_wh * 1000).toFixed(3)}mWh per token
+                </div>
+              )}
+              {summaryStats.A.inter_token_cv !== undefined && (
+                <div className="text-green-700">Token Jitter (CV): {summaryStats.A.inter_token_cv.toFixed(2)}</div>
+              )}
+              {summaryStats.A.longest_stall_ms !== undefined && (
+                <div className="text-green-700">
+                  Longest Stall: {summaryStats.A.longest_stall_ms.toFixed(0)}ms ({summaryStats.A.stall_count ?? 0} stalls &gt; {summaryStats.A.stall_threshold_ms ?? 250}ms)
+                </div>
+              )}
+              {summaryStats.A.load_memory_delta_gb !== undefined && (
+                <div className="text-green-700">
+                  Memory: +{summaryStats.A.load_memory_delta_gb.toFixed(2)} GB load, +{(summaryStats.A.generation_memory_delta_gb ?? 0).toFixed(2)} GB generation peak
+                </div>
+              )}
+              {summaryStats.A.context_used_tokens !== undefined && summaryStats.A.n_ctx && (
+                <div className="text-green-700">
+                  Context: {summaryStats.A.context_used_tokens} / {summaryStats.A.n_ctx} tokens ({(summaryStats.A.context_used_tokens / summaryStats.A.n_ctx * 100).toFixed(0)}%), peak {summaryStats.A.peak_context_used_tokens ?? summaryStats.A.context_used_tokens}
+                </div>
+              )}
             </div>
           )}
           {summaryStats.B && (
@@ -144,12 +172,40 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
               {summaryStats.B.avg_tps && (
                 <div className="text-purple-700">Avg TPS: {summaryStats.B.avg_tps.toFixed(2)}</div>
               )}
+              {summaryStats.B.prefill_tps !== undefined && (
+                <div className="text-purple-700">Prefill: {summaryStats.B.prefill_tps.toFixed(1)} tok/s</div>
+              )}
               {summaryStats.B.energy_per_token_wh && (
                 <div className="text-purple-700">Energy per Token: {(summaryStats.B.energy_per_token_wh * 1000).toFixed(3)}mWh</div>
               )}
+              {summaryStats.B.prefill_energy_per_token_wh !== undefined && summaryStats.B.decode_energy_per_token_wh !== undefined && (
+                <div className="text-purple-700">
+                  Prefill / Decode: {(summaryStats.B.prefill_energy_per_token_wh * 1000).toFixed(3)} / {(summaryStats.B.decode_energy_per_token_wh * 1000).toFixed(3)}mWh per token
+                </div>
+              )}
+              {summaryStats.B.inter_token_cv !== undefined && (
+                <div className="text-purple-700">Token Jitter (CV): {summaryStats.B.inter_token_cv.toFixed(2)}</div>
+              )}
+              {summaryStats.B.longest_stall_ms !== undefined && (
+                <div className="text-purple-700">
+                  Longest Stall: {summaryStats.B.longest_stall_ms.toFixed(0)}ms ({summaryStats.B.stall_count ?? 0} stalls &gt; {summaryStats.B.stall_threshold_ms ?? 250}ms)
+                </div>
+              )}
+              {summaryStats.B.load_memory_delta_gb !== undefined && (
+                <div className="text-purple-700">
+                  Memory: +{summaryStats.B.load_memory_delta_gb.toFixed(2)} GB load, +{(summaryStats.B.generation_memory_delta_gb ?? 0).toFixed(2)} GB generation peak
+                </div>
+              )}
+              {summaryStats.B.context_used_tokens !== undefined && summaryStats.B.n_ctx && (
+                <div className="text-purple-700">
+                  Context: {summaryStats.B.context_used_tokens} / {summaryStats.B.n_ctx} tokens ({(summaryStats.B.context_used_tokens / summaryStats.B.n_ctx * 100).toFixed(0)}%), peak {summaryStats.B.peak_context_used_tokens ?? summaryStats.B.context_used_tokens}
+                </div>
+              )}
             </div>
           )}
         </div>
+        <ComparisonSummaryButton />
+        <LeaderboardExportButton />
       </div>
 
       {/* Debug telemetry data status */}
@@ -341,6 +397,25 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
                     </div>
                   );
                 })()}
+
+                {/* OS thermal pressure; anything above nominal means macOS is throttling */}
+                {(() => {
+                  const pressure = getLatestTelemetry()?.thermal_pressure;
+                  return pressure ? (
+                    <div className="flex items-center justify-between">
+                      <span>Thermal Pressure:</span>
+                      <span className={`px-2 py-1 rounded text-xs font-medium capitalize ${
+                        pressure === 'nominal'
+                          ? 'bg-green-100 text-green-800'
+                          : pressure === 'moderate'
+                          ? 'bg-orange-100 text-orange-800'
+                          : 'bg-red-100 text-red-800'
+                      }`}>
+                        {pressure}
+                      </span>
+                    </div>
+                  ) : null;
+                })()}
                 
                 {/* Performance Area Sensors */}
                 {(() => {
@@ -377,6 +452,32 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
                     </div>
                   ) : null;
                 })()}
+                
+                {/* Per-Die Breakdown (Ultra chips) */}
+                {(() => {
+                  const latest = getLatestTelemetry();
+                  const dies = latest?.core_temperatures?.dies;
+                  return dies && dies.length > 1 ? (
+                    <div>
+                      <div className="font-medium text-gray-600 mb-1">Per-Die Averages ({dies.length} dies)</div>
+                      <div className="grid grid-cols-2 gap-1">
+                        {dies.map((die) => (
+                          <div key={die.die} className="bg-gray-50 p-1 rounded">
+                            <div className="text-xs font-medium text-gray-600">Die {die.die + 1}</div>
+                            <div className="flex items-center justify-between">
+                              <span>CPU:</span>
+                              <span className="font-mono">{die.cpu_temp_avg != null ? `${die.cpu_temp_avg.toFixed(1)}°C` : '-'}</span>
+                            </div>
+                            <div className="flex items-center justify-between">
+                              <span>GPU:</span>
+                              <span className="font-mono">{die.gpu_temp_avg != null ? `${die.gpu_temp_avg.toFixed(1)}°C` : '-'}</span>
+                            </div>
+                          </div>
+                        ))}
+                      </div>
+                    </div>
+                  ) : null;
+                })()}
               </div>
             </div>
           )}
@@ -453,6 +554,33 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
                         {latest.ram_usage?.toFixed(2)}GB
                       </span>
                     </div>
+                    {/* Swap and compressor growth mean the model no longer fits in RAM */}
+                    {latest.swap_usage != null && (
+                      <div className="flex items-center justify-between">
+                        <span>Swap Used:</span>
+                        <span className="font-mono text-purple-800">{latest.swap_usage.toFixed(2)}GB</span>
+                      </div>
+                    )}
+                    {latest.compressed_memory != null && (
+                      <div className="flex items-center justify-between">
+                        <span>Compressed:</span>
+                        <span className="font-mono text-purple-800">{latest.compressed_memory.toFixed(2)}GB</span>
+                      </div>
+                    )}
+                    {latest.memory_pressure && (
+                      <div className="flex items-center justify-between">
+                        <span>Memory Pressure:</span>
+                        <span className={`px-2 py-1 rounded text-xs font-medium capitalize ${
+                          latest.memory_pressure === 'normal'
+                            ? 'bg-green-100 text-green-800'
+                            : latest.memory_pressure === 'warning'
+                            ? 'bg-orange-100 text-orange-800'
+                            : 'bg-red-100 text-red-800'
+                        }`}>
+                          {latest.memory_pressure}
+                        </span>
+                      </div>
+                    )}
                   </div>
                 </div>
               ) : null;
@@ -531,6 +659,15 @@ export const TelemetryDashboard: React.FC<TelemetryDashboardProps> = ({
                       {latest.cpu_overall_utilization?.toFixed(1) || '0.0'}%
                     </span>
                   </div>
+                  {latest.inference_cpu_utilization != null && (
+                    <div className="flex items-center justify-between">
+                      <span>Inference-attributed:</span>
+                      <span className="font-mono">
+                        {latest.inference_cpu_utilization.toFixed(1)}%
+                        {latest.inference_cpu_share != null && ` (${latest.inference_cpu_share.toFixed(0)}% of load)`}
+                      </span>
+                    </div>
+                  )}
                   
                   {/* P-Core utilization */}
                   {latest.cpu_p_core_utilization && (
@@ -0,0 +1,74 @@
+import React, { useState } from 'react';
+import { SessionPersistence } from '../../services/sessionPersistence';
+import type { CompatibilityReport } from '../../services/sessionPersistence';
+import { errorMessage } from '../../types/errors';
+
+interface DataCompatibilityBannerProps {
+  report: CompatibilityReport | null;
+  onResolved: (report: CompatibilityReport | null) => void;
+}
+
+/**
+ * DataCompatibilityBanner Component
+ *
+ * Shown at startup when saved sessions were written by an older app version and need
+ * migrating, or by a newer one this version cannot read reliably.
+ */
+export const DataCompatibilityBanner: React.FC<DataCompatibilityBannerProps> = ({ report, onResolved }) => {
+  const [isMigrating, setIsMigrating] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  if (!report || (report.needs_migration.length === 0 && report.newer_than_app.length === 0)) return null;
+
+  const handleMigrate = async () => {
+    setIsMigrating(true);
+    setError(null);
+    try {
+      const summary = await SessionPersistence.migrateSessions();
+      if (summary.failed.length > 0) {
+        setError(`${summary.failed.length} session(s) could not be upgraded: ${summary.failed[0].error}`);
+      }
+      onResolved(await SessionPersistence.checkDataCompatibility());
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setIsMigrating(false);
+    }
+  };
+
+  return (
+    <div className="mx-4 mt-3 p-3 bg-amber-50 border border-amber-200 rounded text-sm text-amber-800">
+      <div className="flex items-start justify-between gap-2">
+        <div>
+          {report.needs_migration.length > 0 && (
+            <div className="font-medium">
+              {report.needs_migration.length} saved session(s) use an older data format
+              {report.previous_app_version && report.previous_app_version !== report.app_version
+                ? ` (last opened with ${report.previous_app_version})` : ''}
+            </div>
+          )}
+          {report.newer_than_app.length > 0 && (
+            <div className="text-xs mt-1">
+              {report.newer_than_app.length} session(s) were saved by a newer version of apples2oranges and may not load correctly.
+            </div>
+          )}
+          {error && <div className="text-xs mt-1 text-red-700">{error}</div>}
+        </div>
+        <div className="flex gap-2 shrink-0">
+          {report.needs_migration.length > 0 && (
+            <button
+              onClick={handleMigrate}
+              disabled={isMigrating}
+              className="text-xs px-2 py-1 rounded bg-amber-600 text-white disabled:opacity-50"
+            >
+              {isMigrating ? 'Upgrading...' : 'Upgrade sessions'}
+            </button>
+          )}
+          <button onClick={() => onResolved(null)} className="text-xs text-amber-900 underline">Dismiss</button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default DataCompatibilityBanner;
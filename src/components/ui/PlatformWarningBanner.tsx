@@ -0,0 +1,58 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+export interface PlatformCapabilities {
+  binary_arch: string;
+  apple_silicon_host: boolean | null;
+  rosetta_translated: boolean | null;
+  metal_available: boolean;
+  llama_features: string[];
+  llama_system_info: string;
+  warnings: string[];
+}
+
+export interface HardwareInfo {
+  hardware: {
+    chip: string;
+    total_cores: number;
+    p_cores: number;
+    e_cores: number;
+    memory_gb: number | null;
+    os: string;
+  };
+  capabilities: PlatformCapabilities;
+}
+
+/**
+ * PlatformWarningBanner Component
+ *
+ * Shown at startup when the app runs under Rosetta or its llama.cpp build lacks Metal,
+ * NEON or Accelerate, since every result recorded in that state is unrepresentative.
+ */
+export const PlatformWarningBanner: React.FC = () => {
+  const [warnings, setWarnings] = useState<string[]>([]);
+
+  useEffect(() => {
+    invoke<HardwareInfo>('get_hardware_info')
+      .then((info) => setWarnings(info.capabilities.warnings))
+      .catch((err) => console.warn('Platform capability probe failed:', err));
+  }, []);
+
+  if (warnings.length === 0) return null;
+
+  return (
+    <div className="mx-4 mt-3 p-3 bg-red-50 border border-red-200 rounded text-sm text-red-800">
+      <div className="flex items-start justify-between gap-2">
+        <div>
+          <div className="font-medium">Results on this setup are not representative of the hardware</div>
+          {warnings.map((warning, index) => (
+            <div key={index} className="text-xs mt-1">{warning}</div>
+          ))}
+        </div>
+        <button onClick={() => setWarnings([])} className="text-xs text-red-900 underline shrink-0">Dismiss</button>
+      </div>
+    </div>
+  );
+};
+
+export default PlatformWarningBanner;
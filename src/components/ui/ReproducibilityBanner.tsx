@@ -0,0 +1,48 @@
+import React from 'react';
+import type { ModelFileStatus, SessionReproducibilityReport } from '../../services/sessionPersistence';
+
+interface ReproducibilityBannerProps {
+  report: SessionReproducibilityReport | null;
+  onDismiss: () => void;
+}
+
+const STATUS_TEXT: Record<ModelFileStatus, string> = {
+  unchanged: 'unchanged',
+  changed: 'file has changed since the session was saved',
+  missing: 'file not found',
+  not_recorded: 'no fingerprint recorded (saved by an older version)',
+};
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+/**
+ * ReproducibilityBanner Component
+ *
+ * Shown after loading a saved session whose model files are missing or changed,
+ * so users know a rerun would not be comparable with the saved results.
+ */
+export const ReproducibilityBanner: React.FC<ReproducibilityBannerProps> = ({ report, onDismiss }) => {
+  if (!report || report.comparable) return null;
+
+  return (
+    <div className="mx-4 mt-3 p-3 bg-amber-50 border border-amber-200 rounded text-sm text-amber-800">
+      <div className="flex items-start justify-between gap-2">
+        <div>
+          <div className="font-medium">A rerun may not be comparable with this session</div>
+          <ul className="mt-1 text-xs space-y-0.5">
+            {report.models
+              .filter((model) => model.status !== 'unchanged')
+              .map((model) => (
+                <li key={model.slot}>
+                  Model {model.slot === 'model_a' ? 'A' : 'B'} ({fileName(model.path)}): {STATUS_TEXT[model.status]}
+                </li>
+              ))}
+          </ul>
+        </div>
+        <button onClick={onDismiss} className="text-xs text-amber-900 underline">Dismiss</button>
+      </div>
+    </div>
+  );
+};
+
+export default ReproducibilityBanner;
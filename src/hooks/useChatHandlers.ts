@@ -1,11 +1,13 @@
 import { invoke } from "@tauri-apps/api/core";
 import type React from "react";
 import type { Message } from "../components/chat/MessageItem";
+import type { GenerationTurnResult, JobHandle } from "../types/telemetry";
 import { useChatStore } from "../stores/chatStore";
 import { useModelStore } from "../stores/modelStore";
 import { useTelemetryStore } from "../stores/telemetryStore";
 import { useUIStore } from "../stores/uiStore";
 import { useShallow } from "zustand/react/shallow";
+import { errorCode, errorMessage } from "../types/errors";
 
 interface ContextWarnings {
   modelA: boolean;
@@ -28,6 +30,9 @@ export const useChatHandlers = (options?: {
     setStreamingResponses,
     editingMessageId,
     cancelMessageEdit,
+    applyGenerationResult,
+    samplingOverrides,
+    clearSamplingOverrides,
   } = useChatStore(useShallow((s: any) => ({
     chatHistory: s.chatHistory,
     setChatHistory: s.setChatHistory,
@@ -40,6 +45,9 @@ export const useChatHandlers = (options?: {
     setStreamingResponses: s.setStreamingResponses,
     editingMessageId: s.editingMessageId,
     cancelMessageEdit: s.cancelMessageEdit,
+    applyGenerationResult: s.applyGenerationResult,
+    samplingOverrides: s.samplingOverrides,
+    clearSamplingOverrides: s.clearSamplingOverrides,
   })));
 
   // Model store
@@ -49,6 +57,9 @@ export const useChatHandlers = (options?: {
     systemPrompt,
     telemetry_sampling_hz,
     run_without_telemetry,
+    telemetry_profile,
+    energy_integration_method,
+    rolling_tps_window_secs,
     resetTokenCounts,
   } = useModelStore(useShallow((s) => ({
     modelA: s.modelA,
@@ -56,17 +67,22 @@ export const useChatHandlers = (options?: {
     systemPrompt: s.systemPrompt,
     telemetry_sampling_hz: s.telemetry_sampling_hz,
     run_without_telemetry: s.run_without_telemetry,
+    telemetry_profile: s.telemetry_profile,
+    energy_integration_method: s.energy_integration_method,
+    rolling_tps_window_secs: s.rolling_tps_window_secs,
     resetTokenCounts: s.resetTokenCounts,
   })));
 
   // Telemetry store
   const {
     clearSummaryStats,
+    updateSummaryStats,
     setCooldownActive,
     setCooldownStatus,
     clearCooldownPoints,
   } = useTelemetryStore(useShallow((s) => ({
     clearSummaryStats: s.clearSummaryStats,
+    updateSummaryStats: s.updateSummaryStats,
     setCooldownActive: s.setCooldownActive,
     setCooldownStatus: s.setCooldownStatus,
     clearCooldownPoints: s.clearCooldownPoints,
@@ -82,6 +98,26 @@ export const useChatHandlers = (options?: {
     return `msg_${Date.now()}_${Math.random().toString(36).slice(2, 11)}`;
   };
 
+  // Record each model's final result (finish reason, truncation) on its message and summary
+  const applyTurnResult = (turn: GenerationTurnResult, overriddenModels: string[] = []) => {
+    turn.results.forEach((result) => {
+      applyGenerationResult(result, overriddenModels.includes(result.model));
+      if (result.model === 'A' || result.model === 'B') {
+        updateSummaryStats(result.model, {
+          finish_reason: result.finish_reason,
+          truncated: result.truncated,
+          output_tokens: result.output_tokens,
+          context_used_tokens: result.context_used_tokens,
+          peak_context_used_tokens: Math.max(
+            useTelemetryStore.getState().summaryStats[result.model]?.peak_context_used_tokens ?? 0,
+            result.context_used_tokens,
+          ),
+          n_ctx: result.n_ctx,
+        });
+      }
+    });
+  };
+
   const handleStopGeneration = async () => {
     if (!isLoading) return;
 
@@ -94,6 +130,17 @@ export const useChatHandlers = (options?: {
     // Note: we don't setIsStopping(false) here - let the event handlers do it
   };
 
+  // "Both" mode: stop one model and let the other finish
+  const handleStopModel = async (model: 'A' | 'B') => {
+    if (!isLoading) return;
+
+    try {
+      await invoke("stop_generation", { target: model });
+    } catch (error) {
+      console.error(`Failed to stop model ${model}:`, error);
+    }
+  };
+
   const rerunFromMessage = async (messageId: string) => {
     // Find the message index
     const messageIndex = chatHistory.findIndex((msg: Message) => msg.id === messageId);
@@ -139,7 +186,14 @@ export const useChatHandlers = (options?: {
         (modelA as any).wait_for_cpu_baseline_margin_c ??
         (modelB as any).wait_for_cpu_baseline_margin_c ??
         2.0,
+      heat_soak_target_c: modelA.heat_soak_target_c ?? modelB.heat_soak_target_c,
+      heat_soak_band_c: modelA.heat_soak_band_c ?? modelB.heat_soak_band_c,
+      parallel: modelA.run_models_in_parallel || modelB.run_models_in_parallel || false,
+      isolate_models: modelA.isolate_models || modelB.isolate_models || false,
       run_without_telemetry,
+      telemetry_profile,
+      energy_integration_method,
+      rolling_tps_window_secs,
     };
 
     // Add model configurations based on target
@@ -151,9 +205,13 @@ export const useChatHandlers = (options?: {
     }
 
     try {
-      await invoke("run_generation_turn", { config });
+      const { job_id } = await invoke<JobHandle>("run_generation_turn", { config });
+      const turn = await invoke<GenerationTurnResult>("await_job", { jobId: job_id });
+      applyTurnResult(turn);
     } catch (error) {
-      console.error("Re-run generation error:", error);
+      if (errorCode(error) !== "cancelled") {
+        console.error("Re-run generation error:", errorMessage(error));
+      }
     } finally {
       setIsLoading(false);
     }
@@ -244,7 +302,14 @@ export const useChatHandlers = (options?: {
         (modelA as any).wait_for_cpu_baseline_margin_c ??
         (modelB as any).wait_for_cpu_baseline_margin_c ??
         2.0,
+      heat_soak_target_c: modelA.heat_soak_target_c ?? modelB.heat_soak_target_c,
+      heat_soak_band_c: modelA.heat_soak_band_c ?? modelB.heat_soak_band_c,
+      parallel: modelA.run_models_in_parallel || modelB.run_models_in_parallel || false,
+      isolate_models: modelA.isolate_models || modelB.isolate_models || false,
       run_without_telemetry,
+      telemetry_profile,
+      energy_integration_method,
+      rolling_tps_window_secs,
     };
 
     // Add model configurations based on target
@@ -255,10 +320,26 @@ export const useChatHandlers = (options?: {
       (config as any).model_b = modelB;
     }
 
+    // One-off sampling overrides apply to this turn only; the stored model configs are untouched
+    const overriddenModels: string[] = [];
+    if (config.model_a && samplingOverrides.A) {
+      config.sampling_overrides_a = samplingOverrides.A;
+      overriddenModels.push("A");
+    }
+    if (config.model_b && samplingOverrides.B) {
+      config.sampling_overrides_b = samplingOverrides.B;
+      overriddenModels.push("B");
+    }
+    clearSamplingOverrides();
+
     try {
-      await invoke("run_generation_turn", { config });
+      const { job_id } = await invoke<JobHandle>("run_generation_turn", { config });
+      const turn = await invoke<GenerationTurnResult>("await_job", { jobId: job_id });
+      applyTurnResult(turn, overriddenModels);
     } catch (error) {
-      console.error("Generation error:", error);
+      if (errorCode(error) !== "cancelled") {
+        console.error("Generation error:", errorMessage(error));
+      }
     } finally {
       setIsLoading(false);
     }
@@ -267,6 +348,7 @@ export const useChatHandlers = (options?: {
   return {
     handleSendPrompt,
     handleStopGeneration,
+    handleStopModel,
     rerunFromMessage,
   };
 };
\ No newline at end of file
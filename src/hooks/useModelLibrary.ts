@@ -0,0 +1,31 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { ModelLibrary, type ModelFile } from '../services/modelLibrary';
+
+/**
+ * The model library, reloaded whenever the backend reports GGUFs added or removed
+ */
+export const useModelLibrary = (): ModelFile[] => {
+  const [models, setModels] = useState<ModelFile[]>([]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    const load = () =>
+      ModelLibrary.getModels()
+        .then((files) => { if (!disposed) setModels(files); })
+        .catch((err) => console.error('Failed to load model library:', err));
+
+    load();
+    listen('model_library_changed', load).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  return models;
+};
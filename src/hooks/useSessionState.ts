@@ -8,8 +8,8 @@ interface SessionSnapshot {
   systemPrompt: string;
   modelAPath: string;
   modelBPath: string;
-  modelAContextSize: number;
-  modelBContextSize: number;
+  modelAContextSize: number | 'auto';
+  modelBContextSize: number | 'auto';
   // Hash of message contents to detect edits (for future message editing feature)
   messagesHash: string;
   timestamp: number;
@@ -30,8 +30,8 @@ interface SessionData {
   systemPrompt: string;
   modelAPath: string;
   modelBPath: string;
-  modelAContextSize: number;
-  modelBContextSize: number;
+  modelAContextSize: number | 'auto';
+  modelBContextSize: number | 'auto';
 }
 
 // Simple hash function for message content comparison
@@ -2,11 +2,14 @@ import { useEffect } from 'react';
 import { DEBUG_LOGS } from '../utils/debug';
 import type React from 'react';
 import { listen } from '@tauri-apps/api/event';
-import type { TelemetryDataPoint } from '../types/telemetry';
+import type { TelemetryDataPoint, MemoryUsage, TemperatureUnit } from '../types/telemetry';
 import type { Message } from '../components/chat/MessageItem';
 import type { CoreTemperatureData } from '../stores/telemetryStore';
 import type { useOverlayTelemetry } from './useOverlayTelemetry';
 import { useTelemetryStore } from '../stores/telemetryStore';
+import { useModelStore } from '../stores/modelStore';
+import type { EnergyIntegrationMethod, PromptBudgetEvent } from '../stores/modelStore';
+import type { RunManifest } from '../services/sessionPersistence';
 
 // Event interfaces matching App.tsx
 interface TokenEvent {
@@ -32,6 +35,17 @@ interface SystemPromptTokenEvent {
   timestamp_ms: number;
 }
 
+interface ContextSizeResolvedEvent {
+  model: string;
+  requested: number | 'auto';
+  n_ctx: number;
+  trained_n_ctx?: number | null;
+  memory_limit_n_ctx?: number | null;
+  rope_scaling?: string | null;
+  trained_context_warning?: string | null; // n_ctx exceeds the trained context without RoPE scaling
+  timestamp_ms: number;
+}
+
 interface GenerationTimeEvent {
   generation_time_ms: number;
   model: string;
@@ -44,7 +58,74 @@ interface PowerConsumptionSummaryEvent {
   gpu_energy_wh: number;
   ane_energy_wh: number;
   energy_per_token_wh?: number;
+  integration_method?: EnergyIntegrationMethod;
+  energy_lower_bound_wh?: number | null;    // Sample-and-hold bounds on total_energy_wh
+  energy_upper_bound_wh?: number | null;
+  measurement_overhead_wh?: number | null;  // Calibrated telemetry overhead for the run
+  net_total_energy_wh?: number | null;      // Only set when overhead subtraction is enabled
+  net_energy_per_token_wh?: number | null;
+  idle_power_subtracted_w?: number | null;  // Idle baseline removed from readings; energies are marginal
+  phase_breakdown?: {                       // Prompt processing vs generation (not for parallel runs)
+    prefill_energy_wh: number;
+    prefill_ms: number;
+    prefill_energy_per_token_wh: number | null;
+    decode_energy_wh: number;
+    decode_ms: number;
+    decode_energy_per_token_wh: number | null;
+  } | null;
+  sample_quality?: {                        // Samples left out of the energy integration
+    discarded_samples: number;              // Duplicate or out-of-order timestamps
+    rejected_power_readings: number;        // Negative, non-finite or implausibly high readings
+    series_resets: number;                  // Gaps (e.g. sleep) not integrated across
+  };
+  model: string;
+  timestamp_ms: number;
+}
+
+interface PrefillMetricsEvent {
   model: string;
+  prompt_tokens: number;
+  prefill_ms: number;
+  prefill_tps?: number | null;  // Prompt-eval tokens/sec, separate from decode TPS
+  timestamp_ms: number;
+}
+
+interface GenerationProgressEvent {
+  model: string;
+  tokens_generated: number;
+  max_tokens: number;
+  rolling_tps?: number | null;
+  eta_ms?: number | null;
+  context_used_tokens: number;  // Prompt plus decoded tokens in the context
+  n_ctx: number;
+  elapsed_ms: number;
+  draft_acceptance_rate?: number | null;  // Speculative decoding only
+  timestamp_ms: number;
+}
+
+interface TokenStabilityEvent {
+  model: string;
+  intervals: number;
+  mean_inter_token_ms?: number | null;
+  inter_token_cv?: number | null;       // Coefficient of variation of inter-token latency
+  longest_stall_ms?: number | null;
+  stall_count: number;                  // Gaps longer than stall_threshold_ms
+  stall_threshold_ms: number;
+  timestamp_ms: number;
+}
+
+interface ModelEnergyTotals {
+  total_energy_wh: number;
+  cpu_energy_wh: number;
+  gpu_energy_wh: number;
+  ane_energy_wh: number;
+  energy_lower_bound_wh: number;
+  energy_upper_bound_wh: number;
+}
+
+interface SessionEnergySummaryEvent {
+  run_id: string;
+  models: Record<string, ModelEnergyTotals>;  // Keyed by model label ("A" / "B")
   timestamp_ms: number;
 }
 
@@ -56,6 +137,12 @@ interface CooldownUpdateEvent {
   current_c?: number | null;
   elapsed_s?: number | null;
   timestamp_ms: number;
+  // The same temperatures in the user's preferred unit
+  unit: TemperatureUnit;
+  baseline?: number | null;
+  margin: number;
+  threshold?: number | null;
+  current?: number | null;
 }
 
 interface TelemetryUpdate {
@@ -69,9 +156,13 @@ interface TelemetryUpdate {
   gpu_freq_mhz?: number;
   ram_usage_gb?: number;
   thermal_pressure?: string;
+  swap_usage_gb?: number;
+  compressed_memory_gb?: number;
+  memory_pressure_level?: string;
   ttft_ms?: number;
   current_tps?: number;
   instantaneous_tps?: number;
+  rolling_tps?: number;
   model?: string;
   // Enhanced temperature data
   cpu_temp_avg?: number;
@@ -86,6 +177,10 @@ interface TelemetryUpdate {
   cpu_p_core_utilization?: number[];
   cpu_e_core_utilization?: number[];
   cpu_overall_utilization?: number;
+  inference_cpu_utilization?: number;
+  inference_cpu_share?: number;
+  gpu_utilization?: number;
+  fan_rpm?: number[];  // Per-fan speed; absent on fanless machines
   core_temperatures?: CoreTemperatureData;
   // NEW: Energy consumption fields
   total_energy_wh?: number;
@@ -93,6 +188,11 @@ interface TelemetryUpdate {
   gpu_energy_wh?: number;
   ane_energy_wh?: number;
   energy_rate_wh_per_token?: number;
+  // Rates of change fitted over the last 10 s
+  cpu_temp_rate_c_per_s?: number;
+  gpu_temp_rate_c_per_s?: number;
+  battery_temp_rate_c_per_s?: number;
+  package_power_rate_w_per_s?: number;
 }
 
 interface UseTauriEventListenersOptions {
@@ -231,6 +331,30 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
         setSystemPromptTokenCount(count);
       });
       
+      const unlistenContextSize = await listen<ContextSizeResolvedEvent>("context_size_resolved", (event) => {
+        const { model, requested, n_ctx, trained_context_warning } = event.payload;
+        DEBUG_LOGS && console.log(`[${listenerId}] 📐 CONTEXT SIZE: Model ${model} requested ${requested}, using ${n_ctx}`);
+        useModelStore.getState().updateResolvedContextSize(model as 'A' | 'B', n_ctx);
+        useModelStore.getState().updateContextWarning(model as 'A' | 'B', trained_context_warning ?? null);
+        if (trained_context_warning) {
+          console.warn(`Model ${model}: ${trained_context_warning}`);
+        }
+      });
+
+      const unlistenRunManifest = await listen<RunManifest>("run_manifest", (event) => {
+        DEBUG_LOGS && console.log(`[${listenerId}] 📝 RUN MANIFEST: run ${event.payload.run_id}, ${event.payload.models.length} model(s)`);
+        useModelStore.getState().setRunManifest(event.payload);
+      });
+
+      const unlistenPromptBudget = await listen<PromptBudgetEvent>("prompt_budget", (event) => {
+        const { budgets, warning } = event.payload;
+        DEBUG_LOGS && console.log(`[${listenerId}] 📏 PROMPT BUDGET: ${budgets.map(b => `Model ${b.model} ${b.prompt_tokens}/${b.n_ctx} tokens`).join(', ')}`);
+        useModelStore.getState().setPromptBudget(event.payload);
+        if (warning) {
+          console.warn(`Prompt budget: ${warning}`);
+        }
+      });
+
       const unlistenGenerationTime = await listen<GenerationTimeEvent>("generation_time", (event) => {
         const { generation_time_ms, model } = event.payload;
         DEBUG_LOGS && console.log(`[${listenerId}] ⏱️ GENERATION TIME: Model ${model} took ${generation_time_ms}ms`);
@@ -243,8 +367,10 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
       });
       
       const unlistenPowerSummary = await listen<PowerConsumptionSummaryEvent>("power_consumption_summary", (event) => {
-        const { energy_per_token_wh, model } = event.payload;
+        const { energy_per_token_wh, phase_breakdown, model } = event.payload;
         DEBUG_LOGS && console.log(`[${listenerId}] ⚡ POWER SUMMARY: Model ${model} energy per token: ${energy_per_token_wh}Wh`);
+        // Parallel runs report both models' energy together ("A+B"), not per model
+        if (model !== 'A' && model !== 'B') return;
         
         // Update summary stats with energy per token data
         if (energy_per_token_wh !== undefined) {
@@ -252,6 +378,66 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
             energy_per_token_wh: energy_per_token_wh
           });
         }
+        if (phase_breakdown) {
+          updateSummaryStats(model as 'A' | 'B', {
+            prefill_energy_per_token_wh: phase_breakdown.prefill_energy_per_token_wh ?? undefined,
+            decode_energy_per_token_wh: phase_breakdown.decode_energy_per_token_wh ?? undefined,
+          });
+        }
+      });
+
+      // Prompt processing (prefill) speed, reported before decoding starts
+      const unlistenPrefill = await listen<PrefillMetricsEvent>("prefill_metrics", (event) => {
+        const { model, prompt_tokens, prefill_ms, prefill_tps } = event.payload;
+        DEBUG_LOGS && console.log(`[${listenerId}] 📥 PREFILL: Model ${model} ${prompt_tokens} tokens in ${prefill_ms.toFixed(1)}ms (${prefill_tps} tok/s)`);
+        updateSummaryStats(model as 'A' | 'B', {
+          prefill_tps: prefill_tps ?? undefined,
+          prefill_ms,
+        });
+      });
+
+      // Context window fill while generating, so users see a conversation nearing either model's limit
+      const unlistenProgress = await listen<GenerationProgressEvent>("generation_progress", (event) => {
+        const { model, context_used_tokens, n_ctx, draft_acceptance_rate } = event.payload;
+        const previousPeak = useTelemetryStore.getState().summaryStats[model as 'A' | 'B']?.peak_context_used_tokens ?? 0;
+        updateSummaryStats(model as 'A' | 'B', {
+          context_used_tokens,
+          peak_context_used_tokens: Math.max(previousPeak, context_used_tokens),
+          n_ctx,
+          draft_acceptance_rate: draft_acceptance_rate ?? undefined,
+        });
+      });
+
+      // Inter-token jitter and stalls for a finished generation
+      const unlistenTokenStability = await listen<TokenStabilityEvent>("token_stability", (event) => {
+        const { model, inter_token_cv, longest_stall_ms, stall_count, stall_threshold_ms } = event.payload;
+        DEBUG_LOGS && console.log(`[${listenerId}] 📉 TOKEN STABILITY: Model ${model} CV ${inter_token_cv}, longest stall ${longest_stall_ms}ms, ${stall_count} stalls`);
+        updateSummaryStats(model as 'A' | 'B', {
+          inter_token_cv: inter_token_cv ?? undefined,
+          longest_stall_ms: longest_stall_ms ?? undefined,
+          stall_count,
+          stall_threshold_ms,
+        });
+      });
+
+      // Memory high-water marks during load and generation
+      const unlistenMemoryUsage = await listen<MemoryUsage>("memory_usage", (event) => {
+        const { model, load_peak_gb, load_delta_gb, generation_peak_gb, generation_delta_gb } = event.payload;
+        DEBUG_LOGS && console.log(`[${listenerId}] 🧠 MEMORY: Model ${model} load peak ${load_peak_gb.toFixed(2)}GB, generation peak ${generation_peak_gb.toFixed(2)}GB`);
+        updateSummaryStats(model as 'A' | 'B', {
+          load_memory_delta_gb: load_delta_gb,
+          generation_memory_delta_gb: generation_delta_gb,
+        });
+      });
+
+      // Per-model energy totals for the whole run (both models' totals coexist)
+      const unlistenSessionEnergy = await listen<SessionEnergySummaryEvent>("session_energy_summary", (event) => {
+        DEBUG_LOGS && console.log(`[${listenerId}] ⚡ SESSION ENERGY:`, event.payload.models);
+        Object.entries(event.payload.models).forEach(([model, totals]) => {
+          if (model === 'A' || model === 'B') {
+            updateSummaryStats(model, { total_energy_wh: totals.total_energy_wh });
+          }
+        });
       });
 
       // Cooldown progress listener
@@ -261,20 +447,20 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
         if (payload.state === 'started') {
           setCooldownActive(true);
           setCooldownStatus('started');
-          setCooldownMeta(payload.baseline_c ?? null, payload.threshold_c ?? null, payload.margin_c);
+          setCooldownMeta(payload.baseline ?? null, payload.threshold ?? null, payload.margin, payload.unit);
           // reset points only
           clearCooldownPoints();
           setCooldownActive(true);
           setCooldownStatus('started');
-          setCooldownMeta(payload.baseline_c ?? null, payload.threshold_c ?? null, payload.margin_c);
+          setCooldownMeta(payload.baseline ?? null, payload.threshold ?? null, payload.margin, payload.unit);
         } else if (payload.state === 'progress') {
-          if (payload.current_c !== undefined && payload.current_c !== null) {
-            addCooldownPoint(payload.timestamp_ms, payload.current_c);
+          if (payload.current !== undefined && payload.current !== null) {
+            addCooldownPoint(payload.timestamp_ms, payload.current);
           }
           setCooldownStatus('progress');
         } else if (payload.state === 'complete') {
-          if (payload.current_c !== undefined && payload.current_c !== null) {
-            addCooldownPoint(payload.timestamp_ms, payload.current_c);
+          if (payload.current !== undefined && payload.current !== null) {
+            addCooldownPoint(payload.timestamp_ms, payload.current);
           }
           setCooldownStatus('complete');
           setCooldownActive(false);
@@ -304,8 +490,12 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           cpu_freq: telemetry.cpu_freq_mhz || null,
           gpu_freq: telemetry.gpu_freq_mhz || null,
           ram_usage: telemetry.ram_usage_gb || null,
+          swap_usage: telemetry.swap_usage_gb ?? null,
+          compressed_memory: telemetry.compressed_memory_gb ?? null,
+          memory_pressure: telemetry.memory_pressure_level ?? null,
           tps: telemetry.current_tps || null,
           instantaneous_tps: telemetry.instantaneous_tps || null,
+          rolling_tps: telemetry.rolling_tps ?? null,
           model: telemetry.model || null,
           // Enhanced temperature data
           cpu_temp_avg: telemetry.cpu_temp_avg || null,
@@ -320,6 +510,10 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           cpu_p_core_utilization: telemetry.cpu_p_core_utilization || null,
           cpu_e_core_utilization: telemetry.cpu_e_core_utilization || null,
           cpu_overall_utilization: telemetry.cpu_overall_utilization || null,
+          inference_cpu_utilization: telemetry.inference_cpu_utilization ?? null,
+          inference_cpu_share: telemetry.inference_cpu_share ?? null,
+          gpu_utilization: telemetry.gpu_utilization ?? null,
+          fan_rpm: telemetry.fan_rpm ?? null,
           core_temperatures: telemetry.core_temperatures,
           // NEW: Energy field mappings
           total_energy_wh: telemetry.total_energy_wh || null,
@@ -327,6 +521,10 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           gpu_energy_wh: telemetry.gpu_energy_wh || null,
           ane_energy_wh: telemetry.ane_energy_wh || null,
           energy_rate_wh_per_token: telemetry.energy_rate_wh_per_token || null,
+          cpu_temp_rate_c_per_s: telemetry.cpu_temp_rate_c_per_s ?? null,
+          gpu_temp_rate_c_per_s: telemetry.gpu_temp_rate_c_per_s ?? null,
+          battery_temp_rate_c_per_s: telemetry.battery_temp_rate_c_per_s ?? null,
+          package_power_rate_w_per_s: telemetry.package_power_rate_w_per_s ?? null,
         };
 
         // Add to overlay telemetry system
@@ -343,9 +541,13 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           cpu_freq: telemetry.cpu_freq_mhz || null,
           gpu_freq: telemetry.gpu_freq_mhz || null,
           ram_usage: telemetry.ram_usage_gb || null,
+          swap_usage: telemetry.swap_usage_gb ?? null,
+          compressed_memory: telemetry.compressed_memory_gb ?? null,
+          memory_pressure: telemetry.memory_pressure_level ?? null,
           thermal_pressure: telemetry.thermal_pressure || null,
           tps: telemetry.current_tps || null,
           instantaneous_tps: telemetry.instantaneous_tps || null,
+          rolling_tps: telemetry.rolling_tps ?? null,
           model: telemetry.model || null,
           // Enhanced temperature data
           cpu_temp_avg: telemetry.cpu_temp_avg || null,
@@ -360,6 +562,10 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           cpu_p_core_utilization: telemetry.cpu_p_core_utilization || null,
           cpu_e_core_utilization: telemetry.cpu_e_core_utilization || null,
           cpu_overall_utilization: telemetry.cpu_overall_utilization || null,
+          inference_cpu_utilization: telemetry.inference_cpu_utilization ?? null,
+          inference_cpu_share: telemetry.inference_cpu_share ?? null,
+          gpu_utilization: telemetry.gpu_utilization ?? null,
+          fan_rpm: telemetry.fan_rpm ?? null,
           core_temperatures: telemetry.core_temperatures,
           // NEW: Energy field mappings
           total_energy_wh: telemetry.total_energy_wh || null,
@@ -367,6 +573,10 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
           gpu_energy_wh: telemetry.gpu_energy_wh || null,
           ane_energy_wh: telemetry.ane_energy_wh || null,
           energy_rate_wh_per_token: telemetry.energy_rate_wh_per_token || null,
+          cpu_temp_rate_c_per_s: telemetry.cpu_temp_rate_c_per_s ?? null,
+          gpu_temp_rate_c_per_s: telemetry.gpu_temp_rate_c_per_s ?? null,
+          battery_temp_rate_c_per_s: telemetry.battery_temp_rate_c_per_s ?? null,
+          package_power_rate_w_per_s: telemetry.package_power_rate_w_per_s ?? null,
         };
 
         DEBUG_LOGS && console.log(`[${listenerId}] 📈 FRONTEND: Creating TelemetryData object:`, {
@@ -432,7 +642,15 @@ export const useTauriEventListeners = (options: UseTauriEventListenersOptions) =
         unlistenOutputTokens();
         unlistenSystemPromptTokens();
         unlistenGenerationTime();
+        unlistenContextSize();
+        unlistenRunManifest();
+        unlistenPromptBudget();
         unlistenPowerSummary();
+        unlistenTokenStability();
+        unlistenMemoryUsage();
+        unlistenPrefill();
+        unlistenProgress();
+        unlistenSessionEnergy();
         unlistenCooldown();
         unlistenUserInputTokens();
         unlistenStopped();
@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ApiServerStatus {
+  running: boolean;
+  /** OpenAI-compatible base URL, e.g. "http://127.0.0.1:8080/v1" */
+  base_url: string | null;
+  /** Send as "Authorization: Bearer <token>"; regenerated each time the app starts */
+  token: string | null;
+}
+
+/**
+ * Local OpenAI-compatible server (POST /v1/chat/completions, GET /v1/models) for
+ * driving instrumented runs from scripts and evaluation harnesses
+ */
+export class ApiServer {
+  /** Listen on 127.0.0.1:port; 0 picks a free port */
+  static async start(port: number): Promise<ApiServerStatus> {
+    return await invoke('start_api_server', { port });
+  }
+
+  static async stop(): Promise<ApiServerStatus> {
+    return await invoke('stop_api_server');
+  }
+
+  static async status(): Promise<ApiServerStatus> {
+    return await invoke('get_api_server_status');
+  }
+}
@@ -0,0 +1,128 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Which model of a "Both" suite runs first in each repetition: 'fixed' is A then B as one
+ * turn; 'alternating' and 'randomized' run each model as its own turn, so neither model
+ * always runs second on a warmer SoC ('randomized' runs AB and BA once per pair of repetitions)
+ */
+export type ModelOrder = 'fixed' | 'alternating' | 'randomized';
+
+export interface BenchmarkSuiteRequest {
+  name?: string;
+  prompts: string[];
+  repetitions: number;
+  /** Pause between consecutive runs, in seconds */
+  cooldown_secs?: number;
+  /** Before each later run, wait for the CPU to cool back to its pre-suite temperature */
+  wait_for_cpu_baseline?: boolean;
+  /** Allowed margin above the baseline, in °C (default 2) */
+  cooldown_margin_c?: number;
+  /** Hold runs while macOS reports serious or critical thermal pressure */
+  defer_on_thermal_pressure?: boolean;
+  /** "Both" suites only (default 'fixed') */
+  model_order?: ModelOrder;
+}
+
+export interface BenchmarkRun {
+  prompt_index: number;
+  repetition: number;
+  model: string;
+  /** 0 when the model ran first in its repetition, 1 when second */
+  position: number;
+  ttft_ms: number | null;
+  avg_tps: number | null;
+  total_energy_wh: number | null;
+  energy_per_token_wh: number | null;
+  output_tokens: number;
+  finish_reason: string;
+}
+
+export interface BenchmarkStats {
+  count: number;
+  mean: number;
+  p50: number;
+  p95: number;
+  stddev: number;
+  /** 95% confidence interval of the mean; null for a single run */
+  ci95_low: number | null;
+  ci95_high: number | null;
+}
+
+export interface BenchmarkModelStats {
+  model: string;
+  runs: number;
+  /** Keyed by ttft_ms, avg_tps, total_energy_wh, energy_per_token_wh */
+  metrics: Record<string, BenchmarkStats>;
+}
+
+/** One metric compared between Model A and B over repeated runs; difference is B - A */
+export interface MetricDifference {
+  metric: string;
+  runs_a: number;
+  runs_b: number;
+  mean_a: number;
+  mean_b: number;
+  difference: number;
+  /** Relative to A; null when A is 0 */
+  difference_percent: number | null;
+  /** Bootstrap 95% interval of the difference */
+  ci95_low: number;
+  ci95_high: number;
+  /** Two-sided permutation test; null when either model has fewer than 4 runs */
+  p_value: number | null;
+  /** p_value < 0.05; false when untested */
+  significant: boolean;
+}
+
+export interface BenchmarkReport {
+  uuid: string;
+  name: string;
+  created_at: number;
+  target: string;
+  prompts: string[];
+  repetitions: number;
+  cooldown_secs: number | null;
+  wait_for_cpu_baseline: boolean;
+  defer_on_thermal_pressure: boolean;
+  model_order: ModelOrder;
+  /** Total time runs were held back by cooldown or thermal pressure waits */
+  thermal_wait_secs: number;
+  /** False when the suite was stopped before its last run */
+  completed: boolean;
+  runs: BenchmarkRun[];
+  models: BenchmarkModelStats[];
+  /** B vs A per metric, for "Both" suites where each model ran at least twice */
+  differences: MetricDifference[];
+}
+
+/** Payload of the thermal_pressure_wait event, emitted while a run is deferred */
+export interface ThermalPressureWait {
+  state: 'waiting' | 'cleared' | 'timeout' | 'canceled';
+  pressure: string | null;
+  elapsed_s: number;
+  timestamp_ms: number;
+}
+
+/** Payload of the benchmark_progress event */
+export interface BenchmarkProgress {
+  suite_id: string;
+  completed: number;
+  total: number;
+}
+
+/**
+ * Batch benchmark runs against Model A and/or B
+ */
+export class BenchmarkSuite {
+  /**
+   * Run the suite with a generation config (target, models, telemetry options) and
+   * save the report; progress is reported through benchmark_progress events
+   */
+  static async run(request: BenchmarkSuiteRequest, config: Record<string, unknown>): Promise<BenchmarkReport> {
+    return await invoke('run_benchmark_suite', { request, config });
+  }
+
+  static async listReports(): Promise<BenchmarkReport[]> {
+    return await invoke('list_benchmark_reports');
+  }
+}
@@ -0,0 +1,25 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface EventClassOptions {
+  enabled?: boolean;
+  /** Per event name; events over the rate are dropped (final tokens always arrive) */
+  max_per_second?: number | null;
+}
+
+/** Unset classes are delivered in full; run lifecycle events are never filtered */
+export interface EventStreamOptions {
+  tokens?: EventClassOptions;
+  telemetry?: EventClassOptions;
+  debug?: EventClassOptions;
+  phase_markers?: EventClassOptions;
+}
+
+/**
+ * Event subscriptions of one stream: the window label ("main") for this UI, or an
+ * event_sink spec such as "ws:127.0.0.1:9100"
+ */
+export class EventStream {
+  static async configure(sessionId: string, options: EventStreamOptions): Promise<EventStreamOptions> {
+    return await invoke('configure_event_stream', { sessionId, options });
+  }
+}
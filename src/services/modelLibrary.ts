@@ -0,0 +1,85 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ModelFile {
+  path: string;
+  file_name: string;
+  size_bytes: number;
+  modified_at: number;
+}
+
+export interface ModelLibrarySettings {
+  directories: string[];
+  /** Rescan automatically when GGUFs are added or removed */
+  watch: boolean;
+}
+
+/** GGUF header details returned by inspect_model; no weights are loaded */
+export interface ModelInfo {
+  path: string;
+  file_size_bytes: number;
+  gguf_version: number;
+  name: string | null;
+  architecture: string | null;
+  /** general.size_label, e.g. "8B" */
+  size_label: string | null;
+  parameter_count: number;
+  /** e.g. "Q4_K_M" */
+  quantization: string | null;
+  bits_per_weight: number | null;
+  /** Trained context length */
+  context_length: number | null;
+  vocab_size: number | null;
+  chat_template: string | null;
+  tensor_count: number;
+}
+
+/** Payload of the model_library_changed event */
+export interface ModelLibraryChange {
+  added: ModelFile[];
+  removed: string[];
+  changed: ModelFile[];
+}
+
+/**
+ * GGUF files found under the configured model directories
+ */
+export class ModelLibrary {
+  static async getModels(): Promise<ModelFile[]> {
+    return await invoke('get_model_library');
+  }
+
+  /**
+   * Rescan the model directories now
+   */
+  static async rescan(): Promise<ModelLibraryChange> {
+    return await invoke('rescan_model_library');
+  }
+
+  static async getSettings(): Promise<ModelLibrarySettings> {
+    return await invoke('get_model_library_settings');
+  }
+
+  /**
+   * Store the directories and watch flag; rescans immediately
+   * @returns Settings as stored (directories trimmed and de-duplicated)
+   */
+  static async setSettings(settings: ModelLibrarySettings): Promise<ModelLibrarySettings> {
+    return await invoke('set_model_library_settings', { settings });
+  }
+
+  /**
+   * Move a GGUF to the Trash and drop it from the library. Rejected with
+   * `model_in_use` while the model is loaded or used by a running generation.
+   */
+  static async deleteModelFile(path: string): Promise<ModelLibraryChange> {
+    return await invoke('delete_model_file', { path });
+  }
+
+  /**
+   * Read a GGUF's architecture, parameter count, quantization, context length and
+   * chat template from its header
+   */
+  static async inspectModel(path: string): Promise<ModelInfo> {
+    return await invoke('inspect_model', { path });
+  }
+}
@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BenchmarkSuiteRequest } from './benchmark';
+
+export type NightBatchState = 'scheduled' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+/** Also the payload of the night_batch_summary event, emitted when a batch finishes */
+export interface NightBatchStatus {
+  batch_id: string;
+  name: string | null;
+  /** Local "HH:MM" */
+  start_time: string;
+  scheduled_for_ms: number;
+  state: NightBatchState;
+  started_at_ms: number | null;
+  finished_at_ms: number | null;
+  /** Saved benchmark report, see BenchmarkSuite.listReports */
+  report_uuid: string | null;
+  summary: string | null;
+  error: string | null;
+}
+
+/**
+ * Benchmark suites started unattended at a set time of night, keeping the Mac awake
+ * while they run
+ */
+export class NightBatch {
+  /** Schedule the suite for the next local startTime ("HH:MM"), replacing any batch not yet started */
+  static async schedule(request: BenchmarkSuiteRequest, config: Record<string, unknown>, startTime: string): Promise<NightBatchStatus> {
+    return await invoke('schedule_night_batch', { request, config, startTime });
+  }
+
+  static async get(): Promise<NightBatchStatus | null> {
+    return await invoke('get_night_batch');
+  }
+
+  static async cancel(): Promise<NightBatchStatus | null> {
+    return await invoke('cancel_night_batch');
+  }
+}
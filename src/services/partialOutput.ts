@@ -0,0 +1,20 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Text a model has generated so far in a run */
+export interface PartialOutput {
+  run_id: string;
+  model: string;       // "A" | "B"
+  text: string;
+  finished: boolean;   // false while the run is still generating
+}
+
+/**
+ * Output of the run in progress, e.g. to recover text after the window lost its token
+ * events. Only the latest run is kept: once another run starts, earlier run ids are not found.
+ */
+export class PartialOutputs {
+  /** `runId` is the job's JobStatus.run_id (or the run_id of its GenerationTurnResult) */
+  static async get(runId: string, model: 'A' | 'B'): Promise<PartialOutput> {
+    return await invoke('get_partial_output', { runId, model });
+  }
+}
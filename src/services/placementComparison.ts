@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** One run of the model with a fixed CPU or GPU placement */
+export interface PlacementMeasurement {
+  placement: 'cpu' | 'gpu';
+  n_gpu_layers: number;
+  run_id: string;
+  model_load_ms: number;
+  ttft_ms: number | null;
+  prefill_tps: number | null;
+  avg_tps: number | null;
+  output_tokens: number;
+  /** Only when telemetry was enabled */
+  total_energy_wh: number | null;
+  energy_per_token_wh: number | null;
+  /** Peaks over the run, polled once a second */
+  max_cpu_temp_c: number | null;
+  max_gpu_temp_c: number | null;
+  finish_reason: string;
+}
+
+export interface PlacementComparisonReport {
+  model: string;
+  model_path: string;
+  cpu: PlacementMeasurement | null;
+  gpu: PlacementMeasurement | null;
+  /** GPU decode TPS / CPU decode TPS */
+  gpu_speedup: number | null;
+  /** GPU / CPU energy per token; below 1 means the GPU is more efficient */
+  gpu_energy_per_token_ratio: number | null;
+  /** False when a run was stopped */
+  completed: boolean;
+}
+
+/**
+ * Fully-CPU vs fully-GPU-offloaded runs of one model
+ */
+export class PlacementComparison {
+  /**
+   * Run the targeted model (config.target 'A' or 'B') on the CPU, then on the GPU, with
+   * the same prompt; cooldownSecs pauses between the runs, and the config's
+   * wait_for_cpu_baseline_between_models also waits for the CPU to cool down
+   */
+  static async run(config: Record<string, unknown>, cooldownSecs?: number): Promise<PlacementComparisonReport> {
+    return await invoke('run_placement_comparison', { config, cooldownSecs });
+  }
+}
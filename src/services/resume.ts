@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Seed and tokens of a generation, enough to replay and continue it */
+export interface ResumeCheckpoint {
+  model: string;                 // "A" | "B"
+  model_path: string;
+  seed: number | null;           // null for output streams written before seeds were recorded
+  tokens: number[];
+  text: string;
+  finish_reason: string | null;  // null when the run never finished (crash)
+}
+
+/**
+ * Resuming interrupted generations. Pass a checkpoint as the turn config's `resume`, with the
+ * chat history the interrupted turn started from; the recorded tokens are replayed with the
+ * same seed and generation continues after them. A result's resume_diverged_at reports where
+ * the sampler stopped reproducing the recording (different build, backend or context settings).
+ */
+export class GenerationResume {
+  /** The model's most recent generation in this app session */
+  static async latest(model: 'A' | 'B'): Promise<ResumeCheckpoint> {
+    return await invoke('get_resume_checkpoint', { model });
+  }
+
+  /** Rebuild a checkpoint from an output stream JSONL file, e.g. after a crash */
+  static async fromOutputStream(path: string): Promise<ResumeCheckpoint> {
+    return await invoke('load_resume_checkpoint', { path });
+  }
+}
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import type { EffectiveContextParams, TelemetryDataPoint, TemperatureUnit } from '../types/telemetry';
 
 export interface SavedSession {
   id?: number;
@@ -11,6 +12,241 @@ export interface SavedSession {
   updated_at: number;
 }
 
+export interface ModelFingerprint {
+  path: string;
+  size_bytes: number;
+  fingerprint: string;
+}
+
+// Per-model aggregates of a saved session's telemetry samples, computed in SQLite
+export interface ModelTelemetryStats {
+  model: string | null;
+  samples: number;
+  duration_ms: number | null;
+  max_cpu_temp_c: number | null;
+  max_gpu_temp_c: number | null;
+  avg_package_power_w: number | null;  // CPU + GPU + ANE
+  peak_package_power_w: number | null; // Downsampled samples add each rail's maximum: an upper bound
+  avg_tps: number | null;
+  total_energy_wh: number | null;
+}
+
+export type ModelFileStatus = 'unchanged' | 'changed' | 'missing' | 'not_recorded';
+
+export interface SessionReproducibilityReport {
+  uuid: string;
+  comparable: boolean;
+  models: Array<{
+    slot: 'model_a' | 'model_b';
+    path: string;
+    status: ModelFileStatus;
+    recorded: ModelFingerprint | null;
+    current: ModelFingerprint | null;
+  }>;
+}
+
+// Run provenance captured by the backend at run start (schema apples2oranges.run-manifest/v1)
+export interface RunManifest {
+  schema: string;
+  run_id: string;
+  app_version: string;
+  created_at_ms: number;
+  environment: {
+    hardware: {
+      chip: string;
+      total_cores: number;
+      p_cores: number;
+      e_cores: number;
+      memory_gb: number | null;
+      os: string;
+    };
+    os_version: string | null;
+    power_source: 'ac' | 'battery' | 'ups' | null;
+    low_power_mode: boolean | null;
+    power_adapter?: {
+      watts: number | null;
+      name: string | null;
+      manufacturer: string | null;
+      model: string | null;
+    } | null;
+    env_vars?: Record<string, string>;  // GGML_*, OMP_NUM_THREADS, ... as set for the app
+    build?: Record<string, unknown> | null;  // llama.cpp binding versions, backends, compile features
+  };
+  config: Record<string, unknown>;
+  models: Array<{
+    label: 'A' | 'B';
+    config: Record<string, unknown>;
+    fingerprint: ModelFingerprint | null;
+    effective_context?: EffectiveContextParams | null;  // Filled in once the model has run
+  }>;
+  context_warnings?: string[];  // Fallbacks and A/B parameter mismatches
+}
+
+export interface SessionVersionInfo {
+  uuid: string;
+  name: string;
+  app_version: string | null;
+  schema_version: number;
+  telemetry_schema_version: number;
+}
+
+export interface CompatibilityReport {
+  app_version: string;
+  previous_app_version: string | null;
+  session_schema_version: number;
+  telemetry_schema_version: number;
+  total_sessions: number;
+  needs_migration: SessionVersionInfo[];
+  newer_than_app: SessionVersionInfo[];
+}
+
+export interface MigrationSummary {
+  migrated: string[];
+  failed: Array<{ uuid: string; error: string }>;
+}
+
+// Result of a bulk session operation; unknown UUIDs are listed in `missing`
+export interface BulkSessionResult {
+  affected: string[];
+  missing: string[];
+}
+
+/** Session list entry returned by searchSessions */
+export interface SessionSearchResult {
+  uuid: string;
+  name: string;
+  created_at: number;
+  original_size: number | null;
+  tags: string[];
+}
+
+export interface Experiment {
+  uuid: string;
+  name: string;
+  description: string | null;
+  created_at: number;
+  session_count: number;
+}
+
+export interface MetricSummary {
+  count: number;
+  mean: number;
+  min: number;
+  max: number;
+}
+
+export interface ExperimentModelStats {
+  model: string;
+  sessions: number;
+  metrics: Record<string, MetricSummary>;
+}
+
+export interface ExperimentStats {
+  experiment: Experiment;
+  models: ExperimentModelStats[];
+}
+
+/** Formatting of CSV and HTML exports; stored data is unaffected */
+export interface ReportLocale {
+  decimal_separator: 'point' | 'comma'; // Comma also switches the CSV delimiter to ';'
+  date_format: 'iso' | 'day_month_year' | 'month_day_year';
+  local_time: boolean; // false: timestamps in UTC
+}
+
+export interface TelemetryRetentionPolicy {
+  fields: string[] | null; // null keeps every field; timestamp and model are always kept
+  max_samples?: number | null; // null keeps every sample; beyond it the oldest are downsampled (min 100)
+}
+
+/** Stored as session_data.telemetry_pruning when the policy removed fields */
+export interface TelemetryPruneReport {
+  dropped_fields: string[];
+  samples: number;
+  bytes_before: number;
+  bytes_after: number;
+}
+
+/** Stored as session_data.telemetry_downsampling when the sample cap merged samples */
+export interface TelemetryDownsampleReport {
+  max_samples: number;
+  samples_before: number;
+  samples_after: number;
+}
+
+/** Inclusive creation-time bounds in Unix seconds; omit a side to leave it open */
+export interface HistoryRange {
+  from?: number | null;
+  to?: number | null;
+}
+
+export interface MetricHistoryPoint {
+  session_uuid: string;
+  session_name: string;
+  created_at: number;
+  model: string;
+  model_path: string | null;
+  value: number;
+  /** Value relative to the recording machine's hardware baseline (1.0 = reference), when known */
+  normalized_value: number | null;
+}
+
+/** One metric of one model in both sessions; delta is B - A, temperatures are °C */
+export interface MetricDelta {
+  metric: string;
+  session_a: number | null;
+  session_b: number | null;
+  delta: number | null;
+  delta_percent: number | null;
+}
+
+/** Both sessions' samples of a model, averaged per second since its first sample */
+export interface AlignedSample {
+  elapsed_s: number;
+  tps_a: number | null;
+  tps_b: number | null;
+  power_a: number | null;
+  power_b: number | null;
+}
+
+export interface SessionComparison {
+  session_a: { uuid: string; name: string; created_at: number };
+  session_b: { uuid: string; name: string; created_at: number };
+  models: { model: string; metrics: MetricDelta[]; aligned: AlignedSample[] }[];
+}
+
+/** Idle power and temperatures; runs with subtract_idle_baseline report energy above it */
+export interface IdleBaseline {
+  captured_at_ms: number;
+  duration_s: number;
+  sampling_hz: number;
+  samples: number;
+  power: { cpu_w: number; gpu_w: number; ane_w: number };
+  avg_cpu_temp_c: number | null;
+  avg_gpu_temp_c: number | null;
+}
+
+/** Result of the fixed hardware micro-benchmark; stamped into sessions saved afterwards */
+export interface HardwareBaseline {
+  benchmark_version: number;
+  measured_at_ms: number;
+  hardware: {
+    chip: string;
+    total_cores: number;
+    p_cores: number;
+    e_cores: number;
+    memory_gb: number | null;
+    os: string;
+  };
+  reference_model: { path: string; size_bytes: number; fingerprint: string };
+  prompt_tokens: number;
+  output_tokens: number;
+  ttft_ms: number | null;
+  prefill_tps: number | null;
+  decode_tps: number | null;
+  avg_package_power_w: number | null;
+  energy_per_token_wh: number | null;
+}
+
 export interface CreateSessionRequest {
   name: string;
   session_data: any;
@@ -50,6 +286,43 @@ export class SessionPersistence {
     return await invoke('load_session', { uuid });
   }
 
+  /**
+   * Telemetry samples of a saved session (session_data only holds a marker for them)
+   * @param uuid Session UUID
+   * @returns Samples in recorded order
+   */
+  static async getSessionTelemetry(uuid: string): Promise<TelemetryDataPoint[]> {
+    return await invoke('get_session_telemetry', { uuid });
+  }
+
+  /**
+   * Flush samples of the chat being recorded to the database
+   * @param recordingId Id of the current recording
+   * @param samples Samples recorded since the last flush
+   * @returns How many samples the recording now holds
+   */
+  static async appendTelemetrySamples(recordingId: string, samples: TelemetryDataPoint[]): Promise<number> {
+    return await invoke('append_telemetry_samples', { recordingId, samples });
+  }
+
+  /**
+   * Start a recording from a loaded session's samples, so saving again keeps them
+   * @param recordingId Id of the new recording
+   * @param uuid Session UUID
+   * @returns How many samples were copied
+   */
+  static async resumeTelemetryRecording(recordingId: string, uuid: string): Promise<number> {
+    return await invoke('resume_telemetry_recording', { recordingId, uuid });
+  }
+
+  /**
+   * Drop the samples of a recording that will not be saved
+   * @param recordingId Id of the recording
+   */
+  static async discardTelemetryRecording(recordingId: string): Promise<number> {
+    return await invoke('discard_telemetry_recording', { recordingId });
+  }
+
   /**
    * Delete session by UUID
    * @param uuid Session UUID
@@ -59,6 +332,273 @@ export class SessionPersistence {
     return await invoke('delete_saved_session', { uuid });
   }
 
+  /**
+   * Delete several sessions in one transaction
+   * @param uuids Session UUIDs
+   * @returns Deleted UUIDs and those that were not found
+   */
+  static async deleteSessions(uuids: string[]): Promise<BulkSessionResult> {
+    return await invoke('delete_sessions', { uuids });
+  }
+
+  /**
+   * Add a tag to several sessions in one transaction
+   * @param uuids Session UUIDs
+   * @param tag Tag to add (sessions that already have it are unchanged)
+   * @returns Tagged UUIDs and those that were not found
+   */
+  static async tagSessions(uuids: string[], tag: string): Promise<BulkSessionResult> {
+    return await invoke('tag_sessions', { uuids, tag });
+  }
+
+  /**
+   * Replace a session's tags
+   * @param uuid Session UUID
+   * @param tags New tags (blank and duplicate tags are dropped)
+   * @returns The stored tags, sorted
+   */
+  static async setSessionTags(uuid: string, tags: string[]): Promise<string[]> {
+    return await invoke('set_session_tags', { uuid, tags });
+  }
+
+  /**
+   * Search sessions by name, prompts and model names
+   * @param query Terms that must all match, as prefixes; empty lists every session
+   * @param tags Tags the sessions must all carry
+   * @returns Best matches first (most recently updated first without a query)
+   */
+  static async searchSessions(query: string, tags: string[] = []): Promise<SessionSearchResult[]> {
+    return await invoke('search_sessions', { query, tags });
+  }
+
+  /**
+   * Export several sessions, with their tags, to a single JSON file
+   * @param uuids Session UUIDs
+   * @param path Destination file path
+   * @returns Exported UUIDs and those that were not found
+   */
+  static async exportSessions(uuids: string[], path: string): Promise<BulkSessionResult> {
+    return await invoke('export_sessions', { uuids, path });
+  }
+
+  /**
+   * Export one session (tags and compressed telemetry included) to a portable .a2o file;
+   * resolves to the written path
+   */
+  static async exportSession(uuid: string, path: string): Promise<string> {
+    return await invoke('export_session', { uuid, path });
+  }
+
+  /**
+   * Import a session from a .a2o file; it gets a new uuid if already present
+   */
+  static async importSession(path: string): Promise<SavedSession> {
+    return await invoke('import_session', { path });
+  }
+
+  /**
+   * Get the telemetry field whitelist applied when sessions are saved
+   */
+  static async getTelemetryRetentionPolicy(): Promise<TelemetryRetentionPolicy> {
+    return await invoke('get_telemetry_retention_policy');
+  }
+
+  /**
+   * Set the telemetry field whitelist; only affects sessions saved afterwards. Save it via the
+   * telemetry store's saveTelemetryRetentionPolicy so the run in progress follows the new cap.
+   * @param policy Fields to keep, or null to keep everything
+   * @returns The normalized policy that was stored
+   */
+  static async setTelemetryRetentionPolicy(policy: TelemetryRetentionPolicy): Promise<TelemetryRetentionPolicy> {
+    return await invoke('set_telemetry_retention_policy', { policy });
+  }
+
+  /**
+   * Get the unit temperatures are shown in by summaries, reports, alerts and exports
+   */
+  static async getTemperatureUnit(): Promise<TemperatureUnit> {
+    return await invoke('get_temperature_unit');
+  }
+
+  /**
+   * Set the temperature display unit; recorded telemetry stays in Celsius
+   * @returns The unit that was stored
+   */
+  static async setTemperatureUnit(unit: TemperatureUnit): Promise<TemperatureUnit> {
+    return await invoke('set_temperature_unit', { unit });
+  }
+
+  /**
+   * Follow one summary metric across saved sessions over time, oldest first
+   * @param metric summary_stats field, e.g. "energy_per_token_wh" or "avg_tps"
+   * @param modelFilter Only models whose path contains this text (case-insensitive)
+   * @param range Creation-time bounds
+   */
+  static async getMetricHistory(metric: string, modelFilter?: string, range?: HistoryRange): Promise<MetricHistoryPoint[]> {
+    return await invoke('get_metric_history', { metric, modelFilter: modelFilter ?? null, range: range ?? null });
+  }
+
+  /**
+   * Compare two saved sessions per model: metric deltas (B - A) and time-aligned streams
+   */
+  static async compareSessions(uuidA: string, uuidB: string): Promise<SessionComparison> {
+    return await invoke('compare_sessions', { uuidA, uuidB });
+  }
+
+  /**
+   * Benchmark this machine with a small reference model and store the result
+   * @param modelPath Reference model; use the same one on every machine being compared
+   */
+  static async measureHardwareBaseline(modelPath: string): Promise<HardwareBaseline> {
+    return await invoke('measure_hardware_baseline', { modelPath });
+  }
+
+  /**
+   * The stored hardware baseline, or null if this machine has not been benchmarked yet
+   */
+  static async getHardwareBaseline(): Promise<HardwareBaseline | null> {
+    return await invoke('get_hardware_baseline');
+  }
+
+  /**
+   * Sample idle power with inference stopped and store it as the baseline; keep the
+   * machine otherwise quiet meanwhile (progress arrives as idle_baseline_sample events)
+   * @param durationS Capture length in seconds (default 30, 5-300)
+   */
+  static async captureBaseline(durationS?: number): Promise<IdleBaseline> {
+    return await invoke('capture_baseline', { durationS: durationS ?? null });
+  }
+
+  /**
+   * The stored idle baseline, or null if none has been captured
+   */
+  static async getIdleBaseline(): Promise<IdleBaseline | null> {
+    return await invoke('get_idle_baseline');
+  }
+
+  /**
+   * Create a named group for related sessions
+   * @param name Experiment name
+   * @param description Optional free-form description
+   */
+  static async createExperiment(name: string, description?: string): Promise<Experiment> {
+    return await invoke('create_experiment', { name, description: description ?? null });
+  }
+
+  /**
+   * List experiments, newest first, with their session counts
+   */
+  static async listExperiments(): Promise<Experiment[]> {
+    return await invoke('list_experiments');
+  }
+
+  /**
+   * Add sessions to an experiment in one transaction
+   * @param experimentId Experiment UUID
+   * @param uuids Session UUIDs
+   * @returns Assigned UUIDs and those that were not found
+   */
+  static async assignSessionsToExperiment(experimentId: string, uuids: string[]): Promise<BulkSessionResult> {
+    return await invoke('assign_sessions_to_experiment', { experimentId, uuids });
+  }
+
+  /**
+   * Per-model mean/min/max of summary metrics across an experiment's sessions
+   * @param experimentId Experiment UUID
+   */
+  static async getExperimentStats(experimentId: string): Promise<ExperimentStats> {
+    return await invoke('get_experiment_stats', { experimentId });
+  }
+
+  /**
+   * Write a read-only, self-contained web page (index.html) for a session
+   * @param uuid Session UUID
+   * @param dir Destination directory (created if missing)
+   * @returns Path of the written page
+   */
+  static async exportSessionWebpage(uuid: string, dir: string): Promise<string> {
+    return await invoke('export_session_webpage', { uuid, dir });
+  }
+
+  /**
+   * Write a session's telemetry as CSV, formatted for the report locale
+   * @param uuid Session UUID
+   * @param path Destination file
+   * @returns Number of samples written
+   */
+  static async exportSessionCsv(uuid: string, path: string): Promise<number> {
+    return await invoke('export_session_csv', { uuid, path });
+  }
+
+  /**
+   * Write every field of a session's telemetry samples, unformatted, for pandas or Excel
+   * @param uuid Session UUID
+   * @param format 'csv' (one column per field) or 'jsonl' (one sample per line)
+   * @param path Destination file
+   * @returns Number of samples written
+   */
+  static async exportTelemetry(uuid: string, format: 'csv' | 'jsonl', path: string): Promise<number> {
+    return await invoke('export_telemetry', { uuid, format, path });
+  }
+
+  /**
+   * Get the decimal separator and timestamp layout used by exports
+   */
+  static async getReportLocale(): Promise<ReportLocale> {
+    return await invoke('get_report_locale');
+  }
+
+  /**
+   * Set the decimal separator and timestamp layout used by exports
+   * @returns The locale that was stored
+   */
+  static async setReportLocale(locale: ReportLocale): Promise<ReportLocale> {
+    return await invoke('set_report_locale', { locale });
+  }
+
+  /**
+   * Check that the model files a saved session used are still present and unchanged
+   * @param uuid Session UUID
+   * @returns Per-model status and whether a rerun would be comparable
+   */
+  static async checkReproducibility(uuid: string): Promise<SessionReproducibilityReport> {
+    return await invoke('check_session_reproducibility', { uuid });
+  }
+
+  /**
+   * Peak temperatures, average power and TPS per model, without loading the telemetry
+   * @param uuid Session UUID
+   * @returns One entry per model that recorded samples
+   */
+  static async getTelemetryStats(uuid: string): Promise<ModelTelemetryStats[]> {
+    return await invoke('get_telemetry_stats', { uuid });
+  }
+
+  /**
+   * Get the run manifest stored with a saved session
+   * @param sessionId Session UUID
+   * @returns Manifest, or null for sessions saved before manifests were recorded
+   */
+  static async getRunManifest(sessionId: string): Promise<RunManifest | null> {
+    return await invoke('get_run_manifest', { sessionId });
+  }
+
+  /**
+   * Compare saved sessions against the schemas this app version writes
+   * @returns Sessions that need migrating, and any saved by a newer app
+   */
+  static async checkDataCompatibility(): Promise<CompatibilityReport> {
+    return await invoke('check_data_compatibility');
+  }
+
+  /**
+   * Upgrade sessions saved by older app versions in place
+   * @returns UUIDs of migrated sessions and per-session failures
+   */
+  static async migrateSessions(): Promise<MigrationSummary> {
+    return await invoke('migrate_sessions');
+  }
+
   /**
    * Get lightweight session list for UI display
    * @returns Array of session metadata (uuid, name, created_at, size)
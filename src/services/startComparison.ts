@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface StartMeasurement {
+  model_load_ms: number;
+  model_reused: boolean;
+  ttft_ms: number | null;
+  /** Load plus TTFT: what the user waits for */
+  time_to_first_token_ms: number | null;
+  prefill_ms: number;
+  avg_tps: number | null;
+  load_delta_gb: number;
+  finish_reason: string;
+}
+
+/** Cold and warm runs of one model; deltas are cold minus warm */
+export interface StartComparison {
+  model: string;
+  model_path: string;
+  /** False when the file could not be dropped from the OS cache */
+  page_cache_evicted: boolean;
+  cold: StartMeasurement;
+  warm: StartMeasurement;
+  model_load_delta_ms: number;
+  ttft_delta_ms: number | null;
+  time_to_first_token_delta_ms: number | null;
+  avg_tps_delta: number | null;
+}
+
+export interface StartComparisonReport {
+  run_ids: string[];
+  models: StartComparison[];
+  /** False when a run was stopped */
+  completed: boolean;
+}
+
+/**
+ * Cold-start vs warm-start measurements for Model A and/or B
+ */
+export class StartComparisonRun {
+  /**
+   * Run each targeted model once from a fresh load with the page cache cleared, then
+   * again immediately with the model resident
+   */
+  static async run(config: Record<string, unknown>): Promise<StartComparisonReport> {
+    return await invoke('run_start_comparison', { config });
+  }
+}
@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** gpu is rejected until Metal compute is available */
+export type SyntheticLoadProfile = 'cpu_single' | 'cpu_all' | 'gpu';
+
+export interface SyntheticLoadReport {
+  profile: SyntheticLoadProfile;
+  /** "accelerate" (cblas_sgemm) or "rust" */
+  backend: string;
+  threads: number;
+  duration_s: number;
+  /** Matrix multiplies completed over all threads */
+  iterations: number;
+  gflops: number;
+  /** null when telemetry was not sampled */
+  sampling_hz: number | null;
+  samples: number;
+  avg_cpu_power_w: number | null;
+  avg_gpu_power_w: number | null;
+  avg_package_power_w: number | null;
+  max_cpu_temp_c: number | null;
+  avg_cpu_utilization: number | null;
+}
+
+/**
+ * Known workloads run without a model, to validate telemetry and compare sensors
+ */
+export class SyntheticLoad {
+  /**
+   * Run the load for `duration` seconds (1-600); samples arrive as telemetry_update
+   * events. A samplingHz of 0 runs the load without telemetry.
+   */
+  static async run(profile: SyntheticLoadProfile, duration: number, samplingHz?: number): Promise<SyntheticLoadReport> {
+    return await invoke('run_synthetic_load', { profile, duration, samplingHz });
+  }
+}
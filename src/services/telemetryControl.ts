@@ -0,0 +1,20 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface TelemetryState {
+  paused: boolean;
+  /** A generation's monitor was running and received the change */
+  monitoring: boolean;
+}
+
+/**
+ * Pausing hardware sampling around setup phases (model loading, prompt editing)
+ */
+export class TelemetryControl {
+  /**
+   * Pause or resume sampling; the state carries over to later runs, and energy is not
+   * integrated across a pause
+   */
+  static async setPaused(paused: boolean): Promise<TelemetryState> {
+    return await invoke('set_telemetry_state', { paused });
+  }
+}
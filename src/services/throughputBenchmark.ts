@@ -0,0 +1,71 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ThroughputBenchmarkRequest {
+  prompts: string[];
+  system_prompt?: string;
+  /** Streams decoded together (default 4, at most 16) */
+  concurrency?: number;
+  /** Requests served, cycling through the prompts (default: the larger of prompts and concurrency) */
+  total_requests?: number;
+  /** Extra event destination: "jsonl:<path>", "ws:<host:port>" or "null" */
+  event_sink?: string;
+}
+
+/** One served request */
+export interface StreamThroughput {
+  request: number;
+  prompt_index: number;
+  prompt_tokens: number;
+  output_tokens: number;
+  /** Benchmark start until a stream was free for it */
+  queued_ms: number;
+  /** From admission: prefill plus the first sample */
+  ttft_ms: number | null;
+  latency_ms: number;
+  /** This stream's own rate, first to last token */
+  decode_tps: number | null;
+  finish_reason: 'eog' | 'max_tokens' | 'user_stop';
+}
+
+export interface ThroughputReport {
+  model_path: string;
+  concurrency: number;
+  n_ctx_per_stream: number;
+  requests: StreamThroughput[];
+  total_prompt_tokens: number;
+  total_output_tokens: number;
+  wall_time_ms: number;
+  /** Output tokens of all streams / wall time */
+  aggregate_tps: number | null;
+  /** What each client sees, averaged over the requests */
+  mean_stream_tps: number | null;
+  mean_ttft_ms: number | null;
+  power_samples: number;
+  /** null without power telemetry (macmon) */
+  avg_package_power_w: number | null;
+  energy_per_token_wh: number | null;
+  /** False when stopped before every request was served */
+  completed: boolean;
+}
+
+/** Emitted as `throughput_progress` each time a request finishes */
+export interface ThroughputProgressEvent {
+  completed_requests: number;
+  total_requests: number;
+  output_tokens: number;
+  elapsed_ms: number;
+}
+
+/**
+ * Server-style load: several prompts decoded concurrently by one model
+ */
+export class ThroughputBenchmark {
+  /**
+   * Serve the request with `concurrency` parallel streams using the model config's sampling
+   * and max_tokens; telemetry arrives as telemetry_update events and stop_generation ends
+   * the benchmark early
+   */
+  static async run(config: Record<string, unknown>, request: ThroughputBenchmarkRequest): Promise<ThroughputReport> {
+    return await invoke('run_throughput_benchmark', { config, request });
+  }
+}
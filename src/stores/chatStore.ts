@@ -1,4 +1,5 @@
 import { create } from 'zustand';
+import type { GenerationFinishReason, GenerationResult, SamplingParams } from '../types/telemetry';
 
 // Import interfaces from App.tsx that will be moved to types later
 export interface Message {
@@ -11,6 +12,10 @@ export interface Message {
   avg_tps?: number;
   token_count?: number;
   generation_time_ms?: number;
+  finish_reason?: GenerationFinishReason; // Why generation ended (from the final GenerationResult)
+  truncated?: boolean;                    // Cut off by the max_tokens budget
+  sampling?: SamplingParams;              // Effective sampling parameters for this turn
+  sampling_overridden?: boolean;          // Turn used one-off overrides instead of the stored config
 }
 
 export interface ChatState {
@@ -21,6 +26,7 @@ export interface ChatState {
   isStopping: boolean;
   target: 'A' | 'B' | 'Both';
   streamingResponses: { A?: string; B?: string };
+  samplingOverrides: { A?: SamplingParams; B?: SamplingParams }; // Applied to the next turn only
 
   // Message editing state
   editingMessageId: string | null;
@@ -33,12 +39,15 @@ export interface ChatState {
   setIsStopping: (stopping: boolean) => void;
   setTarget: (target: 'A' | 'B' | 'Both') => void;
   setStreamingResponses: (responses: { A?: string; B?: string }) => void;
+  setSamplingOverride: (model: 'A' | 'B', overrides: SamplingParams | undefined) => void;
+  clearSamplingOverrides: () => void;
   setEditingMessageId: (id: string | null) => void;
   setEditingContent: (content: string) => void;
   
   // Helper actions
   updateMessageTokenCount: (role: 'user' | 'assistant', model: string | undefined, count: number) => void;
   updateMessageGenerationTime: (model: string, generationTimeMs: number) => void;
+  applyGenerationResult: (result: GenerationResult, samplingOverridden?: boolean) => void;
   addMessage: (message: Message) => void;
   updateMessage: (id: string, updates: Partial<Message>) => void;
   startEditingMessage: (messageId: string, content: string) => void;
@@ -59,6 +68,7 @@ export const useChatStore = create<ChatState>((set, get) => ({
   isStopping: false,
   target: 'A',
   streamingResponses: {},
+  samplingOverrides: {},
   editingMessageId: null,
   editingContent: '',
 
@@ -69,6 +79,10 @@ export const useChatStore = create<ChatState>((set, get) => ({
   setIsStopping: (stopping) => set({ isStopping: stopping }),
   setTarget: (target) => set({ target }),
   setStreamingResponses: (responses) => set({ streamingResponses: responses }),
+  setSamplingOverride: (model, overrides) => set((state) => ({
+    samplingOverrides: { ...state.samplingOverrides, [model]: overrides },
+  })),
+  clearSamplingOverrides: () => set({ samplingOverrides: {} }),
   setEditingMessageId: (id) => set({ editingMessageId: id }),
   setEditingContent: (content) => set({ editingContent: content }),
 
@@ -127,6 +141,28 @@ export const useChatStore = create<ChatState>((set, get) => ({
     }
   },
 
+  applyGenerationResult: (result, samplingOverridden = false) => {
+    const { chatHistory } = get();
+
+    // Attach finish reason, truncation and effective sampling to the most recent assistant message from this model
+    for (let i = chatHistory.length - 1; i >= 0; i--) {
+      const message = chatHistory[i];
+      if (message.role === 'assistant' && message.model === result.model) {
+        const updatedHistory = [...chatHistory];
+        updatedHistory[i] = {
+          ...message,
+          finish_reason: result.finish_reason,
+          truncated: result.truncated,
+          token_count: message.token_count ?? result.output_tokens,
+          sampling: result.sampling,
+          sampling_overridden: samplingOverridden,
+        };
+        set({ chatHistory: updatedHistory });
+        break;
+      }
+    }
+  },
+
   addMessage: (message) => {
     const { chatHistory } = get();
     set({ chatHistory: [...chatHistory, message] });
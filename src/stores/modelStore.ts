@@ -1,4 +1,8 @@
 import { create } from 'zustand';
+import type { RunManifest } from '../services/sessionPersistence';
+
+// How the backend integrates sampled power into energy
+export type EnergyIntegrationMethod = 'trapezoid' | 'rectangle' | 'monotone_cubic';
 
 export interface ModelConfig {
   model_path: string;
@@ -10,9 +14,45 @@ export interface ModelConfig {
   repeat_last_n?: number;   
   frequency_penalty?: number; 
   presence_penalty?: number; 
-  n_ctx?: number;
+  penalize_newline?: boolean;        // false exempts the newline token from repetition penalties
+  penalty_exempt_tokens?: string[];  // token strings exempt from repetition penalties
+  sampler_preset?: string;           // backend preset name (get_sampler_presets); replaces the sampling fields at run time
+  n_ctx?: number | 'auto';  // 'auto' sizes from GGUF metadata on the backend
+  n_batch?: number;         // Prompt tokens per prefill decode (backend default 512, capped at n_ctx)
+  n_gpu_layers?: number;    // Layers offloaded to the GPU (Metal); 0 = CPU only, unset = llama.cpp default
+  max_tokens?: number;      // Tokens generated per turn (backend default 1024)
+  stop_sequences?: string[]; // Generation ends when the output contains any of these (not included in the reply)
+  chat_template_override?: string; // 'chatml' | 'llama3' | 'mistral' or a Jinja template; replaces the GGUF's embedded template
+  context_shift?: boolean;  // Evict the oldest KV entries (system prompt kept) instead of failing at n_ctx
+  draft_model_path?: string; // Small model with the same vocabulary for speculative decoding
+  n_draft?: number;          // Draft tokens proposed per verification (backend default 5, max 16)
+  auto_truncate?: boolean;  // Drop the oldest turns (system prompt kept) when the prompt does not fit n_ctx
+  loop_detection?: { ngram?: number; window?: number; max_repeats?: number }; // End as 'loop_detected' when the output repeats
+  grammar?: string;          // GBNF grammar (root rule "root") or 'json'; constrains every sampled token
+  seed?: number | 'random';  // Sampler seed; 'random' draws a new one each turn (backend default 1234)
+  core_preference?: 'default' | 'performance' | 'efficiency'; // Experimental: steer inference threads to P-/E-cores (macOS QoS)
   wait_for_cpu_baseline_between_models?: boolean;
   wait_for_cpu_baseline_margin_c?: number; // degrees Celsius tolerance (default 2.0)
+  run_models_in_parallel?: boolean;        // "Both" generates A and B concurrently
+  isolate_models?: boolean;                // Sequential "Both": unload the other model before each side (reloads both every turn)
+  heat_soak_target_c?: number;             // Heat or cool the CPU into target ± band before each model's run
+  heat_soak_band_c?: number;               // degrees Celsius either side of the target (default 2.0)
+}
+
+// Per-model prompt size vs context, checked by the backend before a "Both" run
+export interface ModelPromptBudget {
+  model: string;
+  prompt_tokens: number;     // Same conversation, tokenized with this model's template and vocab
+  n_ctx: number;
+  output_budget: number;     // Reply tokens that fit before the context is full
+  will_truncate: boolean;
+}
+
+export interface PromptBudgetEvent {
+  budgets: ModelPromptBudget[];
+  max_generation_tokens: number;
+  warning?: string | null;   // Set when one model will truncate and the other won't
+  timestamp_ms: number;
 }
 
 // Parameter metadata for UI generation and validation
@@ -135,6 +175,9 @@ export interface ModelState {
   // Global telemetry configuration
   telemetry_sampling_hz: number;  // Global telemetry sampling frequency in Hz
   run_without_telemetry: boolean; // When true, skip telemetry during inference
+  telemetry_profile: 'full' | 'minimal'; // 'minimal' = package power + aggregate temps at <= 1 Hz
+  energy_integration_method: EnergyIntegrationMethod;
+  rolling_tps_window_secs: number; // Window for the rolling TPS metric in seconds
 
   // Model UI state
   modelAPathFocused: boolean;
@@ -145,6 +188,18 @@ export interface ModelState {
   outputTokenCounts: { A?: number; B?: number };
   systemPromptTokenCount: number | null;
 
+  // Context size actually used by the backend for the last run (resolves 'auto')
+  resolvedContextSizes: { A?: number; B?: number };
+
+  // Set when the last run's n_ctx exceeded the model's trained context (saved with the session)
+  contextWarnings: { A?: string; B?: string };
+
+  // Provenance of the last run, saved with the session
+  runManifest: RunManifest | null;
+
+  // Prompt budget preflight of the last "Both" run, saved with the session
+  promptBudget: PromptBudgetEvent | null;
+
   // Actions
   setModelA: (config: ModelConfig) => void;
   setModelB: (config: ModelConfig) => void;
@@ -158,6 +213,9 @@ export interface ModelState {
   setTelemetrySamplingHz: (hz: number) => void;
   setChartRefreshMs: (ms: number) => void;
   setRunWithoutTelemetry: (disable: boolean) => void;
+  setTelemetryProfile: (profile: 'full' | 'minimal') => void;
+  setEnergyIntegrationMethod: (method: EnergyIntegrationMethod) => void;
+  setRollingTpsWindowSecs: (secs: number) => void;
 
   // Helper actions
   updateModelAConfig: (updates: Partial<ModelConfig>) => void;
@@ -165,6 +223,10 @@ export interface ModelState {
   resetTokenCounts: () => void;
   updateInputTokenCount: (model: 'A' | 'B', count: number) => void;
   updateOutputTokenCount: (model: 'A' | 'B', count: number) => void;
+  updateResolvedContextSize: (model: 'A' | 'B', nCtx: number) => void;
+  updateContextWarning: (model: 'A' | 'B', warning: string | null) => void;
+  setRunManifest: (manifest: RunManifest | null) => void;
+  setPromptBudget: (budget: PromptBudgetEvent | null) => void;
   getAvailableTargets: () => ('A' | 'B' | 'Both')[];
   getFilenameFromPath: (path: string) => string;
 
@@ -197,11 +259,18 @@ export const useModelStore = create<ModelState>((set, get) => ({
   telemetry_sampling_hz: 1.0,  // Default to 1Hz (1 sample per second)
   chart_refresh_ms: 2000,       // Default live chart refresh cadence 2s
   run_without_telemetry: false,
+  telemetry_profile: 'full',
+  energy_integration_method: 'trapezoid',
+  rolling_tps_window_secs: 5,   // Default rolling TPS over the last 5 seconds
   modelAPathFocused: false,
   modelBPathFocused: false,
   inputTokenCounts: {},
   outputTokenCounts: {},
   systemPromptTokenCount: null,
+  resolvedContextSizes: {},
+  contextWarnings: {},
+  runManifest: null,
+  promptBudget: null,
 
   // Basic setters
   setModelA: (config) => set({ modelA: config }),
@@ -216,6 +285,9 @@ export const useModelStore = create<ModelState>((set, get) => ({
   setTelemetrySamplingHz: (hz) => set({ telemetry_sampling_hz: Math.max(0.1, Math.min(hz, 50.0)) }), // Clamp between 0.1-50 Hz
   setChartRefreshMs: (ms) => set({ chart_refresh_ms: Math.max(0, Math.min(ms, 3000)) }), // Clamp between 0-3000 ms (0 = on complete)
   setRunWithoutTelemetry: (disable) => set({ run_without_telemetry: !!disable }),
+  setTelemetryProfile: (profile) => set({ telemetry_profile: profile }),
+  setEnergyIntegrationMethod: (method) => set({ energy_integration_method: method }),
+  setRollingTpsWindowSecs: (secs) => set({ rolling_tps_window_secs: Math.max(0.5, Math.min(secs, 60)) }), // Clamp between 0.5-60 s
 
   // Helper actions
   updateModelAConfig: (updates) => {
@@ -256,6 +328,30 @@ export const useModelStore = create<ModelState>((set, get) => ({
     });
   },
 
+  updateResolvedContextSize: (model, nCtx) => {
+    const { resolvedContextSizes } = get();
+    set({
+      resolvedContextSizes: {
+        ...resolvedContextSizes,
+        [model]: nCtx
+      }
+    });
+  },
+
+  updateContextWarning: (model, warning) => {
+    const { contextWarnings } = get();
+    set({
+      contextWarnings: {
+        ...contextWarnings,
+        [model]: warning ?? undefined
+      }
+    });
+  },
+
+  setRunManifest: (manifest) => set({ runManifest: manifest }),
+
+  setPromptBudget: (budget) => set({ promptBudget: budget }),
+
   getAvailableTargets: () => {
     const { modelA, modelB } = get();
     const targets: ('A' | 'B' | 'Both')[] = [];
@@ -1,5 +1,20 @@
 import { create } from 'zustand';
-import type { TelemetryDataPoint, TelemetrySession } from '../types/telemetry';
+import type { TelemetryDataPoint, TelemetrySession, GenerationFinishReason, DieTemperatureData, TemperatureUnit } from '../types/telemetry';
+import { DEFAULT_TELEMETRY_SAMPLE_CAP, downsampleOldest } from '../utils/telemetryDownsampling';
+import { SessionPersistence, type TelemetryRetentionPolicy } from '../services/sessionPersistence';
+
+// Samples are flushed to the current recording in the database in batches of this many,
+// so saving a session references the recording instead of sending every sample
+const TELEMETRY_FLUSH_BATCH = 50;
+
+// Flushes, discards and resumes of recordings run one after another, in call order
+let recordingQueue: Promise<void> = Promise.resolve();
+const enqueueRecordingTask = (task: () => Promise<void>): Promise<void> => {
+  recordingQueue = recordingQueue.then(task).catch((error) => {
+    console.warn('Telemetry recording update failed:', error);
+  });
+  return recordingQueue;
+};
 
 // Import types from App.tsx - these will be moved to a shared types file later
 export interface CoreTemperatureData {
@@ -13,6 +28,7 @@ export interface CoreTemperatureData {
   gpu_temp_max?: number;
   battery_temp_avg?: number;
   thermal_trend: 'Cooling' | 'Heating' | 'Stable' | 'Rapid';
+  dies?: DieTemperatureData[] | null;
 }
 
 export interface TelemetryData {
@@ -25,9 +41,13 @@ export interface TelemetryData {
   cpu_freq: number | null;
   gpu_freq: number | null;
   ram_usage: number | null;
+  swap_usage?: number | null;
+  compressed_memory?: number | null;
+  memory_pressure?: string | null;
   thermal_pressure: string | null;
   tps: number | null;
   instantaneous_tps: number | null;
+  rolling_tps: number | null;
   model: string | null;
   cpu_temp_avg: number | null;
   cpu_temp_max: number | null;
@@ -40,6 +60,11 @@ export interface TelemetryData {
   cpu_p_core_utilization: number[] | null;
   cpu_e_core_utilization: number[] | null;
   cpu_overall_utilization: number | null;
+  inference_cpu_utilization?: number | null;
+  inference_cpu_share?: number | null;
+  gpu_utilization?: number | null;
+  fan_rpm?: number[] | null;
+  merged_samples?: number; // Raw samples averaged into this one by the sample cap
   core_temperatures?: CoreTemperatureData;
   // NEW: Energy consumption fields
   total_energy_wh: number | null;
@@ -47,26 +72,54 @@ export interface TelemetryData {
   gpu_energy_wh: number | null;
   ane_energy_wh: number | null;
   energy_rate_wh_per_token: number | null;
+  // Rates of change (°C/s, W/s) fitted over the last 10 s
+  cpu_temp_rate_c_per_s?: number | null;
+  gpu_temp_rate_c_per_s?: number | null;
+  battery_temp_rate_c_per_s?: number | null;
+  package_power_rate_w_per_s?: number | null;
 }
 
 interface SummaryStats {
   ttft_ms?: number;
   avg_tps?: number;
+  prefill_tps?: number;         // Prompt processing tokens/sec (decode speed is avg_tps)
+  prefill_ms?: number;
+  output_tokens?: number;
   model?: string;
   energy_per_token_wh?: number;
+  prefill_energy_per_token_wh?: number;  // Per prompt token, from the prefill/decode breakdown
+  decode_energy_per_token_wh?: number;   // Per generated token
+  total_energy_wh?: number;
+  inter_token_cv?: number;      // Jitter: std dev / mean of inter-token latency
+  longest_stall_ms?: number;
+  stall_count?: number;         // Inter-token gaps above stall_threshold_ms
+  stall_threshold_ms?: number;
+  load_memory_delta_gb?: number;        // Peak memory growth over the pre-load baseline while loading
+  generation_memory_delta_gb?: number;  // ... and while generating
+  finish_reason?: GenerationFinishReason;
+  truncated?: boolean;          // Output cut off by the max_tokens budget
+  context_used_tokens?: number;       // Prompt plus generated tokens in the context (live during generation)
+  peak_context_used_tokens?: number;  // Highest context fill seen this session
+  n_ctx?: number;
+  draft_acceptance_rate?: number;     // Share of draft tokens the model accepted (speculative decoding only)
 }
 
 export interface TelemetryState {
   // Telemetry data
   telemetryData: TelemetryData[];
+  telemetrySampleCap: number; // Oldest samples are downsampled beyond this (retention policy max_samples)
+  recordingId: string;                    // Database recording the samples of this chat are flushed to
+  recordedSamples: number;                // Samples the recording holds
+  pendingSamples: TelemetryDataPoint[];   // Recorded since the last flush
   summaryStats: { A?: SummaryStats; B?: SummaryStats };
 
   // Cooldown (ephemeral, not persisted)
   cooldownActive: boolean;
   cooldownStatus: 'started' | 'progress' | 'complete' | 'timeout' | 'canceled' | null;
-  cooldownBaselineC: number | null;
-  cooldownThresholdC: number | null;
-  cooldownMarginC: number; // default 2.0
+  cooldownBaseline: number | null;
+  cooldownThreshold: number | null;
+  cooldownMargin: number; // default 2.0
+  cooldownUnit: TemperatureUnit; // Unit of the cooldown values above (user preference)
   cooldownPoints: { timestamp: number; value: number }[];
 
   // Session management
@@ -78,6 +131,8 @@ export interface TelemetryState {
 
   // Actions
   setTelemetryData: (data: TelemetryData[]) => void;
+  setTelemetrySampleCap: (cap: number) => void;
+  saveTelemetryRetentionPolicy: (policy: TelemetryRetentionPolicy) => Promise<TelemetryRetentionPolicy>;
   setSummaryStats: (stats: { A?: SummaryStats; B?: SummaryStats }) => void;
   setTelemetrySessions: (sessions: TelemetrySession[]) => void;
   setSessionSaveDialogOpen: (open: boolean) => void;
@@ -91,27 +146,80 @@ export interface TelemetryState {
   clearSummaryStats: () => void;
   getLatestTelemetry: () => TelemetryData | null;
   transformTelemetryData: () => TelemetryDataPoint[];
+  flushTelemetrySamples: () => Promise<number>;
+  resumeTelemetryRecording: (sessionUuid: string) => Promise<void>;
   updateSummaryStats: (model: 'A' | 'B', stats: Partial<SummaryStats>) => void;
 
   // Cooldown actions
   setCooldownActive: (active: boolean) => void;
   setCooldownStatus: (status: 'started' | 'progress' | 'complete' | 'timeout' | 'canceled' | null) => void;
-  setCooldownMeta: (baseline: number | null, threshold: number | null, margin: number) => void;
+  setCooldownMeta: (baseline: number | null, threshold: number | null, margin: number, unit: TemperatureUnit) => void;
   addCooldownPoint: (timestamp: number, value: number) => void;
   clearCooldownPoints: () => void;
 }
 
+// A sample of the store in the shape sessions save it
+const toDataPoint = (d: TelemetryData): TelemetryDataPoint => ({
+  timestamp: d.timestamp,
+  cpu_power: d.cpu_power,
+  gpu_power: d.gpu_power,
+  ane_power: d.ane_power,
+  cpu_temp: d.cpu_temp,
+  gpu_temp: d.gpu_temp,
+  cpu_freq: d.cpu_freq,
+  gpu_freq: d.gpu_freq,
+  ram_usage: d.ram_usage,
+  swap_usage: d.swap_usage,
+  compressed_memory: d.compressed_memory,
+  memory_pressure: d.memory_pressure,
+  tps: d.tps,
+  instantaneous_tps: d.instantaneous_tps,
+  rolling_tps: d.rolling_tps,
+  model: d.model,
+  cpu_temp_avg: d.cpu_temp_avg,
+  cpu_temp_max: d.cpu_temp_max,
+  cpu_p_core_temps: d.cpu_p_core_temps,
+  cpu_e_core_temps: d.cpu_e_core_temps,
+  gpu_temp_avg: d.gpu_temp_avg,
+  gpu_temp_max: d.gpu_temp_max,
+  gpu_cluster_temps: d.gpu_cluster_temps,
+  battery_temp_avg: d.battery_temp_avg,
+  cpu_p_core_utilization: d.cpu_p_core_utilization,
+  cpu_e_core_utilization: d.cpu_e_core_utilization,
+  cpu_overall_utilization: d.cpu_overall_utilization,
+  inference_cpu_utilization: d.inference_cpu_utilization,
+  inference_cpu_share: d.inference_cpu_share,
+  gpu_utilization: d.gpu_utilization,
+  fan_rpm: d.fan_rpm,
+  core_temperatures: d.core_temperatures,
+  // NEW: Energy field mappings
+  total_energy_wh: d.total_energy_wh,
+  cpu_energy_wh: d.cpu_energy_wh,
+  gpu_energy_wh: d.gpu_energy_wh,
+  ane_energy_wh: d.ane_energy_wh,
+  energy_rate_wh_per_token: d.energy_rate_wh_per_token,
+  cpu_temp_rate_c_per_s: d.cpu_temp_rate_c_per_s,
+  gpu_temp_rate_c_per_s: d.gpu_temp_rate_c_per_s,
+  battery_temp_rate_c_per_s: d.battery_temp_rate_c_per_s,
+  package_power_rate_w_per_s: d.package_power_rate_w_per_s,
+}) as TelemetryDataPoint;
+
 export const useTelemetryStore = create<TelemetryState>((set, get) => ({
   // Initial state
   telemetryData: [],
+  telemetrySampleCap: DEFAULT_TELEMETRY_SAMPLE_CAP,
+  recordingId: crypto.randomUUID(),
+  recordedSamples: 0,
+  pendingSamples: [],
   summaryStats: {},
 
   // Cooldown initial state
   cooldownActive: false,
   cooldownStatus: null,
-  cooldownBaselineC: null,
-  cooldownThresholdC: null,
-  cooldownMarginC: 2.0,
+  cooldownBaseline: null,
+  cooldownThreshold: null,
+  cooldownMargin: 2.0,
+  cooldownUnit: 'celsius',
   cooldownPoints: [],
 
   // Sessions UI state
@@ -123,6 +231,13 @@ export const useTelemetryStore = create<TelemetryState>((set, get) => ({
 
   // Basic setters
   setTelemetryData: (data) => set({ telemetryData: data }),
+  setTelemetrySampleCap: (cap) => set({ telemetrySampleCap: cap }),
+  // Save the policy and apply its sample cap to the run in progress
+  saveTelemetryRetentionPolicy: async (policy) => {
+    const saved = await SessionPersistence.setTelemetryRetentionPolicy(policy);
+    set({ telemetrySampleCap: saved.max_samples ?? DEFAULT_TELEMETRY_SAMPLE_CAP });
+    return saved;
+  },
   setSummaryStats: (stats) => set({ summaryStats: stats }),
   setTelemetrySessions: (sessions) => set({ telemetrySessions: sessions }),
   setSessionSaveDialogOpen: (open) => set({ sessionSaveDialogOpen: open }),
@@ -132,15 +247,49 @@ export const useTelemetryStore = create<TelemetryState>((set, get) => ({
 
   // Helper actions
   addTelemetryData: (data) => {
-    const { telemetryData } = get();
-    const newData = [...telemetryData, data];
-    // Keep only last 10000 data points for performance
-    const result = newData.length > 10000 ? newData.slice(-10000) : newData;
-    set({ telemetryData: result });
+    const { telemetryData, telemetrySampleCap, pendingSamples } = get();
+    // Past the cap, average the oldest samples together instead of dropping them;
+    // the recording in the database keeps every sample
+    set({
+      telemetryData: downsampleOldest([...telemetryData, data], telemetrySampleCap),
+      pendingSamples: [...pendingSamples, toDataPoint(data)],
+    });
+    if (pendingSamples.length + 1 >= TELEMETRY_FLUSH_BATCH) {
+      void get().flushTelemetrySamples();
+    }
   },
 
   clearTelemetryData: () => {
-    set({ telemetryData: [], summaryStats: {} });
+    const { recordingId } = get();
+    set({ telemetryData: [], summaryStats: {}, recordingId: crypto.randomUUID(), recordedSamples: 0, pendingSamples: [] });
+    void enqueueRecordingTask(async () => {
+      await SessionPersistence.discardTelemetryRecording(recordingId);
+    });
+  },
+
+  flushTelemetrySamples: async () => {
+    await enqueueRecordingTask(async () => {
+      const { recordingId, pendingSamples } = get();
+      if (pendingSamples.length === 0) return;
+      set({ pendingSamples: [] });
+      try {
+        const recorded = await SessionPersistence.appendTelemetrySamples(recordingId, pendingSamples);
+        if (get().recordingId === recordingId) set({ recordedSamples: recorded });
+      } catch (error) {
+        // Keep the batch for the next flush
+        if (get().recordingId === recordingId) set({ pendingSamples: [...pendingSamples, ...get().pendingSamples] });
+        throw error;
+      }
+    });
+    return get().recordedSamples;
+  },
+
+  resumeTelemetryRecording: (sessionUuid) => {
+    const { recordingId } = get();
+    return enqueueRecordingTask(async () => {
+      const recorded = await SessionPersistence.resumeTelemetryRecording(recordingId, sessionUuid);
+      if (get().recordingId === recordingId) set({ recordedSamples: recorded });
+    });
   },
 
   clearSummaryStats: () => {
@@ -152,41 +301,7 @@ export const useTelemetryStore = create<TelemetryState>((set, get) => ({
     return telemetryData.length > 0 ? telemetryData[telemetryData.length - 1] : null;
   },
 
-  transformTelemetryData: () => {
-    const { telemetryData } = get();
-    return telemetryData.map(d => ({
-      timestamp: d.timestamp,
-      cpu_power: d.cpu_power,
-      gpu_power: d.gpu_power,
-      ane_power: d.ane_power,
-      cpu_temp: d.cpu_temp,
-      gpu_temp: d.gpu_temp,
-      cpu_freq: d.cpu_freq,
-      gpu_freq: d.gpu_freq,
-      ram_usage: d.ram_usage,
-      tps: d.tps,
-      instantaneous_tps: d.instantaneous_tps,
-      model: d.model,
-      cpu_temp_avg: d.cpu_temp_avg,
-      cpu_temp_max: d.cpu_temp_max,
-      cpu_p_core_temps: d.cpu_p_core_temps,
-      cpu_e_core_temps: d.cpu_e_core_temps,
-      gpu_temp_avg: d.gpu_temp_avg,
-      gpu_temp_max: d.gpu_temp_max,
-      gpu_cluster_temps: d.gpu_cluster_temps,
-      battery_temp_avg: d.battery_temp_avg,
-      cpu_p_core_utilization: d.cpu_p_core_utilization,
-      cpu_e_core_utilization: d.cpu_e_core_utilization,
-      cpu_overall_utilization: d.cpu_overall_utilization,
-      core_temperatures: d.core_temperatures,
-      // NEW: Energy field mappings
-      total_energy_wh: d.total_energy_wh,
-      cpu_energy_wh: d.cpu_energy_wh,
-      gpu_energy_wh: d.gpu_energy_wh,
-      ane_energy_wh: d.ane_energy_wh,
-      energy_rate_wh_per_token: d.energy_rate_wh_per_token,
-    } as TelemetryDataPoint));
-  },
+  transformTelemetryData: () => get().telemetryData.map(toDataPoint),
 
   updateSummaryStats: (model, stats) => {
     const { summaryStats } = get();
@@ -201,7 +316,7 @@ export const useTelemetryStore = create<TelemetryState>((set, get) => ({
   // Cooldown actions
   setCooldownActive: (active) => set({ cooldownActive: active }),
   setCooldownStatus: (status) => set({ cooldownStatus: status }),
-  setCooldownMeta: (baseline, threshold, margin) => set({ cooldownBaselineC: baseline, cooldownThresholdC: threshold, cooldownMarginC: margin }),
+  setCooldownMeta: (baseline, threshold, margin, unit) => set({ cooldownBaseline: baseline, cooldownThreshold: threshold, cooldownMargin: margin, cooldownUnit: unit }),
   addCooldownPoint: (timestamp, value) => {
     const { cooldownPoints } = get();
     const updated = [...cooldownPoints, { timestamp, value }];
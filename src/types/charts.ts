@@ -61,7 +61,12 @@ export type TelemetryVariable =
   | 'tps'
   | 'cpu_overall_utilization'
   | 'cpu_freq'
-  | 'gpu_freq';
+  | 'gpu_freq'
+  | 'gpu_utilization'
+  | 'fan_rpm'
+  | 'cpu_temp_rate'
+  | 'gpu_temp_rate'
+  | 'package_power_rate';
 
 export interface VariableConfig {
   key: TelemetryVariable;
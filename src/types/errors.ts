@@ -0,0 +1,50 @@
+// Errors returned by Tauri commands (A2oError in src-tauri/src/error.rs)
+
+export type A2oErrorCode =
+  | 'model_not_found'
+  | 'model_load_failed'
+  | 'model_in_use'
+  | 'tokenization_failed'
+  | 'inference_failed'
+  | 'telemetry_failed'
+  | 'sensor_unavailable'
+  | 'database_error'
+  | 'not_found'
+  | 'invalid_input'
+  | 'io_error'
+  | 'cancelled'
+  | 'run_already_in_progress'
+  | 'insufficient_disk_space'
+  | 'context_overflow'
+  | 'internal';
+
+export interface A2oError {
+  code: A2oErrorCode;
+  message: string;
+  active_run_id?: string;   // Set for run_already_in_progress
+  required_bytes?: number;  // Set for insufficient_disk_space (expected size plus headroom)
+  available_bytes?: number;
+  model?: string;           // context_overflow: the model whose prompt did not fit, with its counts
+  prompt_tokens?: number;
+  n_ctx?: number;
+}
+
+/** Payload of the disk_space_warning event, emitted when a write is refused */
+export interface DiskSpaceWarning {
+  operation: string;
+  path: string;
+  required_bytes: number;
+  available_bytes: number;
+}
+
+export const isA2oError = (err: unknown): err is A2oError =>
+  typeof err === 'object' && err !== null
+  && typeof (err as A2oError).code === 'string'
+  && typeof (err as A2oError).message === 'string';
+
+/** Human-readable message for anything thrown by `invoke` */
+export const errorMessage = (err: unknown): string =>
+  isA2oError(err) ? err.message : err instanceof Error ? err.message : String(err);
+
+export const errorCode = (err: unknown): A2oErrorCode | undefined =>
+  isA2oError(err) ? err.code : undefined;
@@ -1,3 +1,5 @@
+import type { A2oError } from './errors';
+
 // Enhanced telemetry types for hybrid charting system
 export interface TelemetrySession {
   id: string;
@@ -14,6 +16,8 @@ export interface TelemetrySession {
     telemetry_sampling_hz?: number;
     wait_for_cpu_baseline_between_models?: boolean;
     wait_for_cpu_baseline_margin_c?: number;
+    heat_soak_target_c?: number;
+    heat_soak_band_c?: number;
     run_without_telemetry?: boolean;
   };
   // Chat conversation data
@@ -32,8 +36,147 @@ export interface Message {
   ttft_ms?: number;    // Time to First Token
   avg_tps?: number;    // Average Tokens Per Second
   token_count?: number; // Total tokens in message
+  finish_reason?: GenerationFinishReason; // Why generation ended
+  truncated?: boolean;  // Cut off by the max_tokens budget
 }
 
+export type GenerationFinishReason = 'eog' | 'max_tokens' | 'stop_sequence' | 'loop_detected' | 'user_stop';
+
+// Context parameters llama.cpp actually used, read back after the context was created
+export interface EffectiveContextParams {
+  n_ctx: number;
+  n_batch: number;
+  n_ubatch: number;
+  n_threads: number;
+  n_threads_batch: number;
+  n_gpu_layers_requested: number | null;
+  gpu_offload_supported: boolean;
+  gpu_offload: { offloaded_layers: number; total_layers: number } | null;
+  flash_attention: string | null;
+  warnings: string[];
+}
+
+// Structured outcome of one model's generation, part of a run_generation_turn job's result
+export interface GenerationResult {
+  model: string;
+  text: string;
+  finish_reason: GenerationFinishReason;
+  truncated: boolean;
+  stop_sequence: string | null;  // Set when finish_reason is 'stop_sequence'
+  input_tokens: number;
+  output_tokens: number;
+  model_load_ms: number;  // Near zero when the model was already resident
+  model_reused: boolean;  // Taken from the model cache instead of loaded from disk
+  effective_context: EffectiveContextParams | null;
+  ttft_ms: number | null;
+  prefill_ms: number;
+  generation_time_ms: number;
+  avg_tps: number | null;
+  energy: {
+    total_energy_wh: number;
+    energy_per_token_wh?: number | null;
+    [key: string]: unknown;
+  } | null;                    // null for parallel runs, whose energy is only known combined
+  combined_energy: {           // Parallel runs: both models together (model "A+B")
+    total_energy_wh: number;
+    energy_per_token_wh?: number | null;
+    [key: string]: unknown;
+  } | null;
+  memory: MemoryUsage;
+  sampling: SamplingParams; // Effective sampling for this turn, including per-turn overrides
+  context_used_tokens: number; // Peak context fill for this turn: prompt plus generated tokens
+  n_ctx: number;
+  context_shifts: number;      // KV cache evictions in context-shift mode
+  resumed_tokens: number;      // Recorded tokens replayed when resuming a generation
+  resume_diverged_at: number | null; // First replayed token the sampler did not reproduce
+  speculative: SpeculativeStats | null; // Draft acceptance when a draft model was configured
+}
+
+// Draft-model acceptance over a run, also the payload's stats in the speculative_decoding event
+export interface SpeculativeStats {
+  draft_model: string;
+  n_draft: number;
+  rounds: number;              // Target decodes that verified a draft
+  drafted_tokens: number;
+  accepted_tokens: number;
+  acceptance_rate: number | null;
+}
+
+// Payload of the context_shift event, emitted each time a context-shift run evicts tokens
+export interface ContextShiftEvent {
+  model: string;
+  shift: number;              // 1 for the first shift of the run
+  kept_tokens: number;        // Prefix never evicted (system prompt / attention sinks)
+  discarded_tokens: number;
+  n_ctx: number;
+  tokens_generated: number;
+  timestamp_ms: number;
+}
+
+// Payload of the context_truncated event, emitted when auto_truncate dropped the oldest turns
+export interface ContextTruncatedEvent {
+  model: string;
+  dropped_messages: number;
+  original_prompt_tokens: number;
+  prompt_tokens: number;      // After dropping
+  n_ctx: number;
+  timestamp_ms: number;
+}
+
+// Sampling parameters; as a per-turn override, unset fields keep the model's stored value
+export interface SamplingParams {
+  temperature?: number | null;
+  top_k?: number | null;
+  top_p?: number | null;
+  min_p?: number | null;
+  repeat_penalty?: number | null;
+  repeat_last_n?: number | null;
+  frequency_penalty?: number | null;
+  presence_penalty?: number | null;
+  penalize_newline?: boolean | null;
+  seed?: number | 'random' | null; // On a GenerationResult, the seed the turn used
+}
+
+// Unified memory high-water marks for one model run (system-wide used memory)
+export interface MemoryUsage {
+  model: string;
+  baseline_gb: number;          // Used memory before the model was loaded
+  load_peak_gb: number;
+  load_delta_gb: number;
+  generation_peak_gb: number;
+  generation_delta_gb: number;
+  timestamp_ms: number;
+}
+
+export interface GenerationTurnResult {
+  run_id: string;
+  results: GenerationResult[];
+}
+
+// run_generation_turn returns a job id at once; await_job resolves with the turn result
+export interface JobHandle {
+  job_id: string;
+}
+
+export type JobState = 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface JobStatus {
+  job_id: string;
+  kind: string;
+  state: JobState;
+  /** Run the job drives once it has started; pass to get_partial_output */
+  run_id: string | null;
+  created_at_ms: number;
+  finished_at_ms: number | null;
+  result: GenerationTurnResult | null;
+  error: A2oError | null;
+}
+
+/** Display preference for temperatures; recorded telemetry is always Celsius */
+export type TemperatureUnit = 'celsius' | 'fahrenheit';
+
+export const temperatureSymbol = (unit: TemperatureUnit) => (unit === 'fahrenheit' ? '°F' : '°C');
+
 export interface TelemetryDataPoint {
   timestamp: number;
   cpu_power: number | null;
@@ -44,8 +187,12 @@ export interface TelemetryDataPoint {
   cpu_freq: number | null;
   gpu_freq: number | null;
   ram_usage: number | null;
+  swap_usage?: number | null;         // GB swapped out
+  compressed_memory?: number | null;  // GB held by the memory compressor
+  memory_pressure?: string | null;    // 'normal' | 'warning' | 'critical'
   tps: number | null;
   instantaneous_tps: number | null;
+  rolling_tps: number | null;
   model: string | null;
   // Enhanced data
   cpu_temp_avg: number | null;
@@ -59,6 +206,10 @@ export interface TelemetryDataPoint {
   cpu_p_core_utilization: number[] | null;
   cpu_e_core_utilization: number[] | null;
   cpu_overall_utilization: number | null;
+  inference_cpu_utilization?: number | null;
+  inference_cpu_share?: number | null;
+  gpu_utilization?: number | null;
+  fan_rpm?: number[] | null;  // Per-fan speed (RPM)
   core_temperatures?: CoreTemperatureData;
   // NEW: Energy consumption fields
   total_energy_wh: number | null;
@@ -66,6 +217,11 @@ export interface TelemetryDataPoint {
   gpu_energy_wh: number | null;
   ane_energy_wh: number | null;
   energy_rate_wh_per_token: number | null;
+  // Rates of change (°C/s, W/s) fitted over the last 10 s
+  cpu_temp_rate_c_per_s?: number | null;
+  gpu_temp_rate_c_per_s?: number | null;
+  battery_temp_rate_c_per_s?: number | null;
+  package_power_rate_w_per_s?: number | null;
 }
 
 export interface TelemetryDataPointWithRelativeTime extends TelemetryDataPoint {
@@ -102,6 +258,16 @@ export interface CoreTemperatureData {
   gpu_temp_max?: number;
   battery_temp_avg?: number;
   thermal_trend: 'Cooling' | 'Heating' | 'Stable' | 'Rapid';
+  dies?: DieTemperatureData[] | null; // Per-die breakdown on multi-die (Ultra) chips
+}
+
+export interface DieTemperatureData {
+  die: number;
+  p_cores: number[];
+  e_cores: number[];
+  gpu_temps: number[];
+  cpu_temp_avg?: number | null;
+  gpu_temp_avg?: number | null;
 }
 
 export interface SessionSummary {
@@ -111,13 +277,20 @@ export interface SessionSummary {
   peak_cpu_temp: number;
   avg_power_consumption: number;
   model_performance: {
-    [model: string]: {
-      ttft_ms: number;
-      avg_tps: number;
-    }
+    [model: string]: ModelPerformance;
   };
 }
 
+export interface ModelPerformance {
+  ttft_ms: number;
+  avg_tps: number;
+  prefill_tps?: number;         // Prompt processing speed (avg_tps is decode speed)
+  // Inter-token stability (absent for sessions saved before it was recorded)
+  inter_token_cv?: number;
+  longest_stall_ms?: number;
+  stall_count?: number;
+}
+
 // Chart data transformation types
 export interface ChartDataPoint {
   x: number;
@@ -2,6 +2,7 @@ import type { DropdownOption } from '../components/ui/CustomDropdown';
 
 // Context length dropdown options with predefined common sizes
 export const CONTEXT_LENGTH_OPTIONS: DropdownOption[] = [
+  { id: 'auto', label: 'Auto (from model)', data: 'auto' },
   { id: '4k', label: '4,096 (4k)', data: 4096 },
   { id: '8k', label: '8,192 (8k)', data: 8192 },
   { id: '16k', label: '16,384 (16k)', data: 16384 },
@@ -14,7 +15,7 @@ export const CONTEXT_LENGTH_OPTIONS: DropdownOption[] = [
 ];
 
 // Helper function to find option by value or create custom option
-export const getContextLengthOption = (value: number | undefined): DropdownOption | null => {
+export const getContextLengthOption = (value: number | 'auto' | undefined): DropdownOption | null => {
   if (value === undefined) return null;
   
   // Check if value matches any predefined option
@@ -1,4 +1,4 @@
-import type { TelemetrySession, TelemetryDataPoint, Message } from '../types/telemetry';
+import type { TelemetrySession, TelemetryDataPoint, Message, ModelPerformance } from '../types/telemetry';
 
 // Simple in-memory storage for demo purposes
 // In a real application, you might use IndexedDB, localStorage, or a backend API
@@ -90,7 +90,7 @@ class SessionStorageManager {
       : 0;
 
     // Calculate model performance metrics
-    const modelPerformance: { [model: string]: { ttft_ms: number; avg_tps: number } } = {};
+    const modelPerformance: { [model: string]: ModelPerformance } = {};
     
     // Group data by model
     const modelData: { [model: string]: TelemetryDataPoint[] } = {};
@@ -0,0 +1,58 @@
+// In-run telemetry sample cap: once a run holds more samples than the cap, neighbouring
+// samples in the older half are averaged together, so multi-hour runs keep bounded memory
+// with the oldest data coarsest. Each merged sample keeps the min and max of the fields it
+// averages, so peaks survive. Mirrors the backend's downsample_oldest, which applies the
+// same cap to the saved session. Summary stats come from the backend and stay exact.
+
+export const DEFAULT_TELEMETRY_SAMPLE_CAP = 10000;
+
+interface DownsampleablePoint {
+  model: string | null;
+  merged_samples?: number; // Raw samples averaged into this one (absent means 1)
+  merged_range?: Record<string, [number, number]>; // [min, max] of each averaged field over those samples
+}
+
+const weight = (point: DownsampleablePoint) => point.merged_samples ?? 1;
+
+const fieldRange = (point: DownsampleablePoint, key: string, value: number): [number, number] =>
+  point.merged_range?.[key] ?? [value, value];
+
+// Weighted average of numeric fields, keeping their min and max; other fields keep the
+// newer sample's value
+const mergeSamples = <T extends DownsampleablePoint>(older: T, newer: T): T => {
+  const [weightOlder, weightNewer] = [weight(older), weight(newer)];
+  const merged: Record<string, unknown> = { ...newer };
+  const ranges: Record<string, [number, number]> = {};
+  for (const [key, value] of Object.entries(newer)) {
+    const previous = (older as Record<string, unknown>)[key];
+    if (key !== 'merged_samples' && typeof value === 'number' && typeof previous === 'number') {
+      merged[key] = (previous * weightOlder + value * weightNewer) / (weightOlder + weightNewer);
+      const [[minOlder, maxOlder], [minNewer, maxNewer]] = [fieldRange(older, key, previous), fieldRange(newer, key, value)];
+      ranges[key] = [Math.min(minOlder, minNewer), Math.max(maxOlder, maxNewer)];
+    }
+  }
+  merged.merged_samples = weightOlder + weightNewer;
+  merged.merged_range = ranges;
+  return merged as T;
+};
+
+/** Bring `points` down to `maxSamples` by averaging the oldest samples; models are never mixed */
+export const downsampleOldest = <T extends DownsampleablePoint>(points: T[], maxSamples: number): T[] => {
+  let result = points;
+  while (result.length > maxSamples) {
+    const half = Math.floor(result.length / 2);
+    const merged: T[] = [];
+    for (let i = 0; i < half; ) {
+      if (i + 1 < half && result[i].model === result[i + 1].model) {
+        merged.push(mergeSamples(result[i], result[i + 1]));
+        i += 2;
+      } else {
+        merged.push(result[i]);
+        i += 1;
+      }
+    }
+    if (merged.length === half) break; // Models alternate every sample: nothing left to merge
+    result = [...merged, ...result.slice(half)];
+  }
+  return result;
+};